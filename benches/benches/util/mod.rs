@@ -14,7 +14,7 @@ pub fn runtime_from_file<P: AsRef<Path>>(p: P) -> Runtime {
     let path = PathOrInline::Path(compute_resource_path(p));
     let (mut driver, file_id) = Driver::with_file(
         Config {
-            optimization_lvl: OptimizationLevel::Aggressive,
+            optimization_lvl: Some(OptimizationLevel::Aggressive),
             ..Config::default()
         },
         path,
@@ -4,9 +4,57 @@
 #include <stdint.h>
 
 /**
- * Defines the current ABI version
+ * Defines the current ABI version, encoded as `MM_mm_pp` (major, minor,
+ * patch).
+ *
+ * Within a major version the layout of the ABI only grows: a new minor
+ * version may append optional sections to [`AssemblyInfo`], which are
+ * announced through its [`Capabilities`]. A runtime can therefore load
+ * assemblies that were generated for an older minor version of the same major
+ * version, see [`is_compatible_abi_version`].
+ */
+#define MUN_ABI_VERSION 10500
+
+/**
+ * Defines the oldest ABI version that is compatible with the current ABI
+ * version
  */
-#define MUN_ABI_VERSION 300
+#define MUN_MIN_ABI_VERSION 10000
+
+/**
+ * Represents the role of a function in an assembly.
+ */
+enum MunFunctionKind
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
+    /**
+     * A regular function.
+     */
+    Normal,
+    /**
+     * A function annotated with `#[test]`. A test takes no arguments and
+     * returns either nothing or a `bool` that indicates whether it passed.
+     */
+    Test,
+    /**
+     * A function annotated with `#[bench]`. A benchmark takes no arguments
+     * and returns nothing.
+     */
+    Bench,
+    /**
+     * A function declared as `resumable fn`. Calling `fn_ptr` with the
+     * arguments of the function, followed by a pointer to a
+     * [`CoroutineContext`](crate::CoroutineContext), starts a coroutine that
+     * is suspended before the body of the function is executed. The function
+     * itself returns nothing.
+     */
+    Resumable,
+};
+#ifndef __cplusplus
+typedef uint8_t MunFunctionKind;
+#endif // __cplusplus
 
 /**
  * Represents the kind of memory management a struct uses.
@@ -27,7 +75,9 @@ enum MunStructMemoryKind
      *
      * NOTE: When a value struct is used in an external API, a wrapper is
      * created that _pins_ the value on the heap. The heap-allocated value
-     * needs to be *manually deallocated*!
+     * needs to be *manually deallocated*! Small `#[repr(C)]` value structs
+     * are passed by value instead, see
+     * [`StructDefinition::passed_by_value`].
      */
     Value,
 };
@@ -35,6 +85,39 @@ enum MunStructMemoryKind
 typedef uint8_t MunStructMemoryKind;
 #endif // __cplusplus
 
+/**
+ * Represents how the fields of a struct are laid out in memory, as specified
+ * with the `#[repr(..)]` attribute.
+ */
+enum MunStructRepr
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
+    /**
+     * The fields are laid out in the order in which they are declared, each
+     * aligned to its natural alignment. The compiler is free to change this
+     * layout in the future.
+     */
+    Default,
+    /**
+     * `#[repr(C)]`: the fields are laid out in the order in which they are
+     * declared, each aligned to its natural alignment, exactly like a C
+     * compiler would. This layout is guaranteed to remain stable, so hosts can
+     * share plain-old-data buffers with Mun code.
+     */
+    C,
+    /**
+     * `#[repr(packed)]` or `#[repr(C, packed)]`: the fields are laid out in
+     * the order in which they are declared without any padding in between
+     * them, and the struct is aligned to a single byte.
+     */
+    Packed,
+};
+#ifndef __cplusplus
+typedef uint8_t MunStructRepr;
+#endif // __cplusplus
+
 /**
  * Represents a globally unique identifier (GUID).
  */
@@ -159,6 +242,10 @@ typedef struct MunFunctionDefinition {
      * Function pointer
      */
     const void *fn_ptr;
+    /**
+     * Function kind
+     */
+    MunFunctionKind kind;
 } MunFunctionDefinition;
 
 /**
@@ -189,6 +276,22 @@ typedef struct MunStructDefinition {
      * Struct memory kind
      */
     MunStructMemoryKind memory_kind;
+    /**
+     * How the fields of the struct are laid out in memory. Introduced in ABI
+     * version 1.1. It occupies what used to be trailing padding, which is
+     * zeroed, i.e. [`StructRepr::Default`], in assemblies that were
+     * generated for ABI version 1.0.
+     */
+    MunStructRepr repr;
+    /**
+     * Whether values of the struct are passed to and returned from public
+     * functions by value, following the C calling convention of the target,
+     * instead of as handles to garbage collected copies. Introduced in ABI
+     * version 1.2. It occupies what used to be trailing padding, which is
+     * zeroed, i.e. `false`, in assemblies that were generated for older ABI
+     * versions.
+     */
+    bool passed_by_value;
 } MunStructDefinition;
 
 /**
@@ -322,8 +425,60 @@ typedef struct MunTypeLut {
     uint32_t num_entries;
 } MunTypeLut;
 
+/**
+ * A set of optional ABI features that an assembly makes use of.
+ *
+ * Each minor version of the ABI may introduce new capabilities, together with
+ * the optional sections of [`AssemblyInfo`] that they enable. An optional
+ * section may only be accessed if the corresponding capability is set, because
+ * an assembly that was generated for an older minor version does not contain
+ * it.
+ */
+typedef uint32_t MunCapabilities;
+/**
+ * The assembly contains the default values of struct fields, see
+ * [`AssemblyInfo::field_defaults`]. Introduced in ABI version 1.3.
+ */
+#define MunCapabilities_FIELD_DEFAULTS (1 << 0)
+/**
+ * The assembly contains functions that other assemblies link against, but
+ * that may not be accessed by the host, see
+ * [`AssemblyInfo::private_functions`]. Introduced in ABI version 1.4.
+ */
+#define MunCapabilities_PRIVATE_FUNCTIONS (1 << 1)
+
+/**
+ * Represents the default value of a struct field, which is used to initialize
+ * the field when it is added to the struct of an allocated object while hot
+ * reloading. Introduced in ABI version 1.3, see
+ * [`Capabilities::FIELD_DEFAULTS`](crate::Capabilities::FIELD_DEFAULTS).
+ */
+typedef struct MunFieldDefault {
+    /**
+     * The unique identifier of the struct that declares the field
+     */
+    struct MunGuid struct_guid;
+    /**
+     * The index of the field in the struct
+     */
+    uint16_t field_index;
+    /**
+     * The size of the value in bytes
+     */
+    uint16_t value_size;
+    /**
+     * The value, in the memory representation of the field's type
+     */
+    const uint8_t *value;
+} MunFieldDefault;
+
 /**
  * Represents an assembly declaration.
+ *
+ * The layout of this struct only grows within a major version of the ABI.
+ * Optional sections that are introduced by later minor versions are appended
+ * after [`AssemblyInfo::capabilities`] and may only be accessed if the
+ * corresponding [`Capabilities`] are set.
  */
 typedef struct MunAssemblyInfo {
     /**
@@ -346,6 +501,64 @@ typedef struct MunAssemblyInfo {
      * Number of dependencies
      */
     uint32_t num_dependencies;
+    /**
+     * Optional ABI features that the assembly makes use of
+     */
+    MunCapabilities capabilities;
+    /**
+     * Default values of struct fields. Only present if
+     * [`Capabilities::FIELD_DEFAULTS`] is set.
+     */
+    const struct MunFieldDefault *field_defaults;
+    /**
+     * Number of default values of struct fields. Only present if
+     * [`Capabilities::FIELD_DEFAULTS`] is set.
+     */
+    uint32_t num_field_defaults;
+    /**
+     * Functions that other assemblies link against, but that are not part of
+     * [`AssemblyInfo::symbols`]. Only present if
+     * [`Capabilities::PRIVATE_FUNCTIONS`] is set.
+     */
+    const struct MunFunctionDefinition *private_functions;
+    /**
+     * Number of private functions. Only present if
+     * [`Capabilities::PRIVATE_FUNCTIONS`] is set.
+     */
+    uint32_t num_private_functions;
 } MunAssemblyInfo;
 
+/**
+ * Represents a coroutine that was started by calling a
+ * [`FunctionKind::Resumable`](crate::FunctionKind::Resumable) function.
+ *
+ * The function fills in the context before it suspends for the first time.
+ * Afterwards, the coroutine is driven by calling `resume` with `handle`, until
+ * it returns a non-zero value to indicate that the function has returned. A
+ * coroutine must be destroyed by calling `destroy` with `handle` exactly
+ * once, which frees its frame.
+ */
+typedef struct MunCoroutineContext {
+    /**
+     * Opaque handle to the frame of the coroutine
+     */
+    void *handle;
+    /**
+     * Resumes the coroutine until it suspends again. Returns a non-zero value
+     * if the function has returned, after which the coroutine can no longer
+     * be resumed.
+     */
+    uint8_t (*resume)(void*);
+    /**
+     * Destroys the coroutine and frees its frame
+     */
+    void (*destroy)(void*);
+    /**
+     * The value to which the `yield` expression at which the coroutine is
+     * suspended evaluates when it is resumed. Must be set before every call
+     * to `resume`.
+     */
+    float resume_value;
+} MunCoroutineContext;
+
 #endif /* MUN_ABI_H_ */
@@ -51,7 +51,9 @@ enum MunStructMemoryKind
      *
      * NOTE: When a value struct is used in an external API, a wrapper is
      * created that _pins_ the value on the heap. The heap-allocated value
-     * needs to be *manually deallocated*!
+     * needs to be *manually deallocated*! Small `#[repr(C)]` value structs
+     * are passed by value instead, see
+     * [`StructDefinition::passed_by_value`].
      */
     MUN_STRUCT_MEMORY_KIND_VALUE,
 };
@@ -59,6 +61,39 @@ enum MunStructMemoryKind
 typedef uint8_t MunStructMemoryKind;
 #endif // __cplusplus
 
+/**
+ * Represents how the fields of a struct are laid out in memory, as specified
+ * with the `#[repr(..)]` attribute.
+ */
+enum MunStructRepr
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
+    /**
+     * The fields are laid out in the order in which they are declared, each
+     * aligned to its natural alignment. The compiler is free to change this
+     * layout in the future.
+     */
+    MUN_STRUCT_REPR_DEFAULT,
+    /**
+     * `#[repr(C)]`: the fields are laid out in the order in which they are
+     * declared, each aligned to its natural alignment, exactly like a C
+     * compiler would. This layout is guaranteed to remain stable, so hosts can
+     * share plain-old-data buffers with Mun code.
+     */
+    MUN_STRUCT_REPR_C,
+    /**
+     * `#[repr(packed)]` or `#[repr(C, packed)]`: the fields are laid out in
+     * the order in which they are declared without any padding in between
+     * them, and the struct is aligned to a single byte.
+     */
+    MUN_STRUCT_REPR_PACKED,
+};
+#ifndef __cplusplus
+typedef uint8_t MunStructRepr;
+#endif // __cplusplus
+
 /**
  * A C-style handle to an error message.
  *
@@ -307,6 +342,7 @@ enum MunTypeKind_Tag
     MUN_TYPE_KIND_POINTER,
     MUN_TYPE_KIND_STRUCT,
     MUN_TYPE_KIND_ARRAY,
+    MUN_TYPE_KIND_OPAQUE,
 };
 #ifndef __cplusplus
 typedef uint8_t MunTypeKind_Tag;
@@ -330,6 +366,10 @@ typedef union MunTypeKind {
         MunTypeKind_Tag array_tag;
         struct MunArrayInfo array;
     };
+    struct {
+        MunTypeKind_Tag opaque_tag;
+        struct MunGuid opaque;
+    };
 } MunTypeKind;
 
 /**
@@ -808,6 +848,29 @@ struct MunErrorHandle mun_struct_type_guid(struct MunStructInfo ty, struct MunGu
 struct MunErrorHandle mun_struct_type_memory_kind(struct MunStructInfo ty,
                                                   MunStructMemoryKind *memory_kind);
 
+/**
+ * Returns how the fields of the struct are laid out in memory.
+ *
+ * # Safety
+ *
+ * This function results in undefined behavior if the passed in `StructType`
+ * has been deallocated by a previous call to [`mun_type_release`].
+ */
+struct MunErrorHandle mun_struct_type_repr(struct MunStructInfo ty, MunStructRepr *repr);
+
+/**
+ * Returns whether values of the struct are passed to and returned from public
+ * Mun functions by value, following the C calling convention, instead of as
+ * handles to garbage collected values.
+ *
+ * # Safety
+ *
+ * This function results in undefined behavior if the passed in `StructType`
+ * has been deallocated by a previous call to [`mun_type_release`].
+ */
+struct MunErrorHandle mun_struct_type_is_passed_by_value(struct MunStructInfo ty,
+                                                         bool *passed_by_value);
+
 /**
  * Retrieves the field with the given name.
  *
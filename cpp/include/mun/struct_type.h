@@ -53,6 +53,15 @@ public:
         return memory_kind;
     }
 
+    /**
+     * @brief Returns how the struct's fields are laid out in memory.
+     */
+    [[nodiscard]] MunStructRepr repr() const noexcept {
+        MunStructRepr repr;
+        MUN_ASSERT(mun_struct_type_repr(m_struct_info, &repr));
+        return repr;
+    }
+
 private:
     MunStructInfo m_struct_info;
 };
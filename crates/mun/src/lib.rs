@@ -3,7 +3,9 @@ mod ops;
 use std::ffi::OsString;
 
 use clap::{Parser, Subcommand};
-use ops::{build, init, language_server, new, start};
+use ops::{
+    bench, bindgen, build, check, daemon, fmt, init, language_server, new, repl, start, test,
+};
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -21,6 +23,16 @@ enum Command {
     /// Compiles a local Mun file into a module
     Build(build::Args),
 
+    /// Reports the diagnostics of a Mun project without building it
+    Check(check::Args),
+
+    /// Builds a Mun project whenever its sources change and accepts build
+    /// requests from runtimes
+    Daemon(daemon::Args),
+
+    /// Formats the source files of a Mun project
+    Fmt(fmt::Args),
+
     /// Create a new Mun project at the specified location
     New(new::Args),
 
@@ -29,6 +41,18 @@ enum Command {
 
     /// Invoke a function from a munlib
     Start(start::Args),
+
+    /// Builds a Mun project and runs all of its `#[test]` functions
+    Test(test::Args),
+
+    /// Builds a Mun project and measures all of its `#[bench]` functions
+    Bench(bench::Args),
+
+    /// Generate typed Rust bindings for the structs and functions of a munlib
+    Bindgen(bindgen::Args),
+
+    /// Start an interactive session that evaluates Mun expressions
+    Repl(repl::Args),
 }
 
 #[derive(Copy, Debug, Clone, PartialEq, Eq)]
@@ -55,9 +79,16 @@ where
     let args = Args::parse_from(args);
     match args.command {
         Command::Build(args) => build::build(args),
+        Command::Check(args) => check::check(args),
+        Command::Daemon(args) => daemon::daemon(args),
+        Command::Fmt(args) => fmt::fmt(args),
         Command::LanguageServer(args) => language_server::language_server(args),
         Command::New(args) => new::new(args),
         Command::Init(args) => init::init(args),
         Command::Start(args) => start::start(args),
+        Command::Test(args) => test::test(args),
+        Command::Bench(args) => bench::bench(args),
+        Command::Bindgen(args) => bindgen::bindgen(args),
+        Command::Repl(args) => repl::repl(args),
     }
 }
@@ -1,5 +1,12 @@
+pub mod bench;
+pub mod bindgen;
 pub mod build;
+pub mod check;
+pub mod daemon;
+pub mod fmt;
 pub mod init;
 pub mod language_server;
 pub mod new;
+pub mod repl;
 pub mod start;
+pub mod test;
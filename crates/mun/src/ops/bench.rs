@@ -0,0 +1,305 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context};
+use mun_compiler::{ensure_package_output_dir, Config, Driver};
+use mun_runtime::Runtime;
+
+use crate::{
+    ops::{
+        build::{display_color, locate_manifest, UseColor},
+        test::build_and_load,
+    },
+    ExitStatus,
+};
+
+/// The time during which a benchmark is run before it is measured.
+const WARMUP_TIME: Duration = Duration::from_millis(500);
+
+/// The number of samples that are measured for every benchmark.
+const NUM_SAMPLES: usize = 50;
+
+/// The approximate time it takes to measure a single sample.
+const SAMPLE_TIME: Duration = Duration::from_millis(10);
+
+/// Relative changes compared to the baseline that are smaller than this are
+/// considered noise.
+const NOISE_THRESHOLD: f64 = 0.05;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Only run the benchmarks whose name contains this string
+    filter: Option<String>,
+
+    /// Path to the manifest of the project
+    #[clap(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Use color in output
+    #[clap(long, value_enum)]
+    color: Option<UseColor>,
+
+    /// The name of the baseline to compare the results against
+    #[clap(long, default_value_t = String::from("base"))]
+    baseline: String,
+
+    /// The name of the baseline to store the results as
+    #[clap(long, default_value_t = String::from("base"))]
+    save_baseline: String,
+}
+
+/// The time a single iteration of a benchmark takes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Measurement {
+    /// The mean time of an iteration in nanoseconds
+    mean: f64,
+
+    /// The standard deviation of the time of an iteration in nanoseconds
+    std_dev: f64,
+
+    /// The number of samples that were rejected as outliers
+    num_outliers: usize,
+}
+
+impl Measurement {
+    /// Computes the mean and standard deviation of the time per iteration of
+    /// the specified `samples`. Samples that lie more than 1.5 times the
+    /// interquartile range below the first or above the third quartile are
+    /// rejected as outliers.
+    fn from_samples(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(f64::total_cmp);
+
+        let q1 = percentile(&samples, 0.25);
+        let q3 = percentile(&samples, 0.75);
+        let iqr = q3 - q1;
+        let (low, high) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+
+        let num_samples = samples.len();
+        samples.retain(|&sample| (low..=high).contains(&sample));
+
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance = samples
+            .iter()
+            .map(|sample| (sample - mean).powi(2))
+            .sum::<f64>()
+            / samples.len() as f64;
+
+        Measurement {
+            mean,
+            std_dev: variance.sqrt(),
+            num_outliers: num_samples - samples.len(),
+        }
+    }
+}
+
+/// Returns the `p`th percentile of the non-empty and sorted `samples`,
+/// interpolating between the closest samples.
+fn percentile(samples: &[f64], p: f64) -> f64 {
+    let rank = p * (samples.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    samples[lower] + (samples[upper] - samples[lower]) * (rank - lower as f64)
+}
+
+/// This method is invoked when the executable is run with the `bench` argument
+/// indicating that a user requested us to build a project in the current
+/// directory or one of its parent directories and to measure all of its
+/// `#[bench]` functions.
+pub fn bench(args: Args) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting bench");
+
+    let display_colors = display_color(args.color);
+    let manifest_path = locate_manifest(args.manifest_path.as_deref())?;
+
+    log::info!("located manifest at: {}", manifest_path.display());
+
+    let config = Config::default();
+    let (package, mut driver) = Driver::with_package_path(&manifest_path, config.clone())?;
+    let Some(runtimes) = build_and_load(&mut driver, display_colors)? else {
+        return Ok(ExitStatus::Error);
+    };
+
+    let baseline_dir = ensure_package_output_dir(&package, &config)?.join("bench");
+    let baseline = read_baseline(&baseline_path(&baseline_dir, &args.baseline))?;
+
+    // Every assembly is loaded together with its dependencies, so the same
+    // benchmark can be encountered more than once.
+    let mut benches_run = HashSet::new();
+    let mut results = BTreeMap::new();
+    for runtime in &runtimes {
+        let benches: Vec<String> = runtime
+            .bench_functions()
+            .into_iter()
+            .filter(|name| {
+                args.filter
+                    .as_ref()
+                    .map_or(true, |f| name.contains(f.as_str()))
+            })
+            .filter(|name| benches_run.insert(name.clone()))
+            .collect();
+        for name in benches {
+            let measurement = run_bench(runtime, &name)?;
+            print!(
+                "bench {name} ... {:.1} ns/iter (+/- {:.1})",
+                measurement.mean, measurement.std_dev
+            );
+            if measurement.num_outliers > 0 {
+                print!(" [{} outliers rejected]", measurement.num_outliers);
+            }
+            println!();
+
+            if let Some(&base) = baseline.get(&name) {
+                println!("    {}", compare_to_baseline(measurement.mean, base));
+            }
+            results.insert(name, measurement.mean);
+        }
+    }
+
+    println!("\nbench result: {} measured", results.len());
+
+    // Merge the results into the saved baseline, so a filtered run does not
+    // discard the results of other benchmarks.
+    let save_path = baseline_path(&baseline_dir, &args.save_baseline);
+    let mut saved = read_baseline(&save_path)?;
+    saved.extend(results);
+    write_baseline(&save_path, &saved)?;
+
+    Ok(ExitStatus::Success)
+}
+
+/// Measures the time per iteration of the benchmark `name`.
+fn run_bench(runtime: &Runtime, name: &str) -> Result<Measurement, anyhow::Error> {
+    // Warm up, doubling the number of iterations until the warmup time has passed,
+    // to estimate the time per iteration.
+    let start = Instant::now();
+    let mut iterations = 1;
+    let mut total_iterations = 0;
+    while start.elapsed() < WARMUP_TIME {
+        run_iterations(runtime, name, iterations)?;
+        total_iterations += iterations;
+        iterations *= 2;
+    }
+    let estimate = start.elapsed().as_nanos() as f64 / total_iterations as f64;
+
+    let iterations_per_sample = ((SAMPLE_TIME.as_nanos() as f64 / estimate) as usize).max(1);
+    let samples = (0..NUM_SAMPLES)
+        .map(|_| -> Result<f64, anyhow::Error> {
+            let start = Instant::now();
+            run_iterations(runtime, name, iterations_per_sample)?;
+            Ok(start.elapsed().as_nanos() as f64 / iterations_per_sample as f64)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Measurement::from_samples(samples))
+}
+
+/// Invokes the benchmark `name` the specified number of times.
+fn run_iterations(runtime: &Runtime, name: &str, iterations: usize) -> anyhow::Result<()> {
+    runtime
+        .invoke_batch::<(), ()>(name, &vec![(); iterations])
+        .map(|_| ())
+        .map_err(|e| anyhow!("{}", e))
+}
+
+/// Describes the change of the mean time per iteration compared to the
+/// baseline.
+fn compare_to_baseline(mean: f64, base: f64) -> String {
+    let change = (mean - base) / base;
+    let verdict = if change > NOISE_THRESHOLD {
+        "regressed"
+    } else if change < -NOISE_THRESHOLD {
+        "improved"
+    } else {
+        "within noise threshold"
+    };
+    format!("change: {:+.2}% ({verdict})", change * 100.0)
+}
+
+/// Returns the path of the file that stores the baseline called `name`.
+fn baseline_path(baseline_dir: &Path, name: &str) -> PathBuf {
+    baseline_dir.join(name).with_extension("txt")
+}
+
+/// Reads the mean time per iteration of every benchmark from a baseline file.
+/// Every line contains the name of a benchmark, followed by its mean time in
+/// nanoseconds. Returns an empty baseline if the file does not exist.
+fn read_baseline(path: &Path) -> Result<BTreeMap<String, f64>, anyhow::Error> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read baseline '{}'", path.display()))?;
+    Ok(parse_baseline(&contents))
+}
+
+/// Parses the contents of a baseline file, skipping malformed lines.
+fn parse_baseline(contents: &str) -> BTreeMap<String, f64> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (name, mean) = line.split_once(' ')?;
+            Some((name.to_owned(), mean.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Writes the mean time per iteration of every benchmark to a baseline file.
+fn write_baseline(path: &Path, baseline: &BTreeMap<String, f64>) -> Result<(), anyhow::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let contents = baseline
+        .iter()
+        .fold(String::new(), |mut contents, (name, mean)| {
+            let _ = writeln!(contents, "{name} {mean}");
+            contents
+        });
+    std::fs::write(path, contents)
+        .with_context(|| format!("could not write baseline '{}'", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::{compare_to_baseline, parse_baseline, Measurement};
+
+    #[test]
+    fn test_reject_outliers() {
+        let samples = vec![10.0, 11.0, 9.0, 10.0, 12.0, 10.0, 11.0, 10.0, 100.0];
+
+        let measurement = Measurement::from_samples(samples);
+        assert_eq!(measurement.num_outliers, 1);
+        assert_eq!(measurement.mean, 10.375);
+    }
+
+    #[test]
+    fn test_parse_baseline() {
+        let baseline = parse_baseline("fib 1234.5\nmalformed\nsort 10\n");
+        assert_eq!(
+            baseline,
+            BTreeMap::from([(String::from("fib"), 1234.5), (String::from("sort"), 10.0)])
+        );
+    }
+
+    #[test]
+    fn test_compare_to_baseline() {
+        assert_eq!(
+            compare_to_baseline(120.0, 100.0),
+            "change: +20.00% (regressed)"
+        );
+        assert_eq!(
+            compare_to_baseline(80.0, 100.0),
+            "change: -20.00% (improved)"
+        );
+        assert_eq!(
+            compare_to_baseline(101.0, 100.0),
+            "change: +1.00% (within noise threshold)"
+        );
+    }
+}
@@ -0,0 +1,467 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    path::PathBuf,
+};
+
+use anyhow::anyhow;
+use mun_abi::{self as abi, PrimitiveType};
+use mun_libloader::MunLibrary;
+
+use crate::ExitStatus;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The library to generate bindings for
+    library: PathBuf,
+
+    /// The file to write the bindings to. Defaults to stdout.
+    #[clap(long, short)]
+    output: Option<PathBuf>,
+}
+
+/// Generates typed Rust bindings for the structs and functions of a munlib.
+pub fn bindgen(args: Args) -> anyhow::Result<ExitStatus> {
+    // Safety: we assume that the passed in library is safe
+    let library = unsafe { MunLibrary::new(&args.library) }?;
+
+    // Safety: we assume that the passed in library is safe
+    let version = unsafe { library.get_abi_version() };
+    if !abi::is_compatible_abi_version(version) {
+        return Err(anyhow!(
+            "ABI version mismatch. `{}` is `{version}` but mun supports `{}` through `{}`",
+            args.library.display(),
+            abi::MIN_ABI_VERSION,
+            abi::ABI_VERSION
+        ));
+    }
+
+    let origin = args
+        .library
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    // Safety: the ABI version of the library is compatible
+    let info = unsafe { library.get_info() };
+    let bindings = generate_bindings(&info, &origin)?;
+
+    match args.output {
+        Some(output) => std::fs::write(&output, bindings)
+            .map_err(|e| anyhow!("failed to write '{}': {e}", output.display()))?,
+        None => print!("{bindings}"),
+    }
+
+    Ok(ExitStatus::Success)
+}
+
+/// The structs and functions of a Mun module, and its submodules.
+#[derive(Default)]
+struct Module<'a> {
+    structs: Vec<&'a abi::TypeDefinition<'a>>,
+    functions: Vec<&'a abi::FunctionDefinition<'a>>,
+    submodules: BTreeMap<&'a str, Module<'a>>,
+}
+
+impl<'a> Module<'a> {
+    /// Returns the module at `path`, relative to this module.
+    fn submodule(&mut self, path: &[&'a str]) -> &mut Module<'a> {
+        path.iter().fold(self, |module, name| {
+            module.submodules.entry(*name).or_default()
+        })
+    }
+}
+
+/// Generates Rust code for the symbols of an assembly.
+struct BindingsGenerator<'a> {
+    /// The Rust types of all primitive types
+    primitives: HashMap<abi::Guid, &'static str>,
+    /// The paths of the structs that are defined in the assembly
+    struct_paths: HashMap<abi::Guid, Vec<&'a str>>,
+    /// The names of all types that are referenced by the assembly
+    type_names: HashMap<&'a abi::TypeId<'a>, &'a str>,
+}
+
+/// Generates Rust bindings for all structs and public functions of the
+/// assembly described by `info`.
+///
+/// Every Mun struct is wrapped in a newtype around a
+/// [`StructRef`](mun_runtime::StructRef) with typed accessors for its fields,
+/// and every function gets a typed wrapper around
+/// [`Runtime::invoke`](mun_runtime::Runtime::invoke). The items are nested in
+/// Rust modules that mirror the Mun modules in which they are defined. An error
+/// is returned if an item uses a type that cannot be marshalled, e.g. an opaque
+/// type or a struct of another assembly.
+fn generate_bindings<'a>(info: &'a abi::AssemblyInfo<'a>, origin: &str) -> anyhow::Result<String> {
+    // `usize` and `isize` share the type ids of the integers of the target's
+    // pointer width, so they are mapped to e.g. `u64` and `i64`.
+    let primitives = [
+        (i8::guid(), i8::name()),
+        (i16::guid(), i16::name()),
+        (i32::guid(), i32::name()),
+        (i64::guid(), i64::name()),
+        (i128::guid(), i128::name()),
+        (u8::guid(), u8::name()),
+        (u16::guid(), u16::name()),
+        (u32::guid(), u32::name()),
+        (u64::guid(), u64::name()),
+        (u128::guid(), u128::name()),
+        (f32::guid(), f32::name()),
+        (f64::guid(), f64::name()),
+        (bool::guid(), bool::name()),
+        (<()>::guid(), <()>::name()),
+    ]
+    .into_iter()
+    .map(|(guid, name)| {
+        let rust_name = match name.strip_prefix("core::") {
+            Some("empty") => "()",
+            Some(name) => name,
+            None => name,
+        };
+        (*guid, rust_name)
+    })
+    .collect();
+
+    let mut root = Module::default();
+    let mut struct_paths = HashMap::new();
+    for ty in info.symbols.types() {
+        if !ty.data.is_struct() {
+            continue;
+        }
+        let path: Vec<&str> = ty.name().split("::").collect();
+        let (_, module_path) = path.split_last().expect("empty type name");
+        root.submodule(module_path).structs.push(ty);
+        struct_paths.insert(*ty.as_concrete(), path);
+    }
+    for function in info.symbols.functions() {
+        let path: Vec<&str> = function.prototype.name().split("::").collect();
+        let (_, module_path) = path.split_last().expect("empty function name");
+        root.submodule(module_path).functions.push(function);
+    }
+
+    let generator = BindingsGenerator {
+        primitives,
+        struct_paths,
+        type_names: info
+            .type_lut
+            .iter()
+            .map(|(type_id, _, name)| (type_id, name))
+            .collect(),
+    };
+
+    let mut bindings =
+        format!("// Generated by `mun bindgen` from `{origin}`. Do not edit this file by hand.\n");
+    generator.write_module(&mut bindings, &root, 0)?;
+    Ok(bindings)
+}
+
+impl<'a> BindingsGenerator<'a> {
+    /// Writes the bindings of the items of `module`, and its submodules, which
+    /// is nested `depth` modules deep.
+    fn write_module(
+        &self,
+        out: &mut String,
+        module: &Module<'a>,
+        depth: usize,
+    ) -> anyhow::Result<()> {
+        for ty in module.structs.iter() {
+            out.push('\n');
+            self.write_struct(out, ty, depth)?;
+        }
+
+        for function in module.functions.iter() {
+            out.push('\n');
+            self.write_function(out, function, depth)?;
+        }
+
+        for (name, submodule) in module.submodules.iter() {
+            let mut contents = String::new();
+            self.write_module(&mut contents, submodule, depth + 1)?;
+
+            write!(out, "\npub mod {} {{", rust_ident(name))?;
+            for line in contents.trim_start_matches('\n').lines() {
+                if line.is_empty() {
+                    out.push('\n');
+                } else {
+                    write!(out, "\n    {line}")?;
+                }
+            }
+            out.push_str("\n}\n");
+        }
+
+        Ok(())
+    }
+
+    /// Writes a typed wrapper for the struct `ty`.
+    fn write_struct(
+        &self,
+        out: &mut String,
+        ty: &'a abi::TypeDefinition<'a>,
+        depth: usize,
+    ) -> anyhow::Result<()> {
+        let struct_info = ty.as_struct().expect("expected a struct");
+        let full_name = ty.name();
+        let name = rust_ident(full_name.rsplit("::").next().unwrap_or(full_name));
+
+        writeln!(
+            out,
+            "/// A reference to an instance of the Mun struct `{full_name}`."
+        )?;
+        writeln!(out, "#[derive(Clone)]")?;
+        writeln!(
+            out,
+            "pub struct {name}<'r>(pub ::mun_runtime::StructRef<'r>);"
+        )?;
+        writeln!(out)?;
+        writeln!(out, "impl<'r> {name}<'r> {{")?;
+        writeln!(out, "    /// The name of the struct in Mun")?;
+        writeln!(out, "    pub const NAME: &'static str = \"{full_name}\";")?;
+        for (field_name, field_type) in struct_info
+            .field_names()
+            .zip(struct_info.field_types().iter())
+        {
+            writeln!(out)?;
+            let field_ty = self.rust_type(field_type, depth).ok_or_else(|| {
+                anyhow!(
+                    "cannot generate bindings for the field `{field_name}` of `{full_name}`: the type `{}` is not supported",
+                    self.type_name(field_type)
+                )
+            })?;
+
+            let getter = if field_name.starts_with(|c: char| c.is_ascii_digit()) {
+                format!("field_{field_name}")
+            } else {
+                field_name.to_owned()
+            };
+
+            writeln!(
+                out,
+                "    /// Returns the value of the field `{field_name}`."
+            )?;
+            writeln!(
+                out,
+                "    pub fn {}(&self) -> {field_ty} {{",
+                rust_ident(&getter)
+            )?;
+            writeln!(
+                out,
+                "        self.0.get(\"{field_name}\").expect(\"invalid binding for `{full_name}`\")"
+            )?;
+            writeln!(out, "    }}")?;
+            writeln!(out)?;
+            writeln!(out, "    /// Sets the value of the field `{field_name}`.")?;
+            writeln!(
+                out,
+                "    pub fn set_{getter}(&mut self, value: {field_ty}) {{"
+            )?;
+            writeln!(
+                out,
+                "        self.0.set(\"{field_name}\", value).expect(\"invalid binding for `{full_name}`\");"
+            )?;
+            writeln!(out, "    }}")?;
+        }
+        writeln!(out, "}}")?;
+
+        writeln!(out)?;
+        writeln!(
+            out,
+            "impl<'r> ::mun_runtime::ArgumentReflection for {name}<'r> {{"
+        )?;
+        writeln!(
+            out,
+            "    fn type_info(&self, runtime: &::mun_runtime::Runtime) -> ::mun_runtime::Type {{"
+        )?;
+        writeln!(
+            out,
+            "        ::mun_runtime::ArgumentReflection::type_info(&self.0, runtime)"
+        )?;
+        writeln!(out, "    }}")?;
+        writeln!(out, "}}")?;
+
+        writeln!(out)?;
+        writeln!(
+            out,
+            "impl<'r> ::mun_runtime::ReturnTypeReflection for {name}<'r> {{"
+        )?;
+        writeln!(
+            out,
+            "    fn accepts_type(ty: &::mun_runtime::Type) -> bool {{"
+        )?;
+        writeln!(out, "        ty.is_struct() && ty.name() == Self::NAME")?;
+        writeln!(out, "    }}")?;
+        writeln!(out)?;
+        writeln!(out, "    fn type_hint() -> &'static str {{")?;
+        writeln!(out, "        Self::NAME")?;
+        writeln!(out, "    }}")?;
+        writeln!(out, "}}")?;
+
+        writeln!(out)?;
+        writeln!(out, "impl<'r> ::mun_runtime::Marshal<'r> for {name}<'r> {{")?;
+        writeln!(
+            out,
+            "    type MunType = <::mun_runtime::StructRef<'r> as ::mun_runtime::Marshal<'r>>::MunType;"
+        )?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "    fn marshal_from<'t>(value: Self::MunType, runtime: &'t ::mun_runtime::Runtime) -> Self"
+        )?;
+        writeln!(out, "    where")?;
+        writeln!(out, "        Self: 'r,")?;
+        writeln!(out, "        't: 'r,")?;
+        writeln!(out, "    {{")?;
+        writeln!(
+            out,
+            "        Self(::mun_runtime::Marshal::marshal_from(value, runtime))"
+        )?;
+        writeln!(out, "    }}")?;
+        writeln!(out)?;
+        writeln!(out, "    fn marshal_into(self) -> Self::MunType {{")?;
+        writeln!(out, "        ::mun_runtime::Marshal::marshal_into(self.0)")?;
+        writeln!(out, "    }}")?;
+        writeln!(out)?;
+        writeln!(out, "    fn marshal_from_ptr<'t>(")?;
+        writeln!(out, "        ptr: ::std::ptr::NonNull<Self::MunType>,")?;
+        writeln!(out, "        runtime: &'t ::mun_runtime::Runtime,")?;
+        writeln!(out, "        type_info: &::mun_runtime::Type,")?;
+        writeln!(out, "    ) -> Self")?;
+        writeln!(out, "    where")?;
+        writeln!(out, "        Self: 'r,")?;
+        writeln!(out, "        't: 'r,")?;
+        writeln!(out, "    {{")?;
+        writeln!(
+            out,
+            "        Self(::mun_runtime::Marshal::marshal_from_ptr(ptr, runtime, type_info))"
+        )?;
+        writeln!(out, "    }}")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "    fn marshal_to_ptr(value: Self, ptr: ::std::ptr::NonNull<Self::MunType>, type_info: &::mun_runtime::Type) {{"
+        )?;
+        writeln!(
+            out,
+            "        ::mun_runtime::Marshal::marshal_to_ptr(value.0, ptr, type_info);"
+        )?;
+        writeln!(out, "    }}")?;
+        writeln!(out, "}}")?;
+
+        Ok(())
+    }
+
+    /// Writes a typed wrapper that invokes the Mun function `function`.
+    fn write_function(
+        &self,
+        out: &mut String,
+        function: &'a abi::FunctionDefinition<'a>,
+        depth: usize,
+    ) -> anyhow::Result<()> {
+        let full_name = function.prototype.name();
+        let name = rust_ident(full_name.rsplit("::").next().unwrap_or(full_name));
+        let signature = &function.prototype.signature;
+
+        let rust_type = |type_id: &abi::TypeId<'a>| {
+            self.rust_type(type_id, depth).ok_or_else(|| {
+                anyhow!(
+                    "cannot generate bindings for the function `{full_name}`: the type `{}` is not supported",
+                    self.type_name(type_id)
+                )
+            })
+        };
+
+        let arg_types = signature
+            .arg_types()
+            .iter()
+            .map(rust_type)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let return_type = match signature.return_type() {
+            Some(return_type) => rust_type(&return_type)?,
+            None => String::from("()"),
+        };
+
+        let mut params = String::new();
+        for (idx, ty) in arg_types.iter().enumerate() {
+            write!(params, ", arg{idx}: {ty}")?;
+        }
+        let (args_type, args) = if arg_types.len() == 1 {
+            (format!("({},)", arg_types[0]), String::from("(arg0,)"))
+        } else {
+            (
+                format!("({})", arg_types.join(", ")),
+                format!(
+                    "({})",
+                    (0..arg_types.len())
+                        .map(|idx| format!("arg{idx}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )
+        };
+
+        // Only introduce a lifetime if one of the arguments references the runtime,
+        // otherwise the lifetime of the return type is elided
+        let (generics, runtime_ref, return_type) = if args_type.contains("'r") {
+            ("<'r>", "&'r ", return_type)
+        } else {
+            ("", "&", return_type.replace("'r", "'_"))
+        };
+
+        writeln!(out, "/// Invokes the Mun function `{full_name}`.")?;
+        writeln!(
+            out,
+            "pub fn {name}{generics}(runtime: {runtime_ref}::mun_runtime::Runtime{params}) -> ::std::result::Result<{return_type}, ::mun_runtime::InvokeErr<'static, {args_type}>> {{"
+        )?;
+        writeln!(out, "    runtime.invoke(\"{full_name}\", {args})")?;
+        writeln!(out, "}}")?;
+
+        Ok(())
+    }
+
+    /// Returns the Rust type that represents `type_id` in a module that is
+    /// nested `depth` modules deep, or `None` if the type is not supported.
+    fn rust_type(&self, type_id: &abi::TypeId<'a>, depth: usize) -> Option<String> {
+        match type_id {
+            abi::TypeId::Concrete(guid) => {
+                if let Some(primitive) = self.primitives.get(guid) {
+                    Some((*primitive).to_owned())
+                } else {
+                    let path = self.struct_paths.get(guid)?;
+                    let path = path.iter().map(|name| rust_ident(name)).collect::<Vec<_>>();
+                    Some(format!(
+                        "{}{}<'r>",
+                        "super::".repeat(depth),
+                        path.join("::")
+                    ))
+                }
+            }
+            abi::TypeId::Array(array) => {
+                let element_type = self.rust_type(array.element, depth)?;
+                Some(format!("::mun_runtime::ArrayRef<'r, {element_type}>"))
+            }
+            abi::TypeId::Pointer(_) => None,
+        }
+    }
+
+    /// Returns the name of `type_id` for use in error messages.
+    fn type_name(&self, type_id: &abi::TypeId<'a>) -> String {
+        self.type_names
+            .get(type_id)
+            .map_or_else(|| type_id.to_string(), |name| (*name).to_owned())
+    }
+}
+
+/// Returns `name` as a Rust identifier, escaping Rust keywords.
+fn rust_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+        "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+        "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe",
+        "unsized", "use", "virtual", "where", "while", "yield",
+    ];
+
+    if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_owned()
+    }
+}
@@ -4,7 +4,9 @@ use std::{
 };
 
 use anyhow::anyhow;
-use mun_compiler::{Config, DisplayColor, Target};
+use mun_compiler::{Config, DisplayColor, Driver, Emit, Seal, Target};
+use mun_libloader::{integrity, MunLibrary};
+use mun_memory::{diff::memory_diff, type_table::TypeTable, Type};
 use mun_project::MANIFEST_FILENAME;
 
 use crate::ExitStatus;
@@ -16,24 +18,60 @@ pub enum UseColor {
     Auto,
 }
 
+/// A kind of output that is emitted for every assembly
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum EmitKind {
+    /// The shared library (*.munlib)
+    Link,
+    /// The LLVM IR (*.ll)
+    LlvmIr,
+    /// The target-specific assembly (*.s)
+    Asm,
+    /// A human-readable description of the functions, types, dispatch table,
+    /// and type lookup table of the *.munlib (*.abi)
+    Abi,
+}
+
 #[derive(clap::Args)]
 pub struct Args {
     /// Path to the manifest of the project
     #[clap(long)]
     manifest_path: Option<PathBuf>,
 
-    /// Optimization level [0,3]
-    #[clap(long, short = 'O', default_value_t = 2)]
-    opt_level: u8,
+    /// Optimization level [0,3] [default: the `opt-level` setting of the
+    /// package, or 2]
+    #[clap(long, short = 'O')]
+    opt_level: Option<u8>,
 
     /// Use color in output
     #[clap(long, value_enum)]
     color: Option<UseColor>,
 
-    /// Emits IR instead of a *.munlib
-    #[clap(long)]
+    /// The kinds of output to emit for every assembly, separated by commas
+    /// [default: link]
+    #[clap(long, value_enum, value_delimiter = ',')]
+    emit: Vec<EmitKind>,
+
+    /// Emits IR instead of a *.munlib; the same as `--emit=llvm-ir`
+    #[clap(long, conflicts_with = "emit")]
     emit_ir: bool,
 
+    /// Check integer arithmetic for overflow [default: the `overflow-checks`
+    /// setting of the package, or enabled if the optimization level is 0]
+    #[clap(long)]
+    overflow_checks: Option<bool>,
+
+    /// Optimize each assembly as a whole after its modules are linked
+    /// [default: the `lto` setting of the package, or disabled]
+    #[clap(long)]
+    lto: Option<bool>,
+
+    /// Instrument functions to report their calls to the profiler of the
+    /// runtime, which measures how often they are called and how long they
+    /// take
+    #[clap(long)]
+    profiling: bool,
+
     /// Run the compiler in watch mode. Watch input files and trigger
     /// recompilation on changes.
     #[clap(long)]
@@ -42,6 +80,23 @@ pub struct Args {
     /// Target for machine code
     #[clap(long, value_parser=parse_target_triple)]
     target: Option<Target>,
+
+    /// Print how hot reloading from the specified *.munlib to the newly built
+    /// one maps the memory of allocated structs
+    #[clap(long, value_name = "MUNLIB", conflicts_with_all = ["watch", "emit", "emit_ir"])]
+    diff_against: Option<PathBuf>,
+
+    /// Embed the content hash of each *.munlib, which allows a runtime to
+    /// detect corrupted libraries
+    #[clap(long, conflicts_with = "emit_ir")]
+    hash: bool,
+
+    /// Embed the content hash of each *.munlib and sign it with the Ed25519
+    /// secret key in the specified file, which contains 64 hexadecimal digits.
+    /// This allows a runtime to refuse to load tampered libraries. The
+    /// corresponding public key is printed.
+    #[clap(long, value_name = "KEY", conflicts_with = "emit_ir")]
+    sign: Option<PathBuf>,
 }
 
 fn parse_target_triple(target_triple: &str) -> Result<Target, String> {
@@ -55,16 +110,176 @@ fn parse_target_triple(target_triple: &str) -> Result<Target, String> {
 pub fn build(args: Args) -> Result<ExitStatus, anyhow::Error> {
     log::trace!("starting build");
 
-    let optimization_lvl = match args.opt_level {
-        0 => mun_compiler::OptimizationLevel::None,
-        1 => mun_compiler::OptimizationLevel::Less,
-        2 => mun_compiler::OptimizationLevel::Default,
-        3 => mun_compiler::OptimizationLevel::Aggressive,
-        _ => return Err(anyhow!("Only optimization levels 0-3 are supported")),
+    let optimization_lvl = optimization_level(args.opt_level)?;
+
+    let display_colors = display_color(args.color);
+
+    let manifest_path = locate_manifest(args.manifest_path.as_deref())?;
+
+    log::info!("located build manifest at: {}", manifest_path.display());
+
+    let seal = if let Some(key_path) = &args.sign {
+        let signing_key = std::fs::read_to_string(key_path)
+            .map_err(anyhow::Error::from)
+            .and_then(|key| Ok(integrity::parse_signing_key(&key)?))
+            .map_err(|e| anyhow!("could not read signing key `{}`: {e}", key_path.display()))?;
+        eprintln!(
+            "Signing munlibs with public key {}",
+            integrity::encode_key(&signing_key.verifying_key())
+        );
+        Some(Seal::Sign(Box::new(signing_key)))
+    } else if args.hash {
+        Some(Seal::Hash)
+    } else {
+        None
+    };
+
+    let compiler_options = Config {
+        target: args
+            .target
+            .unwrap_or_else(|| Target::host_target().expect("unable to determine host target")),
+        optimization_lvl,
+        out_dir: None,
+        emit: emit(&args.emit, args.emit_ir),
+        overflow_checks: args.overflow_checks,
+        lto: args.lto,
+        profiling: args.profiling,
+        seal,
     };
 
-    let display_colors = args
-        .color
+    if let Some(old_library_path) = args.diff_against {
+        build_and_diff(
+            &manifest_path,
+            &old_library_path,
+            compiler_options,
+            display_colors,
+        )
+    } else if args.watch {
+        mun_compiler_daemon::compile_and_watch_manifest(
+            &manifest_path,
+            compiler_options,
+            display_colors,
+        )
+    } else {
+        mun_compiler::compile_manifest(&manifest_path, compiler_options, display_colors)
+    }
+    .map(Into::into)
+}
+
+/// Builds the package at `manifest_path` and prints how the memory of
+/// allocated structs is mapped when hot reloading from the munlib at
+/// `old_library_path` to the newly built munlib of the same module.
+fn build_and_diff(
+    manifest_path: &Path,
+    old_library_path: &Path,
+    config: Config,
+    display_colors: DisplayColor,
+) -> Result<bool, anyhow::Error> {
+    // The old munlib might be overwritten by the build, so load it first.
+    // Safety: Loading a munlib executes its initialisation routines. We trust the
+    // user to only compare munlibs that were generated by the Mun compiler.
+    let (module_path, old_types) = unsafe { load_types(old_library_path) }?;
+
+    let (_package, mut driver) = Driver::with_package_path(manifest_path, config)?;
+    if driver.emit_diagnostics(&mut std::io::stderr(), display_colors)? {
+        return Ok(false);
+    }
+    driver.write_all_assemblies(false)?;
+
+    for new_library_path in driver.assembly_output_paths() {
+        // Safety: The munlib was just generated by the Mun compiler.
+        let (new_module_path, new_types) = unsafe { load_types(&new_library_path) }?;
+        if new_module_path != module_path {
+            continue;
+        }
+
+        let diff = memory_diff(&old_types, &new_types);
+        println!(
+            "Hot reloading from '{}' to '{}':",
+            old_library_path.display(),
+            new_library_path.display()
+        );
+        if diff.is_empty() {
+            println!("No allocated memory has to be mapped.");
+        } else {
+            print!("{diff}");
+            if !diff.is_lossless() {
+                println!("warning: the values of deleted structs and fields are lost");
+            }
+        }
+        return Ok(true);
+    }
+
+    Err(anyhow!(
+        "the package does not contain the module `{module_path}` of '{}'",
+        old_library_path.display()
+    ))
+}
+
+/// Loads the path of the module and the types that are defined by the munlib
+/// at `library_path`.
+///
+/// # Safety
+///
+/// Loading a munlib executes its initialisation routines, see
+/// [`MunLibrary::new`].
+unsafe fn load_types(library_path: &Path) -> Result<(String, Vec<Type>), anyhow::Error> {
+    let library = MunLibrary::new(library_path)?;
+    let info = library.get_info();
+
+    let (_, types) = Type::try_from_abi(
+        info.symbols.types(),
+        info.field_defaults(),
+        TypeTable::default(),
+    )
+    .map_err(|e| {
+        anyhow!(
+            "could not load the types of '{}': {e}",
+            library_path.display()
+        )
+    })?;
+
+    Ok((info.symbols.path().to_owned(), types))
+}
+
+/// Determines the kinds of output to emit from the command line arguments.
+fn emit(kinds: &[EmitKind], emit_ir: bool) -> Emit {
+    if emit_ir {
+        Emit {
+            link: false,
+            llvm_ir: true,
+            asm: false,
+            abi: false,
+        }
+    } else if kinds.is_empty() {
+        Emit::default()
+    } else {
+        Emit {
+            link: kinds.contains(&EmitKind::Link),
+            llvm_ir: kinds.contains(&EmitKind::LlvmIr),
+            asm: kinds.contains(&EmitKind::Asm),
+            abi: kinds.contains(&EmitKind::Abi),
+        }
+    }
+}
+
+/// Converts the optimization level specified on the command line. Returns
+/// `None` if no level was specified.
+pub(crate) fn optimization_level(
+    opt_level: Option<u8>,
+) -> Result<Option<mun_compiler::OptimizationLevel>, anyhow::Error> {
+    opt_level
+        .map(|level| {
+            mun_compiler::optimization_level_from_number(level)
+                .ok_or_else(|| anyhow!("Only optimization levels 0-3 are supported"))
+        })
+        .transpose()
+}
+
+/// Determines whether to use colors in the output from the command line
+/// argument or the `MUN_TERMINAL_COLOR` environment variable.
+pub(crate) fn display_color(color: Option<UseColor>) -> DisplayColor {
+    color
         .map(|clr| match clr {
             UseColor::Disable => DisplayColor::Disable,
             UseColor::Enable => DisplayColor::Enable,
@@ -79,10 +294,14 @@ pub fn build(args: Args) -> Result<ExitStatus, anyhow::Error> {
                 })
                 .ok()
         })
-        .unwrap_or(DisplayColor::Auto);
+        .unwrap_or(DisplayColor::Auto)
+}
 
-    // Locate the manifest
-    let manifest_path = match &args.manifest_path {
+/// Returns the path of the manifest specified on the command line or, if none
+/// was specified, locates the manifest in the current directory or one of its
+/// parents.
+pub(crate) fn locate_manifest(manifest_path: Option<&Path>) -> Result<PathBuf, anyhow::Error> {
+    match manifest_path {
         None => {
             let current_dir =
                 std::env::current_dir().expect("could not determine current working directory");
@@ -92,37 +311,15 @@ pub fn build(args: Args) -> Result<ExitStatus, anyhow::Error> {
                     MANIFEST_FILENAME,
                     current_dir.display()
                 )
-            })?
+            })
         }
-        Some(path) => std::fs::canonicalize(Path::new(&path)).map_err(|_error| {
+        Some(path) => std::fs::canonicalize(path).map_err(|_error| {
             anyhow::anyhow!(
                 "'{}' does not refer to a valid manifest path",
                 path.display()
             )
-        })?,
-    };
-
-    log::info!("located build manifest at: {}", manifest_path.display());
-
-    let compiler_options = Config {
-        target: args
-            .target
-            .unwrap_or_else(|| Target::host_target().expect("unable to determine host target")),
-        optimization_lvl,
-        out_dir: None,
-        emit_ir: args.emit_ir,
-    };
-
-    if args.watch {
-        mun_compiler_daemon::compile_and_watch_manifest(
-            &manifest_path,
-            compiler_options,
-            display_colors,
-        )
-    } else {
-        mun_compiler::compile_manifest(&manifest_path, compiler_options, display_colors)
+        }),
     }
-    .map(Into::into)
 }
 
 /// Find a Mun manifest file in the specified directory or one of its parents.
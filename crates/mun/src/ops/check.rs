@@ -0,0 +1,83 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use mun_compiler::{compute_source_relative_path, is_source_file, Config, Driver};
+
+use crate::{
+    ops::build::{display_color, locate_manifest, UseColor},
+    ExitStatus,
+};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to the manifest of the project
+    #[clap(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Use color in output
+    #[clap(long, value_enum)]
+    color: Option<UseColor>,
+
+    /// Read the contents of the source file at the specified path from stdin
+    /// instead of from disk, e.g. the unsaved contents of an editor buffer.
+    #[clap(long)]
+    stdin_file: Option<PathBuf>,
+}
+
+/// This method is invoked when the executable is run with the `check` argument
+/// indicating that a user requested us to report all diagnostics of a project
+/// in the current directory or one of its parent directories, without
+/// generating any assemblies.
+pub fn check(args: Args) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting check");
+
+    let display_colors = display_color(args.color);
+    let manifest_path = locate_manifest(args.manifest_path.as_deref())?;
+
+    log::info!("located manifest at: {}", manifest_path.display());
+
+    let (package, mut driver) = Driver::with_package_path(&manifest_path, Config::default())?;
+
+    // Overlay the source file with the contents from stdin
+    if let Some(stdin_file) = args.stdin_file {
+        if !is_source_file(&stdin_file) {
+            anyhow::bail!("'{}' is not a Mun source file", stdin_file.display());
+        }
+
+        let source_dir = package.source_directory();
+        let source_dir = std::fs::canonicalize(&source_dir).unwrap_or(source_dir);
+        let path = canonicalize_source_path(&stdin_file)?;
+        let relative_path = compute_source_relative_path(&source_dir, &path)?;
+
+        let mut contents = String::new();
+        std::io::stdin()
+            .read_to_string(&mut contents)
+            .context("could not read source file contents from stdin")?;
+
+        driver.set_overlay(relative_path, Some(contents))?;
+    }
+
+    let has_errors = driver.emit_diagnostics(&mut std::io::stderr(), display_colors)?;
+    Ok((!has_errors).into())
+}
+
+/// Returns the canonical, absolute form of `path`. The file doesn't have to
+/// exist on disk yet, in which case its parent directory is canonicalized
+/// instead.
+fn canonicalize_source_path(path: &Path) -> Result<PathBuf, anyhow::Error> {
+    if let Ok(path) = std::fs::canonicalize(path) {
+        return Ok(path);
+    }
+
+    let path = std::env::current_dir()
+        .context("could not determine the current directory")?
+        .join(path);
+    Ok(match (path.parent(), path.file_name()) {
+        (Some(parent), Some(file_name)) => std::fs::canonicalize(parent)
+            .map_or_else(|_| path.clone(), |parent| parent.join(file_name)),
+        _ => path,
+    })
+}
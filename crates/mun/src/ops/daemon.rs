@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use mun_compiler::Config;
+
+use crate::{
+    ops::build::{display_color, locate_manifest, optimization_level, UseColor},
+    ExitStatus,
+};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to the manifest of the project
+    #[clap(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Optimization level [0,3] [default: the `opt-level` setting of the
+    /// package, or 2]
+    #[clap(long, short = 'O')]
+    opt_level: Option<u8>,
+
+    /// Use color in output
+    #[clap(long, value_enum)]
+    color: Option<UseColor>,
+
+    /// The port on the loopback interface to listen on. By default, any free
+    /// port is used. The address is written to the `.mundaemon` file in the
+    /// output directory, where runtimes look for it.
+    #[clap(long, default_value_t = 0)]
+    port: u16,
+}
+
+/// This method is invoked when the executable is run with the `daemon`
+/// argument indicating that a user requested us to keep building a project in
+/// the current directory or one of its parent directories, and to serve build
+/// requests from runtimes.
+pub fn daemon(args: Args) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting daemon");
+
+    let display_colors = display_color(args.color);
+    let manifest_path = locate_manifest(args.manifest_path.as_deref())?;
+
+    log::info!("located build manifest at: {}", manifest_path.display());
+
+    let config = Config {
+        optimization_lvl: optimization_level(args.opt_level)?,
+        ..Config::default()
+    };
+
+    mun_compiler_daemon::serve_manifest(&manifest_path, config, display_colors, args.port)
+        .map(Into::into)
+}
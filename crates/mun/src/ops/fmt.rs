@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use mun_compiler::iter_source_files;
+use mun_project::Package;
+
+use crate::{ops::build::locate_manifest, ExitStatus};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The source files to format. If no files are specified, all source files
+    /// of the project are formatted.
+    files: Vec<PathBuf>,
+
+    /// Path to the manifest of the project
+    #[clap(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Only check whether the files are formatted, without changing them. If
+    /// a file is not formatted, its path is printed and the command fails.
+    #[clap(long)]
+    check: bool,
+}
+
+/// This method is invoked when the executable is run with the `fmt` argument
+/// indicating that a user requested us to format the source files of a project
+/// in the current directory or one of its parent directories.
+pub fn fmt(args: Args) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting fmt");
+
+    let files = if args.files.is_empty() {
+        let manifest_path = locate_manifest(args.manifest_path.as_deref())?;
+        log::info!("located manifest at: {}", manifest_path.display());
+
+        let package = Package::from_file(&manifest_path)?;
+        iter_source_files(&package.source_directory()).collect()
+    } else {
+        args.files
+    };
+
+    let mut success = true;
+    for path in files {
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("could not read '{}'", path.display()))?;
+
+        let formatted = match mun_fmt::format(&text) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                eprintln!("could not format '{}': {err}", path.display());
+                success = false;
+                continue;
+            }
+        };
+
+        if formatted == text {
+            continue;
+        }
+
+        if args.check {
+            println!("{} is not formatted", path.display());
+            success = false;
+        } else {
+            std::fs::write(&path, formatted)
+                .with_context(|| format!("could not write '{}'", path.display()))?;
+        }
+    }
+
+    Ok(success.into())
+}
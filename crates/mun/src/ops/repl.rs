@@ -0,0 +1,625 @@
+use std::{
+    io::{self, BufRead, Write},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+};
+
+use mun_compiler::{Config, DisplayColor, Driver, PathOrInline, RelativePathBuf};
+use mun_runtime::{ArrayRef, Marshal, ReturnTypeReflection, Runtime, StructRef, Type};
+
+use crate::{
+    ops::{
+        build::{display_color, UseColor},
+        test::describe_panic,
+    },
+    ExitStatus,
+};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Use color in output
+    #[clap(long, value_enum)]
+    color: Option<UseColor>,
+}
+
+/// The path of the file that contains the items that were entered
+const SOURCE_PATH: &str = "mod.mun";
+
+/// The name of the function that evaluates an entered expression
+const EVAL_FN_NAME: &str = "__repl_eval";
+
+const PROMPT: &str = ">> ";
+const CONTINUATION_PROMPT: &str = ".. ";
+
+const HELP: &str = "\
+Enter an expression to evaluate it, or an item, e.g. a function or struct, to
+define it. Items are public, and redefining an item replaces its previous
+definition.
+
+Commands:
+  :type <expr>  Show the type of an expression without evaluating it
+  :items        Show all items that were defined
+  :reset        Remove all items that were defined
+  :help         Show this message
+  :quit         Exit the REPL
+";
+
+/// This method is invoked when the executable is run with the `repl` argument
+/// indicating that a user requested us to start an interactive session, in
+/// which every entered expression is compiled, hot reloaded and evaluated.
+pub fn repl(args: Args) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting repl");
+
+    let mut repl = Repl::new(display_color(args.color))?;
+    println!(
+        "Mun {} REPL, type :help for more information",
+        env!("CARGO_PKG_VERSION")
+    );
+    repl.run(io::stdin().lock(), io::stdout().lock())?;
+    Ok(ExitStatus::Success)
+}
+
+/// The response of the REPL to an entry.
+#[derive(Debug, PartialEq, Eq)]
+enum Response {
+    /// The entry succeeded and produced the specified output, which may be
+    /// empty
+    Output(String),
+    /// The entry failed
+    Error(String),
+    /// The user requested to exit the REPL
+    Quit,
+}
+
+/// An interactive session that compiles every entry into a tiny assembly,
+/// which is hot reloaded into a persistent runtime.
+struct Repl {
+    driver: Driver,
+    display_colors: DisplayColor,
+
+    /// The path that identifies the assembly with the entered items. The
+    /// assemblies are handed to the runtime in memory, so this path never
+    /// exists.
+    library_path: PathBuf,
+    runtime: Option<Runtime>,
+
+    /// The items that were entered so far, in order of definition
+    items: Vec<String>,
+}
+
+impl Repl {
+    fn new(display_colors: DisplayColor) -> anyhow::Result<Self> {
+        let config = Config {
+            out_dir: Some(std::env::temp_dir().join("mun-repl")),
+            ..Config::default()
+        };
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from(SOURCE_PATH),
+            contents: String::new(),
+        };
+        let (driver, file_id) = Driver::with_file(config, input)?;
+        let library_path = driver.assembly_output_path_from_file(file_id);
+
+        Ok(Self {
+            driver,
+            display_colors,
+            library_path,
+            runtime: None,
+            items: Vec::new(),
+        })
+    }
+
+    /// Reads entries from `input` and writes the responses to `output`, until
+    /// the input ends or the user exits.
+    fn run(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        let mut lines = input.lines();
+        let mut entry = String::new();
+        loop {
+            let prompt = if entry.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            };
+            write!(output, "{prompt}")?;
+            output.flush()?;
+
+            let Some(line) = lines.next() else {
+                writeln!(output)?;
+                return Ok(());
+            };
+            entry.push_str(&line?);
+            entry.push('\n');
+
+            // Keep reading lines until all delimiters are closed
+            if !is_complete(&entry) {
+                continue;
+            }
+
+            match self.handle(entry.trim()) {
+                Response::Output(text) if text.is_empty() => {}
+                Response::Output(text) => writeln!(output, "{text}")?,
+                Response::Error(error) => writeln!(output, "{}", error.trim_end())?,
+                Response::Quit => return Ok(()),
+            }
+            entry.clear();
+        }
+    }
+
+    /// Handles a single entry, which is either a command, an item, or an
+    /// expression.
+    fn handle(&mut self, entry: &str) -> Response {
+        if entry.is_empty() {
+            return Response::Output(String::new());
+        }
+
+        if entry.starts_with(':') {
+            let (command, argument) = entry.split_once(char::is_whitespace).unwrap_or((entry, ""));
+            return match command {
+                ":quit" | ":q" => Response::Quit,
+                ":help" | ":h" => Response::Output(HELP.trim_end().to_owned()),
+                ":items" => Response::Output(self.items.join("\n\n")),
+                ":reset" => {
+                    self.items.clear();
+                    Response::Output(String::new())
+                }
+                ":type" | ":t" => self
+                    .infer_type(argument.trim())
+                    .map_or_else(Response::Error, Response::Output),
+                _ => Response::Error(format!(
+                    "unknown command `{command}`, type :help for a list of commands"
+                )),
+            };
+        }
+
+        let result = if is_item(entry) {
+            self.define(entry).map(|()| String::new())
+        } else {
+            self.evaluate(entry)
+        };
+        result.map_or_else(Response::Error, Response::Output)
+    }
+
+    /// Defines the item in `entry`, replacing an existing item with the same
+    /// name.
+    fn define(&mut self, entry: &str) -> Result<(), String> {
+        let mut items = self.items.clone();
+        let name = item_name(entry);
+        match items
+            .iter_mut()
+            .find(|item| name.is_some() && item_name(item) == name)
+        {
+            Some(item) => *item = entry.to_owned(),
+            None => items.push(entry.to_owned()),
+        }
+
+        self.check(items_source(&items))?;
+        self.items = items;
+        Ok(())
+    }
+
+    /// Evaluates the expression `expr` and formats its value. Returns an empty
+    /// string if the expression has no value.
+    fn evaluate(&mut self, expr: &str) -> Result<String, String> {
+        let ty = self.infer_type(expr)?;
+        let ret_type = if ty == "()" || ty == "never" {
+            String::new()
+        } else {
+            format!(" -> {ty}")
+        };
+        self.check(self.source_with(&format!("pub fn {EVAL_FN_NAME}(){ret_type} {{\n{expr}\n}}")))?;
+
+        let runtime = self.load()?;
+        let return_type = runtime
+            .get_function_definition(EVAL_FN_NAME)
+            .ok_or_else(|| format!("`{EVAL_FN_NAME}` was not loaded"))?
+            .prototype
+            .signature
+            .return_type
+            .clone();
+
+        let value = ReturnValue {
+            runtime,
+            function_name: EVAL_FN_NAME,
+        };
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            let text = format_value(&value, &return_type);
+            if return_type.equals::<()>() {
+                String::new()
+            } else {
+                text
+            }
+        }))
+        .map_err(|panic| describe_panic(panic.as_ref()))
+    }
+
+    /// Returns the type of the expression `expr`, as it is written in Mun
+    /// code.
+    fn infer_type(&mut self, expr: &str) -> Result<String, String> {
+        // The function does not specify a return type, so the compiler reports
+        // an error, but it still infers the type of the expression
+        let source = self.source_with(&format!("fn {EVAL_FN_NAME}() {{\n{expr}\n}}"));
+        self.driver
+            .set_file_text(SOURCE_PATH, source)
+            .map_err(|e| e.to_string())?;
+
+        match self.driver.infer_body_type(SOURCE_PATH, EVAL_FN_NAME) {
+            Some(ty) if !ty.contains("{unknown}") => Ok(ty),
+            _ => Err(self
+                .diagnostics()?
+                .unwrap_or_else(|| String::from("could not infer the type of the expression"))),
+        }
+    }
+
+    /// Returns the source of all items, followed by `code`.
+    fn source_with(&self, code: &str) -> String {
+        let mut source = items_source(&self.items);
+        source.push_str(code);
+        source
+    }
+
+    /// Replaces the compiled source with `source`. Returns the diagnostics as
+    /// an error if it contains errors.
+    fn check(&mut self, source: String) -> Result<(), String> {
+        self.driver
+            .set_file_text(SOURCE_PATH, source)
+            .map_err(|e| e.to_string())?;
+        match self.diagnostics()? {
+            Some(diagnostics) => Err(diagnostics),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the diagnostics of the compiled source, if it contains errors.
+    fn diagnostics(&self) -> Result<Option<String>, String> {
+        self.driver
+            .emit_diagnostics_to_string(self.display_colors)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Builds the assemblies that changed and hot reloads them, or loads all
+    /// assemblies into a new runtime the first time.
+    fn load(&mut self) -> Result<&Runtime, String> {
+        let assemblies = self
+            .driver
+            .build_changed_assemblies()
+            .map_err(|e| e.to_string())?;
+
+        if let Some(runtime) = &self.runtime {
+            if !assemblies.is_empty() {
+                for assembly in assemblies {
+                    runtime
+                        .stage_assembly(&assembly.path, assembly.bytes)
+                        .map_err(|e| e.to_string())?;
+                }
+
+                // Safety: we compiled the assemblies ourselves
+                if !unsafe { runtime.update() } {
+                    return Err(String::from("could not hot reload the entered code"));
+                }
+            }
+        } else {
+            let mut builder = Runtime::builder(self.library_path.clone());
+            for assembly in assemblies {
+                builder = builder.insert_assembly_bytes(assembly.path, assembly.bytes);
+            }
+
+            // Safety: we compiled the assemblies ourselves
+            let runtime = unsafe { builder.finish() }.map_err(|e| e.to_string())?;
+            self.runtime = Some(runtime);
+        }
+
+        Ok(self.runtime.as_ref().expect("the runtime was constructed"))
+    }
+}
+
+/// Returns the source of a file that contains `items`. Items without a
+/// visibility are made public, so expressions can return their types.
+fn items_source(items: &[String]) -> String {
+    let mut source = String::new();
+    for item in items {
+        if matches!(words(item).next(), Some("fn" | "struct" | "type")) {
+            source.push_str("pub ");
+        }
+        source.push_str(item);
+        source.push_str("\n\n");
+    }
+    source
+}
+
+/// Returns true if all delimiters in `entry` are closed, or if it closes more
+/// delimiters than it opens, which the compiler reports.
+fn is_complete(entry: &str) -> bool {
+    let mut depth = 0;
+    for line in entry.lines() {
+        let code = line.split_once("//").map_or(line, |(code, _)| code);
+        for c in code.chars() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+    depth <= 0
+}
+
+/// Returns the words in `code`, i.e. its identifiers and keywords.
+fn words(code: &str) -> impl Iterator<Item = &str> {
+    code.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+}
+
+/// Returns true if `entry` is an item, e.g. a function or struct, instead of
+/// an expression.
+fn is_item(entry: &str) -> bool {
+    entry.starts_with("#[")
+        || matches!(
+            words(entry).next(),
+            Some("pub" | "fn" | "struct" | "type" | "use" | "extern" | "impl")
+        )
+}
+
+/// Returns the name of the function, struct or type alias that `item`
+/// defines, if any.
+fn item_name(item: &str) -> Option<String> {
+    // Skip parenthesized words, e.g. the memory kind in `struct(value) Foo`
+    let mut depth = 0;
+    let code: String = item
+        .chars()
+        .map(|c| match c {
+            '(' | '[' => {
+                depth += 1;
+                ' '
+            }
+            ')' | ']' => {
+                depth -= 1;
+                ' '
+            }
+            _ if depth > 0 => ' ',
+            c => c,
+        })
+        .collect();
+
+    let mut words = words(&code);
+    words.find(|word| matches!(*word, "fn" | "struct" | "type"))?;
+    words.next().map(ToOwned::to_owned)
+}
+
+/// A Mun value whose type is only known at runtime.
+trait DynamicValue<'r> {
+    /// Retrieves the value as a `T`, which must accept the type of the value.
+    fn get<T: ReturnTypeReflection + Marshal<'r> + 'r>(&self) -> T;
+}
+
+/// The value that a Mun function without arguments returns.
+struct ReturnValue<'r> {
+    runtime: &'r Runtime,
+    function_name: &'r str,
+}
+
+impl<'r> DynamicValue<'r> for ReturnValue<'r> {
+    fn get<T: ReturnTypeReflection + Marshal<'r> + 'r>(&self) -> T {
+        self.runtime
+            .invoke(self.function_name, ())
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+/// The value of a field of a Mun struct.
+struct FieldValue<'a, 'r> {
+    parent: &'a StructRef<'r>,
+    name: &'a str,
+}
+
+impl<'r> DynamicValue<'r> for FieldValue<'_, 'r> {
+    fn get<T: ReturnTypeReflection + Marshal<'r> + 'r>(&self) -> T {
+        self.parent.get(self.name).unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+/// Invokes the macro `$m` with all primitive types that can be formatted.
+macro_rules! with_primitive_types {
+    ($m:ident) => {
+        $m!(bool, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
+    };
+}
+
+/// Formats a value of type `ty`, e.g. `Foo { a: 1, b: [2.0, 3.0] }`.
+fn format_value<'r>(value: &impl DynamicValue<'r>, ty: &Type) -> String {
+    macro_rules! format_primitive {
+        ($($primitive:ty),*) => {
+            $(if ty.equals::<$primitive>() {
+                return format!("{:?}", value.get::<$primitive>());
+            })*
+        };
+    }
+    with_primitive_types!(format_primitive);
+
+    if ty.equals::<()>() {
+        value.get::<()>();
+        String::from("()")
+    } else if ty.is_struct() {
+        format_struct(&value.get::<StructRef<'r>>())
+    } else if let Some(array) = ty.as_array() {
+        format_array(value, &array.element_type())
+    } else {
+        format!("<{}>", ty.name())
+    }
+}
+
+/// Formats the fields of a struct, e.g. `Foo { a: 1, b: 2 }`.
+fn format_struct(value: &StructRef<'_>) -> String {
+    let ty = value.type_info();
+    let fields: Vec<String> = ty
+        .as_struct()
+        .expect("a `StructRef` always refers to a struct")
+        .fields()
+        .iter()
+        .map(|field| {
+            let name = field.name();
+            let field_value = FieldValue {
+                parent: value,
+                name,
+            };
+            format!("{name}: {}", format_value(&field_value, &field.ty()))
+        })
+        .collect();
+
+    if fields.is_empty() {
+        ty.name().to_owned()
+    } else {
+        format!("{} {{ {} }}", ty.name(), fields.join(", "))
+    }
+}
+
+/// Formats the elements of an array, e.g. `[1, 2, 3]`.
+fn format_array<'r>(value: &impl DynamicValue<'r>, element_ty: &Type) -> String {
+    macro_rules! format_primitive_elements {
+        ($($primitive:ty),*) => {
+            $(if element_ty.equals::<$primitive>() {
+                let array = value.get::<ArrayRef<'r, $primitive>>();
+                let elements: Vec<String> = array.iter().map(|e| format!("{e:?}")).collect();
+                return format!("[{}]", elements.join(", "));
+            })*
+        };
+    }
+    with_primitive_types!(format_primitive_elements);
+
+    if element_ty.is_struct() {
+        let array = value.get::<ArrayRef<'r, StructRef<'r>>>();
+        let elements: Vec<String> = array.iter().map(|e| format_struct(&e)).collect();
+        format!("[{}]", elements.join(", "))
+    } else {
+        // Formatting nested arrays would require a Rust type for every level of
+        // nesting
+        format!("<[{}]>", element_ty.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mun_compiler::DisplayColor;
+
+    use super::{is_complete, is_item, item_name, Repl, Response};
+
+    fn output(text: &str) -> Response {
+        Response::Output(text.to_owned())
+    }
+
+    /// Runs a session that reads the lines of `input`, and returns everything
+    /// the REPL wrote, including prompts.
+    fn session(input: &str) -> String {
+        let mut repl = Repl::new(DisplayColor::Disable).unwrap();
+        let mut output = Vec::new();
+        repl.run(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_entries() {
+        assert!(is_complete("1 + 2"));
+        assert!(!is_complete("fn foo() {\n    bar(\n"));
+        assert!(is_complete("fn foo() { // {\n}"));
+
+        assert!(is_item("pub(package) fn foo() {}"));
+        assert!(is_item("#[test]\nfn foo() {}"));
+        assert!(is_item("struct Foo;"));
+        assert!(!is_item("foo(1)"));
+        assert!(!is_item("function(1)"));
+
+        assert_eq!(item_name("pub fn foo(a: i32) {}").as_deref(), Some("foo"));
+        assert_eq!(
+            item_name("#[test]\nstruct(value) Bar { a: f32 }").as_deref(),
+            Some("Bar")
+        );
+        assert_eq!(item_name("type Baz = i32;").as_deref(), Some("Baz"));
+        assert_eq!(item_name("use foo::bar;"), None);
+    }
+
+    #[test]
+    fn test_repl() {
+        let mut repl = Repl::new(DisplayColor::Disable).unwrap();
+
+        assert_eq!(repl.handle("1 + 2"), output("3"));
+        assert_eq!(repl.handle(":type 1.0 * 2.0"), output("f64"));
+        assert_eq!(repl.handle("struct Vector2 { x: f32, y: f32 }"), output(""));
+        assert_eq!(
+            repl.handle("fn length_squared(v: Vector2) -> f32 {\n    v.x * v.x + v.y * v.y\n}"),
+            output("")
+        );
+        assert_eq!(
+            repl.handle("length_squared(Vector2 { x: 3.0, y: 4.0 })"),
+            output("25.0")
+        );
+        assert_eq!(
+            repl.handle("[Vector2 { x: 1.0, y: 2.0 }]"),
+            output("[Vector2 { x: 1.0, y: 2.0 }]")
+        );
+
+        // Redefining an item replaces it, and the runtime is hot reloaded
+        assert_eq!(
+            repl.handle("fn length_squared(v: Vector2) -> f32 { v.x * v.x }"),
+            output("")
+        );
+        assert_eq!(
+            repl.handle("length_squared(Vector2 { x: 3.0, y: 4.0 })"),
+            output("9.0")
+        );
+        assert_eq!(
+            repl.handle(":items"),
+            output(
+                "struct Vector2 { x: f32, y: f32 }\n\n\
+                fn length_squared(v: Vector2) -> f32 { v.x * v.x }"
+            )
+        );
+
+        // Errors do not change the defined items
+        assert!(matches!(repl.handle("undefined"), Response::Error(_)));
+        assert!(matches!(
+            repl.handle("fn length_squared() -> f32 { undefined }"),
+            Response::Error(_)
+        ));
+        assert_eq!(
+            repl.handle("length_squared(Vector2 { x: 3.0, y: 4.0 })"),
+            output("9.0")
+        );
+
+        assert_eq!(repl.handle(":quit"), Response::Quit);
+    }
+
+    #[test]
+    fn test_session_evaluate() {
+        assert_eq!(
+            session("1 + 2\n{\n    let a = 40;\n    a + 2\n}\n"),
+            ">> 3\n>> .. .. .. 42\n>> \n"
+        );
+    }
+
+    #[test]
+    fn test_session_redefine_function() {
+        let input = "\
+fn answer() -> i32 { 41 }
+answer()
+fn answer() -> i32 {
+    42
+}
+answer()
+";
+        assert_eq!(session(input), ">> >> 41\n>> .. .. >> 42\n>> \n");
+    }
+
+    #[test]
+    fn test_session_recover_from_compile_error() {
+        let input = "\
+fn answer() -> i32 { 42 }
+fn answer() -> i32 { undefined }
+answer() +
+answer()
+";
+        let output = session(input);
+        assert!(output.starts_with(">> >> "), "{output}");
+        assert!(output.contains("error"), "{output}");
+        assert!(output.contains("undefined"), "{output}");
+        assert!(output.ends_with(">> 42\n>> \n"), "{output}");
+    }
+}
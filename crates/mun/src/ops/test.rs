@@ -0,0 +1,163 @@
+use std::{
+    any::Any,
+    collections::HashSet,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+};
+
+use anyhow::anyhow;
+use mun_compiler::{Config, DisplayColor, Driver};
+use mun_runtime::Runtime;
+
+use crate::{
+    ops::build::{display_color, locate_manifest, UseColor},
+    ExitStatus,
+};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Only run the tests whose name contains this string
+    filter: Option<String>,
+
+    /// Path to the manifest of the project
+    #[clap(long)]
+    manifest_path: Option<PathBuf>,
+
+    /// Use color in output
+    #[clap(long, value_enum)]
+    color: Option<UseColor>,
+}
+
+/// The outcome of running a single test.
+enum TestResult {
+    Passed,
+    Failed(String),
+}
+
+/// This method is invoked when the executable is run with the `test` argument
+/// indicating that a user requested us to build a project in the current
+/// directory or one of its parent directories and to run all of its
+/// `#[test]` functions.
+pub fn test(args: Args) -> Result<ExitStatus, anyhow::Error> {
+    log::trace!("starting test");
+
+    let display_colors = display_color(args.color);
+    let manifest_path = locate_manifest(args.manifest_path.as_deref())?;
+
+    log::info!("located manifest at: {}", manifest_path.display());
+
+    let (_package, mut driver) = Driver::with_package_path(&manifest_path, Config::default())?;
+    let Some(runtimes) = build_and_load(&mut driver, display_colors)? else {
+        return Ok(ExitStatus::Error);
+    };
+
+    // Every assembly is loaded together with its dependencies, so the same test can
+    // be encountered more than once.
+    let mut tests_run = HashSet::new();
+    let mut failures = Vec::new();
+    for runtime in &runtimes {
+        let tests: Vec<String> = runtime
+            .test_functions()
+            .into_iter()
+            .filter(|name| {
+                args.filter
+                    .as_ref()
+                    .map_or(true, |f| name.contains(f.as_str()))
+            })
+            .filter(|name| tests_run.insert(name.clone()))
+            .collect();
+        for name in tests {
+            match run_test(runtime, &name) {
+                TestResult::Passed => println!("test {name} ... ok"),
+                TestResult::Failed(reason) => {
+                    println!("test {name} ... FAILED");
+                    failures.push((name, reason));
+                }
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (name, reason) in &failures {
+            println!("    {name}: {reason}");
+        }
+    }
+
+    let num_failed = failures.len();
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        if num_failed == 0 { "ok" } else { "FAILED" },
+        tests_run.len() - num_failed,
+        num_failed
+    );
+
+    Ok((num_failed == 0).into())
+}
+
+/// Builds all assemblies of the package and loads each of them in a separate
+/// runtime. Returns `None` if the package contains errors.
+pub(crate) fn build_and_load(
+    driver: &mut Driver,
+    display_colors: DisplayColor,
+) -> Result<Option<Vec<Runtime>>, anyhow::Error> {
+    if driver.emit_diagnostics(&mut std::io::stderr(), display_colors)? {
+        return Ok(None);
+    }
+    driver.write_all_assemblies(false)?;
+
+    let runtimes = driver
+        .assembly_output_paths()
+        .into_iter()
+        // Safety: we compiled the libraries ourselves
+        .map(|library_path| unsafe { Runtime::builder(library_path).finish() })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(runtimes))
+}
+
+/// Invokes the test function `name`. A test fails if it returns `false` or if
+/// it panics. Mun functions are invoked through the `C-unwind` ABI, so a panic
+/// in Mun code unwinds into this function and is reported as a failure.
+fn run_test(runtime: &Runtime, name: &str) -> TestResult {
+    let returns_bool = runtime
+        .get_function_definition(name)
+        .is_some_and(|def| def.prototype.signature.return_type.equals::<bool>());
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        if returns_bool {
+            runtime
+                .invoke::<bool, ()>(name, ())
+                .map_err(|e| anyhow!("{}", e))
+        } else {
+            runtime
+                .invoke::<(), ()>(name, ())
+                .map(|()| true)
+                .map_err(|e| anyhow!("{}", e))
+        }
+    }));
+
+    match result {
+        Ok(Ok(true)) => TestResult::Passed,
+        Ok(Ok(false)) => TestResult::Failed(String::from("returned `false`")),
+        Ok(Err(e)) => TestResult::Failed(e.to_string()),
+        Err(panic) => TestResult::Failed(describe_panic(panic.as_ref())),
+    }
+}
+
+/// Describes the payload of a panic that occurred in Mun code, e.g.
+/// `panicked: attempt to add with overflow`.
+pub(crate) fn describe_panic(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(ToString::to_string)
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .or_else(|| {
+            panic
+                .downcast_ref::<mun_runtime::OverflowError>()
+                .map(ToString::to_string)
+        })
+        .map_or_else(
+            || String::from("panicked"),
+            |message| format!("panicked: {message}"),
+        )
+}
@@ -0,0 +1,131 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use mun::run_with_args;
+use mun_runtime::Runtime;
+
+/// The bindings that `mun bindgen` generates for `bindgen/mod.mun`. They are
+/// compiled as part of this test, which verifies that they are valid Rust.
+#[allow(dead_code)]
+mod bindings {
+    include!("bindgen/bindings.rs");
+}
+
+/// The source of the project for which bindings are generated
+const SOURCE: &str = include_str!("bindgen/mod.mun");
+
+/// The location of the generated bindings
+const BINDINGS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/bindgen/bindings.rs");
+
+/// Creates a project in `dir` with `source` as its only source file, and builds
+/// it. Returns the path of the built munlib.
+fn build_project(dir: &Path, source: &str) -> PathBuf {
+    let project_path = dir.join("bindgen");
+    let args: Vec<OsString> = vec!["mun".into(), "new".into(), project_path.as_path().into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    std::fs::write(project_path.join("src/mod.mun"), source).unwrap();
+
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "build".into(),
+        "--manifest-path".into(),
+        project_path.join("mun.toml").into(),
+    ];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    project_path.join("target/mod.munlib")
+}
+
+/// Runs `mun bindgen` for `library` and writes the bindings to `output`.
+fn bindgen(library: &Path, output: &Path) -> Result<mun::ExitStatus, anyhow::Error> {
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "bindgen".into(),
+        library.into(),
+        "--output".into(),
+        output.into(),
+    ];
+    run_with_args(args)
+}
+
+/// Verifies that the checked-in bindings match the output of `mun bindgen`.
+/// Run this test with `UPDATE_BINDINGS=1` to regenerate them.
+#[test]
+fn bindings_are_up_to_date() {
+    let dir = tempfile::tempdir().unwrap();
+    let library = build_project(dir.path(), SOURCE);
+
+    let output = dir.path().join("bindings.rs");
+    assert_eq!(
+        bindgen(&library, &output).unwrap(),
+        mun::ExitStatus::Success
+    );
+    let bindings = std::fs::read_to_string(output).unwrap();
+
+    if std::env::var_os("UPDATE_BINDINGS").is_some() {
+        std::fs::write(BINDINGS_PATH, bindings).unwrap();
+    } else {
+        let expected = std::fs::read_to_string(BINDINGS_PATH)
+            .unwrap()
+            .replace("\r\n", "\n");
+        assert_eq!(
+            bindings, expected,
+            "the bindings are out of date, run this test with `UPDATE_BINDINGS=1` to regenerate them"
+        );
+    }
+}
+
+/// Invokes the functions of `bindgen/mod.mun` through the generated bindings.
+#[test]
+fn invoke_through_bindings() {
+    let dir = tempfile::tempdir().unwrap();
+    let library = build_project(dir.path(), SOURCE);
+
+    // Safety: since we compiled the code ourselves, loading the library should be
+    // safe
+    let runtime = unsafe { Runtime::builder(library).finish() }.unwrap();
+
+    let mut vector = bindings::new_vector(&runtime, 3.0, 4.0).unwrap();
+    assert_eq!(vector.x(), 3.0);
+    assert_eq!(vector.y(), 4.0);
+    assert_eq!(
+        bindings::length_squared(&runtime, vector.clone()).unwrap(),
+        25.0
+    );
+
+    vector.set_y(0.0);
+    assert_eq!(bindings::length_squared(&runtime, vector).unwrap(), 9.0);
+
+    let buffer = bindings::new_buffer(&runtime, 16, -4).unwrap();
+    assert_eq!(buffer.len(), 16);
+    assert_eq!(buffer.offset(), -4);
+    assert_eq!(bindings::end(&runtime, buffer).unwrap(), 12);
+}
+
+/// Verifies that `mun bindgen` fails for types that cannot be marshalled,
+/// instead of leaving out the items that use them.
+#[test]
+fn unsupported_type() {
+    let dir = tempfile::tempdir().unwrap();
+    let library = build_project(
+        dir.path(),
+        r"
+pub extern struct Texture;
+
+pub fn identity(texture: Texture) -> Texture {
+    texture
+}
+",
+    );
+
+    let output = dir.path().join("bindings.rs");
+    let err = bindgen(&library, &output).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "cannot generate bindings for the function `identity`: the type `Texture` is not supported"
+    );
+    assert!(!output.exists());
+}
@@ -0,0 +1,175 @@
+// Generated by `mun bindgen` from `mod.munlib`. Do not edit this file by hand.
+
+/// A reference to an instance of the Mun struct `Buffer`.
+#[derive(Clone)]
+pub struct Buffer<'r>(pub ::mun_runtime::StructRef<'r>);
+
+impl<'r> Buffer<'r> {
+    /// The name of the struct in Mun
+    pub const NAME: &'static str = "Buffer";
+
+    /// Returns the value of the field `len`.
+    pub fn len(&self) -> u64 {
+        self.0.get("len").expect("invalid binding for `Buffer`")
+    }
+
+    /// Sets the value of the field `len`.
+    pub fn set_len(&mut self, value: u64) {
+        self.0.set("len", value).expect("invalid binding for `Buffer`");
+    }
+
+    /// Returns the value of the field `offset`.
+    pub fn offset(&self) -> i64 {
+        self.0.get("offset").expect("invalid binding for `Buffer`")
+    }
+
+    /// Sets the value of the field `offset`.
+    pub fn set_offset(&mut self, value: i64) {
+        self.0.set("offset", value).expect("invalid binding for `Buffer`");
+    }
+}
+
+impl<'r> ::mun_runtime::ArgumentReflection for Buffer<'r> {
+    fn type_info(&self, runtime: &::mun_runtime::Runtime) -> ::mun_runtime::Type {
+        ::mun_runtime::ArgumentReflection::type_info(&self.0, runtime)
+    }
+}
+
+impl<'r> ::mun_runtime::ReturnTypeReflection for Buffer<'r> {
+    fn accepts_type(ty: &::mun_runtime::Type) -> bool {
+        ty.is_struct() && ty.name() == Self::NAME
+    }
+
+    fn type_hint() -> &'static str {
+        Self::NAME
+    }
+}
+
+impl<'r> ::mun_runtime::Marshal<'r> for Buffer<'r> {
+    type MunType = <::mun_runtime::StructRef<'r> as ::mun_runtime::Marshal<'r>>::MunType;
+
+    fn marshal_from<'t>(value: Self::MunType, runtime: &'t ::mun_runtime::Runtime) -> Self
+    where
+        Self: 'r,
+        't: 'r,
+    {
+        Self(::mun_runtime::Marshal::marshal_from(value, runtime))
+    }
+
+    fn marshal_into(self) -> Self::MunType {
+        ::mun_runtime::Marshal::marshal_into(self.0)
+    }
+
+    fn marshal_from_ptr<'t>(
+        ptr: ::std::ptr::NonNull<Self::MunType>,
+        runtime: &'t ::mun_runtime::Runtime,
+        type_info: &::mun_runtime::Type,
+    ) -> Self
+    where
+        Self: 'r,
+        't: 'r,
+    {
+        Self(::mun_runtime::Marshal::marshal_from_ptr(ptr, runtime, type_info))
+    }
+
+    fn marshal_to_ptr(value: Self, ptr: ::std::ptr::NonNull<Self::MunType>, type_info: &::mun_runtime::Type) {
+        ::mun_runtime::Marshal::marshal_to_ptr(value.0, ptr, type_info);
+    }
+}
+
+/// A reference to an instance of the Mun struct `Vector2`.
+#[derive(Clone)]
+pub struct Vector2<'r>(pub ::mun_runtime::StructRef<'r>);
+
+impl<'r> Vector2<'r> {
+    /// The name of the struct in Mun
+    pub const NAME: &'static str = "Vector2";
+
+    /// Returns the value of the field `x`.
+    pub fn x(&self) -> f32 {
+        self.0.get("x").expect("invalid binding for `Vector2`")
+    }
+
+    /// Sets the value of the field `x`.
+    pub fn set_x(&mut self, value: f32) {
+        self.0.set("x", value).expect("invalid binding for `Vector2`");
+    }
+
+    /// Returns the value of the field `y`.
+    pub fn y(&self) -> f32 {
+        self.0.get("y").expect("invalid binding for `Vector2`")
+    }
+
+    /// Sets the value of the field `y`.
+    pub fn set_y(&mut self, value: f32) {
+        self.0.set("y", value).expect("invalid binding for `Vector2`");
+    }
+}
+
+impl<'r> ::mun_runtime::ArgumentReflection for Vector2<'r> {
+    fn type_info(&self, runtime: &::mun_runtime::Runtime) -> ::mun_runtime::Type {
+        ::mun_runtime::ArgumentReflection::type_info(&self.0, runtime)
+    }
+}
+
+impl<'r> ::mun_runtime::ReturnTypeReflection for Vector2<'r> {
+    fn accepts_type(ty: &::mun_runtime::Type) -> bool {
+        ty.is_struct() && ty.name() == Self::NAME
+    }
+
+    fn type_hint() -> &'static str {
+        Self::NAME
+    }
+}
+
+impl<'r> ::mun_runtime::Marshal<'r> for Vector2<'r> {
+    type MunType = <::mun_runtime::StructRef<'r> as ::mun_runtime::Marshal<'r>>::MunType;
+
+    fn marshal_from<'t>(value: Self::MunType, runtime: &'t ::mun_runtime::Runtime) -> Self
+    where
+        Self: 'r,
+        't: 'r,
+    {
+        Self(::mun_runtime::Marshal::marshal_from(value, runtime))
+    }
+
+    fn marshal_into(self) -> Self::MunType {
+        ::mun_runtime::Marshal::marshal_into(self.0)
+    }
+
+    fn marshal_from_ptr<'t>(
+        ptr: ::std::ptr::NonNull<Self::MunType>,
+        runtime: &'t ::mun_runtime::Runtime,
+        type_info: &::mun_runtime::Type,
+    ) -> Self
+    where
+        Self: 'r,
+        't: 'r,
+    {
+        Self(::mun_runtime::Marshal::marshal_from_ptr(ptr, runtime, type_info))
+    }
+
+    fn marshal_to_ptr(value: Self, ptr: ::std::ptr::NonNull<Self::MunType>, type_info: &::mun_runtime::Type) {
+        ::mun_runtime::Marshal::marshal_to_ptr(value.0, ptr, type_info);
+    }
+}
+
+/// Invokes the Mun function `end`.
+pub fn end<'r>(runtime: &'r ::mun_runtime::Runtime, arg0: Buffer<'r>) -> ::std::result::Result<i64, ::mun_runtime::InvokeErr<'static, (Buffer<'r>,)>> {
+    runtime.invoke("end", (arg0,))
+}
+
+/// Invokes the Mun function `length_squared`.
+pub fn length_squared<'r>(runtime: &'r ::mun_runtime::Runtime, arg0: Vector2<'r>) -> ::std::result::Result<f32, ::mun_runtime::InvokeErr<'static, (Vector2<'r>,)>> {
+    runtime.invoke("length_squared", (arg0,))
+}
+
+/// Invokes the Mun function `new_buffer`.
+pub fn new_buffer(runtime: &::mun_runtime::Runtime, arg0: u64, arg1: i64) -> ::std::result::Result<Buffer<'_>, ::mun_runtime::InvokeErr<'static, (u64, i64)>> {
+    runtime.invoke("new_buffer", (arg0, arg1))
+}
+
+/// Invokes the Mun function `new_vector`.
+pub fn new_vector(runtime: &::mun_runtime::Runtime, arg0: f32, arg1: f32) -> ::std::result::Result<Vector2<'_>, ::mun_runtime::InvokeErr<'static, (f32, f32)>> {
+    runtime.invoke("new_vector", (arg0, arg1))
+}
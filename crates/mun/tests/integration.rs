@@ -53,6 +53,288 @@ fn mun_emit_ir() {
     assert!(ir_path.is_file());
 }
 
+/// Verifies that a newly created project can be used to emit the assembly and
+/// ABI of its munlib.
+#[test]
+fn mun_emit() {
+    let project_dir = tempfile::Builder::new()
+        .prefix(PROJECT_DIR)
+        .tempdir()
+        .unwrap();
+
+    let project_path = project_dir.path().join(PROJECT_NAME);
+
+    let args: Vec<OsString> = vec!["mun".into(), "new".into(), project_path.as_path().into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    build(&project_path, &["--emit=link,asm,abi"]);
+
+    assert!(project_path.join("target/mod.munlib").is_file());
+    assert!(project_path.join("target/mod.s").is_file());
+    assert!(!project_path.join("target/mod.ll").exists());
+
+    let abi = std::fs::read_to_string(project_path.join("target/mod.abi")).unwrap();
+    assert!(abi.contains("fn main() -> core::f64"), "{abi}");
+}
+
+/// Verifies that Rust bindings can be generated for a newly created project.
+#[test]
+fn mun_bindgen() {
+    let project_dir = tempfile::Builder::new()
+        .prefix(PROJECT_DIR)
+        .tempdir()
+        .unwrap();
+
+    let project_path = project_dir.path().join(PROJECT_NAME);
+
+    let args: Vec<OsString> = vec!["mun".into(), "new".into(), project_path.as_path().into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+    build(&project_path, &[]);
+
+    let bindings_path = project_path.join("target/bindings.rs");
+    let args: Vec<OsString> = vec![
+        "mun".into(),
+        "bindgen".into(),
+        project_path.join("target/mod.munlib").into(),
+        "--output".into(),
+        bindings_path.as_path().into(),
+    ];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let bindings = std::fs::read_to_string(bindings_path).unwrap();
+    assert!(bindings.contains(
+        "pub fn main(runtime: &::mun_runtime::Runtime) -> ::std::result::Result<f64, ::mun_runtime::InvokeErr<'static, ()>> {"
+    ));
+    assert!(bindings.contains("runtime.invoke(\"main\", ())"));
+}
+
+/// Verifies that `mun fmt` formats the source files of a project and that
+/// `mun fmt --check` reports unformatted files.
+#[test]
+fn mun_fmt() {
+    let project_dir = tempfile::Builder::new()
+        .prefix(PROJECT_DIR)
+        .tempdir()
+        .unwrap();
+
+    let project_path = project_dir.path().join(PROJECT_NAME);
+
+    let args: Vec<OsString> = vec!["mun".into(), "new".into(), project_path.as_path().into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let source_path = project_path.join("src/mod.mun");
+    std::fs::write(&source_path, "pub fn main()->f64{ 3.14 }").unwrap();
+
+    let fmt_args = |extra_args: &[&str]| {
+        let mut args: Vec<OsString> = vec![
+            "mun".into(),
+            "fmt".into(),
+            "--manifest-path".into(),
+            project_path.join("mun.toml").into(),
+        ];
+        args.extend(extra_args.iter().map(OsString::from));
+        args
+    };
+    assert_eq!(
+        run_with_args(fmt_args(&["--check"])).unwrap(),
+        mun::ExitStatus::Error
+    );
+    assert_eq!(
+        run_with_args(fmt_args(&[])).unwrap(),
+        mun::ExitStatus::Success
+    );
+    assert_eq!(
+        std::fs::read_to_string(&source_path).unwrap(),
+        "pub fn main() -> f64 {\n    3.14\n}\n"
+    );
+    assert_eq!(
+        run_with_args(fmt_args(&["--check"])).unwrap(),
+        mun::ExitStatus::Success
+    );
+}
+
+/// Verifies that `mun check` reports errors without generating any assemblies.
+#[test]
+fn mun_check() {
+    let project_dir = tempfile::Builder::new()
+        .prefix(PROJECT_DIR)
+        .tempdir()
+        .unwrap();
+
+    let project_path = project_dir.path().join(PROJECT_NAME);
+
+    let args: Vec<OsString> = vec!["mun".into(), "new".into(), project_path.as_path().into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let check_args: Vec<OsString> = vec![
+        "mun".into(),
+        "check".into(),
+        "--manifest-path".into(),
+        project_path.join("mun.toml").into(),
+    ];
+    assert_eq!(
+        run_with_args(check_args.clone()).unwrap(),
+        mun::ExitStatus::Success
+    );
+    assert!(!project_path.join("target/mod.munlib").exists());
+
+    std::fs::write(
+        project_path.join("src/mod.mun"),
+        "pub fn main() -> f64 { foo }",
+    )
+    .unwrap();
+    assert_eq!(run_with_args(check_args).unwrap(), mun::ExitStatus::Error);
+}
+
+/// Verifies that `mun test` runs all `#[test]` functions of a project and
+/// reports whether they passed.
+#[test]
+fn mun_test() {
+    let project_dir = tempfile::Builder::new()
+        .prefix(PROJECT_DIR)
+        .tempdir()
+        .unwrap();
+
+    let project_path = project_dir.path().join(PROJECT_NAME);
+
+    let args: Vec<OsString> = vec!["mun".into(), "new".into(), project_path.as_path().into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    let test_args = |extra_args: &[&str]| {
+        let mut args: Vec<OsString> = vec![
+            "mun".into(),
+            "test".into(),
+            "--manifest-path".into(),
+            project_path.join("mun.toml").into(),
+        ];
+        args.extend(extra_args.iter().map(OsString::from));
+        args
+    };
+
+    std::fs::write(
+        project_path.join("src/mod.mun"),
+        r#"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+
+    #[test]
+    fn test_add() -> bool { add(1, 2) == 3 }
+
+    #[test]
+    fn test_add_wrong() -> bool { add(1, 2) == 4 }
+
+    #[test]
+    fn test_unit() {}
+
+    #[test]
+    fn test_panic() { assert(false) }
+    "#,
+    )
+    .unwrap();
+    assert_eq!(
+        run_with_args(test_args(&[])).unwrap(),
+        mun::ExitStatus::Error
+    );
+    assert_eq!(
+        run_with_args(test_args(&["test_add"])).unwrap(),
+        mun::ExitStatus::Error
+    );
+    assert_eq!(
+        run_with_args(test_args(&["test_unit"])).unwrap(),
+        mun::ExitStatus::Success
+    );
+    assert_eq!(
+        run_with_args(test_args(&["test_panic"])).unwrap(),
+        mun::ExitStatus::Error
+    );
+}
+
+/// Verifies that `mun bench` measures all `#[bench]` functions of a project
+/// and stores the results as a baseline.
+#[test]
+fn mun_bench() {
+    let project_dir = tempfile::Builder::new()
+        .prefix(PROJECT_DIR)
+        .tempdir()
+        .unwrap();
+
+    let project_path = project_dir.path().join(PROJECT_NAME);
+
+    let args: Vec<OsString> = vec!["mun".into(), "new".into(), project_path.as_path().into()];
+    assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+
+    std::fs::write(
+        project_path.join("src/mod.mun"),
+        r#"
+    pub fn fibonacci(n: i64) -> i64 {
+        if n <= 1 { n } else { fibonacci(n - 1) + fibonacci(n - 2) }
+    }
+
+    #[bench]
+    fn bench_fibonacci() { fibonacci(10); }
+    "#,
+    )
+    .unwrap();
+
+    let bench_args: Vec<OsString> = vec![
+        "mun".into(),
+        "bench".into(),
+        "--manifest-path".into(),
+        project_path.join("mun.toml").into(),
+    ];
+    assert_eq!(run_with_args(bench_args).unwrap(), mun::ExitStatus::Success);
+
+    let baseline = std::fs::read_to_string(project_path.join("target/bench/base.txt")).unwrap();
+    assert!(baseline.starts_with("bench_fibonacci "));
+}
+
+/// Verifies that a project can use the items of a package it depends on, and
+/// that the assembly of the dependency is loaded together with the project.
+#[test]
+fn mun_build_with_dependency() {
+    let project_dir = tempfile::Builder::new()
+        .prefix(PROJECT_DIR)
+        .tempdir()
+        .unwrap();
+
+    let project_path = project_dir.path().join(PROJECT_NAME);
+    let dependency_path = project_dir.path().join("physics");
+    for path in [&project_path, &dependency_path] {
+        let args: Vec<OsString> = vec!["mun".into(), "new".into(), path.as_path().into()];
+        assert_eq!(run_with_args(args).unwrap(), mun::ExitStatus::Success);
+    }
+
+    std::fs::write(
+        dependency_path.join("src/mod.mun"),
+        r#"
+    pub fn gravity() -> f64 { 9.81 }
+    "#,
+    )
+    .unwrap();
+
+    let manifest_path = project_path.join("mun.toml");
+    let mut manifest = std::fs::read_to_string(&manifest_path).unwrap();
+    manifest.push_str("\n[dependencies]\nphysics = { path = \"../physics\" }\n");
+    std::fs::write(&manifest_path, manifest).unwrap();
+
+    std::fs::write(
+        project_path.join("src/mod.mun"),
+        r#"
+    pub fn main() -> f64 { physics::gravity() * 2.0 }
+    "#,
+    )
+    .unwrap();
+
+    build(&project_path, &[]);
+    assert!(project_path.join("target/physics.munlib").is_file());
+
+    // Safety: since we compiled the code ourselves, loading the library should be
+    // safe
+    let builder = Runtime::builder(project_path.join("target/mod.munlib"));
+    let runtime = unsafe { builder.finish() }.unwrap();
+    let result: f64 = runtime.invoke("main", ()).unwrap();
+    assert_eq!(result, 19.62);
+}
+
 fn build(project: &Path, args: &[&str]) {
     let args: Vec<OsString> = vec![
         OsString::from("mun"),
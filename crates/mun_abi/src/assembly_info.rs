@@ -1,8 +1,85 @@
-use std::{ffi::CStr, os::raw::c_char, slice, str};
+use std::{ffi::CStr, fmt, os::raw::c_char, slice, str};
 
-use crate::{DispatchTable, ModuleInfo, TypeLut};
+use crate::{
+    DispatchTable, FieldDefault, FunctionDefinition, FunctionKind, FunctionPrototype, ModuleInfo,
+    TypeId, TypeLut,
+};
+
+/// A set of optional ABI features that an assembly makes use of.
+///
+/// Each minor version of the ABI may introduce new capabilities, together with
+/// the optional sections of [`AssemblyInfo`] that they enable. An optional
+/// section may only be accessed if the corresponding capability is set, because
+/// an assembly that was generated for an older minor version does not contain
+/// it.
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+    /// The assembly contains the default values of struct fields, see
+    /// [`AssemblyInfo::field_defaults`]. Introduced in ABI version 1.3.
+    pub const FIELD_DEFAULTS: Capabilities = Capabilities(1 << 0);
+
+    /// The assembly contains functions that other assemblies link against, but
+    /// that may not be accessed by the host, see
+    /// [`AssemblyInfo::private_functions`]. Introduced in ABI version 1.4.
+    pub const PRIVATE_FUNCTIONS: Capabilities = Capabilities(1 << 1);
+
+    /// The capabilities that are supported by the current
+    /// [`ABI_VERSION`](crate::ABI_VERSION)
+    pub const SUPPORTED: Capabilities =
+        Capabilities::FIELD_DEFAULTS.union(Capabilities::PRIVATE_FUNCTIONS);
+
+    /// Returns an empty set of capabilities.
+    pub const fn empty() -> Self {
+        Capabilities(0)
+    }
+
+    /// Constructs a set of capabilities from its raw bits, retaining bits that
+    /// do not correspond to a known capability.
+    pub const fn from_bits_retain(bits: u32) -> Self {
+        Capabilities(bits)
+    }
+
+    /// Returns the raw bits of the capabilities.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns true if no capabilities are set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if all capabilities in `other` are also set in `self`.
+    pub const fn contains(self, other: Capabilities) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the capabilities that are set in either `self` or `other`.
+    pub const fn union(self, other: Capabilities) -> Capabilities {
+        Capabilities(self.0 | other.0)
+    }
+
+    /// Returns the capabilities that are set in `self`, but not in `other`.
+    pub const fn difference(self, other: Capabilities) -> Capabilities {
+        Capabilities(self.0 & !other.0)
+    }
+}
+
+impl fmt::Debug for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Capabilities({:#x})", self.0)
+    }
+}
 
 /// Represents an assembly declaration.
+///
+/// The layout of this struct only grows within a major version of the ABI.
+/// Optional sections that are introduced by later minor versions are appended
+/// after [`AssemblyInfo::capabilities`] and may only be accessed if the
+/// corresponding [`Capabilities`] are set.
 #[repr(C)]
 pub struct AssemblyInfo<'a> {
     /// Symbols of the top-level module
@@ -15,6 +92,21 @@ pub struct AssemblyInfo<'a> {
     pub(crate) dependencies: *const *const c_char,
     /// Number of dependencies
     pub num_dependencies: u32,
+    /// Optional ABI features that the assembly makes use of
+    pub capabilities: Capabilities,
+    /// Default values of struct fields. Only present if
+    /// [`Capabilities::FIELD_DEFAULTS`] is set.
+    pub(crate) field_defaults: *const FieldDefault,
+    /// Number of default values of struct fields. Only present if
+    /// [`Capabilities::FIELD_DEFAULTS`] is set.
+    pub(crate) num_field_defaults: u32,
+    /// Functions that other assemblies link against, but that are not part of
+    /// [`AssemblyInfo::symbols`]. Only present if
+    /// [`Capabilities::PRIVATE_FUNCTIONS`] is set.
+    pub(crate) private_functions: *const FunctionDefinition<'a>,
+    /// Number of private functions. Only present if
+    /// [`Capabilities::PRIVATE_FUNCTIONS`] is set.
+    pub(crate) num_private_functions: u32,
 }
 
 impl<'a> AssemblyInfo<'a> {
@@ -30,6 +122,173 @@ impl<'a> AssemblyInfo<'a> {
             .iter()
             .map(|d| unsafe { str::from_utf8_unchecked(CStr::from_ptr(*d).to_bytes()) })
     }
+
+    /// Returns the optional ABI features that the assembly makes use of.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Returns the default values of struct fields, or an empty slice if the
+    /// assembly does not contain any.
+    pub fn field_defaults(&self) -> &[FieldDefault] {
+        if !self.capabilities.contains(Capabilities::FIELD_DEFAULTS) || self.num_field_defaults == 0
+        {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.field_defaults, self.num_field_defaults as usize) }
+        }
+    }
+
+    /// Returns the functions that other assemblies link against, but that may
+    /// not be accessed by the host, e.g. a `pub(package)` function or a private
+    /// function of a parent module. Returns an empty slice if the assembly
+    /// does not contain any.
+    pub fn private_functions(&self) -> &[FunctionDefinition<'a>] {
+        if !self.capabilities.contains(Capabilities::PRIVATE_FUNCTIONS)
+            || self.num_private_functions == 0
+        {
+            &[]
+        } else {
+            unsafe {
+                slice::from_raw_parts(self.private_functions, self.num_private_functions as usize)
+            }
+        }
+    }
+
+    /// Returns the capabilities of the assembly that are not supported by the
+    /// current [`ABI_VERSION`](crate::ABI_VERSION). An assembly that makes use
+    /// of unsupported capabilities cannot be loaded.
+    pub fn unsupported_capabilities(&self) -> Capabilities {
+        self.capabilities.difference(Capabilities::SUPPORTED)
+    }
+}
+
+impl<'a> AssemblyInfo<'a> {
+    /// Returns the name of the type with the specified `type_id`, as recorded
+    /// in the type lookup table or the type definitions of the assembly. Types
+    /// that are unknown to the assembly are represented by their id.
+    fn type_name(&self, type_id: &TypeId<'_>) -> String {
+        match type_id {
+            TypeId::Concrete(guid) => self
+                .type_lut
+                .iter()
+                .find(|(id, _, _)| *id == type_id)
+                .map(|(_, _, name)| name.to_owned())
+                .or_else(|| {
+                    self.symbols
+                        .types()
+                        .iter()
+                        .find(|ty| ty.as_concrete() == guid)
+                        .map(|ty| ty.name().to_owned())
+                })
+                .unwrap_or_else(|| guid.to_string()),
+            TypeId::Pointer(pointer) => format!(
+                "*{} {}",
+                if pointer.mutable { "mut" } else { "const" },
+                self.type_name(pointer.pointee)
+            ),
+            TypeId::Array(array) => format!("[{}]", self.type_name(array.element)),
+        }
+    }
+
+    /// Writes the signature of the function with the specified `prototype`.
+    fn fmt_prototype(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        prototype: &FunctionPrototype<'_>,
+    ) -> fmt::Result {
+        let signature = &prototype.signature;
+        let arg_types = signature
+            .arg_types()
+            .iter()
+            .map(|ty| self.type_name(ty))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "fn {}({arg_types})", prototype.name())?;
+        if let Some(return_type) = signature.return_type() {
+            write!(f, " -> {}", self.type_name(&return_type))?;
+        }
+        Ok(())
+    }
+}
+
+/// A human-readable description of the symbols, dispatch table, and type
+/// lookup table of the assembly, as the runtime sees them when it links the
+/// assembly. Types are referred to by name where possible.
+impl<'a> fmt::Display for AssemblyInfo<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "module `{}`", self.symbols.path())?;
+        writeln!(f, "capabilities: {:#x}", self.capabilities.bits())?;
+
+        writeln!(f, "dependencies:")?;
+        for dependency in self.dependencies() {
+            writeln!(f, "    {dependency}")?;
+        }
+
+        writeln!(f, "functions:")?;
+        for function in self.symbols.functions() {
+            f.write_str("    ")?;
+            match function.kind {
+                FunctionKind::Normal => (),
+                FunctionKind::Test => f.write_str("#[test] ")?,
+                FunctionKind::Bench => f.write_str("#[bench] ")?,
+                FunctionKind::Resumable => f.write_str("resumable ")?,
+            }
+            self.fmt_prototype(f, &function.prototype)?;
+            writeln!(f)?;
+        }
+        for function in self.private_functions() {
+            f.write_str("    (private) ")?;
+            self.fmt_prototype(f, &function.prototype)?;
+            writeln!(f)?;
+        }
+
+        writeln!(f, "types:")?;
+        for ty in self.symbols.types() {
+            write!(
+                f,
+                "    {} {}: {} bytes, aligned to {} bytes",
+                ty.as_concrete(),
+                ty.name(),
+                ty.size_in_bytes(),
+                ty.alignment()
+            )?;
+            if let Some(s) = ty.as_struct() {
+                write!(f, ", {:?} struct", s.memory_kind)?;
+                if let Some(attribute) = s.repr.attribute() {
+                    write!(f, ", {attribute}")?;
+                }
+                if s.passed_by_value {
+                    write!(f, ", passed by value")?;
+                }
+                writeln!(f)?;
+                for ((name, ty), offset) in
+                    s.field_names().zip(s.field_types()).zip(s.field_offsets())
+                {
+                    writeln!(f, "        {offset:>4}: {name}: {}", self.type_name(ty))?;
+                }
+            } else {
+                writeln!(f)?;
+            }
+        }
+
+        writeln!(f, "dispatch table:")?;
+        for (index, prototype) in self.dispatch_table.prototypes().iter().enumerate() {
+            write!(f, "    {index:>4}: ")?;
+            self.fmt_prototype(f, prototype)?;
+            writeln!(f)?;
+        }
+
+        writeln!(f, "type lookup table:")?;
+        for (index, (type_id, _, name)) in self.type_lut.iter().enumerate() {
+            match type_id {
+                TypeId::Concrete(guid) => writeln!(f, "    {index:>4}: {guid} {name}")?,
+                _ => writeln!(f, "    {index:>4}: {name}")?,
+            }
+        }
+
+        Ok(())
+    }
 }
 
 unsafe impl<'a> Send for AssemblyInfo<'a> {}
@@ -55,11 +314,16 @@ impl<'a> serde::Serialize for AssemblyInfo<'a> {
 
 #[cfg(test)]
 mod tests {
-    use std::ffi::CString;
+    use std::{ffi::CString, ptr};
 
-    use crate::test_utils::{
-        fake_assembly_info, fake_dispatch_table, fake_module_info, fake_type_lut, FAKE_DEPENDENCY,
-        FAKE_MODULE_PATH,
+    use crate::{
+        test_utils::{
+            fake_assembly_info, fake_dispatch_table, fake_fn_prototype, fake_module_info,
+            fake_struct_definition, fake_type_definition, fake_type_lut, FAKE_DEPENDENCY,
+            FAKE_FIELD_NAME, FAKE_FN_NAME, FAKE_MODULE_PATH, FAKE_STRUCT_NAME,
+        },
+        Capabilities, FieldDefault, FunctionDefinition, FunctionKind, Guid, HasStaticTypeId,
+        StructMemoryKind, StructRepr, TypeDefinitionData,
     };
 
     #[test]
@@ -79,4 +343,173 @@ mod tests {
             assert_eq!(lhs, *rhs);
         }
     }
+
+    #[test]
+    fn test_assembly_info_capabilities() {
+        let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
+        let module = fake_module_info(&module_path, &[], &[]);
+
+        let dispatch_table = fake_dispatch_table(&[], &mut []);
+        let type_lut = fake_type_lut(&[], &mut [], &[]);
+
+        let mut assembly = fake_assembly_info(module, dispatch_table, type_lut, &[]);
+        assert!(assembly.capabilities().is_empty());
+        assert!(assembly.unsupported_capabilities().is_empty());
+
+        let unknown = Capabilities::from_bits_retain(1 << 31);
+        assembly.capabilities = unknown;
+        assert_eq!(assembly.capabilities(), unknown);
+        assert_eq!(assembly.unsupported_capabilities(), unknown);
+    }
+
+    #[test]
+    fn test_assembly_info_field_defaults() {
+        let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
+        let module = fake_module_info(&module_path, &[], &[]);
+
+        let dispatch_table = fake_dispatch_table(&[], &mut []);
+        let type_lut = fake_type_lut(&[], &mut [], &[]);
+
+        let value = 100i32.to_ne_bytes();
+        let field_defaults = [FieldDefault {
+            struct_guid: Guid::from_str(FAKE_STRUCT_NAME),
+            field_index: 1,
+            value_size: value.len() as u16,
+            value: value.as_ptr(),
+        }];
+
+        let mut assembly = fake_assembly_info(module, dispatch_table, type_lut, &[]);
+        assembly.field_defaults = field_defaults.as_ptr();
+        assembly.num_field_defaults = field_defaults.len() as u32;
+
+        // The section may only be accessed if the capability is set
+        assert!(assembly.field_defaults().is_empty());
+
+        assembly.capabilities = Capabilities::FIELD_DEFAULTS;
+        assert!(assembly.unsupported_capabilities().is_empty());
+        let [field_default] = assembly.field_defaults() else {
+            panic!("expected a single field default");
+        };
+        assert_eq!(field_default.struct_guid, Guid::from_str(FAKE_STRUCT_NAME));
+        assert_eq!(field_default.field_index, 1);
+        assert_eq!(field_default.value(), value);
+    }
+
+    #[test]
+    fn test_assembly_info_private_functions() {
+        let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
+        let functions = [FunctionDefinition {
+            prototype: fake_fn_prototype(&fn_name, &[], None),
+            fn_ptr: ptr::null(),
+            kind: FunctionKind::Normal,
+        }];
+
+        let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
+        let module = fake_module_info(&module_path, &[], &[]);
+
+        let dispatch_table = fake_dispatch_table(&[], &mut []);
+        let type_lut = fake_type_lut(&[], &mut [], &[]);
+
+        let mut assembly = fake_assembly_info(module, dispatch_table, type_lut, &[]);
+        assembly.private_functions = functions.as_ptr();
+        assembly.num_private_functions = functions.len() as u32;
+
+        // The section may only be accessed if the capability is set
+        assert!(assembly.private_functions().is_empty());
+
+        assembly.capabilities = Capabilities::PRIVATE_FUNCTIONS;
+        assert!(assembly.unsupported_capabilities().is_empty());
+        let [function] = assembly.private_functions() else {
+            panic!("expected a single private function");
+        };
+        assert_eq!(function.prototype.name(), FAKE_FN_NAME);
+
+        // Private functions are not part of the symbols that the host can access
+        assert!(assembly.symbols.functions().is_empty());
+    }
+
+    #[test]
+    fn test_capabilities_set_operations() {
+        let a = Capabilities::from_bits_retain(0b01);
+        let ab = Capabilities::from_bits_retain(0b11);
+
+        assert!(ab.contains(a));
+        assert!(!a.contains(ab));
+        assert!(a.contains(Capabilities::empty()));
+        assert_eq!(ab.difference(a).bits(), 0b10);
+        assert_eq!(a.union(ab), ab);
+        assert!(a.difference(ab).is_empty());
+    }
+
+    #[test]
+    fn test_assembly_info_display() {
+        let i32_type_id = i32::type_id();
+        let i32_name = CString::new("core::i32").expect("Invalid fake type name.");
+
+        let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
+        let fn_prototype = fake_fn_prototype(
+            &fn_name,
+            std::slice::from_ref(i32_type_id),
+            Some(i32_type_id.clone()),
+        );
+        let functions = [FunctionDefinition {
+            prototype: fn_prototype.clone(),
+            fn_ptr: ptr::null(),
+            kind: FunctionKind::Test,
+        }];
+
+        let struct_name = CString::new(FAKE_STRUCT_NAME).expect("Invalid fake struct name");
+        let field_name = CString::new(FAKE_FIELD_NAME).expect("Invalid fake field name");
+        let mut struct_info = fake_struct_definition(
+            &struct_name,
+            &[field_name.as_ptr()],
+            std::slice::from_ref(i32_type_id),
+            &[0],
+            StructMemoryKind::Value,
+        );
+        struct_info.repr = StructRepr::C;
+        struct_info.passed_by_value = true;
+        let struct_guid = struct_info.guid;
+        let types = [fake_type_definition(
+            &struct_name,
+            32,
+            4,
+            TypeDefinitionData::Struct(struct_info),
+        )];
+
+        let module_path = CString::new(FAKE_MODULE_PATH).expect("Invalid fake module path.");
+        let module = fake_module_info(&module_path, &functions, &types);
+
+        let prototypes = [fn_prototype];
+        let dispatch_table = fake_dispatch_table(&prototypes, &mut [ptr::null()]);
+        let type_lut = fake_type_lut(
+            std::slice::from_ref(i32_type_id),
+            &mut [ptr::null()],
+            &[i32_name.as_ptr()],
+        );
+
+        let dependency = CString::new(FAKE_DEPENDENCY).expect("Invalid fake dependency.");
+        let assembly = fake_assembly_info(module, dispatch_table, type_lut, &[dependency.as_ptr()]);
+
+        assert_eq!(
+            assembly.to_string(),
+            format!(
+                "\
+module `{FAKE_MODULE_PATH}`
+capabilities: 0x0
+dependencies:
+    {FAKE_DEPENDENCY}
+functions:
+    #[test] fn {FAKE_FN_NAME}(core::i32) -> core::i32
+types:
+    {struct_guid} {FAKE_STRUCT_NAME}: 4 bytes, aligned to 4 bytes, Value struct, #[repr(C)], passed by value
+           0: {FAKE_FIELD_NAME}: core::i32
+dispatch table:
+       0: fn {FAKE_FN_NAME}(core::i32) -> core::i32
+type lookup table:
+       0: {i32_type_id} core::i32
+"
+            )
+        );
+    }
 }
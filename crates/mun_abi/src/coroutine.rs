@@ -0,0 +1,26 @@
+use std::ffi::c_void;
+
+/// Represents a coroutine that was started by calling a
+/// [`FunctionKind::Resumable`](crate::FunctionKind::Resumable) function.
+///
+/// The function fills in the context before it suspends for the first time.
+/// Afterwards, the coroutine is driven by calling `resume` with `handle`, until
+/// it returns a non-zero value to indicate that the function has returned. A
+/// coroutine must be destroyed by calling `destroy` with `handle` exactly
+/// once, which frees its frame.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct CoroutineContext {
+    /// Opaque handle to the frame of the coroutine
+    pub handle: *mut c_void,
+    /// Resumes the coroutine until it suspends again. Returns a non-zero value
+    /// if the function has returned, after which the coroutine can no longer
+    /// be resumed.
+    pub resume: extern "C" fn(*mut c_void) -> u8,
+    /// Destroys the coroutine and frees its frame
+    pub destroy: extern "C" fn(*mut c_void),
+    /// The value to which the `yield` expression at which the coroutine is
+    /// suspended evaluates when it is resumed. Must be set before every call
+    /// to `resume`.
+    pub resume_value: f32,
+}
@@ -17,6 +17,33 @@ pub struct FunctionDefinition<'a> {
     pub prototype: FunctionPrototype<'a>,
     /// Function pointer
     pub fn_ptr: *const c_void,
+    /// Function kind
+    pub kind: FunctionKind,
+}
+
+/// Represents the role of a function in an assembly.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum FunctionKind {
+    /// A regular function.
+    #[default]
+    Normal,
+
+    /// A function annotated with `#[test]`. A test takes no arguments and
+    /// returns either nothing or a `bool` that indicates whether it passed.
+    Test,
+
+    /// A function annotated with `#[bench]`. A benchmark takes no arguments
+    /// and returns nothing.
+    Bench,
+
+    /// A function declared as `resumable fn`. Calling `fn_ptr` with the
+    /// arguments of the function, followed by a pointer to a
+    /// [`CoroutineContext`](crate::CoroutineContext), starts a coroutine that
+    /// is suspended before the body of the function is executed. The function
+    /// itself returns nothing.
+    Resumable,
 }
 
 /// Represents a function prototype. A function prototype contains the name,
@@ -94,9 +121,10 @@ impl<'a> serde::Serialize for FunctionDefinition<'a> {
     {
         use serde::ser::SerializeStruct;
 
-        let mut s = serializer.serialize_struct("FunctionDefinition", 1)?;
+        let mut s = serializer.serialize_struct("FunctionDefinition", 2)?;
         s.serialize_field("prototype", &self.prototype)?;
         s.skip_field("fn_ptr")?;
+        s.serialize_field("kind", &self.kind)?;
         s.end()
     }
 }
@@ -0,0 +1,123 @@
+/// Marks the start of the [`IntegrityInfo`] that is embedded in an assembly.
+/// Tools that seal or verify a munlib locate the integrity information in the
+/// contents of the shared library by searching for this sequence of bytes.
+pub const INTEGRITY_MAGIC: [u8; 16] = *b"MUN_INTEGRITY_V1";
+
+/// Represents the integrity information of an assembly.
+///
+/// Every assembly embeds an unsealed copy of this struct, which contains only
+/// the [`INTEGRITY_MAGIC`]. Sealing the assembly after it is linked stores a
+/// SHA-256 hash of the contents of the shared library, and optionally an
+/// Ed25519 signature of that hash, in the shared library itself. The hash is
+/// computed with the `hash` and `signature` fields zeroed.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IntegrityInfo {
+    /// Equal to [`INTEGRITY_MAGIC`]
+    pub magic: [u8; 16],
+    /// Describes which of the other fields are set; see
+    /// [`IntegrityInfo::HASHED`] and [`IntegrityInfo::SIGNED`]
+    pub flags: u8,
+    /// The SHA-256 hash of the contents of the shared library
+    pub hash: [u8; 32],
+    /// The Ed25519 signature of the `hash`
+    pub signature: [u8; 64],
+}
+
+impl IntegrityInfo {
+    /// Set in the `flags` when the `hash` is set
+    pub const HASHED: u8 = 1 << 0;
+    /// Set in the `flags` when the `signature` is set
+    pub const SIGNED: u8 = 1 << 1;
+
+    /// The size of the integrity information in bytes
+    pub const SIZE: usize = std::mem::size_of::<IntegrityInfo>();
+    /// The offset in bytes of the `flags` field
+    pub const FLAGS_OFFSET: usize = 16;
+    /// The offset in bytes of the `hash` field
+    pub const HASH_OFFSET: usize = 17;
+    /// The offset in bytes of the `signature` field
+    pub const SIGNATURE_OFFSET: usize = 49;
+
+    /// The integrity information of an assembly that is not sealed
+    pub const UNSEALED: IntegrityInfo = IntegrityInfo {
+        magic: INTEGRITY_MAGIC,
+        flags: 0,
+        hash: [0; 32],
+        signature: [0; 64],
+    };
+
+    /// Reads integrity information from its byte representation.
+    pub fn from_bytes(bytes: &[u8; IntegrityInfo::SIZE]) -> Self {
+        let mut info = IntegrityInfo::UNSEALED;
+        info.magic
+            .copy_from_slice(&bytes[..IntegrityInfo::FLAGS_OFFSET]);
+        info.flags = bytes[IntegrityInfo::FLAGS_OFFSET];
+        info.hash
+            .copy_from_slice(&bytes[IntegrityInfo::HASH_OFFSET..IntegrityInfo::SIGNATURE_OFFSET]);
+        info.signature
+            .copy_from_slice(&bytes[IntegrityInfo::SIGNATURE_OFFSET..]);
+        info
+    }
+
+    /// Returns the byte representation of the integrity information.
+    pub fn to_bytes(&self) -> [u8; IntegrityInfo::SIZE] {
+        let mut bytes = [0; IntegrityInfo::SIZE];
+        bytes[..IntegrityInfo::FLAGS_OFFSET].copy_from_slice(&self.magic);
+        bytes[IntegrityInfo::FLAGS_OFFSET] = self.flags;
+        bytes[IntegrityInfo::HASH_OFFSET..IntegrityInfo::SIGNATURE_OFFSET]
+            .copy_from_slice(&self.hash);
+        bytes[IntegrityInfo::SIGNATURE_OFFSET..].copy_from_slice(&self.signature);
+        bytes
+    }
+
+    /// Returns the content hash of the assembly, if it is hashed.
+    pub fn hash(&self) -> Option<&[u8; 32]> {
+        (self.flags & IntegrityInfo::HASHED != 0).then_some(&self.hash)
+    }
+
+    /// Returns the signature of the content hash, if the assembly is signed.
+    pub fn signature(&self) -> Option<&[u8; 64]> {
+        (self.flags & IntegrityInfo::SIGNED != 0).then_some(&self.signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IntegrityInfo, INTEGRITY_MAGIC};
+
+    #[test]
+    fn test_integrity_info_layout() {
+        assert_eq!(IntegrityInfo::SIZE, 16 + 1 + 32 + 64);
+        assert_eq!(std::mem::align_of::<IntegrityInfo>(), 1);
+    }
+
+    #[test]
+    fn test_integrity_info_bytes_roundtrip() {
+        let info = IntegrityInfo {
+            magic: INTEGRITY_MAGIC,
+            flags: IntegrityInfo::HASHED | IntegrityInfo::SIGNED,
+            hash: [1; 32],
+            signature: [2; 64],
+        };
+
+        let bytes = info.to_bytes();
+        assert_eq!(&bytes[..16], &INTEGRITY_MAGIC);
+        assert_eq!(IntegrityInfo::from_bytes(&bytes), info);
+
+        // The byte representation matches the in-memory layout
+        let in_memory = unsafe {
+            std::slice::from_raw_parts(
+                (&info as *const IntegrityInfo).cast::<u8>(),
+                IntegrityInfo::SIZE,
+            )
+        };
+        assert_eq!(in_memory, &bytes);
+    }
+
+    #[test]
+    fn test_integrity_info_unsealed() {
+        assert_eq!(IntegrityInfo::UNSEALED.hash(), None);
+        assert_eq!(IntegrityInfo::UNSEALED.signature(), None);
+    }
+}
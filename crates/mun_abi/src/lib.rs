@@ -6,40 +6,94 @@
 
 use std::{ffi::CStr, fmt};
 
-pub use assembly_info::AssemblyInfo;
+pub use assembly_info::{AssemblyInfo, Capabilities};
+pub use coroutine::CoroutineContext;
 pub use dispatch_table::DispatchTable;
-pub use function_info::{FunctionDefinition, FunctionPrototype, FunctionSignature};
+pub use function_info::{FunctionDefinition, FunctionKind, FunctionPrototype, FunctionSignature};
+pub use integrity_info::{IntegrityInfo, INTEGRITY_MAGIC};
+pub use log::{LogArgKind, LogKind};
 pub use module_info::ModuleInfo;
+pub use overflow_op::OverflowOp;
 pub use primitive::PrimitiveType;
-pub use struct_info::{StructDefinition, StructMemoryKind};
+pub use struct_info::{FieldDefault, StructDefinition, StructMemoryKind, StructRepr};
 pub use type_id::{ArrayTypeId, HasStaticTypeId, PointerTypeId, TypeId};
 pub use type_info::{HasStaticTypeName, TypeDefinition, TypeDefinitionData};
 pub use type_lut::TypeLut;
+pub use validation::{validate_assembly_info, InvalidAssemblyInfo};
 
 // C bindings can be manually generated by running `cargo gen-abi`.
 mod assembly_info;
+mod coroutine;
 mod dispatch_table;
 mod function_info;
+mod integrity_info;
+mod log;
 mod module_info;
+mod overflow_op;
 mod primitive;
 pub mod static_type_map;
 mod struct_info;
 mod type_id;
 mod type_info;
 mod type_lut;
+mod validation;
 
 #[cfg(test)]
 mod test_utils;
 
-/// Defines the current ABI version
+/// Defines the current ABI version, encoded as `MM_mm_pp` (major, minor,
+/// patch).
+///
+/// Within a major version the layout of the ABI only grows: a new minor
+/// version may append optional sections to [`AssemblyInfo`], which are
+/// announced through its [`Capabilities`]. A runtime can therefore load
+/// assemblies that were generated for an older minor version of the same major
+/// version, see [`is_compatible_abi_version`].
 #[allow(clippy::zero_prefixed_literal)]
-pub const ABI_VERSION: u32 = 00_03_00;
+pub const ABI_VERSION: u32 = 01_05_00;
+/// Defines the oldest ABI version that is compatible with the current ABI
+/// version
+#[allow(clippy::zero_prefixed_literal)]
+pub const MIN_ABI_VERSION: u32 = 01_00_00;
 /// Defines the name for the `get_info` function
 pub const GET_INFO_FN_NAME: &str = "get_info";
 /// Defines the name for the `get_version` function
 pub const GET_VERSION_FN_NAME: &str = "get_version";
+/// Defines the name for the `get_compiler_version` function
+pub const GET_COMPILER_VERSION_FN_NAME: &str = "get_compiler_version";
+/// Defines the name for the `get_integrity` function
+pub const GET_INTEGRITY_FN_NAME: &str = "get_integrity";
 /// Defines the name for the `set_allocator_handle` function
 pub const SET_ALLOCATOR_HANDLE_FN_NAME: &str = "set_allocator_handle";
+/// Defines the name for the constant that holds the ABI version
+pub const ABI_VERSION_SYMBOL_NAME: &str = "MUN_ABI_VERSION";
+/// Defines the name for the constant that holds the [`AssemblyInfo`]
+pub const ASSEMBLY_INFO_SYMBOL_NAME: &str = "MUN_ASSEMBLY_INFO";
+/// Defines the name for the constant that holds the version of the compiler,
+/// as a null-terminated string
+pub const COMPILER_VERSION_SYMBOL_NAME: &str = "MUN_COMPILER_VERSION";
+
+/// Returns the major version of an encoded ABI `version`.
+pub const fn abi_major_version(version: u32) -> u32 {
+    version / 1_00_00
+}
+
+/// Returns the minor version of an encoded ABI `version`.
+pub const fn abi_minor_version(version: u32) -> u32 {
+    version / 1_00 % 1_00
+}
+
+/// Returns whether an assembly that was generated for the ABI `version` can be
+/// loaded by a runtime that implements the current [`ABI_VERSION`].
+///
+/// This is the case when the versions share the same major version and
+/// `version` is not older than [`MIN_ABI_VERSION`] nor newer than
+/// [`ABI_VERSION`].
+pub const fn is_compatible_abi_version(version: u32) -> bool {
+    abi_major_version(version) == abi_major_version(ABI_VERSION)
+        && version >= MIN_ABI_VERSION
+        && version <= ABI_VERSION
+}
 
 /// Represents a globally unique identifier (GUID).
 #[repr(C)]
@@ -56,6 +110,15 @@ impl Guid {
     pub fn from_cstr(str: &CStr) -> Guid {
         Guid(extendhash::md5::compute_hash(str.to_bytes()))
     }
+
+    /// Create the GUID of an opaque type with the specified name. Opaque types
+    /// are declared in Mun as `extern struct` and are owned by the host,
+    /// which registers them with the runtime by their name. As such, the GUID
+    /// only depends on the name of the type and not on the module in which it
+    /// is declared.
+    pub fn from_opaque_type_name(name: &str) -> Guid {
+        Guid::from_str(&format!("extern struct {name}"))
+    }
 }
 
 impl fmt::Display for Guid {
@@ -123,3 +186,20 @@ pub enum Privacy {
 
 // TODO: Fix leakage of pointer types in struct fields due to integration tests
 // and test utils
+
+#[cfg(test)]
+mod tests {
+    use super::{is_compatible_abi_version, ABI_VERSION, MIN_ABI_VERSION};
+
+    #[test]
+    #[allow(clippy::zero_prefixed_literal)]
+    fn test_is_compatible_abi_version() {
+        assert!(is_compatible_abi_version(ABI_VERSION));
+        assert!(is_compatible_abi_version(MIN_ABI_VERSION));
+
+        // Older major versions, newer minor versions, and newer major versions
+        assert!(!is_compatible_abi_version(00_04_00));
+        assert!(!is_compatible_abi_version(ABI_VERSION + 1_00));
+        assert!(!is_compatible_abi_version(ABI_VERSION + 1_00_00));
+    }
+}
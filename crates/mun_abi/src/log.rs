@@ -0,0 +1,85 @@
+/// Represents the built-in function that Mun code called to write a message.
+/// The kind is passed to the `log` intrinsic, which is provided by the runtime.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LogKind {
+    /// A message that is written with `print(...)`
+    Print,
+    /// A message that is written with `log(...)`
+    Log,
+}
+
+impl LogKind {
+    /// Returns the kind that is represented by `value`, or `None` if `value`
+    /// does not represent a kind.
+    pub fn from_u8(value: u8) -> Option<LogKind> {
+        match value {
+            0 => Some(LogKind::Print),
+            1 => Some(LogKind::Log),
+            _ => None,
+        }
+    }
+}
+
+/// Represents the type of an argument that is passed to the `log` intrinsic.
+///
+/// Every argument is passed as a 128-bit value that is split into two 64-bit
+/// words, the least significant word first. Integers are sign- or
+/// zero-extended, booleans are `0` or `1`, and floating-point numbers are
+/// passed as their bits.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LogArgKind {
+    /// A `bool`
+    Bool,
+    /// A signed integer
+    Int,
+    /// An unsigned integer
+    UInt,
+    /// An `f32`
+    Float32,
+    /// An `f64`
+    Float64,
+}
+
+impl LogArgKind {
+    /// Returns the kind that is represented by `value`, or `None` if `value`
+    /// does not represent a kind.
+    pub fn from_u8(value: u8) -> Option<LogArgKind> {
+        match value {
+            0 => Some(LogArgKind::Bool),
+            1 => Some(LogArgKind::Int),
+            2 => Some(LogArgKind::UInt),
+            3 => Some(LogArgKind::Float32),
+            4 => Some(LogArgKind::Float64),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogArgKind, LogKind};
+
+    #[test]
+    fn test_log_kind_from_u8() {
+        for kind in [LogKind::Print, LogKind::Log] {
+            assert_eq!(LogKind::from_u8(kind as u8), Some(kind));
+        }
+        assert_eq!(LogKind::from_u8(2), None);
+    }
+
+    #[test]
+    fn test_log_arg_kind_from_u8() {
+        for kind in [
+            LogArgKind::Bool,
+            LogArgKind::Int,
+            LogArgKind::UInt,
+            LogArgKind::Float32,
+            LogArgKind::Float64,
+        ] {
+            assert_eq!(LogArgKind::from_u8(kind as u8), Some(kind));
+        }
+        assert_eq!(LogArgKind::from_u8(5), None);
+    }
+}
@@ -86,7 +86,7 @@ mod tests {
             FAKE_FN_NAME, FAKE_MODULE_PATH, FAKE_STRUCT_NAME,
         },
         type_id::HasStaticTypeId,
-        FunctionDefinition, StructMemoryKind, TypeDefinition, TypeDefinitionData,
+        FunctionDefinition, FunctionKind, StructMemoryKind, TypeDefinition, TypeDefinitionData,
     };
 
     #[test]
@@ -117,6 +117,7 @@ mod tests {
         let fn_info = FunctionDefinition {
             prototype: fn_prototype,
             fn_ptr: ptr::null(),
+            kind: FunctionKind::default(),
         };
         let functions = &[fn_info];
 
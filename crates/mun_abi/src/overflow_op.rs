@@ -0,0 +1,61 @@
+use std::fmt;
+
+/// Represents an integer operation that overflowed in Mun code that was
+/// compiled with overflow checks. The operation is passed to the `overflow`
+/// intrinsic, which is provided by the runtime.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverflowOp {
+    /// An addition, e.g. `a + b` or `a += b`
+    Add,
+    /// A subtraction, e.g. `a - b` or `a -= b`
+    Subtract,
+    /// A multiplication, e.g. `a * b` or `a *= b`
+    Multiply,
+    /// A negation, e.g. `-a`
+    Negate,
+}
+
+impl OverflowOp {
+    /// Returns the operation that is represented by `value`, or `None` if
+    /// `value` does not represent an operation.
+    pub fn from_u8(value: u8) -> Option<OverflowOp> {
+        match value {
+            0 => Some(OverflowOp::Add),
+            1 => Some(OverflowOp::Subtract),
+            2 => Some(OverflowOp::Multiply),
+            3 => Some(OverflowOp::Negate),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OverflowOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = match self {
+            OverflowOp::Add => "add",
+            OverflowOp::Subtract => "subtract",
+            OverflowOp::Multiply => "multiply",
+            OverflowOp::Negate => "negate",
+        };
+        write!(f, "attempt to {verb} with overflow")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OverflowOp;
+
+    #[test]
+    fn test_overflow_op_from_u8() {
+        for op in [
+            OverflowOp::Add,
+            OverflowOp::Subtract,
+            OverflowOp::Multiply,
+            OverflowOp::Negate,
+        ] {
+            assert_eq!(OverflowOp::from_u8(op as u8), Some(op));
+        }
+        assert_eq!(OverflowOp::from_u8(4), None);
+    }
+}
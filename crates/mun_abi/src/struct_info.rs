@@ -21,6 +21,47 @@ pub struct StructDefinition<'a> {
     // TODO: Add struct accessibility level
     /// Struct memory kind
     pub memory_kind: StructMemoryKind,
+    /// How the fields of the struct are laid out in memory. Introduced in ABI
+    /// version 1.1. It occupies what used to be trailing padding, which is
+    /// zeroed, i.e. [`StructRepr::Default`], in assemblies that were
+    /// generated for ABI version 1.0.
+    pub repr: StructRepr,
+    /// Whether values of the struct are passed to and returned from public
+    /// functions by value, following the C calling convention of the target,
+    /// instead of as handles to garbage collected copies. Introduced in ABI
+    /// version 1.2. It occupies what used to be trailing padding, which is
+    /// zeroed, i.e. `false`, in assemblies that were generated for older ABI
+    /// versions.
+    pub passed_by_value: bool,
+}
+
+/// Represents the default value of a struct field, which is used to initialize
+/// the field when it is added to the struct of an allocated object while hot
+/// reloading. Introduced in ABI version 1.3, see
+/// [`Capabilities::FIELD_DEFAULTS`](crate::Capabilities::FIELD_DEFAULTS).
+#[repr(C)]
+#[derive(Debug)]
+pub struct FieldDefault {
+    /// The unique identifier of the struct that declares the field
+    pub struct_guid: Guid,
+    /// The index of the field in the struct
+    pub field_index: u16,
+    /// The size of the value in bytes
+    pub(crate) value_size: u16,
+    /// The value, in the memory representation of the field's type
+    pub(crate) value: *const u8,
+}
+
+impl FieldDefault {
+    /// Returns the bytes of the value, in the memory representation of the
+    /// field's type.
+    pub fn value(&self) -> &[u8] {
+        if self.value_size == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.value, self.value_size as usize) }
+        }
+    }
 }
 
 /// Represents the kind of memory management a struct uses.
@@ -38,10 +79,48 @@ pub enum StructMemoryKind {
     ///
     /// NOTE: When a value struct is used in an external API, a wrapper is
     /// created that _pins_ the value on the heap. The heap-allocated value
-    /// needs to be *manually deallocated*!
+    /// needs to be *manually deallocated*! Small `#[repr(C)]` value structs
+    /// are passed by value instead, see
+    /// [`StructDefinition::passed_by_value`].
     Value,
 }
 
+/// Represents how the fields of a struct are laid out in memory, as specified
+/// with the `#[repr(..)]` attribute.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum StructRepr {
+    /// The fields are laid out in the order in which they are declared, each
+    /// aligned to its natural alignment. The compiler is free to change this
+    /// layout in the future.
+    #[default]
+    Default,
+
+    /// `#[repr(C)]`: the fields are laid out in the order in which they are
+    /// declared, each aligned to its natural alignment, exactly like a C
+    /// compiler would. This layout is guaranteed to remain stable, so hosts can
+    /// share plain-old-data buffers with Mun code.
+    C,
+
+    /// `#[repr(packed)]` or `#[repr(C, packed)]`: the fields are laid out in
+    /// the order in which they are declared without any padding in between
+    /// them, and the struct is aligned to a single byte.
+    Packed,
+}
+
+impl StructRepr {
+    /// Returns the attribute that specifies this representation, e.g.
+    /// `#[repr(C)]`, or `None` for the default representation.
+    pub fn attribute(self) -> Option<&'static str> {
+        match self {
+            StructRepr::Default => None,
+            StructRepr::C => Some("#[repr(C)]"),
+            StructRepr::Packed => Some("#[repr(packed)]"),
+        }
+    }
+}
+
 impl<'a> StructDefinition<'a> {
     /// Returns the struct's field names.
     pub fn field_names(&self) -> impl Iterator<Item = &str> {
@@ -104,7 +183,7 @@ impl<'a> serde::Serialize for StructDefinition<'a> {
             offset: &'a u16,
         }
 
-        let mut s = serializer.serialize_struct("StructInfo", 3)?;
+        let mut s = serializer.serialize_struct("StructInfo", 5)?;
 
         s.serialize_field("guid", &self.guid)?;
         s.serialize_field(
@@ -121,18 +200,24 @@ impl<'a> serde::Serialize for StructDefinition<'a> {
                 .collect_vec(),
         )?;
         s.serialize_field("memory_kind", &self.memory_kind)?;
+        s.serialize_field("repr", &self.repr)?;
+        s.serialize_field("passed_by_value", &self.passed_by_value)?;
         s.end()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::ffi::CString;
+    use std::{
+        ffi::{c_char, CString},
+        mem::size_of,
+    };
 
-    use super::StructMemoryKind;
+    use super::{StructDefinition, StructMemoryKind};
     use crate::{
         test_utils::{fake_struct_definition, FAKE_FIELD_NAME, FAKE_STRUCT_NAME},
         type_id::HasStaticTypeId,
+        Guid, TypeId,
     };
 
     #[test]
@@ -195,4 +280,26 @@ mod tests {
 
         assert_eq!(struct_info.memory_kind, struct_memory_kind);
     }
+
+    #[test]
+    fn test_struct_info_extensions_in_padding() {
+        /// The layout of `StructDefinition` in ABI version 1.0
+        #[repr(C)]
+        #[allow(dead_code)]
+        struct StructDefinitionV1_0 {
+            guid: Guid,
+            field_names: *const *const c_char,
+            field_types: *const TypeId<'static>,
+            field_offsets: *const u16,
+            num_fields: u16,
+            memory_kind: StructMemoryKind,
+        }
+
+        // The size must not change, otherwise arrays of type definitions in
+        // assemblies generated for ABI version 1.0 could no longer be read
+        assert_eq!(
+            size_of::<StructDefinition<'_>>(),
+            size_of::<StructDefinitionV1_0>()
+        );
+    }
 }
@@ -1,12 +1,14 @@
 use std::{
     ffi::{self, CStr},
     os::raw::c_char,
+    ptr,
 };
 
 use crate::{
     type_id::{HasStaticTypeId, TypeId},
-    AssemblyInfo, DispatchTable, FunctionDefinition, FunctionPrototype, FunctionSignature, Guid,
-    ModuleInfo, StructDefinition, StructMemoryKind, TypeDefinition, TypeDefinitionData, TypeLut,
+    AssemblyInfo, Capabilities, DispatchTable, FunctionDefinition, FunctionPrototype,
+    FunctionSignature, Guid, ModuleInfo, StructDefinition, StructMemoryKind, StructRepr,
+    TypeDefinition, TypeDefinitionData, TypeLut,
 };
 
 pub(crate) const FAKE_TYPE_GUID: Guid =
@@ -31,6 +33,11 @@ pub(crate) fn fake_assembly_info<'a>(
         type_lut,
         dependencies: dependencies.as_ptr(),
         num_dependencies: dependencies.len() as u32,
+        capabilities: Capabilities::empty(),
+        field_defaults: ptr::null(),
+        num_field_defaults: 0,
+        private_functions: ptr::null(),
+        num_private_functions: 0,
     }
 }
 
@@ -115,6 +122,8 @@ pub(crate) fn fake_struct_definition<'a>(
         field_offsets: field_offsets.as_ptr(),
         num_fields: field_names.len() as u16,
         memory_kind,
+        repr: StructRepr::default(),
+        passed_by_value: false,
     }
 }
 
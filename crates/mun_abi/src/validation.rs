@@ -0,0 +1,388 @@
+use std::{error::Error, ffi::CStr, fmt, mem, os::raw::c_char};
+
+use crate::{
+    AssemblyInfo, Capabilities, DispatchTable, FieldDefault, FunctionDefinition, FunctionKind,
+    FunctionPrototype, ModuleInfo, StructDefinition, StructMemoryKind, StructRepr, TypeDefinition,
+    TypeId, TypeLut,
+};
+
+/// The maximum nesting depth of a [`TypeId`], e.g. of a pointer to a pointer.
+/// This bounds the validation of type ids that refer to themselves.
+const MAX_TYPE_ID_DEPTH: usize = 64;
+
+/// An error that occurs when an [`AssemblyInfo`] refers to memory outside of
+/// the memory that it is validated against, or contains a value that is
+/// invalid for its type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvalidAssemblyInfo {
+    /// A pointer refers to memory outside of the validated memory, or is not
+    /// properly aligned
+    InvalidPointer(usize),
+    /// A string is not terminated within the validated memory, or is not valid
+    /// UTF-8
+    InvalidString(usize),
+    /// The value of an enum or `bool` is out of range
+    InvalidValue(&'static str),
+    /// A type id is nested more deeply than is supported
+    TypeIdTooDeep,
+}
+
+impl fmt::Display for InvalidAssemblyInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidAssemblyInfo::InvalidPointer(address) => {
+                write!(f, "invalid pointer to {address:#x}")
+            }
+            InvalidAssemblyInfo::InvalidString(address) => {
+                write!(f, "invalid string at {address:#x}")
+            }
+            InvalidAssemblyInfo::InvalidValue(name) => write!(f, "invalid value of {name}"),
+            InvalidAssemblyInfo::TypeIdTooDeep => write!(f, "type id is nested too deeply"),
+        }
+    }
+}
+
+impl Error for InvalidAssemblyInfo {}
+
+/// Validates that the [`AssemblyInfo`] at `info`, and everything it refers to,
+/// lies within `memory` and contains valid values. If it does, `info` can be
+/// read and all accessors of the assembly info can be used safely, as long as
+/// `memory` is not modified.
+///
+/// This is used to inspect an assembly whose contents are not trusted, e.g. a
+/// munlib that was mapped into memory without loading it.
+pub fn validate_assembly_info(
+    info: *const AssemblyInfo<'_>,
+    memory: &[u8],
+) -> Result<(), InvalidAssemblyInfo> {
+    let validator = Validator { memory };
+    let info = validator.check(info, 1)?;
+
+    // Safety: every pointer is checked to lie within `memory` before it is
+    // dereferenced, and the pointers that are dereferenced are derived from
+    // `memory`.
+    unsafe {
+        validator.module_info(std::ptr::addr_of!((*info).symbols))?;
+        validator.dispatch_table(std::ptr::addr_of!((*info).dispatch_table))?;
+        validator.type_lut(std::ptr::addr_of!((*info).type_lut))?;
+        validator.strings((*info).dependencies, (*info).num_dependencies as usize)?;
+
+        let capabilities = (*info).capabilities;
+        if capabilities.contains(Capabilities::FIELD_DEFAULTS) {
+            validator
+                .field_defaults((*info).field_defaults, (*info).num_field_defaults as usize)?;
+        }
+        if capabilities.contains(Capabilities::PRIVATE_FUNCTIONS) {
+            validator.function_definitions(
+                (*info).private_functions,
+                (*info).num_private_functions as usize,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The layout of [`TypeId::Pointer`], see RFC 2195.
+#[repr(C)]
+struct PointerTypeIdRepr {
+    _tag: u8,
+    pointee: *const TypeId<'static>,
+    mutable: u8,
+}
+
+/// The layout of [`TypeId::Array`], see RFC 2195.
+#[repr(C)]
+struct ArrayTypeIdRepr {
+    _tag: u8,
+    element: *const TypeId<'static>,
+}
+
+/// The layout of [`TypeDefinitionData::Struct`](crate::TypeDefinitionData),
+/// see RFC 2195.
+#[repr(C)]
+struct StructDataRepr {
+    tag: u8,
+    definition: StructDefinition<'static>,
+}
+
+struct Validator<'m> {
+    memory: &'m [u8],
+}
+
+impl Validator<'_> {
+    /// Checks that `count` values of type `T` at `ptr` lie within the memory
+    /// and are properly aligned, and returns a pointer to them that is
+    /// derived from the memory.
+    fn check<T>(&self, ptr: *const T, count: usize) -> Result<*const T, InvalidAssemblyInfo> {
+        let address = ptr as usize;
+        let offset = address
+            .checked_sub(self.memory.as_ptr() as usize)
+            .filter(|offset| {
+                mem::size_of::<T>()
+                    .checked_mul(count)
+                    .and_then(|len| offset.checked_add(len))
+                    .is_some_and(|end| end <= self.memory.len())
+            })
+            .filter(|_| address % mem::align_of::<T>() == 0)
+            .ok_or(InvalidAssemblyInfo::InvalidPointer(address))?;
+
+        Ok(self.memory[offset..].as_ptr().cast::<T>())
+    }
+
+    /// Checks that `count` values of type `T` at `ptr` are valid, using
+    /// `validate` to check each value. An empty slice may be null.
+    unsafe fn slice<T>(
+        &self,
+        ptr: *const T,
+        count: usize,
+        mut validate: impl FnMut(*const T) -> Result<(), InvalidAssemblyInfo>,
+    ) -> Result<(), InvalidAssemblyInfo> {
+        if count == 0 {
+            return Ok(());
+        }
+
+        let ptr = self.check(ptr, count)?;
+        (0..count).try_for_each(|idx| validate(ptr.add(idx)))
+    }
+
+    /// Checks that the value of type `T` at `ptr`, which must be a `u8`
+    /// enum or `bool`, is at most `max`.
+    unsafe fn value<T>(
+        ptr: *const T,
+        max: u8,
+        name: &'static str,
+    ) -> Result<(), InvalidAssemblyInfo> {
+        debug_assert_eq!(mem::size_of::<T>(), 1);
+        if *ptr.cast::<u8>() <= max {
+            Ok(())
+        } else {
+            Err(InvalidAssemblyInfo::InvalidValue(name))
+        }
+    }
+
+    /// Checks that `ptr` points to a nul-terminated UTF-8 string.
+    fn string(&self, ptr: *const c_char) -> Result<(), InvalidAssemblyInfo> {
+        let ptr = self.check(ptr, 1)?;
+        let offset = ptr as usize - self.memory.as_ptr() as usize;
+        CStr::from_bytes_until_nul(&self.memory[offset..])
+            .ok()
+            .and_then(|string| string.to_str().ok())
+            .map(|_| ())
+            .ok_or(InvalidAssemblyInfo::InvalidString(ptr as usize))
+    }
+
+    unsafe fn strings(
+        &self,
+        ptr: *const *const c_char,
+        count: usize,
+    ) -> Result<(), InvalidAssemblyInfo> {
+        self.slice(ptr, count, |string| self.string(*string))
+    }
+
+    unsafe fn module_info(&self, module: *const ModuleInfo<'_>) -> Result<(), InvalidAssemblyInfo> {
+        self.string((*module).path)?;
+        self.function_definitions((*module).functions, (*module).num_functions as usize)?;
+        self.slice(
+            (*module).types,
+            (*module).num_types as usize,
+            |type_definition| self.type_definition(type_definition),
+        )
+    }
+
+    unsafe fn dispatch_table(
+        &self,
+        dispatch_table: *const DispatchTable<'_>,
+    ) -> Result<(), InvalidAssemblyInfo> {
+        let num_entries = (*dispatch_table).num_entries as usize;
+        self.slice((*dispatch_table).prototypes, num_entries, |prototype| {
+            self.function_prototype(prototype)
+        })?;
+        self.slice((*dispatch_table).fn_ptrs.cast_const(), num_entries, |_| {
+            Ok(())
+        })
+    }
+
+    unsafe fn type_lut(&self, type_lut: *const TypeLut<'_>) -> Result<(), InvalidAssemblyInfo> {
+        let num_entries = (*type_lut).num_entries as usize;
+        self.slice((*type_lut).type_ids, num_entries, |type_id| {
+            self.type_id(type_id, 0)
+        })?;
+        self.slice((*type_lut).type_handles.cast_const(), num_entries, |_| {
+            Ok(())
+        })?;
+        self.strings((*type_lut).type_names, num_entries)
+    }
+
+    unsafe fn field_defaults(
+        &self,
+        ptr: *const FieldDefault,
+        count: usize,
+    ) -> Result<(), InvalidAssemblyInfo> {
+        self.slice(ptr, count, |field_default| {
+            self.slice(
+                (*field_default).value,
+                (*field_default).value_size as usize,
+                |_| Ok(()),
+            )
+        })
+    }
+
+    unsafe fn function_definitions(
+        &self,
+        ptr: *const FunctionDefinition<'_>,
+        count: usize,
+    ) -> Result<(), InvalidAssemblyInfo> {
+        self.slice(ptr, count, |definition| {
+            self.function_prototype(std::ptr::addr_of!((*definition).prototype))?;
+            Self::value(
+                std::ptr::addr_of!((*definition).kind),
+                FunctionKind::Resumable as u8,
+                "FunctionKind",
+            )
+        })
+    }
+
+    unsafe fn function_prototype(
+        &self,
+        prototype: *const FunctionPrototype<'_>,
+    ) -> Result<(), InvalidAssemblyInfo> {
+        self.string((*prototype).name)?;
+
+        let signature = std::ptr::addr_of!((*prototype).signature);
+        self.slice(
+            (*signature).arg_types,
+            (*signature).num_arg_types as usize,
+            |type_id| self.type_id(type_id, 0),
+        )?;
+        self.type_id(std::ptr::addr_of!((*signature).return_type), 0)
+    }
+
+    unsafe fn type_definition(
+        &self,
+        type_definition: *const TypeDefinition<'_>,
+    ) -> Result<(), InvalidAssemblyInfo> {
+        self.string((*type_definition).name)?;
+
+        let data = std::ptr::addr_of!((*type_definition).data).cast::<StructDataRepr>();
+        Self::value(std::ptr::addr_of!((*data).tag), 0, "TypeDefinitionData")?;
+
+        let definition = std::ptr::addr_of!((*data).definition);
+        let num_fields = (*definition).num_fields as usize;
+        self.strings((*definition).field_names, num_fields)?;
+        self.slice((*definition).field_types, num_fields, |type_id| {
+            self.type_id(type_id, 0)
+        })?;
+        self.slice((*definition).field_offsets, num_fields, |_| Ok(()))?;
+        Self::value(
+            std::ptr::addr_of!((*definition).memory_kind),
+            StructMemoryKind::Value as u8,
+            "StructMemoryKind",
+        )?;
+        Self::value(
+            std::ptr::addr_of!((*definition).repr),
+            StructRepr::Packed as u8,
+            "StructRepr",
+        )?;
+        Self::value(std::ptr::addr_of!((*definition).passed_by_value), 1, "bool")
+    }
+
+    /// Checks the type id at `type_id`, which is nested `depth` levels deep in
+    /// another type id.
+    unsafe fn type_id(
+        &self,
+        type_id: *const TypeId<'_>,
+        depth: usize,
+    ) -> Result<(), InvalidAssemblyInfo> {
+        if depth > MAX_TYPE_ID_DEPTH {
+            return Err(InvalidAssemblyInfo::TypeIdTooDeep);
+        }
+
+        match *type_id.cast::<u8>() {
+            0 => Ok(()),
+            1 => {
+                let pointer = type_id.cast::<PointerTypeIdRepr>();
+                Self::value(std::ptr::addr_of!((*pointer).mutable), 1, "bool")?;
+                let pointee = self.check((*pointer).pointee, 1)?;
+                self.type_id(pointee, depth + 1)
+            }
+            2 => {
+                let array = type_id.cast::<ArrayTypeIdRepr>();
+                let element = self.check((*array).element, 1)?;
+                self.type_id(element, depth + 1)
+            }
+            _ => Err(InvalidAssemblyInfo::InvalidValue("TypeId")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{mem, ptr};
+
+    use super::{validate_assembly_info, InvalidAssemblyInfo};
+    use crate::AssemblyInfo;
+
+    /// Memory that is aligned for an [`AssemblyInfo`].
+    #[repr(C, align(8))]
+    struct Memory([u8; 512]);
+
+    /// The offset in [`Memory`] at which the module path is stored
+    const PATH_OFFSET: usize = 384;
+
+    /// Returns memory that starts with a zeroed assembly info with a valid
+    /// module path.
+    fn new_memory() -> Box<Memory> {
+        let mut memory = Box::new(Memory([0; 512]));
+        memory.0[PATH_OFFSET..PATH_OFFSET + 4].copy_from_slice(b"mod\0");
+        let path = memory.0[PATH_OFFSET..].as_ptr().cast();
+        let info = info_mut(&mut memory);
+        unsafe { ptr::write(info, mem::zeroed()) };
+        info.symbols.path = path;
+        memory
+    }
+
+    fn info_mut(memory: &mut Memory) -> &mut AssemblyInfo<'static> {
+        assert!(mem::size_of::<AssemblyInfo<'_>>() <= PATH_OFFSET);
+        unsafe { &mut *memory.0.as_mut_ptr().cast::<AssemblyInfo<'static>>() }
+    }
+
+    fn validate(memory: &Memory) -> Result<(), InvalidAssemblyInfo> {
+        validate_assembly_info(memory.0.as_ptr().cast(), &memory.0)
+    }
+
+    #[test]
+    fn test_validate_assembly_info() {
+        let memory = new_memory();
+        assert_eq!(validate(&memory), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_assembly_info_out_of_bounds() {
+        let mut memory = new_memory();
+        let functions = memory.0.as_ptr().wrapping_add(4096).cast();
+        let info = info_mut(&mut memory);
+        info.symbols.num_functions = 1;
+        info.symbols.functions = functions;
+        assert!(matches!(
+            validate(&memory),
+            Err(InvalidAssemblyInfo::InvalidPointer(_))
+        ));
+
+        let memory = new_memory();
+        assert!(matches!(
+            validate_assembly_info(memory.0.as_ptr().cast(), &memory.0[..16]),
+            Err(InvalidAssemblyInfo::InvalidPointer(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_assembly_info_unterminated_string() {
+        let mut memory = new_memory();
+        memory.0[PATH_OFFSET..].fill(b'a');
+        assert!(matches!(
+            validate(&memory),
+            Err(InvalidAssemblyInfo::InvalidString(_))
+        ));
+    }
+}
@@ -2,7 +2,8 @@ use std::{path::Path, sync::Arc};
 
 use anyhow::anyhow;
 use apple_codesign::{SigningSettings, UnifiedSigner};
-use inkwell::context::Context;
+use inkwell::{context::Context, targets::FileType};
+use rayon::prelude::*;
 use tempfile::NamedTempFile;
 
 use crate::{
@@ -27,6 +28,12 @@ impl<'db, 'ink, 'ctx> Assembly<'db, 'ink, 'ctx> {
         Self { code_gen, module }
     }
 
+    /// Returns the LLVM module that contains the code of the assembly.
+    #[cfg(test)]
+    pub(crate) fn module(&self) -> &inkwell::module::Module<'ink> {
+        &self.module
+    }
+
     /// Tries to convert the assembly into an `ObjectFile`.
     pub fn into_object_file(self) -> Result<ObjectFile, anyhow::Error> {
         ObjectFile::new(
@@ -42,6 +49,14 @@ impl<'db, 'ink, 'ctx> Assembly<'db, 'ink, 'ctx> {
             .print_to_file(output_path)
             .map_err(|e| anyhow!("{}", e))
     }
+
+    /// Tries to write the `Assembly`'s target-specific assembly to file.
+    pub fn write_asm_to_file(self, output_path: &Path) -> Result<(), anyhow::Error> {
+        self.code_gen
+            .target_machine
+            .write_to_file(&self.module, FileType::Assembly, output_path)
+            .map_err(|e| anyhow!("{}", e))
+    }
 }
 
 /// Builds an assembly for the specified file
@@ -94,21 +109,42 @@ pub(crate) fn build_target_assembly(
     let inkwell_context = Context::create();
     let code_gen_context = CodeGenContext::new(&inkwell_context, db);
 
-    // Build an assembly for the module
-    let assembly = build_assembly(db, &code_gen_context, module_group);
-
-    // Convert the assembly into an object file
-    let obj_file = assembly
-        .into_object_file()
-        .expect("unable to create object file");
-
     // Construct a temporary file for the assembly
     let file = NamedTempFile::new().expect("could not create temp file for shared object");
 
-    // Translate the object file into a shared object
-    obj_file
-        .into_shared_object(file.path())
-        .expect("could not link object file");
+    if db.lto() {
+        // Link-time optimization requires all code of the assembly to be part of the
+        // same LLVM module.
+        let assembly = build_assembly(db, &code_gen_context, module_group);
+
+        // Convert the assembly into an object file
+        let obj_file = assembly
+            .into_object_file()
+            .expect("unable to create object file");
+
+        // Translate the object file into a shared object
+        obj_file
+            .into_shared_object(file.path())
+            .expect("could not link object file");
+    } else {
+        // Build an object file that contains the symbols of the assembly
+        let module_partition = db.module_partition();
+        let symbols = AssemblyBuilder::new(&code_gen_context, &module_partition, module_group)
+            .build_symbols()
+            .expect("unable to create assembly")
+            .into_object_file()
+            .expect("unable to create object file");
+
+        // Link the symbols with the code of all modules into a shared object
+        let module_objects: Vec<_> = module_partition[module_group]
+            .iter()
+            .map(|module| db.module_object(module_group, module))
+            .collect();
+        let objects: Vec<_> = module_objects.iter().map(|obj| &obj.object).collect();
+        symbols
+            .link_shared_object(&objects, file.path())
+            .expect("could not link object files");
+    }
 
     let target = db.target();
     if target.options.is_like_osx {
@@ -121,6 +157,94 @@ pub(crate) fn build_target_assembly(
     Arc::new(TargetAssembly { file })
 }
 
+/// Builds the target assemblies of all module groups on a thread pool, after
+/// which they can be retrieved from the database with
+/// [`CodeGenDatabase::target_assembly`].
+///
+/// The modules of all groups are compiled in parallel, each in its own LLVM
+/// context. The resulting object files are subsequently linked into the
+/// assemblies, in the order of the modules of each group.
+pub fn build_target_assemblies<DB>(db: &DB)
+where
+    DB: CodeGenDatabase + salsa::ParallelDatabase,
+{
+    let module_partition = db.module_partition();
+
+    // The object files of the modules are not used if the assemblies are optimized
+    // as a whole.
+    if !db.lto() {
+        let modules: Vec<_> = module_partition
+            .iter()
+            .flat_map(|(module_group_id, module_group)| {
+                module_group
+                    .iter()
+                    .map(move |module| (module_group_id, module))
+            })
+            .map(|(module_group_id, module)| (db.snapshot(), module_group_id, module))
+            .collect();
+        modules
+            .into_par_iter()
+            .for_each(|(db, module_group_id, module)| {
+                db.module_object(module_group_id, module);
+            });
+    }
+
+    let module_groups: Vec<_> = module_partition
+        .iter()
+        .map(|(module_group_id, _)| (db.snapshot(), module_group_id))
+        .collect();
+    module_groups
+        .into_par_iter()
+        .for_each(|(db, module_group_id)| {
+            db.target_assembly(module_group_id);
+        });
+}
+
+/// A `ModuleObject` is a reference to an object file stored on disk, which
+/// contains the code of a single module of an assembly.
+#[derive(Debug)]
+pub struct ModuleObject {
+    object: ObjectFile,
+}
+
+impl PartialEq for ModuleObject {
+    fn eq(&self, other: &Self) -> bool {
+        self.path().eq(other.path())
+    }
+}
+
+impl Eq for ModuleObject {}
+
+impl ModuleObject {
+    /// Returns the current location of the object file
+    pub fn path(&self) -> &Path {
+        self.object.path()
+    }
+}
+
+/// Builds an object file for the specified module of a module group.
+pub(crate) fn build_module_object(
+    db: &dyn CodeGenDatabase,
+    module_group: ModuleGroupId,
+    module: mun_hir::Module,
+) -> Arc<ModuleObject> {
+    // Setup the code generation context
+    let inkwell_context = Context::create();
+    let code_gen_context = CodeGenContext::new(&inkwell_context, db);
+
+    // Build an assembly that only contains the code of the module
+    let module_partition = db.module_partition();
+    let assembly = AssemblyBuilder::new(&code_gen_context, &module_partition, module_group)
+        .build_module(module)
+        .expect("unable to create assembly");
+
+    let object = assembly
+        .into_object_file()
+        .expect("unable to create object file");
+
+    Arc::new(ModuleObject { object })
+}
+
 /// An `AssemblyIr` is a reference to an IR file stored on disk.
 #[derive(Debug)]
 pub struct AssemblyIr {
@@ -171,3 +295,55 @@ pub(crate) fn build_assembly_ir(
 
     Arc::new(AssemblyIr { file })
 }
+
+/// An `AssemblyAsm` is a reference to a file with the target-specific assembly
+/// of a module stored on disk.
+#[derive(Debug)]
+pub struct AssemblyAsm {
+    file: NamedTempFile,
+}
+
+impl PartialEq for AssemblyAsm {
+    fn eq(&self, other: &Self) -> bool {
+        self.path().eq(other.path())
+    }
+}
+
+impl Eq for AssemblyAsm {}
+
+impl AssemblyAsm {
+    pub const EXTENSION: &'static str = "s";
+
+    /// Returns the current location of the assembly file.
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Copies the assembly file to the specified location
+    pub fn copy_to<P: AsRef<Path>>(&self, destination: P) -> Result<(), std::io::Error> {
+        std::fs::copy(self.path(), destination).map(|_| ())
+    }
+}
+
+/// Builds a target-specific assembly file for the specified module.
+pub(crate) fn build_assembly_asm(
+    db: &dyn CodeGenDatabase,
+    module_group: ModuleGroupId,
+) -> Arc<AssemblyAsm> {
+    // Setup the code generation context
+    let inkwell_context = Context::create();
+    let code_gen_context = CodeGenContext::new(&inkwell_context, db);
+
+    // Build an assembly for the module
+    let assembly = build_assembly(db, &code_gen_context, module_group);
+
+    // Construct a temporary file for the assembly
+    let file = NamedTempFile::new().expect("could not create temp file for assembly file");
+
+    // Write the target-specific assembly to disk
+    assembly
+        .write_asm_to_file(file.path())
+        .expect("could not write to temp file");
+
+    Arc::new(AssemblyAsm { file })
+}
@@ -3,7 +3,8 @@ pub use context::CodeGenContext;
 pub use error::CodeGenerationError;
 use inkwell::{
     module::Module,
-    passes::{PassManager, PassManagerBuilder},
+    passes::{PassBuilderOptions, PassManager, PassManagerBuilder},
+    targets::TargetMachine,
     OptimizationLevel,
 };
 pub(crate) use object_file::ObjectFile;
@@ -15,12 +16,44 @@ mod object_file;
 pub mod symbols;
 
 /// Optimizes the specified LLVM `Module` using the default passes for the given
-/// `OptimizationLevel`.
-fn optimize_module(module: &Module<'_>, optimization_lvl: OptimizationLevel) {
+/// `OptimizationLevel`. If `lto` is enabled, the module is subsequently
+/// optimized as a whole, which enables inlining and constant propagation
+/// across the Mun modules that were linked into it.
+///
+/// The coroutines of resumable functions are always split first, because the
+/// code generator cannot lower them otherwise. This uses the new pass manager,
+/// as the legacy coroutine passes have to be scheduled by the optimization
+/// pipeline to split coroutines at all.
+fn optimize_module(
+    module: &Module<'_>,
+    target_machine: &TargetMachine,
+    optimization_lvl: OptimizationLevel,
+    lto: bool,
+) {
+    module
+        .run_passes(
+            "function(coro-early),cgscc(coro-split),function(coro-cleanup)",
+            target_machine,
+            PassBuilderOptions::create(),
+        )
+        .expect("invalid coroutine passes");
+
     let pass_builder = PassManagerBuilder::create();
     pass_builder.set_optimization_level(optimization_lvl);
+    match optimization_lvl {
+        OptimizationLevel::None => (),
+        OptimizationLevel::Less | OptimizationLevel::Default => {
+            pass_builder.set_inliner_with_threshold(225);
+        }
+        OptimizationLevel::Aggressive => pass_builder.set_inliner_with_threshold(275),
+    }
 
     let module_pass_manager = PassManager::create(());
     pass_builder.populate_module_pass_manager(&module_pass_manager);
+    if lto && optimization_lvl != OptimizationLevel::None {
+        // Symbols are not internalized, because the runtime looks up the
+        // exported functions of the assembly.
+        pass_builder.populate_lto_pass_manager(&module_pass_manager, false, true);
+    }
     module_pass_manager.run_on(module);
 }
@@ -1,10 +1,17 @@
-use inkwell::module::{Linkage, Module};
-use rustc_hash::FxHashSet;
+use std::collections::BTreeSet;
+
+use inkwell::{
+    module::{Linkage, Module},
+    GlobalVisibility,
+};
 
 use crate::{
     assembly::Assembly,
     code_gen::{optimize_module, symbols, CodeGenContext, CodeGenerationError},
-    ir::{file::gen_file_ir, file_group::gen_file_group_ir},
+    ir::{
+        file::{gen_file_ir, FunctionBodies},
+        file_group::gen_file_group_ir,
+    },
     value::{IrTypeContext, IrValueContext},
     ModuleGroupId, ModulePartition,
 };
@@ -36,11 +43,68 @@ impl<'db, 'ink, 'ctx, 't> AssemblyBuilder<'db, 'ink, 'ctx, 't> {
         }
     }
 
-    /// Constructs an object file.
+    /// Constructs an assembly that contains the code of all modules of the
+    /// group, as well as its symbols.
     pub fn build(self) -> Result<Assembly<'db, 'ink, 'ctx>, anyhow::Error> {
+        self.build_with_bodies(FunctionBodies::All)
+    }
+
+    /// Constructs an assembly that only contains the symbols of the group. The
+    /// code of its modules is built separately by [`Self::build_module`], and
+    /// linked with the symbols into a shared object.
+    pub fn build_symbols(self) -> Result<Assembly<'db, 'ink, 'ctx>, anyhow::Error> {
+        self.build_with_bodies(FunctionBodies::None)
+    }
+
+    /// Constructs an assembly that only contains the code of the specified
+    /// module of the group. Functions of other modules are only declared.
+    pub fn build_module(
+        self,
+        module: mun_hir::Module,
+    ) -> Result<Assembly<'db, 'ink, 'ctx>, anyhow::Error> {
         let module_group = &self.module_group_partition[self.module_group_id];
         let group_ir = gen_file_group_ir(self.code_gen, module_group);
-        let file = gen_file_ir(self.code_gen, &group_ir, module_group);
+        let file = gen_file_ir(
+            self.code_gen,
+            &group_ir,
+            module_group,
+            FunctionBodies::Module(module),
+        );
+
+        self.assembly_module
+            .link_in_module(file.llvm_module.clone())
+            .map_err(|e| CodeGenerationError::ModuleLinkerError(e.to_string()))?;
+
+        // The functions are linked with the other object files of the assembly, but
+        // must not be exported from the shared object.
+        for function in self.assembly_module.get_functions() {
+            let function = function.as_global_value();
+            if !function.is_declaration() {
+                function.set_visibility(GlobalVisibility::Hidden);
+            }
+        }
+
+        // Inlining across modules requires all code to be part of the same LLVM
+        // module, so link-time optimization is not applicable.
+        optimize_module(
+            &self.assembly_module,
+            &self.code_gen.target_machine,
+            self.code_gen.optimization_level,
+            false,
+        );
+
+        Ok(Assembly::new(self.code_gen, self.assembly_module))
+    }
+
+    /// Constructs an assembly that contains the symbols of the group and the
+    /// specified function bodies.
+    fn build_with_bodies(
+        self,
+        bodies: FunctionBodies,
+    ) -> Result<Assembly<'db, 'ink, 'ctx>, anyhow::Error> {
+        let module_group = &self.module_group_partition[self.module_group_id];
+        let group_ir = gen_file_group_ir(self.code_gen, module_group);
+        let file = gen_file_ir(self.code_gen, &group_ir, module_group, bodies);
 
         // Clone the LLVM modules so that we can modify it without modifying the cached
         // value.
@@ -52,6 +116,17 @@ impl<'db, 'ink, 'ctx, 't> AssemblyBuilder<'db, 'ink, 'ctx, 't> {
             .link_in_module(file.llvm_module.clone())
             .map_err(|e| CodeGenerationError::ModuleLinkerError(e.to_string()))?;
 
+        // The linker drops declarations that are not referenced. The symbols refer to
+        // the functions of the group, even if their bodies are compiled into other
+        // object files of the assembly, so they have to be declared again.
+        for function in file.llvm_module.get_functions() {
+            let name = function.get_name().to_string_lossy();
+            if self.assembly_module.get_function(&name).is_none() {
+                self.assembly_module
+                    .add_function(&name, function.get_type(), None);
+            }
+        }
+
         if self.code_gen.db.target().options.is_like_windows {
             // Add the useless `_fltused` symbol to indicate that the object file supports
             // floating-point values. This is required for Windows.
@@ -75,16 +150,19 @@ impl<'db, 'ink, 'ctx, 't> AssemblyBuilder<'db, 'ink, 'ctx, 't> {
             module: &self.assembly_module,
         };
 
-        // Build the set of dependencies
+        // Build the set of dependencies. A group that contains an entire package also
+        // contains the children of its modules. A `BTreeSet` is used to guarantee
+        // deterministically ordered output.
         let direct_children = module_group
             .iter()
             .flat_map(|module| module.children(self.code_gen.db))
+            .filter(|&module| !module_group.contains(module))
             .collect();
         let dependencies = group_ir
             .referenced_modules
             .union(&direct_children)
             .filter_map(|&module| self.module_group_partition.group_for_module(module))
-            .collect::<FxHashSet<_>>()
+            .collect::<BTreeSet<_>>()
             .into_iter()
             .map(|group_id| {
                 self.module_group_partition[group_id]
@@ -108,7 +186,12 @@ impl<'db, 'ink, 'ctx, 't> AssemblyBuilder<'db, 'ink, 'ctx, 't> {
         );
 
         // Optimize the assembly module
-        optimize_module(&self.assembly_module, self.code_gen.optimization_level);
+        optimize_module(
+            &self.assembly_module,
+            &self.code_gen.target_machine,
+            self.code_gen.optimization_level,
+            self.code_gen.lto && bodies == FunctionBodies::All,
+        );
 
         // Debug print the IR
         //println!("{}", assembly_module.print_to_string().to_string());
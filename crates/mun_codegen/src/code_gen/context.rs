@@ -2,7 +2,7 @@ use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use inkwell::{context::Context, module::Module, targets::TargetMachine, types::StructType};
 
-use crate::{ir::ty::HirTypeCache, CodeGenDatabase};
+use crate::{db::create_target_machine, ir::ty::HirTypeCache, CodeGenDatabase};
 
 pub struct CodeGenContext<'db, 'ink> {
     /// The current LLVM context
@@ -20,6 +20,17 @@ pub struct CodeGenContext<'db, 'ink> {
     /// The optimization level
     pub optimization_level: inkwell::OptimizationLevel,
 
+    /// Whether integer arithmetic checks for overflow
+    pub overflow_checks: bool,
+
+    /// Whether the assembly is optimized as a whole after its modules are
+    /// linked
+    pub lto: bool,
+
+    /// Whether function bodies report their calls to the profiler of the
+    /// runtime
+    pub profiling: bool,
+
     /// The target to generate code for
     pub target_machine: Rc<TargetMachine>,
 }
@@ -28,12 +39,15 @@ impl<'db, 'ink> CodeGenContext<'db, 'ink> {
     /// Constructs a new `CodeGenContext` from an LLVM context and a
     /// `CodeGenDatabase`.
     pub fn new(context: &'ink Context, db: &'db dyn CodeGenDatabase) -> Self {
-        let target_machine = db.target_machine().0;
+        let target_machine = Rc::new(create_target_machine(db));
         Self {
             context,
             rust_types: RefCell::new(HashMap::default()),
             hir_types: HirTypeCache::new(context, db.upcast(), target_machine.get_target_data()),
             optimization_level: db.optimization_level(),
+            overflow_checks: db.overflow_checks(),
+            lto: db.lto(),
+            profiling: db.profiling(),
             target_machine,
             db: db.upcast(),
         }
@@ -2,10 +2,15 @@ use std::{io::Write, path::Path};
 
 use inkwell::targets::{FileType, TargetMachine};
 use mun_target::spec;
+use parking_lot::{const_mutex, Mutex};
 use tempfile::NamedTempFile;
 
 use crate::{code_gen::CodeGenerationError, linker};
 
+/// The linker cannot be used by multiple threads at the same time.
+static LINKER_LOCK: Mutex<()> = const_mutex(());
+
+#[derive(Debug)]
 pub struct ObjectFile {
     target: spec::Target,
     obj_file: NamedTempFile,
@@ -34,14 +39,33 @@ impl ObjectFile {
         })
     }
 
+    /// Returns the location of the object file.
+    pub fn path(&self) -> &Path {
+        self.obj_file.path()
+    }
+
     /// Links the object file into a shared object.
     pub fn into_shared_object(self, output_path: &Path) -> Result<(), anyhow::Error> {
+        self.link_shared_object(&[], output_path)
+    }
+
+    /// Links the object file and the `others` into a shared object. The objects
+    /// are linked in the specified order, so the output is deterministic.
+    pub fn link_shared_object(
+        &self,
+        others: &[&ObjectFile],
+        output_path: &Path,
+    ) -> Result<(), anyhow::Error> {
         // Construct a linker for the target
         let mut linker = linker::create_with_target(&self.target);
-        linker.add_object(self.obj_file.path())?;
+        linker.add_object(self.path())?;
+        for other in others {
+            linker.add_object(other.path())?;
+        }
 
-        // Link the object
+        // Link the objects
         linker.build_shared_object(output_path)?;
+        let _lock = LINKER_LOCK.lock();
         linker.finalize()?;
 
         Ok(())
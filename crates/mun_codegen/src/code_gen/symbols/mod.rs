@@ -1,23 +1,26 @@
 use std::{collections::HashSet, convert::TryFrom, ffi::CString};
 
-use inkwell::{attributes::Attribute, module::Linkage, types::AnyType};
+use inkwell::{
+    attributes::Attribute,
+    module::Linkage,
+    types::{AnyType, StructType},
+    AddressSpace,
+};
 use ir_type_builder::TypeIdBuilder;
 use itertools::Itertools;
 use mun_abi as abi;
-use mun_hir::{HirDatabase, TyKind};
+use mun_hir::{HasVisibility, HirDatabase, TyKind};
 
 use crate::{
     ir::{
         dispatch_table::{DispatchTable, DispatchableFunction},
         function,
-        ty::{guid_from_struct, HirTypeCache},
+        ty::{guid_from_result, guid_from_struct, HirTypeCache, RESULT_FIELD_NAMES},
         type_table::TypeTable,
         types as ir,
     },
     type_info::HasStaticTypeId,
-    value::{
-        AsValue, CanInternalize, Global, IrValueContext, IterAsIrValue, SizedValueType, Value,
-    },
+    value::{AsValue, CanInternalize, IrValueContext, IterAsIrValue, SizedValueType, Value},
 };
 
 mod ir_type_builder;
@@ -115,39 +118,42 @@ fn get_type_definition_array<'ink>(
     types
         .sorted_by_cached_key(|type_info| match type_info.interned() {
             TyKind::Struct(s) => s.full_name(db),
+            TyKind::Result(..) => hir_types.type_id(type_info).name.clone(),
             _ => unreachable!("unsupported export type"),
         })
-        .map(|type_info| match type_info.interned() {
-            TyKind::Struct(s) => {
-                let inkwell_type = hir_types.get_struct_type(*s);
-                let struct_name = s.full_name(db);
-                ir::TypeDefinition {
-                    name: CString::new(struct_name.clone())
-                        .expect("typename is not a valid CString")
-                        .intern(format!("type_info::<{struct_name}>::name"), context)
-                        .as_value(context),
-                    size_in_bits: context
-                        .type_context
-                        .target_data
-                        .get_bit_size(&inkwell_type)
-                        .try_into()
-                        .expect("could not convert size in bits to smaller size"),
-                    alignment: context
-                        .type_context
-                        .target_data
-                        .get_abi_alignment(&inkwell_type)
-                        .try_into()
-                        .expect("could not convert alignment to smaller size"),
-                    data: ir::TypeDefinitionData::Struct(gen_struct_info(
-                        db,
-                        *s,
-                        context,
-                        hir_types,
-                        ir_type_builder,
-                    )),
-                }
+        .map(|type_info| {
+            let (inkwell_type, struct_name, data) = match type_info.interned() {
+                TyKind::Struct(s) => (
+                    hir_types.get_struct_type(*s),
+                    s.full_name(db),
+                    gen_struct_info(db, *s, context, hir_types, ir_type_builder),
+                ),
+                TyKind::Result(ok_ty, err_ty) => (
+                    hir_types.get_result_type(ok_ty, err_ty),
+                    hir_types.type_id(&type_info).name.clone(),
+                    gen_result_info(db, ok_ty, err_ty, context, hir_types, ir_type_builder),
+                ),
+                _ => unreachable!("unsupported export type"),
+            };
+            ir::TypeDefinition {
+                name: CString::new(struct_name.clone())
+                    .expect("typename is not a valid CString")
+                    .intern(format!("type_info::<{struct_name}>::name"), context)
+                    .as_value(context),
+                size_in_bits: context
+                    .type_context
+                    .target_data
+                    .get_bit_size(&inkwell_type)
+                    .try_into()
+                    .expect("could not convert size in bits to smaller size"),
+                alignment: context
+                    .type_context
+                    .target_data
+                    .get_abi_alignment(&inkwell_type)
+                    .try_into()
+                    .expect("could not convert alignment to smaller size"),
+                data: ir::TypeDefinitionData::Struct(data),
             }
-            _ => unreachable!("unsupported export type"),
         })
         .into_const_private_pointer_or_null("fn.get_info.types", context)
 }
@@ -159,16 +165,76 @@ fn gen_struct_info<'ink>(
     hir_types: &HirTypeCache<'_, 'ink>,
     ir_type_builder: &TypeIdBuilder<'ink, '_, '_, '_>,
 ) -> ir::StructDefinition<'ink> {
-    let struct_ir = hir_types.get_struct_type(hir_struct);
-    let name = hir_struct.full_name(db);
-    let fields = hir_struct.fields(db);
+    let fields: Vec<_> = hir_struct
+        .fields(db)
+        .into_iter()
+        .map(|field| (field.name(db).to_string(), field.ty(db)))
+        .collect();
+
+    gen_struct_definition(
+        &hir_struct.full_name(db),
+        guid_from_struct(db, hir_struct),
+        hir_types.get_struct_type(hir_struct),
+        &fields,
+        hir_struct.data(db.upcast()).memory_kind,
+        hir_struct.data(db.upcast()).repr,
+        hir_types.get_public_struct_value_type(hir_struct).is_some(),
+        context,
+        hir_types,
+        ir_type_builder,
+    )
+}
 
+/// Constructs the struct definition of a `Result` type. A `Result` is
+/// represented as a value struct with the fields `is_err`, `ok`, and `err`.
+fn gen_result_info<'ink>(
+    db: &dyn HirDatabase,
+    ok_ty: &mun_hir::Ty,
+    err_ty: &mun_hir::Ty,
+    context: &IrValueContext<'ink, '_, '_>,
+    hir_types: &HirTypeCache<'_, 'ink>,
+    ir_type_builder: &TypeIdBuilder<'ink, '_, '_, '_>,
+) -> ir::StructDefinition<'ink> {
+    let result_ty = TyKind::Result(ok_ty.clone(), err_ty.clone()).intern();
+    let fields: Vec<_> = RESULT_FIELD_NAMES
+        .iter()
+        .map(ToString::to_string)
+        .zip([TyKind::Bool.intern(), ok_ty.clone(), err_ty.clone()])
+        .collect();
+
+    gen_struct_definition(
+        &hir_types.type_id(&result_ty).name,
+        guid_from_result(db, ok_ty, err_ty),
+        hir_types.get_result_type(ok_ty, err_ty),
+        &fields,
+        abi::StructMemoryKind::Value,
+        abi::StructRepr::Default,
+        false,
+        context,
+        hir_types,
+        ir_type_builder,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn gen_struct_definition<'ink>(
+    name: &str,
+    guid: abi::Guid,
+    struct_ir: StructType<'ink>,
+    fields: &[(String, mun_hir::Ty)],
+    memory_kind: abi::StructMemoryKind,
+    repr: abi::StructRepr,
+    passed_by_value: bool,
+    context: &IrValueContext<'ink, '_, '_>,
+    hir_types: &HirTypeCache<'_, 'ink>,
+    ir_type_builder: &TypeIdBuilder<'ink, '_, '_, '_>,
+) -> ir::StructDefinition<'ink> {
     // Construct an array of field names (or null if there are no fields)
     let field_names = fields
         .iter()
         .enumerate()
-        .map(|(idx, field)| {
-            CString::new(field.name(db).to_string())
+        .map(|(idx, (field_name, _))| {
+            CString::new(field_name.as_str())
                 .expect("field name is not a valid CString")
                 .intern(format!("struct_info::<{name}>::field_names.{idx}"), context)
                 .as_value(context)
@@ -178,8 +244,8 @@ fn gen_struct_info<'ink>(
     // Construct an array of field types (or null if there are no fields)
     let field_types = fields
         .iter()
-        .map(|field| {
-            let field_type_info = hir_types.type_id(&field.ty(db));
+        .map(|(_, field_ty)| {
+            let field_type_info = hir_types.type_id(field_ty);
             ir_type_builder.construct_from_type_id(&field_type_info)
         })
         .into_const_private_pointer_or_null(format!("struct_info::<{name}>::field_types"), context);
@@ -201,7 +267,7 @@ fn gen_struct_info<'ink>(
         );
 
     ir::StructDefinition {
-        guid: guid_from_struct(db, hir_struct),
+        guid,
         field_names,
         field_types,
         field_offsets,
@@ -209,11 +275,75 @@ fn gen_struct_info<'ink>(
             .len()
             .try_into()
             .expect("could not convert num_fields to smaller bit size"),
-        memory_kind: hir_struct.data(db.upcast()).memory_kind,
+        memory_kind,
+        repr,
+        passed_by_value,
     }
 }
 
-/// Construct a global that holds a reference to all functions. e.g.:
+/// Construct a global that holds the default values of the fields of all
+/// exported structs. e.g.:
+/// `MunFieldDefault[] field_defaults = { ... }`
+fn gen_field_defaults<'ink>(
+    db: &dyn HirDatabase,
+    context: &IrValueContext<'ink, '_, '_>,
+    types: impl Iterator<Item = mun_hir::Ty>,
+    hir_types: &HirTypeCache<'_, 'ink>,
+) -> (Value<'ink, *const ir::FieldDefault<'ink>>, u32) {
+    let field_defaults: Vec<_> = types
+        .filter_map(|ty| match ty.interned() {
+            TyKind::Struct(s) => Some(*s),
+            _ => None,
+        })
+        .sorted_by_cached_key(|s| s.full_name(db))
+        .flat_map(|s| {
+            let name = s.full_name(db);
+            s.fields(db)
+                .into_iter()
+                .enumerate()
+                .filter_map(move |(idx, field)| {
+                    let value = hir_types.get_field_default(field)?;
+                    Some((s, name.clone(), idx, value))
+                })
+        })
+        .map(|(s, name, idx, value)| {
+            let global = context.module.add_global(
+                value.get_type(),
+                None,
+                &format!("struct_info::<{name}>::field_defaults.{idx}"),
+            );
+            global.set_initializer(&value);
+            global.set_constant(true);
+            global.set_linkage(Linkage::Private);
+            global.set_unnamed_addr(true);
+
+            ir::FieldDefault {
+                struct_guid: guid_from_struct(db, s),
+                field_index: idx.try_into().expect("too many fields"),
+                value_size: context
+                    .type_context
+                    .target_data
+                    .get_abi_size(&value.get_type())
+                    .try_into()
+                    .expect("could not convert the size of a default value to smaller size"),
+                value: Value::<*const u8>::with_cast(global.as_pointer_value(), context),
+            }
+        })
+        .collect();
+
+    let num_field_defaults = field_defaults
+        .len()
+        .try_into()
+        .expect("too many field defaults");
+    (
+        field_defaults
+            .into_iter()
+            .into_const_private_pointer_or_null("fn.get_info.field_defaults", context),
+        num_field_defaults,
+    )
+}
+
+/// Construct an array that holds a reference to all functions. e.g.:
 /// `MunFunctionDefinition[] definitions = { ... }`
 fn get_function_definition_array<'ink, 'a>(
     db: &dyn HirDatabase,
@@ -221,7 +351,7 @@ fn get_function_definition_array<'ink, 'a>(
     functions: impl Iterator<Item = &'a mun_hir::Function>,
     hir_types: &HirTypeCache<'_, 'ink>,
     ir_type_builder: &TypeIdBuilder<'ink, '_, '_, '_>,
-) -> Global<'ink, [ir::FunctionDefinition<'ink>]> {
+) -> Vec<ir::FunctionDefinition<'ink>> {
     let module = context.module;
     functions
         .sorted_by_cached_key(|f| f.full_name(db))
@@ -229,14 +359,17 @@ fn get_function_definition_array<'ink, 'a>(
             let name = f.name(db).to_string();
 
             // Get the function from the cloned module and modify the linkage of the
-            // function.
+            // function. If the body of the function is compiled into another object file
+            // of the assembly, it is only declared.
             let value = module
                 // If a wrapper function exists, use that (required for struct types)
                 .get_function(&format!("{name}_wrapper"))
                 // Otherwise, use the normal function
                 .or_else(|| module.get_function(&name))
                 .unwrap();
-            value.set_linkage(Linkage::Private);
+            if !value.as_global_value().is_declaration() {
+                value.set_linkage(Linkage::Private);
+            }
 
             // Generate the signature from the function
             let prototype =
@@ -247,10 +380,26 @@ fn get_function_definition_array<'ink, 'a>(
                     value.as_global_value().as_pointer_value(),
                     context,
                 ),
+                kind: if f.is_resumable(db) {
+                    abi::FunctionKind::Resumable
+                } else if f.is_test(db) {
+                    abi::FunctionKind::Test
+                } else if f.is_bench(db) {
+                    abi::FunctionKind::Bench
+                } else {
+                    abi::FunctionKind::Normal
+                },
             }
         })
-        .into_value(context)
-        .into_const_private_global("fn.get_info.functions", context)
+        .collect()
+}
+
+/// Returns true if the host may access the specified function. Functions that
+/// are not `pub` are only exported for other module groups to link against.
+/// Tests and benchmarks are invoked by their harness, regardless of their
+/// visibility.
+fn is_host_accessible(db: &dyn HirDatabase, function: mun_hir::Function) -> bool {
+    function.visibility(db).is_externally_visible() || function.is_test(db) || function.is_bench(db)
 }
 
 /// Generate the type lookup table information. e.g.:
@@ -363,14 +512,32 @@ pub(super) fn gen_reflection_ir<'db, 'ink>(
 ) {
     let ir_type_builder = TypeIdBuilder::new(context);
 
-    let num_functions = function_definitions.len() as u32;
+    // Functions that the host may not access are not part of the module info,
+    // they are only exported for other assemblies to link against.
+    let (public_functions, private_functions): (Vec<_>, Vec<_>) = function_definitions
+        .iter()
+        .partition(|&&f| is_host_accessible(db, f));
+
+    let num_functions = public_functions.len() as u32;
     let functions = get_function_definition_array(
         db,
         context,
-        function_definitions.iter(),
+        public_functions.into_iter(),
         hir_types,
         &ir_type_builder,
-    );
+    )
+    .into_value(context)
+    .into_const_private_global("fn.get_info.functions", context);
+
+    let num_private_functions = private_functions.len() as u32;
+    let private_functions = get_function_definition_array(
+        db,
+        context,
+        private_functions.into_iter(),
+        hir_types,
+        &ir_type_builder,
+    )
+    .into_const_private_pointer_or_null("fn.get_info.private_functions", context);
 
     // Get the TypeTable global
     let num_types = type_definitions.len() as u32;
@@ -399,6 +566,19 @@ pub(super) fn gen_reflection_ir<'db, 'ink>(
 
     let type_lut = gen_type_lut(context, type_table, &ir_type_builder);
 
+    let (field_defaults, num_field_defaults) =
+        gen_field_defaults(db, context, type_definitions.iter().cloned(), hir_types);
+
+    // Capabilities are only advertised if they are used, so older runtimes
+    // can still load assemblies that do not make use of them.
+    let mut capabilities = abi::Capabilities::empty();
+    if num_field_defaults > 0 {
+        capabilities = capabilities.union(abi::Capabilities::FIELD_DEFAULTS);
+    }
+    if num_private_functions > 0 {
+        capabilities = capabilities.union(abi::Capabilities::PRIVATE_FUNCTIONS);
+    }
+
     // Construct the actual `get_info` function
     gen_get_info_fn(
         db,
@@ -406,20 +586,33 @@ pub(super) fn gen_reflection_ir<'db, 'ink>(
         module_info,
         dispatch_table,
         type_lut,
+        capabilities,
+        field_defaults,
+        num_field_defaults,
+        private_functions,
+        num_private_functions,
         optimization_level,
         dependencies,
     );
     gen_set_allocator_handle_fn(context);
     gen_get_version_fn(context);
+    gen_get_compiler_version_fn(context);
+    gen_get_integrity_fn(context);
 }
 
 /// Construct the actual `get_info` function.
+#[allow(clippy::too_many_arguments)]
 fn gen_get_info_fn<'ink>(
     db: &dyn HirDatabase,
     context: &IrValueContext<'ink, '_, '_>,
     module_info: ir::ModuleInfo<'ink>,
     dispatch_table: ir::DispatchTable<'ink>,
     type_lut: ir::TypeLut<'ink>,
+    capabilities: abi::Capabilities,
+    field_defaults: Value<'ink, *const ir::FieldDefault<'ink>>,
+    num_field_defaults: u32,
+    private_functions: Value<'ink, *const ir::FunctionDefinition<'ink>>,
+    num_private_functions: u32,
     optimization_level: inkwell::OptimizationLevel,
     dependencies: Vec<String>,
 ) {
@@ -456,48 +649,13 @@ fn gen_get_info_fn<'ink>(
         get_symbols_fn.add_attribute(inkwell::attributes::AttributeLoc::Param(0), type_attribute);
     }
 
-    let builder = context.context.create_builder();
-    let body_ir = context.context.append_basic_block(get_symbols_fn, "body");
-    builder.position_at_end(body_ir);
-
-    // Get a pointer to the IR value that will hold the return value. Again this
-    // differs depending on the C ABI.
-    let result_ptr = if target.options.is_like_windows {
-        get_symbols_fn
-            .get_nth_param(0)
-            .unwrap()
-            .into_pointer_value()
-    } else {
-        builder.build_alloca(
-            Value::<ir::AssemblyInfo<'ink>>::get_ir_type(context.type_context),
-            "",
-        )
-    };
-
-    // Get access to the structs internals
-    let symbols_addr = builder
-        .build_struct_gep(result_ptr, 1, "symbols")
-        .expect("could not retrieve `symbols` from result struct");
-    let dispatch_table_addr = builder
-        .build_struct_gep(result_ptr, 3, "dispatch_table")
-        .expect("could not retrieve `dispatch_table` from result struct");
-    let type_lut_addr = builder
-        .build_struct_gep(result_ptr, 5, "type_lut")
-        .expect("could not retrieve `type_lut` from result struct");
-    let dependencies_addr = builder
-        .build_struct_gep(result_ptr, 7, "dependencies")
-        .expect("could not retrieve `dependencies` from result struct");
-    let num_dependencies_addr = builder
-        .build_struct_gep(result_ptr, 9, "num_dependencies")
-        .expect("could not retrieve `num_dependencies` from result struct");
-
-    // Assign the struct values one by one.
-    builder.build_store(symbols_addr, module_info.as_value(context).value);
-    builder.build_store(dispatch_table_addr, dispatch_table.as_value(context).value);
-    builder.build_store(type_lut_addr, type_lut.as_value(context).value);
-    builder.build_store(
-        dependencies_addr,
-        dependencies
+    // The assembly info is also exported as a constant, so that it can be read
+    // from the shared library without loading it.
+    let assembly_info = ir::AssemblyInfo {
+        symbols: module_info,
+        dispatch_table,
+        type_lut,
+        dependencies: dependencies
             .iter()
             .enumerate()
             .map(|(idx, name)| {
@@ -506,24 +664,40 @@ fn gen_get_info_fn<'ink>(
                     .intern(format!("dependency{idx}"), context)
                     .as_value(context)
             })
-            .into_const_private_pointer_or_null("dependencies", context)
-            .value,
-    );
-    builder.build_store(
-        num_dependencies_addr,
-        context.context.i32_type().const_int(
-            u32::try_from(dependencies.len())
-                .expect("too many dependencies")
-                .into(),
-            false,
-        ),
+            .into_const_private_pointer_or_null("dependencies", context),
+        num_dependencies: u32::try_from(dependencies.len()).expect("too many dependencies"),
+        capabilities: capabilities.bits(),
+        field_defaults,
+        num_field_defaults,
+        private_functions,
+        num_private_functions,
+    }
+    .as_value(context)
+    .into_global(
+        abi::ASSEMBLY_INFO_SYMBOL_NAME,
+        context,
+        true,
+        Linkage::DLLExport,
+        None,
     );
 
-    // Construct the return statement of the function.
+    let builder = context.context.create_builder();
+    let body_ir = context.context.append_basic_block(get_symbols_fn, "body");
+    builder.position_at_end(body_ir);
+
+    let assembly_info = builder.build_load(assembly_info.value.as_pointer_value(), "");
+
+    // Construct the return statement of the function. Again this differs
+    // depending on the C ABI.
     if target.options.is_like_windows {
+        let result_ptr = get_symbols_fn
+            .get_nth_param(0)
+            .unwrap()
+            .into_pointer_value();
+        builder.build_store(result_ptr, assembly_info);
         builder.build_return(None);
     } else {
-        builder.build_return(Some(&builder.build_load(result_ptr, "")));
+        builder.build_return(Some(&assembly_info));
     }
 
     // Run the function optimizer on the generate function
@@ -558,7 +732,18 @@ fn gen_set_allocator_handle_fn(context: &IrValueContext<'_, '_, '_>) {
 
 /// Generates a `get_version` method that returns the current abi version.
 /// Specifically, it returns the abi version the function was generated in.
+///
+/// The abi version is also exported as a constant, so that it can be read from
+/// the shared library without loading it.
 fn gen_get_version_fn(context: &IrValueContext<'_, '_, '_>) {
+    abi::ABI_VERSION.as_value(context).into_global(
+        abi::ABI_VERSION_SYMBOL_NAME,
+        context,
+        true,
+        Linkage::DLLExport,
+        None,
+    );
+
     let get_version_fn = context.module.add_function(
         abi::GET_VERSION_FN_NAME,
         Value::<fn() -> u32>::get_ir_type(context.type_context),
@@ -571,3 +756,72 @@ fn gen_get_version_fn(context: &IrValueContext<'_, '_, '_>) {
 
     builder.build_return(Some(&abi::ABI_VERSION.as_value(context).value));
 }
+
+/// Generates a `get_compiler_version` method that returns the version of the
+/// compiler that generated the assembly, as a null-terminated string.
+///
+/// The string is also exported as a constant, so that it can be read from the
+/// shared library without loading it.
+fn gen_get_compiler_version_fn(context: &IrValueContext<'_, '_, '_>) {
+    let get_compiler_version_fn = context.module.add_function(
+        abi::GET_COMPILER_VERSION_FN_NAME,
+        Value::<fn() -> *const u8>::get_ir_type(context.type_context),
+        Some(Linkage::DLLExport),
+    );
+
+    let compiler_version = CString::new(env!("CARGO_PKG_VERSION"))
+        .expect("compiler version is not a valid CString")
+        .as_bytes_with_nul()
+        .as_value(context)
+        .into_global(
+            abi::COMPILER_VERSION_SYMBOL_NAME,
+            context,
+            true,
+            Linkage::DLLExport,
+            None,
+        );
+
+    let builder = context.context.create_builder();
+    let body_ir = context
+        .context
+        .append_basic_block(get_compiler_version_fn, "body");
+    builder.position_at_end(body_ir);
+
+    let compiler_version_ptr = builder.build_bitcast(
+        compiler_version.value.as_pointer_value(),
+        context.context.i8_type().ptr_type(AddressSpace::default()),
+        "compiler_version_ptr",
+    );
+    builder.build_return(Some(&compiler_version_ptr));
+}
+
+/// Generates a `get_integrity` method that returns a pointer to the integrity
+/// information of the assembly.
+///
+/// The integrity information is sealed after linking, by overwriting its bytes
+/// in the shared library. It is therefore stored in a global that is neither
+/// merged with other constants, nor optimized away.
+fn gen_get_integrity_fn(context: &IrValueContext<'_, '_, '_>) {
+    let get_integrity_fn = context.module.add_function(
+        abi::GET_INTEGRITY_FN_NAME,
+        Value::<fn() -> *const u8>::get_ir_type(context.type_context),
+        Some(Linkage::DLLExport),
+    );
+
+    let integrity = abi::IntegrityInfo::UNSEALED
+        .to_bytes()
+        .as_slice()
+        .as_value(context)
+        .into_global("integrity", context, true, Linkage::Private, None);
+
+    let builder = context.context.create_builder();
+    let body_ir = context.context.append_basic_block(get_integrity_fn, "body");
+    builder.position_at_end(body_ir);
+
+    let integrity_ptr = builder.build_bitcast(
+        integrity.value.as_pointer_value(),
+        context.context.i8_type().ptr_type(AddressSpace::default()),
+        "integrity_ptr",
+    );
+    builder.build_return(Some(&integrity_ptr));
+}
@@ -1,13 +1,17 @@
-use std::{rc::Rc, sync::Arc};
+use std::sync::{Arc, Once};
 
-use by_address::ByAddress;
-use inkwell::targets::{CodeModel, InitializationConfig, RelocMode, Target, TargetTriple};
+use inkwell::targets::{
+    CodeModel, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
+};
 
-use crate::{AssemblyIr, ModuleGroupId, ModulePartition, TargetAssembly};
+use crate::{
+    AssemblyAsm, AssemblyIr, ModuleGroupId, ModuleObject, ModulePartition, TargetAssembly,
+};
 
 /// The `CodeGenDatabase` enables caching of code generation stages.
 /// Inkwell/LLVM objects are not stored in the cache because they are not
-/// thread-safe.
+/// thread-safe. This allows queries to be executed on multiple threads, see
+/// [`build_target_assemblies`](crate::build_target_assemblies).
 ///
 /// The main purpose of using this Salsa database is to enable caching of
 /// high-level objects based on changes to source files. Although the code
@@ -19,19 +23,46 @@ pub trait CodeGenDatabase: mun_hir::HirDatabase + mun_db::Upcast<dyn mun_hir::Hi
     #[salsa::input]
     fn optimization_level(&self) -> inkwell::OptimizationLevel;
 
+    /// Set whether integer arithmetic checks for overflow. If enabled, an
+    /// overflow is reported to the runtime instead of silently wrapping.
+    #[salsa::input]
+    fn overflow_checks(&self) -> bool;
+
+    /// Set whether each assembly is optimized as a whole after all of its
+    /// modules are linked into it, which allows functions to be inlined across
+    /// modules.
+    #[salsa::input]
+    fn lto(&self) -> bool;
+
+    /// Set whether function bodies call into the profiler of the runtime when
+    /// they are entered and when they return. This is meant for profiling
+    /// builds, the hooks are not emitted otherwise.
+    #[salsa::input]
+    fn profiling(&self) -> bool;
+
     /// Returns the current module partition
     #[salsa::invoke(crate::module_partition::build_partition)]
     fn module_partition(&self) -> Arc<ModulePartition>;
 
-    /// Returns the inkwell target machine that completely describes the code
-    /// generation target. All target-specific information should be
-    /// accessible through this interface.
-    fn target_machine(&self) -> ByAddress<Rc<inkwell::targets::TargetMachine>>;
-
     /// Returns a file containing the IR for the specified module.
     #[salsa::invoke(crate::assembly::build_assembly_ir)]
     fn assembly_ir(&self, module_group: ModuleGroupId) -> Arc<AssemblyIr>;
 
+    /// Returns a file containing the target-specific assembly for the
+    /// specified module.
+    #[salsa::invoke(crate::assembly::build_assembly_asm)]
+    fn assembly_asm(&self, module_group: ModuleGroupId) -> Arc<AssemblyAsm>;
+
+    /// Returns an object file that contains the code of the specified module of
+    /// a module group. Unless link-time optimization is enabled, the object
+    /// files of all modules of a group are linked into its assembly.
+    #[salsa::invoke(crate::assembly::build_module_object)]
+    fn module_object(
+        &self,
+        module_group: ModuleGroupId,
+        module: mun_hir::Module,
+    ) -> Arc<ModuleObject>;
+
     /// Returns a fully linked shared object for the specified module.
     #[salsa::invoke(crate::assembly::build_target_assembly)]
     fn target_assembly(&self, module_group: ModuleGroupId) -> Arc<TargetAssembly>;
@@ -40,13 +71,21 @@ pub trait CodeGenDatabase: mun_hir::HirDatabase + mun_db::Upcast<dyn mun_hir::Hi
 /// Constructs the primary interface to the complete machine description for the
 /// target machine. All target-specific information should be accessible through
 /// this interface.
-fn target_machine(db: &dyn CodeGenDatabase) -> ByAddress<Rc<inkwell::targets::TargetMachine>> {
+///
+/// A target machine cannot be shared between threads, so every code generation
+/// context constructs its own.
+pub(crate) fn create_target_machine(db: &dyn CodeGenDatabase) -> TargetMachine {
+    // The targets are registered globally, which is not thread-safe.
+    static INITIALIZE_TARGETS: Once = Once::new();
+
     // Get the HIR target
     let target = db.target();
 
-    // Initialize the x86 target
-    Target::initialize_x86(&InitializationConfig::default());
-    Target::initialize_aarch64(&InitializationConfig::default());
+    // Initialize the x86 and aarch64 targets
+    INITIALIZE_TARGETS.call_once(|| {
+        Target::initialize_x86(&InitializationConfig::default());
+        Target::initialize_aarch64(&InitializationConfig::default());
+    });
 
     // Retrieve the LLVM target using the specified target.
     let target_triple = TargetTriple::create(&db.target().llvm_target);
@@ -54,7 +93,7 @@ fn target_machine(db: &dyn CodeGenDatabase) -> ByAddress<Rc<inkwell::targets::Ta
         .expect("could not find llvm target tripple for Mun target");
 
     // Construct target machine for machine code generation
-    let target_machine = llvm_target
+    llvm_target
         .create_target_machine(
             &target_triple,
             &target.options.cpu,
@@ -63,7 +102,5 @@ fn target_machine(db: &dyn CodeGenDatabase) -> ByAddress<Rc<inkwell::targets::Ta
             RelocMode::PIC,
             CodeModel::Default,
         )
-        .expect("could not create llvm target machine");
-
-    ByAddress(Rc::new(target_machine))
+        .expect("could not create llvm target machine")
 }
@@ -26,4 +26,31 @@ intrinsics! {
     ///
     /// Note that the elements in the array are left uninitialized.
     pub fn new_array(type_handle: *const ffi::c_void, length: usize, alloc_handle: *mut ffi::c_void) -> *const *mut ffi::c_void;
+
+    /// Reports that the integer operation `op` overflowed. `op` is the discriminant of an
+    /// `abi::OverflowOp`. This function never returns, it unwinds the stack up to the host.
+    pub fn overflow(op: u8) -> ();
+
+    /// Allocates `size` bytes for the frame of a coroutine in the allocator referred to by
+    /// `alloc_handle`. The allocator keeps the objects that are referenced from the frame alive
+    /// until it is freed with `coroutine_free`.
+    pub fn coroutine_alloc(size: usize, alloc_handle: *mut ffi::c_void) -> *mut u8;
+
+    /// Frees the frame of a coroutine that was allocated with `coroutine_alloc` in the allocator
+    /// referred to by `alloc_handle`.
+    pub fn coroutine_free(frame: *mut u8, alloc_handle: *mut ffi::c_void) -> ();
+
+    /// Reports to the profiler of the runtime that the function called `name` was entered. `name`
+    /// is a null-terminated string that is unique to the function within its assembly.
+    pub fn profile_enter(name: *const u8) -> ();
+
+    /// Reports to the profiler of the runtime that the function called `name` returns.
+    pub fn profile_exit(name: *const u8) -> ();
+
+    /// Writes a formatted message through the runtime. `kind` is the discriminant of an
+    /// `abi::LogKind`. Every `{}` in the UTF-8 `format` string of `format_len` bytes is replaced by
+    /// the next of the `arg_count` arguments. The type of each argument is the discriminant of an
+    /// `abi::LogArgKind` in `arg_kinds`, and its value is stored in two consecutive words of
+    /// `args`.
+    pub fn log(kind: u8, format: *const u8, format_len: usize, arg_kinds: *const u8, args: *const u64, arg_count: usize) -> ();
 }
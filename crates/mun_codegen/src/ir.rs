@@ -10,6 +10,8 @@ use inkwell::{
 
 mod array;
 pub mod body;
+mod call_conv;
+pub(crate) mod coroutine;
 #[macro_use]
 pub(crate) mod dispatch_table;
 pub mod file;
@@ -4,24 +4,30 @@ use inkwell::{
     basic_block::BasicBlock,
     builder::Builder,
     context::Context,
+    module::{Linkage, Module},
+    types::BasicTypeEnum,
     values::{
-        AggregateValueEnum, BasicMetadataValueEnum, BasicValueEnum, CallSiteValue, FloatValue,
-        FunctionValue, GlobalValue, IntValue, PointerValue, StructValue,
+        AggregateValueEnum, BasicMetadataValueEnum, BasicValue, BasicValueEnum, CallSiteValue,
+        CallableValue, FloatValue, FunctionValue, GlobalValue, InstructionValue, IntValue,
+        PointerValue, StructValue,
     },
     AddressSpace, FloatPredicate, IntPredicate,
 };
 use mun_abi as abi;
 use mun_hir::{
     ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, HirDatabase, HirDisplay, InferenceResult,
-    Literal, LogicOp, Name, Ordering, Pat, PatId, Path, ResolveBitness, Resolver, Statement,
-    TyKind, UnaryOp, ValueNs,
+    Literal, LogKind, LogicOp, Name, Ordering, Pat, PatId, Path, ResolveBitness, Resolver,
+    ResultVariant, Statement, TyKind, UnaryOp, ValueNs,
 };
 
 use crate::{
-    intrinsics,
+    intrinsics::{self, Intrinsic},
     ir::{
-        dispatch_table::DispatchTable, ty::HirTypeCache, type_table::TypeTable, RuntimeArrayValue,
-        RuntimeReferenceValue,
+        coroutine::{self, Coroutine},
+        dispatch_table::DispatchTable,
+        ty::HirTypeCache,
+        type_table::TypeTable,
+        RuntimeArrayValue, RuntimeReferenceValue,
     },
     module_group::ModuleGroup,
     value::Global,
@@ -59,6 +65,14 @@ pub(crate) struct BodyIrGenerator<'db, 'ink, 't> {
     hir_function: mun_hir::Function,
     external_globals: ExternalGlobals<'ink>,
     module_group: &'t ModuleGroup,
+    module: &'t Module<'ink>,
+    overflow_checks: bool,
+    profiling: bool,
+    /// The name with which the function reports its calls to the profiler of
+    /// the runtime, if profiling is enabled
+    profile_name: Option<PointerValue<'ink>>,
+    /// The coroutine of the function, if it is resumable
+    coroutine: Option<Coroutine<'ink>>,
 }
 
 impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
@@ -73,6 +87,9 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         external_globals: ExternalGlobals<'ink>,
         hir_types: &'t HirTypeCache<'db, 'ink>,
         module_group: &'t ModuleGroup,
+        module: &'t Module<'ink>,
+        overflow_checks: bool,
+        profiling: bool,
     ) -> Self {
         let (hir_function, ir_function) = function;
 
@@ -103,11 +120,20 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             external_globals,
             hir_types,
             module_group,
+            module,
+            overflow_checks,
+            profiling,
+            profile_name: None,
+            coroutine: None,
         }
     }
 
     /// Generates IR for the body of the function.
     pub fn gen_fn_body(&mut self) {
+        if self.hir_function.is_resumable(self.db) {
+            self.gen_coroutine_begin();
+        }
+
         // Iterate over all parameters and their type and store them so we can reference
         // them later in code.
         for (i, (pat, _ty)) in self.body.params().iter().enumerate() {
@@ -136,6 +162,8 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             }
         }
 
+        self.gen_profile_enter();
+
         // Generate code for the body of the function
         let ret_value = self.gen_expr(self.body.body_expr());
 
@@ -151,12 +179,115 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             .ret()
             .clone();
         if !block_ret_type.is_never() {
-            if fn_ret_type.is_empty() {
+            if let Some(coroutine) = &self.coroutine {
+                self.builder
+                    .build_unconditional_branch(coroutine.final_block);
+            } else if fn_ret_type.is_empty() {
+                self.gen_profile_exit();
                 self.builder.build_return(None);
             } else if let Some(value) = ret_value {
+                self.gen_profile_exit();
                 self.builder.build_return(Some(&value));
             }
         }
+
+        if self.coroutine.is_some() {
+            self.gen_coroutine_end();
+        }
+    }
+
+    /// Generates the ramp of the coroutine of a resumable function, which
+    /// allocates the frame of the coroutine and suspends before the body of
+    /// the function is executed.
+    fn gen_coroutine_begin(&mut self) {
+        let (id, frame_size) =
+            Coroutine::gen_id(self.module, &self.builder, self.hir_types.get_usize_type());
+
+        let alloc_fn = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            &intrinsics::coroutine_alloc,
+        );
+        let frame = self
+            .builder
+            .build_call(
+                alloc_fn,
+                &[frame_size.into(), self.get_allocator_handle_ptr().into()],
+                "frame",
+            )
+            .try_as_basic_value()
+            .left()
+            .expect("coroutine_alloc returns a pointer")
+            .into_pointer_value();
+
+        // The context of the coroutine is passed as the last parameter
+        let context_ptr = self
+            .fn_value
+            .get_last_param()
+            .expect("resumable functions take a coroutine context")
+            .into_pointer_value();
+
+        self.coroutine = Some(Coroutine::gen_begin(
+            self.module,
+            &self.builder,
+            self.fn_value,
+            id,
+            frame,
+            context_ptr,
+        ));
+    }
+
+    /// Generates the end of the coroutine of a resumable function, which frees
+    /// the frame of the coroutine when it is destroyed.
+    fn gen_coroutine_end(&self) {
+        let coroutine = self
+            .coroutine
+            .as_ref()
+            .expect("only resumable functions have a coroutine");
+        coroutine.gen_end(self.module, &self.builder, self.fn_value, |frame| {
+            let free_fn = self.dispatch_table.gen_intrinsic_lookup(
+                self.external_globals.dispatch_table,
+                &self.builder,
+                &intrinsics::coroutine_free,
+            );
+            self.builder.build_call(
+                free_fn,
+                &[frame.into(), self.get_allocator_handle_ptr().into()],
+                "",
+            );
+        });
+    }
+
+    /// Reports to the profiler of the runtime that the function is entered,
+    /// if profiling is enabled.
+    fn gen_profile_enter(&mut self) {
+        if !self.profiling {
+            return;
+        }
+
+        let name = self
+            .builder
+            .build_global_string_ptr(&self.hir_function.full_name(self.db), "profile_name")
+            .as_pointer_value();
+        self.profile_name = Some(name);
+        self.gen_profile_hook(&intrinsics::profile_enter, name);
+    }
+
+    /// Reports to the profiler of the runtime that the function returns, if
+    /// profiling is enabled. Must be called before every return.
+    fn gen_profile_exit(&self) {
+        if let Some(name) = self.profile_name {
+            self.gen_profile_hook(&intrinsics::profile_exit, name);
+        }
+    }
+
+    fn gen_profile_hook(&self, intrinsic: &impl Intrinsic, name: PointerValue<'ink>) {
+        let hook = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            intrinsic,
+        );
+        self.builder.build_call(hook, &[name.into()], "");
     }
 
     pub fn gen_fn_wrapper(&mut self) {
@@ -168,11 +299,15 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             .map(|(idx, ty)| {
                 let param = self.fn_value.get_nth_param(idx as u32).unwrap();
                 if let Some(s) = ty.as_struct() {
-                    if s.data(self.db.upcast()).memory_kind == abi::StructMemoryKind::Value {
+                    if self.hir_types.get_public_struct_value_type(s).is_some() {
+                        self.gen_struct_from_coerced(s, param)
+                    } else if s.data(self.db.upcast()).memory_kind == abi::StructMemoryKind::Value {
                         deref_heap_value(&self.builder, param)
                     } else {
                         param
                     }
+                } else if ty.as_result().is_some() {
+                    deref_heap_value(&self.builder, param)
                 } else {
                     param
                 }
@@ -199,13 +334,19 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                 self.builder.build_return(None);
             } else if let Some(value) = ret_value {
                 let ret_value = if let Some(hir_struct) = fn_ret_type.as_struct() {
-                    if hir_struct.data(self.db.upcast()).memory_kind
+                    if let Some(coerced_ty) =
+                        self.hir_types.get_public_struct_value_type(hir_struct)
+                    {
+                        self.gen_coerced_from_struct(value.into_struct_value(), coerced_ty)
+                    } else if hir_struct.data(self.db.upcast()).memory_kind
                         == mun_hir::StructMemoryKind::Value
                     {
-                        self.gen_struct_alloc_on_heap(hir_struct, value.into_struct_value())
+                        self.gen_struct_alloc_on_heap(&fn_ret_type, value.into_struct_value())
                     } else {
                         value
                     }
+                } else if fn_ret_type.as_result().is_some() {
+                    self.gen_struct_alloc_on_heap(&fn_ret_type, value.into_struct_value())
                 } else {
                     value
                 };
@@ -214,6 +355,48 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         }
     }
 
+    /// Reinterprets a struct that was passed by value to a public function,
+    /// coerced to the registers in which the target passes it, as the struct
+    /// type.
+    fn gen_struct_from_coerced(
+        &self,
+        hir_struct: mun_hir::Struct,
+        param: BasicValueEnum<'ink>,
+    ) -> BasicValueEnum<'ink> {
+        let struct_ir_ty = self.hir_types.get_struct_type(hir_struct);
+
+        // The coerced type is at least as large and as aligned as the struct
+        let coerced_ptr = self
+            .new_alloca_builder()
+            .build_alloca(param.get_type(), "coerced");
+        self.builder.build_store(coerced_ptr, param);
+        let struct_ptr = self.builder.build_pointer_cast(
+            coerced_ptr,
+            struct_ir_ty.ptr_type(AddressSpace::default()),
+            "coerced_struct",
+        );
+        self.builder.build_load(struct_ptr, "by_value")
+    }
+
+    /// Coerces a struct that is returned by value from a public function to
+    /// the registers in which the target returns it.
+    fn gen_coerced_from_struct(
+        &self,
+        value: StructValue<'ink>,
+        coerced_ty: BasicTypeEnum<'ink>,
+    ) -> BasicValueEnum<'ink> {
+        let coerced_ptr = self
+            .new_alloca_builder()
+            .build_alloca(coerced_ty, "coerced");
+        let struct_ptr = self.builder.build_pointer_cast(
+            coerced_ptr,
+            value.get_type().ptr_type(AddressSpace::default()),
+            "coerced_struct",
+        );
+        self.builder.build_store(struct_ptr, value);
+        self.builder.build_load(coerced_ptr, "coerced")
+    }
+
     /// Generates IR for the specified expression. Dependending on the type of
     /// expression an IR value is returned.
     fn gen_expr(&mut self, expr: ExprId) -> Option<inkwell::values::BasicValueEnum<'ink>> {
@@ -247,6 +430,10 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                             .map(|expr| self.gen_expr(*expr).expect("expected a value").into())
                             .collect();
 
+                        if def.is_resumable(self.db) {
+                            return Some(self.gen_resumable_call(def, args));
+                        }
+
                         self.gen_call(def, &args)
                             .try_as_basic_value()
                             .left()
@@ -283,6 +470,17 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             } => self.gen_field(expr, *receiver_expr, name),
             Expr::Array(exprs) => self.gen_array(expr, exprs).map(Into::into),
             Expr::Index { base, index } => self.gen_index(expr, *base, *index),
+            Expr::ArrayLength { array } => self.gen_array_length(*array),
+            Expr::Cast {
+                expr: value_expr, ..
+            } => self.gen_cast(expr, *value_expr),
+            Expr::ResultCtor {
+                variant,
+                expr: value_expr,
+            } => self.gen_result_ctor(expr, *variant, *value_expr),
+            Expr::Try { expr: value_expr } => self.gen_try(*value_expr),
+            Expr::Log { kind, format, args } => self.gen_log(*kind, format, args),
+            Expr::Yield => Some(self.gen_yield()),
             Expr::Missing => unimplemented!("unimplemented expr type {:?}", &body[expr]),
         }
     }
@@ -373,17 +571,23 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
             mun_hir::StructMemoryKind::Value => struct_lit.into(),
             mun_hir::StructMemoryKind::Gc => {
                 // TODO: Root memory in GC
-                self.gen_struct_alloc_on_heap(hir_struct, struct_lit)
+                self.gen_struct_alloc_on_heap(&hir_struct.ty(self.db), struct_lit)
             }
         }
     }
 
+    /// Copies a struct value of the specified type, either a value struct or a
+    /// `Result`, to a newly allocated object on the heap.
     fn gen_struct_alloc_on_heap(
         &mut self,
-        hir_struct: mun_hir::Struct,
+        ty: &mun_hir::Ty,
         struct_lit: StructValue<'_>,
     ) -> BasicValueEnum<'ink> {
-        let struct_ir_ty = self.hir_types.get_struct_type(hir_struct);
+        let struct_ir_ty = match ty.interned() {
+            TyKind::Struct(hir_struct) => self.hir_types.get_struct_type(*hir_struct),
+            TyKind::Result(ok_ty, err_ty) => self.hir_types.get_result_type(ok_ty, err_ty),
+            _ => unreachable!("only structs and results can be allocated on the heap"),
+        };
         let new_fn_ptr = self.dispatch_table.gen_intrinsic_lookup(
             self.external_globals.dispatch_table,
             &self.builder,
@@ -393,7 +597,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         let type_info_ptr = self.type_table.gen_type_info_lookup(
             self.context,
             &self.builder,
-            &self.hir_types.type_id(&hir_struct.ty(self.db)),
+            &self.hir_types.type_id(ty),
             self.external_globals.type_table,
         );
 
@@ -428,7 +632,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                 struct_ir_ty
                     .ptr_type(AddressSpace::default())
                     .ptr_type(AddressSpace::default()),
-                &format!("ref<{}>", hir_struct.name(self.db)),
+                &format!("ref<{}>", ty.display(self.db)),
             )
             .into_pointer_value();
 
@@ -451,9 +655,27 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
     ) -> BasicValueEnum<'ink> {
         let struct_ty = self.infer[type_expr].clone();
         let hir_struct = struct_ty.as_struct().unwrap(); // Can only really get here if the type is a struct
-        let fields: Vec<BasicValueEnum<'ink>> = fields
+
+        // The fields are evaluated in the order in which they are written, but stored
+        // in the order in which they are declared. Omitted fields are
+        // initialized with their default value.
+        let mut values: HashMap<Name, BasicValueEnum<'ink>> = fields
             .iter()
-            .map(|field| self.gen_expr(field.expr).expect("expected a field value"))
+            .map(|field| {
+                let value = self.gen_expr(field.expr).expect("expected a field value");
+                (field.name.clone(), value)
+            })
+            .collect();
+        let fields: Vec<BasicValueEnum<'ink>> = hir_struct
+            .fields(self.db)
+            .into_iter()
+            .map(|field| {
+                values.remove(&field.name(self.db)).unwrap_or_else(|| {
+                    self.hir_types
+                        .get_field_default(field)
+                        .expect("an omitted field must have a default value")
+                })
+            })
             .collect();
 
         self.gen_struct_alloc(hir_struct, fields)
@@ -471,6 +693,94 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         self.gen_struct_alloc(hir_struct, args)
     }
 
+    /// Generates IR for a `Result` constructor, e.g. `Ok(1)` or `Err(false)`
+    fn gen_result_ctor(
+        &mut self,
+        tgt_expr: ExprId,
+        variant: ResultVariant,
+        value_expr: ExprId,
+    ) -> Option<BasicValueEnum<'ink>> {
+        let value = self.gen_expr(value_expr)?;
+        let (ok_ty, err_ty) = self.infer[tgt_expr]
+            .as_result()
+            .expect("expected a result type");
+        let result_ir_ty = self.hir_types.get_result_type(ok_ty, err_ty);
+
+        // Only the field of the variant is initialized, the other field remains
+        // undefined
+        let (is_err, field_idx) = match variant {
+            ResultVariant::Ok => (false, 1),
+            ResultVariant::Err => (true, 2),
+        };
+        let is_err = self
+            .hir_types
+            .get_bool_type()
+            .const_int(u64::from(is_err), false);
+        let result = self
+            .builder
+            .build_insert_value(result_ir_ty.get_undef(), is_err, 0, "is_err")
+            .expect("Failed to initialize result discriminant.");
+        let result = self
+            .builder
+            .build_insert_value(result, value, field_idx, "init")
+            .expect("Failed to initialize result value.");
+        Some(result.into_struct_value().into())
+    }
+
+    /// Generates IR for a `?` expression. If the `Result` is an `Err`, the
+    /// error is returned from the function. Otherwise, evaluates to the `Ok`
+    /// value.
+    fn gen_try(&mut self, value_expr: ExprId) -> Option<BasicValueEnum<'ink>> {
+        let result = self.gen_expr(value_expr)?.into_struct_value();
+        let is_err = self
+            .builder
+            .build_extract_value(result, 0, "is_err")
+            .expect("could not extract result discriminant")
+            .into_int_value();
+
+        let err_block = self.context.append_basic_block(self.fn_value, "try_err");
+        let ok_block = self.context.append_basic_block(self.fn_value, "try_ok");
+        self.builder
+            .build_conditional_branch(is_err, err_block, ok_block);
+
+        // Propagate the error by returning it from the function
+        self.builder.position_at_end(err_block);
+        let fn_ret_type = self
+            .hir_function
+            .ty(self.db)
+            .callable_sig(self.db)
+            .unwrap()
+            .ret()
+            .clone();
+        let (ret_ok_ty, ret_err_ty) = fn_ret_type
+            .as_result()
+            .expect("the `?` operator can only be used in functions that return a result");
+        let err = self
+            .builder
+            .build_extract_value(result, 2, "err")
+            .expect("could not extract result error");
+        let ret_ir_ty = self.hir_types.get_result_type(ret_ok_ty, ret_err_ty);
+        let ret_value = self
+            .builder
+            .build_insert_value(ret_ir_ty.get_undef(), is_err, 0, "is_err")
+            .expect("Failed to initialize result discriminant.");
+        let ret_value = self
+            .builder
+            .build_insert_value(ret_value, err, 2, "init")
+            .expect("Failed to initialize result error.");
+        self.gen_profile_exit();
+        self.builder
+            .build_return(Some(&ret_value.into_struct_value()));
+
+        // Continue with the `Ok` value
+        self.builder.position_at_end(ok_block);
+        Some(
+            self.builder
+                .build_extract_value(result, 1, "ok")
+                .expect("could not extract result value"),
+        )
+    }
+
     /// Generates IR for a unit struct literal, e.g `Foo`
     fn gen_unit_struct_lit(&mut self, type_expr: ExprId) -> BasicValueEnum<'ink> {
         let struct_ty = self.infer[type_expr].clone();
@@ -672,6 +982,144 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         }
     }
 
+    /// Generates IR for an `as` expression that converts the value of `expr` to
+    /// the type of `tgt_expr`.
+    ///
+    /// Integers are truncated or sign- or zero-extended depending on the
+    /// signedness of the source type. Floats are converted to integers by
+    /// rounding towards zero, saturating on overflow and converting NaN to
+    /// zero.
+    fn gen_cast(&mut self, tgt_expr: ExprId, expr: ExprId) -> Option<BasicValueEnum<'ink>> {
+        let value = self.gen_expr(expr)?;
+        let from_ty = self.infer[expr].clone();
+        let to_ty = self.infer[tgt_expr].clone();
+        let value = match (from_ty.interned(), to_ty.interned()) {
+            (&TyKind::Int(from), &TyKind::Int(to)) => {
+                let value = value.into_int_value();
+                let from_width = self.hir_types.get_int_type(from).get_bit_width();
+                let to_int_ty = self.hir_types.get_int_type(to);
+                match from_width.cmp(&to_int_ty.get_bit_width()) {
+                    std::cmp::Ordering::Greater => {
+                        self.builder.build_int_truncate(value, to_int_ty, "cast")
+                    }
+                    std::cmp::Ordering::Less if from.signedness.is_signed() => {
+                        self.builder.build_int_s_extend(value, to_int_ty, "cast")
+                    }
+                    std::cmp::Ordering::Less => {
+                        self.builder.build_int_z_extend(value, to_int_ty, "cast")
+                    }
+                    std::cmp::Ordering::Equal => value,
+                }
+                .into()
+            }
+            (&TyKind::Int(from), &TyKind::Float(to)) => {
+                let value = value.into_int_value();
+                let to_float_ty = self.hir_types.get_float_type(to);
+                if from.signedness.is_signed() {
+                    self.builder
+                        .build_signed_int_to_float(value, to_float_ty, "cast")
+                } else {
+                    self.builder
+                        .build_unsigned_int_to_float(value, to_float_ty, "cast")
+                }
+                .into()
+            }
+            (&TyKind::Float(from), &TyKind::Float(to)) => {
+                let value = value.into_float_value();
+                let to_float_ty = self.hir_types.get_float_type(to);
+                match (from.bitness, to.bitness) {
+                    (mun_hir::FloatBitness::X32, mun_hir::FloatBitness::X64) => {
+                        self.builder.build_float_ext(value, to_float_ty, "cast")
+                    }
+                    (mun_hir::FloatBitness::X64, mun_hir::FloatBitness::X32) => {
+                        self.builder.build_float_trunc(value, to_float_ty, "cast")
+                    }
+                    _ => value,
+                }
+                .into()
+            }
+            (&TyKind::Float(_), &TyKind::Int(to)) => self
+                .gen_saturating_float_to_int(value.into_float_value(), to)
+                .into(),
+            (TyKind::Bool, &TyKind::Int(to)) => {
+                let to_int_ty = self.hir_types.get_int_type(to);
+                self.builder
+                    .build_int_z_extend(value.into_int_value(), to_int_ty, "cast")
+                    .into()
+            }
+            // Casting a value to its own type is a no-op
+            _ => value,
+        };
+        Some(value)
+    }
+
+    /// Generates IR to convert a floating point value to an integer by rounding
+    /// towards zero. Values that do not fit in the integer saturate to its
+    /// minimum or maximum value and NaN is converted to zero.
+    fn gen_saturating_float_to_int(
+        &mut self,
+        value: FloatValue<'ink>,
+        to: mun_hir::IntTy,
+    ) -> IntValue<'ink> {
+        let int_ty = self.hir_types.get_int_type(to);
+        let float_ty = value.get_type();
+        let bit_width = int_ty.get_bit_width();
+
+        // The bounds are powers of two, which are exactly representable as floats
+        let is_signed = to.signedness.is_signed();
+        let (int_min, int_max, float_min, float_max) = if is_signed {
+            let max_bound = 2f64.powi(bit_width as i32 - 1);
+            let int_max = self.builder.build_right_shift(
+                int_ty.const_all_ones(),
+                int_ty.const_int(1, false),
+                false,
+                "int_max",
+            );
+            let int_min = self.builder.build_not(int_max, "int_min");
+            (int_min, int_max, -max_bound, max_bound)
+        } else {
+            let max_bound = 2f64.powi(bit_width as i32);
+            (int_ty.const_zero(), int_ty.const_all_ones(), 0.0, max_bound)
+        };
+
+        // The result of the conversion is poison if the value is out of range, in
+        // which case it is not selected.
+        let converted = if is_signed {
+            self.builder
+                .build_float_to_signed_int(value, int_ty, "cast")
+        } else {
+            self.builder
+                .build_float_to_unsigned_int(value, int_ty, "cast")
+        };
+        let below_min = self.builder.build_float_compare(
+            FloatPredicate::OLT,
+            value,
+            float_ty.const_float(float_min),
+            "below_min",
+        );
+        let above_max = self.builder.build_float_compare(
+            FloatPredicate::OGE,
+            value,
+            float_ty.const_float(float_max),
+            "above_max",
+        );
+        let is_nan = self
+            .builder
+            .build_float_compare(FloatPredicate::UNO, value, value, "is_nan");
+
+        let result = self
+            .builder
+            .build_select(below_min, int_min, converted, "saturated")
+            .into_int_value();
+        let result = self
+            .builder
+            .build_select(above_max, int_max, result, "saturated")
+            .into_int_value();
+        self.builder
+            .build_select(is_nan, int_ty.const_zero(), result, "saturated")
+            .into_int_value()
+    }
+
     /// Generates IR to calculate a unary operation on a floating point value.
     fn gen_unary_op_float(&mut self, expr: ExprId, op: UnaryOp) -> Option<BasicValueEnum<'ink>> {
         let value: FloatValue<'ink> = self
@@ -700,6 +1148,19 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         match op {
             UnaryOp::Neg => {
                 if signedness == mun_hir::Signedness::Signed {
+                    if self.overflow_checks {
+                        let zero = value.get_type().const_zero();
+                        return Some(
+                            self.gen_checked_int_op(
+                                zero,
+                                value,
+                                "sub",
+                                signedness,
+                                abi::OverflowOp::Negate,
+                            )
+                            .into(),
+                        );
+                    }
                     Some(self.builder.build_int_neg(value, "neg").into())
                 } else {
                     unimplemented!("Operator {:?} is not implemented for unsigned integer", op)
@@ -746,7 +1207,8 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                     None => rhs,
                 };
                 let place = self.gen_place_expr(lhs_expr)?;
-                self.builder.build_store(place, rhs);
+                let store = self.builder.build_store(place, rhs);
+                self.align_place_access(lhs_expr, store);
                 Some(self.gen_empty())
             }
             BinaryOp::LogicOp(op) => Some(self.gen_logic_bin_op(lhs, rhs, op).into()),
@@ -810,7 +1272,8 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                     None => rhs,
                 };
                 let place = self.gen_place_expr(lhs_expr)?;
-                self.builder.build_store(place, rhs);
+                let store = self.builder.build_store(place, rhs);
+                self.align_place_access(lhs_expr, store);
                 Some(self.gen_empty())
             }
             BinaryOp::LogicOp(_) => {
@@ -848,7 +1311,8 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                     None => rhs,
                 };
                 let place = self.gen_place_expr(lhs_expr)?;
-                self.builder.build_store(place, rhs);
+                let store = self.builder.build_store(place, rhs);
+                self.align_place_access(lhs_expr, store);
                 Some(self.gen_empty())
             }
             BinaryOp::LogicOp(_) => {
@@ -879,7 +1343,8 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                     None => rhs,
                 };
                 let place = self.gen_place_expr(lhs_expr)?;
-                self.builder.build_store(place, rhs);
+                let store = self.builder.build_store(place, rhs);
+                self.align_place_access(lhs_expr, store);
                 Some(self.gen_empty())
             }
             _ => unimplemented!("Operator {:?} is not implemented for struct", op),
@@ -908,7 +1373,8 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                     None => rhs,
                 };
                 let place = self.gen_place_expr(lhs_expr)?;
-                self.builder.build_store(place, rhs);
+                let store = self.builder.build_store(place, rhs);
+                self.align_place_access(lhs_expr, store);
                 Some(self.gen_empty())
             }
             _ => unimplemented!("Operator {:?} is not implemented for struct", op),
@@ -995,6 +1461,15 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         signedness: mun_hir::Signedness,
     ) -> IntValue<'ink> {
         match op {
+            ArithOp::Add if self.overflow_checks => {
+                self.gen_checked_int_op(lhs, rhs, "add", signedness, abi::OverflowOp::Add)
+            }
+            ArithOp::Subtract if self.overflow_checks => {
+                self.gen_checked_int_op(lhs, rhs, "sub", signedness, abi::OverflowOp::Subtract)
+            }
+            ArithOp::Multiply if self.overflow_checks => {
+                self.gen_checked_int_op(lhs, rhs, "mul", signedness, abi::OverflowOp::Multiply)
+            }
             ArithOp::Add => self.builder.build_int_add(lhs, rhs, "add"),
             ArithOp::Subtract => self.builder.build_int_sub(lhs, rhs, "sub"),
             ArithOp::Divide => match signedness {
@@ -1021,6 +1496,77 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         }
     }
 
+    /// Generates IR to calculate an integer operation with one of LLVM's
+    /// `llvm.*.with.overflow` intrinsics. If the operation overflows, the
+    /// `overflow` intrinsic is called to report `op` to the runtime, which
+    /// never returns.
+    fn gen_checked_int_op(
+        &mut self,
+        lhs: IntValue<'ink>,
+        rhs: IntValue<'ink>,
+        name: &str,
+        signedness: mun_hir::Signedness,
+        op: abi::OverflowOp,
+    ) -> IntValue<'ink> {
+        let int_ty = lhs.get_type();
+        let intrinsic_name = format!(
+            "llvm.{}{name}.with.overflow.i{}",
+            if signedness.is_signed() { 's' } else { 'u' },
+            int_ty.get_bit_width()
+        );
+        let intrinsic = self
+            .module
+            .get_function(&intrinsic_name)
+            .unwrap_or_else(|| {
+                let ret_ty = self
+                    .context
+                    .struct_type(&[int_ty.into(), self.context.bool_type().into()], false);
+                self.module.add_function(
+                    &intrinsic_name,
+                    ret_ty.fn_type(&[int_ty.into(), int_ty.into()], false),
+                    None,
+                )
+            });
+
+        let result = self
+            .builder
+            .build_call(intrinsic, &[lhs.into(), rhs.into()], name)
+            .try_as_basic_value()
+            .left()
+            .expect("overflow intrinsics return a value")
+            .into_struct_value();
+        let value = self
+            .builder
+            .build_extract_value(result, 0, name)
+            .expect("overflow intrinsics return the result of the operation")
+            .into_int_value();
+        let overflowed = self
+            .builder
+            .build_extract_value(result, 1, "overflowed")
+            .expect("overflow intrinsics return whether the operation overflowed")
+            .into_int_value();
+
+        let overflow_block = self.context.append_basic_block(self.fn_value, "overflow");
+        let continue_block = self
+            .context
+            .append_basic_block(self.fn_value, "no_overflow");
+        self.builder
+            .build_conditional_branch(overflowed, overflow_block, continue_block);
+
+        self.builder.position_at_end(overflow_block);
+        let overflow_fn = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            &intrinsics::overflow,
+        );
+        let op = self.context.i8_type().const_int(op as u64, false);
+        self.builder.build_call(overflow_fn, &[op.into()], "");
+        self.builder.build_unreachable();
+
+        self.builder.position_at_end(continue_block);
+        value
+    }
+
     fn gen_arith_bin_op_float(
         &mut self,
         lhs: FloatValue<'ink>,
@@ -1087,6 +1633,34 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         }
     }
 
+    /// Returns true if the place expression `expr` refers to a field of a
+    /// packed struct, or to a field nested inside one. The address of such
+    /// a field is not necessarily aligned.
+    fn is_unaligned_place(&self, expr: ExprId) -> bool {
+        let body = self.body.clone();
+        match &body[expr] {
+            Expr::Field { expr, .. } => {
+                self.infer[*expr]
+                    .as_struct()
+                    .is_some_and(|s| s.data(self.db.upcast()).repr == abi::StructRepr::Packed)
+                    || self.is_unaligned_place(*expr)
+            }
+            _ => false,
+        }
+    }
+
+    /// Lowers the alignment of a load or store `instruction` that accesses the
+    /// place expression `expr` to a single byte, if the place is not
+    /// necessarily aligned. By default, LLVM assumes the natural alignment of
+    /// the accessed type.
+    fn align_place_access(&self, expr: ExprId, instruction: InstructionValue<'ink>) {
+        if self.is_unaligned_place(expr) {
+            instruction
+                .set_alignment(1)
+                .expect("expected a load or store instruction");
+        }
+    }
+
     /// Returns true if a call to the specified function should be looked up in
     /// the dispatch table; if false is returned the function should be
     /// called directly.
@@ -1211,7 +1785,11 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         let ret_value = ret_expr.and_then(|expr| self.gen_expr(expr));
 
         // Construct a return statement from the returned value of the body
-        if let Some(value) = ret_value {
+        self.gen_profile_exit();
+        if let Some(coroutine) = &self.coroutine {
+            self.builder
+                .build_unconditional_branch(coroutine.final_block);
+        } else if let Some(value) = ret_value {
             self.builder.build_return(Some(&value));
         } else {
             self.builder.build_return(None);
@@ -1220,6 +1798,139 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         None
     }
 
+    /// Generates IR for a `yield` expression, which suspends the coroutine of
+    /// the function and evaluates to the value with which it is resumed.
+    fn gen_yield(&mut self) -> BasicValueEnum<'ink> {
+        let coroutine = self
+            .coroutine
+            .as_ref()
+            .expect("`yield` can only be used in resumable functions");
+        let resume_block = self.context.append_basic_block(self.fn_value, "resume");
+        coroutine.gen_suspend(self.module, &self.builder, resume_block);
+
+        self.builder.position_at_end(resume_block);
+        self.gen_resume_value()
+    }
+
+    /// Loads the value with which the coroutine of the function was last
+    /// resumed.
+    fn gen_resume_value(&self) -> BasicValueEnum<'ink> {
+        let coroutine = self
+            .coroutine
+            .as_ref()
+            .expect("only resumable functions have a coroutine");
+        let resume_value_ptr = self
+            .builder
+            .build_struct_gep(
+                coroutine.context_ptr,
+                coroutine::RESUME_VALUE_FIELD,
+                "resume_value_ptr",
+            )
+            .expect("`CoroutineContext` has a resume value");
+        self.builder.build_load(resume_value_ptr, "resume_value")
+    }
+
+    /// Generates IR for a call to a resumable function from another resumable
+    /// function. The call starts the coroutine of the callee, which is resumed
+    /// with the same value whenever the caller is resumed, until it returns.
+    fn gen_resumable_call(
+        &mut self,
+        function: mun_hir::Function,
+        mut args: Vec<BasicMetadataValueEnum<'ink>>,
+    ) -> BasicValueEnum<'ink> {
+        let callee_context = self
+            .new_alloca_builder()
+            .build_alloca(coroutine::context_type(self.context), "callee_context");
+        args.push(callee_context.into());
+        self.gen_call(function, &args);
+
+        let resume_block = self.context.append_basic_block(self.fn_value, "await");
+        let suspend_block = self
+            .context
+            .append_basic_block(self.fn_value, "await_suspend");
+        let destroy_block = self
+            .context
+            .append_basic_block(self.fn_value, "await_destroy");
+        let done_block = self.context.append_basic_block(self.fn_value, "await_done");
+        self.builder.build_unconditional_branch(resume_block);
+
+        // Resume the callee with the value with which the caller was resumed
+        self.builder.position_at_end(resume_block);
+        let resume_value = self.gen_resume_value();
+        let callee_resume_value_ptr = self
+            .builder
+            .build_struct_gep(
+                callee_context,
+                coroutine::RESUME_VALUE_FIELD,
+                "callee_resume_value_ptr",
+            )
+            .expect("`CoroutineContext` has a resume value");
+        self.builder
+            .build_store(callee_resume_value_ptr, resume_value);
+        let is_done = self.gen_coroutine_context_call(
+            callee_context,
+            coroutine::RESUME_FIELD,
+            "callee_resume",
+        );
+        let is_done = self.builder.build_int_compare(
+            IntPredicate::NE,
+            is_done
+                .expect("resuming a coroutine returns whether it is done")
+                .into_int_value(),
+            self.context.i8_type().const_zero(),
+            "is_done",
+        );
+        self.builder
+            .build_conditional_branch(is_done, done_block, suspend_block);
+
+        // Until the callee returns, the caller suspends whenever the callee does
+        let coroutine = self
+            .coroutine
+            .as_ref()
+            .expect("resumable functions can only be called from resumable functions");
+        let cleanup_block = coroutine.cleanup_block;
+        self.builder.position_at_end(suspend_block);
+        coroutine.gen_suspend_or(self.module, &self.builder, resume_block, destroy_block);
+
+        // Destroying the caller also destroys the callee
+        self.builder.position_at_end(destroy_block);
+        self.gen_coroutine_context_call(callee_context, coroutine::DESTROY_FIELD, "callee_destroy");
+        self.builder.build_unconditional_branch(cleanup_block);
+
+        self.builder.position_at_end(done_block);
+        self.gen_coroutine_context_call(callee_context, coroutine::DESTROY_FIELD, "callee_destroy");
+        self.gen_empty()
+    }
+
+    /// Calls the function that is stored in the field at `field_index` of the
+    /// coroutine context at `context_ptr` with the handle of the coroutine.
+    fn gen_coroutine_context_call(
+        &self,
+        context_ptr: PointerValue<'ink>,
+        field_index: u32,
+        name: &str,
+    ) -> Option<BasicValueEnum<'ink>> {
+        let handle_ptr = self
+            .builder
+            .build_struct_gep(context_ptr, coroutine::HANDLE_FIELD, "callee_handle_ptr")
+            .expect("`CoroutineContext` has a handle");
+        let handle = self.builder.build_load(handle_ptr, "callee_handle");
+        let function_ptr = self
+            .builder
+            .build_struct_gep(context_ptr, field_index, &format!("{name}_ptr"))
+            .expect("`CoroutineContext` has the field");
+        let function: CallableValue<'ink> = self
+            .builder
+            .build_load(function_ptr, name)
+            .into_pointer_value()
+            .try_into()
+            .expect("the field of `CoroutineContext` is a function pointer");
+        self.builder
+            .build_call(function, &[handle.into()], name)
+            .try_as_basic_value()
+            .left()
+    }
+
     fn gen_break(
         &mut self,
         _expr: ExprId,
@@ -1372,7 +2083,7 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
 
     fn gen_field(
         &mut self,
-        _expr: ExprId,
+        expr: ExprId,
         receiver_expr: ExprId,
         name: &Name,
     ) -> Option<BasicValueEnum<'ink>> {
@@ -1405,7 +2116,14 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
                         "could not get pointer to field `{hir_struct_name}::{name}` at index {field_idx}"
                     )
                 });
-            Some(self.builder.build_load(field_ptr, field_ir_name))
+            let value = self.builder.build_load(field_ptr, field_ir_name);
+            self.align_place_access(
+                expr,
+                value
+                    .as_instruction_value()
+                    .expect("expected a load instruction"),
+            );
+            Some(value)
         } else {
             let receiver_value = self.gen_expr(receiver_expr)?;
             let receiver_value = self.opt_deref_value(receiver_expr, receiver_value);
@@ -1586,6 +2304,158 @@ impl<'db, 'ink, 't> BodyIrGenerator<'db, 'ink, 't> {
         })
     }
 
+    /// Generates code to fetch the length of an array
+    fn gen_array_length(&mut self, array: ExprId) -> Option<BasicValueEnum<'ink>> {
+        // Safety: the inferred type of the expression is an array
+        let array = unsafe {
+            RuntimeArrayValue::from_ptr_unchecked(self.gen_expr(array)?.into_pointer_value())
+        };
+        let length_ptr = array.get_length_ptr(&self.builder);
+        Some(self.builder.build_load(length_ptr, "length"))
+    }
+
+    /// Generates a call to the `log` intrinsic that writes a formatted message
+    /// through the runtime. Every argument is widened to 128 bits and stored as
+    /// two words, next to an array that holds the type of every argument.
+    fn gen_log(
+        &mut self,
+        kind: LogKind,
+        format: &str,
+        args: &[ExprId],
+    ) -> Option<BasicValueEnum<'ink>> {
+        let i8_type = self.context.i8_type();
+        let i64_type = self.context.i64_type();
+        let i128_type = self.context.i128_type();
+        let usize_type = self.hir_types.get_usize_type();
+
+        let alloca_builder = self.new_alloca_builder();
+        let arg_kinds = alloca_builder.build_array_alloca(
+            i8_type,
+            usize_type.const_int(args.len() as u64, false),
+            "log_arg_kinds",
+        );
+        let arg_words = alloca_builder.build_array_alloca(
+            i64_type,
+            usize_type.const_int(2 * args.len() as u64, false),
+            "log_args",
+        );
+
+        for (idx, arg) in args.iter().enumerate() {
+            let value = self.gen_expr(*arg)?;
+            let (arg_kind, value) = match self.infer[*arg].interned() {
+                TyKind::Bool => (
+                    abi::LogArgKind::Bool,
+                    self.builder
+                        .build_int_z_extend(value.into_int_value(), i128_type, "log_arg"),
+                ),
+                TyKind::Int(ty) if ty.signedness.is_signed() => (
+                    abi::LogArgKind::Int,
+                    self.builder.build_int_s_extend_or_bit_cast(
+                        value.into_int_value(),
+                        i128_type,
+                        "log_arg",
+                    ),
+                ),
+                TyKind::Int(_) => (
+                    abi::LogArgKind::UInt,
+                    self.builder.build_int_z_extend_or_bit_cast(
+                        value.into_int_value(),
+                        i128_type,
+                        "log_arg",
+                    ),
+                ),
+                TyKind::Float(ty) => {
+                    let (arg_kind, bits_type) = match ty.bitness {
+                        mun_hir::FloatBitness::X32 => {
+                            (abi::LogArgKind::Float32, self.context.i32_type())
+                        }
+                        mun_hir::FloatBitness::X64 => (abi::LogArgKind::Float64, i64_type),
+                    };
+                    let bits = self
+                        .builder
+                        .build_bitcast(value, bits_type, "log_arg_bits")
+                        .into_int_value();
+                    (
+                        arg_kind,
+                        self.builder.build_int_z_extend(bits, i128_type, "log_arg"),
+                    )
+                }
+                _ => {
+                    unreachable!("cannot format a value of this type, are we missing a diagnostic?")
+                }
+            };
+
+            let kind_ptr = unsafe {
+                self.builder.build_gep(
+                    arg_kinds,
+                    &[usize_type.const_int(idx as u64, false)],
+                    "log_arg_kind",
+                )
+            };
+            self.builder
+                .build_store(kind_ptr, i8_type.const_int(arg_kind as u64, false));
+
+            let low = self.builder.build_int_truncate(value, i64_type, "low");
+            let high = self.builder.build_right_shift(
+                value,
+                i128_type.const_int(64, false),
+                false,
+                "high",
+            );
+            let high = self.builder.build_int_truncate(high, i64_type, "high");
+            for (word_idx, word) in [low, high].into_iter().enumerate() {
+                let word_ptr = unsafe {
+                    self.builder.build_gep(
+                        arg_words,
+                        &[usize_type.const_int((2 * idx + word_idx) as u64, false)],
+                        "log_arg_word",
+                    )
+                };
+                self.builder.build_store(word_ptr, word);
+            }
+        }
+
+        // The format string is stored without a null terminator, because it may contain
+        // null characters.
+        let format_value = self.context.const_string(format.as_bytes(), false);
+        let format_global = self
+            .module
+            .add_global(format_value.get_type(), None, "log_format");
+        format_global.set_initializer(&format_value);
+        format_global.set_constant(true);
+        format_global.set_linkage(Linkage::Private);
+        format_global.set_unnamed_addr(true);
+        let format_ptr = self.builder.build_pointer_cast(
+            format_global.as_pointer_value(),
+            i8_type.ptr_type(AddressSpace::default()),
+            "log_format",
+        );
+
+        let log_fn = self.dispatch_table.gen_intrinsic_lookup(
+            self.external_globals.dispatch_table,
+            &self.builder,
+            &intrinsics::log,
+        );
+        let log_kind = match kind {
+            LogKind::Print => abi::LogKind::Print,
+            LogKind::Log => abi::LogKind::Log,
+        };
+        self.builder.build_call(
+            log_fn,
+            &[
+                i8_type.const_int(log_kind as u64, false).into(),
+                format_ptr.into(),
+                usize_type.const_int(format.len() as u64, false).into(),
+                arg_kinds.into(),
+                arg_words.into(),
+                usize_type.const_int(args.len() as u64, false).into(),
+            ],
+            "",
+        );
+
+        Some(self.context.const_struct(&[], false).into())
+    }
+
     /// Returns a pointer to the allocator handle
     fn get_allocator_handle_ptr(&self) -> PointerValue<'ink> {
         self.builder
@@ -0,0 +1,220 @@
+//! Determines how small value structs are passed to and returned from public
+//! functions by value, following the C calling convention of the target.
+//!
+//! LLVM does not implement the C calling convention for aggregates by itself;
+//! like a C compiler, we have to coerce a struct to the types of the
+//! registers in which the target passes it. The rules below cover structs of
+//! at most two registers on the targets that Mun supports.
+
+use inkwell::{context::Context, types::BasicTypeEnum};
+use mun_target::spec::Target;
+
+/// The maximum size in bytes of a struct that is passed in registers, i.e. two
+/// 64-bit registers.
+const MAX_SIZE_IN_REGISTERS: u64 = 16;
+
+/// A scalar field of a struct, possibly nested in another struct.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Scalar {
+    /// The offset of the scalar from the start of the outermost struct in
+    /// bytes
+    pub offset: u64,
+    /// The size of the scalar in bytes
+    pub size: u64,
+    /// Whether the scalar is a floating-point number
+    pub is_float: bool,
+}
+
+/// Returns the type to which a struct of `size` bytes, aligned to `align`
+/// bytes and consisting of `scalars`, is coerced when it is passed to or
+/// returned from a function that follows the C calling convention of the
+/// `target`. Returns `None` if the target does not pass the struct in
+/// registers.
+pub(crate) fn coerced_struct_type<'ink>(
+    context: &'ink Context,
+    target: &Target,
+    size: u64,
+    align: u64,
+    scalars: &[Scalar],
+) -> Option<BasicTypeEnum<'ink>> {
+    if size == 0 || size > MAX_SIZE_IN_REGISTERS || scalars.is_empty() {
+        return None;
+    }
+
+    match (target.arch.as_ref(), target.options.is_like_windows) {
+        ("x86_64", false) => coerce_sysv_x86_64(context, size, scalars),
+        ("x86_64", true) => coerce_win64(context, size),
+        ("aarch64", false) => Some(coerce_aarch64(context, size, align, scalars)),
+        _ => None,
+    }
+}
+
+/// The System V AMD64 ABI classifies each eightbyte of a struct separately: an
+/// eightbyte that only contains floating-point numbers is passed in a vector
+/// register, any other eightbyte in a general purpose register.
+fn coerce_sysv_x86_64<'ink>(
+    context: &'ink Context,
+    size: u64,
+    scalars: &[Scalar],
+) -> Option<BasicTypeEnum<'ink>> {
+    let mut eightbyte_types = Vec::with_capacity(2);
+    for index in 0..size.div_ceil(8) {
+        let start = index * 8;
+        let end = size.min(start + 8);
+        let overlapping: Vec<&Scalar> = scalars
+            .iter()
+            .filter(|scalar| scalar.offset < end && scalar.offset + scalar.size > start)
+            .collect();
+
+        let eightbyte_type: BasicTypeEnum<'ink> = if overlapping.is_empty() {
+            // An eightbyte that only consists of padding requires alignments
+            // that a struct of this size cannot have.
+            return None;
+        } else if overlapping.iter().any(|scalar| !scalar.is_float) {
+            context
+                .custom_width_int_type(((end - start) * 8) as u32)
+                .into()
+        } else {
+            match overlapping.as_slice() {
+                [scalar] if scalar.size == 8 => context.f64_type().into(),
+                [_] => context.f32_type().into(),
+                _ => context.f32_type().vec_type(2).into(),
+            }
+        };
+        eightbyte_types.push(eightbyte_type);
+    }
+
+    Some(match eightbyte_types.as_slice() {
+        [ty] => *ty,
+        types => context.struct_type(types, false).into(),
+    })
+}
+
+/// The Windows x64 calling convention passes structs of 1, 2, 4, or 8 bytes in
+/// a general purpose register, and all other structs by reference.
+fn coerce_win64(context: &Context, size: u64) -> Option<BasicTypeEnum<'_>> {
+    matches!(size, 1 | 2 | 4 | 8).then(|| context.custom_width_int_type((size * 8) as u32).into())
+}
+
+/// The `AArch64` procedure call standard passes homogeneous aggregates of up to
+/// four floating-point numbers of the same type in vector registers, and all
+/// other structs of at most 16 bytes in general purpose registers.
+fn coerce_aarch64<'ink>(
+    context: &'ink Context,
+    size: u64,
+    align: u64,
+    scalars: &[Scalar],
+) -> BasicTypeEnum<'ink> {
+    let first = scalars[0];
+    let is_homogeneous_float_aggregate = scalars.len() <= 4
+        && scalars
+            .iter()
+            .all(|scalar| scalar.is_float && scalar.size == first.size);
+
+    if is_homogeneous_float_aggregate {
+        let float_type = if first.size == 8 {
+            context.f64_type()
+        } else {
+            context.f32_type()
+        };
+        float_type.array_type(scalars.len() as u32).into()
+    } else if size <= 8 {
+        context.i64_type().into()
+    } else if align == 16 {
+        context.i128_type().into()
+    } else {
+        context.i64_type().array_type(2).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use inkwell::context::Context;
+    use mun_target::spec::Target;
+
+    use super::{coerced_struct_type, Scalar};
+
+    fn int(offset: u64, size: u64) -> Scalar {
+        Scalar {
+            offset,
+            size,
+            is_float: false,
+        }
+    }
+
+    fn float(offset: u64, size: u64) -> Scalar {
+        Scalar {
+            offset,
+            size,
+            is_float: true,
+        }
+    }
+
+    /// Returns the LLVM type to which a struct is coerced on the target with
+    /// the specified `triple`, printed as a string.
+    fn coerce(triple: &str, size: u64, align: u64, scalars: &[Scalar]) -> Option<String> {
+        let context = Context::create();
+        let target = Target::search(triple).expect("unknown target");
+        coerced_struct_type(&context, &target, size, align, scalars)
+            .map(|ty| ty.print_to_string().to_string())
+    }
+
+    #[test]
+    fn sysv_x86_64() {
+        let triple = "x86_64-unknown-linux-gnu";
+        assert_eq!(
+            coerce(triple, 8, 4, &[float(0, 4), float(4, 4)]).as_deref(),
+            Some("<2 x float>")
+        );
+        assert_eq!(
+            coerce(triple, 12, 4, &[float(0, 4), float(4, 4), float(8, 4)]).as_deref(),
+            Some("{ <2 x float>, float }")
+        );
+        assert_eq!(
+            coerce(triple, 16, 8, &[int(0, 4), float(8, 8)]).as_deref(),
+            Some("{ i64, double }")
+        );
+        assert_eq!(
+            coerce(triple, 8, 4, &[int(0, 4), float(4, 4)]).as_deref(),
+            Some("i64")
+        );
+        assert_eq!(
+            coerce(triple, 3, 1, &[int(0, 1), int(1, 1), int(2, 1)]).as_deref(),
+            Some("i24")
+        );
+        assert_eq!(
+            coerce(triple, 24, 8, &[int(0, 8), int(8, 8), int(16, 8)]),
+            None
+        );
+    }
+
+    #[test]
+    fn win64() {
+        let triple = "x86_64-pc-windows-msvc";
+        assert_eq!(
+            coerce(triple, 8, 4, &[float(0, 4), float(4, 4)]).as_deref(),
+            Some("i64")
+        );
+        assert_eq!(
+            coerce(triple, 12, 4, &[int(0, 4), int(4, 4), int(8, 4)]),
+            None
+        );
+    }
+
+    #[test]
+    fn aarch64() {
+        let triple = "aarch64-apple-darwin";
+        assert_eq!(
+            coerce(triple, 12, 4, &[float(0, 4), float(4, 4), float(8, 4)]).as_deref(),
+            Some("[3 x float]")
+        );
+        assert_eq!(
+            coerce(triple, 16, 8, &[int(0, 4), float(8, 8)]).as_deref(),
+            Some("[2 x i64]")
+        );
+        assert_eq!(
+            coerce(triple, 8, 4, &[int(0, 4), float(4, 4)]).as_deref(),
+            Some("i64")
+        );
+    }
+}
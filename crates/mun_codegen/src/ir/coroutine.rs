@@ -0,0 +1,452 @@
+//! Lowers resumable functions to LLVM coroutines, see
+//! <https://llvm.org/docs/Coroutines.html>.
+//!
+//! A resumable function is the ramp of its coroutine: calling it allocates the
+//! frame of the coroutine, fills in the [`abi::CoroutineContext`] that is
+//! passed as its last argument, and suspends before the body is executed. The
+//! coroutine passes of LLVM later split the function into the ramp and the
+//! functions that resume and destroy the coroutine, which spill the values
+//! that live across suspension points to the frame.
+//!
+//! Inkwell cannot represent the `token` values that identify a coroutine and
+//! its suspension points, so the intrinsics that consume them are called
+//! through the LLVM C API.
+//!
+//! [`abi::CoroutineContext`]: mun_abi::CoroutineContext
+
+use std::marker::PhantomData;
+
+use inkwell::{
+    attributes::AttributeLoc,
+    basic_block::BasicBlock,
+    builder::Builder,
+    context::Context,
+    intrinsics::Intrinsic,
+    module::{Linkage, Module},
+    types::{AsTypeRef, BasicTypeEnum, IntType, StructType},
+    values::{AsValueRef, FunctionValue, IntValue, PointerValue},
+    AddressSpace,
+};
+use llvm_sys::{core as llvm, prelude::LLVMValueRef};
+
+/// The name of the IR type of an
+/// [`abi::CoroutineContext`](mun_abi::CoroutineContext)
+const CONTEXT_TYPE_NAME: &str = "CoroutineContext";
+
+/// The index of the `handle` field of a `CoroutineContext`
+pub(crate) const HANDLE_FIELD: u32 = 0;
+/// The index of the `resume` field of a `CoroutineContext`
+pub(crate) const RESUME_FIELD: u32 = 1;
+/// The index of the `destroy` field of a `CoroutineContext`
+pub(crate) const DESTROY_FIELD: u32 = 2;
+/// The index of the `resume_value` field of a `CoroutineContext`
+pub(crate) const RESUME_VALUE_FIELD: u32 = 3;
+
+/// The name of the function that resumes a coroutine of the module
+const RESUME_FN_NAME: &str = "coroutine_resume";
+/// The name of the function that destroys a coroutine of the module
+const DESTROY_FN_NAME: &str = "coroutine_destroy";
+
+/// Returns the IR type of an
+/// [`abi::CoroutineContext`](mun_abi::CoroutineContext).
+pub(crate) fn context_type(context: &Context) -> StructType<'_> {
+    if let Some(ty) = context.get_struct_type(CONTEXT_TYPE_NAME) {
+        return ty;
+    }
+
+    let handle_ty = context.i8_type().ptr_type(AddressSpace::default());
+    let resume_ty = context.i8_type().fn_type(&[handle_ty.into()], false);
+    let destroy_ty = context.void_type().fn_type(&[handle_ty.into()], false);
+
+    let ty = context.opaque_struct_type(CONTEXT_TYPE_NAME);
+    ty.set_body(
+        &[
+            handle_ty.into(),
+            resume_ty.ptr_type(AddressSpace::default()).into(),
+            destroy_ty.ptr_type(AddressSpace::default()).into(),
+            context.f32_type().into(),
+        ],
+        false,
+    );
+    ty
+}
+
+/// The `token` that identifies a coroutine.
+#[derive(Clone, Copy)]
+pub(crate) struct CoroutineId<'ink> {
+    token: LLVMValueRef,
+    _context: PhantomData<&'ink Context>,
+}
+
+/// The coroutine of a resumable function whose body is being generated.
+pub(crate) struct Coroutine<'ink> {
+    /// The `token` that identifies the coroutine
+    id: CoroutineId<'ink>,
+    /// The handle to the frame of the coroutine
+    handle: PointerValue<'ink>,
+    /// The pointer to the `CoroutineContext` that was passed to the function
+    pub context_ptr: PointerValue<'ink>,
+    /// The block that frees the frame when the coroutine is destroyed
+    pub cleanup_block: BasicBlock<'ink>,
+    /// The block that returns to the caller when the coroutine suspends
+    pub suspend_block: BasicBlock<'ink>,
+    /// The block to which the function branches when it returns
+    pub final_block: BasicBlock<'ink>,
+}
+
+impl<'ink> Coroutine<'ink> {
+    /// Generates the start of the ramp of a coroutine in the current block of
+    /// `builder`, up to the allocation of its frame. Returns the `token` that
+    /// identifies the coroutine and the size of the frame, as a `size_type`.
+    pub fn gen_id(
+        module: &Module<'ink>,
+        builder: &Builder<'ink>,
+        size_type: IntType<'ink>,
+    ) -> (CoroutineId<'ink>, IntValue<'ink>) {
+        let context = module.get_context();
+        let null = context
+            .i8_type()
+            .ptr_type(AddressSpace::default())
+            .const_null();
+
+        let id = build_intrinsic_call(
+            module,
+            builder,
+            "llvm.coro.id",
+            &[],
+            &[
+                context.i32_type().const_zero().as_value_ref(),
+                null.as_value_ref(),
+                null.as_value_ref(),
+                null.as_value_ref(),
+            ],
+        );
+
+        let size = builder
+            .build_call(
+                intrinsic_declaration(module, "llvm.coro.size", &[size_type.into()]),
+                &[],
+                "frame_size",
+            )
+            .try_as_basic_value()
+            .left()
+            .expect("`llvm.coro.size` returns a value")
+            .into_int_value();
+
+        (
+            CoroutineId {
+                token: id,
+                _context: PhantomData,
+            },
+            size,
+        )
+    }
+
+    /// Generates the rest of the ramp of the coroutine identified by `id`,
+    /// whose frame was allocated at `memory`. The context of the coroutine is
+    /// filled in, after which the coroutine suspends. Afterwards, `builder` is
+    /// positioned in the block at which the coroutine is first resumed.
+    pub fn gen_begin(
+        module: &Module<'ink>,
+        builder: &Builder<'ink>,
+        fn_value: FunctionValue<'ink>,
+        id: CoroutineId<'ink>,
+        memory: PointerValue<'ink>,
+        context_ptr: PointerValue<'ink>,
+    ) -> Self {
+        let context = module.get_context();
+
+        // The coroutine passes only split functions that the frontend marked as
+        // unsplit coroutines
+        fn_value.add_attribute(
+            AttributeLoc::Function,
+            context.create_string_attribute("coroutine.presplit", "0"),
+        );
+
+        build_intrinsic_call(
+            module,
+            builder,
+            "llvm.coro.begin",
+            &[],
+            &[id.token, memory.as_value_ref()],
+        );
+        let handle = last_call_value(builder, "llvm.coro.begin");
+
+        let handle_ptr = builder
+            .build_struct_gep(context_ptr, HANDLE_FIELD, "handle_ptr")
+            .expect("`CoroutineContext` has a handle");
+        builder.build_store(handle_ptr, handle);
+        let resume_ptr = builder
+            .build_struct_gep(context_ptr, RESUME_FIELD, "resume_ptr")
+            .expect("`CoroutineContext` has a resume function");
+        builder.build_store(
+            resume_ptr,
+            resume_fn(module).as_global_value().as_pointer_value(),
+        );
+        let destroy_ptr = builder
+            .build_struct_gep(context_ptr, DESTROY_FIELD, "destroy_ptr")
+            .expect("`CoroutineContext` has a destroy function");
+        builder.build_store(
+            destroy_ptr,
+            destroy_fn(module).as_global_value().as_pointer_value(),
+        );
+
+        let coroutine = Coroutine {
+            id,
+            handle,
+            context_ptr,
+            cleanup_block: context.append_basic_block(fn_value, "cleanup"),
+            suspend_block: context.append_basic_block(fn_value, "suspend"),
+            final_block: context.append_basic_block(fn_value, "final"),
+        };
+
+        let start_block = context.append_basic_block(fn_value, "start");
+        coroutine.gen_suspend(module, builder, start_block);
+        start_block
+            .move_after(builder.get_insert_block().unwrap())
+            .unwrap();
+        builder.position_at_end(start_block);
+
+        coroutine
+    }
+
+    /// Suspends the coroutine. When it is resumed, execution continues at
+    /// `resume_block`. When it is destroyed instead, execution continues at
+    /// `destroy_block`.
+    pub fn gen_suspend_or(
+        &self,
+        module: &Module<'ink>,
+        builder: &Builder<'ink>,
+        resume_block: BasicBlock<'ink>,
+        destroy_block: BasicBlock<'ink>,
+    ) {
+        let context = module.get_context();
+        let state = gen_suspend_state(module, builder, false);
+        builder.build_switch(
+            state,
+            self.suspend_block,
+            &[
+                (context.i8_type().const_zero(), resume_block),
+                (context.i8_type().const_int(1, false), destroy_block),
+            ],
+        );
+    }
+
+    /// Suspends the coroutine. When it is resumed, execution continues at
+    /// `resume_block`.
+    pub fn gen_suspend(
+        &self,
+        module: &Module<'ink>,
+        builder: &Builder<'ink>,
+        resume_block: BasicBlock<'ink>,
+    ) {
+        self.gen_suspend_or(module, builder, resume_block, self.cleanup_block);
+    }
+
+    /// Generates the blocks that end the coroutine: the final suspension
+    /// point, the block that frees the frame, and the block that returns to
+    /// the caller. `gen_free` generates the call that frees the memory of the
+    /// frame.
+    pub fn gen_end(
+        &self,
+        module: &Module<'ink>,
+        builder: &Builder<'ink>,
+        fn_value: FunctionValue<'ink>,
+        gen_free: impl FnOnce(PointerValue<'ink>),
+    ) {
+        let context = module.get_context();
+        let last_block = fn_value.get_last_basic_block().unwrap();
+        self.final_block.move_after(last_block).unwrap();
+        self.cleanup_block.move_after(self.final_block).unwrap();
+        self.suspend_block.move_after(self.cleanup_block).unwrap();
+
+        // A coroutine that suspended at its final suspension point cannot be resumed
+        builder.position_at_end(self.final_block);
+        let state = gen_suspend_state(module, builder, true);
+        let resumed_block = context.append_basic_block(fn_value, "resumed_after_return");
+        builder.build_switch(
+            state,
+            self.suspend_block,
+            &[
+                (context.i8_type().const_zero(), resumed_block),
+                (context.i8_type().const_int(1, false), self.cleanup_block),
+            ],
+        );
+        resumed_block.move_after(self.suspend_block).unwrap();
+        builder.position_at_end(resumed_block);
+        builder.build_unreachable();
+
+        builder.position_at_end(self.cleanup_block);
+        build_intrinsic_call(
+            module,
+            builder,
+            "llvm.coro.free",
+            &[],
+            &[self.id.token, self.handle.as_value_ref()],
+        );
+        gen_free(last_call_value(builder, "llvm.coro.free"));
+        builder.build_unconditional_branch(self.suspend_block);
+
+        builder.position_at_end(self.suspend_block);
+        builder.build_call(
+            intrinsic_declaration(module, "llvm.coro.end", &[]),
+            &[self.handle.into(), context.bool_type().const_zero().into()],
+            "",
+        );
+        builder.build_return(None);
+    }
+}
+
+/// Returns the function that resumes a coroutine of the `module` and returns a
+/// non-zero value if it has returned. It is stored in the
+/// `CoroutineContext`, so hosts can resume coroutines through the C calling
+/// convention.
+fn resume_fn<'ink>(module: &Module<'ink>) -> FunctionValue<'ink> {
+    if let Some(function) = module.get_function(RESUME_FN_NAME) {
+        return function;
+    }
+
+    let context = module.get_context();
+    let handle_ty = context.i8_type().ptr_type(AddressSpace::default());
+    let function = module.add_function(
+        RESUME_FN_NAME,
+        context.i8_type().fn_type(&[handle_ty.into()], false),
+        Some(Linkage::Internal),
+    );
+
+    let builder = context.create_builder();
+    builder.position_at_end(context.append_basic_block(function, "body"));
+    let handle = function.get_first_param().unwrap();
+    builder.build_call(
+        intrinsic_declaration(module, "llvm.coro.resume", &[]),
+        &[handle.into()],
+        "",
+    );
+    let done = builder
+        .build_call(
+            intrinsic_declaration(module, "llvm.coro.done", &[]),
+            &[handle.into()],
+            "done",
+        )
+        .try_as_basic_value()
+        .left()
+        .expect("`llvm.coro.done` returns a value")
+        .into_int_value();
+    let done = builder.build_int_z_extend(done, context.i8_type(), "done");
+    builder.build_return(Some(&done));
+
+    function
+}
+
+/// Returns the function that destroys a coroutine of the `module`, see
+/// [`resume_fn`].
+fn destroy_fn<'ink>(module: &Module<'ink>) -> FunctionValue<'ink> {
+    if let Some(function) = module.get_function(DESTROY_FN_NAME) {
+        return function;
+    }
+
+    let context = module.get_context();
+    let handle_ty = context.i8_type().ptr_type(AddressSpace::default());
+    let function = module.add_function(
+        DESTROY_FN_NAME,
+        context.void_type().fn_type(&[handle_ty.into()], false),
+        Some(Linkage::Internal),
+    );
+
+    let builder = context.create_builder();
+    builder.position_at_end(context.append_basic_block(function, "body"));
+    builder.build_call(
+        intrinsic_declaration(module, "llvm.coro.destroy", &[]),
+        &[function.get_first_param().unwrap().into()],
+        "",
+    );
+    builder.build_return(None);
+
+    function
+}
+
+/// Generates a suspension point and returns its state: `0` if the coroutine
+/// is resumed, `1` if it is destroyed, and `-1` if it suspended.
+fn gen_suspend_state<'ink>(
+    module: &Module<'ink>,
+    builder: &Builder<'ink>,
+    is_final: bool,
+) -> IntValue<'ink> {
+    let context = module.get_context();
+    let suspend = intrinsic_declaration(module, "llvm.coro.suspend", &[]);
+
+    // Safety: the first parameter of `llvm.coro.suspend` is a `token`
+    let no_save = unsafe {
+        llvm::LLVMConstNull(llvm::LLVMTypeOf(llvm::LLVMGetParam(
+            suspend.as_value_ref(),
+            0,
+        )))
+    };
+    build_intrinsic_call(
+        module,
+        builder,
+        "llvm.coro.suspend",
+        &[],
+        &[
+            no_save,
+            context
+                .bool_type()
+                .const_int(u64::from(is_final), false)
+                .as_value_ref(),
+        ],
+    );
+
+    builder
+        .get_insert_block()
+        .and_then(BasicBlock::get_last_instruction)
+        .and_then(|instruction| IntValue::try_from(instruction).ok())
+        .expect("`llvm.coro.suspend` returns an integer")
+}
+
+/// Returns the declaration of the LLVM intrinsic called `name`, with the
+/// specified `overloads`.
+fn intrinsic_declaration<'ink>(
+    module: &Module<'ink>,
+    name: &str,
+    overloads: &[BasicTypeEnum<'ink>],
+) -> FunctionValue<'ink> {
+    Intrinsic::find(name)
+        .and_then(|intrinsic| intrinsic.get_declaration(module, overloads))
+        .unwrap_or_else(|| panic!("unknown intrinsic `{name}`"))
+}
+
+/// Calls the LLVM intrinsic called `name` with `args`, which may include
+/// `token` values. Returns the result of the call, which may be a `token`.
+fn build_intrinsic_call<'ink>(
+    module: &Module<'ink>,
+    builder: &Builder<'ink>,
+    name: &str,
+    overloads: &[BasicTypeEnum<'ink>],
+    args: &[LLVMValueRef],
+) -> LLVMValueRef {
+    let function = intrinsic_declaration(module, name, overloads);
+    let mut args = args.to_vec();
+
+    // Safety: the builder, function and arguments are valid and belong to the same
+    // context
+    unsafe {
+        llvm::LLVMBuildCall2(
+            builder.as_mut_ptr(),
+            function.get_type().as_type_ref(),
+            function.as_value_ref(),
+            args.as_mut_ptr(),
+            args.len() as u32,
+            b"\0".as_ptr().cast(),
+        )
+    }
+}
+
+/// Returns the pointer that is returned by the call to the intrinsic called
+/// `name`, which was the last instruction that `builder` generated.
+fn last_call_value<'ink>(builder: &Builder<'ink>, name: &str) -> PointerValue<'ink> {
+    builder
+        .get_insert_block()
+        .and_then(BasicBlock::get_last_instruction)
+        .and_then(|instruction| PointerValue::try_from(instruction).ok())
+        .unwrap_or_else(|| panic!("`{name}` returns a pointer"))
+}
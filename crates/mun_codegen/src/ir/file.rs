@@ -1,7 +1,8 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
 
 use inkwell::module::Module;
-use mun_hir::{HasVisibility, ModuleDef};
+use itertools::Itertools;
+use mun_hir::{HasVisibility, ModuleDef, TypeWalk};
 
 use super::body::ExternalGlobals;
 use crate::{
@@ -22,11 +23,36 @@ pub struct FileIr<'ink> {
     pub type_definitions: HashSet<mun_hir::Ty>,
 }
 
+/// Specifies the functions of a module group for which [`gen_file_ir`]
+/// generates a body. The other functions are only declared, which allows the
+/// modules of a group to be compiled into separate object files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FunctionBodies {
+    /// The bodies of all functions of the module group
+    All,
+    /// Only the bodies of the functions of the specified module
+    Module(mun_hir::Module),
+    /// No bodies at all
+    None,
+}
+
+impl FunctionBodies {
+    /// Returns true if the bodies of the functions of `module` are generated.
+    fn includes(self, module: mun_hir::Module) -> bool {
+        match self {
+            FunctionBodies::All => true,
+            FunctionBodies::Module(m) => m == module,
+            FunctionBodies::None => false,
+        }
+    }
+}
+
 /// Generates IR for the specified file.
 pub(crate) fn gen_file_ir<'ink>(
     code_gen: &CodeGenContext<'_, 'ink>,
     group_ir: &FileGroupIr<'ink>,
     module_group: &ModuleGroup,
+    bodies: FunctionBodies,
 ) -> FileIr<'ink> {
     let llvm_module = code_gen.context.create_module(&module_group.name);
 
@@ -47,6 +73,13 @@ pub(crate) fn gen_file_ir<'ink>(
                 functions.insert(f, fun);
 
                 let fn_sig = f.ty(code_gen.db).callable_sig(code_gen.db).unwrap();
+                if f.visibility(code_gen.db).is_externally_visible() {
+                    // `Result`s used in the public API need type information to be
+                    // marshalled by the host
+                    for ty in fn_sig.params().iter().chain(std::iter::once(fn_sig.ret())) {
+                        collect_result_types(ty, &mut type_definitions);
+                    }
+                }
                 if f.visibility(code_gen.db).is_externally_visible()
                     && !fn_sig.marshallable(code_gen.db)
                 {
@@ -60,8 +93,15 @@ pub(crate) fn gen_file_ir<'ink>(
                 }
             }
         }
+        // Extern structs are owned by the host, their type information is never
+        // exported
         if let ModuleDef::Struct(s) = def {
-            type_definitions.insert(s.ty(code_gen.db));
+            if !s.is_extern(code_gen.db) {
+                type_definitions.insert(s.ty(code_gen.db));
+                for field in s.fields(code_gen.db) {
+                    collect_result_types(&field.ty(code_gen.db), &mut type_definitions);
+                }
+            }
         }
     }
 
@@ -88,8 +128,13 @@ pub(crate) fn gen_file_ir<'ink>(
     // Construct requirements for generating the bodies
     let fn_pass_manager = function::create_pass_manager(&llvm_module, code_gen.optimization_level);
 
-    // Generate the function bodies
-    for (hir_function, llvm_function) in functions.iter() {
+    // Generate the function bodies. They are generated in a deterministic order,
+    // because intrinsics are declared when they are first used.
+    for (hir_function, llvm_function) in functions
+        .iter()
+        .filter(|(f, _)| bodies.includes(f.module(code_gen.db)))
+        .sorted_by_key(|(f, _)| **f)
+    {
         let mut code_gen = BodyIrGenerator::new(
             code_gen.context,
             code_gen.db,
@@ -100,13 +145,20 @@ pub(crate) fn gen_file_ir<'ink>(
             external_globals.clone(),
             &code_gen.hir_types,
             module_group,
+            &llvm_module,
+            code_gen.overflow_checks,
+            // The calls of a coroutine span multiple suspensions, so they are not profiled
+            code_gen.profiling && !hir_function.is_resumable(code_gen.db),
         );
 
         code_gen.gen_fn_body();
         fn_pass_manager.run_on(llvm_function);
     }
 
-    for (hir_function, llvm_function) in wrapper_functions.iter() {
+    for (hir_function, llvm_function) in wrapper_functions
+        .iter()
+        .filter(|(f, _)| bodies.includes(f.module(code_gen.db)))
+    {
         let mut code_gen = BodyIrGenerator::new(
             code_gen.context,
             code_gen.db,
@@ -117,6 +169,9 @@ pub(crate) fn gen_file_ir<'ink>(
             external_globals.clone(),
             &code_gen.hir_types,
             module_group,
+            &llvm_module,
+            code_gen.overflow_checks,
+            false,
         );
 
         code_gen.gen_fn_wrapper();
@@ -136,3 +191,14 @@ pub(crate) fn gen_file_ir<'ink>(
         type_definitions,
     }
 }
+
+/// Collects all `Result` types that occur in `ty`, including `ty` itself.
+/// `Result` types are not declared anywhere, so their type information is
+/// emitted by every file that uses them.
+fn collect_result_types(ty: &mun_hir::Ty, type_definitions: &mut HashSet<mun_hir::Ty>) {
+    ty.walk(&mut |ty| {
+        if ty.as_result().is_some() {
+            type_definitions.insert(ty.clone());
+        }
+    });
+}
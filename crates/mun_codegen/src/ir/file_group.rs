@@ -57,10 +57,26 @@ pub(crate) fn gen_file_group_ir<'ink>(
                     code_gen.db,
                     &mut intrinsics_map,
                     &mut needs_alloc,
+                    code_gen.overflow_checks,
                     &f.body(code_gen.db),
                     &f.infer(code_gen.db),
                 );
 
+                if f.is_resumable(code_gen.db) {
+                    intrinsics::collect_coroutine_frame(
+                        code_gen.context,
+                        code_gen.target_machine.get_target_data(),
+                        &mut intrinsics_map,
+                        &mut needs_alloc,
+                    );
+                } else if code_gen.profiling {
+                    intrinsics::collect_profiling_hooks(
+                        code_gen.context,
+                        code_gen.target_machine.get_target_data(),
+                        &mut intrinsics_map,
+                    );
+                }
+
                 let fn_sig = f.ty(code_gen.db).callable_sig(code_gen.db).unwrap();
                 if f.visibility(code_gen.db).is_externally_visible()
                     && !fn_sig.marshallable(code_gen.db)
@@ -1,7 +1,9 @@
 use std::{collections::BTreeMap, sync::Arc};
 
 use inkwell::{context::Context, targets::TargetData, types::FunctionType};
-use mun_hir::{Body, Expr, ExprId, HirDatabase, InferenceResult, ValueNs};
+use mun_hir::{
+    ArithOp, BinaryOp, Body, Expr, ExprId, HirDatabase, InferenceResult, TyKind, UnaryOp, ValueNs,
+};
 
 use crate::{
     intrinsics::{self, Intrinsic},
@@ -33,6 +35,7 @@ fn collect_expr<'ink>(
     db: &'_ dyn HirDatabase,
     intrinsics: &mut IntrinsicsMap<'ink>,
     needs_alloc: &mut bool,
+    overflow_checks: bool,
     expr_id: ExprId,
     body: &Arc<Body>,
     infer: &InferenceResult,
@@ -74,6 +77,14 @@ fn collect_expr<'ink>(
         *needs_alloc = true;
     }
 
+    if let Expr::Log { .. } = expr {
+        collect_intrinsic(context, target, &intrinsics::log, intrinsics);
+    }
+
+    if overflow_checks && is_overflow_checked(expr, infer) {
+        collect_intrinsic(context, target, &intrinsics::overflow, intrinsics);
+    }
+
     // Recurse further
     expr.walk_child_exprs(|expr_id| {
         collect_expr(
@@ -82,6 +93,7 @@ fn collect_expr<'ink>(
             db,
             intrinsics,
             needs_alloc,
+            overflow_checks,
             expr_id,
             body,
             infer,
@@ -89,13 +101,41 @@ fn collect_expr<'ink>(
     });
 }
 
+/// Returns true if the specified expression is an integer operation that can
+/// overflow, and is therefore checked for overflow if overflow checks are
+/// enabled.
+fn is_overflow_checked(expr: &Expr, infer: &InferenceResult) -> bool {
+    match expr {
+        Expr::BinaryOp {
+            lhs,
+            op:
+                Some(
+                    BinaryOp::ArithOp(ArithOp::Add | ArithOp::Subtract | ArithOp::Multiply)
+                    | BinaryOp::Assignment {
+                        op: Some(ArithOp::Add | ArithOp::Subtract | ArithOp::Multiply),
+                    },
+                ),
+            ..
+        } => matches!(infer[*lhs].interned(), TyKind::Int(_)),
+        Expr::UnaryOp {
+            expr,
+            op: UnaryOp::Neg,
+        } => {
+            matches!(infer[*expr].interned(), TyKind::Int(int_ty) if int_ty.signedness.is_signed())
+        }
+        _ => false,
+    }
+}
+
 /// Collects all intrinsics from the specified `body`.
+#[allow(clippy::too_many_arguments)]
 pub fn collect_fn_body<'ink>(
     context: &'ink Context,
     target: TargetData,
     db: &dyn HirDatabase,
     intrinsics: &mut IntrinsicsMap<'ink>,
     needs_alloc: &mut bool,
+    overflow_checks: bool,
     body: &Arc<Body>,
     infer: &InferenceResult,
 ) {
@@ -105,12 +145,37 @@ pub fn collect_fn_body<'ink>(
         db,
         intrinsics,
         needs_alloc,
+        overflow_checks,
         body.body_expr(),
         body,
         infer,
     );
 }
 
+/// Collects the intrinsics that a function body calls to report its calls to
+/// the profiler of the runtime.
+pub fn collect_profiling_hooks<'ink>(
+    context: &'ink Context,
+    target: TargetData,
+    intrinsics: &mut IntrinsicsMap<'ink>,
+) {
+    collect_intrinsic(context, &target, &intrinsics::profile_enter, intrinsics);
+    collect_intrinsic(context, &target, &intrinsics::profile_exit, intrinsics);
+}
+
+/// Collects the intrinsics that a resumable function calls to allocate and
+/// free the frame of its coroutine.
+pub fn collect_coroutine_frame<'ink>(
+    context: &'ink Context,
+    target: TargetData,
+    intrinsics: &mut IntrinsicsMap<'ink>,
+    needs_alloc: &mut bool,
+) {
+    collect_intrinsic(context, &target, &intrinsics::coroutine_alloc, intrinsics);
+    collect_intrinsic(context, &target, &intrinsics::coroutine_free, intrinsics);
+    *needs_alloc = true;
+}
+
 /// Collects all intrinsics from a function wrapper body.
 pub fn collect_wrapper_body<'ink>(
     context: &'ink Context,
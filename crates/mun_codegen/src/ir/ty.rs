@@ -7,16 +7,18 @@ use inkwell::{
         AnyTypeEnum, BasicType, BasicTypeEnum, FloatType, FunctionType, IntType, PointerType,
         StructType,
     },
+    values::BasicValueEnum,
     AddressSpace,
 };
 use mun_abi::Guid;
 use mun_hir::{
-    FloatBitness, HirDatabase, HirDisplay, IntBitness, ResolveBitness, Signedness, Ty, TyKind,
+    FieldDefaultValue, FloatBitness, HirDatabase, HirDisplay, IntBitness, ResolveBitness,
+    Signedness, Ty, TyKind,
 };
 use smallvec::SmallVec;
 
 use crate::{
-    ir::IsIrType,
+    ir::{call_conv, coroutine, IsIrType},
     type_info::{HasStaticTypeId, TypeId, TypeIdData},
 };
 
@@ -27,6 +29,7 @@ pub struct HirTypeCache<'db, 'ink> {
     target_data: TargetData,
     types: RefCell<HashMap<mun_hir::TyKind, StructType<'ink>>>,
     array_ty_to_type_id: RefCell<HashMap<mun_hir::TyKind, Arc<TypeId>>>,
+    result_ty_to_type_id: RefCell<HashMap<mun_hir::TyKind, Arc<TypeId>>>,
     struct_to_type_id: RefCell<HashMap<mun_hir::Struct, Arc<TypeId>>>,
 }
 
@@ -40,6 +43,7 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
             types: RefCell::new(HashMap::default()),
             struct_to_type_id: RefCell::default(),
             array_ty_to_type_id: RefCell::default(),
+            result_ty_to_type_id: RefCell::default(),
         }
     }
 
@@ -103,11 +107,37 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
                     .expect("could not convert struct field to basic type")
             })
             .collect();
-        ir_ty.set_body(&field_types, false);
+        let packed = struct_ty.data(self.db.upcast()).repr == mun_hir::StructRepr::Packed;
+        ir_ty.set_body(&field_types, packed);
 
         ir_ty
     }
 
+    /// Returns the constant that a field is initialized with if it is omitted
+    /// from a record literal, or `None` if the field has no default value.
+    pub fn get_field_default(&self, field: mun_hir::Field) -> Option<BasicValueEnum<'ink>> {
+        let value = field.default_value(self.db)?;
+        let ty = match field.ty(self.db).interned() {
+            TyKind::TypeAlias(alias) => alias.target_type(self.db),
+            _ => field.ty(self.db),
+        };
+        let default = match (ty.interned(), value) {
+            (TyKind::Bool, FieldDefaultValue::Bool(value)) => {
+                self.get_bool_type().const_int(value.into(), false).into()
+            }
+            // The value is truncated to the bitness of the field
+            (TyKind::Int(int_ty), FieldDefaultValue::Int(value)) => self
+                .get_int_type(*int_ty)
+                .const_int_arbitrary_precision(&[value as u64, (value >> 64) as u64])
+                .into(),
+            (TyKind::Float(float_ty), FieldDefaultValue::Float(value)) => {
+                self.get_float_type(*float_ty).const_float(value).into()
+            }
+            _ => unreachable!("the default value of a field must match its type"),
+        };
+        Some(default)
+    }
+
     /// Returns the IR type of the specified array type.
     pub fn get_array_type(&self, element_ty: &Ty) -> StructType<'ink> {
         // Get the type from the cache
@@ -156,6 +186,48 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
         ir_ty
     }
 
+    /// Returns the IR type of the specified `Result` type.
+    pub fn get_result_type(&self, ok_ty: &Ty, err_ty: &Ty) -> StructType<'ink> {
+        // Get the type from the cache
+        let ty = TyKind::Result(ok_ty.clone(), err_ty.clone());
+        if let Some(ir_ty) = self.types.borrow().get(&ty) {
+            return *ir_ty;
+        };
+
+        // Opaquely construct the result struct type and store it in the cache
+        let ir_ty = self.context.opaque_struct_type(&format!(
+            "Result<{}, {}>",
+            ok_ty.display(self.db),
+            err_ty.display(self.db)
+        ));
+        self.types.borrow_mut().insert(ty, ir_ty);
+
+        // Mun Results are represented as a value struct:
+        //
+        // ```c
+        // struct Result {
+        //     bool is_err;
+        //     T ok;
+        //     E err;
+        // }
+        // ```
+        //
+        // Only the field that corresponds to `is_err` is initialized.
+
+        let ok_ir_type = self
+            .get_basic_type(ok_ty)
+            .expect("could not convert result ok type to basic type");
+        let err_ir_type = self
+            .get_basic_type(err_ty)
+            .expect("could not convert result err type to basic type");
+        ir_ty.set_body(
+            &[self.get_bool_type().into(), ok_ir_type, err_ir_type],
+            false,
+        );
+
+        ir_ty
+    }
+
     /// Returns the type of an array that should be used for variables. Arrays
     /// are always stored on the heap so this will always be a pointer to an
     /// Array<Ty>.
@@ -173,6 +245,13 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
     /// that should be used for variables.
     pub fn get_struct_reference_type(&self, struct_ty: mun_hir::Struct) -> BasicTypeEnum<'ink> {
         let ir_ty = self.get_struct_type(struct_ty);
+        if struct_ty.is_extern(self.db) {
+            // Extern structs are opaque handles owned by the host
+            // extern struct Foo;
+            // Foo*
+            return ir_ty.ptr_type(AddressSpace::default()).into();
+        }
+
         match struct_ty.data(self.db.upcast()).memory_kind {
             mun_hir::StructMemoryKind::Gc => {
                 // GC values are pointers to pointers
@@ -196,23 +275,103 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
         &self,
         struct_ty: mun_hir::Struct,
     ) -> BasicTypeEnum<'ink> {
+        if struct_ty.is_extern(self.db) {
+            return self.get_struct_reference_type(struct_ty);
+        }
+
+        // Small `#[repr(C)]` value structs are passed by value
+        if let Some(ir_ty) = self.get_public_struct_value_type(struct_ty) {
+            return ir_ty;
+        }
+
         let ir_ty = self.get_struct_type(struct_ty);
 
         // GC values are pointers to pointers
         // struct Foo {}
         // Foo**
         //
-        // Value structs are converted to GC types in the public API.
+        // Other value structs are converted to GC types in the public API.
         ir_ty
             .ptr_type(AddressSpace::default())
             .ptr_type(AddressSpace::default())
             .into()
     }
 
-    /// Returns the type of the specified function definition
-    pub fn get_function_type(&self, ty: mun_hir::Function) -> FunctionType<'ink> {
-        let ty = self.db.callable_sig(ty.into());
-        let param_tys: Vec<_> = ty
+    /// Returns the type with which values of the specified struct are passed to
+    /// and returned from functions in the public API, if they are passed by
+    /// value. Only `#[repr(C)]` value structs that consist of primitive types
+    /// and fit in two registers are passed by value, following the C calling
+    /// convention of the target. Returns `None` for all other structs.
+    pub fn get_public_struct_value_type(
+        &self,
+        struct_ty: mun_hir::Struct,
+    ) -> Option<BasicTypeEnum<'ink>> {
+        if struct_ty.is_extern(self.db)
+            || struct_ty.data(self.db.upcast()).repr != mun_hir::StructRepr::C
+        {
+            return None;
+        }
+
+        let mut scalars = Vec::new();
+        self.collect_struct_scalars(struct_ty, 0, &mut scalars)?;
+
+        let ir_ty = self.get_struct_type(struct_ty);
+        call_conv::coerced_struct_type(
+            self.context,
+            &self.db.target(),
+            self.target_data.get_abi_size(&ir_ty),
+            self.target_data.get_abi_alignment(&ir_ty).into(),
+            &scalars,
+        )
+    }
+
+    /// Collects the scalar fields of a value struct that is located at
+    /// `offset` bytes from the start of the outermost struct, including the
+    /// fields of nested value structs. Returns `None` if the struct contains
+    /// references or is packed.
+    fn collect_struct_scalars(
+        &self,
+        struct_ty: mun_hir::Struct,
+        offset: u64,
+        scalars: &mut Vec<call_conv::Scalar>,
+    ) -> Option<()> {
+        let data = struct_ty.data(self.db.upcast());
+        if struct_ty.is_extern(self.db)
+            || data.memory_kind != mun_hir::StructMemoryKind::Value
+            || data.repr == mun_hir::StructRepr::Packed
+        {
+            return None;
+        }
+
+        let ir_ty = self.get_struct_type(struct_ty);
+        for (idx, field) in struct_ty.fields(self.db).into_iter().enumerate() {
+            let field_offset = offset + self.target_data.offset_of_element(&ir_ty, idx as u32)?;
+            let field_ty = field.ty(self.db);
+            let is_float = match field_ty.interned() {
+                TyKind::Float(_) => true,
+                TyKind::Int(_) | TyKind::Bool => false,
+                TyKind::Struct(s) => {
+                    self.collect_struct_scalars(*s, field_offset, scalars)?;
+                    continue;
+                }
+                _ => return None,
+            };
+            let field_ir_ty = self.get_basic_type(&field_ty)?;
+            scalars.push(call_conv::Scalar {
+                offset: field_offset,
+                size: self.target_data.get_store_size(&field_ir_ty),
+                is_float,
+            });
+        }
+        Some(())
+    }
+
+    /// Returns the type of the specified function definition. A resumable
+    /// function returns nothing and takes a pointer to the context of its
+    /// coroutine as an additional parameter.
+    pub fn get_function_type(&self, function: mun_hir::Function) -> FunctionType<'ink> {
+        let ty = self.db.callable_sig(function.into());
+        let mut param_tys: Vec<_> = ty
             .params()
             .iter()
             .map(|p| {
@@ -222,6 +381,15 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
             })
             .collect();
 
+        if function.is_resumable(self.db) {
+            param_tys.push(
+                coroutine::context_type(self.context)
+                    .ptr_type(AddressSpace::default())
+                    .into(),
+            );
+            return self.context.void_type().fn_type(&param_tys, false);
+        }
+
         let return_type = ty.ret();
         match return_type.interned() {
             TyKind::Tuple(0, _) => self.context.void_type().fn_type(&param_tys, false),
@@ -268,6 +436,7 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
             TyKind::Struct(struct_ty) => Some(self.get_struct_reference_type(*struct_ty)),
             TyKind::Bool => Some(self.get_bool_type().into()),
             TyKind::Array(element_ty) => Some(self.get_array_reference_type(element_ty).into()),
+            TyKind::Result(ok_ty, err_ty) => Some(self.get_result_type(ok_ty, err_ty).into()),
             _ => None,
         }
     }
@@ -284,6 +453,13 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
             TyKind::Struct(struct_ty) => Some(self.get_public_struct_reference_type(*struct_ty)),
             TyKind::Bool => Some(self.get_bool_type().into()),
             TyKind::Array(element_ty) => Some(self.get_array_reference_type(element_ty).into()),
+            // Results are converted to GC types in the public API, like value structs.
+            TyKind::Result(ok_ty, err_ty) => Some(
+                self.get_result_type(ok_ty, err_ty)
+                    .ptr_type(AddressSpace::default())
+                    .ptr_type(AddressSpace::default())
+                    .into(),
+            ),
             _ => None,
         }
     }
@@ -304,6 +480,7 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
             }
             TyKind::Bool => Some(self.get_bool_type().into()),
             TyKind::Array(element_ty) => Some(self.get_array_reference_type(element_ty).into()),
+            TyKind::Result(ok_ty, err_ty) => Some(self.get_result_type(ok_ty, err_ty).into()),
             _ => None,
         }
     }
@@ -355,13 +532,21 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
                 }
             }
             TyKind::Bool => bool::type_id().clone(),
+            TyKind::Tuple(0, _) => <()>::type_id().clone(),
             &TyKind::Struct(s) => self
                 .struct_to_type_id
                 .borrow_mut()
                 .entry(s)
                 .or_insert_with(|| {
+                    // Extern structs are referred to by their plain name, the same name the
+                    // host uses to register them
+                    let name = if s.is_extern(self.db) {
+                        s.name(self.db).to_string()
+                    } else {
+                        s.full_name(self.db)
+                    };
                     Arc::new(TypeId {
-                        name: s.full_name(self.db),
+                        name,
                         data: TypeIdData::Concrete(guid_from_struct(self.db, s)),
                     })
                 })
@@ -388,12 +573,37 @@ impl<'db, 'ink> HirTypeCache<'db, 'ink> {
 
                 array_type_id
             }
+            TyKind::Result(ok_ty, err_ty) => {
+                {
+                    let read_only = self.result_ty_to_type_id.borrow();
+                    if let Some(r) = read_only.get(ty.interned()) {
+                        return r.clone();
+                    }
+                }
+
+                let ok_type_id = self.type_id(ok_ty);
+                let err_type_id = self.type_id(err_ty);
+                let result_type_id = Arc::new(TypeId {
+                    name: format!("Result<{}, {}>", &ok_type_id.name, &err_type_id.name),
+                    data: TypeIdData::Concrete(guid_from_result(self.db, ok_ty, err_ty)),
+                });
+
+                self.result_ty_to_type_id
+                    .borrow_mut()
+                    .insert(ty.interned().clone(), result_type_id.clone());
+
+                result_type_id
+            }
             _ => unimplemented!("{} unhandled", ty.display(self.db)),
         }
     }
 }
 
 pub fn guid_from_struct(db: &dyn HirDatabase, s: mun_hir::Struct) -> Guid {
+    if s.is_extern(db) {
+        return Guid::from_opaque_type_name(&s.name(db).to_string());
+    }
+
     let name = s.full_name(db);
     let fields: Vec<String> = s
         .fields(db)
@@ -407,9 +617,35 @@ pub fn guid_from_struct(db: &dyn HirDatabase, s: mun_hir::Struct) -> Guid {
         })
         .collect();
 
+    let repr = s.data(db.upcast()).repr;
     Guid::from_str(&format!(
-        "struct {name}{{{fields}}}",
+        "{repr}struct {name}{{{fields}}}",
+        repr = repr
+            .attribute()
+            .map_or(String::new(), |attr| format!("{attr} ")),
         name = &name,
         fields = fields.join(",")
     ))
 }
+
+/// The names of the fields of the struct that represents a `Result` type, in
+/// the order in which they are laid out in memory.
+pub const RESULT_FIELD_NAMES: [&str; 3] = ["is_err", "ok", "err"];
+
+pub fn guid_from_result(db: &dyn HirDatabase, ok_ty: &Ty, err_ty: &Ty) -> Guid {
+    let ok_ty_string = ok_ty
+        .guid_string(db)
+        .expect("type should be convertible to a string");
+    let err_ty_string = err_ty
+        .guid_string(db)
+        .expect("type should be convertible to a string");
+
+    Guid::from_str(&format!(
+        "struct Result<{ok}, {err}>{{{is_err}: core::bool,{ok_name}: {ok},{err_name}: {err}}}",
+        ok = ok_ty_string,
+        err = err_ty_string,
+        is_err = RESULT_FIELD_NAMES[0],
+        ok_name = RESULT_FIELD_NAMES[1],
+        err_name = RESULT_FIELD_NAMES[2],
+    ))
+}
@@ -205,6 +205,12 @@ impl<'db, 'ink, 't> TypeTableBuilder<'db, 'ink, 't> {
 
     /// Collects unique `TypeInfo` from the specified struct type.
     pub fn collect_struct(&mut self, hir_struct: mun_hir::Struct) {
+        // Extern structs are only collected when they are used, because every type in
+        // the table has to be provided by the host.
+        if hir_struct.is_extern(self.db) {
+            return;
+        }
+
         let type_info = self.hir_types.type_id(&hir_struct.ty(self.db));
         self.collect_type(type_info);
 
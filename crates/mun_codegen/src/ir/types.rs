@@ -46,6 +46,30 @@ impl<'ink> TransparentValue<'ink> for abi::StructMemoryKind {
     }
 }
 
+impl<'ink> TransparentValue<'ink> for abi::StructRepr {
+    type Target = u8;
+
+    fn as_target_value(&self, context: &IrValueContext<'ink, '_, '_>) -> Value<'ink, Self::Target> {
+        (*self as u8).as_value(context)
+    }
+
+    fn as_bytes_and_ptrs(&self, _: &IrTypeContext<'ink, '_>) -> Vec<BytesOrPtr<'ink>> {
+        vec![vec![*self as u8].into()]
+    }
+}
+
+impl<'ink> TransparentValue<'ink> for abi::FunctionKind {
+    type Target = u8;
+
+    fn as_target_value(&self, context: &IrValueContext<'ink, '_, '_>) -> Value<'ink, Self::Target> {
+        (*self as u8).as_value(context)
+    }
+
+    fn as_bytes_and_ptrs(&self, _: &IrTypeContext<'ink, '_>) -> Vec<BytesOrPtr<'ink>> {
+        vec![vec![*self as u8].into()]
+    }
+}
+
 #[derive(AsValue)]
 #[repr(u8)]
 pub enum TypeId<'ink> {
@@ -96,6 +120,7 @@ pub struct FunctionPrototype<'ink> {
 pub struct FunctionDefinition<'ink> {
     pub prototype: FunctionPrototype<'ink>,
     pub fn_ptr: Value<'ink, *const fn()>,
+    pub kind: abi::FunctionKind,
 }
 
 #[derive(AsValue)]
@@ -106,6 +131,16 @@ pub struct StructDefinition<'ink> {
     pub field_offsets: Value<'ink, *const u16>,
     pub num_fields: u16,
     pub memory_kind: abi::StructMemoryKind,
+    pub repr: abi::StructRepr,
+    pub passed_by_value: bool,
+}
+
+#[derive(AsValue)]
+pub struct FieldDefault<'ink> {
+    pub struct_guid: abi::Guid,
+    pub field_index: u16,
+    pub value_size: u16,
+    pub value: Value<'ink, *const u8>,
 }
 
 #[derive(AsValue)]
@@ -139,4 +174,9 @@ pub struct AssemblyInfo<'ink> {
     pub type_lut: TypeLut<'ink>,
     pub dependencies: Value<'ink, *const *const u8>,
     pub num_dependencies: u32,
+    pub capabilities: u32,
+    pub field_defaults: Value<'ink, *const FieldDefault<'ink>>,
+    pub num_field_defaults: u32,
+    pub private_functions: Value<'ink, *const FunctionDefinition<'ink>>,
+    pub num_private_functions: u32,
 }
@@ -32,14 +32,18 @@ fn abi_struct_sizes() {
     test_type_size::<abi::Guid, abi::Guid>(&type_context);
     test_type_size::<abi::Privacy, abi::Privacy>(&type_context);
     test_type_size::<abi::StructMemoryKind, abi::StructMemoryKind>(&type_context);
+    test_type_size::<abi::StructRepr, abi::StructRepr>(&type_context);
+    test_type_size::<abi::FunctionKind, abi::FunctionKind>(&type_context);
     test_type_size::<abi::TypeId<'_>, ir::TypeId<'_>>(&type_context);
     test_type_size::<abi::PointerTypeId<'_>, ir::PointerTypeId<'_>>(&type_context);
     test_type_size::<abi::ArrayTypeId<'_>, ir::ArrayTypeId<'_>>(&type_context);
     test_type_size::<abi::TypeDefinitionData<'_>, ir::TypeDefinitionData<'_>>(&type_context);
     test_type_size::<abi::StructDefinition<'_>, ir::StructDefinition<'_>>(&type_context);
     test_type_size::<abi::TypeDefinition<'_>, ir::TypeDefinition<'_>>(&type_context);
+    test_type_size::<abi::FieldDefault, ir::FieldDefault<'_>>(&type_context);
     test_type_size::<abi::FunctionSignature<'_>, ir::FunctionSignature<'_>>(&type_context);
     test_type_size::<abi::FunctionPrototype<'_>, ir::FunctionPrototype<'_>>(&type_context);
+    test_type_size::<abi::FunctionDefinition<'_>, ir::FunctionDefinition<'_>>(&type_context);
     test_type_size::<abi::ModuleInfo<'_>, ir::ModuleInfo<'_>>(&type_context);
     test_type_size::<abi::DispatchTable<'_>, ir::DispatchTable<'_>>(&type_context);
     test_type_size::<abi::TypeLut<'_>, ir::TypeLut<'_>>(&type_context);
@@ -88,6 +88,10 @@ impl Linker for LdLinker {
         // Link as dynamic library
         self.args.push("--shared".to_owned());
 
+        // The unwinder looks up the frames of Mun functions through the
+        // `PT_GNU_EH_FRAME` header, which allows panics to unwind through them
+        self.args.push("--eh-frame-hdr".to_owned());
+
         // Specify output path
         self.args.push("-o".to_owned());
         self.args.push(path_str.to_owned());
@@ -242,8 +246,20 @@ impl Linker for MsvcLinker {
         self.args.push(format!("/EXPORT:{}", abi::GET_INFO_FN_NAME));
         self.args
             .push(format!("/EXPORT:{}", abi::GET_VERSION_FN_NAME));
+        self.args
+            .push(format!("/EXPORT:{}", abi::GET_COMPILER_VERSION_FN_NAME));
+        self.args
+            .push(format!("/EXPORT:{}", abi::GET_INTEGRITY_FN_NAME));
         self.args
             .push(format!("/EXPORT:{}", abi::SET_ALLOCATOR_HANDLE_FN_NAME));
+        self.args
+            .push(format!("/EXPORT:{},DATA", abi::ABI_VERSION_SYMBOL_NAME));
+        self.args
+            .push(format!("/EXPORT:{},DATA", abi::ASSEMBLY_INFO_SYMBOL_NAME));
+        self.args.push(format!(
+            "/EXPORT:{},DATA",
+            abi::COMPILER_VERSION_SYMBOL_NAME
+        ));
         self.args.push(format!("/IMPLIB:{dll_lib_path_str}"));
         self.args.push(format!("/OUT:{dll_path_str}"));
         Ok(())
@@ -35,6 +35,15 @@ impl salsa::Database for MockDatabase {
     }
 }
 
+impl salsa::ParallelDatabase for MockDatabase {
+    fn snapshot(&self) -> salsa::Snapshot<Self> {
+        salsa::Snapshot::new(MockDatabase {
+            storage: self.storage.snapshot(),
+            events: Mutex::default(),
+        })
+    }
+}
+
 impl mun_db::Upcast<dyn mun_hir::AstDatabase> for MockDatabase {
     fn upcast(&self) -> &(dyn mun_hir::AstDatabase + 'static) {
         self
@@ -72,6 +81,9 @@ impl Default for MockDatabase {
             events: Mutex::default(),
         };
         db.set_optimization_level(OptimizationLevel::Default);
+        db.set_overflow_checks(false);
+        db.set_lto(false);
+        db.set_profiling(false);
         db.set_target(Target::host_target().unwrap());
         db
     }
@@ -89,6 +89,12 @@ impl ModuleGroup {
             return false;
         }
 
+        // Tests and benchmarks are invoked by their harness, regardless of their
+        // visibility.
+        if function.is_test(db) || function.is_bench(db) {
+            return true;
+        }
+
         let vis = function.visibility(db);
         match vis {
             // If the function is publicly accessible it must always be exported
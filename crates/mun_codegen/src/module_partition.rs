@@ -71,23 +71,33 @@ impl Index<ModuleGroupId> for ModulePartition {
     }
 }
 
-/// Builds a module partition from the contents of the database
+/// Builds a module partition from the contents of the database. Every module
+/// of the main package forms a group of its own, whereas all modules of a
+/// library package are grouped together in a single assembly named after the
+/// package.
 pub(crate) fn build_partition(db: &dyn CodeGenDatabase) -> Arc<ModulePartition> {
     let mut partition = ModulePartition::default();
-    for module in mun_hir::Package::all(db.upcast())
-        .into_iter()
-        .flat_map(|package| package.modules(db.upcast()))
-    {
-        let name = if module.name(db.upcast()).is_some() {
-            module.full_name(db.upcast())
-        } else {
-            String::from("mod")
-        };
+    for package in mun_hir::Package::all(db.upcast()) {
+        let modules = package.modules(db.upcast());
+        if let Some(name) = package.name(db.upcast()) {
+            partition.add_group(db.upcast(), ModuleGroup::new(db.upcast(), name, modules));
+            continue;
+        }
+
+        for module in modules {
+            // The root module of a package without a name has no name either
+            let name = module.full_name(db.upcast());
+            let name = if name.is_empty() {
+                String::from("mod")
+            } else {
+                name
+            };
 
-        partition.add_group(
-            db.upcast(),
-            ModuleGroup::new(db.upcast(), name, vec![module]),
-        );
+            partition.add_group(
+                db.upcast(),
+                ModuleGroup::new(db.upcast(), name, vec![module]),
+            );
+        }
     }
     Arc::new(partition)
 }
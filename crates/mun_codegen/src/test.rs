@@ -1,14 +1,19 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, collections::BTreeMap};
 
-use inkwell::{context::Context, OptimizationLevel};
+use inkwell::{context::Context, values::AnyValue, OptimizationLevel};
 use mun_db::Upcast;
 use mun_hir::{diagnostics::DiagnosticSink, HirDatabase};
 use mun_hir_input::{SourceDatabase, WithFixture};
 use mun_target::spec::Target;
+use rayon::prelude::*;
+use salsa::ParallelDatabase;
 
 use crate::{
     code_gen::{AssemblyBuilder, CodeGenContext},
-    ir::{file::gen_file_ir, file_group::gen_file_group_ir},
+    ir::{
+        file::{gen_file_ir, FunctionBodies},
+        file_group::gen_file_group_ir,
+    },
     mock::MockDatabase,
     CodeGenDatabase,
 };
@@ -1070,17 +1075,351 @@ fn nested_private_recursive_fn_with_args() {
     );
 }
 
+#[test]
+fn cast_expr() {
+    test_snapshot_unoptimized(
+        "cast_expr",
+        r"
+    pub fn int_to_float(a: i32) -> f64 { a as f64 }
+    pub fn uint_to_float(a: u64) -> f32 { a as f32 }
+    pub fn float_to_int(a: f64) -> i16 { a as i16 }
+    pub fn float_to_uint(a: f32) -> u32 { a as u32 }
+    pub fn sign_extend(a: i8) -> i64 { a as i64 }
+    pub fn zero_extend(a: u8) -> i64 { a as i64 }
+    pub fn truncate(a: i32) -> u8 { a as u8 }
+    pub fn reinterpret_sign(a: i32) -> u32 { a as u32 }
+    pub fn widen_float(a: f32) -> f64 { a as f64 }
+    pub fn narrow_float(a: f64) -> f32 { a as f32 }
+    pub fn bool_to_int(a: bool) -> usize { a as usize }
+    ",
+    );
+}
+
+#[test]
+fn overflow_checked_arithmetic() {
+    test_snapshot_overflow_checked(
+        "overflow_checked_arithmetic",
+        r"
+    pub fn add(a: u8, b: u8) -> u8 { a + b }
+    pub fn sub(a: i32, b: i32) -> i32 { a - b }
+    pub fn mul(a: u64, b: u64) -> u64 { a * b }
+    pub fn negate(a: i32) -> i32 { -a }
+    pub fn add_assign(a: i16, b: i16) -> i16 {
+        a += b;
+        a
+    }
+    ",
+    );
+}
+
+#[test]
+fn try_expr() {
+    test_snapshot_unoptimized(
+        "try_expr",
+        r"
+    fn check(a: i32) -> Result<i32, bool> {
+        if a < 0 { Err(false) } else { Ok(a) }
+    }
+
+    pub fn double(a: i32) -> Result<i32, bool> {
+        let b = check(a)?;
+        Ok(b * 2)
+    }
+    ",
+    );
+}
+
+#[test]
+fn struct_repr() {
+    test_snapshot_unoptimized(
+        "struct_repr",
+        r#"
+    #[repr(C)]
+    pub struct(value) Particle { alive: bool, position: f64, id: u16 }
+
+    #[repr(packed)]
+    pub struct(value) PackedParticle { alive: bool, position: f64, id: u16 }
+
+    pub fn position(p: Particle) -> f64 {
+        p.position
+    }
+
+    pub fn packed_position(p: PackedParticle) -> f64 {
+        p.position
+    }
+
+    pub fn set_packed_id(p: PackedParticle, id: u16) -> PackedParticle {
+        p.id = id;
+        p
+    }
+    "#,
+    );
+}
+
+#[test]
+fn struct_by_value_calling_convention() {
+    test_snapshot(
+        "struct_by_value_calling_convention",
+        r#"
+    #[repr(C)]
+    pub struct(value) Vector2 { x: f32, y: f32 }
+
+    #[repr(C)]
+    pub struct(value) Mixed { a: i32, b: f32, c: u8 }
+
+    #[repr(C)]
+    pub struct(value) Large { a: f64, b: f64, c: f64 }
+
+    pub struct(value) Default { x: f32, y: f32 }
+
+    pub fn add(a: Vector2, b: Vector2) -> Vector2 {
+        Vector2 { x: a.x + b.x, y: a.y + b.y }
+    }
+
+    pub fn mixed(m: Mixed) -> Mixed {
+        m
+    }
+
+    pub fn large(l: Large) -> Large {
+        l
+    }
+
+    pub fn default(d: Default) -> Default {
+        d
+    }
+    "#,
+    );
+}
+
+#[test]
+fn for_expr() {
+    test_snapshot_unoptimized(
+        "for_expr",
+        r#"
+    pub fn sum_range(n: i32) -> i32 {
+        let sum = 0;
+        for i in 0..n {
+            sum += i;
+        }
+        sum
+    }
+
+    pub fn sum_inclusive_range(n: u8) -> u32 {
+        let sum = 0;
+        for i in 0..=n {
+            sum += i as u32;
+        }
+        sum
+    }
+
+    pub fn sum_until(array: [i32], limit: i32) -> i32 {
+        let sum = 0;
+        for value in array {
+            if value > limit {
+                break;
+            }
+            sum += value;
+        }
+        sum
+    }
+    "#,
+    );
+}
+
+#[test]
+fn log_expr() {
+    test_snapshot_unoptimized(
+        "log_expr",
+        r#"
+    pub fn update(health: i32, speed: f32, count: u64) {
+        print("health = {}, speed = {}, count = {}", health, speed, count);
+        log("player is {{moving}}: {}", speed > 0.0);
+        print("no arguments");
+    }
+    "#,
+    );
+}
+
+#[test]
+fn struct_field_defaults() {
+    test_snapshot_unoptimized(
+        "struct_field_defaults",
+        r#"
+    pub struct(gc) Enemy { hp: i32 = 100, speed: f32 = -1.5, alive: bool = true, level: u8 }
+    pub struct(value) Point { x: f64 = 1.0, y: f64 = 2.0 }
+
+    pub fn new_enemy(level: u8) -> Enemy {
+        Enemy { level }
+    }
+
+    pub fn fast_enemy(level: u8) -> Enemy {
+        Enemy { speed: 3.0, level, hp: 50 }
+    }
+
+    pub fn origin() -> Point {
+        Point { }
+    }
+    "#,
+    );
+}
+
+#[test]
+fn resumable_fn() {
+    test_snapshot_unoptimized(
+        "resumable_fn",
+        r#"
+    pub resumable fn wait(seconds: f32) {
+        let elapsed = 0.0;
+        while elapsed < seconds {
+            elapsed += yield;
+        }
+    }
+
+    pub struct(gc) Door { open: bool }
+
+    pub resumable fn open(door: Door) {
+        wait(1.0);
+        door.open = true;
+    }
+    "#,
+    );
+}
+
+#[test]
+fn parallel_module_build_matches_serial_build() {
+    let mut db = MockDatabase::with_files(
+        r#"
+    //- /mod.mun
+    pub fn main() {}
+
+    //- /mod.mun package:game
+    #[repr(C)]
+    pub struct(value) Vector2 { x: f32, y: f32 }
+
+    pub fn update(position: Vector2, speed: f32) -> Vector2 {
+        physics::step(position, ai::think(speed))
+    }
+
+    //- /physics.mun package:game
+    use super::Vector2;
+
+    pub(package) fn step(position: Vector2, velocity: Vector2) -> Vector2 {
+        Vector2 { x: position.x + velocity.x, y: position.y + velocity.y }
+    }
+
+    //- /ai.mun package:game
+    use super::Vector2;
+
+    pub(package) fn think(speed: f32) -> Vector2 {
+        Vector2 { x: speed, y: -speed }
+    }
+    "#,
+    );
+    db.set_optimization_level(OptimizationLevel::None);
+    let module_partition = db.module_partition();
+    assert!(
+        module_partition
+            .iter()
+            .any(|(_, module_group)| module_group.iter().count() > 1),
+        "expected a module group with multiple modules"
+    );
+
+    for (module_group_id, module_group) in module_partition.iter() {
+        // Build the entire assembly as a single LLVM module
+        let llvm_context = Context::create();
+        let code_gen = CodeGenContext::new(&llvm_context, db.upcast());
+        let assembly = AssemblyBuilder::new(&code_gen, &module_partition, module_group_id)
+            .build()
+            .expect("Failed to build assembly");
+        let serial = defined_functions(assembly.module());
+
+        // Build the symbols and each module in its own LLVM context, in parallel
+        let jobs: Vec<_> = std::iter::once(None)
+            .chain(module_group.iter().map(Some))
+            .map(|module| (db.snapshot(), module))
+            .collect();
+        let parallel = jobs
+            .into_par_iter()
+            .map(|(db, module)| {
+                let llvm_context = Context::create();
+                let code_gen = CodeGenContext::new(&llvm_context, db.upcast());
+                let module_partition = db.module_partition();
+                let builder = AssemblyBuilder::new(&code_gen, &module_partition, module_group_id);
+                let assembly = match module {
+                    Some(module) => builder.build_module(module),
+                    None => builder.build_symbols(),
+                }
+                .expect("Failed to build assembly");
+                defined_functions(assembly.module())
+            })
+            .reduce(BTreeMap::new, |mut functions, other| {
+                for (name, ir) in other {
+                    assert!(
+                        functions.insert(name.clone(), ir).is_none(),
+                        "function `{name}` is defined more than once"
+                    );
+                }
+                functions
+            });
+
+        assert_eq!(serial, parallel, "in module group `{}`", module_group.name);
+    }
+}
+
+/// Returns the IR of the functions that are defined in `module` by their name.
+/// The linkage, visibility and attribute groups of the functions are omitted,
+/// because they differ between assemblies that are built as a whole and per
+/// module.
+fn defined_functions(module: &inkwell::module::Module<'_>) -> BTreeMap<String, String> {
+    module
+        .get_functions()
+        .filter(|function| !function.as_global_value().is_declaration())
+        .map(|function| {
+            let name = function.get_name().to_string_lossy().into_owned();
+            let ir = function
+                .print_to_string()
+                .to_string()
+                .replace("define hidden ", "define ")
+                .replace("define private ", "define ")
+                .lines()
+                .map(|line| {
+                    line.split(' ')
+                        .filter(|token| {
+                            !token
+                                .strip_prefix('#')
+                                .is_some_and(|id| id.bytes().all(|b| b.is_ascii_digit()))
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            (name, ir)
+        })
+        .collect()
+}
+
 fn test_snapshot(name: &str, text: &str) {
-    test_snapshot_with_optimization(name, text, OptimizationLevel::Default);
+    test_snapshot_with_options(name, text, OptimizationLevel::Default, false);
 }
 
 fn test_snapshot_unoptimized(name: &str, text: &str) {
-    test_snapshot_with_optimization(name, text, OptimizationLevel::None);
+    test_snapshot_with_options(name, text, OptimizationLevel::None, false);
+}
+
+fn test_snapshot_overflow_checked(name: &str, text: &str) {
+    test_snapshot_with_options(name, text, OptimizationLevel::None, true);
 }
 
-fn test_snapshot_with_optimization(name: &str, text: &str, opt: OptimizationLevel) {
+fn test_snapshot_with_options(
+    name: &str,
+    text: &str,
+    opt: OptimizationLevel,
+    overflow_checks: bool,
+) {
     let mut db = MockDatabase::with_files(text);
     db.set_optimization_level(opt);
+    db.set_overflow_checks(overflow_checks);
     db.set_target(Target::host_target().unwrap());
 
     // Build and extra diagnostics
@@ -1117,7 +1456,7 @@ fn test_snapshot_with_optimization(name: &str, text: &str, opt: OptimizationLeve
     let value = if messages.is_empty() {
         itertools::Itertools::intersperse(module_parition.iter().map(|(module_group_id, module_group)| {
             let group_ir = gen_file_group_ir(&code_gen, module_group);
-            let file_ir = gen_file_ir(&code_gen, &group_ir, module_group);
+            let file_ir = gen_file_ir(&code_gen, &group_ir, module_group, FunctionBodies::All);
 
             let group_ir = group_ir.llvm_module.print_to_string().to_string();
             // println!("=== GROUP IR:\n {} ",&group_ir);
@@ -1128,6 +1467,14 @@ fn test_snapshot_with_optimization(name: &str, text: &str, opt: OptimizationLeve
             let module_builder = AssemblyBuilder::new(&code_gen, &module_parition, module_group_id);
             let _obj_file = module_builder.build().expect("Failed to build object file");
 
+            // And the symbols and modules of assemblies that are built in parallel
+            let module_builder = AssemblyBuilder::new(&code_gen, &module_parition, module_group_id);
+            let _obj_file = module_builder.build_symbols().expect("Failed to build object file");
+            for module in module_group.iter() {
+                let module_builder = AssemblyBuilder::new(&code_gen, &module_parition, module_group_id);
+                let _obj_file = module_builder.build_module(module).expect("Failed to build object file");
+            }
+
             format!(
                 "; == FILE IR ({}) =====================================\n{}\n; == GROUP IR ({}) ====================================\n{}",
                 module_group.relative_file_path(),
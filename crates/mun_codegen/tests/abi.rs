@@ -42,6 +42,7 @@ fn test_abi_compatibility() {
                 return_type: None,
               ),
             ),
+            kind: Normal,
           ),
           FunctionDefinition(
             prototype: FunctionPrototype(
@@ -53,6 +54,7 @@ fn test_abi_compatibility() {
                 return_type: Some(Concrete("17797a74-19d6-3217-d235-954317885bfa")),
               ),
             ),
+            kind: Normal,
           ),
         ],
         types: [
@@ -75,6 +77,8 @@ fn test_abi_compatibility() {
                 ),
               ],
               memory_kind: Value,
+              repr: Default,
+              passed_by_value: false,
             )),
           ),
           TypeDefinition(
@@ -96,6 +100,8 @@ fn test_abi_compatibility() {
                 ),
               ],
               memory_kind: Gc,
+              repr: Default,
+              passed_by_value: false,
             )),
           ),
         ],
@@ -177,3 +183,52 @@ fn test_abi_compatibility() {
     )
     "#);
 }
+
+#[test]
+fn test_struct_repr_field_offsets() {
+    let driver = CompileTestDriver::from_file(
+        r#"
+    pub struct(value) Particle { alive: bool, position: f64, id: u16 }
+
+    #[repr(C)]
+    pub struct(value) CParticle { alive: bool, position: f64, id: u16 }
+
+    #[repr(packed)]
+    pub struct(value) PackedParticle { alive: bool, position: f64, id: u16 }
+    "#,
+    );
+
+    // Safety: We compiled the code ourselves, therefor loading the library is safe
+    let lib = unsafe { MunLibrary::new(driver.lib_path()) }
+        .expect("Failed to load generated Mun library.");
+    let info = unsafe { lib.get_info() };
+
+    let layout = |name: &str| {
+        let ty = info
+            .symbols
+            .types()
+            .iter()
+            .find(|ty| ty.name() == name)
+            .unwrap_or_else(|| panic!("missing type `{name}`"));
+        let s = ty.as_struct().expect("expected a struct");
+        (
+            s.repr,
+            s.field_offsets().to_vec(),
+            ty.size_in_bytes(),
+            ty.alignment(),
+        )
+    };
+
+    assert_eq!(
+        layout("Particle"),
+        (abi::StructRepr::Default, vec![0, 8, 16], 24, 8)
+    );
+    assert_eq!(
+        layout("CParticle"),
+        (abi::StructRepr::C, vec![0, 8, 16], 24, 8)
+    );
+    assert_eq!(
+        layout("PackedParticle"),
+        (abi::StructRepr::Packed, vec![0, 1, 9], 11, 1)
+    );
+}
@@ -633,6 +633,11 @@ pub fn as_value_derive(input: TokenStream) -> TokenStream {
                         // same variable name.
                         let type_context = context;
 
+                        // Construct the type of the enum before the types of its variants' fields.
+                        // Otherwise, a field that refers back to the enum would be sized while the
+                        // enum is still opaque.
+                        <Self as crate::value::SizedValueType>::get_ir_type(type_context);
+
                         // The chunk size is the same as the tag's size
                         let chunk_ty = <#repr_ty>::get_ir_type(type_context);
                         let chunk_size = std::mem::size_of::<#repr_ty>();
@@ -64,8 +64,19 @@ impl CompilerDatabase {
     /// Applies the given configuration to the database
     pub fn set_config(&mut self, config: &Config) {
         self.set_target(config.target.clone());
-        self.set_optimization_level(config.optimization_lvl);
+        self.set_optimization_level(config.optimization_lvl());
+        self.set_lto(config.lto());
+        self.set_profiling(config.profiling);
+        self.set_overflow_checks(config.overflow_checks());
     }
 }
 
 impl salsa::Database for CompilerDatabase {}
+
+impl salsa::ParallelDatabase for CompilerDatabase {
+    fn snapshot(&self) -> salsa::Snapshot<Self> {
+        salsa::Snapshot::new(CompilerDatabase {
+            storage: self.storage.snapshot(),
+        })
+    }
+}
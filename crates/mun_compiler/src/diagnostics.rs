@@ -67,6 +67,25 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_mismatched_numeric_type_error() {
+        insta::assert_snapshot!(compilation_errors(
+            "\n\nfn main(a: i64, b: u8) {\nlet c: i32 = a;\n\nlet d: i32 = b;\n}"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_cast_error() {
+        insta::assert_snapshot!(compilation_errors(
+            "\n\nfn main(a: f64) {\nlet b = a as bool;\n}"
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_field_default_error() {
+        insta::assert_snapshot!(compilation_errors("\n\nstruct Foo {\na: i32 = 1 + 2,\n}"));
+    }
+
     #[test]
     fn test_duplicate_definition_error() {
         insta::assert_snapshot!(compilation_errors(
@@ -107,4 +126,11 @@ mod tests {
     fn test_expected_function() {
         insta::assert_snapshot!(compilation_errors("\n\nfn foo() { let a = 3; a(); }"));
     }
+
+    #[test]
+    fn test_lint_warnings() {
+        insta::assert_snapshot!(compilation_errors(
+            "\n\npub fn main() {\nlet a = 3;\nreturn;\nlet b = 4;\n}\n\nfn foo() {}"
+        ));
+    }
 }
@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use annotate_snippets::{Annotation, AnnotationType, Renderer, Slice, Snippet, SourceAnnotation};
 use mun_diagnostics::DiagnosticForWith;
-use mun_hir::HirDatabase;
+use mun_hir::{diagnostics::Severity, HirDatabase};
 use mun_hir_input::{FileId, LineIndex};
 use mun_paths::RelativePathBuf;
 use mun_syntax::SyntaxError;
@@ -61,14 +61,26 @@ pub(crate) fn emit_hir_diagnostic(
     display_colors: bool,
     writer: &mut dyn std::io::Write,
 ) -> std::io::Result<()> {
+    let annotation_type = match diagnostic.severity() {
+        Severity::Error => AnnotationType::Error,
+        Severity::Warning => AnnotationType::Warning,
+    };
     diagnostic.with_diagnostic(db, |diagnostic| {
-        emit_diagnostic(diagnostic, db, file_id, display_colors, writer)
+        emit_diagnostic(
+            diagnostic,
+            annotation_type,
+            db,
+            file_id,
+            display_colors,
+            writer,
+        )
     })
 }
 
 /// Emits a diagnostic by writting a snippet to the specified `writer`.
 fn emit_diagnostic(
     diagnostic: &dyn mun_diagnostics::Diagnostic,
+    annotation_type: AnnotationType,
     db: &impl HirDatabase,
     file_id: FileId,
     display_colors: bool,
@@ -140,7 +152,7 @@ fn emit_diagnostic(
         title: Some(Annotation {
             id: None,
             label: Some(&title),
-            annotation_type: AnnotationType::Error,
+            annotation_type,
         }),
         slices: annotations
             .iter()
@@ -170,7 +182,7 @@ fn emit_diagnostic(
                                 usize::from(annotation.range.end()) - line_offset,
                             ),
                             label: annotation.message.as_str(),
-                            annotation_type: AnnotationType::Error,
+                            annotation_type,
                         })
                         .collect(),
                     fold: true,
@@ -1,9 +1,9 @@
 //! `Driver` is a stateful compiler frontend that enables incremental
 //! compilation by retaining state from previous compilation.
 
-use mun_codegen::{AssemblyIr, CodeGenDatabase, ModuleGroup, TargetAssembly};
-use mun_hir::{AstDatabase, DiagnosticSink, Module};
-use mun_hir_input::{FileId, PackageSet, SourceDatabase, SourceRoot, SourceRootId};
+use mun_codegen::{AssemblyAsm, AssemblyIr, CodeGenDatabase, ModuleGroup, TargetAssembly};
+use mun_hir::{AstDatabase, DiagnosticSink, HirDatabase, HirDisplay, Module, ModuleDef};
+use mun_hir_input::{FileId, PackageId, PackageSet, SourceDatabase, SourceRoot, SourceRootId};
 use mun_paths::RelativePathBuf;
 
 use crate::{
@@ -13,9 +13,11 @@ use crate::{
 
 mod config;
 mod display_color;
+mod engine_api;
+mod source_generator;
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     convert::TryInto,
     io::Cursor,
     path::{Path, PathBuf},
@@ -24,14 +26,44 @@ use std::{
 };
 
 use mun_db::Upcast;
+use mun_libloader::MunLibrary;
 use mun_project::{Package, LOCKFILE_NAME};
+pub use mun_std::{CORE_PACKAGE_NAME, STD_PACKAGE_NAME};
+use mun_target::spec::Target;
 use walkdir::WalkDir;
 
-pub use self::{config::Config, display_color::DisplayColor};
+pub use self::{
+    config::{optimization_level_from_number, Config, Emit, Seal},
+    display_color::DisplayColor,
+    engine_api::EngineApiGenerator,
+    source_generator::{GeneratedSource, SourceGenerator},
+};
 use crate::diagnostics_snippets::{emit_hir_diagnostic, emit_syntax_error};
 
 pub const WORKSPACE: SourceRootId = SourceRootId(0);
 
+/// The source root that contains the sources of the `core` package.
+pub const CORE: SourceRootId = SourceRootId(1);
+
+/// The source root that contains the sources of the `std` package.
+pub const STD: SourceRootId = SourceRootId(2);
+
+/// The extension of the files that describe the `AssemblyInfo` of a munlib,
+/// see [`Emit::abi`].
+pub const ABI_EXTENSION: &str = "abi";
+
+/// An assembly that was built by the [`Driver`] and returned to the caller
+/// instead of being written to the output directory, see
+/// [`Driver::build_changed_assemblies`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InMemoryAssembly {
+    /// The path to which the [`Driver`] would write the assembly
+    pub path: PathBuf,
+
+    /// The contents of the assembly's shared library
+    pub bytes: Vec<u8>,
+}
+
 pub struct Driver {
     db: CompilerDatabase,
     out_dir: PathBuf,
@@ -43,7 +75,15 @@ pub struct Driver {
 
     module_to_temp_assembly_path: HashMap<Module, PathBuf>,
 
-    emit_ir: bool,
+    source_generators: Vec<Box<dyn SourceGenerator + Send>>,
+    generated_files: HashSet<RelativePathBuf>,
+
+    /// The contents of the files that are hidden by an overlay, or `None` if
+    /// the file did not exist before it was overlaid.
+    overlaid_files: HashMap<FileId, Option<Arc<str>>>,
+
+    emit: Emit,
+    seal: Option<Seal>,
 }
 
 impl Driver {
@@ -57,7 +97,11 @@ impl Driver {
             file_id_to_path: HashMap::default(),
             next_file_id: 0,
             module_to_temp_assembly_path: HashMap::default(),
-            emit_ir: config.emit_ir,
+            source_generators: Vec::new(),
+            generated_files: HashSet::default(),
+            overlaid_files: HashMap::default(),
+            emit: config.emit,
+            seal: config.seal,
         }
     }
 
@@ -90,6 +134,9 @@ impl Driver {
 
         let mut package_set = PackageSet::default();
         package_set.add_package(WORKSPACE);
+        driver.add_core_package(&mut package_set);
+        let std = driver.add_std_package(&mut package_set);
+        add_std_dependencies(&mut package_set, std);
         driver.db.set_packages(Arc::new(package_set));
 
         driver.path_to_file_id.insert(rel_path, file_id);
@@ -104,6 +151,22 @@ impl Driver {
     ) -> Result<(Package, Driver), anyhow::Error> {
         // Load the manifest file as a package
         let package = Package::from_file(package_path)?;
+        package.check_mun_version(&toolchain_version())?;
+
+        // Command line settings take precedence over the settings of the package
+        let mut config = config;
+        if config.overflow_checks.is_none() {
+            config.overflow_checks = package.manifest().overflow_checks();
+        }
+        if config.optimization_lvl.is_none() {
+            config.optimization_lvl = package
+                .manifest()
+                .opt_level()
+                .and_then(optimization_level_from_number);
+        }
+        if config.lto.is_none() {
+            config.lto = package.manifest().lto();
+        }
 
         // Determine output directory
         let output_dir = ensure_package_output_dir(&package, &config)
@@ -119,18 +182,7 @@ impl Driver {
             anyhow::bail!("the source directory does not exist")
         }
 
-        for source_file_path in iter_source_files(&source_directory) {
-            let relative_path = compute_source_relative_path(&source_directory, &source_file_path)?;
-
-            // Load the contents of the file
-            let file_contents = std::fs::read_to_string(&source_file_path).map_err(|e| {
-                anyhow::anyhow!(
-                    "could not read contents of '{}': {}",
-                    source_file_path.display(),
-                    e
-                )
-            })?;
-
+        for (relative_path, file_contents) in read_source_files(&source_directory)? {
             let file_id = driver.alloc_file_id(&relative_path)?;
             driver.db.set_file_text(file_id, Arc::from(file_contents));
             driver.db.set_file_source_root(file_id, WORKSPACE);
@@ -145,9 +197,23 @@ impl Driver {
             .set_source_root(WORKSPACE, Arc::new(driver.source_root.clone()));
 
         let mut package_set = PackageSet::default();
-        package_set.add_package(WORKSPACE);
+        let package_id = package_set.add_package(WORKSPACE);
+        driver.add_core_package(&mut package_set);
+        let std = driver.add_std_package(&mut package_set);
+        driver.add_dependencies(
+            &mut package_set,
+            &package,
+            package_id,
+            &mut DependencyGraph::new(&package)?,
+        )?;
+        add_std_dependencies(&mut package_set, std);
         driver.db.set_packages(Arc::new(package_set));
 
+        // Generate the extern declarations of the engine API
+        if let Some(engine_api_path) = package.engine_api_path() {
+            driver.add_source_generator(EngineApiGenerator::new(engine_api_path)?)?;
+        }
+
         Ok((package, driver))
     }
 }
@@ -188,6 +254,169 @@ impl Driver {
     }
 }
 
+impl Driver {
+    /// Adds the `core` package to the database and makes it the prelude of all
+    /// other packages in the `package_set`. Its sources are embedded in the
+    /// compiler, so they are not part of the paths known to the driver.
+    fn add_core_package(&mut self, package_set: &mut PackageSet) {
+        let sources = mun_std::CORE_PACKAGE_SOURCES
+            .iter()
+            .map(|(rel_path, contents)| (RelativePathBuf::from(*rel_path), *contents));
+        let core = self.add_library_package(package_set, CORE_PACKAGE_NAME, CORE, sources);
+        package_set.set_prelude(core);
+    }
+
+    /// Adds the `std` package to the database. Its sources are embedded in the
+    /// compiler, so they are not part of the paths known to the driver.
+    fn add_std_package(&mut self, package_set: &mut PackageSet) -> PackageId {
+        let sources = mun_std::STD_PACKAGE_SOURCES
+            .iter()
+            .map(|(rel_path, contents)| (RelativePathBuf::from(*rel_path), *contents));
+        self.add_library_package(package_set, STD_PACKAGE_NAME, STD, sources)
+    }
+
+    /// Adds the packages that `package` depends on to the database, together
+    /// with their own dependencies. A package that is depended on multiple
+    /// times is only added once.
+    ///
+    /// The sources of dependencies are not part of the paths known to the
+    /// driver, so changes to them are not picked up until the driver is
+    /// recreated.
+    fn add_dependencies(
+        &mut self,
+        package_set: &mut PackageSet,
+        package: &Package,
+        package_id: PackageId,
+        graph: &mut DependencyGraph,
+    ) -> anyhow::Result<()> {
+        for (name, manifest_path) in package.dependency_manifest_paths() {
+            let manifest_path = manifest_path.canonicalize().map_err(|e| {
+                anyhow::anyhow!(
+                    "could not find dependency '{name}' of package '{}' at '{}': {e}",
+                    package.name(),
+                    manifest_path.display()
+                )
+            })?;
+
+            if graph.in_progress.contains(&manifest_path) {
+                anyhow::bail!(
+                    "package '{}' has a cyclic dependency on '{name}'",
+                    package.name()
+                );
+            }
+
+            let dependency_id = if let Some(&dependency_id) = graph.loaded.get(&manifest_path) {
+                dependency_id
+            } else {
+                let dependency = Package::from_file(&manifest_path)?;
+                dependency.check_mun_version(&toolchain_version())?;
+                let dependency_name = dependency.name().replace('-', "_");
+                let is_name_taken = package_set
+                    .iter()
+                    .any(|id| package_set[id].name.as_deref() == Some(dependency_name.as_str()));
+                if is_name_taken {
+                    anyhow::bail!(
+                        "the name of dependency '{name}' conflicts with another package called \
+                         '{dependency_name}'"
+                    );
+                }
+
+                let source_directory = dependency.source_directory();
+                if !source_directory.is_dir() {
+                    anyhow::bail!("the source directory of dependency '{name}' does not exist");
+                }
+                let sources = read_source_files(&source_directory)?;
+
+                // Every package has its own source root
+                let source_root_id = SourceRootId(
+                    package_set
+                        .iter()
+                        .count()
+                        .try_into()
+                        .map_err(|_e| anyhow::anyhow!("too many dependencies"))?,
+                );
+                let dependency_id = self.add_library_package(
+                    package_set,
+                    &dependency_name,
+                    source_root_id,
+                    sources,
+                );
+
+                graph.in_progress.push(manifest_path.clone());
+                self.add_dependencies(package_set, &dependency, dependency_id, graph)?;
+                graph.in_progress.pop();
+                graph.loaded.insert(manifest_path, dependency_id);
+                dependency_id
+            };
+
+            package_set.add_dependency(package_id, name, dependency_id);
+        }
+        Ok(())
+    }
+
+    /// Adds a library package called `name` with the specified `sources` to
+    /// the database. The files of the package are not part of the paths known
+    /// to the driver.
+    fn add_library_package<S: Into<Arc<str>>>(
+        &mut self,
+        package_set: &mut PackageSet,
+        name: &str,
+        source_root_id: SourceRootId,
+        sources: impl IntoIterator<Item = (RelativePathBuf, S)>,
+    ) -> PackageId {
+        let mut source_root = SourceRoot::default();
+        for (rel_path, contents) in sources {
+            let file_id = FileId(self.next_file_id as u32);
+            self.next_file_id += 1;
+            self.db.set_file_text(file_id, contents.into());
+            self.db.set_file_source_root(file_id, source_root_id);
+            source_root.insert_file(file_id, rel_path);
+        }
+        self.db
+            .set_source_root(source_root_id, Arc::new(source_root));
+
+        package_set.add_library(name, source_root_id)
+    }
+}
+
+/// Returns the version of the Mun toolchain that the compiler is part of.
+fn toolchain_version() -> semver::Version {
+    semver::Version::parse(env!("CARGO_PKG_VERSION"))
+        .expect("the version of the compiler is not a valid semantic version")
+}
+
+/// Makes the `std` package available to all packages in `package_set`, except
+/// for the prelude and `std` itself.
+fn add_std_dependencies(package_set: &mut PackageSet, std: PackageId) {
+    let packages = package_set
+        .iter()
+        .filter(|&package| package != std && Some(package) != package_set.prelude())
+        .collect::<Vec<_>>();
+    for package in packages {
+        package_set.add_dependency(package, STD_PACKAGE_NAME, std);
+    }
+}
+
+/// Keeps track of the dependencies that were loaded by
+/// [`Driver::add_dependencies`], keyed by the canonical path of their manifest.
+struct DependencyGraph {
+    /// The packages that were loaded
+    loaded: HashMap<PathBuf, PackageId>,
+
+    /// The packages whose dependencies are being loaded, used to detect cycles
+    in_progress: Vec<PathBuf>,
+}
+
+impl DependencyGraph {
+    /// Constructs a graph that starts at the specified root `package`.
+    fn new(package: &Package) -> anyhow::Result<Self> {
+        Ok(Self {
+            loaded: HashMap::new(),
+            in_progress: vec![package.manifest_path().canonicalize()?],
+        })
+    }
+}
+
 impl Driver {
     /// Sets the contents of a specific file.
     pub fn set_file_text(
@@ -207,7 +436,8 @@ impl Driver {
 
 impl Driver {
     /// Emits all diagnostic messages currently in the database; returns true if
-    /// errors were emitted.
+    /// errors were emitted. Warnings of lints are only emitted if there are no
+    /// errors.
     pub fn emit_diagnostics(
         &self,
         writer: &mut dyn std::io::Write,
@@ -260,9 +490,42 @@ impl Driver {
             }
         }
 
+        if !has_error {
+            self.emit_lints(writer, emit_colors)?;
+        }
+
         Ok(has_error)
     }
 
+    /// Emits the warnings of all lints.
+    fn emit_lints(
+        &self,
+        writer: &mut dyn std::io::Write,
+        emit_colors: bool,
+    ) -> Result<(), anyhow::Error> {
+        for package in mun_hir::Package::all(self.db.upcast()) {
+            for module in package.modules(self.db.upcast()) {
+                if let Some(file_id) = module.file_id(self.db.upcast()) {
+                    let mut error = None;
+                    module.lints(
+                        self.db.upcast(),
+                        &mut DiagnosticSink::new(|d| {
+                            if let Err(e) =
+                                emit_hir_diagnostic(d, &self.db, file_id, emit_colors, writer)
+                            {
+                                error = Some(e);
+                            };
+                        }),
+                    );
+                    if let Some(e) = error {
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Returns all diagnostics as a human readable string
     pub fn emit_diagnostics_to_string(
         &self,
@@ -326,6 +589,24 @@ impl Driver {
             .with_extension(AssemblyIr::EXTENSION)
     }
 
+    /// Returns the paths where the driver writes the assemblies of all modules,
+    /// sorted and without duplicates.
+    pub fn assembly_output_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = mun_hir::Package::all(self.db.upcast())
+            .into_iter()
+            .flat_map(|package| package.modules(self.db.upcast()))
+            .map(|module| self.assembly_output_path(module))
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    /// Returns the directory to which the assemblies are written
+    pub fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
     /// Returns the output path for the specified module group without an
     /// extension
     fn path_for_module_group(&self, module_group: &ModuleGroup) -> PathBuf {
@@ -334,21 +615,94 @@ impl Driver {
 
     /// Writes all assemblies. If `force` is false, the binary will not be
     /// written if there are no changes since last time it was written.
-    pub fn write_all_assemblies(&mut self, force: bool) -> Result<(), anyhow::Error> {
+    /// Returns the paths of the assemblies that were written.
+    pub fn write_all_assemblies(&mut self, force: bool) -> Result<Vec<PathBuf>, anyhow::Error> {
         let _lock = self.acquire_filesystem_output_lock();
 
+        // Build all assemblies in parallel, before they are written one by one
+        if self.emit.link {
+            mun_codegen::build_target_assemblies(&self.db);
+        }
+
         // Create a copy of all current files
+        let mut written = Vec::new();
         for package in mun_hir::Package::all(self.db.upcast()) {
             for module in package.modules(self.db.upcast()) {
-                if self.emit_ir {
+                if self.emit.llvm_ir {
                     self.write_assembly_ir(module)?;
-                } else {
-                    self.write_target_assembly(module, force)?;
+                }
+                if self.emit.asm {
+                    self.write_assembly_asm(module)?;
+                }
+                if self.emit.abi {
+                    self.write_assembly_abi(module)?;
+                }
+                if !self.emit.link {
+                    continue;
+                }
+                if let Some(path) = self.write_target_assembly(module, force)? {
+                    // Multiple modules can be part of the same assembly
+                    if !written.contains(&path) {
+                        written.push(path);
+                    }
                 }
             }
         }
 
-        Ok(())
+        Ok(written)
+    }
+
+    /// Builds all assemblies that changed since they were last built or
+    /// written, and returns their contents instead of writing them to the
+    /// output directory. This allows a runtime in the same process to stage
+    /// the assemblies, see `Runtime::stage_assembly`, without a file watcher
+    /// having to pick up changes in the output directory.
+    ///
+    /// The linker still writes each shared library to a temporary file, which
+    /// is read back into memory. Only the round trip through the output
+    /// directory and the file watcher is skipped.
+    ///
+    /// The assemblies are always built for the target machine, even if the
+    /// driver is configured to emit IR.
+    pub fn build_changed_assemblies(&mut self) -> Result<Vec<InMemoryAssembly>, anyhow::Error> {
+        mun_codegen::build_target_assemblies(&self.db);
+
+        let module_partition = self.db.module_partition();
+        let mut assemblies: Vec<InMemoryAssembly> = Vec::new();
+        for package in mun_hir::Package::all(self.db.upcast()) {
+            for module in package.modules(self.db.upcast()) {
+                let module_group_id = module_partition
+                    .group_for_module(module)
+                    .expect("could not find the module in the module partition");
+
+                // Did the assembly change since last time?
+                let assembly = self.db.target_assembly(module_group_id);
+                if self
+                    .module_to_temp_assembly_path
+                    .get(&module)
+                    .map(AsRef::as_ref)
+                    == Some(assembly.path())
+                {
+                    continue;
+                }
+                self.module_to_temp_assembly_path
+                    .insert(module, assembly.path().to_path_buf());
+
+                // Multiple modules can be part of the same assembly
+                let path = self
+                    .path_for_module_group(&module_partition[module_group_id])
+                    .with_extension(TargetAssembly::EXTENSION);
+                if assemblies.iter().any(|assembly| assembly.path == path) {
+                    continue;
+                }
+
+                let mut bytes = std::fs::read(assembly.path())?;
+                self.seal_assembly(&mut bytes)?;
+                assemblies.push(InMemoryAssembly { path, bytes });
+            }
+        }
+
+        Ok(assemblies)
     }
 
     /// Acquires a filesystem lock on the output directory. This ensures that
@@ -379,13 +733,13 @@ impl Driver {
     /// Generates an assembly for the target machine and specified module and
     /// stores it in the output location. If `force` is false, the binary
     /// will not be written if there are no changes since last time it was
-    /// written. Returns `true` if the assembly was written, `false`
+    /// written. Returns the path of the assembly if it was written, `None`
     /// if it was up to date.
     fn write_target_assembly(
         &mut self,
         module: Module,
         force: bool,
-    ) -> Result<bool, anyhow::Error> {
+    ) -> Result<Option<PathBuf>, anyhow::Error> {
         log::trace!("writing target assembly for {:?}", module);
 
         // Find the module group to which the module belongs
@@ -412,17 +766,44 @@ impl Driver {
                 .map(AsRef::as_ref)
                 == Some(assembly.path())
         {
-            return Ok(false);
+            return Ok(None);
         }
 
         // It did change or we are forced, so write it to disk
-        assembly.copy_to(&assembly_path)?;
+        if self.seal.is_some() {
+            let mut bytes = std::fs::read(assembly.path())?;
+            self.seal_assembly(&mut bytes)?;
+            std::fs::write(&assembly_path, bytes)?;
+        } else {
+            assembly.copy_to(&assembly_path)?;
+        }
 
         // Store the information so we maybe don't have to write it next time
         self.module_to_temp_assembly_path
             .insert(module, assembly.path().to_path_buf());
 
-        Ok(true)
+        Ok(Some(assembly_path))
+    }
+
+    /// Seals the contents of an assembly according to the configuration of the
+    /// driver, if any.
+    fn seal_assembly(&self, bytes: &mut [u8]) -> Result<(), anyhow::Error> {
+        let signing_key = match &self.seal {
+            None => return Ok(()),
+            Some(Seal::Hash) => None,
+            Some(Seal::Sign(signing_key)) => Some(signing_key.as_ref()),
+        };
+
+        // Sealing modifies the shared library, which would invalidate the code
+        // signature that is required to load it on Apple platforms
+        if self.db.target().options.is_like_osx {
+            return Err(anyhow::anyhow!(
+                "sealing assemblies is not supported for Apple targets"
+            ));
+        }
+
+        mun_libloader::integrity::seal(bytes, signing_key)?;
+        Ok(())
     }
 
     /// Generates IR for the specified module and stores it in the output
@@ -450,6 +831,76 @@ impl Driver {
 
         Ok(())
     }
+
+    /// Generates the target-specific assembly for the specified module and
+    /// stores it in the output location.
+    fn write_assembly_asm(&mut self, module: mun_hir::Module) -> Result<(), anyhow::Error> {
+        log::trace!("writing assembly file for {:?}", module);
+
+        // Find the module group to which the module belongs
+        let module_partition = self.db.module_partition();
+        let module_group_id = module_partition
+            .group_for_module(module)
+            .expect("could not find the module in the module partition");
+        let module_group = &module_partition[module_group_id];
+
+        // Get the target-specific assembly
+        let assembly_asm = self.db.assembly_asm(module_group_id);
+
+        // Determine the filename of the group
+        let assembly_path = self
+            .path_for_module_group(module_group)
+            .with_extension(AssemblyAsm::EXTENSION);
+
+        // Write to disk
+        assembly_asm.copy_to(assembly_path)?;
+
+        Ok(())
+    }
+
+    /// Writes a human-readable description of the `AssemblyInfo` of the munlib
+    /// of the specified module to the output location. The description is
+    /// read from the compiled munlib, so it matches what the runtime sees when
+    /// it links the munlib.
+    fn write_assembly_abi(&mut self, module: mun_hir::Module) -> Result<(), anyhow::Error> {
+        log::trace!("writing assembly ABI for {:?}", module);
+
+        let target = self.db.target();
+        let host_target = Target::host_target()
+            .ok_or_else(|| anyhow::anyhow!("unable to determine host target"))?;
+        if target.llvm_target != host_target.llvm_target {
+            anyhow::bail!(
+                "emitting the ABI of an assembly is only supported when compiling for the host \
+                target `{}`",
+                host_target.llvm_target
+            );
+        }
+
+        // Find the module group to which the module belongs
+        let module_partition = self.db.module_partition();
+        let module_group_id = module_partition
+            .group_for_module(module)
+            .expect("could not find the module in the module partition");
+        let module_group = &module_partition[module_group_id];
+
+        // Get the compiled assembly
+        let assembly = self.db.target_assembly(module_group_id);
+
+        // Safety: Loading a munlib and retrieving its assembly info executes code in
+        // the munlib. The munlib was just generated by the compiler, so this is safe.
+        let library = unsafe { MunLibrary::new(assembly.path()) }?;
+        let description = unsafe { library.get_info() }.to_string();
+
+        // Determine the filename of the group
+        let abi_path = self
+            .path_for_module_group(module_group)
+            .with_extension(ABI_EXTENSION);
+
+        // Write to disk
+        std::fs::write(abi_path, description)?;
+
+        Ok(())
+    }
 }
 
 impl Driver {
@@ -458,6 +909,41 @@ impl Driver {
         self.path_to_file_id.get(path.as_ref()).copied()
     }
 
+    /// Returns the type of the value that the body of the function called
+    /// `function_name` in the file at `path` evaluates to, as it is written in
+    /// Mun code, e.g. `i64` or `[Foo]`. The type is inferred even if it does
+    /// not match the return type of the function, which allows determining
+    /// the type of an expression by wrapping it in a function. Returns `None`
+    /// if the file does not contain the function.
+    pub fn infer_body_type<P: AsRef<RelativePath>>(
+        &self,
+        path: P,
+        function_name: &str,
+    ) -> Option<String> {
+        let file_id = self.get_file_id_for_path(path)?;
+        let function = mun_hir::Package::all(self.db.upcast())
+            .into_iter()
+            .flat_map(|package| package.modules(self.db.upcast()))
+            .filter(|module| module.file_id(self.db.upcast()) == Some(file_id))
+            .flat_map(|module| module.declarations(self.db.upcast()))
+            .find_map(|def| match def {
+                ModuleDef::Function(function)
+                    if function.name(self.db.upcast()).to_string() == function_name =>
+                {
+                    Some(function)
+                }
+                _ => None,
+            })?;
+
+        let body = function.body(self.db.upcast());
+        let infer = function.infer(self.db.upcast());
+        Some(
+            infer[body.body_expr()]
+                .display(self.db.upcast())
+                .to_string(),
+        )
+    }
+
     /// Tells the driver that the file at the specified `path` has changed its
     /// contents. Returns the `FileId` of the modified file.
     pub fn update_file<P: AsRef<RelativePath>>(&mut self, path: P, contents: String) -> FileId {
@@ -465,13 +951,21 @@ impl Driver {
             .path_to_file_id
             .get(path.as_ref())
             .expect("writing to a file that is not part of the source root should never happen");
-        self.db.set_file_text(file_id, Arc::from(contents));
+        if let Some(hidden) = self.overlaid_files.get_mut(&file_id) {
+            *hidden = Some(Arc::from(contents));
+        } else {
+            self.db.set_file_text(file_id, Arc::from(contents));
+        }
         file_id
     }
 
     /// Adds a new file to the driver. Returns the `FileId` of the new file.
     pub fn add_file<P: AsRef<RelativePath>>(&mut self, path: P, contents: String) -> FileId {
         let file_id = self.alloc_file_id(path.as_ref()).unwrap();
+        if let Some(hidden) = self.overlaid_files.get_mut(&file_id) {
+            *hidden = Some(Arc::from(contents));
+            return file_id;
+        }
 
         // Insert the new file
         self.db.set_file_text(file_id, Arc::from(contents));
@@ -492,6 +986,10 @@ impl Driver {
             .path_to_file_id
             .get(path.as_ref())
             .expect("removing to a file that is not part of the source root should never happen");
+        if let Some(hidden) = self.overlaid_files.get_mut(&file_id) {
+            *hidden = None;
+            return file_id;
+        }
 
         // Update the source root
         self.source_root.remove_file(file_id);
@@ -530,6 +1028,138 @@ impl Driver {
     }
 }
 
+impl Driver {
+    /// Overlays the contents of the file at the specified `path` with in-memory
+    /// `contents`, e.g. the unsaved contents of an editor buffer or contents
+    /// read from stdin. If the file is not part of the source root, it is
+    /// added. Passing `None` removes the overlay and restores the contents the
+    /// file had before.
+    ///
+    /// While a file is overlaid, changes made through [`Driver::update_file`],
+    /// [`Driver::add_file`] and [`Driver::remove_file`] are recorded but only
+    /// take effect once the overlay is removed.
+    pub fn set_overlay<P: AsRef<RelativePath>>(
+        &mut self,
+        path: P,
+        contents: Option<String>,
+    ) -> anyhow::Result<FileId> {
+        let path = path.as_ref();
+        let Some(contents) = contents else {
+            let file_id = *self
+                .path_to_file_id
+                .get(path)
+                .ok_or_else(|| anyhow::anyhow!("the path '{}' is unknown", path))?;
+            match self.overlaid_files.remove(&file_id) {
+                Some(Some(text)) => self.db.set_file_text(file_id, text),
+                Some(None) => {
+                    self.remove_file(path);
+                }
+                None => {}
+            }
+            return Ok(file_id);
+        };
+
+        if self.is_source_file(path) {
+            let file_id = self.path_to_file_id[path];
+            let text = self.db.file_text(file_id);
+            self.overlaid_files.entry(file_id).or_insert(Some(text));
+            self.db.set_file_text(file_id, Arc::from(contents));
+            Ok(file_id)
+        } else {
+            let file_id = self.add_file(path, contents);
+            self.overlaid_files.insert(file_id, None);
+            Ok(file_id)
+        }
+    }
+
+    /// Returns true if the contents of the file at the specified `path` are
+    /// overlaid with in-memory contents.
+    pub fn has_overlay<P: AsRef<RelativePath>>(&self, path: P) -> bool {
+        self.path_to_file_id
+            .get(path.as_ref())
+            .is_some_and(|file_id| self.overlaid_files.contains_key(file_id))
+    }
+}
+
+impl Driver {
+    /// Registers a [`SourceGenerator`] with the driver and adds the sources it
+    /// generates to the source root. Generated sources are resolved like any
+    /// other source file, so they can be referenced from other modules of the
+    /// package.
+    pub fn add_source_generator(
+        &mut self,
+        generator: impl SourceGenerator + Send + 'static,
+    ) -> anyhow::Result<()> {
+        self.source_generators.push(Box::new(generator));
+        if let Err(e) = self.regenerate_sources() {
+            self.source_generators.pop();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Runs all registered source generators again and synchronizes their
+    /// output with the source root. Generated files that are no longer
+    /// produced are removed.
+    pub fn regenerate_sources(&mut self) -> anyhow::Result<()> {
+        let mut sources: HashMap<RelativePathBuf, String> = HashMap::default();
+        for generator in self.source_generators.iter() {
+            for GeneratedSource { rel_path, contents } in generator.generate()? {
+                if sources.contains_key(&rel_path) {
+                    anyhow::bail!("the path '{}' is generated more than once", rel_path);
+                }
+                if !self.generated_files.contains(&rel_path) && self.is_source_file(&rel_path) {
+                    anyhow::bail!(
+                        "the generated path '{}' conflicts with an existing source file",
+                        rel_path
+                    );
+                }
+                sources.insert(rel_path, contents);
+            }
+        }
+
+        // Remove all files that are no longer generated
+        let stale_files: Vec<_> = self
+            .generated_files
+            .iter()
+            .filter(|path| !sources.contains_key(*path))
+            .cloned()
+            .collect();
+        for path in stale_files {
+            self.remove_file(&path);
+            self.generated_files.remove(&path);
+        }
+
+        // Add or update the generated files
+        for (rel_path, contents) in sources {
+            if self.generated_files.contains(&rel_path) {
+                self.update_file(&rel_path, contents);
+            } else {
+                self.add_file(&rel_path, contents);
+                self.generated_files.insert(rel_path);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the file at the specified `path` is generated by one of
+    /// the registered source generators.
+    pub fn is_generated_file<P: AsRef<RelativePath>>(&self, path: P) -> bool {
+        self.generated_files.contains(path.as_ref())
+    }
+
+    /// Returns true if the specified `path` is currently part of the source
+    /// root.
+    fn is_source_file(&self, path: &RelativePath) -> bool {
+        self.path_to_file_id.get(path).map_or(false, |file_id| {
+            self.source_root.files().any(|id| id == *file_id)
+        })
+    }
+}
+
+/// Returns the paths of all Mun source files in the specified directory and its
+/// subdirectories.
 pub fn iter_source_files(source_dir: &Path) -> impl Iterator<Item = PathBuf> {
     WalkDir::new(source_dir)
         .into_iter()
@@ -537,3 +1167,23 @@ pub fn iter_source_files(source_dir: &Path) -> impl Iterator<Item = PathBuf> {
         .filter(|e| is_source_file(e.path()))
         .map(|e| e.path().to_path_buf())
 }
+
+/// Reads all source files in the specified `source_dir`, returning their
+/// contents together with their paths relative to `source_dir`.
+fn read_source_files(source_dir: &Path) -> anyhow::Result<Vec<(RelativePathBuf, String)>> {
+    iter_source_files(source_dir)
+        .map(|source_file_path| {
+            let relative_path = compute_source_relative_path(source_dir, &source_file_path)?;
+
+            // Load the contents of the file
+            let file_contents = std::fs::read_to_string(&source_file_path).map_err(|e| {
+                anyhow::anyhow!(
+                    "could not read contents of '{}': {}",
+                    source_file_path.display(),
+                    e
+                )
+            })?;
+            Ok((relative_path, file_contents))
+        })
+        .collect()
+}
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 pub use mun_codegen::OptimizationLevel;
+use mun_libloader::integrity::SigningKey;
 use mun_target::spec::Target;
 
 /// Describes all the permanent settings that are used during compilations.
@@ -9,15 +10,77 @@ pub struct Config {
     /// The target triple to compile the code for.
     pub target: Target,
 
-    /// The optimization level to use for the IR generation.
-    pub optimization_lvl: OptimizationLevel,
+    /// The optimization level to use for the IR generation. If `None`, the
+    /// `opt-level` setting of the package is used. If the package does not
+    /// specify it either, [`OptimizationLevel::Default`] is used.
+    pub optimization_lvl: Option<OptimizationLevel>,
+
+    /// Whether each assembly is optimized as a whole after its modules are
+    /// linked, which allows functions to be inlined across modules. If `None`,
+    /// the `lto` setting of the package is used. If the package does not
+    /// specify it either, it is disabled.
+    pub lto: Option<bool>,
 
     /// The optional output directory to store all outputs. If no directory is
     /// specified all output is stored in a temporary directory.
     pub out_dir: Option<PathBuf>,
 
-    /// Whether or not to emit an IR file instead of a munlib.
-    pub emit_ir: bool,
+    /// The kinds of output that are emitted for every assembly.
+    pub emit: Emit,
+
+    /// Whether integer arithmetic checks for overflow. If `None`, the
+    /// `overflow-checks` setting of the package is used. If the package does
+    /// not specify it either, overflow checks are only enabled for unoptimized
+    /// builds.
+    pub overflow_checks: Option<bool>,
+
+    /// Whether functions report their calls to the profiler of the runtime,
+    /// see `Runtime::profile`. This adds overhead to every function call, so
+    /// it is disabled by default.
+    pub profiling: bool,
+
+    /// How assemblies are sealed when they are written, to allow a runtime to
+    /// verify their integrity before loading them. If `None`, assemblies are
+    /// not sealed.
+    pub seal: Option<Seal>,
+}
+
+/// Describes the kinds of output that are emitted for every assembly. All
+/// outputs of an assembly are written next to each other and only differ in
+/// their extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Emit {
+    /// Emit the linked shared object (`*.munlib`)
+    pub link: bool,
+    /// Emit the LLVM IR (`*.ll`)
+    pub llvm_ir: bool,
+    /// Emit the target-specific assembly (`*.s`)
+    pub asm: bool,
+    /// Emit a human-readable description of the symbols, dispatch table, and
+    /// type lookup table of the munlib, as the runtime sees them when linking
+    /// it (`*.abi`). This loads the munlib, so it is only supported when
+    /// compiling for the host.
+    pub abi: bool,
+}
+
+impl Default for Emit {
+    fn default() -> Self {
+        Emit {
+            link: true,
+            llvm_ir: false,
+            asm: false,
+            abi: false,
+        }
+    }
+}
+
+/// Describes how assemblies are sealed; see [`mun_libloader::integrity`].
+#[derive(Debug, Clone)]
+pub enum Seal {
+    /// Embed the content hash of the assembly
+    Hash,
+    /// Embed the content hash of the assembly and sign it with the key
+    Sign(Box<SigningKey>),
 }
 
 impl Default for Config {
@@ -27,9 +90,44 @@ impl Default for Config {
             // This unwrap is safe because we only compile for targets that have an implemented host
             // triple.
             target: target.unwrap(),
-            optimization_lvl: OptimizationLevel::Default,
+            optimization_lvl: None,
+            lto: None,
             out_dir: None,
-            emit_ir: false,
+            emit: Emit::default(),
+            overflow_checks: None,
+            profiling: false,
+            seal: None,
         }
     }
 }
+
+impl Config {
+    /// Returns the optimization level to use for the IR generation.
+    pub fn optimization_lvl(&self) -> OptimizationLevel {
+        self.optimization_lvl.unwrap_or(OptimizationLevel::Default)
+    }
+
+    /// Returns whether each assembly is optimized as a whole after its modules
+    /// are linked.
+    pub fn lto(&self) -> bool {
+        self.lto.unwrap_or(false)
+    }
+
+    /// Returns whether integer arithmetic checks for overflow.
+    pub fn overflow_checks(&self) -> bool {
+        self.overflow_checks
+            .unwrap_or(self.optimization_lvl() == OptimizationLevel::None)
+    }
+}
+
+/// Converts a numeric optimization level, from 0 to 3, into an
+/// [`OptimizationLevel`]. Returns `None` if the level is out of range.
+pub fn optimization_level_from_number(level: u8) -> Option<OptimizationLevel> {
+    match level {
+        0 => Some(OptimizationLevel::None),
+        1 => Some(OptimizationLevel::Less),
+        2 => Some(OptimizationLevel::Default),
+        3 => Some(OptimizationLevel::Aggressive),
+        _ => None,
+    }
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use mun_paths::RelativePathBuf;
+use mun_project::EngineApi;
+use mun_syntax::SyntaxKind;
+
+use super::{GeneratedSource, SourceGenerator};
+
+/// A [`SourceGenerator`] that generates the `extern` declarations of all
+/// functions and opaque types described by an engine API description file.
+///
+/// The declarations are generated in a module that is named after the file
+/// stem of the description, see [`EngineApi::module_name`].
+pub struct EngineApiGenerator {
+    path: PathBuf,
+    rel_path: RelativePathBuf,
+}
+
+impl EngineApiGenerator {
+    /// Constructs a generator for the engine API description at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let module_name = EngineApi::module_name(&path, is_keyword)?;
+        let rel_path = RelativePathBuf::from(format!("{module_name}.mun"));
+        Ok(Self { path, rel_path })
+    }
+}
+
+impl SourceGenerator for EngineApiGenerator {
+    fn generate(&self) -> anyhow::Result<Vec<GeneratedSource>> {
+        let engine_api = EngineApi::from_file(&self.path)?;
+        let file_name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let contents = engine_api.to_extern_declarations(&file_name, is_keyword)?;
+        Ok(vec![GeneratedSource::new(self.rel_path.clone(), contents)])
+    }
+}
+
+/// Returns whether `name` is a reserved keyword.
+fn is_keyword(name: &str) -> bool {
+    SyntaxKind::from_keyword(name).is_some()
+}
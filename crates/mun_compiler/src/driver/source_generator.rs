@@ -0,0 +1,44 @@
+use mun_paths::RelativePathBuf;
+
+/// A Mun source file that was synthesized by a [`SourceGenerator`] instead of
+/// being read from disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedSource {
+    /// The path of the file relative to the source directory of the package.
+    /// This determines the module in which the generated items end up.
+    pub rel_path: RelativePathBuf,
+
+    /// The Mun source code of the file
+    pub contents: String,
+}
+
+impl GeneratedSource {
+    /// Constructs a new generated source file.
+    pub fn new(rel_path: impl Into<RelativePathBuf>, contents: impl Into<String>) -> Self {
+        Self {
+            rel_path: rel_path.into(),
+            contents: contents.into(),
+        }
+    }
+}
+
+/// A `SourceGenerator` contributes synthesized Mun source code to the
+/// compilation of a package.
+///
+/// Generated sources are added to the source root of the package before any
+/// name resolution takes place, so they behave exactly like files on disk.
+/// This enables host tooling to derive Mun code (e.g. `extern fn` declarations
+/// from an engine API dump) from a single source of truth.
+pub trait SourceGenerator {
+    /// Returns all the source files this generator contributes.
+    fn generate(&self) -> anyhow::Result<Vec<GeneratedSource>>;
+}
+
+impl<F> SourceGenerator for F
+where
+    F: Fn() -> anyhow::Result<Vec<GeneratedSource>>,
+{
+    fn generate(&self) -> anyhow::Result<Vec<GeneratedSource>> {
+        self()
+    }
+}
@@ -21,7 +21,11 @@ pub use mun_target::spec::Target;
 
 pub use crate::{
     db::CompilerDatabase,
-    driver::{Config, DisplayColor, Driver},
+    driver::{
+        iter_source_files, optimization_level_from_number, Config, DisplayColor, Driver, Emit,
+        EngineApiGenerator, GeneratedSource, InMemoryAssembly, Seal, SourceGenerator,
+        ABI_EXTENSION, CORE_PACKAGE_NAME, STD_PACKAGE_NAME,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -130,7 +134,10 @@ pub fn compute_source_relative_path(
 mod test {
     use std::path::Path;
 
-    use crate::{compute_source_relative_path, is_source_file, RelativePath};
+    use crate::{
+        compute_source_relative_path, is_source_file, Config, DisplayColor, Driver,
+        GeneratedSource, PathOrInline, RelativePath, RelativePathBuf,
+    };
 
     #[test]
     fn test_is_source_file() {
@@ -152,4 +159,124 @@ mod test {
             RelativePath::new("foo/bar/main.mun")
         );
     }
+
+    #[test]
+    fn test_source_generator() {
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("mod.mun"),
+            contents: r#"
+            use package::engine::spawn_enemy;
+
+            pub fn main() { spawn_enemy(1.0) }
+            "#
+            .to_owned(),
+        };
+        let (mut driver, _) = Driver::with_file(Config::default(), input).unwrap();
+        assert!(driver
+            .emit_diagnostics_to_string(DisplayColor::Disable)
+            .unwrap()
+            .is_some());
+
+        driver
+            .add_source_generator(|| {
+                Ok(vec![GeneratedSource::new(
+                    "engine.mun",
+                    "pub extern fn spawn_enemy(health: f32);",
+                )])
+            })
+            .unwrap();
+        assert!(driver.is_generated_file("engine.mun"));
+        assert_eq!(
+            driver
+                .emit_diagnostics_to_string(DisplayColor::Disable)
+                .unwrap(),
+            None
+        );
+
+        // Generated files must not shadow existing source files
+        assert!(driver
+            .add_source_generator(|| Ok(vec![GeneratedSource::new("mod.mun", "")]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_overlay() {
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("mod.mun"),
+            contents: "pub fn main() -> i32 { 0 }".to_owned(),
+        };
+        let (mut driver, _) = Driver::with_file(Config::default(), input).unwrap();
+        assert_eq!(
+            driver
+                .emit_diagnostics_to_string(DisplayColor::Disable)
+                .unwrap(),
+            None
+        );
+
+        // The contents of the overlay are used instead of the contents of the file
+        driver
+            .set_overlay("mod.mun", Some("pub fn main() -> i32 { foo }".to_owned()))
+            .unwrap();
+        assert!(driver.has_overlay("mod.mun"));
+        assert!(driver
+            .emit_diagnostics_to_string(DisplayColor::Disable)
+            .unwrap()
+            .is_some());
+
+        // Updates to an overlaid file only take effect once the overlay is removed
+        driver.update_file("mod.mun", "pub fn main() -> i32 { 1 }".to_owned());
+        assert!(driver
+            .emit_diagnostics_to_string(DisplayColor::Disable)
+            .unwrap()
+            .is_some());
+        driver.set_overlay("mod.mun", None).unwrap();
+        assert!(!driver.has_overlay("mod.mun"));
+        assert_eq!(
+            driver
+                .emit_diagnostics_to_string(DisplayColor::Disable)
+                .unwrap(),
+            None
+        );
+
+        // Overlaying a file that doesn't exist adds it until the overlay is removed
+        driver
+            .set_overlay("foo.mun", Some("pub fn bar() -> i32 { baz }".to_owned()))
+            .unwrap();
+        assert!(driver
+            .emit_diagnostics_to_string(DisplayColor::Disable)
+            .unwrap()
+            .is_some());
+        driver.set_overlay("foo.mun", None).unwrap();
+        assert_eq!(
+            driver
+                .emit_diagnostics_to_string(DisplayColor::Disable)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_infer_body_type() {
+        let input = PathOrInline::Inline {
+            rel_path: RelativePathBuf::from("mod.mun"),
+            contents: r#"
+            struct Foo { a: i64 }
+
+            fn foo() { [Foo { a: 1 }] }
+            fn bar() -> f64 { 1.0 }
+            "#
+            .to_owned(),
+        };
+        let (driver, _) = Driver::with_file(Config::default(), input).unwrap();
+        assert_eq!(
+            driver.infer_body_type("mod.mun", "foo").as_deref(),
+            Some("[Foo]")
+        );
+        assert_eq!(
+            driver.infer_body_type("mod.mun", "bar").as_deref(),
+            Some("f64")
+        );
+        assert_eq!(driver.infer_body_type("mod.mun", "baz"), None);
+        assert_eq!(driver.infer_body_type("foo.mun", "foo"), None);
+    }
 }
@@ -1,3 +1,5 @@
+mod server;
+
 use std::{
     io::stderr,
     path::Path,
@@ -6,7 +8,9 @@ use std::{
 };
 
 use mun_compiler::{compute_source_relative_path, is_source_file, Config, DisplayColor, Driver};
+use mun_project::daemon::{Notification, Request};
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use server::Server;
 
 /// Compiles and watches the package at the specified path. Recompiles changes
 /// that occur.
@@ -14,6 +18,32 @@ pub fn compile_and_watch_manifest(
     manifest_path: &Path,
     config: Config,
     display_color: DisplayColor,
+) -> Result<bool, anyhow::Error> {
+    watch_manifest(manifest_path, config, display_color, None)
+}
+
+/// Compiles and watches the package at the specified path like
+/// [`compile_and_watch_manifest`], and additionally accepts build requests from
+/// clients on the specified port of the loopback interface. If `port` is zero,
+/// any free port is used. Clients are notified of every build. See
+/// [`mun_project::daemon`] for the protocol.
+///
+/// The compiler database is kept in memory between builds, so only the parts
+/// of the package that changed are recompiled.
+pub fn serve_manifest(
+    manifest_path: &Path,
+    config: Config,
+    display_color: DisplayColor,
+    port: u16,
+) -> Result<bool, anyhow::Error> {
+    watch_manifest(manifest_path, config, display_color, Some(port))
+}
+
+fn watch_manifest(
+    manifest_path: &Path,
+    config: Config,
+    display_color: DisplayColor,
+    port: Option<u16>,
 ) -> Result<bool, anyhow::Error> {
     // Create the compiler driver
     let (package, mut driver) = Driver::with_package_path(manifest_path, config)?;
@@ -26,9 +56,23 @@ pub fn compile_and_watch_manifest(
     watcher.watch(&source_directory, RecursiveMode::Recursive)?;
     println!("Watching: {}", source_directory.display());
 
+    // Also watch the engine API description, if any, to regenerate its extern
+    // declarations
+    let engine_api_path = package.engine_api_path();
+    if let Some(engine_api_path) = &engine_api_path {
+        watcher.watch(engine_api_path, RecursiveMode::NonRecursive)?;
+        println!("Watching: {}", engine_api_path.display());
+    }
+
     // Emit all current errors, and write the assemblies if no errors occured
-    if !driver.emit_diagnostics(&mut stderr(), display_color)? {
-        driver.write_all_assemblies(false)?;
+    build(&mut driver, display_color, None)?;
+
+    let mut server = port
+        .map(|port| Server::bind(port, driver.out_dir()))
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("could not start the compiler daemon: {e}"))?;
+    if let Some(server) = &server {
+        println!("Listening on: {}", server.local_addr()?);
     }
 
     // Insert Ctrl+C handler so we can gracefully quit
@@ -41,26 +85,40 @@ pub fn compile_and_watch_manifest(
 
     // Start watching filesystem events.
     while !should_quit.load(std::sync::atomic::Ordering::SeqCst) {
+        if let Some(server) = &mut server {
+            let requests = server.poll();
+            if requests.contains(&Request::Build) {
+                build(&mut driver, display_color, Some(server))?;
+            }
+        }
+
         if let Ok(event) = watcher_rx.recv_timeout(Duration::from_millis(1)) {
             use notify::DebouncedEvent::{Create, Remove, Rename, Write};
             match event {
+                Write(ref path) | Create(ref path)
+                    if engine_api_path.as_deref() == Some(path.as_path()) =>
+                {
+                    log::info!("Regenerating {}", path.display());
+                    match driver.regenerate_sources() {
+                        Ok(()) => {
+                            build(&mut driver, display_color, server.as_mut())?;
+                        }
+                        Err(e) => log::error!("{}", e),
+                    }
+                }
                 Write(ref path) if is_source_file(path) => {
                     let relative_path = compute_source_relative_path(&source_directory, path)?;
                     let file_contents = std::fs::read_to_string(path)?;
                     log::info!("Modifying {}", relative_path);
                     driver.update_file(relative_path, file_contents);
-                    if !driver.emit_diagnostics(&mut stderr(), display_color)? {
-                        driver.write_all_assemblies(false)?;
-                    }
+                    build(&mut driver, display_color, server.as_mut())?;
                 }
                 Create(ref path) if is_source_file(path) => {
                     let relative_path = compute_source_relative_path(&source_directory, path)?;
                     let file_contents = std::fs::read_to_string(path)?;
                     log::info!("Creating {}", relative_path);
                     driver.add_file(relative_path, file_contents);
-                    if !driver.emit_diagnostics(&mut stderr(), display_color)? {
-                        driver.write_all_assemblies(false)?;
-                    }
+                    build(&mut driver, display_color, server.as_mut())?;
                 }
                 Remove(ref path) if is_source_file(path) => {
                     // Simply remove the source file from the source root
@@ -86,9 +144,7 @@ pub fn compile_and_watch_manifest(
 
                     log::info!("Renaming {} to {}", from_relative_path, to_relative_path,);
                     driver.rename(from_relative_path, to_relative_path);
-                    if !driver.emit_diagnostics(&mut stderr(), display_color)? {
-                        driver.write_all_assemblies(false)?;
-                    }
+                    build(&mut driver, display_color, server.as_mut())?;
                 }
                 _ => {}
             }
@@ -97,3 +153,32 @@ pub fn compile_and_watch_manifest(
 
     Ok(true)
 }
+
+/// Emits all diagnostics and, if there are no errors, writes the assemblies
+/// that changed. Notifies the clients of the `server` of the outcome. Returns
+/// whether the build succeeded.
+fn build(
+    driver: &mut Driver,
+    display_color: DisplayColor,
+    server: Option<&mut Server>,
+) -> Result<bool, anyhow::Error> {
+    let mut notifications = Vec::new();
+    let success = if driver.emit_diagnostics(&mut stderr(), display_color)? {
+        notifications.push(Notification::Failed);
+        false
+    } else {
+        notifications.extend(
+            driver
+                .write_all_assemblies(false)?
+                .into_iter()
+                .map(Notification::Assembly),
+        );
+        notifications.push(Notification::Finished);
+        true
+    };
+
+    if let Some(server) = server {
+        server.notify(&notifications);
+    }
+    Ok(success)
+}
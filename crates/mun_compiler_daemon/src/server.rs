@@ -0,0 +1,524 @@
+use std::{
+    fmt::Write as _,
+    fs::OpenOptions,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+};
+
+use mun_project::daemon::{DaemonInfo, Notification, Request, DAEMON_FILENAME};
+
+/// The maximum length of a request in bytes. Clients that send longer requests
+/// are disconnected.
+const MAX_REQUEST_LEN: usize = 4 * 1024;
+
+/// The maximum number of bytes that are buffered for a client that does not
+/// read its notifications. Clients that fall further behind are disconnected.
+const MAX_OUTPUT_LEN: usize = 1024 * 1024;
+
+/// The number of random bytes in the token with which clients authenticate
+const TOKEN_LEN: usize = 16;
+
+/// Accepts connections from clients of the compiler daemon, receives their
+/// requests, and notifies them of builds. See [`mun_project::daemon`] for the
+/// protocol.
+///
+/// The server never blocks, so it can be polled from the loop that watches the
+/// file system.
+pub(crate) struct Server {
+    listener: TcpListener,
+    clients: Vec<Client>,
+    token: String,
+    info_path: PathBuf,
+}
+
+/// A connection with a client of the compiler daemon
+struct Client {
+    address: SocketAddr,
+    /// Whether the client sent the token of the server
+    authenticated: bool,
+    reader: BufReader<TcpStream>,
+    /// The bytes of a request that was only partially received
+    pending: Vec<u8>,
+    /// The bytes of notifications that have not been sent yet, because the
+    /// client was not ready to receive them
+    output: Vec<u8>,
+}
+
+impl Server {
+    /// Binds a server to the specified port on the loopback interface, or to
+    /// any free port if `port` is zero. The address of the server and the
+    /// token with which clients authenticate are written to the output
+    /// directory `out_dir`.
+    pub fn bind(port: u16, out_dir: &Path) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        listener.set_nonblocking(true)?;
+
+        let info = DaemonInfo {
+            address: listener.local_addr()?,
+            token: generate_token()?,
+        };
+        let info_path = out_dir.join(DAEMON_FILENAME);
+        write_private_file(&info_path, info.to_string().as_bytes())?;
+
+        Ok(Self {
+            listener,
+            clients: Vec::new(),
+            token: info.token,
+            info_path,
+        })
+    }
+
+    /// Returns the address on which the server listens
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts pending connections, sends buffered notifications, and returns
+    /// the requests that clients sent since the last poll.
+    pub fn poll(&mut self) -> Vec<Request> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, address)) => {
+                    if let Err(e) = stream.set_nonblocking(true) {
+                        log::error!("could not accept connection from {address}: {e}");
+                        continue;
+                    }
+                    log::info!("Client connected: {address}");
+                    self.clients.push(Client {
+                        address,
+                        authenticated: false,
+                        reader: BufReader::new(stream),
+                        pending: Vec::new(),
+                        output: Vec::new(),
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    log::error!("could not accept connection: {e}");
+                    break;
+                }
+            }
+        }
+
+        let mut requests = Vec::new();
+        let token = &self.token;
+        self.clients.retain_mut(|client| {
+            match client
+                .flush()
+                .and_then(|()| client.receive(token, &mut requests))
+            {
+                Ok(()) => true,
+                Err(e) => {
+                    log::info!("Client disconnected: {}: {e}", client.address);
+                    false
+                }
+            }
+        });
+        requests
+    }
+
+    /// Sends the `notifications` to all authenticated clients. Notifications
+    /// that a client is not ready to receive are buffered, and sent by later
+    /// calls to [`Server::poll`] or [`Server::notify`].
+    pub fn notify(&mut self, notifications: &[Notification]) {
+        let message = notifications
+            .iter()
+            .fold(String::new(), |mut message, notification| {
+                let _ = writeln!(message, "{notification}");
+                message
+            });
+
+        self.clients.retain_mut(|client| {
+            if !client.authenticated {
+                return true;
+            }
+
+            match client.send(message.as_bytes()) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::info!("Client disconnected: {}: {e}", client.address);
+                    false
+                }
+            }
+        });
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        // Clients should no longer try to connect to this server
+        let _ = std::fs::remove_file(&self.info_path);
+    }
+}
+
+/// Generates a random token with which clients authenticate.
+fn generate_token() -> io::Result<String> {
+    let mut bytes = [0u8; TOKEN_LEN];
+    getrandom::getrandom(&mut bytes).map_err(io::Error::from)?;
+    Ok(bytes.iter().fold(String::new(), |mut token, b| {
+        let _ = write!(token, "{b:02x}");
+        token
+    }))
+}
+
+/// Writes `contents` to the file at `path`, which only the current user may
+/// read on Unix, so other users cannot learn the token.
+fn write_private_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        options.mode(0o600);
+
+        // The mode only applies to files that are created
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(contents)
+}
+
+/// Compares two tokens in constant time, so the time it takes does not reveal
+/// how much of the token a client guessed correctly.
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+impl Client {
+    /// Receives all complete requests that are available without blocking.
+    /// Returns an error if the client disconnected, or if its first request
+    /// does not authenticate it with the `token`.
+    fn receive(&mut self, token: &str, requests: &mut Vec<Request>) -> io::Result<()> {
+        loop {
+            let limit = (MAX_REQUEST_LEN - self.pending.len()) as u64;
+            match self
+                .reader
+                .by_ref()
+                .take(limit)
+                .read_until(b'\n', &mut self.pending)
+            {
+                Ok(0) if limit == 0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "request too long",
+                    ))
+                }
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "connection closed",
+                    ))
+                }
+                Ok(_) if self.pending.ends_with(b"\n") => {
+                    let request = std::str::from_utf8(&self.pending)
+                        .map_err(anyhow::Error::from)
+                        .and_then(str::parse);
+                    self.pending.clear();
+
+                    match request {
+                        Ok(Request::Authenticate(client_token))
+                            if tokens_match(&client_token, token) =>
+                        {
+                            self.authenticated = true;
+                        }
+                        _ if !self.authenticated => {
+                            return Err(io::Error::new(
+                                io::ErrorKind::PermissionDenied,
+                                "client did not authenticate",
+                            ));
+                        }
+                        Ok(Request::Authenticate(_)) => (),
+                        Ok(request) => requests.push(request),
+                        Err(e) => log::error!("{}: {e}", self.address),
+                    }
+                }
+                Ok(_) => (),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    /// Queues the `message` and sends as much of the queued output as possible
+    /// without blocking. Returns an error if the client disconnected or does
+    /// not keep up with its notifications.
+    fn send(&mut self, message: &[u8]) -> io::Result<()> {
+        if self.output.len() + message.len() > MAX_OUTPUT_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "client does not receive its notifications",
+            ));
+        }
+        self.output.extend_from_slice(message);
+        self.flush()
+    }
+
+    /// Sends as much of the queued output as possible without blocking.
+    /// Returns an error if the client disconnected.
+    fn flush(&mut self) -> io::Result<()> {
+        while !self.output.is_empty() {
+            match self.reader.get_mut().write(&self.output) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(written) => {
+                    self.output.drain(..written);
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Read, Write},
+        net::TcpStream,
+        path::PathBuf,
+        time::{Duration, Instant},
+    };
+
+    use mun_project::daemon::{DaemonInfo, Notification, Request, DAEMON_FILENAME};
+    use mun_runtime::Runtime;
+    use mun_test::CompileTestDriver;
+
+    use super::Server;
+
+    /// Connects a client to the `server` that authenticates with `token`, and
+    /// waits until the server accepted it.
+    fn connect(server: &mut Server, token: &str) -> TcpStream {
+        let mut stream = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+        stream
+            .write_all(format!("{}\n", Request::Authenticate(token.to_owned())).as_bytes())
+            .unwrap();
+        stream.set_nonblocking(true).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while server.clients.is_empty() {
+            assert!(Instant::now() < deadline, "the client was not accepted");
+            server.poll();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        stream
+    }
+
+    #[test]
+    fn bind_writes_daemon_info() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let server = Server::bind(0, out_dir.path()).unwrap();
+
+        let info_path = out_dir.path().join(DAEMON_FILENAME);
+        let info: DaemonInfo = std::fs::read_to_string(&info_path)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(info.address, server.local_addr().unwrap());
+        assert_eq!(info.token, server.token);
+        assert_eq!(info.token.len(), 32);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = std::fs::metadata(&info_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        drop(server);
+        assert!(!info_path.exists());
+    }
+
+    #[test]
+    fn unauthenticated_client_is_disconnected() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let mut server = Server::bind(0, out_dir.path()).unwrap();
+        let mut stream = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+        stream
+            .write_all(
+                format!(
+                    "{}\nbuild\n",
+                    Request::Authenticate("not the token".to_owned())
+                )
+                .as_bytes(),
+            )
+            .unwrap();
+        stream.set_nonblocking(true).unwrap();
+
+        // The server closes the connection without handling the request
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut buf = [0u8; 64];
+        loop {
+            assert!(Instant::now() < deadline, "the client was not disconnected");
+            assert!(server.poll().is_empty());
+            server.notify(&[Notification::Finished]);
+            match stream.read(&mut buf) {
+                Ok(0) => break,
+                Ok(_) => panic!("an unauthenticated client received a notification"),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                Err(_) => break,
+            }
+        }
+        assert!(server.clients.is_empty());
+    }
+
+    #[test]
+    fn slow_client_receives_all_notifications() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let mut server = Server::bind(0, out_dir.path()).unwrap();
+        let token = server.token.clone();
+        let mut stream = connect(&mut server, &token);
+
+        // Authenticate the client
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !server.clients[0].authenticated {
+            assert!(
+                Instant::now() < deadline,
+                "the client was not authenticated"
+            );
+            server.poll();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        // Send more notifications than fit in the socket buffers, while the
+        // client does not read
+        let notifications: Vec<Notification> = (0..4096)
+            .map(|idx| Notification::Assembly(PathBuf::from(format!("target/{idx:0>100}.munlib"))))
+            .chain([Notification::Finished])
+            .collect();
+        server.notify(&notifications);
+        assert_eq!(server.clients.len(), 1);
+
+        let mut received = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !received.ends_with(b"finished\n") {
+            assert!(
+                Instant::now() < deadline,
+                "not all notifications were received"
+            );
+            server.poll();
+
+            let mut buffer = [0; 4096];
+            match stream.read(&mut buffer) {
+                Ok(len) => received.extend_from_slice(&buffer[..len]),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                Err(e) => panic!("could not receive notifications: {e}"),
+            }
+        }
+
+        let received: Vec<Notification> = std::str::from_utf8(&received)
+            .unwrap()
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect();
+        assert_eq!(received, notifications);
+    }
+
+    #[test]
+    fn runtime_relinks_notified_assemblies() {
+        let mut driver = CompileTestDriver::from_file(
+            r"
+        pub fn value() -> i32 { 5 }
+        ",
+        );
+        let mut server = Server::bind(0, driver.lib_path().parent().unwrap()).unwrap();
+
+        // Safety: We compiled the library ourselves, therefor loading the library is
+        // safe.
+        let runtime = unsafe { Runtime::builder(driver.lib_path()).finish() }
+            .expect("Failed to construct runtime");
+        assert_eq!(
+            runtime.connect_to_daemon().unwrap(),
+            server.local_addr().unwrap()
+        );
+
+        runtime.request_build().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !server.poll().contains(&Request::Build) {
+            assert!(
+                Instant::now() < deadline,
+                "the build request was not received"
+            );
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        driver.update_file(
+            "mod.mun",
+            r"
+        pub fn value() -> i32 { 10 }
+        ",
+        );
+        server.notify(&[
+            Notification::Assembly(driver.lib_path().to_path_buf()),
+            Notification::Finished,
+        ]);
+
+        // Safety: We compiled the library ourselves, therefor updating the runtime is
+        // safe.
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !unsafe { runtime.update() } {
+            assert!(
+                Instant::now() < deadline,
+                "runtime did not relink the notified assembly within 10 seconds"
+            );
+            server.poll();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        let value: i32 = runtime.invoke("value", ()).unwrap();
+        assert_eq!(value, 10);
+    }
+
+    #[test]
+    fn runtime_ignores_unknown_assemblies() {
+        let driver = CompileTestDriver::from_file(
+            r"
+        pub fn value() -> i32 { 5 }
+        ",
+        );
+        let mut server = Server::bind(0, driver.lib_path().parent().unwrap()).unwrap();
+
+        // Safety: We compiled the library ourselves, therefor loading the library is
+        // safe.
+        let runtime = unsafe { Runtime::builder(driver.lib_path()).finish() }
+            .expect("Failed to construct runtime");
+        runtime.connect_to_daemon().unwrap();
+
+        // Wait until the runtime is authenticated, so it receives the notifications
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !server.clients.iter().any(|client| client.authenticated) {
+            assert!(
+                Instant::now() < deadline,
+                "the runtime was not authenticated"
+            );
+            server.poll();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        let unknown_path = driver.lib_path().with_file_name("unknown.munlib");
+        server.notify(&[Notification::Assembly(unknown_path), Notification::Finished]);
+
+        // Safety: We compiled the library ourselves, therefor updating the runtime is
+        // safe.
+        let deadline = Instant::now() + Duration::from_millis(100);
+        while Instant::now() < deadline {
+            assert!(!unsafe { runtime.update() });
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        let value: i32 = runtime.invoke("value", ()).unwrap();
+        assert_eq!(value, 5);
+    }
+}
@@ -0,0 +1,91 @@
+use std::panic::{self, UnwindSafe};
+
+/// An error signifying a cancelled operation.
+///
+/// Salsa databases support cancellation of queries that are running on a
+/// snapshot of the database: as soon as an input of the database is modified,
+/// all snapshots of the previous revision are considered stale. Any
+/// computation on a stale snapshot unwinds with a `Canceled` payload the next
+/// time it checks for cancellation, which is converted back into an error by
+/// [`Canceled::catch`].
+pub struct Canceled {
+    // This is here so that you cannot construct a Canceled
+    _private: (),
+}
+
+impl Canceled {
+    fn new() -> Self {
+        Canceled { _private: () }
+    }
+
+    /// Aborts the current computation by unwinding with a `Canceled` payload.
+    pub fn throw() -> ! {
+        // We use resume and not panic here to avoid running the panic
+        // hook (that is, to avoid collecting and printing backtrace).
+        panic::resume_unwind(Box::new(Canceled::new()))
+    }
+
+    /// Runs `f` and converts an unwind with a `Canceled` payload into an error.
+    /// Any other panic is propagated.
+    pub fn catch<F, T>(f: F) -> Result<T, Canceled>
+    where
+        F: FnOnce() -> T + UnwindSafe,
+    {
+        panic::catch_unwind(f).map_err(|err| match err.downcast::<Canceled>() {
+            Ok(canceled) => *canceled,
+            Err(payload) => panic::resume_unwind(payload),
+        })
+    }
+}
+
+impl std::fmt::Display for Canceled {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.write_str("canceled")
+    }
+}
+
+impl std::fmt::Debug for Canceled {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(fmt, "Canceled")
+    }
+}
+
+impl std::error::Error for Canceled {}
+
+/// A trait to check whether the current revision of a salsa database has been
+/// canceled.
+///
+/// Salsa only checks for cancellation between queries. Long-running
+/// computations within a single query should call
+/// [`CheckCanceled::unwind_if_canceled`] periodically to make sure that they
+/// are aborted as soon as possible when the input changes.
+pub trait CheckCanceled {
+    /// Unwinds with a [`Canceled`] payload if a new revision of the database is
+    /// pending.
+    fn unwind_if_canceled(&self);
+}
+
+impl<T: salsa::Database + ?Sized> CheckCanceled for T {
+    fn unwind_if_canceled(&self) {
+        if self.salsa_runtime().is_current_revision_canceled() {
+            Canceled::throw()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Canceled;
+
+    #[test]
+    fn catch() {
+        assert_eq!(Canceled::catch(|| 5).unwrap(), 5);
+        assert!(Canceled::catch(|| -> i32 { Canceled::throw() }).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "not canceled")]
+    fn catch_propagates_other_panics() {
+        let _ = Canceled::catch(|| panic!("not canceled"));
+    }
+}
@@ -1,6 +1,10 @@
 //! This crate provides utilitiy functions to be used alongside salsa databases
 //! used by mun.
 
+mod cancelation;
+
+pub use cancelation::{Canceled, CheckCanceled};
+
 pub trait Upcast<T: ?Sized> {
     fn upcast(&self) -> &T;
 }
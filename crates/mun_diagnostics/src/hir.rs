@@ -5,11 +5,13 @@ mod access_unknown_field;
 mod duplicate_definition_error;
 mod expected_function;
 mod exported_private;
+mod invalid_cast;
 mod mismatched_type;
 mod missing_fields;
 mod possibly_unitialized_variable;
 mod unresolved_type;
 mod unresolved_value;
+mod unsupported_field_default;
 
 use mun_hir::Diagnostic as HirDiagnostic;
 use mun_syntax::TextRange;
@@ -26,6 +28,8 @@ impl<DB: mun_hir::HirDatabase> DiagnosticForWith<DB> for dyn mun_hir::Diagnostic
             f(&unresolved_type::UnresolvedType::new(with, v))
         } else if let Some(v) = self.downcast_ref::<mun_hir::diagnostics::ExpectedFunction>() {
             f(&expected_function::ExpectedFunction::new(with, v))
+        } else if let Some(v) = self.downcast_ref::<mun_hir::diagnostics::InvalidCast>() {
+            f(&invalid_cast::InvalidCast::new(with, v))
         } else if let Some(v) = self.downcast_ref::<mun_hir::diagnostics::MismatchedType>() {
             f(&mismatched_type::MismatchedType::new(with, v))
         } else if let Some(v) =
@@ -42,6 +46,11 @@ impl<DB: mun_hir::HirDatabase> DiagnosticForWith<DB> for dyn mun_hir::Diagnostic
             f(&missing_fields::MissingFields::new(with, v))
         } else if let Some(v) = self.downcast_ref::<mun_hir::diagnostics::ExportedPrivate>() {
             f(&exported_private::ExportedPrivate::new(with, v))
+        } else if let Some(v) = self.downcast_ref::<mun_hir::diagnostics::UnsupportedFieldDefault>()
+        {
+            f(&unsupported_field_default::UnsupportedFieldDefault::new(
+                with, v,
+            ))
         } else {
             f(&GenericHirDiagnostic { diagnostic: self })
         }
@@ -0,0 +1,53 @@
+use mun_hir::HirDisplay;
+use mun_syntax::TextRange;
+
+use super::HirDiagnostic;
+use crate::{Diagnostic, SourceAnnotation};
+
+/// An error that is emitted when a value is cast to a type it cannot be
+/// converted to:
+///
+/// ```mun
+/// # fn main() {
+///     let a = 3.0;
+///     let b = a as bool;  // cannot cast `f64` as `bool`
+/// # }
+/// ```
+pub struct InvalidCast<'db, 'diag, DB: mun_hir::HirDatabase> {
+    db: &'db DB,
+    diag: &'diag mun_hir::diagnostics::InvalidCast,
+}
+
+impl<'db, 'diag, DB: mun_hir::HirDatabase> Diagnostic for InvalidCast<'db, 'diag, DB> {
+    fn range(&self) -> TextRange {
+        self.diag.highlight_range()
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "cannot cast `{}` as `{}`",
+            self.diag.from.display(self.db),
+            self.diag.to.display(self.db)
+        )
+    }
+
+    fn primary_annotation(&self) -> Option<SourceAnnotation> {
+        Some(SourceAnnotation {
+            range: self.diag.highlight_range(),
+            message: "invalid cast".to_owned(),
+        })
+    }
+
+    fn footer(&self) -> Vec<String> {
+        vec![String::from(
+            "only numeric types can be cast to other numeric types, and `bool` to integers",
+        )]
+    }
+}
+
+impl<'db, 'diag, DB: mun_hir::HirDatabase> InvalidCast<'db, 'diag, DB> {
+    /// Constructs a new instance of `InvalidCast`
+    pub fn new(db: &'db DB, diag: &'diag mun_hir::diagnostics::InvalidCast) -> Self {
+        InvalidCast { db, diag }
+    }
+}
@@ -1,4 +1,4 @@
-use mun_hir::HirDisplay;
+use mun_hir::{FloatBitness, HirDisplay, IntBitness, Signedness, TyKind};
 use mun_syntax::TextRange;
 
 use super::HirDiagnostic;
@@ -36,6 +36,67 @@ impl<'db, 'diag, DB: mun_hir::HirDatabase> Diagnostic for MismatchedType<'db, 'd
     fn primary_annotation(&self) -> Option<SourceAnnotation> {
         None
     }
+
+    fn footer(&self) -> Vec<String> {
+        let expected = &self.diag.expected;
+        let found = &self.diag.found;
+        let Some(lossless) = numeric_conversion_is_lossless(found.interned(), expected.interned())
+        else {
+            return Vec::new();
+        };
+
+        let mut note = format!(
+            "numeric types are never converted implicitly, use `as {}` to convert the `{}`",
+            expected.display(self.db),
+            found.display(self.db)
+        );
+        if !lossless {
+            note.push_str(", which may truncate the value or lose precision");
+        }
+        vec![note]
+    }
+}
+
+/// Returns whether converting a value of the numeric type `from` to the numeric
+/// type `to` preserves every possible value, or `None` if either type is not a
+/// concrete numeric type. Conversions to or from pointer-sized integers are
+/// considered lossy because their size depends on the target.
+fn numeric_conversion_is_lossless(from: &TyKind, to: &TyKind) -> Option<bool> {
+    let int_bits = |bitness: IntBitness| match bitness {
+        IntBitness::Xsize => None,
+        IntBitness::X8 => Some(8),
+        IntBitness::X16 => Some(16),
+        IntBitness::X32 => Some(32),
+        IntBitness::X64 => Some(64),
+        IntBitness::X128 => Some(128),
+    };
+    // The number of bits of an integer that a float can represent exactly
+    let mantissa_bits = |bitness: FloatBitness| match bitness {
+        FloatBitness::X32 => 24,
+        FloatBitness::X64 => 53,
+    };
+
+    let lossless = match (from, to) {
+        (TyKind::Int(from), TyKind::Int(to)) => {
+            match (int_bits(from.bitness), int_bits(to.bitness)) {
+                (Some(from_bits), Some(to_bits)) => match (from.signedness, to.signedness) {
+                    (Signedness::Signed, Signedness::Unsigned) => false,
+                    (Signedness::Unsigned, Signedness::Signed) => from_bits < to_bits,
+                    _ => from_bits <= to_bits,
+                },
+                _ => false,
+            }
+        }
+        (TyKind::Int(from), TyKind::Float(to)) => {
+            int_bits(from.bitness).is_some_and(|bits| bits <= mantissa_bits(to.bitness))
+        }
+        (TyKind::Float(from), TyKind::Float(to)) => {
+            mantissa_bits(from.bitness) <= mantissa_bits(to.bitness)
+        }
+        (TyKind::Float(_), TyKind::Int(_)) => false,
+        _ => return None,
+    };
+    Some(lossless)
 }
 
 impl<'db, 'diag, DB: mun_hir::HirDatabase> MismatchedType<'db, 'diag, DB> {
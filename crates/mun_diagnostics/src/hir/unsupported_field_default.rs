@@ -0,0 +1,52 @@
+use mun_syntax::TextRange;
+
+use super::HirDiagnostic;
+use crate::{Diagnostic, SourceAnnotation};
+
+/// An error that is emitted when the default value of a field is not a boolean
+/// or number literal:
+///
+/// ```mun
+/// struct Enemy {
+///     hp: i32 = max_hp(), // unsupported default value for field `hp`
+/// }
+///
+/// fn max_hp() -> i32 { 100 }
+/// ```
+pub struct UnsupportedFieldDefault<'db, 'diag, DB: mun_hir::HirDatabase> {
+    _db: &'db DB,
+    diag: &'diag mun_hir::diagnostics::UnsupportedFieldDefault,
+}
+
+impl<'db, 'diag, DB: mun_hir::HirDatabase> Diagnostic for UnsupportedFieldDefault<'db, 'diag, DB> {
+    fn range(&self) -> TextRange {
+        self.diag.highlight_range()
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "unsupported default value for field `{}`",
+            self.diag.field_name
+        )
+    }
+
+    fn primary_annotation(&self) -> Option<SourceAnnotation> {
+        Some(SourceAnnotation {
+            range: self.diag.highlight_range(),
+            message: "expected a boolean or number literal".to_owned(),
+        })
+    }
+
+    fn footer(&self) -> Vec<String> {
+        vec![String::from(
+            "default values are stored in the assembly, so they must be known at compile time",
+        )]
+    }
+}
+
+impl<'db, 'diag, DB: mun_hir::HirDatabase> UnsupportedFieldDefault<'db, 'diag, DB> {
+    /// Constructs a new instance of `UnsupportedFieldDefault`
+    pub fn new(db: &'db DB, diag: &'diag mun_hir::diagnostics::UnsupportedFieldDefault) -> Self {
+        UnsupportedFieldDefault { _db: db, diag }
+    }
+}
@@ -0,0 +1,380 @@
+//! Formats Mun source code.
+//!
+//! The formatter works on the concrete syntax tree of a source file and only
+//! changes the whitespace between tokens. The only exception is the trailing
+//! comma that is added to struct definitions that span multiple lines. Because
+//! comments are tokens in the syntax tree, they are preserved as they are.
+//!
+//! The layout of the code is determined as follows:
+//! - Every item, statement and field of a struct definition is placed on its
+//!   own line and the bodies of functions, blocks, `impl`s and struct
+//!   definitions are spread over multiple lines. Attributes are placed on the
+//!   lines above the item they belong to.
+//! - All other line breaks are preserved, but consecutive empty lines are
+//!   collapsed into one.
+//! - The indentation of a line is determined by the number of delimiters (`{`,
+//!   `(` or `[`) that enclose it and that are followed by a line break.
+//! - The spacing between tokens on the same line is normalized.
+
+use std::collections::HashSet;
+
+use mun_syntax::{
+    SourceFile, SyntaxElement, SyntaxError, SyntaxKind, SyntaxNode, SyntaxToken, TextSize,
+};
+
+/// The string used for a single level of indentation
+const INDENT: &str = "    ";
+
+/// An error that can occur while formatting source code.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FormatError {
+    /// The source code contains syntax errors. Formatting it could change its
+    /// meaning, so it is left untouched.
+    #[error("the source contains {} syntax error(s)", .0.len())]
+    SyntaxErrors(Vec<SyntaxError>),
+}
+
+/// Formats the specified Mun source code. Returns an error if the source code
+/// contains syntax errors.
+pub fn format(text: &str) -> Result<String, FormatError> {
+    let parse = SourceFile::parse(text);
+    if !parse.errors().is_empty() {
+        return Err(FormatError::SyntaxErrors(parse.errors().to_vec()));
+    }
+
+    let tokens = collect_tokens(&parse.syntax_node());
+    Ok(Printer::default().print(&tokens))
+}
+
+/// A non-whitespace token of the source and the information required to
+/// determine the whitespace that precedes it.
+#[derive(Debug)]
+struct Token {
+    kind: SyntaxKind,
+    text: String,
+
+    /// The kind of the node that contains the token
+    parent: SyntaxKind,
+
+    /// The number of line breaks that preceded the token in the source
+    newlines: usize,
+
+    /// True if the token is the first token of an item, statement or field
+    /// that is placed on its own line
+    starts_line: bool,
+
+    /// True if the token is a brace of a block that is always spread over
+    /// multiple lines
+    expanded: bool,
+}
+
+impl Token {
+    fn is_opening_delimiter(&self) -> bool {
+        matches!(
+            self.kind,
+            SyntaxKind::L_CURLY | SyntaxKind::L_PAREN | SyntaxKind::L_BRACKET
+        )
+    }
+
+    fn is_closing_delimiter(&self) -> bool {
+        matches!(
+            self.kind,
+            SyntaxKind::R_CURLY | SyntaxKind::R_PAREN | SyntaxKind::R_BRACKET
+        )
+    }
+
+    fn is_line_comment(&self) -> bool {
+        self.kind == SyntaxKind::COMMENT && self.text.starts_with("//")
+    }
+
+    /// Returns true if the token is the operator of a prefix expression, e.g.
+    /// the `-` in `-a`.
+    fn is_prefix_operator(&self) -> bool {
+        self.parent == SyntaxKind::PREFIX_EXPR
+            && matches!(self.kind, SyntaxKind::MINUS | SyntaxKind::EXCLAMATION)
+    }
+}
+
+/// Collects all non-whitespace tokens of the tree rooted at `root`.
+fn collect_tokens(root: &SyntaxNode) -> Vec<Token> {
+    let line_starts: HashSet<TextSize> = root
+        .descendants()
+        .filter(|node| {
+            node.parent()
+                .is_some_and(|parent| is_line_container(&parent))
+        })
+        .filter_map(|node| first_non_trivia_token(&node))
+        .chain(
+            // The item that follows an attribute starts on a new line
+            root.descendants()
+                .filter(|node| node.kind() == SyntaxKind::ATTR)
+                .filter_map(|attr| attr.last_token()?.next_token())
+                .filter_map(|token| next_non_trivia_token(&token)),
+        )
+        .map(|token| token.text_range().start())
+        .collect();
+
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut newlines = 0;
+    for token in root
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+    {
+        if token.kind() == SyntaxKind::WHITESPACE {
+            newlines += token.text().matches('\n').count();
+            continue;
+        }
+
+        let parent = token.parent().expect("a token always has a parent");
+        let expanded = matches!(token.kind(), SyntaxKind::L_CURLY | SyntaxKind::R_CURLY)
+            && is_line_container(&parent);
+
+        // Struct definitions that span multiple lines end with a trailing comma
+        if expanded
+            && token.kind() == SyntaxKind::R_CURLY
+            && parent.kind() == SyntaxKind::RECORD_FIELD_DEF_LIST
+        {
+            insert_trailing_comma(&mut tokens);
+        }
+
+        tokens.push(Token {
+            kind: token.kind(),
+            text: token.text().to_owned(),
+            parent: parent.kind(),
+            newlines,
+            starts_line: line_starts.contains(&token.text_range().start()),
+            expanded,
+        });
+        newlines = 0;
+    }
+
+    tokens
+}
+
+/// Returns true if every child node of `node` is placed on its own line.
+fn is_line_container(node: &SyntaxNode) -> bool {
+    match node.kind() {
+        SyntaxKind::SOURCE_FILE => true,
+        SyntaxKind::BLOCK_EXPR
+        | SyntaxKind::ASSOCIATED_ITEM_LIST
+        | SyntaxKind::RECORD_FIELD_DEF_LIST => {
+            // Empty bodies are kept on a single line, e.g. `fn foo() {}`
+            node.children_with_tokens().any(|child| {
+                !matches!(
+                    child.kind(),
+                    SyntaxKind::L_CURLY | SyntaxKind::R_CURLY | SyntaxKind::WHITESPACE
+                )
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Returns the first token of `node` that is not whitespace or a comment.
+fn first_non_trivia_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .find(|token| !token.kind().is_trivia())
+}
+
+/// Returns `token` or the first token after it that is not whitespace or a
+/// comment.
+fn next_non_trivia_token(token: &SyntaxToken) -> Option<SyntaxToken> {
+    std::iter::successors(Some(token.clone()), SyntaxToken::next_token)
+        .find(|token| !token.kind().is_trivia())
+}
+
+/// Adds a comma after the last field of a struct definition, if it doesn't
+/// have one already. Comments that follow the last field remain after the
+/// comma.
+fn insert_trailing_comma(tokens: &mut Vec<Token>) {
+    let Some(last) = tokens
+        .iter()
+        .rposition(|token| token.kind != SyntaxKind::COMMENT)
+    else {
+        return;
+    };
+    if matches!(tokens[last].kind, SyntaxKind::COMMA | SyntaxKind::L_CURLY) {
+        return;
+    }
+
+    tokens.insert(
+        last + 1,
+        Token {
+            kind: SyntaxKind::COMMA,
+            text: String::from(","),
+            parent: SyntaxKind::RECORD_FIELD_DEF_LIST,
+            newlines: 0,
+            starts_line: false,
+            expanded: false,
+        },
+    );
+}
+
+/// The whitespace that is placed between two tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Separator {
+    None,
+    Space,
+    Newline { blank_line: bool },
+}
+
+/// An opening delimiter that has not been closed yet.
+#[derive(Debug)]
+struct Delimiter {
+    /// Whether the delimiter is followed by a line break, which indents the
+    /// lines until the closing delimiter. `None` until the first token after
+    /// the delimiter is printed.
+    broken: Option<bool>,
+}
+
+/// Prints tokens with the whitespace between them.
+#[derive(Default)]
+struct Printer {
+    output: String,
+    delimiters: Vec<Delimiter>,
+}
+
+impl Printer {
+    fn print(mut self, tokens: &[Token]) -> String {
+        let mut prev: Option<&Token> = None;
+        for token in tokens {
+            let closed = if token.is_closing_delimiter() {
+                self.delimiters.pop()
+            } else {
+                None
+            };
+            let closes_broken = closed.is_some_and(|delimiter| delimiter.broken == Some(true));
+
+            let separator = prev.map_or(Separator::None, |prev| {
+                separator(prev, token, closes_broken)
+            });
+
+            // The first token after a delimiter determines whether the delimiter is broken.
+            // Comments are skipped, to allow a comment after an opening delimiter.
+            if !token.is_closing_delimiter() && token.kind != SyntaxKind::COMMENT {
+                if let Some(delimiter) = self.delimiters.last_mut() {
+                    if delimiter.broken.is_none() {
+                        delimiter.broken = Some(matches!(separator, Separator::Newline { .. }));
+                    }
+                }
+            }
+
+            match separator {
+                Separator::None => {}
+                Separator::Space => self.output.push(' '),
+                Separator::Newline { blank_line } => {
+                    if blank_line {
+                        self.output.push('\n');
+                    }
+                    self.output.push('\n');
+                    let depth = self
+                        .delimiters
+                        .iter()
+                        .filter(|delimiter| delimiter.broken == Some(true))
+                        .count();
+                    self.output.push_str(&INDENT.repeat(depth));
+                }
+            }
+            self.output.push_str(&token.text);
+
+            if token.is_opening_delimiter() {
+                self.delimiters.push(Delimiter {
+                    broken: token.expanded.then_some(true),
+                });
+            }
+
+            prev = Some(token);
+        }
+
+        let len = self.output.trim_end().len();
+        self.output.truncate(len);
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        self.output
+    }
+}
+
+/// Determines the whitespace between two consecutive tokens.
+/// `closes_broken` is true if `cur` closes a delimiter that is followed by a
+/// line break.
+fn separator(prev: &Token, cur: &Token, closes_broken: bool) -> Separator {
+    // No empty lines directly after an opening or before a closing delimiter
+    let newline = |newlines: usize| Separator::Newline {
+        blank_line: newlines > 1 && !prev.is_opening_delimiter() && !cur.is_closing_delimiter(),
+    };
+
+    if prev.is_line_comment() {
+        newline(cur.newlines)
+    } else if cur.kind == SyntaxKind::COMMENT {
+        // Comments at the end of a line remain there, only line comments can follow the
+        // opening brace of a block
+        if cur.newlines > 0 || (prev.expanded && !cur.is_line_comment()) {
+            newline(cur.newlines)
+        } else {
+            Separator::Space
+        }
+    } else if (prev.is_opening_delimiter() && prev.expanded)
+        || (cur.is_closing_delimiter() && (cur.expanded || closes_broken))
+    {
+        newline(0)
+    } else if cur.kind == SyntaxKind::ELSE_KW
+        || (prev.kind == SyntaxKind::COMMENT && cur.newlines == 0)
+    {
+        // A block comment in front of an item or statement, e.g. `/* a */ let a = 1;`
+        Separator::Space
+    } else if cur.starts_line || cur.newlines > 0 {
+        newline(cur.newlines)
+    } else if needs_space(prev, cur) {
+        Separator::Space
+    } else {
+        Separator::None
+    }
+}
+
+/// Returns true if two consecutive tokens on the same line are separated by a
+/// space.
+fn needs_space(prev: &Token, cur: &Token) -> bool {
+    use SyntaxKind::{
+        ARG_LIST, ATTR, COLON, COLONCOLON, COMMA, DOT, DOTDOT, DOTDOTEQ, GT, HASH, INDEX,
+        INDEX_EXPR, LT, L_BRACKET, L_CURLY, L_PAREN, MEMORY_TYPE_SPECIFIER, PARAM_LIST, QUESTION,
+        R_BRACKET, R_CURLY, R_PAREN, SEMI, TOKEN_TREE, TUPLE_FIELD_DEF_LIST, TYPE_ARG_LIST, USE_KW,
+        USE_TREE_LIST, VISIBILITY,
+    };
+
+    if prev.is_prefix_operator() {
+        return false;
+    }
+
+    match (prev.kind, cur.kind) {
+        (_, COMMA | SEMI | COLON | DOT | INDEX | R_PAREN | R_BRACKET | QUESTION)
+        | (L_PAREN | L_BRACKET | DOT | COLONCOLON | HASH, _)
+        | (L_CURLY, R_CURLY) => false,
+        // Type arguments, e.g. `Result<i32, bool>`
+        (_, LT | GT) if cur.parent == TYPE_ARG_LIST => false,
+        // Ranges, e.g. `0..10`
+        (_, DOTDOT | DOTDOTEQ) | (DOTDOT | DOTDOTEQ, _) => false,
+        (LT, _) if prev.parent == TYPE_ARG_LIST => false,
+        // `use ::foo` or `use {foo, ::bar}`
+        (_, COLONCOLON) => matches!(prev.kind, USE_KW | COMMA),
+        // Calls, parameter lists, tuple structs, `pub(package)`, `struct(gc)` and attributes
+        (_, L_PAREN) => !matches!(
+            cur.parent,
+            ARG_LIST
+                | PARAM_LIST
+                | TUPLE_FIELD_DEF_LIST
+                | VISIBILITY
+                | MEMORY_TYPE_SPECIFIER
+                | TOKEN_TREE
+        ),
+        (_, L_BRACKET) => !matches!(cur.parent, INDEX_EXPR | ATTR),
+        // `use foo::{bar, baz}`
+        (L_CURLY, _) => prev.parent != USE_TREE_LIST,
+        (_, R_CURLY) => cur.parent != USE_TREE_LIST,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests;
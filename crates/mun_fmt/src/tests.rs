@@ -0,0 +1,300 @@
+use mun_syntax::{tokenize, SyntaxKind};
+
+use crate::{format, FormatError};
+
+/// Formats `text` and checks that formatting is idempotent and that only the
+/// whitespace and trailing commas were changed.
+fn check(text: &str) -> String {
+    let formatted = format(text).expect("could not format source");
+    assert_eq!(
+        format(&formatted).expect("could not format formatted source"),
+        formatted,
+        "formatting is not idempotent"
+    );
+    assert_eq!(
+        significant_tokens(&formatted),
+        significant_tokens(text),
+        "formatting changed more than whitespace"
+    );
+    formatted
+}
+
+/// Returns the text of all tokens in `text`, except whitespace and commas.
+fn significant_tokens(text: &str) -> Vec<&str> {
+    let mut offset = 0;
+    tokenize(text)
+        .into_iter()
+        .filter_map(|token| {
+            let start = offset;
+            offset += usize::from(token.len);
+            (!matches!(token.kind, SyntaxKind::WHITESPACE | SyntaxKind::COMMA))
+                .then(|| &text[start..offset])
+        })
+        .collect()
+}
+
+#[test]
+fn functions() {
+    insta::assert_snapshot!(check(
+        r#"
+pub   fn add(a:i32,b :i32)->i32{a+b}
+fn   empty ( ) { }
+extern fn external(value: f32)  ->  f32;
+pub(package) fn  negate(a: bool) -> bool { let b = !a ; -1 ; b }
+"#
+    ), @r###"
+    pub fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+    fn empty() {}
+    extern fn external(value: f32) -> f32;
+    pub(package) fn negate(a: bool) -> bool {
+        let b = !a;
+        -1;
+        b
+    }
+    "###);
+}
+
+#[test]
+fn structs() {
+    insta::assert_snapshot!(check(
+        r#"
+struct Foo { a: i32, b: f64 }
+struct(gc) Bar{a:Foo,
+    // The last field
+    b:[i32]}
+struct(value) Baz ( i32 , f32 ) ;
+struct Unit;
+struct Empty {}
+type Alias=Foo;
+"#
+    ), @r###"
+    struct Foo {
+        a: i32,
+        b: f64,
+    }
+    struct(gc) Bar {
+        a: Foo,
+        // The last field
+        b: [i32],
+    }
+    struct(value) Baz(i32, f32);
+    struct Unit;
+    struct Empty {}
+    type Alias = Foo;
+    "###);
+}
+
+#[test]
+fn expressions() {
+    insta::assert_snapshot!(check(
+        r#"
+fn main(foo: Foo) -> i32 {
+    let a = Foo{a:1,b:2.0};
+    let b = Bar( 1 , 2 );
+    let c:[i32] = [ 1,2,3 ];
+    c [ 0 ]+foo . a+b.0+-( a.a*2 )
+}
+"#
+    ), @r###"
+    fn main(foo: Foo) -> i32 {
+        let a = Foo { a: 1, b: 2.0 };
+        let b = Bar(1, 2);
+        let c: [i32] = [1, 2, 3];
+        c[0] + foo.a + b.0 + -(a.a * 2)
+    }
+    "###);
+}
+
+#[test]
+fn results() {
+    insta::assert_snapshot!(check(
+        r#"
+fn parse(a: i32) -> Result < i32 , [ bool ] > {
+    let b = check( a ) ? ;
+    Ok( b ? + 1 )
+}
+"#
+    ), @r###"
+    fn parse(a: i32) -> Result<i32, [bool]> {
+        let b = check(a)?;
+        Ok(b? + 1)
+    }
+    "###);
+}
+
+#[test]
+fn control_flow() {
+    insta::assert_snapshot!(check(
+        r#"
+fn main(a: i32) -> i32 {
+    let b = if a > 3 { 1 }
+    else if a == 2 { 2 } else { 3 };
+    while b<a { b += 1; if b == 5 { break; } }
+    for i in 0 ..= a { b += i; }
+    loop { return b }
+}
+"#
+    ), @r###"
+    fn main(a: i32) -> i32 {
+        let b = if a > 3 {
+            1
+        } else if a == 2 {
+            2
+        } else {
+            3
+        };
+        while b < a {
+            b += 1;
+            if b == 5 {
+                break;
+            }
+        }
+        for i in 0..=a {
+            b += i;
+        }
+        loop {
+            return b
+        }
+    }
+    "###);
+}
+
+#[test]
+fn use_and_impl() {
+    insta::assert_snapshot!(check(
+        r#"
+use   package::foo::{ self,Bar as Baz };
+use super::*;
+impl Foo { fn new() -> Self { Foo::with(0) }
+
+    fn with(a: i32) -> Self { Self { a } } }
+"#
+    ), @r###"
+    use package::foo::{self, Bar as Baz};
+    use super::*;
+    impl Foo {
+        fn new() -> Self {
+            Foo::with(0)
+        }
+
+        fn with(a: i32) -> Self {
+            Self { a }
+        }
+    }
+    "###);
+}
+
+#[test]
+fn comments() {
+    insta::assert_snapshot!(check(
+        r#"
+// A comment at the start of the file
+
+
+/// Adds two numbers
+fn add(a: i32, b: i32) -> i32 { // trailing comment
+    /* a block comment */ a +
+        // a comment between operands
+        b
+}
+fn main() { /* a block comment after a brace */ let a = add( // first
+        1,
+        2);
+    // A comment at the end of a block
+}
+// A comment at the end of the file"#
+    ), @r###"
+    // A comment at the start of the file
+
+    /// Adds two numbers
+    fn add(a: i32, b: i32) -> i32 { // trailing comment
+        /* a block comment */ a +
+        // a comment between operands
+        b
+    }
+    fn main() {
+        /* a block comment after a brace */ let a = add( // first
+            1,
+            2
+        );
+        // A comment at the end of a block
+    }
+    // A comment at the end of the file
+    "###);
+}
+
+#[test]
+fn preserves_line_breaks() {
+    insta::assert_snapshot!(check(
+        r#"
+
+
+
+fn main() -> Foo {
+
+
+    let a = 1;
+
+
+
+    let b = 2;
+
+    Foo {
+      a,
+            b,
+    }
+
+}
+"#
+    ), @r###"
+    fn main() -> Foo {
+        let a = 1;
+
+        let b = 2;
+
+        Foo {
+            a,
+            b,
+        }
+    }
+    "###);
+}
+
+#[test]
+fn attributes() {
+    insta::assert_snapshot!(check(
+        r#"
+# [ allow ( dead_code ,unused_variables ) ] fn foo() { let a = 1; }
+impl Foo {
+#[allow(unused_parameters)]   #[test]
+fn bar(a: i32) {}
+}
+"#
+    ), @r###"
+    #[allow(dead_code, unused_variables)]
+    fn foo() {
+        let a = 1;
+    }
+    impl Foo {
+        #[allow(unused_parameters)]
+        #[test]
+        fn bar(a: i32) {}
+    }
+    "###);
+}
+
+#[test]
+fn empty_file() {
+    assert_eq!(check(""), "");
+    assert_eq!(check("  \n\n "), "");
+}
+
+#[test]
+fn syntax_errors() {
+    assert!(matches!(
+        format("fn main( {"),
+        Err(FormatError::SyntaxErrors(errors)) if !errors.is_empty()
+    ));
+}
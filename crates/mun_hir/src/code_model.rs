@@ -12,8 +12,11 @@ pub use self::{
     function::{Function, FunctionData},
     module::{Module, ModuleDef},
     package::Package,
-    r#impl::{AssocItem, ImplData},
-    r#struct::{Field, Struct, StructData, StructKind, StructMemoryKind},
+    r#impl::{AssocItem, Impl, ImplData},
+    r#struct::{
+        Field, FieldDefault, FieldDefaultValue, Struct, StructData, StructKind, StructMemoryKind,
+        StructRepr,
+    },
     src::HasSource,
     type_alias::{TypeAlias, TypeAliasData},
 };
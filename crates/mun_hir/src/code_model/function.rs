@@ -3,11 +3,11 @@ use std::{iter::once, sync::Arc};
 use mun_hir_input::FileId;
 use mun_syntax::{ast, ast::TypeAscriptionOwner};
 
-use super::Module;
+use super::{Impl, Module};
 use crate::{
     expr::{validator::ExprValidator, BodySourceMap},
     has_module::HasModule,
-    ids::{FunctionId, Lookup},
+    ids::{FunctionId, ItemContainerId, Lookup},
     item_tree::FunctionFlags,
     name_resolution::Namespace,
     resolve::HasResolver,
@@ -103,6 +103,21 @@ impl FunctionData {
     pub fn is_extern(&self) -> bool {
         self.flags.is_extern()
     }
+
+    /// Returns true if this function is annotated with `#[test]`.
+    pub fn is_test(&self) -> bool {
+        self.flags.is_test()
+    }
+
+    /// Returns true if this function is annotated with `#[bench]`.
+    pub fn is_bench(&self) -> bool {
+        self.flags.is_bench()
+    }
+
+    /// Returns true if this function is marked as `resumable`.
+    pub fn is_resumable(&self) -> bool {
+        self.flags.is_resumable()
+    }
 }
 
 impl Function {
@@ -110,14 +125,31 @@ impl Function {
         self.id.module(db.upcast()).into()
     }
 
+    /// Returns the `impl` in which this function is defined or `None` if it
+    /// is defined in a module.
+    pub fn parent_impl(self, db: &dyn HirDatabase) -> Option<Impl> {
+        match self.id.lookup(db.upcast()).container {
+            ItemContainerId::ImplId(id) => Some(id.into()),
+            ItemContainerId::ModuleId(_) => None,
+        }
+    }
+
     /// Returns the full name of the function including all module specifiers
-    /// (e.g: `foo::bar`).
+    /// (e.g: `foo::bar`), prefixed with the name of its package if the package
+    /// has one.
     pub fn full_name(self, db: &dyn HirDatabase) -> String {
+        let module = self.module(db);
         itertools::Itertools::intersperse(
-            self.module(db)
-                .path_to_root(db)
+            module
+                .package()
+                .name(db)
                 .into_iter()
-                .filter_map(|module| module.name(db))
+                .chain(
+                    module
+                        .path_to_root(db)
+                        .into_iter()
+                        .filter_map(|module| module.name(db)),
+                )
                 .chain(once(self.name(db).to_string())),
             String::from("::"),
         )
@@ -172,6 +204,21 @@ impl Function {
         db.fn_data(self.id).flags.is_extern()
     }
 
+    /// Returns true if this function is annotated with `#[test]`.
+    pub fn is_test(self, db: &dyn HirDatabase) -> bool {
+        db.fn_data(self.id).flags.is_test()
+    }
+
+    /// Returns true if this function is annotated with `#[bench]`.
+    pub fn is_bench(self, db: &dyn HirDatabase) -> bool {
+        db.fn_data(self.id).flags.is_bench()
+    }
+
+    /// Returns true if this function is marked as `resumable`.
+    pub fn is_resumable(self, db: &dyn HirDatabase) -> bool {
+        db.fn_data(self.id).flags.is_resumable()
+    }
+
     pub(crate) fn body_source_map(self, db: &dyn HirDatabase) -> Arc<BodySourceMap> {
         db.body_with_source_map(self.id.into()).1
     }
@@ -22,6 +22,20 @@ impl Impl {
         inherent_impls.all_impls().map(Self::from).collect()
     }
 
+    /// Returns all the implementations for the specified type.
+    pub fn all_for_type(db: &dyn HirDatabase, ty: &Ty) -> Vec<Impl> {
+        let Some(strukt) = ty.as_struct() else {
+            return Vec::new();
+        };
+        let inherent_impls = db.inherent_impls_in_package(strukt.module(db).package().id);
+        inherent_impls
+            .for_self_ty(ty)
+            .iter()
+            .copied()
+            .map(Self::from)
+            .collect()
+    }
+
     /// The module in which the `impl` was defined.
     ///
     /// Note that this is not necessarily the module in which the self type was
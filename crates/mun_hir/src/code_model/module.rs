@@ -1,7 +1,10 @@
 use mun_hir_input::{FileId, ModuleId};
 
 use super::{r#impl::Impl, AssocItem, Function, Package, Struct, TypeAlias};
-use crate::{ids::ItemDefinitionId, primitive_type::PrimitiveType, DiagnosticSink, HirDatabase};
+use crate::{
+    ids::ItemDefinitionId, name_resolution::PerNs, primitive_type::PrimitiveType,
+    semantics::ScopeDef, DiagnosticSink, HirDatabase, Name, Visibility,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub struct Module {
@@ -72,6 +75,39 @@ impl Module {
             .collect()
     }
 
+    /// Returns all the names that are in scope in this module, both
+    /// declarations and imports. If `visible_from` is specified, only the
+    /// items that are visible from that module are returned.
+    pub fn scope(
+        self,
+        db: &dyn HirDatabase,
+        visible_from: Option<Module>,
+    ) -> Vec<(Name, ScopeDef)> {
+        let package_defs = db.package_defs(self.id.package);
+        package_defs.modules[self.id.local_id]
+            .entries()
+            .filter_map(|(name, def)| {
+                let def = match visible_from {
+                    Some(module) => {
+                        let is_visible =
+                            |(_, vis): &(_, Visibility)| vis.is_visible_from(db, module.id);
+                        PerNs {
+                            types: def.types.filter(is_visible),
+                            values: def.values.filter(is_visible),
+                        }
+                    }
+                    None => def,
+                };
+                (!def.is_none()).then(|| (name.clone(), def))
+            })
+            .flat_map(|(name, def)| {
+                ScopeDef::all_items(def)
+                    .into_iter()
+                    .map(move |item| (name.clone(), item))
+            })
+            .collect()
+    }
+
     /// Iterate over all diagnostics from this `Module` by placing them in the
     /// `sink`
     pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink<'_>) {
@@ -111,6 +147,13 @@ impl Module {
         }
     }
 
+    /// Adds the warnings of all lints for the items in this module to the
+    /// `DiagnosticSink`. Unlike [`Module::diagnostics`], these don't prevent
+    /// the module from being compiled.
+    pub fn lints(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink<'_>) {
+        crate::lints::module_lints(db, self, sink);
+    }
+
     /// Returns all the child modules of this module
     pub fn children(self, db: &dyn HirDatabase) -> Vec<Module> {
         let module_tree = db.module_tree(self.id.package);
@@ -137,12 +180,15 @@ impl Module {
         res
     }
 
-    /// Returns the name of this module including all parent modules
+    /// Returns the name of this module including all parent modules, prefixed
+    /// with the name of its package if the package has one.
     pub fn full_name(self, db: &dyn HirDatabase) -> String {
         itertools::Itertools::intersperse(
-            self.path_to_root(db)
-                .iter()
-                .filter_map(|&module| module.name(db)),
+            self.package().name(db).into_iter().chain(
+                self.path_to_root(db)
+                    .iter()
+                    .filter_map(|&module| module.name(db)),
+            ),
             String::from("::"),
         )
         .collect()
@@ -166,6 +212,20 @@ pub enum ModuleDef {
     TypeAlias(TypeAlias),
 }
 
+impl ModuleDef {
+    /// Returns the module in which this item is defined. For a module this is
+    /// its parent. Returns `None` for builtin types and the root module.
+    pub fn module(self, db: &dyn HirDatabase) -> Option<Module> {
+        match self {
+            ModuleDef::Module(it) => it.parent(db),
+            ModuleDef::Function(it) => Some(it.module(db)),
+            ModuleDef::Struct(it) => Some(it.module(db)),
+            ModuleDef::TypeAlias(it) => Some(it.module(db)),
+            ModuleDef::PrimitiveType(_) => None,
+        }
+    }
+}
+
 impl From<Function> for ModuleDef {
     fn from(t: Function) -> Self {
         ModuleDef::Function(t)
@@ -15,6 +15,12 @@ impl Package {
         db.packages().iter().map(|id| Package { id }).collect()
     }
 
+    /// Returns the name by which other packages refer to this package, or
+    /// `None` if the package cannot be referred to by other packages.
+    pub fn name(self, db: &dyn HirDatabase) -> Option<String> {
+        db.packages()[self.id].name.clone()
+    }
+
     /// Returns the root module of the package (represented by the `mod.rs` in
     /// the source root)
     pub fn root_module(self, db: &dyn HirDatabase) -> Module {
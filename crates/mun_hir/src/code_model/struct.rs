@@ -1,15 +1,17 @@
 use std::{fmt, iter::once, sync::Arc};
 
-pub use ast::StructMemoryKind;
+pub use ast::{StructMemoryKind, StructRepr};
 use la_arena::{Arena, Idx};
 use mun_hir_input::FileId;
 use mun_syntax::{
     ast,
-    ast::{NameOwner, TypeAscriptionOwner, VisibilityOwner},
+    ast::{ExternOwner, NameOwner, TypeAscriptionOwner, VisibilityOwner},
+    SyntaxToken,
 };
 
 use super::Module;
 use crate::{
+    expr::lower_literal,
     has_module::HasModule,
     ids::{Lookup, StructId},
     name::AsName,
@@ -18,7 +20,7 @@ use crate::{
     ty::lower::LowerTyMap,
     type_ref::{LocalTypeRefId, TypeRefMap, TypeRefSourceMap},
     visibility::RawVisibility,
-    DefDatabase, DiagnosticSink, HasVisibility, HirDatabase, Name, Ty, Visibility,
+    DefDatabase, DiagnosticSink, HasVisibility, HirDatabase, Literal, Name, Ty, Visibility,
 };
 
 pub(crate) mod validator;
@@ -55,6 +57,36 @@ impl Field {
         self.parent.data(db.upcast()).fields[self.id].name.clone()
     }
 
+    /// Returns the value that the field is initialized with if it is omitted
+    /// from a record literal, or `None` if the field has no (valid) default
+    /// value.
+    pub fn default_value(self, db: &dyn HirDatabase) -> Option<FieldDefaultValue> {
+        let data = self.parent.data(db.upcast());
+        let FieldDefault::Literal { literal, negated } = data.fields[self.id].default.as_ref()?
+        else {
+            return None;
+        };
+        match literal {
+            Literal::Bool(value) if !negated => Some(FieldDefaultValue::Bool(*value)),
+            Literal::Int(literal) => Some(FieldDefaultValue::Int(if *negated {
+                literal.value.wrapping_neg()
+            } else {
+                literal.value
+            })),
+            Literal::Float(literal) => Some(FieldDefaultValue::Float(if *negated {
+                -literal.value
+            } else {
+                literal.value
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the struct that defines this field
+    pub fn parent_struct(self) -> Struct {
+        self.parent
+    }
+
     /// Returns the index of this field in the parent
     pub fn index(self, _db: &dyn HirDatabase) -> u32 {
         self.id.into_raw().into()
@@ -86,13 +118,21 @@ impl Struct {
     }
 
     /// Returns the full name of the struct including all module specifiers
-    /// (e.g: `foo::Bar`).
+    /// (e.g: `foo::Bar`), prefixed with the name of its package if the package
+    /// has one.
     pub fn full_name(self, db: &dyn HirDatabase) -> String {
+        let module = self.module(db);
         itertools::Itertools::intersperse(
-            self.module(db)
-                .path_to_root(db)
+            module
+                .package()
+                .name(db)
                 .into_iter()
-                .filter_map(|module| module.name(db))
+                .chain(
+                    module
+                        .path_to_root(db)
+                        .into_iter()
+                        .filter_map(|module| module.name(db)),
+                )
                 .chain(once(self.name(db).to_string())),
             String::from("::"),
         )
@@ -119,6 +159,12 @@ impl Struct {
         db.type_for_def(self.into(), Namespace::Types)
     }
 
+    /// Returns true if this is an opaque `extern struct` whose instances are
+    /// owned by the host.
+    pub fn is_extern(self, db: &dyn HirDatabase) -> bool {
+        self.data(db.upcast()).is_extern
+    }
+
     pub fn lower(self, db: &dyn HirDatabase) -> Arc<LowerTyMap> {
         db.lower_struct(self)
     }
@@ -129,6 +175,9 @@ impl Struct {
         lower.add_diagnostics(db, self.file_id(db), data.type_ref_source_map(), sink);
         let validator = validator::StructValidator::new(self, db, self.file_id(db));
         validator.validate_privacy(sink);
+        validator.validate_extern(sink);
+        validator.validate_repr(sink);
+        validator.validate_defaults(sink);
     }
 }
 
@@ -136,6 +185,7 @@ impl Struct {
 /// ```mun
 /// struct Foo {
 ///     a: int, // <- this
+///     b: int = 1, // <- or this, which has a default value
 /// }
 /// ```
 /// or
@@ -149,6 +199,28 @@ pub struct FieldData {
     pub name: Name,
     pub type_ref: LocalTypeRefId,
     pub visibility: RawVisibility,
+    pub default: Option<FieldDefault>,
+}
+
+/// The default value of a record field, e.g. `100` in `hp: i32 = 100`. Fields
+/// that have a default value can be omitted from record literals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldDefault {
+    /// A literal, which is negated for defaults like `-1`
+    Literal { literal: Literal, negated: bool },
+    /// An expression that is not a (negated) literal, which is reported by the
+    /// validator
+    Unsupported,
+}
+
+/// The value of a field's default, see [`Field::default_value`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldDefaultValue {
+    Bool(bool),
+    /// The two's complement representation of an integer, which is truncated
+    /// to the bitness of the field
+    Int(u128),
+    Float(f64),
 }
 
 /// A struct's fields' data (record, tuple, or unit struct)
@@ -179,6 +251,8 @@ pub struct StructData {
     pub fields: Arena<FieldData>,
     pub kind: StructKind,
     pub memory_kind: StructMemoryKind,
+    pub repr: StructRepr,
+    pub is_extern: bool,
     type_ref_map: TypeRefMap,
     type_ref_source_map: TypeRefSourceMap,
 }
@@ -195,6 +269,12 @@ impl StructData {
             .map(|s| s.kind())
             .unwrap_or_default();
 
+        let repr = if src.is_extern() {
+            StructRepr::Default
+        } else {
+            repr_from_hints(src.repr_hints())
+        };
+
         let mut type_ref_builder = TypeRefMap::builder();
         let (fields, kind) = match src.kind() {
             ast::StructKind::Record(r) => {
@@ -204,6 +284,13 @@ impl StructData {
                         name: fd.name().map_or_else(Name::missing, |n| n.as_name()),
                         type_ref: type_ref_builder.alloc_from_node_opt(fd.ascribed_type().as_ref()),
                         visibility: RawVisibility::from_ast(fd.visibility()),
+                        default: fd.default_value().map(|expr| match default_literal(&expr) {
+                            Some((literal, negated)) => FieldDefault::Literal {
+                                literal: lower_literal(&literal).0,
+                                negated,
+                            },
+                            None => FieldDefault::Unsupported,
+                        }),
                     })
                     .collect();
                 (fields, StructKind::Record)
@@ -216,6 +303,7 @@ impl StructData {
                         name: Name::new_tuple_field(index),
                         type_ref: type_ref_builder.alloc_from_node_opt(fd.type_ref().as_ref()),
                         visibility: RawVisibility::from_ast(fd.visibility()),
+                        default: None,
                     })
                     .collect();
                 (fields, StructKind::Tuple)
@@ -232,6 +320,8 @@ impl StructData {
             fields,
             kind,
             memory_kind,
+            repr,
+            is_extern: src.is_extern(),
             type_ref_map,
             type_ref_source_map,
         })
@@ -246,6 +336,37 @@ impl StructData {
     }
 }
 
+/// Returns the literal of a field's default value and whether it is negated,
+/// e.g. `1` and `true` for `-1`, or `None` if the default value is not a
+/// (negated) literal.
+pub(crate) fn default_literal(expr: &ast::Expr) -> Option<(ast::Literal, bool)> {
+    match expr.kind() {
+        ast::ExprKind::Literal(literal) => Some((literal, false)),
+        ast::ExprKind::PrefixExpr(prefix) if prefix.op_kind() == Some(ast::PrefixOp::Neg) => {
+            match prefix.expr()?.kind() {
+                ast::ExprKind::Literal(literal) => Some((literal, true)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Determines how the fields of a struct are laid out in memory from the hints
+/// of its `#[repr(..)]` attributes. Unknown hints are reported by the
+/// validator and otherwise ignored.
+fn repr_from_hints(hints: impl Iterator<Item = SyntaxToken>) -> StructRepr {
+    let mut repr = StructRepr::Default;
+    for hint in hints {
+        match hint.text() {
+            "packed" => repr = StructRepr::Packed,
+            "C" if repr == StructRepr::Default => repr = StructRepr::C,
+            _ => (),
+        }
+    }
+    repr
+}
+
 impl HasVisibility for Struct {
     fn visibility(&self, db: &dyn HirDatabase) -> Visibility {
         self.data(db.upcast())
@@ -253,3 +374,13 @@ impl HasVisibility for Struct {
             .resolve(db.upcast(), &self.id.resolver(db.upcast()))
     }
 }
+
+impl HasVisibility for Field {
+    /// Fields are private to the module of their struct, unless they are
+    /// declared with a visibility modifier.
+    fn visibility(&self, db: &dyn HirDatabase) -> Visibility {
+        self.parent.data(db.upcast()).fields[self.id]
+            .visibility
+            .resolve(db.upcast(), &self.parent.id.resolver(db.upcast()))
+    }
+}
@@ -1,9 +1,23 @@
+use std::collections::HashSet;
+
 use mun_hir_input::FileId;
+use mun_syntax::{ast, ast::AttrsOwner, AstNode, AstPtr, SyntaxNodePtr};
 
-use super::Struct;
+use super::{default_literal, Struct, StructKind, StructMemoryKind, StructRepr};
 use crate::{
-    diagnostics::ExportedPrivate, resolve::HasResolver, visibility::RawVisibility, DiagnosticSink,
-    HasVisibility, HirDatabase, Ty, Visibility,
+    code_model::src::HasSource,
+    diagnostics::{
+        CannotApplyUnaryOp, ExportedPrivate, ExternStructCannotHaveFields,
+        ExternStructCannotHaveMemoryKind, ExternStructCannotHaveRepr, LiteralOutOfRange,
+        MismatchedType, PackedStructCannotContainReferences, UnknownReprHint,
+        UnsupportedFieldDefault,
+    },
+    expr::{lower_literal, LiteralFloatKind, LiteralIntKind},
+    in_file::InFile,
+    resolve::HasResolver,
+    ty::{FloatTy, IntTy, ResolveBitness},
+    visibility::RawVisibility,
+    DiagnosticSink, HasVisibility, HirDatabase, Literal, Ty, TyKind, Visibility,
 };
 
 #[cfg(test)]
@@ -59,4 +73,207 @@ impl<'a> StructValidator<'a> {
                 });
             });
     }
+
+    /// Validates that an `extern struct` is opaque: it neither declares fields
+    /// nor specifies how its memory is managed.
+    pub fn validate_extern(&self, sink: &mut DiagnosticSink<'_>) {
+        let struct_data = self.strukt.data(self.db.upcast());
+        if !struct_data.is_extern {
+            return;
+        }
+
+        let src = self.strukt.source(self.db.upcast());
+        if struct_data.kind != StructKind::Unit {
+            sink.push(ExternStructCannotHaveFields {
+                strukt: src.as_ref().map(|s| SyntaxNodePtr::new(s.syntax())),
+            });
+        }
+
+        if let Some(memory_type_specifier) = src.value.memory_type_specifier() {
+            sink.push(ExternStructCannotHaveMemoryKind {
+                memory_kind: InFile::new(
+                    self.file_id,
+                    SyntaxNodePtr::new(memory_type_specifier.syntax()),
+                ),
+            });
+        }
+    }
+
+    /// Validates the `#[repr(..)]` attributes of the struct. Their hints must
+    /// be known, extern structs cannot specify a representation, and the
+    /// fields of a packed struct cannot contain references.
+    pub fn validate_repr(&self, sink: &mut DiagnosticSink<'_>) {
+        let struct_data = self.strukt.data(self.db.upcast());
+        let src = self.strukt.source(self.db.upcast());
+
+        let repr_attrs = src
+            .value
+            .attrs()
+            .filter(|attr| attr.simple_name().as_deref() == Some("repr"));
+        for attr in repr_attrs {
+            let attr_ptr = InFile::new(self.file_id, SyntaxNodePtr::new(attr.syntax()));
+            if struct_data.is_extern {
+                sink.push(ExternStructCannotHaveRepr { attr: attr_ptr });
+                continue;
+            }
+
+            let hints = attr
+                .token_tree()
+                .into_iter()
+                .flat_map(|token_tree| token_tree.idents().collect::<Vec<_>>());
+            for hint in hints {
+                if !matches!(hint.text(), "C" | "packed") {
+                    sink.push(UnknownReprHint {
+                        attr: attr_ptr.clone(),
+                        hint: hint.text().into(),
+                        hint_range: hint.text_range(),
+                    });
+                }
+            }
+        }
+
+        if struct_data.repr != StructRepr::Packed {
+            return;
+        }
+
+        let resolver = self.strukt.id.resolver(self.db.upcast());
+        for (_, field_data) in struct_data.fields.iter() {
+            let type_ref = field_data.type_ref;
+            let (ty, _) = Ty::from_hir(self.db, &resolver, struct_data.type_ref_map(), type_ref);
+            if contains_references(self.db, &ty, &mut HashSet::new()) {
+                sink.push(PackedStructCannotContainReferences {
+                    file: self.file_id,
+                    type_ref: struct_data
+                        .type_ref_source_map()
+                        .type_ref_syntax(type_ref)
+                        .unwrap(),
+                });
+            }
+        }
+    }
+
+    /// Validates the default values of the fields. A default value must be a
+    /// (negated) literal whose type matches the type of its field.
+    pub fn validate_defaults(&self, sink: &mut DiagnosticSink<'_>) {
+        let src = self.strukt.source(self.db.upcast());
+        let ast::StructKind::Record(record) = src.value.kind() else {
+            return;
+        };
+
+        for (field, field_src) in self.strukt.fields(self.db).into_iter().zip(record.fields()) {
+            let Some(default) = field_src.default_value() else {
+                continue;
+            };
+            let default_ptr = SyntaxNodePtr::new(default.syntax());
+
+            let Some((literal, negated)) = default_literal(&default) else {
+                sink.push(UnsupportedFieldDefault {
+                    file: self.file_id,
+                    default: default_ptr,
+                    field_name: field.name(self.db),
+                });
+                continue;
+            };
+            let literal_ptr = InFile::new(self.file_id, AstPtr::new(&literal));
+            let (literal, errors) = lower_literal(&literal);
+            if !errors.is_empty() {
+                for err in errors {
+                    err.add_to(literal_ptr.clone(), sink);
+                }
+                continue;
+            }
+
+            let ty = match field.ty(self.db).interned() {
+                TyKind::TypeAlias(alias) => alias.target_type(self.db),
+                _ => field.ty(self.db),
+            };
+
+            // Unsuffixed literals can be of any integer or floating-point type,
+            // respectively. Otherwise, they default to `i32` and `f64`.
+            let literal_ty = match &literal {
+                Literal::Bool(_) => TyKind::Bool.intern(),
+                Literal::Int(lit) => match lit.kind {
+                    LiteralIntKind::Suffixed(suffix) => TyKind::Int(IntTy {
+                        bitness: suffix.bitness,
+                        signedness: suffix.signedness,
+                    })
+                    .intern(),
+                    LiteralIntKind::Unsuffixed if matches!(ty.interned(), TyKind::Int(_)) => {
+                        ty.clone()
+                    }
+                    LiteralIntKind::Unsuffixed => TyKind::Int(IntTy::i32()).intern(),
+                },
+                Literal::Float(lit) => match lit.kind {
+                    LiteralFloatKind::Suffixed(suffix) => TyKind::Float(FloatTy {
+                        bitness: suffix.bitness,
+                    })
+                    .intern(),
+                    LiteralFloatKind::Unsuffixed if matches!(ty.interned(), TyKind::Float(_)) => {
+                        ty.clone()
+                    }
+                    LiteralFloatKind::Unsuffixed => TyKind::Float(FloatTy::f64()).intern(),
+                },
+                Literal::String(_) => {
+                    sink.push(UnsupportedFieldDefault {
+                        file: self.file_id,
+                        default: default_ptr,
+                        field_name: field.name(self.db),
+                    });
+                    continue;
+                }
+            };
+
+            if ty.is_unknown() {
+                // An error has already been emitted for the type of the field
+            } else if ty != literal_ty {
+                sink.push(MismatchedType {
+                    file: self.file_id,
+                    expr: default_ptr,
+                    expected: ty,
+                    found: literal_ty,
+                });
+            } else if negated && matches!(literal, Literal::Bool(_)) {
+                sink.push(CannotApplyUnaryOp {
+                    file: self.file_id,
+                    expr: default_ptr,
+                    ty,
+                });
+            } else if let (Literal::Int(lit), TyKind::Int(int_ty)) = (&literal, ty.interned()) {
+                if lit.value > int_ty.resolve(&self.db.target_data_layout()).max() {
+                    sink.push(LiteralOutOfRange {
+                        literal: literal_ptr,
+                        int_ty: *int_ty,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Returns true if a value of type `ty` contains a reference: a
+/// garbage-collected struct, an extern struct, or an array. The `visited`
+/// value structs are skipped to guard against recursive structs.
+fn contains_references(db: &dyn HirDatabase, ty: &Ty, visited: &mut HashSet<Struct>) -> bool {
+    match ty.interned() {
+        TyKind::Struct(s) => {
+            let data = s.data(db.upcast());
+            if data.is_extern || data.memory_kind == StructMemoryKind::Gc {
+                return true;
+            }
+            visited.insert(*s)
+                && s.fields(db)
+                    .into_iter()
+                    .any(|field| contains_references(db, &field.ty(db), visited))
+        }
+        TyKind::Array(_) => true,
+        TyKind::Result(ok_ty, err_ty) => {
+            contains_references(db, ok_ty, visited) || contains_references(db, err_ty, visited)
+        }
+        TyKind::Tuple(_, substs) => substs
+            .interned()
+            .iter()
+            .any(|ty| contains_references(db, ty, visited)),
+        TyKind::TypeAlias(alias) => contains_references(db, &alias.target_type(db), visited),
+        _ => false,
+    }
 }
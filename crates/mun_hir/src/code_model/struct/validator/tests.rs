@@ -40,3 +40,70 @@ fn test_private_leak_struct_fields() {
     394..397: can't leak private type
     "###);
 }
+
+#[test]
+fn test_struct_repr() {
+    insta::assert_snapshot!(diagnostics(
+        r#"
+    #[repr(C)]
+    struct(value) Vec3 { x: f32, y: f32, z: f32 }
+
+    #[repr(C, packed)]
+    struct(value) Particle { position: Vec3, alive: bool }
+
+    #[repr(align)]
+    struct Unknown;
+
+    #[repr(C)]
+    extern struct Handle;
+
+    #[repr(packed)]
+    struct Invalid { gc: Unknown, array: [i32], particle: Particle }
+    "#),
+    @r###"
+    140..145: unrecognized representation hint `align`, expected `C` or `packed`
+    165..175: extern structs cannot specify a representation
+    236..243: fields of packed structs cannot contain references
+    252..257: fields of packed structs cannot contain references
+    "###);
+}
+
+#[test]
+fn test_struct_field_defaults() {
+    insta::assert_snapshot!(diagnostics(
+        r#"
+    struct Enemy {
+        hp: i32 = 100,
+        speed: f32 = -1.5,
+        alive: bool = true,
+        level: u8 = 1u8,
+        xp: Experience = 0,
+        name: Enemy,
+    }
+
+    type Experience = u64;
+
+    struct Invalid {
+        a: i32 = 1 + 2,
+        b: f32 = 1,
+        c: bool = -false,
+        d: u8 = 256,
+        e: i32 = 1u8,
+        f: f64 = 1_foo,
+        g: bool = "true",
+        h: i32 = max_hp(),
+    }
+
+    fn max_hp() -> i32 { 100 }
+    "#),
+    @r#"
+    200..205: unsupported default value for field `a`, expected a boolean or number literal
+    220..221: mismatched type
+    237..243: cannot apply unary operator
+    257..260: literal out of range for `u8`
+    275..278: mismatched type
+    293..298: invalid suffix `foo`
+    314..320: unsupported default value for field `g`, expected a boolean or number literal
+    335..343: unsupported default value for field `h`, expected a boolean or number literal
+    "#);
+}
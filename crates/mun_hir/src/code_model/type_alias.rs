@@ -50,6 +50,8 @@ impl TypeAlias {
         db.lower_type_alias(self)
     }
 
+    /// Returns the type this alias refers to, resolving any intermediate
+    /// aliases. Returns an unknown type if the alias is cyclic.
     pub fn target_type(self, db: &dyn HirDatabase) -> Ty {
         let data = self.data(db.upcast());
         let mut ty = Ty::from_hir(
@@ -60,7 +62,14 @@ impl TypeAlias {
         )
         .0;
 
+        let mut visited = vec![self];
         while let &TyKind::TypeAlias(alias) = ty.interned() {
+            // Cyclic aliases are reported by the `TypeAliasValidator`
+            if visited.contains(&alias) {
+                return TyKind::Unknown.intern();
+            }
+            visited.push(alias);
+
             let data = alias.data(db.upcast());
             ty = Ty::from_hir(
                 db,
@@ -6,6 +6,7 @@ use mun_db::Upcast;
 use mun_hir_input::{FileId, PackageId, SourceDatabase};
 use mun_syntax::{ast, Parse, SourceFile};
 use mun_target::{abi, spec::Target};
+use rustc_hash::FxHashSet;
 
 use crate::{
     code_model::{FunctionData, ImplData, StructData, TypeAliasData},
@@ -119,6 +120,9 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
 
     #[salsa::invoke(InherentImpls::inherent_impls_in_package_query)]
     fn inherent_impls_in_package(&self, package: PackageId) -> Arc<InherentImpls>;
+
+    #[salsa::invoke(crate::lints::used_functions_query)]
+    fn used_functions(&self, package: PackageId) -> Arc<FxHashSet<FunctionId>>;
 }
 
 fn parse_query(db: &dyn AstDatabase, file_id: FileId) -> Parse<SourceFile> {
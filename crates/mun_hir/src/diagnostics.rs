@@ -5,6 +5,17 @@ use mun_syntax::{ast, AstPtr, SmolStr, SyntaxNode, SyntaxNodePtr, TextRange};
 
 use crate::{code_model::StructKind, in_file::InFile, HirDatabase, IntTy, Name, Ty};
 
+/// The severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// The code is invalid and cannot be compiled
+    Error,
+
+    /// The code is valid but likely contains a mistake, e.g. a variable that is
+    /// never used
+    Warning,
+}
+
 /// Diagnostic defines `mun_hir` API for errors and warnings.
 ///
 /// It is used as a `dyn` object, which you can downcast to concrete
@@ -21,6 +32,9 @@ pub trait Diagnostic: Any + Send + Sync + fmt::Debug + 'static {
     fn highlight_range(&self) -> TextRange {
         self.source().value.range()
     }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
     fn as_any(&self) -> &(dyn Any + Send + 'static);
 }
 
@@ -159,6 +173,29 @@ impl Diagnostic for PrivateAccess {
     }
 }
 
+/// An error that is emitted when a field is accessed or initialized outside of
+/// the modules that it is visible from.
+#[derive(Debug)]
+pub struct PrivateField {
+    pub file: FileId,
+    pub field: SyntaxNodePtr,
+    pub name: Name,
+}
+
+impl Diagnostic for PrivateField {
+    fn message(&self) -> String {
+        format!("field `{}` is private", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.field.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct ExpectedFunction {
     pub file: FileId,
@@ -359,6 +396,109 @@ impl Diagnostic for CannotApplyUnaryOp {
     }
 }
 
+#[derive(Debug)]
+pub struct InvalidCast {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub from: Ty,
+    pub to: Ty,
+}
+
+impl Diagnostic for InvalidCast {
+    fn message(&self) -> String {
+        "invalid cast".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidTryOperand {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub found: Ty,
+}
+
+impl Diagnostic for InvalidTryOperand {
+    fn message(&self) -> String {
+        "the `?` operator can only be applied to values of type `Result`".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct TryOutsideResultFn {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for TryOutsideResultFn {
+    fn message(&self) -> String {
+        "the `?` operator can only be used in a function that returns `Result`".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct YieldOutsideResumableFn {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for YieldOutsideResumableFn {
+    fn message(&self) -> String {
+        "`yield` can only be used in a resumable function".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct ResumableCallOutsideResumableFn {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for ResumableCallOutsideResumableFn {
+    fn message(&self) -> String {
+        "a resumable function can only be called from another resumable function".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct DuplicateDefinition {
     pub name: String,
@@ -440,6 +580,92 @@ impl Diagnostic for BreakWithValueOutsideLoop {
     }
 }
 
+#[derive(Debug)]
+pub struct RangeOutsideFor {
+    pub file: FileId,
+    pub range_expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for RangeOutsideFor {
+    fn message(&self) -> String {
+        "ranges can only be used as the iterable of a `for` loop".to_owned()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.range_expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidFormatString {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for InvalidFormatString {
+    fn message(&self) -> String {
+        "invalid format string: unmatched brace, use `{{` or `}}` to write a brace".to_owned()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct FormatArgCountMismatch {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl Diagnostic for FormatArgCountMismatch {
+    fn message(&self) -> String {
+        format!(
+            "the format string has {} placeholders but {} arguments were supplied",
+            self.expected, self.found
+        )
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct CannotFormat {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+    pub found: Ty,
+}
+
+impl Diagnostic for CannotFormat {
+    fn message(&self) -> String {
+        "only booleans, integers and floating-point numbers can be formatted".to_owned()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct AccessUnknownField {
     pub file: FileId,
@@ -628,7 +854,8 @@ pub struct ExternNonPrimitiveParam {
 
 impl Diagnostic for ExternNonPrimitiveParam {
     fn message(&self) -> String {
-        "extern functions can only have primitives as parameter- and return types".to_string()
+        "extern functions can only have primitives or extern structs as parameter- and return types"
+            .to_string()
     }
 
     fn source(&self) -> InFile<SyntaxNodePtr> {
@@ -640,6 +867,195 @@ impl Diagnostic for ExternNonPrimitiveParam {
     }
 }
 
+/// An error that is emitted if an `extern struct` declares fields. The layout
+/// of an extern struct is only known to the host.
+#[derive(Debug)]
+pub struct ExternStructCannotHaveFields {
+    pub strukt: InFile<SyntaxNodePtr>,
+}
+
+impl Diagnostic for ExternStructCannotHaveFields {
+    fn message(&self) -> String {
+        "extern structs cannot have fields".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.strukt.clone()
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted if an `extern struct` specifies a memory kind. The
+/// memory of an extern struct is always managed by the host.
+#[derive(Debug)]
+pub struct ExternStructCannotHaveMemoryKind {
+    pub memory_kind: InFile<SyntaxNodePtr>,
+}
+
+impl Diagnostic for ExternStructCannotHaveMemoryKind {
+    fn message(&self) -> String {
+        "extern structs cannot specify a memory kind".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.memory_kind.clone()
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted if an `extern struct` specifies a representation
+/// with `#[repr(..)]`. The layout of an extern struct is only known to the
+/// host.
+#[derive(Debug)]
+pub struct ExternStructCannotHaveRepr {
+    pub attr: InFile<SyntaxNodePtr>,
+}
+
+impl Diagnostic for ExternStructCannotHaveRepr {
+    fn message(&self) -> String {
+        "extern structs cannot specify a representation".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.attr.clone()
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted for a hint of a `#[repr(..)]` attribute that is not
+/// recognized.
+#[derive(Debug)]
+pub struct UnknownReprHint {
+    pub attr: InFile<SyntaxNodePtr>,
+    pub hint: SmolStr,
+
+    /// The range of the hint
+    pub hint_range: TextRange,
+}
+
+impl Diagnostic for UnknownReprHint {
+    fn message(&self) -> String {
+        format!(
+            "unrecognized representation hint `{}`, expected `C` or `packed`",
+            self.hint
+        )
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.attr.clone()
+    }
+
+    fn highlight_range(&self) -> TextRange {
+        self.hint_range
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted if a field of a `#[repr(packed)]` struct contains a
+/// reference, such as a garbage-collected struct or an array. The fields of a
+/// packed struct are not aligned, but references must be.
+#[derive(Debug)]
+pub struct PackedStructCannotContainReferences {
+    pub file: FileId,
+    pub type_ref: AstPtr<ast::TypeRef>,
+}
+
+impl Diagnostic for PackedStructCannotContainReferences {
+    fn message(&self) -> String {
+        "fields of packed structs cannot contain references".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.type_ref.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted if the default value of a field is an expression
+/// other than a boolean or number literal. Default values are stored in the
+/// assembly, so they must be known at compile time.
+#[derive(Debug)]
+pub struct UnsupportedFieldDefault {
+    pub file: FileId,
+    pub default: SyntaxNodePtr,
+    pub field_name: Name,
+}
+
+impl Diagnostic for UnsupportedFieldDefault {
+    fn message(&self) -> String {
+        format!(
+            "unsupported default value for field `{}`, expected a boolean or number literal",
+            self.field_name
+        )
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.default.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted if Mun code tries to construct an instance of an
+/// `extern struct`. Instances of extern structs can only be created by the
+/// host.
+#[derive(Debug)]
+pub struct CannotConstructExternStruct {
+    pub file: FileId,
+    pub expr: SyntaxNodePtr,
+}
+
+impl Diagnostic for CannotConstructExternStruct {
+    fn message(&self) -> String {
+        "extern structs can only be constructed by the host".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct TypeAnnotationsNeeded {
+    pub file: FileId,
+    pub node: SyntaxNodePtr,
+}
+
+impl Diagnostic for TypeAnnotationsNeeded {
+    fn message(&self) -> String {
+        "type annotations needed".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.node.clone())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 /// An error that is emitted if a literal is too large to even parse
 #[derive(Debug)]
 pub struct IntLiteralTooLarge {
@@ -865,3 +1281,250 @@ impl Diagnostic for InvalidSelfTyImpl {
         self
     }
 }
+
+/// A warning that is emitted for a local variable that is never used.
+#[derive(Debug)]
+pub struct UnusedVariable {
+    pub file: FileId,
+    pub pat: SyntaxNodePtr,
+    pub name: Name,
+}
+
+impl Diagnostic for UnusedVariable {
+    fn message(&self) -> String {
+        format!("unused variable: `{}`", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.pat.clone())
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A warning that is emitted for a function parameter that is never used.
+#[derive(Debug)]
+pub struct UnusedParameter {
+    pub file: FileId,
+    pub pat: SyntaxNodePtr,
+    pub name: Name,
+}
+
+impl Diagnostic for UnusedParameter {
+    fn message(&self) -> String {
+        format!("unused parameter: `{}`", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.pat.clone())
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A warning that is emitted for a statement or expression that can never be
+/// executed because the code before it always returns, e.g. the statement
+/// after a `return`.
+#[derive(Debug)]
+pub struct UnreachableCode {
+    pub file: FileId,
+    pub node: SyntaxNodePtr,
+}
+
+impl Diagnostic for UnreachableCode {
+    fn message(&self) -> String {
+        "unreachable code".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.node.clone())
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A warning that is emitted for a private function that is never called.
+#[derive(Debug)]
+pub struct UnusedFunction {
+    pub file: FileId,
+    pub func: AstPtr<ast::FunctionDef>,
+    pub name: Name,
+
+    /// The range of the name of the function
+    pub name_range: TextRange,
+}
+
+impl Diagnostic for UnusedFunction {
+    fn message(&self) -> String {
+        format!("function `{}` is never used", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.func.clone().into())
+    }
+
+    fn highlight_range(&self) -> TextRange {
+        self.name_range
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// The attribute that marks a function to be invoked by a harness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestFunctionKind {
+    /// A function annotated with `#[test]`
+    Test,
+
+    /// A function annotated with `#[bench]`
+    Bench,
+}
+
+/// The reason why a function annotated with `#[test]` or `#[bench]` cannot be
+/// invoked by its harness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidTestFunctionReason {
+    /// The function has parameters
+    HasParameters,
+
+    /// The function returns a type that the harness does not support
+    InvalidReturnType,
+
+    /// The function is provided by the host
+    Extern,
+
+    /// The function is defined in an `impl`
+    Method,
+}
+
+/// An error that is emitted for a function annotated with `#[test]` or
+/// `#[bench]` that cannot be invoked by its harness.
+#[derive(Debug)]
+pub struct InvalidTestFunction {
+    pub func: InFile<SyntaxNodePtr>,
+    pub kind: TestFunctionKind,
+    pub reason: InvalidTestFunctionReason,
+
+    /// The range of the name of the function
+    pub name_range: TextRange,
+}
+
+impl Diagnostic for InvalidTestFunction {
+    fn message(&self) -> String {
+        let kind = match self.kind {
+            TestFunctionKind::Test => "test",
+            TestFunctionKind::Bench => "benchmark",
+        };
+        match (self.reason, self.kind) {
+            (InvalidTestFunctionReason::HasParameters, _) => {
+                format!("{kind} functions cannot have parameters")
+            }
+            (InvalidTestFunctionReason::InvalidReturnType, TestFunctionKind::Test) => {
+                format!("{kind} functions must return `()` or `bool`")
+            }
+            (InvalidTestFunctionReason::InvalidReturnType, TestFunctionKind::Bench) => {
+                format!("{kind} functions must return `()`")
+            }
+            (InvalidTestFunctionReason::Extern, _) => format!("{kind} functions cannot be extern"),
+            (InvalidTestFunctionReason::Method, _) => {
+                format!("{kind} functions cannot be defined in an `impl`")
+            }
+        }
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.func.clone()
+    }
+
+    fn highlight_range(&self) -> TextRange {
+        self.name_range
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// The reason why a function marked as `resumable` cannot be started by the
+/// host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidResumableFunctionReason {
+    /// The function returns a value
+    InvalidReturnType,
+
+    /// The function is provided by the host
+    Extern,
+
+    /// The function has a parameter that is passed by value, i.e. a value
+    /// struct or a `Result`
+    ValueParameter,
+
+    /// The function is annotated with `#[test]` or `#[bench]`
+    TestFunction,
+}
+
+/// An error that is emitted for a function marked as `resumable` that cannot
+/// be started by the host.
+#[derive(Debug)]
+pub struct InvalidResumableFunction {
+    pub func: InFile<SyntaxNodePtr>,
+    pub reason: InvalidResumableFunctionReason,
+
+    /// The range of the name of the function
+    pub name_range: TextRange,
+}
+
+impl Diagnostic for InvalidResumableFunction {
+    fn message(&self) -> String {
+        match self.reason {
+            InvalidResumableFunctionReason::InvalidReturnType => {
+                "resumable functions must return `()`".to_string()
+            }
+            InvalidResumableFunctionReason::Extern => {
+                "resumable functions cannot be extern".to_string()
+            }
+            InvalidResumableFunctionReason::ValueParameter => {
+                "parameters of resumable functions cannot be value structs or `Result`s".to_string()
+            }
+            InvalidResumableFunctionReason::TestFunction => {
+                "resumable functions cannot be tests or benchmarks".to_string()
+            }
+        }
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        self.func.clone()
+    }
+
+    fn highlight_range(&self) -> TextRange {
+        self.name_range
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
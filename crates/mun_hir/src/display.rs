@@ -129,6 +129,13 @@ fn write_type_ref(
             }
             write!(f, ")")
         }
+        TypeRef::Result(ok, err) => {
+            write!(f, "Result<")?;
+            write_type_ref(*ok, container, f)?;
+            write!(f, ", ")?;
+            write_type_ref(*err, container, f)?;
+            write!(f, ">")
+        }
         TypeRef::Error => write!(f, "{{error}}"),
     }
 }
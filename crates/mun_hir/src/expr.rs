@@ -6,7 +6,7 @@ use mun_hir_input::FileId;
 pub use mun_syntax::ast::PrefixOp as UnaryOp;
 use mun_syntax::{
     ast,
-    ast::{ArgListOwner, BinOp, LoopBodyOwner, NameOwner, TypeAscriptionOwner},
+    ast::{ArgListOwner, AstToken, BinOp, LoopBodyOwner, NameOwner, TypeAscriptionOwner},
     AstNode, AstPtr,
 };
 use rustc_hash::FxHashMap;
@@ -31,7 +31,21 @@ pub type PatId = Idx<Pat>;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ExprDiagnostic {
-    LiteralError { expr: ExprId, err: LiteralError },
+    LiteralError {
+        expr: ExprId,
+        err: LiteralError,
+    },
+    RangeOutsideFor {
+        expr: ExprId,
+    },
+    InvalidFormatString {
+        expr: ExprId,
+    },
+    FormatArgCountMismatch {
+        expr: ExprId,
+        expected: usize,
+        found: usize,
+    },
 }
 
 /// The body of an item (function, const etc.).
@@ -198,6 +212,10 @@ impl BodySourceMap {
         self.pat_map.get(&Either::Left(AstPtr::new(node))).cloned()
     }
 
+    pub(crate) fn node_self_param(&self, node: &ast::SelfParam) -> Option<PatId> {
+        self.pat_map.get(&Either::Right(AstPtr::new(node))).cloned()
+    }
+
     pub fn type_refs(&self) -> &TypeRefSourceMap {
         &self.type_refs
     }
@@ -304,6 +322,30 @@ pub enum Expr {
         base: ExprId,
         index: ExprId,
     },
+    Cast {
+        expr: ExprId,
+        type_ref: LocalTypeRefId,
+    },
+    /// The `?` operator, which returns early from the function if `expr` is an
+    /// `Err`.
+    Try {
+        expr: ExprId,
+    },
+    /// Constructs a `Result`, e.g. `Ok(expr)` or `Err(expr)`.
+    ResultCtor {
+        variant: ResultVariant,
+        expr: ExprId,
+    },
+    /// Suspends a resumable function until it is resumed. Evaluates to the
+    /// value with which the function is resumed.
+    Yield,
+    /// Writes a formatted message through the runtime, e.g. `print("{}", a)`.
+    /// Every `{}` in `format` is replaced by the next argument.
+    Log {
+        kind: LogKind,
+        format: String,
+        args: Vec<ExprId>,
+    },
     Block {
         statements: Vec<Statement>,
         tail: Option<ExprId>,
@@ -331,9 +373,29 @@ pub enum Expr {
         name: Name,
     },
     Array(Vec<ExprId>),
+    /// The number of elements in an array. This expression can't be written
+    /// in source code, it is introduced when desugaring a `for` loop over an
+    /// array.
+    ArrayLength {
+        array: ExprId,
+    },
     Literal(Literal),
 }
 
+/// The variants of the built-in `Result` type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ResultVariant {
+    Ok,
+    Err,
+}
+
+/// The built-in functions that write a formatted message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LogKind {
+    Print,
+    Log,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum BinaryOp {
     LogicOp(LogicOp),
@@ -377,7 +439,7 @@ pub enum ArithOp {
 impl Expr {
     pub fn walk_child_exprs(&self, mut f: impl FnMut(ExprId)) {
         match self {
-            Expr::Missing | Expr::Path(_) | Expr::Literal(_) => {}
+            Expr::Missing | Expr::Path(_) | Expr::Literal(_) | Expr::Yield => {}
             Expr::Block { statements, tail } => {
                 for stmt in statements {
                     match stmt {
@@ -403,7 +465,12 @@ impl Expr {
                 f(*lhs);
                 f(*rhs);
             }
-            Expr::Field { expr, .. } | Expr::UnaryOp { expr, .. } => {
+            Expr::Field { expr, .. }
+            | Expr::UnaryOp { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Try { expr }
+            | Expr::ResultCtor { expr, .. }
+            | Expr::ArrayLength { array: expr } => {
                 f(*expr);
             }
             Expr::If {
@@ -441,7 +508,7 @@ impl Expr {
                 f(*base);
                 f(*index);
             }
-            Expr::Array(exprs) => {
+            Expr::Array(exprs) | Expr::Log { args: exprs, .. } => {
                 for expr in exprs {
                     f(*expr);
                 }
@@ -517,6 +584,17 @@ impl<'a> ExprCollector<'a> {
         id
     }
 
+    /// Allocates an expression that was introduced by desugaring the syntax
+    /// node `ptr`. Diagnostics of the expression point to `ptr`, but `ptr`
+    /// itself maps to the expression it was desugared to.
+    fn alloc_expr_desugared(&mut self, expr: Expr, ptr: AstPtr<ast::Expr>) -> ExprId {
+        let id = self.exprs.alloc(expr);
+        self.source_map
+            .expr_map_back
+            .insert(id, InFile::new(self.current_file_id, Either::Left(ptr)));
+        id
+    }
+
     fn alloc_expr_field_shorthand(&mut self, expr: Expr, ptr: RecordPtr) -> ExprId {
         let ptr = Either::Right(ptr);
         let id = self.exprs.alloc(expr);
@@ -623,43 +701,21 @@ impl<'a> ExprCollector<'a> {
         match expr.kind() {
             ast::ExprKind::LoopExpr(expr) => self.collect_loop(expr),
             ast::ExprKind::WhileExpr(expr) => self.collect_while(expr),
+            ast::ExprKind::ForExpr(expr) => self.collect_for(expr),
             ast::ExprKind::ReturnExpr(r) => self.collect_return(r),
             ast::ExprKind::BreakExpr(r) => self.collect_break(r),
             ast::ExprKind::BlockExpr(b) => self.collect_block(b),
-            ast::ExprKind::Literal(e) => match e.kind() {
-                ast::LiteralKind::Bool(value) => {
-                    let lit = Literal::Bool(value);
-                    self.alloc_expr(Expr::Literal(lit), syntax_ptr)
-                }
-                ast::LiteralKind::IntNumber(lit) => {
-                    let (text, suffix) = lit.split_into_parts();
-                    let (lit, errors) = integer_lit(text, suffix);
-                    let expr_id = self.alloc_expr(Expr::Literal(lit), syntax_ptr);
-
-                    for err in errors {
-                        self.diagnostics
-                            .push(ExprDiagnostic::LiteralError { expr: expr_id, err });
-                    }
+            ast::ExprKind::Literal(e) => {
+                let (lit, errors) = lower_literal(&e);
+                let expr_id = self.alloc_expr(Expr::Literal(lit), syntax_ptr);
 
-                    expr_id
+                for err in errors {
+                    self.diagnostics
+                        .push(ExprDiagnostic::LiteralError { expr: expr_id, err });
                 }
-                ast::LiteralKind::FloatNumber(lit) => {
-                    let (text, suffix) = lit.split_into_parts();
-                    let (lit, errors) = float_lit(text, suffix);
-                    let expr_id = self.alloc_expr(Expr::Literal(lit), syntax_ptr);
-
-                    for err in errors {
-                        self.diagnostics
-                            .push(ExprDiagnostic::LiteralError { expr: expr_id, err });
-                    }
 
-                    expr_id
-                }
-                ast::LiteralKind::String(_lit) => {
-                    let lit = Literal::String(String::default());
-                    self.alloc_expr(Expr::Literal(lit), syntax_ptr)
-                }
-            },
+                expr_id
+            }
             ast::ExprKind::PrefixExpr(e) => {
                 let expr = self.collect_expr_opt(e.expr());
                 if let Some(op) = e.op_kind() {
@@ -869,6 +925,14 @@ impl<'a> ExprCollector<'a> {
                 inner
             }
             ast::ExprKind::CallExpr(e) => {
+                if let Some((variant, arg)) = result_ctor(&e) {
+                    let expr = self.collect_expr(arg);
+                    return self.alloc_expr(Expr::ResultCtor { variant, expr }, syntax_ptr);
+                }
+                if let Some((kind, format, args)) = log_call(&e) {
+                    return self.collect_log(kind, &format, args, syntax_ptr);
+                }
+
                 let callee = self.collect_expr_opt(e.expr());
                 let args = if let Some(arg_list) = e.arg_list() {
                     arg_list.args().map(|e| self.collect_expr(e)).collect()
@@ -886,6 +950,28 @@ impl<'a> ExprCollector<'a> {
                 let index = self.collect_expr_opt(e.index());
                 self.alloc_expr(Expr::Index { base, index }, syntax_ptr)
             }
+            ast::ExprKind::CastExpr(e) => {
+                let expr = self.collect_expr_opt(e.expr());
+                let type_ref = self
+                    .type_ref_builder
+                    .alloc_from_node_opt(e.type_ref().as_ref());
+                self.alloc_expr(Expr::Cast { expr, type_ref }, syntax_ptr)
+            }
+            ast::ExprKind::TryExpr(e) => {
+                let expr = self.collect_expr_opt(e.expr());
+                self.alloc_expr(Expr::Try { expr }, syntax_ptr)
+            }
+            ast::ExprKind::YieldExpr(_) => self.alloc_expr(Expr::Yield, syntax_ptr),
+            ast::ExprKind::RangeExpr(e) => {
+                // Ranges are only supported as the iterable of a `for` loop, which is desugared
+                // before reaching this point.
+                self.collect_expr_opt(e.start());
+                self.collect_expr_opt(e.end());
+                let expr = self.alloc_expr(Expr::Missing, syntax_ptr);
+                self.diagnostics
+                    .push(ExprDiagnostic::RangeOutsideFor { expr });
+                expr
+            }
         }
     }
 
@@ -941,6 +1027,315 @@ impl<'a> ExprCollector<'a> {
         self.alloc_expr(Expr::While { condition, body }, syntax_node_ptr)
     }
 
+    /// Lowers a call to one of the built-in functions that write a formatted
+    /// message and checks that its format string has a placeholder for every
+    /// argument.
+    fn collect_log(
+        &mut self,
+        kind: LogKind,
+        format: &ast::String,
+        args: Vec<ast::Expr>,
+        syntax_ptr: AstPtr<ast::Expr>,
+    ) -> ExprId {
+        let format = string_lit(format.text());
+        let args: Vec<_> = args.into_iter().map(|arg| self.collect_expr(arg)).collect();
+        let found = args.len();
+        let placeholders = format_placeholder_count(&format);
+        let expr = self.alloc_expr(Expr::Log { kind, format, args }, syntax_ptr);
+        match placeholders {
+            None => self
+                .diagnostics
+                .push(ExprDiagnostic::InvalidFormatString { expr }),
+            Some(expected) if expected != found => {
+                self.diagnostics
+                    .push(ExprDiagnostic::FormatArgCountMismatch {
+                        expr,
+                        expected,
+                        found,
+                    });
+            }
+            Some(_) => (),
+        }
+        expr
+    }
+
+    fn collect_for(&mut self, expr: ast::ForExpr) -> ExprId {
+        let syntax_node_ptr = AstPtr::new(&expr.clone().into());
+        let iterable = expr.iterable();
+        let range = iterable
+            .as_ref()
+            .and_then(|iterable| match iterable.kind() {
+                ast::ExprKind::RangeExpr(range) => Some(range),
+                _ => None,
+            });
+        let statements = if let Some(range) = range {
+            self.desugar_range_loop(&expr, range)
+        } else {
+            self.desugar_array_loop(&expr, iterable)
+        };
+        self.alloc_expr(
+            Expr::Block {
+                statements,
+                tail: None,
+            },
+            syntax_node_ptr,
+        )
+    }
+
+    /// Desugars `for pat in start..end { body }` to:
+    ///
+    /// ```mun
+    /// let mut index = start;
+    /// let end = end;
+    /// while index < end {
+    ///     let pat = index;
+    ///     index += 1;
+    ///     body
+    /// }
+    /// ```
+    ///
+    /// An inclusive range, e.g. `start..=end`, never increments `index` past
+    /// `end`, so it doesn't overflow if `end` is the maximum value of its type:
+    ///
+    /// ```mun
+    /// let mut index = start;
+    /// let end = end;
+    /// let mut more = index <= end;
+    /// while more {
+    ///     let pat = index;
+    ///     more = index < end;
+    ///     if more { index += 1; }
+    ///     body
+    /// }
+    /// ```
+    fn desugar_range_loop(&mut self, expr: &ast::ForExpr, range: ast::RangeExpr) -> Vec<Statement> {
+        let ptr = AstPtr::new(&range.clone().into());
+        let index = Name::new_desugared("for index");
+        let end = Name::new_desugared("for end");
+        let more = Name::new_desugared("for more");
+
+        let start_expr = self.collect_expr_opt(range.start());
+        let end_expr = self.collect_expr_opt(range.end());
+        let mut statements = vec![
+            self.desugared_let(&index, start_expr),
+            self.desugared_let(&end, end_expr),
+        ];
+
+        let mut body_statements = vec![];
+        let condition = if range.is_inclusive() {
+            let index_le_end = self.desugared_cmp(&index, &end, false, ptr.clone());
+            statements.push(self.desugared_let(&more, index_le_end));
+
+            let pat = self.collect_pat_opt(expr.pat());
+            let index_expr = self.desugared_path(&index, ptr.clone());
+            body_statements.push(Statement::Let {
+                pat,
+                type_ref: None,
+                initializer: Some(index_expr),
+            });
+            let index_lt_end = self.desugared_cmp(&index, &end, true, ptr.clone());
+            let assign_more = self.desugared_assign(&more, None, index_lt_end, ptr.clone());
+            body_statements.push(Statement::Expr(assign_more));
+            let increment = self.desugared_increment(&index, ptr.clone());
+            let increment = self.alloc_expr_desugared(
+                Expr::Block {
+                    statements: vec![Statement::Expr(increment)],
+                    tail: None,
+                },
+                ptr.clone(),
+            );
+            let more_expr = self.desugared_path(&more, ptr.clone());
+            let increment_if_more = self.alloc_expr_desugared(
+                Expr::If {
+                    condition: more_expr,
+                    then_branch: increment,
+                    else_branch: None,
+                },
+                ptr.clone(),
+            );
+            body_statements.push(Statement::Expr(increment_if_more));
+            self.desugared_path(&more, ptr.clone())
+        } else {
+            let pat = self.collect_pat_opt(expr.pat());
+            let index_expr = self.desugared_path(&index, ptr.clone());
+            body_statements.push(Statement::Let {
+                pat,
+                type_ref: None,
+                initializer: Some(index_expr),
+            });
+            let increment = self.desugared_increment(&index, ptr.clone());
+            body_statements.push(Statement::Expr(increment));
+            self.desugared_cmp(&index, &end, true, ptr.clone())
+        };
+
+        let body = self.desugared_loop_body(expr, body_statements);
+        let while_expr = self.alloc_expr_desugared(Expr::While { condition, body }, ptr);
+        statements.push(Statement::Expr(while_expr));
+        statements
+    }
+
+    /// Desugars `for pat in array { body }` to:
+    ///
+    /// ```mun
+    /// let array = array;
+    /// let length = <length of array>;
+    /// let mut index = 0;
+    /// while index < length {
+    ///     let pat = array[index];
+    ///     index += 1;
+    ///     body
+    /// }
+    /// ```
+    fn desugar_array_loop(
+        &mut self,
+        expr: &ast::ForExpr,
+        iterable: Option<ast::Expr>,
+    ) -> Vec<Statement> {
+        let ptr = iterable
+            .as_ref()
+            .map_or_else(|| AstPtr::new(&expr.clone().into()), AstPtr::new);
+        let array = Name::new_desugared("for array");
+        let length = Name::new_desugared("for length");
+        let index = Name::new_desugared("for index");
+
+        let array_expr = self.collect_expr_opt(iterable);
+        let array_path = self.desugared_path(&array, ptr.clone());
+        let length_expr =
+            self.alloc_expr_desugared(Expr::ArrayLength { array: array_path }, ptr.clone());
+        let zero = self.desugared_int(0, ptr.clone());
+        let mut statements = vec![
+            self.desugared_let(&array, array_expr),
+            self.desugared_let(&length, length_expr),
+            self.desugared_let(&index, zero),
+        ];
+
+        let pat = self.collect_pat_opt(expr.pat());
+        let base = self.desugared_path(&array, ptr.clone());
+        let index_expr = self.desugared_path(&index, ptr.clone());
+        let element = self.alloc_expr_desugared(
+            Expr::Index {
+                base,
+                index: index_expr,
+            },
+            ptr.clone(),
+        );
+        let increment = self.desugared_increment(&index, ptr.clone());
+        let body_statements = vec![
+            Statement::Let {
+                pat,
+                type_ref: None,
+                initializer: Some(element),
+            },
+            Statement::Expr(increment),
+        ];
+
+        let condition = self.desugared_cmp(&index, &length, true, ptr.clone());
+        let body = self.desugared_loop_body(expr, body_statements);
+        let while_expr = self.alloc_expr_desugared(Expr::While { condition, body }, ptr);
+        statements.push(Statement::Expr(while_expr));
+        statements
+    }
+
+    /// Collects the body of a `for` loop, and inserts `statements` at its
+    /// start.
+    fn desugared_loop_body(
+        &mut self,
+        expr: &ast::ForExpr,
+        mut statements: Vec<Statement>,
+    ) -> ExprId {
+        let body = self.collect_block_opt(expr.loop_body());
+        if let Expr::Block {
+            statements: body_statements,
+            ..
+        } = &mut self.exprs[body]
+        {
+            statements.append(body_statements);
+            *body_statements = statements;
+            body
+        } else {
+            self.alloc_expr_desugared(
+                Expr::Block {
+                    statements,
+                    tail: None,
+                },
+                AstPtr::new(&expr.clone().into()),
+            )
+        }
+    }
+
+    /// Returns the statement `let name = initializer;`. The binding is hidden
+    /// from source code.
+    fn desugared_let(&mut self, name: &Name, initializer: ExprId) -> Statement {
+        let pat = self.pats.alloc(Pat::Bind { name: name.clone() });
+        Statement::Let {
+            pat,
+            type_ref: None,
+            initializer: Some(initializer),
+        }
+    }
+
+    fn desugared_path(&mut self, name: &Name, ptr: AstPtr<ast::Expr>) -> ExprId {
+        self.alloc_expr_desugared(Expr::Path(Path::from(name.clone())), ptr)
+    }
+
+    fn desugared_int(&mut self, value: u128, ptr: AstPtr<ast::Expr>) -> ExprId {
+        let literal = Literal::Int(LiteralInt {
+            kind: LiteralIntKind::Unsuffixed,
+            value,
+        });
+        self.alloc_expr_desugared(Expr::Literal(literal), ptr)
+    }
+
+    /// Returns the expression `lhs < rhs`, or `lhs <= rhs` if not `strict`.
+    fn desugared_cmp(
+        &mut self,
+        lhs: &Name,
+        rhs: &Name,
+        strict: bool,
+        ptr: AstPtr<ast::Expr>,
+    ) -> ExprId {
+        let lhs = self.desugared_path(lhs, ptr.clone());
+        let rhs = self.desugared_path(rhs, ptr.clone());
+        let op = BinaryOp::CmpOp(CmpOp::Ord {
+            ordering: Ordering::Less,
+            strict,
+        });
+        self.alloc_expr_desugared(
+            Expr::BinaryOp {
+                lhs,
+                rhs,
+                op: Some(op),
+            },
+            ptr,
+        )
+    }
+
+    /// Returns the expression `name = rhs`, or e.g. `name += rhs` for an
+    /// arithmetic `op`.
+    fn desugared_assign(
+        &mut self,
+        name: &Name,
+        op: Option<ArithOp>,
+        rhs: ExprId,
+        ptr: AstPtr<ast::Expr>,
+    ) -> ExprId {
+        let lhs = self.desugared_path(name, ptr.clone());
+        self.alloc_expr_desugared(
+            Expr::BinaryOp {
+                lhs,
+                rhs,
+                op: Some(BinaryOp::Assignment { op }),
+            },
+            ptr,
+        )
+    }
+
+    /// Returns the expression `name += 1`.
+    fn desugared_increment(&mut self, name: &Name, ptr: AstPtr<ast::Expr>) -> ExprId {
+        let one = self.desugared_int(1, ptr.clone());
+        self.desugared_assign(name, Some(ArithOp::Add), one, ptr)
+    }
+
     fn finish(mut self) -> (Body, BodySourceMap) {
         let (type_refs, type_ref_source_map) = self.type_ref_builder.finish();
         let body = Body {
@@ -961,6 +1356,99 @@ impl<'a> ExprCollector<'a> {
     }
 }
 
+/// If `call` constructs a `Result`, e.g. `Ok(a)`, returns the variant and the
+/// argument of the call.
+fn result_ctor(call: &ast::CallExpr) -> Option<(ResultVariant, ast::Expr)> {
+    let ast::ExprKind::PathExpr(callee) = call.expr()?.kind() else {
+        return None;
+    };
+    let path = Path::from_ast(callee.path()?)?;
+    let variant = match path.as_ident()? {
+        name if *name == name![Ok] => ResultVariant::Ok,
+        name if *name == name![Err] => ResultVariant::Err,
+        _ => return None,
+    };
+
+    let mut args = call.arg_list()?.args();
+    let arg = args.next()?;
+    args.next().is_none().then_some((variant, arg))
+}
+
+/// If `call` calls one of the built-in functions that write a formatted
+/// message, e.g. `print("{}", a)`, returns the kind of the function, the format
+/// string and the arguments that follow it. A call is only recognized if its
+/// first argument is a string literal.
+fn log_call(call: &ast::CallExpr) -> Option<(LogKind, ast::String, Vec<ast::Expr>)> {
+    let ast::ExprKind::PathExpr(callee) = call.expr()?.kind() else {
+        return None;
+    };
+    let path = Path::from_ast(callee.path()?)?;
+    let kind = match path.as_ident()? {
+        name if *name == name![print] => LogKind::Print,
+        name if *name == name![log] => LogKind::Log,
+        _ => return None,
+    };
+
+    let mut args = call.arg_list()?.args();
+    let ast::ExprKind::Literal(literal) = args.next()?.kind() else {
+        return None;
+    };
+    let ast::LiteralKind::String(format) = literal.kind() else {
+        return None;
+    };
+    Some((kind, format, args.collect()))
+}
+
+/// Returns the value of a string literal, whose `text` includes the quotes.
+/// Escape sequences that are not recognized are kept as is.
+fn string_lit(text: &str) -> String {
+    let mut chars = text.chars();
+    let quote = chars.next();
+    let text = chars.as_str();
+    let text = quote
+        .and_then(|quote| text.strip_suffix(quote))
+        .unwrap_or(text);
+
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some('0') => value.push('\0'),
+            Some(c @ ('\\' | '"' | '\'')) => value.push(c),
+            Some(c) => {
+                value.push('\\');
+                value.push(c);
+            }
+            None => value.push('\\'),
+        }
+    }
+    value
+}
+
+/// Returns the number of `{}` placeholders in a format string, or `None` if
+/// the format string contains a brace that is not part of a placeholder. Braces
+/// are escaped by doubling them, e.g. `{{`.
+fn format_placeholder_count(format: &str) -> Option<usize> {
+    let mut count = 0;
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.next_if_eq(&'}').is_some() => count += 1,
+            '{' | '}' if chars.next_if_eq(&c).is_some() => (),
+            '{' | '}' => return None,
+            _ => (),
+        }
+    }
+    Some(count)
+}
+
 /// Removes any underscores from a string if present
 fn strip_underscores(s: &str) -> Cow<'_, str> {
     if s.contains('_') {
@@ -972,6 +1460,23 @@ fn strip_underscores(s: &str) -> Cow<'_, str> {
     }
 }
 
+/// Lowers a literal from the syntax tree, returning any errors that occurred
+/// while parsing it. The contents of string literals are not retained.
+pub(crate) fn lower_literal(literal: &ast::Literal) -> (Literal, Vec<LiteralError>) {
+    match literal.kind() {
+        ast::LiteralKind::Bool(value) => (Literal::Bool(value), Vec::new()),
+        ast::LiteralKind::IntNumber(lit) => {
+            let (text, suffix) = lit.split_into_parts();
+            integer_lit(text, suffix)
+        }
+        ast::LiteralKind::FloatNumber(lit) => {
+            let (text, suffix) = lit.split_into_parts();
+            float_lit(text, suffix)
+        }
+        ast::LiteralKind::String(_lit) => (Literal::String(String::default()), Vec::new()),
+    }
+}
+
 /// Parses the given string into a float literal
 fn float_lit(str: &str, suffix: Option<&str>) -> (Literal, Vec<LiteralError>) {
     let str = strip_underscores(str);
@@ -997,7 +1502,12 @@ fn filtered_float_lit(str: &str, suffix: Option<&str>, base: u32) -> (Literal, V
     };
 
     let value = if base == 10 {
-        f64::from_str(str).expect("could not parse floating point number, this is definitely a bug")
+        // The lexer also produces float literals for malformed input, e.g. an exponent
+        // without digits (`1e`)
+        f64::from_str(str).unwrap_or_else(|_| {
+            errors.push(LiteralError::LexerError);
+            0.0
+        })
     } else {
         0.0
     };
@@ -1063,8 +1573,8 @@ fn integer_lit(str: &str, suffix: Option<&str>) -> (Literal, Vec<LiteralError>)
 mod test {
     use crate::{
         expr::{
-            float_lit, integer_lit, LiteralError, LiteralFloat, LiteralFloatKind, LiteralInt,
-            LiteralIntKind,
+            float_lit, format_placeholder_count, integer_lit, string_lit, LiteralError,
+            LiteralFloat, LiteralFloatKind, LiteralInt, LiteralIntKind,
         },
         primitive_type::{PrimitiveFloat, PrimitiveInt},
         Literal,
@@ -1312,6 +1822,17 @@ mod test {
 
     #[test]
     fn test_float_literals() {
+        assert_eq!(
+            float_lit("1e", None),
+            (
+                Literal::Float(LiteralFloat {
+                    kind: LiteralFloatKind::Unsuffixed,
+                    value: 0.0
+                }),
+                vec![LiteralError::LexerError]
+            )
+        );
+
         assert_eq!(
             float_lit("1234.1234", None),
             (
@@ -1378,21 +1899,72 @@ mod test {
             )
         );
     }
+
+    #[test]
+    fn test_string_literals() {
+        assert_eq!(string_lit(r#""foo""#), "foo");
+        assert_eq!(string_lit(r#"'foo'"#), "foo");
+        assert_eq!(string_lit(r#""a\tb\n\"c\"\\""#), "a\tb\n\"c\"\\");
+        assert_eq!(string_lit(r#""\q""#), "\\q");
+    }
+
+    #[test]
+    fn test_format_placeholders() {
+        assert_eq!(format_placeholder_count(""), Some(0));
+        assert_eq!(format_placeholder_count("{} + {} = {}"), Some(3));
+        assert_eq!(format_placeholder_count("{{}} {}"), Some(1));
+        assert_eq!(format_placeholder_count("{{{}}}"), Some(1));
+        assert_eq!(format_placeholder_count("{"), None);
+        assert_eq!(format_placeholder_count("{a}"), None);
+        assert_eq!(format_placeholder_count("}"), None);
+    }
 }
 
 mod diagnostics {
-    use mun_syntax::SmolStr;
+    use mun_syntax::{ast, AstPtr, SmolStr};
 
     use super::{ExprDiagnostic, LiteralError};
     use crate::{
         code_model::DefWithBody,
         diagnostics::{
-            DiagnosticSink, IntLiteralTooLarge, InvalidFloatingPointLiteral, InvalidLiteral,
-            InvalidLiteralSuffix,
+            DiagnosticSink, FormatArgCountMismatch, IntLiteralTooLarge,
+            InvalidFloatingPointLiteral, InvalidFormatString, InvalidLiteral, InvalidLiteralSuffix,
+            RangeOutsideFor,
         },
+        in_file::InFile,
         HirDatabase,
     };
 
+    impl LiteralError {
+        /// Reports the error for the specified `literal`.
+        pub(crate) fn add_to(
+            &self,
+            literal: InFile<AstPtr<ast::Literal>>,
+            sink: &mut DiagnosticSink<'_>,
+        ) {
+            match self {
+                LiteralError::IntTooLarge => sink.push(IntLiteralTooLarge { literal }),
+                LiteralError::LexerError => sink.push(InvalidLiteral { literal }),
+                LiteralError::InvalidIntSuffix(suffix) => sink.push(InvalidLiteralSuffix {
+                    literal,
+                    suffix: SmolStr::new(suffix),
+                }),
+                LiteralError::InvalidFloatSuffix(suffix) => {
+                    sink.push(InvalidLiteralSuffix {
+                        literal,
+                        suffix: SmolStr::new(suffix),
+                    });
+                }
+                LiteralError::NonDecimalFloat(base) => {
+                    sink.push(InvalidFloatingPointLiteral {
+                        literal,
+                        base: *base,
+                    });
+                }
+            }
+        }
+    }
+
     impl ExprDiagnostic {
         pub(crate) fn add_to(
             &self,
@@ -1414,26 +1986,46 @@ mod diagnostics {
                                 .cast()
                                 .expect("could not cast expression to literal")
                         });
-                    match err {
-                        LiteralError::IntTooLarge => sink.push(IntLiteralTooLarge { literal }),
-                        LiteralError::LexerError => sink.push(InvalidLiteral { literal }),
-                        LiteralError::InvalidIntSuffix(suffix) => sink.push(InvalidLiteralSuffix {
-                            literal,
-                            suffix: SmolStr::new(suffix),
-                        }),
-                        LiteralError::InvalidFloatSuffix(suffix) => {
-                            sink.push(InvalidLiteralSuffix {
-                                literal,
-                                suffix: SmolStr::new(suffix),
-                            });
-                        }
-                        LiteralError::NonDecimalFloat(base) => {
-                            sink.push(InvalidFloatingPointLiteral {
-                                literal,
-                                base: *base,
-                            });
-                        }
-                    }
+                    err.add_to(literal, sink);
+                }
+                ExprDiagnostic::RangeOutsideFor { expr } => {
+                    let range_expr = source_map
+                        .expr_syntax(*expr)
+                        .expect("could not retrieve expr from source map");
+                    sink.push(RangeOutsideFor {
+                        file: range_expr.file_id,
+                        range_expr: range_expr
+                            .value
+                            .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr()),
+                    });
+                }
+                ExprDiagnostic::InvalidFormatString { expr } => {
+                    let call = source_map
+                        .expr_syntax(*expr)
+                        .expect("could not retrieve expr from source map");
+                    sink.push(InvalidFormatString {
+                        file: call.file_id,
+                        expr: call
+                            .value
+                            .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr()),
+                    });
+                }
+                ExprDiagnostic::FormatArgCountMismatch {
+                    expr,
+                    expected,
+                    found,
+                } => {
+                    let call = source_map
+                        .expr_syntax(*expr)
+                        .expect("could not retrieve expr from source map");
+                    sink.push(FormatArgCountMismatch {
+                        file: call.file_id,
+                        expr: call
+                            .value
+                            .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr()),
+                        expected: *expected,
+                        found: *found,
+                    });
                 }
             }
         }
@@ -1,12 +1,13 @@
 use std::sync::Arc;
 
-use mun_syntax::{AstNode, SyntaxNodePtr};
+use mun_syntax::{ast::NameOwner, AstNode, SyntaxNodePtr};
 
 use crate::{
     code_model::src::HasSource,
     diagnostics::{
         CyclicType, DiagnosticSink, ExportedPrivate, ExternCannotHaveBody, ExternNonPrimitiveParam,
-        FreeTypeAliasWithoutTypeRef, PrivateTypeAlias,
+        FreeTypeAliasWithoutTypeRef, InvalidResumableFunction, InvalidResumableFunctionReason,
+        InvalidTestFunction, InvalidTestFunctionReason, PrivateTypeAlias, TestFunctionKind,
     },
     expr::BodySourceMap,
     in_file::InFile,
@@ -46,6 +47,8 @@ impl<'a> ExprValidator<'a> {
         self.validate_uninitialized_access(sink);
         self.validate_extern(sink);
         self.validate_privacy(sink);
+        self.validate_test(sink);
+        self.validate_resumable(sink);
     }
 
     pub fn validate_privacy(&self, sink: &mut DiagnosticSink<'_>) {
@@ -99,10 +102,17 @@ impl<'a> ExprValidator<'a> {
             }),
         }
 
+        // Only primitives and opaque extern structs can cross the boundary with the
+        // host
+        let is_valid_extern_ty = |ty: &Ty| match ty.as_struct() {
+            Some(s) => s.is_extern(self.db),
+            None => true,
+        };
+
         if let Some(sig) = self.func.ty(self.db).callable_sig(self.db) {
             let fn_data = self.func.data(self.db.upcast());
             for (arg_ty, ty_ref) in sig.params().iter().zip(fn_data.params()) {
-                if arg_ty.as_struct().is_some() {
+                if !is_valid_extern_ty(arg_ty) {
                     let arg_ptr = fn_data
                         .type_ref_source_map()
                         .type_ref_syntax(*ty_ref)
@@ -115,7 +125,7 @@ impl<'a> ExprValidator<'a> {
             }
 
             let return_ty = sig.ret();
-            if return_ty.as_struct().is_some() {
+            if !is_valid_extern_ty(return_ty) {
                 let arg_ptr = fn_data
                     .type_ref_source_map()
                     .type_ref_syntax(*fn_data.ret_type())
@@ -127,6 +137,81 @@ impl<'a> ExprValidator<'a> {
             }
         }
     }
+
+    /// Validates that a function annotated with `#[test]` or `#[bench]` can be
+    /// invoked by its harness.
+    pub fn validate_test(&self, sink: &mut DiagnosticSink<'_>) {
+        let kind = if self.func.is_test(self.db) {
+            TestFunctionKind::Test
+        } else if self.func.is_bench(self.db) {
+            TestFunctionKind::Bench
+        } else {
+            return;
+        };
+
+        let reason = if self.func.is_extern(self.db) {
+            InvalidTestFunctionReason::Extern
+        } else if self.func.parent_impl(self.db).is_some() {
+            InvalidTestFunctionReason::Method
+        } else if !self.func.data(self.db.upcast()).params().is_empty() {
+            InvalidTestFunctionReason::HasParameters
+        } else {
+            let ret_type = self.func.ret_type(self.db);
+            let returns_bool = matches!(ret_type.interned(), TyKind::Bool);
+            if ret_type.is_empty() || (kind == TestFunctionKind::Test && returns_bool) {
+                return;
+            }
+            InvalidTestFunctionReason::InvalidReturnType
+        };
+
+        let src = self.func.source(self.db.upcast());
+        let name_range = src.value.name().map_or_else(
+            || src.value.syntax().text_range(),
+            |name| name.syntax().text_range(),
+        );
+        sink.push(InvalidTestFunction {
+            func: src.map(|f| SyntaxNodePtr::new(f.syntax())),
+            kind,
+            reason,
+            name_range,
+        });
+    }
+
+    /// Validates that a function marked as `resumable` can be started by the
+    /// host.
+    pub fn validate_resumable(&self, sink: &mut DiagnosticSink<'_>) {
+        if !self.func.is_resumable(self.db) {
+            return;
+        }
+
+        let reason = if self.func.is_extern(self.db) {
+            InvalidResumableFunctionReason::Extern
+        } else if self.func.is_test(self.db) || self.func.is_bench(self.db) {
+            InvalidResumableFunctionReason::TestFunction
+        } else if !self.func.ret_type(self.db).is_empty() {
+            InvalidResumableFunctionReason::InvalidReturnType
+        } else if !self
+            .func
+            .ty(self.db)
+            .callable_sig(self.db)
+            .map_or(true, |sig| sig.marshallable(self.db))
+        {
+            InvalidResumableFunctionReason::ValueParameter
+        } else {
+            return;
+        };
+
+        let src = self.func.source(self.db.upcast());
+        let name_range = src.value.name().map_or_else(
+            || src.value.syntax().text_range(),
+            |name| name.syntax().text_range(),
+        );
+        sink.push(InvalidResumableFunction {
+            func: src.map(|f| SyntaxNodePtr::new(f.syntax())),
+            reason,
+            name_range,
+        });
+    }
 }
 
 pub struct TypeAliasValidator<'a> {
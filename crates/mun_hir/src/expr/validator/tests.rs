@@ -181,3 +181,102 @@ fn test_type_alias_with_private_type_alias() {
     "#,
     ), @"17..36: type alias `Foo` is private");
 }
+
+#[test]
+fn test_invalid_test_functions() {
+    insta::assert_snapshot!(diagnostics(
+        r#"
+    #[test]
+    fn valid() {}
+
+    #[test]
+    fn valid_bool() -> bool { true }
+
+    #[test]
+    fn params(a: i32) {}
+
+    #[test]
+    fn returns_int() -> i32 { 0 }
+
+    #[test]
+    extern fn host();
+
+    struct Foo;
+    impl Foo {
+        #[test]
+        fn method() {}
+    }
+    "#,
+    ), @r###"
+    76..82: test functions cannot have parameters
+    106..117: test functions must return `()` or `bool`
+    152..156: test functions cannot be extern
+    203..209: test functions cannot be defined in an `impl`
+    "###);
+}
+
+#[test]
+fn test_invalid_bench_functions() {
+    insta::assert_snapshot!(diagnostics(
+        r#"
+    #[bench]
+    fn valid() {}
+
+    #[bench]
+    fn params(a: i32) {}
+
+    #[bench]
+    fn returns_bool() -> bool { true }
+
+    #[bench]
+    extern fn host();
+    "#,
+    ), @r###"
+    36..42: benchmark functions cannot have parameters
+    67..79: benchmark functions must return `()`
+    119..123: benchmark functions cannot be extern
+    "###);
+}
+
+#[test]
+fn test_resumable_functions() {
+    insta::assert_snapshot!(diagnostics(
+        r#"
+    struct(value) Vec2 { x: f32, y: f32 }
+    pub struct(gc) Door { open: bool }
+
+    resumable fn wait(seconds: f32) {
+        let elapsed = 0.0;
+        while elapsed < seconds {
+            elapsed += yield;
+        }
+    }
+
+    pub resumable fn open(door: Door) {
+        wait(1.0);
+        door.open = true;
+    }
+
+    resumable fn returns_int() -> i32 { 0 }
+
+    resumable fn by_value(position: Vec2) {}
+
+    extern resumable fn host();
+
+    fn not_resumable() {
+        let dt = yield;
+        wait(2.0);
+    }
+
+    #[test]
+    resumable fn waits() {}
+    "#,
+    ), @r###"
+    285..296: resumable functions must return `()`
+    326..334: parameters of resumable functions cannot be value structs or `Result`s
+    375..379: resumable functions cannot be extern
+    418..423: `yield` can only be used in a resumable function
+    429..438: a resumable function can only be called from another resumable function
+    464..469: resumable functions cannot be tests or benchmarks
+    "###);
+}
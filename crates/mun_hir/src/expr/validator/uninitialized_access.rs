@@ -104,7 +104,12 @@ impl<'d> ExprValidator<'d> {
                     };
                 }
             }
-            Expr::UnaryOp { expr, .. } | Expr::Field { expr, .. } => {
+            Expr::UnaryOp { expr, .. }
+            | Expr::Field { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Try { expr }
+            | Expr::ResultCtor { expr, .. }
+            | Expr::ArrayLength { array: expr } => {
                 self.validate_expr_access(sink, initialized_patterns, *expr, ExprKind::Normal);
             }
             Expr::BinaryOp { lhs, rhs, op } => {
@@ -183,12 +188,12 @@ impl<'d> ExprValidator<'d> {
                 self.validate_expr_access(sink, initialized_patterns, *base, ExprKind::Normal);
                 self.validate_expr_access(sink, initialized_patterns, *index, ExprKind::Normal);
             }
-            Expr::Array(exprs) => {
+            Expr::Array(exprs) | Expr::Log { args: exprs, .. } => {
                 for expr in exprs {
                     self.validate_expr_access(sink, initialized_patterns, *expr, ExprKind::Normal);
                 }
             }
-            Expr::Literal(_) | Expr::Missing => {}
+            Expr::Literal(_) | Expr::Missing | Expr::Yield => {}
         }
     }
 
@@ -316,6 +316,9 @@ bitflags::bitflags! {
         const HAS_SELF_PARAM = 1 << 0;
         const HAS_BODY = 1 << 1;
         const IS_EXTERN = 1 << 2;
+        const IS_TEST = 1 << 3;
+        const IS_BENCH = 1 << 4;
+        const IS_RESUMABLE = 1 << 5;
     }
 }
 
@@ -334,6 +337,21 @@ impl FunctionFlags {
     pub fn is_extern(self) -> bool {
         self.contains(Self::IS_EXTERN)
     }
+
+    /// Whether the function is annotated with `#[test]`.
+    pub fn is_test(self) -> bool {
+        self.contains(Self::IS_TEST)
+    }
+
+    /// Whether the function is annotated with `#[bench]`.
+    pub fn is_bench(self) -> bool {
+        self.contains(Self::IS_BENCH)
+    }
+
+    /// Whether the function is marked as `resumable`.
+    pub fn is_resumable(self) -> bool {
+        self.contains(Self::IS_RESUMABLE)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -5,7 +5,7 @@ use std::{collections::HashMap, convert::TryInto, marker::PhantomData, sync::Arc
 use la_arena::{Idx, RawIdx};
 use mun_hir_input::FileId;
 use mun_syntax::ast::{
-    self, ExternOwner, ModuleItemOwner, NameOwner, StructKind, TypeAscriptionOwner,
+    self, AttrsOwner, ExternOwner, ModuleItemOwner, NameOwner, StructKind, TypeAscriptionOwner,
 };
 use smallvec::SmallVec;
 
@@ -66,11 +66,11 @@ impl Context {
     pub(super) fn lower_module_items(mut self, item_owner: &impl ModuleItemOwner) -> ItemTree {
         let top_level = item_owner
             .items()
-            .filter_map(|item| self.lower_mod_item(&item))
-            .flat_map(|items| items.0)
+            .flat_map(|item| self.lower_mod_item(&item).0)
             .collect::<Vec<_>>();
 
-        // Check duplicates
+        // Check duplicates. Items without a name, which are only defined to analyze
+        // code that contains syntax errors, are never duplicates.
         let mut set = HashMap::<Name, &ModItem>::new();
         for item in top_level.iter() {
             let name = match item {
@@ -90,7 +90,7 @@ impl Context {
                 }
                 ModItem::Impl(_) => None,
             };
-            if let Some(name) = name {
+            if let Some(name) = name.filter(|name| !name.is_hidden()) {
                 if let Some(first_item) = set.get(name) {
                     self.diagnostics
                         .push(diagnostics::ItemTreeDiagnostic::DuplicateDefinition {
@@ -112,16 +112,18 @@ impl Context {
         }
     }
 
-    /// Lowers a single module item
-    fn lower_mod_item(&mut self, item: &ast::ModuleItem) -> Option<ModItems> {
+    /// Lowers a single module item. Items with syntax errors, e.g. a missing
+    /// name or type, are lowered with placeholders, so the rest of the item can
+    /// still be analyzed.
+    fn lower_mod_item(&mut self, item: &ast::ModuleItem) -> ModItems {
         match item.kind() {
-            ast::ModuleItemKind::FunctionDef(ast) => self.lower_function(&ast).map(Into::into),
-            ast::ModuleItemKind::StructDef(ast) => self.lower_struct(&ast).map(Into::into),
-            ast::ModuleItemKind::TypeAliasDef(ast) => self.lower_type_alias(&ast).map(Into::into),
-            ast::ModuleItemKind::Use(ast) => Some(ModItems(
-                self.lower_use(&ast).into_iter().map(Into::into).collect(),
-            )),
-            ast::ModuleItemKind::Impl(ast) => self.lower_impl(&ast).map(Into::into),
+            ast::ModuleItemKind::FunctionDef(ast) => self.lower_function(&ast).into(),
+            ast::ModuleItemKind::StructDef(ast) => self.lower_struct(&ast).into(),
+            ast::ModuleItemKind::TypeAliasDef(ast) => self.lower_type_alias(&ast).into(),
+            ast::ModuleItemKind::Use(ast) => {
+                ModItems(self.lower_use(&ast).into_iter().map(Into::into).collect())
+            }
+            ast::ModuleItemKind::Impl(ast) => self.lower_impl(&ast).into(),
         }
     }
 
@@ -152,8 +154,8 @@ impl Context {
     }
 
     /// Lowers a function
-    fn lower_function(&mut self, func: &ast::FunctionDef) -> Option<LocalItemTreeId<Function>> {
-        let name = func.name()?.as_name();
+    fn lower_function(&mut self, func: &ast::FunctionDef) -> LocalItemTreeId<Function> {
+        let name = lower_name(func);
         let visibility = lower_visibility(func);
         let mut types = TypeRefMap::builder();
 
@@ -205,6 +207,16 @@ impl Context {
         if has_self_param {
             flags |= FunctionFlags::HAS_SELF_PARAM;
         }
+        if func.is_resumable() {
+            flags |= FunctionFlags::IS_RESUMABLE;
+        }
+        for attr in func.attrs() {
+            match attr.simple_name().as_deref() {
+                Some("test") => flags |= FunctionFlags::IS_TEST,
+                Some("bench") => flags |= FunctionFlags::IS_BENCH,
+                _ => {}
+            }
+        }
 
         let res = Function {
             name,
@@ -216,12 +228,12 @@ impl Context {
             flags,
         };
 
-        Some(self.data.functions.alloc(res).into())
+        self.data.functions.alloc(res).into()
     }
 
     /// Lowers a struct
-    fn lower_struct(&mut self, strukt: &ast::StructDef) -> Option<LocalItemTreeId<Struct>> {
-        let name = strukt.name()?.as_name();
+    fn lower_struct(&mut self, strukt: &ast::StructDef) -> LocalItemTreeId<Struct> {
+        let name = lower_name(strukt);
         let visibility = lower_visibility(strukt);
         let mut types = TypeRefMap::builder();
         let fields = self.lower_fields(&strukt.kind(), &mut types);
@@ -235,7 +247,7 @@ impl Context {
             fields,
             ast_id,
         };
-        Some(self.data.structs.alloc(res).into())
+        self.data.structs.alloc(res).into()
     }
 
     /// Lowers the fields of a struct or enum
@@ -265,9 +277,8 @@ impl Context {
     ) -> IdRange<Field> {
         let start = self.next_field_idx();
         for field in fields.fields() {
-            if let Some(data) = lower_record_field(&field, types) {
-                let _idx = self.data.fields.alloc(data);
-            }
+            let data = lower_record_field(&field, types);
+            let _idx = self.data.fields.alloc(data);
         }
         let end = self.next_field_idx();
         IdRange::new(start..end)
@@ -289,11 +300,8 @@ impl Context {
     }
 
     /// Lowers a type alias (e.g. `type Foo = Bar`)
-    fn lower_type_alias(
-        &mut self,
-        type_alias: &ast::TypeAliasDef,
-    ) -> Option<LocalItemTreeId<TypeAlias>> {
-        let name = type_alias.name()?.as_name();
+    fn lower_type_alias(&mut self, type_alias: &ast::TypeAliasDef) -> LocalItemTreeId<TypeAlias> {
+        let name = lower_name(type_alias);
         let visibility = lower_visibility(type_alias);
         let mut types = TypeRefMap::builder();
         let type_ref = type_alias.type_ref().map(|ty| types.alloc_from_node(&ty));
@@ -306,19 +314,19 @@ impl Context {
             type_ref,
             ast_id,
         };
-        Some(self.data.type_aliases.alloc(res).into())
+        self.data.type_aliases.alloc(res).into()
     }
 
-    fn lower_impl(&mut self, impl_def: &ast::Impl) -> Option<LocalItemTreeId<Impl>> {
+    fn lower_impl(&mut self, impl_def: &ast::Impl) -> LocalItemTreeId<Impl> {
         let ast_id = self.source_ast_id_map.ast_id(impl_def);
         let mut types = TypeRefMap::builder();
-        let self_ty = impl_def.type_ref().map(|ty| types.alloc_from_node(&ty))?;
+        let self_ty = types.alloc_from_node_opt(impl_def.type_ref().as_ref());
 
         let items = impl_def
             .associated_item_list()
             .into_iter()
             .flat_map(|it| it.associated_items())
-            .filter_map(|item| self.lower_associated_item(&item))
+            .map(|item| self.lower_associated_item(&item))
             .collect();
 
         let (types, _types_source_map) = types.finish();
@@ -330,14 +338,13 @@ impl Context {
             ast_id,
         };
 
-        Some(self.data.impls.alloc(res).into())
+        self.data.impls.alloc(res).into()
     }
 
-    fn lower_associated_item(&mut self, item: &ast::AssociatedItem) -> Option<AssociatedItem> {
-        let item: AssociatedItem = match item.kind() {
-            ast::AssociatedItemKind::FunctionDef(ast) => self.lower_function(&ast).map(Into::into),
-        }?;
-        Some(item)
+    fn lower_associated_item(&mut self, item: &ast::AssociatedItem) -> AssociatedItem {
+        match item.kind() {
+            ast::AssociatedItemKind::FunctionDef(ast) => self.lower_function(&ast).into(),
+        }
     }
 
     /// Returns the `Idx` of the next `Field`
@@ -354,11 +361,10 @@ impl Context {
 }
 
 /// Lowers a record field (e.g. `a:i32`)
-fn lower_record_field(field: &ast::RecordFieldDef, types: &mut TypeRefMapBuilder) -> Option<Field> {
-    let name = field.name()?.as_name();
+fn lower_record_field(field: &ast::RecordFieldDef, types: &mut TypeRefMapBuilder) -> Field {
+    let name = lower_name(field);
     let type_ref = types.alloc_from_node_opt(field.ascribed_type().as_ref());
-    let res = Field { name, type_ref };
-    Some(res)
+    Field { name, type_ref }
 }
 
 /// Lowers a tuple field (e.g. `i32`)
@@ -372,6 +378,13 @@ fn lower_tuple_field(
     Field { name, type_ref }
 }
 
+/// Lowers the name of an `ast::NameOwner`. If the name is missing because of a
+/// syntax error, a placeholder is returned that can not be referred to.
+fn lower_name(item: &impl NameOwner) -> Name {
+    item.name()
+        .map_or_else(Name::missing, |name| name.as_name())
+}
+
 /// Lowers an `ast::VisibilityOwner`
 fn lower_visibility(item: &impl ast::VisibilityOwner) -> RawVisibilityId {
     let vis = RawVisibility::from_ast(item.visibility());
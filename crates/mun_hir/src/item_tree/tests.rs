@@ -94,3 +94,20 @@ fn test_duplicate_import() {
     )
     .unwrap());
 }
+
+#[test]
+fn test_missing_names() {
+    insta::assert_snapshot!(print_item_tree(
+        r#"
+    fn (a:i32) -> i32 {}
+    fn () {}
+    struct { a: i32 }
+    struct Foo { pub, b: }
+    type = i32;
+    impl {
+        fn (self) {}
+    }
+    "#
+    )
+    .unwrap());
+}
@@ -11,8 +11,8 @@ pub use mun_hir_input::ModuleId;
 pub use salsa;
 
 pub use self::code_model::{
-    Field, Function, FunctionData, HasSource, Module, ModuleDef, Package, Struct, StructMemoryKind,
-    TypeAlias,
+    AssocItem, Field, FieldDefault, FieldDefaultValue, Function, FunctionData, HasSource, Impl,
+    Module, ModuleDef, Package, Struct, StructMemoryKind, StructRepr, TypeAlias,
 };
 pub use crate::{
     db::{
@@ -22,8 +22,8 @@ pub use crate::{
     diagnostics::{Diagnostic, DiagnosticSink},
     display::HirDisplay,
     expr::{
-        ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, ExprScopes, Literal, LogicOp, Ordering, Pat,
-        PatId, RecordLitField, Statement, UnaryOp,
+        ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, ExprScopes, Literal, LogKind, LogicOp,
+        Ordering, Pat, PatId, RecordLitField, ResultVariant, Statement, UnaryOp,
     },
     ids::ItemLoc,
     in_file::InFile,
@@ -34,7 +34,7 @@ pub use crate::{
     resolve::{resolver_for_expr, resolver_for_scope, Resolver, TypeNs, ValueNs},
     ty::{
         lower::CallableDef, FloatTy, InferenceResult, IntTy, ResolveBitness, Substitution, Ty,
-        TyKind,
+        TyKind, TypeWalk,
     },
     visibility::{HasVisibility, Visibility},
 };
@@ -50,6 +50,7 @@ mod expr;
 mod ids;
 mod in_file;
 mod item_tree;
+mod lints;
 mod name;
 mod name_resolution;
 mod path;
@@ -0,0 +1,328 @@
+//! Lints are checks of valid code that report code that is likely a mistake,
+//! e.g. a variable that is never used. They are reported as warnings and can
+//! be suppressed for a function by annotating it (or the `impl` that contains
+//! it) with `#[allow(...)]`, e.g. `#[allow(unused_variables)]`.
+
+use std::{collections::HashSet, sync::Arc};
+
+use mun_hir_input::PackageId;
+use mun_syntax::{
+    ast::{self, AttrsOwner, NameOwner},
+    AstNode, AstPtr, SyntaxNodePtr,
+};
+use rustc_hash::FxHashSet;
+
+use crate::{
+    code_model::src::HasSource,
+    diagnostics::{
+        DiagnosticSink, UnreachableCode, UnusedFunction, UnusedParameter, UnusedVariable,
+    },
+    ids::FunctionId,
+    resolve::{resolver_for_expr, ValueNs},
+    AssocItem, Body, CallableDef, Expr, ExprId, Function, HasVisibility, HirDatabase, Module,
+    ModuleDef, Package, Pat, PatId, Statement, TyKind, Visibility,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// A check that can be suppressed with `#[allow(...)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Lint {
+    /// A local variable that is never used
+    UnusedVariables,
+
+    /// A function parameter that is never used
+    UnusedParameters,
+
+    /// Code that can never be executed
+    UnreachableCode,
+
+    /// A private function that is never called
+    DeadCode,
+}
+
+impl Lint {
+    /// Returns the name that is used to refer to the lint in an attribute.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariables => "unused_variables",
+            Lint::UnusedParameters => "unused_parameters",
+            Lint::UnreachableCode => "unreachable_code",
+            Lint::DeadCode => "dead_code",
+        }
+    }
+
+    /// Returns the lint with the specified name.
+    pub(crate) fn from_name(name: &str) -> Option<Lint> {
+        [
+            Lint::UnusedVariables,
+            Lint::UnusedParameters,
+            Lint::UnreachableCode,
+            Lint::DeadCode,
+        ]
+        .into_iter()
+        .find(|lint| lint.name() == name)
+    }
+}
+
+/// Adds the warnings for all functions in the module to the `DiagnosticSink`.
+pub(crate) fn module_lints(db: &dyn HirDatabase, module: Module, sink: &mut DiagnosticSink<'_>) {
+    let used_functions = db.used_functions(module.package().id);
+
+    let free_functions = module
+        .declarations(db)
+        .into_iter()
+        .filter_map(|decl| match decl {
+            ModuleDef::Function(f) => Some(f),
+            _ => None,
+        });
+    for func in free_functions {
+        let lints = FunctionLints::new(db, func);
+        lints.check_body(sink);
+        if !used_functions.contains(&func.id) {
+            lints.check_unused(sink);
+        }
+    }
+
+    for associated_item in module.impls(db).into_iter().flat_map(|i| i.items(db)) {
+        let AssocItem::Function(func) = associated_item;
+        FunctionLints::new(db, func).check_body(sink);
+    }
+}
+
+/// Returns all functions in the package that are referenced from the body of
+/// another function. This requires type inference of every function in the
+/// package, so it is computed once per package instead of once per module.
+pub(crate) fn used_functions_query(
+    db: &dyn HirDatabase,
+    package: PackageId,
+) -> Arc<FxHashSet<FunctionId>> {
+    let mut used = FxHashSet::default();
+    for module in (Package { id: package }).modules(db) {
+        let free_functions = module
+            .declarations(db)
+            .into_iter()
+            .filter_map(|decl| match decl {
+                ModuleDef::Function(f) => Some(f),
+                _ => None,
+            });
+        let methods = module
+            .impls(db)
+            .into_iter()
+            .flat_map(|i| i.items(db))
+            .map(|AssocItem::Function(f)| f);
+
+        for func in free_functions.chain(methods) {
+            let body = func.body(db);
+            let infer = func.infer(db);
+            used.extend(
+                body.exprs()
+                    .filter_map(|(expr, _)| match infer[expr].interned() {
+                        TyKind::FnDef(CallableDef::Function(callee), _) if *callee != func => {
+                            Some(callee.id)
+                        }
+                        _ => None,
+                    }),
+            );
+        }
+    }
+    Arc::new(used)
+}
+
+/// Checks the lints of a single function.
+struct FunctionLints<'a> {
+    db: &'a dyn HirDatabase,
+    func: Function,
+    source: ast::FunctionDef,
+    allowed: HashSet<Lint>,
+}
+
+impl<'a> FunctionLints<'a> {
+    fn new(db: &'a dyn HirDatabase, func: Function) -> Self {
+        let source = func.source(db.upcast()).value;
+
+        // Lints can be allowed on the function itself or on the `impl` that contains it
+        let allowed = source
+            .syntax()
+            .ancestors()
+            .flat_map(|node| {
+                ast::FunctionDef::cast(node.clone())
+                    .map(|f| f.attrs())
+                    .or_else(|| ast::Impl::cast(node).map(|i| i.attrs()))
+                    .into_iter()
+                    .flatten()
+            })
+            .filter(|attr| attr.simple_name().as_deref() == Some("allow"))
+            .filter_map(|attr| attr.token_tree())
+            .flat_map(|token_tree| token_tree.idents().collect::<Vec<_>>())
+            .filter_map(|ident| Lint::from_name(ident.text()))
+            .collect();
+
+        FunctionLints {
+            db,
+            func,
+            source,
+            allowed,
+        }
+    }
+
+    fn is_allowed(&self, lint: Lint) -> bool {
+        self.allowed.contains(&lint)
+    }
+
+    /// Checks the body of the function for unused bindings and unreachable
+    /// code.
+    fn check_body(&self, sink: &mut DiagnosticSink<'_>) {
+        if self.func.is_extern(self.db) {
+            return;
+        }
+
+        let body = self.func.body(self.db);
+        self.check_unused_bindings(&body, sink);
+        if !self.is_allowed(Lint::UnreachableCode) {
+            self.check_unreachable_code(&body, body.body_expr(), sink);
+        }
+    }
+
+    /// Reports the local variables and parameters that are never referenced.
+    fn check_unused_bindings(&self, body: &Body, sink: &mut DiagnosticSink<'_>) {
+        let used: HashSet<PatId> = body
+            .exprs()
+            .filter_map(|(expr, data)| match data {
+                Expr::Path(path) => {
+                    let resolver = resolver_for_expr(self.db.upcast(), body.owner(), expr);
+                    match resolver.resolve_path_as_value_fully(self.db.upcast(), path) {
+                        Some((ValueNs::LocalBinding(pat), _)) => Some(pat),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect();
+
+        let self_param = body.self_param().map(|(pat, _)| *pat);
+        let params: HashSet<PatId> = body.params().iter().map(|(pat, _)| *pat).collect();
+
+        let (_, source_map) = self.db.body_with_source_map(self.func.id.into());
+        let file = self.func.file_id(self.db);
+        for (pat, data) in body.pats() {
+            let Pat::Bind { name } = data else {
+                continue;
+            };
+            if used.contains(&pat)
+                || Some(pat) == self_param
+                || name.as_str().is_some_and(|name| name.starts_with('_'))
+            {
+                continue;
+            }
+            let Some(source) = source_map.pat_syntax(pat) else {
+                continue;
+            };
+            let Some(ptr) = source.value.left() else {
+                continue;
+            };
+
+            if params.contains(&pat) {
+                if !self.is_allowed(Lint::UnusedParameters) {
+                    sink.push(UnusedParameter {
+                        file,
+                        pat: ptr.into(),
+                        name: name.clone(),
+                    });
+                }
+            } else if !self.is_allowed(Lint::UnusedVariables) {
+                sink.push(UnusedVariable {
+                    file,
+                    pat: ptr.into(),
+                    name: name.clone(),
+                });
+            }
+        }
+    }
+
+    /// Reports the first statement of every block that follows a statement
+    /// that never returns, e.g. a `return` expression.
+    fn check_unreachable_code(&self, body: &Body, expr: ExprId, sink: &mut DiagnosticSink<'_>) {
+        let Expr::Block { statements, tail } = &body[expr] else {
+            body[expr].walk_child_exprs(|child| self.check_unreachable_code(body, child, sink));
+            return;
+        };
+
+        let infer = self.func.infer(self.db);
+        let mut nodes = statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::Let {
+                    pat, initializer, ..
+                } => (Some(*pat), *initializer),
+                Statement::Expr(expr) => (None, Some(*expr)),
+            })
+            .chain(tail.map(|tail| (None, Some(tail))));
+
+        while let Some((_, expr)) = nodes.next() {
+            if let Some(expr) = expr {
+                self.check_unreachable_code(body, expr, sink);
+            }
+            if !expr.is_some_and(|expr| infer[expr].is_never()) {
+                continue;
+            }
+
+            // Everything after a statement that never returns is unreachable, but only the
+            // first statement is reported
+            if let Some((pat, expr)) = nodes.next() {
+                if let Some(node) = self.statement_syntax(pat, expr) {
+                    sink.push(UnreachableCode {
+                        file: self.func.file_id(self.db),
+                        node,
+                    });
+                }
+            }
+            return;
+        }
+    }
+
+    /// Returns the syntax node of a statement, which is either a `let`
+    /// statement that binds `pat` or the expression `expr`.
+    fn statement_syntax(&self, pat: Option<PatId>, expr: Option<ExprId>) -> Option<SyntaxNodePtr> {
+        let (_, source_map) = self.db.body_with_source_map(self.func.id.into());
+        match (pat, expr) {
+            (Some(pat), _) => {
+                let root = self.db.parse(self.func.file_id(self.db)).syntax_node();
+                let pat = source_map.pat_syntax(pat)?.value.left()?.to_node(&root);
+                let let_stmt = pat.syntax().ancestors().find_map(ast::LetStmt::cast)?;
+                Some(SyntaxNodePtr::new(let_stmt.syntax()))
+            }
+            (None, Some(expr)) => Some(
+                source_map
+                    .expr_syntax(expr)?
+                    .value
+                    .either(|ptr| ptr.syntax_node_ptr(), |ptr| ptr.syntax_node_ptr()),
+            ),
+            (None, None) => None,
+        }
+    }
+
+    /// Reports the function if it is private, not provided by the host and not
+    /// a test or benchmark. Must only be called for functions that are never
+    /// referenced.
+    fn check_unused(&self, sink: &mut DiagnosticSink<'_>) {
+        if self.is_allowed(Lint::DeadCode)
+            || self.func.is_extern(self.db)
+            || self.func.is_test(self.db)
+            || self.func.is_bench(self.db)
+            || !matches!(self.func.visibility(self.db), Visibility::Module(_))
+        {
+            return;
+        }
+        let Some(name) = self.source.name() else {
+            return;
+        };
+        sink.push(UnusedFunction {
+            file: self.func.file_id(self.db),
+            func: AstPtr::new(&self.source),
+            name: self.func.name(self.db),
+            name_range: name.syntax().text_range(),
+        });
+    }
+}
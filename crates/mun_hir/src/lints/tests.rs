@@ -0,0 +1,128 @@
+use crate::utils::tests::lints;
+
+#[test]
+fn unused_variables() {
+    insta::assert_snapshot!(lints(
+        r#"
+    pub fn foo() -> i32 {
+        let a = 1;
+        let b = 2;
+        let _c = 3;
+        let d: i32;
+        d = 4;
+        b
+    }
+    "#), @r###"
+    30..31: unused variable: `a`
+    "###);
+}
+
+#[test]
+fn unused_parameters() {
+    insta::assert_snapshot!(lints(
+        r#"
+    pub struct Foo;
+    impl Foo {
+        pub fn bar(self, a: i32) {}
+    }
+    pub fn foo(a: i32, b: i32, _c: i32) -> i32 {
+        a
+    }
+    extern fn baz(a: i32);
+    "#), @r###"
+    80..81: unused parameter: `b`
+    48..49: unused parameter: `a`
+    "###);
+}
+
+#[test]
+fn unreachable_code() {
+    insta::assert_snapshot!(lints(
+        r#"
+    pub fn foo(a: bool) -> i32 {
+        if a {
+            return 1;
+            let b = 2;
+            let c = 3;
+        }
+        return 4;
+        5
+    }
+    pub fn bar() {
+        loop {
+            break;
+            bar();
+        }
+        let a = return;
+        bar();
+    }
+    pub fn baz(a: bool) -> i32 {
+        if a { return 1 } else { return 2 };
+        3
+    }
+    "#), @r###"
+    70..71: unused variable: `b`
+    89..90: unused variable: `c`
+    66..76: unreachable code
+    120..121: unreachable code
+    194..195: unused variable: `a`
+    173..178: unreachable code
+    210..215: unreachable code
+    293..294: unreachable code
+    "###);
+}
+
+#[test]
+fn dead_code() {
+    insta::assert_snapshot!(lints(
+        r#"
+    fn foo() {}
+    fn bar() { bar() }
+    fn baz() {}
+    pub fn qux() { baz() }
+    pub(package) fn quux() {}
+    extern fn corge();
+    #[test]
+    fn grault() {}
+    #[bench]
+    fn garply() {}
+    "#), @r###"
+    3..6: function `foo` is never used
+    15..18: function `bar` is never used
+    82..86: function `quux` is never used
+    "###);
+}
+
+#[test]
+fn allow_attribute() {
+    insta::assert_snapshot!(lints(
+        r#"
+    #[allow(dead_code, unused_variables)]
+    fn foo(a: i32) {
+        let b = 1;
+    }
+    #[allow(unused_parameters)]
+    pub fn bar(a: i32) {
+        return;
+        let b = 1;
+    }
+    pub struct Foo;
+    #[allow(unused_parameters, unreachable_code)]
+    impl Foo {
+        pub fn baz(a: i32) {
+            return;
+            let b = 1;
+        }
+    }
+    #[allow(unknown)]
+    #[allow(unused_variables)]
+    pub fn qux() {
+        let a = 1;
+    }
+    "#), @r###"
+    45..46: unused parameter: `a`
+    141..142: unused variable: `b`
+    137..147: unreachable code
+    276..277: unused variable: `b`
+    "###);
+}
@@ -119,6 +119,9 @@ impl InherentImpls {
                     let name = match item {
                         AssocItemId::FunctionId(it) => db.fn_data(*it).name().clone(),
                     };
+                    if name.is_hidden() {
+                        continue;
+                    }
                     match name_to_item.entry(name) {
                         Entry::Vacant(entry) => {
                             entry.insert(*item);
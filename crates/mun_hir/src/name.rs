@@ -55,6 +55,19 @@ impl Name {
         Name::new_text("[missing name]".into())
     }
 
+    /// Creates a name for a binding that is introduced by desugaring. These
+    /// names can never be written in source code, so they never conflict with
+    /// user-defined names.
+    pub(crate) fn new_desugared(text: &str) -> Name {
+        Name::new_text(SmolStr::new(format!("[{text}]")))
+    }
+
+    /// Returns true if this name can not be written in source code, e.g.
+    /// because it was introduced by desugaring.
+    pub(crate) fn is_hidden(&self) -> bool {
+        matches!(&self.0, Repr::Text(text) if text.starts_with('['))
+    }
+
     pub(crate) fn as_tuple_index(&self) -> Option<usize> {
         match self.0 {
             Repr::TupleField(idx) => Some(idx),
@@ -113,7 +126,9 @@ pub mod known {
     known_names!(
         // Primitives
         int, isize, i8, i16, i32, i64, i128, uint, usize, u8, u16, u32, u64, u128, float, f32, f64,
-        bool,
+        bool, // Result
+        Result, Ok, Err, // Built-in functions
+        print, log,
     );
 
     // self/Self cannot be used as an identifier
@@ -62,18 +62,65 @@ impl PackageDefs {
         (res.resolved_def, res.segment_index)
     }
 
-    /// Resolves the specified `name` from within the specified `module`
+    /// Resolves the specified `name` from within the specified `module`. Names
+    /// are looked up in the scope of the module, then in the packages the
+    /// package depends on, then in the prelude, and finally in the builtin
+    /// scope.
     fn resolve_name_in_module(
         &self,
-        _db: &dyn DefDatabase,
+        db: &dyn DefDatabase,
         module: PackageModuleId,
         name: &Name,
     ) -> PerNs<(ItemDefinitionId, Visibility)> {
         self[module]
             .get(name)
+            .or(self.resolve_name_in_dependencies(db, name))
+            .or(self.resolve_name_in_prelude(db, name))
             .or(BUILTIN_SCOPE.get(name).copied().unwrap_or_else(PerNs::none))
     }
 
+    /// Resolves the specified `name` as the root module of a package that this
+    /// package depends on.
+    fn resolve_name_in_dependencies(
+        &self,
+        db: &dyn DefDatabase,
+        name: &Name,
+    ) -> PerNs<(ItemDefinitionId, Visibility)> {
+        let packages = db.packages();
+        let dependency = packages
+            .dependencies(self.id)
+            .find(|(dependency_name, _)| name.as_str() == Some(*dependency_name));
+        match dependency {
+            Some((_, package)) => PerNs::types((
+                ModuleId {
+                    package,
+                    local_id: db.module_tree(package).root,
+                }
+                .into(),
+                Visibility::Public,
+            )),
+            None => PerNs::none(),
+        }
+    }
+
+    /// Resolves the specified `name` in the root module of the prelude package.
+    /// Only public items are part of the prelude.
+    fn resolve_name_in_prelude(
+        &self,
+        db: &dyn DefDatabase,
+        name: &Name,
+    ) -> PerNs<(ItemDefinitionId, Visibility)> {
+        match db.packages().prelude() {
+            Some(prelude) if prelude != self.id => {
+                let prelude_defs = db.package_defs(prelude);
+                prelude_defs[prelude_defs.module_tree.root]
+                    .get(name)
+                    .and_then(|(item, vis)| vis.is_externally_visible().then_some((item, vis)))
+            }
+            _ => PerNs::none(),
+        }
+    }
+
     /// Resolves the specified `path` from within the specified `module`. Also
     /// returns whether or not additions to the `PackageDef` would change
     /// the result or whether a fixed point has been reached. This is useful
@@ -119,6 +166,9 @@ impl PackageDefs {
             )),
         };
 
+        // The package in which the path is resolved, which changes when the path
+        // refers to an item in another package.
+        let mut package = self.id;
         for (i, segment) in segments {
             let (curr, vis) = match curr_per_ns.take_types() {
                 Some(r) => r,
@@ -128,6 +178,10 @@ impl PackageDefs {
             };
 
             curr_per_ns = match curr {
+                ItemDefinitionId::ModuleId(module) if module.package != self.id => {
+                    package = module.package;
+                    db.package_defs(module.package)[module.local_id].get(segment)
+                }
                 ItemDefinitionId::ModuleId(module) => self[module.local_id].get(segment),
                 // TODO: Enum variants
                 s => {
@@ -135,17 +189,12 @@ impl PackageDefs {
                         PerNs::types((s, vis)),
                         ReachedFixedPoint::Yes,
                         Some(i),
-                        Some(self.module_tree.package),
+                        Some(package),
                     );
                 }
             };
         }
 
-        ResolvePathResult::with(
-            curr_per_ns,
-            ReachedFixedPoint::Yes,
-            None,
-            Some(self.module_tree.package),
-        )
+        ResolvePathResult::with(curr_per_ns, ReachedFixedPoint::Yes, None, Some(package))
     }
 }
@@ -1,4 +1,5 @@
 use la_arena::ArenaMap;
+use mun_db::CheckCanceled;
 use mun_hir_input::{FileId, ModuleId, PackageId, PackageModuleId};
 use rustc_hash::FxHashMap;
 
@@ -206,6 +207,10 @@ impl<'db> DefCollector<'db> {
         // Now, as long as we have unresolved imports, try to resolve them, or part of
         // them.
         while !self.unresolved_imports.is_empty() {
+            // Resolving imports might take many iterations, abort early if the input
+            // changed
+            self.db.unwind_if_canceled();
+
             // Keep track of whether we were able to resolve anything
             let mut resolved_something = false;
 
@@ -296,6 +301,29 @@ impl<'db> DefCollector<'db> {
         if import.is_glob {
             #[allow(clippy::match_same_arms)]
             match resolution.take_types() {
+                Some((ItemDefinitionId::ModuleId(m), _)) if m.package != self.package_id => {
+                    // The definitions of other packages are complete, so only the public items
+                    // that are currently in the module have to be imported.
+                    let package_defs = self.db.package_defs(m.package);
+                    let resolutions = package_defs[m.local_id]
+                        .entries()
+                        .map(|(n, res)| ImportResolution {
+                            name: Some(n.clone()),
+                            resolution: res.and_then(|(item, vis)| {
+                                vis.is_externally_visible().then_some((item, vis))
+                            }),
+                        })
+                        .filter(|res| !res.resolution.is_none())
+                        .collect::<Vec<_>>();
+
+                    self.update(
+                        import_module_id,
+                        import_visibility,
+                        ImportType::Glob,
+                        import.source,
+                        &resolutions,
+                    );
+                }
                 Some((ItemDefinitionId::ModuleId(m), _)) => {
                     let scope = &self.package_defs[m.local_id];
 
@@ -342,25 +370,23 @@ impl<'db> DefCollector<'db> {
                     // than a type?
                 }
             }
-        } else {
-            match import.path.segments.last() {
-                Some(last_segment) => {
-                    let name = match &import.alias {
-                        Some(ImportAlias::Alias(name)) => Some(name.clone()),
-                        Some(ImportAlias::Underscore) => None,
-                        None => Some(last_segment.clone()),
-                    };
+        } else if let Some(last_segment) = import.path.segments.last() {
+            let name = match &import.alias {
+                Some(ImportAlias::Alias(name)) => Some(name.clone()),
+                Some(ImportAlias::Underscore) => None,
+                None => Some(last_segment.clone()),
+            };
 
-                    self.update(
-                        import_module_id,
-                        import_visibility,
-                        ImportType::Named,
-                        import.source,
-                        &[ImportResolution { name, resolution }],
-                    );
-                }
-                None => unreachable!(),
-            }
+            self.update(
+                import_module_id,
+                import_visibility,
+                ImportType::Named,
+                import.source,
+                &[ImportResolution { name, resolution }],
+            );
+        } else {
+            // Happens when importing a path without segments, e.g. `use self;`.
+            // There is no name to import.
         }
     }
 
@@ -531,6 +557,13 @@ impl<'a> ModCollectorContext<'a, '_> {
             };
 
             self.def_collector.package_defs.modules[self.module_id].add_definition(id);
+
+            // An item without a name can not be referred to, it is only defined so it is
+            // analyzed.
+            if name.is_hidden() {
+                continue;
+            }
+
             let visibility = Visibility::resolve(
                 self.def_collector.db,
                 &self.def_collector.package_defs.module_tree,
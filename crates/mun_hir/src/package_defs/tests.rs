@@ -178,13 +178,33 @@ fn use_unresolved() {
     "###);
 }
 
+#[test]
+fn use_without_segments() {
+    insta::assert_snapshot!(resolve(
+        r#"
+    //- /foo.mun
+    pub struct Ok;
+
+    //- /mod.mun
+    use self;
+    use package;
+    use foo::Ok;
+    "#),
+    @r###"
+    mod mod
+    +-- use struct package::foo::Ok
+    '-- mod foo
+        '-- struct Ok
+    "###);
+}
+
 #[test]
 fn use_() {
     insta::assert_snapshot!(resolve(
         r#"
     //- /bar.mun
     use package::Foo;
-    pub struct Bar(Foo);
+    pub struct Bar(pub Foo);
 
     //- /mod.mun
     pub use foo::Foo; // Re-export a child's definition
@@ -217,6 +237,76 @@ fn use_() {
     "###);
 }
 
+#[test]
+fn use_from_other_package() {
+    insta::assert_snapshot!(resolve(
+        r#"
+    //- /mod.mun package:core prelude
+    pub use math::Vec2;
+
+    //- /math.mun package:core
+    pub struct Vec2;
+    pub struct Vec3;
+    struct Private;
+
+    //- /mod.mun
+    use core::math::Vec3;
+
+    //- /foo.mun
+    use core::math::*;
+    "#),
+    @r###"
+    mod mod
+    +-- use struct core::math::Vec3
+    '-- mod foo
+        +-- use struct core::math::Vec3
+        '-- use struct core::math::Vec2
+
+    mod mod
+    +-- use struct core::math::Vec2
+    '-- mod math
+        +-- struct Vec2
+        +-- struct Vec3
+        '-- struct Private
+    "###);
+}
+
+#[test]
+fn use_from_dependency() {
+    insta::assert_snapshot!(resolve(
+        r#"
+    //- /mod.mun deps:physics
+    use physics::Body;
+    use physics::shapes::*;
+    use ai::Agent;
+
+    //- /mod.mun package:physics
+    pub struct Body;
+
+    //- /shapes.mun package:physics
+    pub struct Circle;
+    struct Hidden;
+
+    //- /mod.mun package:ai
+    pub struct Agent;
+    "#),
+    @r###"
+    mod mod
+    +-- ERROR: 47..56: unresolved import
+    +-- use struct physics::Body
+    '-- use struct physics::shapes::Circle
+
+    mod mod
+    +-- struct Body
+    '-- mod shapes
+        +-- struct Circle
+        '-- struct Hidden
+
+    mod mod
+    '-- struct Agent
+    "###);
+}
+
 fn resolve(content: &str) -> String {
     let db = MockDatabase::with_files(content);
 
@@ -238,9 +328,7 @@ fn tree_for_module(
     // Construct a tree node
     let mut node = text_trees::StringTreeNode::new(format!(
         "mod {}",
-        module
-            .name(db)
-            .map_or_else(|| "mod".to_owned(), |name| name.to_string())
+        module.name(db).unwrap_or_else(|| "mod".to_owned())
     ));
 
     // Add module level diagnostics
@@ -344,7 +432,7 @@ fn fully_qualified_module_path(db: &dyn HirDatabase, module: Module) -> String {
             .into_iter()
             .map(|m| {
                 m.name(db)
-                    .map_or_else(|| "package".to_owned(), |name| name.to_string())
+                    .unwrap_or_else(|| m.package().name(db).unwrap_or_else(|| "package".to_owned()))
             })
             .rev(),
         "::".to_string(),
@@ -32,6 +32,13 @@ pub(crate) fn print_type_ref<W: Write>(
             }
             write!(write, ")")
         }
+        TypeRef::Result(ok, err) => {
+            write!(write, "Result<")?;
+            print_type_ref(db, type_ref, *ok, write)?;
+            write!(write, ", ")?;
+            print_type_ref(db, type_ref, *err, write)?;
+            write!(write, ">")
+        }
         TypeRef::Error => write!(write, "{{unknown}}"),
     }
 }
@@ -115,28 +115,27 @@ impl Resolver {
 }
 
 impl Resolver {
-    // TODO: This function is useful when we need to resolve paths between modules
-    // /// Resolves a path
-    // fn resolve_module_path(
-    //     &self,
-    //     db: &dyn DefDatabase,
-    //     path: &Path,
-    // ) -> PerNs<(ItemDefinitionId, Visibility)> {
-    //     let (defs, module) = match self.module_scope() {
-    //         None => return PerNs::none(),
-    //         Some(it) => it,
-    //     };
-    //
-    //     let (module_res, segment_index) = defs.resolve_path_in_module(db, module,
-    // &path);
-    //
-    //     // If the `segment_index` contains a value it means the path didn't
-    // resolve completely yet     if segment_index.is_some() {
-    //         return PerNs::none();
-    //     }
-    //
-    //     module_res
-    // }
+    /// Resolves the specified `path` from the module scope of this resolver.
+    /// Returns an empty `PerNs` if the path does not resolve completely.
+    pub fn resolve_module_path(
+        &self,
+        db: &dyn DefDatabase,
+        path: &Path,
+    ) -> PerNs<(ItemDefinitionId, Visibility)> {
+        let Some((defs, module)) = self.module_scope() else {
+            return PerNs::none();
+        };
+
+        let (module_res, segment_index) = defs.resolve_path_in_module(db, module, path);
+
+        // If the `segment_index` contains a value it means the path didn't resolve
+        // completely yet
+        if segment_index.is_some() {
+            return PerNs::none();
+        }
+
+        module_res
+    }
 
     /// Returns the `Module` scope of the resolver
     fn module_scope(&self) -> Option<(&PackageDefs, PackageModuleId)> {
@@ -376,6 +375,7 @@ impl Scope {
                 .expr_scopes
                 .entries(scope.scope_id)
                 .iter()
+                .filter(|entry| !entry.name().is_hidden())
                 .for_each(|entry| visitor(entry.name().clone(), ScopeDef::Local(entry.pat()))),
         }
     }
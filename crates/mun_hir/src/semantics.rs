@@ -14,6 +14,7 @@ mod source_to_def;
 
 use std::cell::RefCell;
 
+use either::Either;
 use mun_hir_input::FileId;
 use mun_syntax::{ast, AstNode, SyntaxNode, TextSize};
 use rustc_hash::FxHashMap;
@@ -21,10 +22,11 @@ use smallvec::SmallVec;
 
 use crate::{
     ids::{DefWithBodyId, ImplId, ItemDefinitionId},
-    resolve::{self, HasResolver},
+    resolve::{self, HasResolver, ValueNs},
     semantics::source_to_def::{SourceToDefCache, SourceToDefContainer, SourceToDefContext},
     source_analyzer::SourceAnalyzer,
-    HirDatabase, InFile, ModuleDef, Name, PatId, PerNs, Resolver, Ty, Visibility,
+    BinaryOp, Expr, Field, Function, HirDatabase, InFile, Module, ModuleDef, Name, Pat, PatId,
+    PerNs, Resolver, Struct, Ty, TypeAlias, Visibility,
 };
 
 /// The primary API to get semantic information, like types, from syntax trees.
@@ -72,6 +74,31 @@ impl<'db> Semantics<'db> {
         self.analyze(expr.syntax()).type_of_expr(self.db, expr)
     }
 
+    /// Resolves the specified path to the item it refers to.
+    pub fn resolve_path(&self, path: &ast::Path) -> Option<PathResolution> {
+        self.analyze(path.syntax()).resolve_path(self.db, path)
+    }
+
+    /// Returns the field that is accessed by the specified field expression.
+    pub fn resolve_field(&self, field: &ast::FieldExpr) -> Option<Field> {
+        self.analyze(field.syntax()).resolve_field(self.db, field)
+    }
+
+    /// Returns the field that is initialized by the specified field of a record
+    /// literal. If the field uses the shorthand syntax (e.g. `Foo { a }`), the
+    /// local that is used to initialize the field is also returned.
+    pub fn resolve_record_field(&self, field: &ast::RecordField) -> Option<(Field, Option<Local>)> {
+        self.analyze(field.syntax())
+            .resolve_record_field(self.db, field)
+    }
+
+    /// Returns the HIR definition of the specified syntax node, e.g. the
+    /// `Function` of an `ast::FunctionDef`.
+    pub fn to_def<T: ToDef>(&self, src: &T) -> Option<T::Def> {
+        let src = self.find_file(src.syntax().clone()).with_value(src.clone());
+        T::to_def(self, src)
+    }
+
     /// Returns the source analyzer for the given node.
     fn analyze(&self, node: &SyntaxNode) -> SourceAnalyzer {
         self.build_analyzer(node, None)
@@ -149,6 +176,36 @@ impl<'db> Semantics<'db> {
     }
 }
 
+/// A trait implemented for syntax nodes that define an item. Use
+/// [`Semantics::to_def`] to find the definition of such a node.
+pub trait ToDef: AstNode + Clone {
+    type Def;
+
+    /// Returns the HIR definition of `src`.
+    fn to_def(sema: &Semantics<'_>, src: InFile<Self>) -> Option<Self::Def>;
+}
+
+macro_rules! to_def_impls {
+    ($(($def:path, $ast:path, $meth:ident)),* ,) => {$(
+        impl ToDef for $ast {
+            type Def = $def;
+
+            fn to_def(sema: &Semantics<'_>, src: InFile<Self>) -> Option<Self::Def> {
+                sema.with_source_to_def_context(|ctx| ctx.$meth(src)).map(Into::into)
+            }
+        }
+    )*}
+}
+
+to_def_impls![
+    (Function, ast::FunctionDef, fn_to_def),
+    (Struct, ast::StructDef, struct_to_def),
+    (TypeAlias, ast::TypeAliasDef, type_alias_to_def),
+    (Field, ast::RecordFieldDef, field_to_def),
+    (Local, ast::BindPat, bind_pat_to_def),
+    (Local, ast::SelfParam, self_param_to_def),
+];
+
 /// Returns the root node of the specified node.
 fn find_root(node: &SyntaxNode) -> SyntaxNode {
     node.ancestors().last().unwrap()
@@ -195,6 +252,17 @@ impl ScopeDef {
     }
 }
 
+/// The item a path resolves to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PathResolution {
+    /// An item that is defined in a module
+    Def(ModuleDef),
+    /// A local binding (only value namespace)
+    Local(Local),
+    /// The `Self` type of an `impl` block
+    SelfType(Impl),
+}
+
 /// An `impl` block
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Impl {
@@ -214,15 +282,95 @@ pub struct Local {
     pub(crate) pat_id: PatId,
 }
 
+impl From<(DefWithBodyId, PatId)> for Local {
+    fn from((parent, pat_id): (DefWithBodyId, PatId)) -> Self {
+        Local { parent, pat_id }
+    }
+}
+
 impl Local {
     /// Returns the type of this local
     pub fn ty(self, db: &dyn HirDatabase) -> Ty {
         let infer = db.infer(self.parent);
         infer[self.pat_id].clone()
     }
+
+    /// Returns the name of this local or `None` if it is not bound to a name.
+    pub fn name(self, db: &dyn HirDatabase) -> Option<Name> {
+        let body = db.body(self.parent);
+        match &body[self.pat_id] {
+            Pat::Bind { name } => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this local is a parameter of its function, including the
+    /// `self` parameter.
+    pub fn is_param(self, db: &dyn HirDatabase) -> bool {
+        let body = db.body(self.parent);
+        body.self_param()
+            .into_iter()
+            .chain(body.params())
+            .any(|(pat_id, _)| *pat_id == self.pat_id)
+    }
+
+    /// Returns true if this local is the `self` parameter of its function.
+    pub fn is_self(self, db: &dyn HirDatabase) -> bool {
+        let body = db.body(self.parent);
+        body.self_param()
+            .is_some_and(|(pat_id, _)| *pat_id == self.pat_id)
+    }
+
+    /// Returns true if a value is assigned to this local anywhere in its body.
+    /// Mun has no `mut` keyword, every local can be assigned to, so this is how
+    /// mutable bindings are told apart. Initializing a local that was declared
+    /// without a value also counts as an assignment.
+    pub fn is_mutated(self, db: &dyn HirDatabase) -> bool {
+        let body = db.body(self.parent);
+        for (_, expr) in body.exprs() {
+            let Expr::BinaryOp {
+                lhs,
+                op: Some(BinaryOp::Assignment { .. }),
+                ..
+            } = expr
+            else {
+                continue;
+            };
+            let Expr::Path(path) = &body[*lhs] else {
+                continue;
+            };
+            let resolver = resolve::resolver_for_expr(db.upcast(), self.parent, *lhs);
+            if let Some((ValueNs::LocalBinding(pat_id), _)) =
+                resolver.resolve_path_as_value_fully(db.upcast(), path)
+            {
+                if pat_id == self.pat_id {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns the pattern or `self` parameter that defines this local.
+    pub fn source(self, db: &dyn HirDatabase) -> InFile<Either<ast::Pat, ast::SelfParam>> {
+        let (_, source_map) = db.body_with_source_map(self.parent);
+        let src = source_map
+            .pat_syntax(self.pat_id)
+            .expect("a local must originate from source");
+        let root = src.file_syntax(db.upcast());
+        src.map(|ptr| {
+            ptr.map_left(|it| it.to_node(&root))
+                .map_right(|it| it.to_node(&root))
+        })
+    }
 }
 
 impl<'a> SemanticsScope<'a> {
+    /// Returns the module in which this scope is defined.
+    pub fn module(&self) -> Option<Module> {
+        self.resolver.module().map(Module::from)
+    }
+
     /// Call the `visit` function for every named item in the scope
     pub fn visit_all_names(&self, visit: &mut dyn FnMut(Name, ScopeDef)) {
         let resolver = &self.resolver;
@@ -1,12 +1,15 @@
 use mun_hir_input::{FileId, ModuleId};
-use mun_syntax::{ast, match_ast, AstNode, SyntaxNode};
+use mun_syntax::{ast, ast::NameOwner, match_ast, AstNode, SyntaxNode};
 use rustc_hash::FxHashMap;
 
 use crate::{
     code_model::src::HasSource,
-    ids::{DefWithBodyId, FunctionId, ItemDefinitionId, Lookup, StructId, TypeAliasId},
+    ids::{
+        AssocItemId, DefWithBodyId, FunctionId, ItemDefinitionId, Lookup, StructId, TypeAliasId,
+    },
     item_scope::ItemScope,
-    DefDatabase, HirDatabase, InFile,
+    name::AsName,
+    DefDatabase, Field, HirDatabase, InFile, PatId, Struct,
 };
 
 pub(super) type SourceToDefCache = FxHashMap<SourceToDefContainer, SourceToDefMap>;
@@ -46,7 +49,7 @@ impl SourceToDefContext<'_, '_> {
     }
 
     /// Find the `FunctionId` associated with the specified syntax tree node.
-    fn fn_to_def(&mut self, src: InFile<ast::FunctionDef>) -> Option<FunctionId> {
+    pub(super) fn fn_to_def(&mut self, src: InFile<ast::FunctionDef>) -> Option<FunctionId> {
         let container = self.find_container(src.as_ref().map(AstNode::syntax))?;
         let db = self.db;
         let def_map = &*self
@@ -56,6 +59,71 @@ impl SourceToDefContext<'_, '_> {
         def_map.functions.get(&src).copied()
     }
 
+    /// Find the `StructId` associated with the specified syntax tree node.
+    pub(super) fn struct_to_def(&mut self, src: InFile<ast::StructDef>) -> Option<StructId> {
+        let container = self.find_container(src.as_ref().map(AstNode::syntax))?;
+        let db = self.db;
+        let def_map = &*self
+            .cache
+            .entry(container)
+            .or_insert_with(|| container.source_to_def_map(db));
+        def_map.structs.get(&src).copied()
+    }
+
+    /// Find the `TypeAliasId` associated with the specified syntax tree node.
+    pub(super) fn type_alias_to_def(
+        &mut self,
+        src: InFile<ast::TypeAliasDef>,
+    ) -> Option<TypeAliasId> {
+        let container = self.find_container(src.as_ref().map(AstNode::syntax))?;
+        let db = self.db;
+        let def_map = &*self
+            .cache
+            .entry(container)
+            .or_insert_with(|| container.source_to_def_map(db));
+        def_map.type_aliases.get(&src).copied()
+    }
+
+    /// Find the `Field` associated with the specified syntax tree node.
+    pub(super) fn field_to_def(&mut self, src: InFile<ast::RecordFieldDef>) -> Option<Field> {
+        let strukt = src
+            .value
+            .syntax()
+            .ancestors()
+            .find_map(ast::StructDef::cast)?;
+        let strukt: Struct = self.struct_to_def(src.with_value(strukt))?.into();
+        let name = src.value.name()?.as_name();
+        strukt.field(self.db, &name)
+    }
+
+    /// Find the local binding associated with the specified syntax tree node.
+    pub(super) fn bind_pat_to_def(
+        &mut self,
+        src: InFile<ast::BindPat>,
+    ) -> Option<(DefWithBodyId, PatId)> {
+        let container = match self.find_container(src.as_ref().map(AstNode::syntax))? {
+            SourceToDefContainer::DefWithBodyId(def) => def,
+            SourceToDefContainer::ModuleId(_) => return None,
+        };
+        let (_, source_map) = self.db.body_with_source_map(container);
+        let pat_id = source_map.node_pat(&src.value.into())?;
+        Some((container, pat_id))
+    }
+
+    /// Find the local binding of the specified `self` parameter.
+    pub(super) fn self_param_to_def(
+        &mut self,
+        src: InFile<ast::SelfParam>,
+    ) -> Option<(DefWithBodyId, PatId)> {
+        let container = match self.find_container(src.as_ref().map(AstNode::syntax))? {
+            SourceToDefContainer::DefWithBodyId(def) => def,
+            SourceToDefContainer::ModuleId(_) => return None,
+        };
+        let (_, source_map) = self.db.body_with_source_map(container);
+        let pat_id = source_map.node_self_param(&src.value)?;
+        Some((container, pat_id))
+    }
+
     /// Finds the `ModuleId` associated with the specified `file`
     fn file_to_def(&self, file_id: FileId) -> Option<ModuleId> {
         let source_root_id = self.db.file_source_root(file_id);
@@ -148,6 +216,18 @@ impl SourceToDef for ItemScope {
         self.declarations()
             .for_each(|item| add_module_def(db.upcast(), &mut result, item));
 
+        // Functions defined in `impl` blocks are not declared in the module scope
+        for impl_id in self.impls() {
+            for item in db.impl_data(impl_id).items.iter() {
+                match *item {
+                    AssocItemId::FunctionId(id) => {
+                        let src = id.lookup(db.upcast()).source(db.upcast());
+                        result.functions.insert(src, id);
+                    }
+                }
+            }
+        }
+
         result
     }
 }
@@ -6,8 +6,13 @@ use mun_syntax::{ast, AstNode, SyntaxNode, TextRange, TextSize};
 use crate::{
     expr::{scope::LocalScopeId, BodySourceMap},
     ids::DefWithBodyId,
-    resolver_for_scope, Body, ExprId, ExprScopes, HirDatabase, InFile, InferenceResult, Resolver,
-    Ty,
+    method_resolution::lookup_method,
+    name::AsName,
+    resolve::ResolveValueResult,
+    resolver_for_scope,
+    semantics::{Impl, Local, PathResolution},
+    Body, ExprId, ExprScopes, Field, Function, HirDatabase, InFile, InferenceResult, PatId, Path,
+    Resolver, Struct, Ty, TypeAlias, TypeNs, ValueNs,
 };
 
 /// A `SourceAnalyzer` is a wrapper which exposes the HIR API in terms of the
@@ -73,6 +78,134 @@ impl SourceAnalyzer {
         Some(self.infer.as_ref()?[expr_id].clone())
     }
 
+    /// Returns the field that is accessed by the specified field expression.
+    pub(crate) fn resolve_field(
+        &self,
+        db: &dyn HirDatabase,
+        field: &ast::FieldExpr,
+    ) -> Option<Field> {
+        let receiver_ty = self.type_of_expr(db, &field.expr()?)?;
+        let name = field.field_access()?.as_name();
+        receiver_ty.as_struct()?.field(db, &name)
+    }
+
+    /// Returns the field that is initialized by the specified field of a record
+    /// literal. If the field uses the shorthand syntax (e.g. `Foo { a }`), the
+    /// local that is used to initialize the field is also returned.
+    pub(crate) fn resolve_record_field(
+        &self,
+        db: &dyn HirDatabase,
+        field: &ast::RecordField,
+    ) -> Option<(Field, Option<Local>)> {
+        let record_lit = field.syntax().ancestors().find_map(ast::RecordLit::cast)?;
+        let strukt = self.type_of_expr(db, &record_lit.into())?.as_struct()?;
+        let name_ref = field.name_ref()?;
+        let field_def = strukt.field(db, &name_ref.as_name())?;
+
+        let local = if field.expr().is_none() {
+            let path = Path::from_name_ref(&name_ref);
+            match self
+                .resolver
+                .resolve_path_as_value_fully(db.upcast(), &path)
+            {
+                Some((ValueNs::LocalBinding(pat_id), _)) => self.local(pat_id),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        Some((field_def, local))
+    }
+
+    /// Resolves the specified path to the item it refers to.
+    ///
+    /// Paths of expressions are resolved in the value namespace first, all
+    /// other paths are resolved in the type namespace. Prefixes of paths (e.g.
+    /// `foo` in `foo::bar`) and paths of `use` declarations are resolved
+    /// relative to the module of the path.
+    pub(crate) fn resolve_path(
+        &self,
+        db: &dyn HirDatabase,
+        path: &ast::Path,
+    ) -> Option<PathResolution> {
+        let hir_path = Path::from_ast(path.clone())?;
+        let parent = path.syntax().parent()?;
+
+        if ast::PathExpr::can_cast(parent.kind()) {
+            match self.resolver.resolve_path_as_value(db.upcast(), &hir_path) {
+                Some(ResolveValueResult::ValueNs(value, _)) => {
+                    return self.resolve_value(value);
+                }
+                Some(ResolveValueResult::Partial(ty, idx))
+                    if idx + 1 == hir_path.segments.len() =>
+                {
+                    return self.resolve_associated_function(db, ty, &hir_path);
+                }
+                _ => {}
+            }
+        }
+
+        // The qualifier of a path, e.g. `Self` in `Self::new`, can also refer to a type
+        if ast::PathExpr::can_cast(parent.kind())
+            || ast::PathType::can_cast(parent.kind())
+            || ast::Path::can_cast(parent.kind())
+        {
+            if let Some((ty, _)) = self
+                .resolver
+                .resolve_path_as_type_fully(db.upcast(), &hir_path)
+            {
+                return Some(resolve_type(ty));
+            }
+        }
+
+        let per_ns = self.resolver.resolve_module_path(db.upcast(), &hir_path);
+        per_ns
+            .take_types()
+            .or_else(|| per_ns.take_values())
+            .map(|(def, _)| PathResolution::Def(def.into()))
+    }
+
+    /// Converts a resolved value into a `PathResolution`.
+    fn resolve_value(&self, value: ValueNs) -> Option<PathResolution> {
+        let res = match value {
+            ValueNs::ImplSelf(id) => PathResolution::SelfType(Impl { id }),
+            ValueNs::LocalBinding(pat_id) => PathResolution::Local(self.local(pat_id)?),
+            ValueNs::FunctionId(id) => PathResolution::Def(Function::from(id).into()),
+            ValueNs::StructId(id) => PathResolution::Def(Struct::from(id).into()),
+        };
+        Some(res)
+    }
+
+    /// Resolves the last segment of `path` as a function that is associated
+    /// with the type `ty`, e.g. `Foo::new`.
+    fn resolve_associated_function(
+        &self,
+        db: &dyn HirDatabase,
+        ty: TypeNs,
+        path: &Path,
+    ) -> Option<PathResolution> {
+        let ty = match ty {
+            TypeNs::SelfType(id) => db.type_for_impl_self(id),
+            TypeNs::StructId(id) => Struct::from(id).ty(db),
+            TypeNs::TypeAliasId(id) => TypeAlias::from(id).target_type(db),
+            TypeNs::PrimitiveType(_) => return None,
+        };
+        let name = path.last_segment()?;
+        let module = self.resolver.module()?;
+        let function = match lookup_method(db, &ty, module, name) {
+            Ok(function) | Err(Some(function)) => function,
+            Err(None) => return None,
+        };
+        Some(PathResolution::Def(Function::from(function).into()))
+    }
+
+    /// Returns the local with the given `pat_id` in the body of this analyzer.
+    fn local(&self, pat_id: PatId) -> Option<Local> {
+        let parent = self.resolver.body_owner()?;
+        Some(Local { parent, pat_id })
+    }
+
     /// Returns the expression id of the given expression or None if it could
     /// not be found.
     fn expr_id(&self, _db: &dyn HirDatabase, expr: &ast::Expr) -> Option<ExprId> {
@@ -81,6 +214,16 @@ impl SourceAnalyzer {
     }
 }
 
+/// Converts a resolved type into a `PathResolution`.
+fn resolve_type(ty: TypeNs) -> PathResolution {
+    match ty {
+        TypeNs::SelfType(id) => PathResolution::SelfType(Impl { id }),
+        TypeNs::StructId(id) => PathResolution::Def(Struct::from(id).into()),
+        TypeNs::TypeAliasId(id) => PathResolution::Def(TypeAlias::from(id).into()),
+        TypeNs::PrimitiveType(id) => PathResolution::Def(id.into()),
+    }
+}
+
 /// Returns the id of the scope that is active at the location of `node`.
 fn scope_for(
     scopes: &ExprScopes,
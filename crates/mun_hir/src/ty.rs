@@ -70,6 +70,10 @@ pub enum TyKind {
     /// An dynamically sized array type
     Array(Ty),
 
+    /// The built-in `Result<T, E>` type, which is either an `Ok(T)` or an
+    /// `Err(E)`.
+    Result(Ty, Ty),
+
     /// A placeholder for a type which could not be computed; this is propagated
     /// to avoid useless error messages. Doubles as a placeholder where type
     /// variables are inserted before type checking, since we want to try to
@@ -160,6 +164,15 @@ impl Ty {
         }
     }
 
+    /// If this type represents a `Result` type, returns references to the
+    /// `Ok` and `Err` types.
+    pub fn as_result(&self) -> Option<(&Ty, &Ty)> {
+        match self.interned() {
+            TyKind::Result(ok, err) => Some((ok, err)),
+            _ => None,
+        }
+    }
+
     /// Returns true if this type represents the empty tuple type
     pub fn is_empty(&self) -> bool {
         matches!(self.interned(), TyKind::Tuple(0, _))
@@ -195,7 +208,9 @@ impl Ty {
             &TyKind::Struct(s) => {
                 let name = s.name(db).to_string();
 
-                Some(if s.data(db.upcast()).memory_kind == StructMemoryKind::Gc {
+                Some(if s.is_extern(db) {
+                    format!("extern struct {name}")
+                } else if s.data(db.upcast()).memory_kind == StructMemoryKind::Gc {
                     format!("struct {name}")
                 } else {
                     let fields: Vec<String> = s
@@ -210,17 +225,27 @@ impl Ty {
                         })
                         .collect();
 
+                    let repr = s.data(db.upcast()).repr;
                     format!(
-                        "struct {name}{{{fields}}}",
+                        "{repr}struct {name}{{{fields}}}",
+                        repr = repr
+                            .attribute()
+                            .map_or(String::new(), |attr| format!("{attr} ")),
                         name = name,
                         fields = fields.join(",")
                     )
                 })
             }
+            TyKind::Tuple(0, _) => Some("core::empty".to_string()),
             TyKind::Bool => Some("core::bool".to_string()),
             TyKind::Float(ty) => Some(format!("core::{}", ty.as_str())),
             TyKind::Int(ty) => Some(format!("core::{}", ty.as_str())),
             TyKind::Array(ty) => Some(format!("[{}]", ty.display(db))),
+            TyKind::Result(ok, err) => Some(format!(
+                "Result<{}, {}>",
+                ok.guid_string(db)?,
+                err.guid_string(db)?
+            )),
             _ => None,
         }
     }
@@ -260,7 +285,9 @@ impl Ty {
         match (self.interned(), other.interned()) {
             (TyKind::Struct(s1), TyKind::Struct(s2)) => s1 == s2,
             (TyKind::Tuple(_, substs1), TyKind::Tuple(_, substs2)) => substs1 == substs2,
-            (TyKind::Array(_), TyKind::Array(_)) | (TyKind::Bool, TyKind::Bool) => true,
+            (TyKind::Array(_), TyKind::Array(_))
+            | (TyKind::Result(..), TyKind::Result(..))
+            | (TyKind::Bool, TyKind::Bool) => true,
             (TyKind::Float(f1), TyKind::Float(f2)) => f1 == f2,
             (TyKind::Int(i1), TyKind::Int(i2)) => i1 == i2,
             (TyKind::FnDef(def, _), TyKind::FnDef(def2, _)) => def == def2,
@@ -354,8 +381,12 @@ impl FnSig {
 
     pub fn marshallable(&self, db: &dyn HirDatabase) -> bool {
         for ty in self.params_and_return.iter() {
+            // Results are passed by value, like value structs
+            if ty.as_result().is_some() {
+                return false;
+            }
             if let Some(s) = ty.as_struct() {
-                if s.data(db.upcast()).memory_kind == StructMemoryKind::Value {
+                if !s.is_extern(db) && s.data(db.upcast()).memory_kind == StructMemoryKind::Value {
                     return false;
                 }
             }
@@ -403,6 +434,9 @@ impl HirDisplay for Ty {
                 write!(f, ") -> {}", sig.ret().display(f.db))
             }
             TyKind::Array(elem_ty) => write!(f, "[{}]", elem_ty.display(f.db)),
+            TyKind::Result(ok, err) => {
+                write!(f, "Result<{}, {}>", ok.display(f.db), err.display(f.db))
+            }
             TyKind::Unknown => write!(f, "{{unknown}}"),
         }
     }
@@ -441,6 +475,10 @@ impl TypeWalk for Ty {
     fn walk(&self, f: &mut impl FnMut(&Ty)) {
         match self.interned() {
             TyKind::Array(elem_ty) => f(elem_ty),
+            TyKind::Result(ok, err) => {
+                ok.walk(f);
+                err.walk(f);
+            }
             _ => {
                 if let Some(substs) = self.type_parameters() {
                     substs.walk(f);
@@ -453,6 +491,10 @@ impl TypeWalk for Ty {
     fn walk_mut(&mut self, f: &mut impl FnMut(&mut Ty)) {
         match self.interned_mut() {
             TyKind::Array(elem_ty) => f(elem_ty),
+            TyKind::Result(ok, err) => {
+                ok.walk_mut(f);
+                err.walk_mut(f);
+            }
             _ => {
                 if let Some(substs) = self.type_parameters_mut() {
                     substs.walk_mut(f);
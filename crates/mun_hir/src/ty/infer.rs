@@ -1,23 +1,27 @@
 use std::{ops::Index, sync::Arc};
 
 use la_arena::ArenaMap;
+use mun_db::CheckCanceled;
 use rustc_hash::FxHashSet;
 
 use crate::{
-    code_model::{Struct, StructKind},
+    code_model::{Field, Struct, StructKind},
     diagnostics::DiagnosticSink,
-    expr::{Body, Expr, ExprId, Literal, Pat, PatId, RecordLitField, Statement, UnaryOp},
+    expr::{
+        Body, Expr, ExprId, Literal, Pat, PatId, RecordLitField, ResultVariant, Statement, UnaryOp,
+    },
     name_resolution::Namespace,
     resolve::{Resolver, TypeNs, ValueNs},
     ty::{
         infer::{diagnostics::InferenceDiagnostic, type_variable::TypeVariableTable},
-        lower::LowerDiagnostic,
+        lower::{CallableDef, LowerDiagnostic},
         op, Ty, TypableDef,
     },
     type_ref::LocalTypeRefId,
-    BinaryOp, Function, HirDatabase, Name, Path,
+    BinaryOp, Function, HasVisibility, HirDatabase, Name, Path,
 };
 
+mod cast;
 mod place_expr;
 mod type_variable;
 mod unify;
@@ -102,7 +106,10 @@ pub fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<InferenceRes
     let mut ctx = InferenceResultBuilder::new(db, &body, resolver);
 
     match def {
-        DefWithBodyId::FunctionId(_) => ctx.infer_signature(),
+        DefWithBodyId::FunctionId(id) => {
+            ctx.is_resumable = db.fn_data(id).is_resumable();
+            ctx.infer_signature();
+        }
     }
 
     ctx.infer_body();
@@ -165,6 +172,9 @@ struct InferenceResultBuilder<'a> {
 
     /// The return type of the function being inferred.
     return_ty: Ty,
+
+    /// Whether the function being inferred is resumable.
+    is_resumable: bool,
 }
 
 impl<'a> InferenceResultBuilder<'a> {
@@ -181,6 +191,7 @@ impl<'a> InferenceResultBuilder<'a> {
             body,
             resolver,
             return_ty: TyKind::Unknown.intern(), // set in collect_fn_signature
+            is_resumable: false,                 // set in collect_fn_signature
         }
     }
 
@@ -325,6 +336,7 @@ impl<'a> InferenceResultBuilder<'a> {
                 then_branch,
                 else_branch,
             } => self.infer_if(tgt_expr, expected, *condition, *then_branch, *else_branch),
+            #[allow(clippy::single_match_else)]
             Expr::BinaryOp { lhs, rhs, op } => match op {
                 Some(op) => {
                     let lhs_expected = match op {
@@ -354,7 +366,12 @@ impl<'a> InferenceResultBuilder<'a> {
                     let rhs_ty = self.infer_expr(*rhs, &Expectation::has_type(rhs_expected));
                     op::binary_op_return_ty(*op, rhs_ty)
                 }
-                _ => error_type(),
+                None => {
+                    // Still infer the operands
+                    self.infer_expr(*lhs, &Expectation::none());
+                    self.infer_expr(*rhs, &Expectation::none());
+                    error_type()
+                }
             },
             Expr::Block { statements, tail } => self.infer_block(statements, *tail, expected),
             Expr::Call { callee: call, args } => self.infer_call(tgt_expr, *call, args, expected),
@@ -413,8 +430,9 @@ impl<'a> InferenceResultBuilder<'a> {
                     let field_ty = def_id
                         .as_ref()
                         .and_then(|it| {
-                            if let Some(field) = it.field(self.db, &field.name) {
-                                Some(field)
+                            if let Some(struct_field) = it.field(self.db, &field.name) {
+                                self.check_field_visibility(tgt_expr, Some(idx), struct_field);
+                                Some(struct_field)
                             } else {
                                 self.diagnostics.push(InferenceDiagnostic::NoSuchField {
                                     id: tgt_expr,
@@ -430,6 +448,12 @@ impl<'a> InferenceResultBuilder<'a> {
                     self.infer_expr(*expr, &Expectation::has_type(ty.clone()));
                 }
                 if let Some(s) = ty.as_struct() {
+                    if s.is_extern(self.db) {
+                        self.diagnostics
+                            .push(InferenceDiagnostic::CannotConstructExternStruct {
+                                id: tgt_expr,
+                            });
+                    }
                     self.check_record_lit(tgt_expr, &ty, s, fields);
                 }
                 ty
@@ -438,21 +462,24 @@ impl<'a> InferenceResultBuilder<'a> {
                 let receiver_ty = self.infer_expr(*expr, &Expectation::none());
                 #[allow(clippy::single_match_else)]
                 match receiver_ty.interned() {
-                    TyKind::Struct(s) => {
-                        match s.field(self.db, name).map(|field| field.ty(self.db)) {
-                            Some(field_ty) => field_ty,
-                            None => {
-                                self.diagnostics
-                                    .push(InferenceDiagnostic::AccessUnknownField {
-                                        id: tgt_expr,
-                                        receiver_ty,
-                                        name: name.clone(),
-                                    });
-
-                                error_type()
-                            }
+                    TyKind::Struct(s) => match s.field(self.db, name) {
+                        Some(field) => {
+                            self.check_field_visibility(tgt_expr, None, field);
+                            field.ty(self.db)
                         }
-                    }
+                        None => {
+                            self.diagnostics
+                                .push(InferenceDiagnostic::AccessUnknownField {
+                                    id: tgt_expr,
+                                    receiver_ty,
+                                    name: name.clone(),
+                                });
+
+                            error_type()
+                        }
+                    },
+                    // An error has already been emitted for the receiver
+                    TyKind::Unknown => error_type(),
                     _ => {
                         self.diagnostics.push(InferenceDiagnostic::NoFields {
                             id: *expr,
@@ -470,6 +497,7 @@ impl<'a> InferenceResultBuilder<'a> {
                         TyKind::Bool | TyKind::Int(_) | TyKind::InferenceVar(InferTy::Int(_)) => {
                             inner_ty
                         }
+                        TyKind::Unknown => error_type(),
                         _ => {
                             self.diagnostics
                                 .push(InferenceDiagnostic::CannotApplyUnaryOp {
@@ -483,6 +511,7 @@ impl<'a> InferenceResultBuilder<'a> {
                         TyKind::Float(_)
                         | TyKind::Int(_)
                         | TyKind::InferenceVar(InferTy::Int(_) | InferTy::Float(_)) => inner_ty,
+                        TyKind::Unknown => error_type(),
                         _ => {
                             self.diagnostics
                                 .push(InferenceDiagnostic::CannotApplyUnaryOp {
@@ -506,6 +535,35 @@ impl<'a> InferenceResultBuilder<'a> {
 
                 TyKind::Array(elem_ty).intern()
             }
+            Expr::Log { args, .. } => {
+                for arg in args {
+                    let arg_ty = self.infer_expr(*arg, &Expectation::none());
+                    let is_formattable = matches!(
+                        arg_ty.interned(),
+                        TyKind::Bool
+                            | TyKind::Int(_)
+                            | TyKind::Float(_)
+                            | TyKind::InferenceVar(InferTy::Int(_) | InferTy::Float(_))
+                            | TyKind::Never
+                            | TyKind::Unknown
+                    );
+                    if !is_formattable {
+                        self.diagnostics.push(InferenceDiagnostic::CannotFormat {
+                            id: *arg,
+                            found: arg_ty,
+                        });
+                    }
+                }
+                Ty::unit()
+            }
+            Expr::ArrayLength { array } => {
+                // A mismatch is not reported here, because the desugared `for` loop that
+                // introduces this expression also indexes the array, which reports it.
+                let array_ty = self.infer_expr(*array, &Expectation::none());
+                let elem_ty = self.type_variables.new_type_var();
+                self.unify(&array_ty, &TyKind::Array(elem_ty).intern());
+                TyKind::Int(IntTy::usize()).intern()
+            }
             Expr::Index { base, index } => {
                 let elem_ty = if expected.ty.is_unknown() {
                     self.type_variables.new_type_var()
@@ -526,6 +584,39 @@ impl<'a> InferenceResultBuilder<'a> {
                     _ => error_type(),
                 }
             }
+            Expr::Cast { expr, type_ref } => {
+                let cast_ty = self.resolve_type(*type_ref);
+                let expr_ty = self.infer_expr(*expr, &Expectation::none());
+                self.check_cast(tgt_expr, &expr_ty, &cast_ty);
+                cast_ty
+            }
+            Expr::ResultCtor { variant, expr } => {
+                let (ok_ty, err_ty) = match expected.ty.as_result() {
+                    Some((ok_ty, err_ty)) => (ok_ty.clone(), err_ty.clone()),
+                    None => (
+                        self.type_variables.new_type_var(),
+                        self.type_variables.new_type_var(),
+                    ),
+                };
+                match variant {
+                    ResultVariant::Ok => {
+                        let ok_ty = self.infer_expr_coerce(*expr, &Expectation::has_type(ok_ty));
+                        TyKind::Result(ok_ty, err_ty).intern()
+                    }
+                    ResultVariant::Err => {
+                        let err_ty = self.infer_expr_coerce(*expr, &Expectation::has_type(err_ty));
+                        TyKind::Result(ok_ty, err_ty).intern()
+                    }
+                }
+            }
+            Expr::Try { expr } => self.infer_try(tgt_expr, *expr),
+            Expr::Yield => {
+                if !self.is_resumable {
+                    self.diagnostics
+                        .push(InferenceDiagnostic::YieldOutsideResumableFn { id: tgt_expr });
+                }
+                TyKind::Float(FloatTy::f32()).intern()
+            }
         };
 
         let ty = self.resolve_ty_as_far_as_possible(ty);
@@ -569,6 +660,48 @@ impl<'a> InferenceResultBuilder<'a> {
         }
     }
 
+    /// Inferences the type of a `?` expression. The operand must be a `Result`
+    /// whose error type matches the error type of the enclosing function.
+    fn infer_try(&mut self, tgt_expr: ExprId, expr: ExprId) -> Ty {
+        let ok_ty = self.type_variables.new_type_var();
+        let err_ty = self.type_variables.new_type_var();
+        let operand_ty = self.infer_expr(expr, &Expectation::none());
+        if !self.unify(
+            &operand_ty,
+            &TyKind::Result(ok_ty.clone(), err_ty.clone()).intern(),
+        ) {
+            if operand_ty.is_known() {
+                self.diagnostics
+                    .push(InferenceDiagnostic::InvalidTryOperand {
+                        id: expr,
+                        found: operand_ty,
+                    });
+            }
+            return error_type();
+        }
+
+        match self.return_ty.as_result() {
+            Some((_, ret_err_ty)) => {
+                let ret_err_ty = ret_err_ty.clone();
+                if !self.unify(&err_ty, &ret_err_ty) {
+                    let found = self.resolve_ty_as_far_as_possible(operand_ty);
+                    self.diagnostics.push(InferenceDiagnostic::MismatchedTypes {
+                        id: expr,
+                        expected: TyKind::Result(ok_ty.clone(), ret_err_ty).intern(),
+                        found,
+                    });
+                }
+            }
+            None if self.return_ty.is_known() => {
+                self.diagnostics
+                    .push(InferenceDiagnostic::TryOutsideResultFn { id: tgt_expr });
+            }
+            None => {}
+        }
+
+        ok_ty
+    }
+
     /// Inferences the type of a call expression.
     fn infer_call(
         &mut self,
@@ -600,14 +733,19 @@ impl<'a> InferenceResultBuilder<'a> {
                 }
 
                 // Still derive subtypes
-                for arg in args.iter() {
-                    self.infer_expr(*arg, &Expectation::none());
-                }
+                self.infer_args_without_params(args);
 
                 callee_ty
             }
             TyKind::FnDef(def, _substs) => {
                 // Found either a tuple struct literal or function
+                if let CallableDef::Function(function) = def {
+                    if function.is_resumable(self.db) && !self.is_resumable {
+                        self.diagnostics.push(
+                            InferenceDiagnostic::ResumableCallOutsideResumableFn { id: tgt_expr },
+                        );
+                    }
+                }
                 let sig = callee_ty.callable_sig(self.db).unwrap();
                 let (param_tys, ret_ty) = (sig.params().to_vec(), sig.ret().clone());
                 self.check_call_argument_count(
@@ -619,11 +757,15 @@ impl<'a> InferenceResultBuilder<'a> {
                 for (&arg, param_ty) in args.iter().zip(param_tys.iter()) {
                     self.infer_expr_coerce(arg, &Expectation::has_type(param_ty.clone()));
                 }
+                if args.len() > param_tys.len() {
+                    self.infer_args_without_params(&args[param_tys.len()..]);
+                }
 
                 ret_ty
             }
             TyKind::Unknown => {
                 // Error has already been emitted somewhere else
+                self.infer_args_without_params(args);
                 error_type()
             }
             _ => {
@@ -632,14 +774,27 @@ impl<'a> InferenceResultBuilder<'a> {
                         id: callee,
                         found: callee_ty,
                     });
+                self.infer_args_without_params(args);
                 error_type()
             }
         }
     }
 
+    /// Infers the types of the arguments of an erroneous call for which no
+    /// parameter types are known, so the arguments can still be analyzed.
+    fn infer_args_without_params(&mut self, args: &[ExprId]) {
+        for arg in args.iter() {
+            self.infer_expr(*arg, &Expectation::none());
+        }
+    }
+
     /// Checks whether the specified struct type is a unit struct.
     fn check_unit_struct_lit(&mut self, tgt_expr: ExprId, expected: Struct) {
         let struct_data = expected.data(self.db.upcast());
+        if struct_data.is_extern {
+            self.diagnostics
+                .push(InferenceDiagnostic::CannotConstructExternStruct { id: tgt_expr });
+        }
         if struct_data.kind != StructKind::Unit {
             self.diagnostics
                 .push(InferenceDiagnostic::MismatchedStructLit {
@@ -695,13 +850,15 @@ impl<'a> InferenceResultBuilder<'a> {
             return;
         }
 
+        // Fields that have a default value can be omitted, as can fields without a
+        // name, which cannot be initialized
         let lit_fields: FxHashSet<_> = fields.iter().map(|f| &f.name).collect();
         let missed_fields: Vec<Name> = struct_data
             .fields
             .iter()
             .filter_map(|(_f, d)| {
                 let name = d.name.clone();
-                if lit_fields.contains(&name) {
+                if lit_fields.contains(&name) || d.default.is_some() || name.is_hidden() {
                     None
                 } else {
                     Some(name)
@@ -718,6 +875,23 @@ impl<'a> InferenceResultBuilder<'a> {
         }
     }
 
+    /// Reports a diagnostic if the `field` that is accessed or initialized by
+    /// the expression `id` is not visible from the current module. `field_idx`
+    /// is the index of the field in a record literal, if any.
+    fn check_field_visibility(&mut self, id: ExprId, field_idx: Option<usize>, field: Field) {
+        let module = self
+            .resolver
+            .module()
+            .expect("resolver must have a module to be able to resolve modules");
+        if !field.visibility(self.db).is_visible_from(self.db, module) {
+            self.diagnostics.push(InferenceDiagnostic::FieldIsPrivate {
+                id,
+                field: field_idx,
+                name: field.name(self.db),
+            });
+        }
+    }
+
     fn infer_path_expr(
         &mut self,
         resolver: &Resolver,
@@ -806,21 +980,33 @@ impl<'a> InferenceResultBuilder<'a> {
     fn resolve_all(mut self) -> InferenceResult {
         // FIXME resolve obligations as well (use Guidance if necessary)
         //let mut tv_stack = Vec::new();
-        let mut expr_types = std::mem::take(&mut self.type_of_expr);
-        for (expr, ty) in expr_types.iter_mut() {
+
+        // Types that could not be inferred are most likely caused by other errors in
+        // the body, in which case they are not reported.
+        let mut report_failures = self.diagnostics.is_empty();
+
+        let mut pat_types = std::mem::take(&mut self.type_of_pat);
+        for (pat, ty) in pat_types.iter_mut() {
             let was_unknown = ty.is_unknown();
             let resolved = self.type_variables.resolve_ty_completely(ty.clone());
-            if !was_unknown && resolved.is_unknown() {
-                self.report_expr_inference_failure(expr);
+            if !was_unknown && resolved.is_unknown() && report_failures {
+                self.diagnostics
+                    .push(InferenceDiagnostic::TypeAnnotationsNeeded { id: pat.into() });
             }
             *ty = resolved;
         }
-        let mut pat_types = std::mem::take(&mut self.type_of_pat);
-        for (pat, ty) in pat_types.iter_mut() {
+
+        // Only report a single expression if none of the patterns could be inferred,
+        // the other expressions most likely depend on it.
+        report_failures &= self.diagnostics.is_empty();
+        let mut expr_types = std::mem::take(&mut self.type_of_expr);
+        for (expr, ty) in expr_types.iter_mut() {
             let was_unknown = ty.is_unknown();
             let resolved = self.type_variables.resolve_ty_completely(ty.clone());
-            if !was_unknown && resolved.is_unknown() {
-                self.report_pat_inference_failure(pat);
+            if !was_unknown && resolved.is_unknown() && report_failures {
+                self.diagnostics
+                    .push(InferenceDiagnostic::TypeAnnotationsNeeded { id: expr.into() });
+                report_failures = false;
             }
             *ty = resolved;
         }
@@ -844,6 +1030,8 @@ impl<'a> InferenceResultBuilder<'a> {
     ) -> Ty {
         let mut diverges = false;
         for stmt in statements {
+            // Inferring a large body can take a while, abort early if the input changed
+            self.db.unwind_if_canceled();
             match stmt {
                 Statement::Let {
                     pat,
@@ -895,15 +1083,19 @@ impl<'a> InferenceResultBuilder<'a> {
         let expected = match &self.active_loop {
             Some(ActiveLoop::Loop(_, info)) => info.clone(),
             Some(_) => {
-                if expr.is_some() {
+                if let Some(expr) = expr {
                     self.diagnostics
                         .push(InferenceDiagnostic::BreakWithValueOutsideLoop { id: tgt_expr });
+                    self.infer_expr(expr, &Expectation::none());
                 }
                 return TyKind::Never.intern();
             }
             None => {
                 self.diagnostics
                     .push(InferenceDiagnostic::BreakOutsideLoop { id: tgt_expr });
+                if let Some(expr) = expr {
+                    self.infer_expr(expr, &Expectation::none());
+                }
                 return TyKind::Never.intern();
             }
         };
@@ -965,26 +1157,6 @@ impl<'a> InferenceResultBuilder<'a> {
         self.infer_loop_block(body, ActiveLoop::While);
         Ty::unit()
     }
-
-    #[allow(clippy::unused_self)]
-    pub fn report_pat_inference_failure(&mut self, _pat: PatId) {
-        //        self.diagnostics.push(InferenceDiagnostic::PatInferenceFailed {
-        //            pat
-        //        });
-        // Currently this should never happen because we can only infer integer and
-        // floating-point types which always have a fallback value.
-        panic!("pattern failed inferencing");
-    }
-
-    #[allow(clippy::unused_self)]
-    pub fn report_expr_inference_failure(&mut self, _expr: ExprId) {
-        //        self.diagnostics.push(InferenceDiagnostic::ExprInferenceFailed {
-        //            expr
-        //        });
-        // Currently this should never happen because we can only infer integer and
-        // floating-point types which always have a fallback value.
-        panic!("expression failed inferencing");
-    }
 }
 
 /// Returns a type used for errors
@@ -1054,18 +1226,23 @@ impl From<PatId> for ExprOrPatId {
 }
 
 mod diagnostics {
+    use mun_syntax::AstPtr;
+
     use crate::{
         code_model::{src::HasSource, StructKind},
         diagnostics::{
             AccessUnknownField, BreakOutsideLoop, BreakWithValueOutsideLoop, CannotApplyBinaryOp,
-            CannotApplyUnaryOp, CyclicType, DiagnosticSink, ExpectedFunction, FieldCountMismatch,
-            IncompatibleBranch, InvalidLhs, LiteralOutOfRange, MismatchedStructLit, MismatchedType,
+            CannotApplyUnaryOp, CannotConstructExternStruct, CannotFormat, CyclicType,
+            DiagnosticSink, ExpectedFunction, FieldCountMismatch, IncompatibleBranch, InvalidCast,
+            InvalidLhs, InvalidTryOperand, LiteralOutOfRange, MismatchedStructLit, MismatchedType,
             MissingElseBranch, MissingFields, NoFields, NoSuchField, ParameterCountMismatch,
-            PrivateAccess, ReturnMissingExpression, UnresolvedType, UnresolvedValue,
+            PrivateAccess, PrivateField, ResumableCallOutsideResumableFn, ReturnMissingExpression,
+            TryOutsideResultFn, TypeAnnotationsNeeded, UnresolvedType, UnresolvedValue,
+            YieldOutsideResumableFn,
         },
         ty::infer::ExprOrPatId,
         type_ref::LocalTypeRefId,
-        ExprId, Function, HirDatabase, IntTy, Name, Ty,
+        ExprId, Function, HirDatabase, InFile, IntTy, Name, Ty,
     };
 
     #[derive(Debug, PartialEq, Eq, Clone)]
@@ -1162,6 +1339,41 @@ mod diagnostics {
         PathIsPrivate {
             id: ExprId,
         },
+        /// A private field is accessed, or initialized by the field with index
+        /// `field` of a record literal
+        FieldIsPrivate {
+            id: ExprId,
+            field: Option<usize>,
+            name: Name,
+        },
+        CannotConstructExternStruct {
+            id: ExprId,
+        },
+        TypeAnnotationsNeeded {
+            id: ExprOrPatId,
+        },
+        InvalidCast {
+            id: ExprId,
+            from: Ty,
+            to: Ty,
+        },
+        InvalidTryOperand {
+            id: ExprId,
+            found: Ty,
+        },
+        TryOutsideResultFn {
+            id: ExprId,
+        },
+        YieldOutsideResumableFn {
+            id: ExprId,
+        },
+        ResumableCallOutsideResumableFn {
+            id: ExprId,
+        },
+        CannotFormat {
+            id: ExprId,
+            found: Ty,
+        },
     }
 
     impl InferenceDiagnostic {
@@ -1173,60 +1385,149 @@ mod diagnostics {
         ) {
             let file = owner.source(db.upcast()).file_id;
             let body = owner.body_source_map(db);
+
+            // Expressions and patterns that are missing from the source, e.g. because of a
+            // syntax error, have no syntax to attach a diagnostic to. The
+            // syntax error is already reported, so these diagnostics are
+            // skipped.
+            let expr_syntax = |id: ExprId| {
+                body.expr_syntax(id).map(|ptr| {
+                    ptr.value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+                })
+            };
+            let expr_or_pat_syntax = |id: ExprOrPatId| match id {
+                ExprOrPatId::ExprId(id) => expr_syntax(id),
+                ExprOrPatId::PatId(id) => body.pat_syntax(id).map(|ptr| {
+                    ptr.value
+                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
+                }),
+            };
             match self {
                 InferenceDiagnostic::UnresolvedValue { id } => {
-                    let expr = match id {
-                        ExprOrPatId::ExprId(id) => body.expr_syntax(*id).map(|ptr| {
-                            ptr.value
-                                .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
-                        }),
-                        ExprOrPatId::PatId(id) => body.pat_syntax(*id).map(|ptr| {
-                            ptr.value
-                                .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
-                        }),
-                    }
-                    .unwrap();
+                    let Some(expr) = expr_or_pat_syntax(*id) else {
+                        return;
+                    };
 
                     sink.push(UnresolvedValue { file, expr });
                 }
                 InferenceDiagnostic::UnresolvedType { id } => {
-                    let type_ref = body.type_ref_syntax(*id).expect("If this is not found, it must be a type ref generated by the library which should never be unresolved.");
+                    // Type refs generated by the library, e.g. the type of a `self` parameter, have
+                    // no syntax. They can only fail to resolve if the source is invalid, which is
+                    // already reported as a syntax error.
+                    let Some(type_ref) = body.type_ref_syntax(*id) else {
+                        return;
+                    };
                     sink.push(UnresolvedType { file, type_ref });
                 }
                 InferenceDiagnostic::CyclicType { id } => {
-                    let type_ref = body.type_ref_syntax(*id).expect("If this is not found, it must be a type ref generated by the library which should never be unresolved.");
+                    let Some(type_ref) = body.type_ref_syntax(*id) else {
+                        return;
+                    };
                     sink.push(CyclicType { file, type_ref });
                 }
                 InferenceDiagnostic::TypeIsPrivate { id } => {
-                    let type_ref = body.type_ref_syntax(*id).expect("If this is not found, it must be a type ref generated by the library which should never be unresolved.");
+                    let Some(type_ref) = body.type_ref_syntax(*id) else {
+                        return;
+                    };
                     sink.push(PrivateAccess {
                         file,
                         expr: type_ref.syntax_node_ptr(),
                     });
                 }
                 InferenceDiagnostic::PathIsPrivate { id } => {
-                    let expr_syntax = body
-                        .expr_syntax(*id)
-                        .map(|ptr| {
-                            ptr.value
-                                .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr())
-                        })
-                        .expect("could not resolve expression to syntax node");
+                    let Some(expr_syntax) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(PrivateAccess {
                         file,
                         expr: expr_syntax,
                     });
                 }
+                InferenceDiagnostic::FieldIsPrivate { id, field, name } => {
+                    let field_syntax = if let Some(field) = field {
+                        owner.body_source_map(db).field_syntax(*id, *field).into()
+                    } else if let Some(expr) = expr_syntax(*id) {
+                        expr
+                    } else {
+                        return;
+                    };
+                    sink.push(PrivateField {
+                        file,
+                        field: field_syntax,
+                        name: name.clone(),
+                    });
+                }
+                InferenceDiagnostic::CannotConstructExternStruct { id } => {
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
+                    sink.push(CannotConstructExternStruct { file, expr });
+                }
+                InferenceDiagnostic::TypeAnnotationsNeeded { id } => {
+                    let Some(node) = expr_or_pat_syntax(*id) else {
+                        return;
+                    };
+
+                    sink.push(TypeAnnotationsNeeded { file, node });
+                }
+                InferenceDiagnostic::InvalidCast { id, from, to } => {
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
+                    sink.push(InvalidCast {
+                        file,
+                        expr,
+                        from: from.clone(),
+                        to: to.clone(),
+                    });
+                }
+                InferenceDiagnostic::InvalidTryOperand { id, found } => {
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
+                    sink.push(InvalidTryOperand {
+                        file,
+                        expr,
+                        found: found.clone(),
+                    });
+                }
+                InferenceDiagnostic::TryOutsideResultFn { id } => {
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
+                    sink.push(TryOutsideResultFn { file, expr });
+                }
+                InferenceDiagnostic::YieldOutsideResumableFn { id } => {
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
+                    sink.push(YieldOutsideResumableFn { file, expr });
+                }
+                InferenceDiagnostic::ResumableCallOutsideResumableFn { id } => {
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
+                    sink.push(ResumableCallOutsideResumableFn { file, expr });
+                }
+                InferenceDiagnostic::CannotFormat { id, found } => {
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
+                    sink.push(CannotFormat {
+                        file,
+                        expr,
+                        found: found.clone(),
+                    });
+                }
                 InferenceDiagnostic::ParameterCountMismatch {
                     id,
                     expected,
                     found,
                 } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(ParameterCountMismatch {
                         file,
                         expr,
@@ -1235,11 +1536,9 @@ mod diagnostics {
                     });
                 }
                 InferenceDiagnostic::ExpectedFunction { id, found } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(ExpectedFunction {
                         file,
                         expr,
@@ -1251,11 +1550,9 @@ mod diagnostics {
                     found,
                     expected,
                 } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(MismatchedType {
                         file,
                         expr,
@@ -1268,11 +1565,9 @@ mod diagnostics {
                     then_ty,
                     else_ty,
                 } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(IncompatibleBranch {
                         file,
                         if_expr: expr,
@@ -1281,11 +1576,9 @@ mod diagnostics {
                     });
                 }
                 InferenceDiagnostic::MissingElseBranch { id, then_ty } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(MissingElseBranch {
                         file,
                         if_expr: expr,
@@ -1293,11 +1586,9 @@ mod diagnostics {
                     });
                 }
                 InferenceDiagnostic::CannotApplyBinaryOp { id, lhs, rhs } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(CannotApplyBinaryOp {
                         file,
                         expr,
@@ -1306,11 +1597,9 @@ mod diagnostics {
                     });
                 }
                 InferenceDiagnostic::CannotApplyUnaryOp { id, ty } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(CannotApplyUnaryOp {
                         file,
                         expr,
@@ -1318,16 +1607,12 @@ mod diagnostics {
                     });
                 }
                 InferenceDiagnostic::InvalidLhs { id, lhs } => {
-                    let id = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
-                    let lhs = body
-                        .expr_syntax(*lhs)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(id) = expr_syntax(*id) else {
+                        return;
+                    };
+                    let Some(lhs) = expr_syntax(*lhs) else {
+                        return;
+                    };
                     sink.push(InvalidLhs {
                         file,
                         expr: id,
@@ -1335,33 +1620,27 @@ mod diagnostics {
                     });
                 }
                 InferenceDiagnostic::ReturnMissingExpression { id } => {
-                    let id = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(id) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(ReturnMissingExpression {
                         file,
                         return_expr: id,
                     });
                 }
                 InferenceDiagnostic::BreakOutsideLoop { id } => {
-                    let id = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(id) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(BreakOutsideLoop {
                         file,
                         break_expr: id,
                     });
                 }
                 InferenceDiagnostic::BreakWithValueOutsideLoop { id } => {
-                    let id = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(id) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(BreakWithValueOutsideLoop {
                         file,
                         break_expr: id,
@@ -1372,11 +1651,9 @@ mod diagnostics {
                     receiver_ty,
                     name,
                 } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(AccessUnknownField {
                         file,
                         expr,
@@ -1389,11 +1666,9 @@ mod diagnostics {
                     expected,
                     found,
                 } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(FieldCountMismatch {
                         file,
                         expr,
@@ -1406,11 +1681,9 @@ mod diagnostics {
                     struct_ty,
                     names,
                 } => {
-                    let fields = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(fields) = expr_syntax(*id) else {
+                        return;
+                    };
 
                     sink.push(MissingFields {
                         file,
@@ -1424,11 +1697,9 @@ mod diagnostics {
                     expected,
                     found,
                 } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(MismatchedStructLit {
                         file,
                         expr,
@@ -1437,11 +1708,9 @@ mod diagnostics {
                     });
                 }
                 InferenceDiagnostic::NoFields { id, found } => {
-                    let expr = body
-                        .expr_syntax(*id)
-                        .unwrap()
-                        .value
-                        .either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                    let Some(expr) = expr_syntax(*id) else {
+                        return;
+                    };
                     sink.push(NoFields {
                         file,
                         receiver_expr: expr,
@@ -1453,16 +1722,13 @@ mod diagnostics {
                     sink.push(NoSuchField { file, field });
                 }
                 InferenceDiagnostic::LiteralOutOfRange { id, literal_ty } => {
-                    let literal = body
-                        .expr_syntax(*id)
-                        .expect("could not retrieve expr from source map")
-                        .map(|expr_src| {
-                            expr_src
-                                .left()
-                                .expect("could not retrieve expr from ExprSource")
-                                .cast()
-                                .expect("could not cast expression to literal")
-                        });
+                    let Some(expr_src) = body.expr_syntax(*id) else {
+                        return;
+                    };
+                    let Some(literal) = expr_src.value.left().and_then(AstPtr::cast) else {
+                        return;
+                    };
+                    let literal = InFile::new(expr_src.file_id, literal);
                     sink.push(LiteralOutOfRange {
                         literal,
                         int_ty: *literal_ty,
@@ -0,0 +1,44 @@
+use super::InferenceResultBuilder;
+use crate::{
+    ty::{
+        infer::{InferTy, InferenceDiagnostic},
+        TyKind,
+    },
+    ExprId, Ty,
+};
+
+impl<'a> InferenceResultBuilder<'a> {
+    /// Checks whether a value of type `from_ty` can be explicitly cast to
+    /// `to_ty` using an `as` expression. Adds a diagnostic message if the
+    /// cast is invalid.
+    ///
+    /// Numeric types can be cast to any other numeric type and booleans can be
+    /// cast to integers. Casting a type to itself is always allowed.
+    pub(super) fn check_cast(&mut self, tgt_expr: ExprId, from_ty: &Ty, to_ty: &Ty) {
+        let is_numeric = |ty: &Ty| {
+            matches!(
+                ty.interned(),
+                TyKind::Int(_)
+                    | TyKind::Float(_)
+                    | TyKind::InferenceVar(InferTy::Int(_) | InferTy::Float(_))
+            )
+        };
+
+        let is_valid = match (from_ty.interned(), to_ty.interned()) {
+            // An error has already been emitted for unresolved types, types that are not yet
+            // known are reported when inference finishes.
+            (TyKind::Unknown | TyKind::InferenceVar(InferTy::Type(_)), _)
+            | (_, TyKind::Unknown)
+            | (TyKind::Bool, TyKind::Int(_)) => true,
+            _ => from_ty == to_ty || (is_numeric(from_ty) && is_numeric(to_ty)),
+        };
+
+        if !is_valid {
+            self.diagnostics.push(InferenceDiagnostic::InvalidCast {
+                id: tgt_expr,
+                from: from_ty.clone(),
+                to: to_ty.clone(),
+            });
+        }
+    }
+}
@@ -140,6 +140,9 @@ impl TypeVariableTable {
             match (a.interned(), b.interned()) {
                 (TyKind::Tuple(_, a), TyKind::Tuple(_, b)) => self.unify_substitutions(db, a, b),
                 (TyKind::Array(t1), TyKind::Array(t2)) => self.unify_inner(db, t1, t2),
+                (TyKind::Result(ok1, err1), TyKind::Result(ok2, err2)) => {
+                    self.unify_inner(db, ok1, ok2) && self.unify_inner(db, err1, err2)
+                }
                 _ => true,
             }
         } else {
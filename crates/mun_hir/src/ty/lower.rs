@@ -9,7 +9,7 @@ pub(crate) use self::diagnostics::LowerDiagnostic;
 use crate::{
     code_model::StructKind,
     diagnostics::DiagnosticSink,
-    ids::ImplId,
+    ids::{ImplId, Lookup},
     name_resolution::Namespace,
     primitive_type::PrimitiveType,
     resolve::{HasResolver, Resolver, TypeNs},
@@ -105,6 +105,13 @@ impl Ty {
                 );
                 Some(TyKind::Array(inner).intern())
             }
+            TypeRef::Result(ok, err) => {
+                let ok =
+                    Self::from_hir_with_diagnostics(db, resolver, type_ref_map, diagnostics, *ok);
+                let err =
+                    Self::from_hir_with_diagnostics(db, resolver, type_ref_map, diagnostics, *err);
+                Some(TyKind::Result(ok, err).intern())
+            }
         };
         if let Some(ty) = res {
             ty
@@ -255,7 +262,7 @@ pub(crate) fn type_for_def(db: &dyn HirDatabase, def: TypableDef, ns: Namespace)
 
 pub(crate) fn type_for_impl_self(db: &dyn HirDatabase, i: ImplId) -> Ty {
     let impl_data = db.impl_data(i);
-    let resolver = i.resolver(db.upcast());
+    let resolver = impl_self_ty_resolver(db, i);
     Ty::from_hir(db, &resolver, &impl_data.type_ref_map, impl_data.self_ty).0
 }
 
@@ -326,10 +333,17 @@ fn type_for_type_alias(_db: &dyn HirDatabase, def: TypeAlias) -> Ty {
 
 pub(crate) fn lower_impl_query(db: &dyn HirDatabase, impl_id: ImplId) -> Arc<LowerTyMap> {
     let impl_data = db.impl_data(impl_id);
-    let resolver = impl_id.resolver(db.upcast());
+    let resolver = impl_self_ty_resolver(db, impl_id);
     lower_types(db, &resolver, &impl_data.type_ref_map)
 }
 
+/// Returns the resolver to resolve the self type of an impl. The self type is
+/// resolved in the scope of the module that contains the impl, within the impl
+/// `Self` would refer to the type that is being resolved.
+fn impl_self_ty_resolver(db: &dyn HirDatabase, impl_id: ImplId) -> Resolver {
+    impl_id.lookup(db.upcast()).module.resolver(db.upcast())
+}
+
 pub mod diagnostics {
     use mun_hir_input::FileId;
 
@@ -386,6 +386,76 @@ fn private_access() {
     "###);
 }
 
+#[test]
+fn private_field_access() {
+    insta::assert_snapshot!(infer(
+        r#"
+    //- /foo.mun
+    pub struct Foo { pub a: i32, pub(package) b: i32, c: i32 }
+    pub struct Bar(pub i32, i32);
+
+    fn main(foo: Foo, bar: Bar) {
+        let a = foo.c;
+        let a = bar.1;
+        let a = Foo { a: 1, b: 2, c: 3 };
+    }
+
+    //- /mod.mun
+    use foo::{Foo, Bar};
+
+    fn main(foo: Foo, bar: Bar) {
+        let a = foo.a;
+        let a = foo.b;
+        let a = foo.c; // private field
+        let a = bar.0;
+        let a = bar.1; // private field
+        let a = Foo { a: 1, b: 2, c: 3 }; // private field
+    }
+    "#),
+    @r#"
+    102..107: field `c` is private
+    157..162: field `1` is private
+    211..215: field `c` is private
+    30..33 'foo': Foo
+    40..43 'bar': Bar
+    50..237 '{     ...ield }': ()
+    60..61 'a': i32
+    64..67 'foo': Foo
+    64..69 'foo.a': i32
+    79..80 'a': i32
+    83..86 'foo': Foo
+    83..88 'foo.b': i32
+    98..99 'a': i32
+    102..105 'foo': Foo
+    102..107 'foo.c': i32
+    134..135 'a': i32
+    138..141 'bar': Bar
+    138..143 'bar.0': i32
+    153..154 'a': i32
+    157..160 'bar': Bar
+    157..162 'bar.1': i32
+    189..190 'a': Foo
+    193..217 'Foo { ...c: 3 }': Foo
+    202..203 '1': i32
+    208..209 '2': i32
+    214..215 '3': i32
+    98..101 'foo': Foo
+    108..111 'bar': Bar
+    118..197 '{     ...3 }; }': ()
+    128..129 'a': i32
+    132..135 'foo': Foo
+    132..137 'foo.c': i32
+    147..148 'a': i32
+    151..154 'bar': Bar
+    151..156 'bar.1': i32
+    166..167 'a': Foo
+    170..194 'Foo { ...c: 3 }': Foo
+    179..180 '1': i32
+    185..186 '2': i32
+    191..192 '3': i32
+    "#);
+}
+
 #[test]
 fn scoped_path() {
     insta::assert_snapshot!(infer(
@@ -578,6 +648,158 @@ fn infer_suffix_literals() {
     "###);
 }
 
+#[test]
+fn infer_cast() {
+    insta::assert_snapshot!(infer(
+        r"
+    fn main(a: i64, b: f32, c: bool) {
+        let d = a as u8;
+        let e = b as i16;
+        let f = c as usize;
+        let g = 300 as u8 as f64;
+        let h = a as i64;
+    }"),
+    @r###"
+    8..9 'a': i64
+    16..17 'b': f32
+    24..25 'c': bool
+    33..155 '{     ...i64; }': ()
+    43..44 'd': u8
+    47..48 'a': i64
+    47..54 'a as u8': u8
+    64..65 'e': i16
+    68..69 'b': f32
+    68..76 'b as i16': i16
+    86..87 'f': usize
+    90..91 'c': bool
+    90..100 'c as usize': usize
+    110..111 'g': f64
+    114..117 '300': i32
+    114..123 '300 as u8': u8
+    114..130 '300 as...as f64': f64
+    140..141 'h': i64
+    144..145 'a': i64
+    144..152 'a as i64': i64
+    "###);
+}
+
+#[test]
+fn infer_invalid_cast() {
+    insta::assert_snapshot!(infer(
+        r"
+    struct Foo;
+
+    fn main(a: f64, b: Foo) {
+        let c = a as bool;
+        let d = b as i32;
+        let e = a as Bar;
+    }"),
+    @r###"
+    51..60: invalid cast
+    74..82: invalid cast
+    101..104: undefined type
+    21..22 'a': f64
+    29..30 'b': Foo
+    37..107 '{     ...Bar; }': ()
+    47..48 'c': bool
+    51..52 'a': f64
+    51..60 'a as bool': bool
+    70..71 'd': i32
+    74..75 'b': Foo
+    74..82 'b as i32': i32
+    92..93 'e': {unknown}
+    96..97 'a': f64
+    96..104 'a as Bar': {unknown}
+    "###);
+}
+
+#[test]
+fn infer_result() {
+    insta::assert_snapshot!(infer(
+        r"
+    fn check(a: i32) -> Result<i32, bool> {
+        if a > 0 { Ok(a) } else { Err(false) }
+    }
+
+    fn parse(a: i32) -> Result<f64, bool> {
+        let b = check(a)?;
+        let c: Result<i32, bool> = Ok(b + 1);
+        Ok(c? as f64)
+    }"),
+    @r###"
+    9..10 'a': i32
+    38..84 '{     ...e) } }': Result<i32, bool>
+    44..82 'if a >...lse) }': Result<i32, bool>
+    47..48 'a': i32
+    47..52 'a > 0': bool
+    51..52 '0': i32
+    53..62 '{ Ok(a) }': Result<i32, bool>
+    55..60 'Ok(a)': Result<i32, bool>
+    58..59 'a': i32
+    68..82 '{ Err(false) }': Result<i32, bool>
+    70..80 'Err(false)': Result<i32, bool>
+    74..79 'false': bool
+    95..96 'a': i32
+    124..210 '{     ...f64) }': Result<f64, bool>
+    134..135 'b': i32
+    138..143 'check': function check(i32) -> Result<i32, bool>
+    138..146 'check(a)': Result<i32, bool>
+    138..147 'check(a)?': i32
+    144..145 'a': i32
+    157..158 'c': Result<i32, bool>
+    180..189 'Ok(b + 1)': Result<i32, bool>
+    183..184 'b': i32
+    183..188 'b + 1': i32
+    187..188 '1': i32
+    195..208 'Ok(c? as f64)': Result<f64, bool>
+    198..199 'c': Result<i32, bool>
+    198..200 'c?': i32
+    198..207 'c? as f64': f64
+    "###);
+}
+
+#[test]
+fn infer_invalid_try() {
+    insta::assert_snapshot!(infer(
+        r"
+    fn check(a: i32) -> Result<i32, bool> {
+        Ok(a)
+    }
+
+    fn main(a: i32) -> i32 {
+        check(a)?
+    }
+
+    fn other(a: i32) -> Result<i32, f64> {
+        let b = a?;
+        check(a)
+    }"),
+    @r###"
+    82..91: the `?` operator can only be used in a function that returns `Result`
+    146..147: the `?` operator can only be applied to values of type `Result`
+    154..162: mismatched type
+    132..164: mismatched type
+    9..10 'a': i32
+    38..51 '{     Ok(a) }': Result<i32, bool>
+    44..49 'Ok(a)': Result<i32, bool>
+    47..48 'a': i32
+    61..62 'a': i32
+    76..93 '{     ...(a)? }': i32
+    82..87 'check': function check(i32) -> Result<i32, bool>
+    82..90 'check(a)': Result<i32, bool>
+    82..91 'check(a)?': i32
+    88..89 'a': i32
+    104..105 'a': i32
+    132..164 '{     ...k(a) }': Result<i32, bool>
+    142..143 'b': {unknown}
+    146..147 'a': i32
+    146..148 'a?': {unknown}
+    154..159 'check': function check(i32) -> Result<i32, bool>
+    154..162 'check(a)': Result<i32, bool>
+    160..161 'a': i32
+    "###);
+}
+
 #[test]
 fn infer_invalid_struct_type() {
     insta::assert_snapshot!(infer(
@@ -1001,6 +1223,203 @@ fn infer_break() {
     "###);
 }
 
+#[test]
+fn infer_for_range() {
+    insta::assert_snapshot!(infer(
+        r#"
+    fn foo() -> i64 {
+        let sum = 0;
+        for i in 0..10 { sum += i; }
+        for i in 1..=255u8 { sum += i as i64; }
+        for _ in 0..sum { break; }
+        sum
+    }
+    "#),
+    @r#"
+    16..152 '{     ... sum }': i64
+    26..29 'sum': i64
+    32..33 '0': i64
+    39..67 'for i ...= i; }': ()
+    43..44 'i': i64
+    48..49 '0': i64
+    51..53 '10': i64
+    54..67 '{ sum += i; }': ()
+    56..59 'sum': i64
+    56..64 'sum += i': ()
+    63..64 'i': i64
+    72..111 'for i ...i64; }': ()
+    76..77 'i': u8
+    81..82 '1': u8
+    85..90 '255u8': u8
+    91..111 '{ sum ...i64; }': ()
+    93..96 'sum': i64
+    93..108 'sum += i as i64': ()
+    100..101 'i': u8
+    100..108 'i as i64': i64
+    116..142 'for _ ...eak; }': ()
+    125..126 '0': i64
+    128..131 'sum': i64
+    132..142 '{ break; }': never
+    134..139 'break': never
+    147..150 'sum': i64
+    "#);
+}
+
+#[test]
+fn infer_for_array() {
+    insta::assert_snapshot!(infer(
+        r#"
+    struct Foo { value: f32 }
+
+    fn foo(values: [f32], foos: [Foo]) -> f32 {
+        let sum = 0.0;
+        for value in values { sum += value; }
+        for foo in foos { sum += foo.value; }
+        for value in [1, 2, 3] { sum += value as f32; }
+        sum
+    }
+    "#),
+    @r#"
+    34..40 'values': [f32]
+    49..53 'foos': [Foo]
+    69..235 '{     ... sum }': f32
+    79..82 'sum': f32
+    85..88 '0.0': f32
+    94..131 'for va...lue; }': ()
+    98..103 'value': f32
+    107..113 'values': [f32]
+    114..131 '{ sum ...lue; }': ()
+    116..119 'sum': f32
+    116..128 'sum += value': ()
+    123..128 'value': f32
+    136..173 'for fo...lue; }': ()
+    140..143 'foo': Foo
+    147..151 'foos': [Foo]
+    152..173 '{ sum ...lue; }': ()
+    154..157 'sum': f32
+    154..170 'sum +=....value': ()
+    161..164 'foo': Foo
+    161..170 'foo.value': f32
+    178..225 'for va...f32; }': ()
+    182..187 'value': i32
+    191..200 '[1, 2, 3]': [i32]
+    192..193 '1': i32
+    195..196 '2': i32
+    198..199 '3': i32
+    201..225 '{ sum ...f32; }': ()
+    203..206 'sum': f32
+    203..222 'sum +=...as f32': ()
+    210..215 'value': i32
+    210..222 'value as f32': f32
+    230..233 'sum': f32
+    "#);
+}
+
+#[test]
+fn infer_for_errors() {
+    insta::assert_snapshot!(infer(
+        r#"
+    fn foo() {
+        let a = 0..10;              // error: ranges can only be used in a `for` loop
+        for _ in 5 {}               // error: mismatched type
+        for _ in 0..true {}         // error: mismatched type
+        for _ in 0..3 { break 3; }  // error: break with value can only appear in a loop
+        for _ in 0..3 { 5 }         // error: mismatched type
+    }
+    "#),
+    @r#"
+    23..28: ranges can only be used as the iterable of a `for` loop
+    106..107: mismatched type
+    164..171: mismatched type
+    229..236: `break` with value can only appear in a `loop`
+    314..315: mismatched type
+    312..317: mismatched type
+    9..353 '{     ...type }': ()
+    19..20 'a': {unknown}
+    23..28 '0..10': {unknown}
+    97..110 'for _ in 5 {}': ()
+    106..107 '5': i32
+    108..110 '{}': ()
+    155..174 'for _ ...rue {}': ()
+    164..165 '0': i32
+    167..171 'true': bool
+    172..174 '{}': ()
+    213..239 'for _ ...k 3; }': ()
+    222..223 '0': i32
+    225..226 '3': i32
+    227..239 '{ break 3; }': never
+    229..236 'break 3': never
+    235..236 '3': i32
+    298..317 'for _ ... { 5 }': ()
+    307..308 '0': i32
+    310..311 '3': i32
+    312..317 '{ 5 }': i32
+    314..315 '5': i32
+    "#);
+}
+
+#[test]
+fn infer_log() {
+    insta::assert_snapshot!(infer(
+        r#"
+    fn print(a: i32) {}
+
+    fn foo(a: u8, b: f32) {
+        print("{} + {} = {}", a, b, true);
+        log("{{braces}}");
+        let c = print("{}", 3);
+        print(5);
+    }
+    "#),
+    @r#"
+    9..10 'a': i32
+    17..19 '{}': ()
+    28..29 'a': u8
+    35..36 'b': f32
+    43..150 '{     ...(5); }': ()
+    49..82 'print(... true)': ()
+    71..72 'a': u8
+    74..75 'b': f32
+    77..81 'true': bool
+    88..105 'log("{...es}}")': ()
+    115..116 'c': ()
+    119..133 'print("{}", 3)': ()
+    131..132 '3': i32
+    139..144 'print': function print(i32) -> ()
+    139..147 'print(5)': ()
+    145..146 '5': i32
+    "#);
+}
+
+#[test]
+fn infer_log_errors() {
+    insta::assert_snapshot!(infer(
+        r#"
+    struct Foo;
+
+    fn foo() {
+        print("{}", Foo);       // error: cannot be formatted
+        print("{} {}", 1);      // error: argument count mismatch
+        log("{", 1);            // error: unmatched brace
+        log("}");               // error: unmatched brace
+    }
+    "#),
+    @r#"
+    86..103: the format string has 2 placeholders but 1 arguments were supplied
+    148..159: invalid format string: unmatched brace, use `{{` or `}}` to write a brace
+    202..210: invalid format string: unmatched brace, use `{{` or `}}` to write a brace
+    40..43: only booleans, integers and floating-point numbers can be formatted
+    22..253 '{     ...race }': ()
+    28..44 'print(..., Foo)': ()
+    40..43 'Foo': Foo
+    86..103 'print(...}", 1)': ()
+    101..102 '1': i32
+    148..159 'log("{", 1)': ()
+    157..158 '1': i32
+    202..210 'log("}")': ()
+    "#);
+}
+
 #[test]
 fn infer_while() {
     insta::assert_snapshot!(infer(
@@ -1041,6 +1460,7 @@ fn infer_while() {
     105..106 '3': i32
     107..119 '{ break 3; }': never
     109..116 'break 3': never
+    115..116 '3': i32
     180..214 'while ...; }; }': ()
     186..187 'n': i32
     186..191 'n < 3': bool
@@ -1171,6 +1591,39 @@ fn struct_lit() {
     "###);
 }
 
+#[test]
+fn struct_lit_field_defaults() {
+    insta::assert_snapshot!(infer(
+        r#"
+    struct Enemy {
+        hp: i32 = 100,
+        speed: f32 = 1.5,
+        level: u8,
+    }
+
+    fn main() {
+        let a = Enemy { level: 1 };
+        let b = Enemy { hp: 50, level: 2 };
+        let c = Enemy { hp: 50 }; // error: missing record fields: level
+    }
+    "#),
+    @r#"
+    170..186: missing record fields:
+    - level
+    84..228 '{     ...evel }': ()
+    94..95 'a': Enemy
+    98..116 'Enemy ...l: 1 }': Enemy
+    113..114 '1': u8
+    126..127 'b': Enemy
+    130..156 'Enemy ...l: 2 }': Enemy
+    142..144 '50': i32
+    153..154 '2': u8
+    166..167 'c': Enemy
+    170..186 'Enemy ...: 50 }': Enemy
+    182..184 '50': i32
+    "#);
+}
+
 #[test]
 fn struct_field_index() {
     insta::assert_snapshot!(infer(
@@ -1287,8 +1740,8 @@ fn extern_fn() {
     "#),
     @r###"
     65..89: extern functions cannot have bodies
-    174..175: extern functions can only have primitives as parameter- and return types
-    279..280: extern functions can only have primitives as parameter- and return types
+    174..175: extern functions can only have primitives or extern structs as parameter- and return types
+    279..280: extern functions can only have primitives or extern structs as parameter- and return types
     14..15 'a': i32
     21..22 'b': i32
     46..63 '{     ...,4); }': ()
@@ -1301,6 +1754,44 @@ fn extern_fn() {
     "###);
 }
 
+#[test]
+fn extern_struct() {
+    insta::assert_snapshot!(infer(
+        r#"
+    pub extern struct Texture;
+    extern struct WithFields { a: i32 }   // extern structs cannot have fields
+    extern struct(gc) WithMemoryKind;     // extern structs cannot specify a memory kind
+
+    extern fn load_texture(width: u32, height: u32) -> Texture;
+    extern fn texture_width(texture: Texture) -> u32;
+
+    fn main() {
+        let a = load_texture(1, 2);
+        texture_width(a);
+        let b = Texture;            // extern structs can only be constructed by the host
+    }
+    "#),
+    @r###"
+    27..62: extern structs cannot have fields
+    115..119: extern structs cannot specify a memory kind
+    377..384: extern structs can only be constructed by the host
+    211..216 'width': u32
+    223..229 'height': u32
+    272..279 'texture': Texture
+    309..452 '{     ...host }': ()
+    319..320 'a': Texture
+    323..335 'load_texture': function load_texture(u32, u32) -> Texture
+    323..341 'load_t...(1, 2)': Texture
+    336..337 '1': u32
+    339..340 '2': u32
+    347..360 'texture_width': function texture_width(Texture) -> u32
+    347..363 'textur...dth(a)': u32
+    361..362 'a': Texture
+    373..374 'b': Texture
+    377..384 'Texture': Texture
+    "###);
+}
+
 #[test]
 fn infer_type_alias() {
     insta::assert_snapshot!(infer(
@@ -1337,6 +1828,8 @@ fn recursive_alias() {
         let b: A;    // error: unknown type
         let c: B;    // error: unknown type
     }
+
+    fn foo() -> A {}
     "#),
     @r###"
     14..29: the name `Foo` is defined multiple times
@@ -1346,9 +1839,187 @@ fn recursive_alias() {
     76..77 'a': Foo
     116..117 'b': A
     156..157 'c': B
+    205..207 '{}': ()
+    "###);
+}
+
+#[test]
+fn error_recovery() {
+    insta::assert_snapshot!(infer(
+        r#"
+    struct Foo { a: i32 }
+    fn foo(a: i32) -> i32 { a }
+    fn main() {
+        let a = bar(1, true);   // unresolved value
+        let b = a.field;        // no cascading errors
+        let c = -a;
+        let d = foo(1, 2, 3);   // extra arguments are still inferred
+        let e = 5(Foo { a: 1 }.a);
+        break 5;
+    }
+        "#),
+    @r###"
+    74..77: undefined value
+    189..201: this function takes 1 parameters but 3 parameters was supplied
+    255..256: expected function type
+    278..285: `break` outside of a loop
+    29..30 'a': i32
+    44..49 '{ a }': i32
+    46..47 'a': i32
+    60..288 '{     ...k 5; }': never
+    70..71 'a': {unknown}
+    74..77 'bar': {unknown}
+    74..86 'bar(1, true)': {unknown}
+    78..79 '1': i32
+    81..85 'true': bool
+    118..119 'b': {unknown}
+    122..123 'a': {unknown}
+    122..129 'a.field': {unknown}
+    169..170 'c': {unknown}
+    173..175 '-a': {unknown}
+    174..175 'a': {unknown}
+    185..186 'd': i32
+    189..192 'foo': function foo(i32) -> i32
+    189..201 'foo(1, 2, 3)': i32
+    193..194 '1': i32
+    196..197 '2': i32
+    199..200 '3': i32
+    251..252 'e': {unknown}
+    255..256 '5': i32
+    255..272 '5(Foo ...1 }.a)': {unknown}
+    257..269 'Foo { a: 1 }': Foo
+    257..271 'Foo { a: 1 }.a': i32
+    266..267 '1': i32
+    278..285 'break 5': never
+    284..285 '5': i32
     "###);
 }
 
+#[test]
+fn type_annotations_needed() {
+    insta::assert_snapshot!(infer(
+        r#"
+    fn foo() {
+        let a = [];
+        let b = a[0];
+    }
+    fn bar() {
+        [][0];
+    }
+        "#),
+    @r###"
+    35..36: type annotations needed
+    62..67: type annotations needed
+    9..46 '{     ...[0]; }': ()
+    19..20 'a': [{unknown}]
+    23..25 '[]': [{unknown}]
+    35..36 'b': {unknown}
+    39..40 'a': [{unknown}]
+    39..43 'a[0]': {unknown}
+    41..42 '0': i32
+    56..70 '{     [][0]; }': ()
+    62..64 '[]': [{unknown}]
+    62..67 '[][0]': {unknown}
+    65..66 '0': i32
+    "###);
+}
+
+#[test]
+fn invalid_self() {
+    insta::assert_snapshot!(infer(
+        r#"
+    fn foo(self) -> i32 { 3 }
+    impl Self {
+        fn bar() {}
+    }
+        "#),
+    @r###"
+    31..35: undefined type
+    7..11 'self': {unknown}
+    20..25 '{ 3 }': i32
+    22..23 '3': i32
+    51..53 '{}': ()
+    "###);
+}
+
+#[test]
+fn malformed_float_literal() {
+    insta::assert_snapshot!(infer(
+        r#"
+    fn foo() -> f64 { 1e }
+        "#),
+    @r###"
+    18..20: invalid literal value
+    16..22 '{ 1e }': f64
+    18..20 '1e': f64
+    "###);
+}
+
+#[test]
+fn infer_prelude_and_package_paths() {
+    insta::assert_snapshot!(infer(
+        r#"
+    //- /mod.mun package:core prelude
+    pub fn abs(value: i32) -> i32 { value }
+    fn private_fn() {}
+
+    //- /mod.mun
+    fn main() {
+        let a = abs(-1);
+        let b = core::abs(a);
+        core::private_fn();
+    }
+    "#),
+    @r###"
+    63..79: access of private type
+    10..84 '{     ...n(); }': ()
+    20..21 'a': i32
+    24..27 'abs': function abs(i32) -> i32
+    24..31 'abs(-1)': i32
+    28..30 '-1': i32
+    29..30 '1': i32
+    41..42 'b': i32
+    45..54 'core::abs': function abs(i32) -> i32
+    45..57 'core::abs(a)': i32
+    55..56 'a': i32
+    63..79 'core::...ate_fn': function private_fn() -> ()
+    63..81 'core::...e_fn()': ()
+    11..16 'value': i32
+    30..39 '{ value }': i32
+    32..37 'value': i32
+    56..58 '{}': ()
+    "###);
+}
+
+#[test]
+fn infer_items_with_syntax_errors() {
+    insta::assert_snapshot!(infer(
+        r#"
+    struct { a: i32 }
+    struct Foo { pub, b: bool }
+    fn (a: i32) -> i32 { a + true }
+    fn () { let b: Foo = Foo { b: 1 }; }
+    impl { fn bar() -> bool { 1 } }
+    "#),
+    @r#"
+    71..75: mismatched type
+    108..109: mismatched type
+    141..142: mismatched type
+    139..144: mismatched type
+    50..51 'a': i32
+    65..77 '{ a + true }': i32
+    67..68 'a': i32
+    67..75 'a + true': {unknown}
+    71..75 'true': bool
+    84..114 '{ let ...1 }; }': ()
+    90..91 'b': Foo
+    99..111 'Foo { b: 1 }': Foo
+    108..109 '1': i32
+    139..144 '{ 1 }': i32
+    141..142 '1': i32
+    "#);
+}
+
 fn infer(content: &str) -> String {
     let db = MockDatabase::with_files(content);
 
@@ -1370,10 +2041,11 @@ fn infer(content: &str) -> String {
 
         for (expr, ty) in infer_result.type_of_expr.iter() {
             let syntax_ptr = match body_source_map.expr_syntax(expr) {
-                Some(sp) => {
+                // Skip expressions that were introduced by desugaring
+                Some(sp) if body_source_map.syntax_expr(sp.value.clone()) == Some(expr) => {
                     sp.map(|ast| ast.either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr()))
                 }
-                None => continue,
+                _ => continue,
             };
             types.push((syntax_ptr, ty));
         }
@@ -19,6 +19,8 @@ pub enum TypeRef {
     Array(LocalTypeRefId),
     Never,
     Tuple(Vec<LocalTypeRefId>),
+    /// The built-in `Result<T, E>` type
+    Result(LocalTypeRefId, LocalTypeRefId),
     Error,
 }
 
@@ -106,10 +108,26 @@ impl TypeRefMapBuilder {
 
         let ptr = AstPtr::new(node);
         let type_ref = match node.kind() {
-            PathType(path) => path
-                .path()
-                .and_then(Path::from_ast)
-                .map_or(TypeRef::Error, TypeRef::Path),
+            PathType(path_type) => {
+                let path = path_type.path().and_then(Path::from_ast);
+                match path_type.type_arg_list() {
+                    None => path.map_or(TypeRef::Error, TypeRef::Path),
+                    Some(type_args) => {
+                        let type_args: Vec<_> = type_args
+                            .type_args()
+                            .map(|type_arg| self.alloc_from_node(&type_arg))
+                            .collect();
+
+                        // `Result` is the only type that accepts type arguments
+                        match (path.as_ref().and_then(Path::as_ident), type_args.as_slice()) {
+                            (Some(name), &[ok, err]) if *name == name![Result] => {
+                                TypeRef::Result(ok, err)
+                            }
+                            _ => TypeRef::Error,
+                        }
+                    }
+                }
+            }
             NeverType(_) => TypeRef::Never,
             ArrayType(inner) => TypeRef::Array(self.alloc_from_node_opt(inner.type_ref().as_ref())),
         };
@@ -40,4 +40,21 @@ pub mod tests {
         drop(diag_sink);
         diags.join("\n")
     }
+
+    /// Returns the warnings of all lints in the specified source.
+    pub fn lints(content: &str) -> String {
+        let (db, _file_id) = MockDatabase::with_single_file(content);
+
+        let mut diags = Vec::new();
+        let mut diag_sink = DiagnosticSink::new(|diag| {
+            diags.push(format!("{:?}: {}", diag.highlight_range(), diag.message()));
+        });
+
+        for module in Package::all(&db).iter().flat_map(|pkg| pkg.modules(&db)) {
+            module.lints(&db, &mut diag_sink);
+        }
+
+        drop(diag_sink);
+        diags.join("\n")
+    }
 }
@@ -19,6 +19,18 @@ pub struct Fixture {
 
     /// The text of the file
     pub text: String,
+
+    /// The name of the library package that contains the file, or `None` if
+    /// the file is part of the main package.
+    pub package: Option<String>,
+
+    /// Whether the package that contains the file is the prelude of all other
+    /// packages.
+    pub is_prelude: bool,
+
+    /// The names of the library packages that the package that contains the
+    /// file depends on.
+    pub dependencies: Vec<String>,
 }
 
 impl Fixture {
@@ -36,6 +48,11 @@ impl Fixture {
     ///
     /// into two separate `Fixture`s one with `relative_path` 'foo.mun' and one
     /// with 'bar.mun'.
+    ///
+    /// A meta line can also place a file in a library package, e.g.
+    /// `//- /mod.mun package:core prelude` places the file in the prelude
+    /// package `core`, and declare dependencies of the package of the file,
+    /// e.g. `//- /mod.mun deps:physics,ai`.
     pub fn parse(text: impl AsRef<str>) -> Vec<Fixture> {
         let text = trim_raw_string_literal(text);
         let mut result: Vec<Fixture> = Vec::new();
@@ -90,9 +107,27 @@ impl Fixture {
         assert!(path.starts_with('/'));
         let relative_path = RelativePathBuf::from(&path[1..]);
 
+        let mut package = None;
+        let mut is_prelude = false;
+        let mut dependencies = Vec::new();
+        for component in &components[1..] {
+            if let Some(name) = component.strip_prefix("package:") {
+                package = Some(name.to_owned());
+            } else if let Some(names) = component.strip_prefix("deps:") {
+                dependencies.extend(names.split(',').map(ToOwned::to_owned));
+            } else if *component == "prelude" {
+                is_prelude = true;
+            } else {
+                panic!("unknown fixture meta component: {component:?}");
+            }
+        }
+
         Fixture {
             relative_path,
             text: String::new(),
+            package,
+            is_prelude,
+            dependencies,
         }
     }
 }
@@ -168,7 +203,10 @@ mod test {
             Fixture::parse(""),
             vec![Fixture {
                 relative_path: RelativePathBuf::from(DEFAULT_FILE_NAME),
-                text: "".to_owned()
+                text: "".to_owned(),
+                package: None,
+                is_prelude: false,
+                dependencies: Vec::new(),
             }]
         );
     }
@@ -179,7 +217,10 @@ mod test {
             Fixture::parse(format!("{META_LINE} /foo.mun\nfn hello_world() {{}}")),
             vec![Fixture {
                 relative_path: RelativePathBuf::from("foo.mun"),
-                text: "fn hello_world() {}\n".to_owned()
+                text: "fn hello_world() {}\n".to_owned(),
+                package: None,
+                is_prelude: false,
+                dependencies: Vec::new(),
             }]
         );
     }
@@ -201,16 +242,38 @@ mod test {
             vec![
                 Fixture {
                     relative_path: RelativePathBuf::from("foo.mun"),
-                    text: "fn hello_world() {\n}\n\n".to_owned()
+                    text: "fn hello_world() {\n}\n\n".to_owned(),
+                    package: None,
+                    is_prelude: false,
+                    dependencies: Vec::new(),
                 },
                 Fixture {
                     relative_path: RelativePathBuf::from("bar.mun"),
-                    text: "fn baz() {\n}\n".to_owned()
+                    text: "fn baz() {\n}\n".to_owned(),
+                    package: None,
+                    is_prelude: false,
+                    dependencies: Vec::new(),
                 }
             ]
         );
     }
 
+    #[test]
+    fn library_package_fixture() {
+        assert_eq!(
+            Fixture::parse(format!(
+                "{META_LINE} /mod.mun package:core prelude deps:libm\npub fn abs() {{}}"
+            )),
+            vec![Fixture {
+                relative_path: RelativePathBuf::from("mod.mun"),
+                text: "pub fn abs() {}\n".to_owned(),
+                package: Some("core".to_owned()),
+                is_prelude: true,
+                dependencies: vec!["libm".to_owned()],
+            }]
+        );
+    }
+
     #[test]
     #[should_panic]
     fn incorrectly_indented_fixture() {
@@ -23,26 +23,69 @@ pub trait WithFixture: Default + SourceDatabase + 'static {
     }
 }
 
-/// Fills the specified database with all the files from the specified `fixture`
+/// Fills the specified database with all the files from the specified
+/// `fixture`. Files that are not part of a library package are added to the
+/// main package, which always has `PackageId(0)`.
 fn with_files(db: &mut dyn SourceDatabase, fixture: &str) -> Vec<FileId> {
     let fixture = Fixture::parse(fixture);
 
-    let mut source_root = SourceRoot::default();
-    let source_root_id = SourceRootId(0);
+    // The source roots of all packages, the main package comes first
+    let mut source_roots: Vec<(Option<String>, bool, SourceRoot)> =
+        vec![(None, false, SourceRoot::default())];
+    let mut dependencies: Vec<(Option<String>, String)> = Vec::new();
     let mut files = Vec::new();
 
     for (idx, entry) in fixture.into_iter().enumerate() {
+        let root_idx = source_roots
+            .iter()
+            .position(|(package, _, _)| *package == entry.package)
+            .unwrap_or_else(|| {
+                source_roots.push((entry.package.clone(), false, SourceRoot::default()));
+                source_roots.len() - 1
+            });
+        let source_root_id = SourceRootId(root_idx.try_into().expect("too many packages"));
+
         let file_id = FileId(idx.try_into().expect("too many files"));
         db.set_file_text(file_id, Arc::from(entry.text));
         db.set_file_source_root(file_id, source_root_id);
+
+        let (_, is_prelude, source_root) = &mut source_roots[root_idx];
         source_root.insert_file(file_id, entry.relative_path);
+        *is_prelude |= entry.is_prelude;
+        dependencies.extend(
+            entry
+                .dependencies
+                .into_iter()
+                .map(|dependency| (entry.package.clone(), dependency)),
+        );
         files.push(file_id);
     }
 
-    db.set_source_root(source_root_id, Arc::new(source_root));
-
     let mut packages = PackageSet::default();
-    packages.add_package(source_root_id);
+    let mut package_ids = Vec::new();
+    for (idx, (name, is_prelude, source_root)) in source_roots.into_iter().enumerate() {
+        let source_root_id = SourceRootId(idx.try_into().expect("too many packages"));
+        db.set_source_root(source_root_id, Arc::new(source_root));
+
+        let package_id = match &name {
+            Some(name) => packages.add_library(name.clone(), source_root_id),
+            None => packages.add_package(source_root_id),
+        };
+        if is_prelude {
+            packages.set_prelude(package_id);
+        }
+        package_ids.push((name, package_id));
+    }
+
+    let package_id = |name: &Option<String>| {
+        package_ids
+            .iter()
+            .find_map(|(package, id)| (package == name).then_some(*id))
+            .expect("unknown package in fixture")
+    };
+    for (from, to) in dependencies {
+        packages.add_dependency(package_id(&from), to.clone(), package_id(&Some(to)));
+    }
     db.set_packages(Arc::new(packages));
 
     files
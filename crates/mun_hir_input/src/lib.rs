@@ -11,7 +11,7 @@ pub use db::{SourceDatabase, SourceDatabaseStorage};
 pub use fixture::{Fixture, WithFixture};
 pub use line_index::{LineCol, LineIndex};
 pub use module_tree::{ModuleData, ModuleTree, PackageModuleId};
-pub use package_set::{PackageData, PackageId, PackageSet};
+pub use package_set::{Dependency, PackageData, PackageId, PackageSet};
 pub use source_root::{SourceRoot, SourceRootId};
 
 /// [`FileId`] is an integer which uniquely identifies a file. File paths are
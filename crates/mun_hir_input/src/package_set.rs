@@ -20,24 +20,110 @@ pub struct PackageId(pub u32);
 pub struct PackageData {
     /// The source root which groups together all the source files of a package.
     pub source_root: SourceRootId,
+
+    /// The name by which other packages refer to this package. Packages
+    /// without a name, like the package that is being built, cannot be
+    /// referred to from other packages.
+    pub name: Option<String>,
+
+    /// The packages that this package depends on.
+    pub dependencies: Vec<Dependency>,
+}
+
+/// A dependency of a package on another package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    /// The name by which the dependent package refers to the dependency
+    pub name: String,
+
+    /// The package that is depended on
+    pub package: PackageId,
 }
 
 /// Contains information about all the packages in the project.
 #[derive(Debug, Clone, Default)]
 pub struct PackageSet {
     arena: FxHashMap<PackageId, PackageData>,
+
+    /// The package whose public root items are implicitly in scope in all
+    /// other packages.
+    prelude: Option<PackageId>,
 }
 
 impl PackageSet {
     /// Adds a new package to the package set with the source files located add
     /// the specified root. Returns the `PackageId` associated with the package.
     pub fn add_package(&mut self, source_root: SourceRootId) -> PackageId {
-        let data = PackageData { source_root };
+        self.insert(PackageData {
+            source_root,
+            name: None,
+            dependencies: Vec::new(),
+        })
+    }
+
+    /// Adds a new library package called `name` to the package set with the
+    /// source files located at the specified root. Other packages can refer to
+    /// the items of the library through paths starting with `name`. Returns
+    /// the `PackageId` associated with the package.
+    pub fn add_library(&mut self, name: impl Into<String>, source_root: SourceRootId) -> PackageId {
+        self.insert(PackageData {
+            source_root,
+            name: Some(name.into()),
+            dependencies: Vec::new(),
+        })
+    }
+
+    fn insert(&mut self, data: PackageData) -> PackageId {
         let package_id = PackageId(self.arena.len() as u32);
         self.arena.insert(package_id, data);
         package_id
     }
 
+    /// Sets the package that acts as the prelude of all other packages. The
+    /// public items of its root module are in scope in every module of the
+    /// other packages, and the other packages can refer to the prelude
+    /// package by its name.
+    pub fn set_prelude(&mut self, package: PackageId) {
+        self.prelude = Some(package);
+    }
+
+    /// Returns the package that acts as the prelude of all other packages, if
+    /// any.
+    pub fn prelude(&self) -> Option<PackageId> {
+        self.prelude
+    }
+
+    /// Adds a dependency on the package `to` to the package `from`. Items of
+    /// `to` can be referred to from `from` through paths starting with
+    /// `name`.
+    ///
+    /// The dependencies between packages must not form a cycle.
+    pub fn add_dependency(&mut self, from: PackageId, name: impl Into<String>, to: PackageId) {
+        self.arena
+            .get_mut(&from)
+            .expect("unknown package")
+            .dependencies
+            .push(Dependency {
+                name: name.into(),
+                package: to,
+            });
+    }
+
+    /// Returns the names and ids of the packages that can be referred to by
+    /// name from the specified `package`: its dependencies and the prelude
+    /// package.
+    pub fn dependencies(&self, package: PackageId) -> impl Iterator<Item = (&str, PackageId)> {
+        let prelude = self
+            .prelude
+            .filter(|&prelude| prelude != package)
+            .and_then(|prelude| Some((self.arena[&prelude].name.as_deref()?, prelude)));
+        self.arena[&package]
+            .dependencies
+            .iter()
+            .map(|dependency| (dependency.name.as_str(), dependency.package))
+            .chain(prelude)
+    }
+
     /// Iterates over all packages
     pub fn iter(&self) -> impl Iterator<Item = PackageId> + '_ {
         self.arena.keys().copied()
@@ -0,0 +1,71 @@
+//! Determines `MUN_LANGUAGE_SERVER_BUILD_ID`, which identifies the build of the
+//! language server. The diagnostics cache is keyed on it.
+//!
+//! When building from a git checkout, this is the hash of the commit. If the
+//! working tree has uncommitted changes, the time of the build is appended, so
+//! every build gets a different identifier. Otherwise, e.g. when building a
+//! published crate, it is the version of the package.
+
+use std::{
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn main() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    println!(
+        "cargo:rustc-env=MUN_LANGUAGE_SERVER_BUILD_ID={}",
+        build_id(Path::new(&manifest_dir))
+    );
+}
+
+fn build_id(manifest_dir: &Path) -> String {
+    let Some(commit) = git(manifest_dir, &["rev-parse", "HEAD"]) else {
+        return std::env::var("CARGO_PKG_VERSION").unwrap();
+    };
+
+    // Determine the identifier again when a commit is checked out or created, or
+    // when any of the sources of the workspace change
+    for path in ["HEAD", "logs/HEAD", "index"] {
+        if let Some(path) = git(manifest_dir, &["rev-parse", "--git-path", path]) {
+            println!(
+                "cargo:rerun-if-changed={}",
+                manifest_dir.join(path).display()
+            );
+        }
+    }
+    if let Some(crates_dir) = manifest_dir.parent() {
+        println!("cargo:rerun-if-changed={}", crates_dir.display());
+    }
+
+    let is_dirty = git(
+        manifest_dir,
+        &["status", "--porcelain", "--untracked-files=no"],
+    )
+    .map_or(true, |status| !status.is_empty());
+    if is_dirty {
+        let build_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        format!("{commit}-dirty-{build_time}")
+    } else {
+        commit
+    }
+}
+
+/// Runs git in `dir` and returns its trimmed output, or `None` if it failed.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout)
+        .ok()
+        .map(|output| output.trim().to_owned())
+}
@@ -1,18 +1,36 @@
 use std::sync::Arc;
 
 use mun_hir::AstDatabase;
-use mun_hir_input::{FileId, LineIndex, PackageId, SourceDatabase};
-use mun_syntax::SourceFile;
+use mun_hir_input::{FileId, LineIndex, PackageId, PackageSet, SourceDatabase};
+use mun_syntax::{SourceFile, TextRange};
+use rustc_hash::FxHashMap;
 use salsa::{ParallelDatabase, Snapshot};
+use sha2::{Digest, Sha256};
 
 use crate::{
-    cancelation::Canceled, change::AnalysisChange, completion, db::AnalysisDatabase, diagnostics,
-    diagnostics::Diagnostic, file_structure, FilePosition,
+    cancelation::Canceled,
+    change::AnalysisChange,
+    completion,
+    db::AnalysisDatabase,
+    diagnostics,
+    diagnostics::Diagnostic,
+    file_structure, goto_definition,
+    inlay_hints::{self, InlayHint, InlayHintsConfig},
+    navigation_target::NavigationTarget,
+    references,
+    rename::{self, RenameResult},
+    source_change::SourceChange,
+    syntax_highlighting::{self, HighlightedRange},
+    FilePosition, FileRange,
 };
 
 /// Result of an operation that can be canceled.
 pub type Cancelable<T> = Result<T, Canceled>;
 
+/// A hash of the contents of a package, see
+/// [`AnalysisSnapshot::package_fingerprint`].
+pub type Fingerprint = [u8; 32];
+
 /// The `Analysis` struct is the basis of all language server operations. It
 /// maintains the current state of the source.
 #[derive(Default)]
@@ -72,6 +90,17 @@ impl AnalysisSnapshot {
         })
     }
 
+    /// Returns a SHA-256 hash of the paths and contents of all the source files
+    /// of the given package and of the packages that it depends on, directly
+    /// or indirectly. If the fingerprint of a package didn't change, neither
+    /// did the results of analyzing it.
+    pub fn package_fingerprint(&self, package_id: PackageId) -> Cancelable<Fingerprint> {
+        self.with_db(|db| {
+            let packages = db.packages();
+            package_fingerprint(db, &packages, package_id, &mut FxHashMap::default())
+        })
+    }
+
     /// Returns the line index for the specified file
     pub fn file_line_index(&self, file_id: FileId) -> Cancelable<Arc<LineIndex>> {
         self.with_db(|db| db.line_index(file_id))
@@ -93,6 +122,65 @@ impl AnalysisSnapshot {
         self.with_db(|db| completion::completions(db, position).map(Into::into))
     }
 
+    /// Returns the definition(s) of the item at the given position
+    pub fn goto_definition(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| goto_definition::goto_definition(db, position))
+    }
+
+    /// Finds all references to the item at the given position
+    pub fn find_all_refs(
+        &self,
+        position: FilePosition,
+        include_declaration: bool,
+    ) -> Cancelable<Option<Vec<FileRange>>> {
+        self.with_db(|db| references::find_all_refs(db, position, include_declaration))
+    }
+
+    /// Checks whether the item at the given position can be renamed and
+    /// returns the range of its name.
+    pub fn prepare_rename(&self, position: FilePosition) -> Cancelable<RenameResult<FileRange>> {
+        self.with_db(|db| rename::prepare_rename(db, position))
+    }
+
+    /// Renames the item at the given position and all its references
+    pub fn rename(
+        &self,
+        position: FilePosition,
+        new_name: &str,
+    ) -> Cancelable<RenameResult<SourceChange>> {
+        self.with_db(|db| rename::rename(db, position, new_name))
+    }
+
+    /// Computes the semantic highlighting of the specified file. If `range` is
+    /// specified, only the tokens in that range are highlighted.
+    pub fn highlight(
+        &self,
+        file_id: FileId,
+        range: Option<TextRange>,
+    ) -> Cancelable<Vec<HighlightedRange>> {
+        self.with_db(|db| syntax_highlighting::highlight(db, file_id, range))
+    }
+
+    /// Computes the inlay hints of the specified file. If `range` is specified,
+    /// only the hints in that range are computed.
+    pub fn inlay_hints(
+        &self,
+        file_id: FileId,
+        range: Option<TextRange>,
+        config: &InlayHintsConfig,
+    ) -> Cancelable<Vec<InlayHint>> {
+        self.with_db(|db| inlay_hints::inlay_hints(db, file_id, range, config))
+    }
+
+    /// Returns the formatted contents of the specified file or `None` if the
+    /// file contains syntax errors.
+    pub fn format(&self, file_id: FileId) -> Cancelable<Option<String>> {
+        self.with_db(|db| mun_fmt::format(&db.file_text(file_id)).ok())
+    }
+
     /// Performs an operation on that may be Canceled.
     fn with_db<F: FnOnce(&AnalysisDatabase) -> T + std::panic::UnwindSafe, T>(
         &self,
@@ -101,3 +189,130 @@ impl AnalysisSnapshot {
         self.db.catch_canceled(f)
     }
 }
+
+/// Computes the fingerprint of a package, see
+/// [`AnalysisSnapshot::package_fingerprint`]. The fingerprints of the packages
+/// that were already computed are stored in `fingerprints`, so a package that
+/// is depended on multiple times is only hashed once.
+fn package_fingerprint(
+    db: &AnalysisDatabase,
+    packages: &PackageSet,
+    package_id: PackageId,
+    fingerprints: &mut FxHashMap<PackageId, Fingerprint>,
+) -> Fingerprint {
+    if let Some(&fingerprint) = fingerprints.get(&package_id) {
+        return fingerprint;
+    }
+
+    // Guard against cyclic dependencies, which are reported elsewhere
+    fingerprints.insert(package_id, Fingerprint::default());
+
+    let source_root = db.source_root(packages[package_id].source_root);
+    let mut files = source_root
+        .files()
+        .map(|file_id| (source_root.relative_path(file_id), file_id))
+        .collect::<Vec<_>>();
+    files.sort_unstable_by_key(|(path, _)| *path);
+
+    // Every string is prefixed with its length, so the boundaries between them
+    // are part of the hash
+    let mut hasher = Sha256::new();
+    let mut update = |bytes: &[u8]| {
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    };
+    for (path, file_id) in files {
+        update(path.as_str().as_bytes());
+        update(db.file_text(file_id).as_bytes());
+    }
+
+    // Package ids are not stable between sessions, so dependencies are
+    // identified by the name that the package refers to them with
+    let mut dependencies = packages.dependencies(package_id).collect::<Vec<_>>();
+    dependencies.sort_unstable_by_key(|(name, _)| *name);
+    for (name, dependency) in dependencies {
+        update(name.as_bytes());
+        update(&package_fingerprint(db, packages, dependency, fingerprints));
+    }
+
+    let fingerprint = hasher.finalize().into();
+    fingerprints.insert(package_id, fingerprint);
+    fingerprint
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use mun_hir_input::PackageId;
+    use salsa::Database;
+
+    use super::Analysis;
+    use crate::{change::AnalysisChange, change_fixture::ChangeFixture};
+
+    #[test]
+    fn apply_change_cancels_snapshots() {
+        let fixture = ChangeFixture::parse(
+            r#"
+        fn main() -> i32 { 5 }
+        "#,
+        );
+        let file_id = fixture.files[0];
+        let mut analysis = Analysis::default();
+        analysis.apply_change(fixture.change);
+
+        // Nothing has been computed for this snapshot yet
+        let snapshot = analysis.snapshot();
+
+        // Applying a change blocks until all snapshots have been dropped, so it has
+        // to happen on another thread.
+        let writer = std::thread::spawn(move || {
+            let mut change = AnalysisChange::new();
+            change.change_file(file_id, Some(Arc::from("fn main() -> i32 { 6 }")));
+            analysis.apply_change(change);
+            analysis
+        });
+
+        // Wait until the writer has announced the new revision
+        while !snapshot.db.salsa_runtime().is_current_revision_canceled() {
+            std::thread::yield_now();
+        }
+
+        assert!(snapshot.diagnostics(file_id).is_err());
+
+        drop(snapshot);
+        let analysis = writer.join().unwrap();
+        assert!(analysis.snapshot().diagnostics(file_id).is_ok());
+    }
+
+    #[test]
+    fn package_fingerprint() {
+        let fixture = ChangeFixture::parse(
+            r#"
+        //- /foo.mun
+        pub fn foo() -> i32 { 5 }
+
+        //- /mod.mun
+        fn main() -> i32 { foo::foo() }
+        "#,
+        );
+        let file_id = fixture.files[0];
+        let package_id = PackageId(0);
+        let mut analysis = Analysis::default();
+        analysis.apply_change(fixture.change);
+
+        let fingerprint = analysis.snapshot().package_fingerprint(package_id).unwrap();
+        assert_eq!(
+            analysis.snapshot().package_fingerprint(package_id).unwrap(),
+            fingerprint
+        );
+
+        let mut change = AnalysisChange::new();
+        change.change_file(file_id, Some(Arc::from("pub fn foo() -> i32 { 6 }")));
+        analysis.apply_change(change);
+        assert_ne!(
+            analysis.snapshot().package_fingerprint(package_id).unwrap(),
+            fingerprint
+        );
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use mun_hir_input::{FileId, Fixture, PackageSet, SourceRoot, SourceRootId};
 use mun_syntax::{TextRange, TextSize};
 
-use crate::change::AnalysisChange;
+use crate::{change::AnalysisChange, db::AnalysisDatabase, FilePosition};
 
 pub const CURSOR_MARKER: &str = "$0";
 
@@ -60,6 +60,22 @@ impl ChangeFixture {
     }
 }
 
+/// Creates an analysis database from a multi-file fixture and a position marked
+/// with `$0`.
+pub(crate) fn position(fixture: &str) -> (AnalysisDatabase, FilePosition) {
+    let change_fixture = ChangeFixture::parse(fixture);
+    let mut database = AnalysisDatabase::default();
+    database.apply_change(change_fixture.change);
+    let (file_id, range_or_offset) = change_fixture
+        .file_position
+        .expect("expected a marker ($0)");
+    let offset = match range_or_offset {
+        RangeOrOffset::Range(_) => panic!(),
+        RangeOrOffset::Offset(it) => it,
+    };
+    (database, FilePosition { file_id, offset })
+}
+
 /// Returns the offset of the first occurrence of `$0` marker and the copy of
 /// `text` without the marker.
 fn try_extract_offset(text: &str) -> Option<(TextSize, String)> {
@@ -6,6 +6,8 @@
 mod context;
 mod dot;
 mod item;
+mod keyword;
+mod qualified_path;
 mod render;
 mod unqualified_path;
 
@@ -17,7 +19,7 @@ pub use item::{CompletionItem, CompletionItemKind, CompletionKind};
 use mun_hir::semantics::ScopeDef;
 
 use crate::{
-    completion::render::{render_field, render_resolution, RenderContext},
+    completion::render::{render_field, render_fn, render_resolution, RenderContext},
     db::AnalysisDatabase,
     FilePosition,
 };
@@ -40,7 +42,9 @@ pub(crate) fn completions(db: &AnalysisDatabase, position: FilePosition) -> Opti
 
     let mut result = Completions::default();
     unqualified_path::complete_unqualified_path(&mut result, &context);
+    qualified_path::complete_qualified_path(&mut result, &context);
     dot::complete_dot(&mut result, &context);
+    keyword::complete_keyword(&mut result, &context);
     Some(result)
 }
 
@@ -75,6 +79,21 @@ impl Completions {
         }
     }
 
+    /// Adds a completion item for a function
+    fn add_function(&mut self, ctx: &CompletionContext<'_>, func: mun_hir::Function) {
+        if let Some(item) = render_fn(RenderContext::new(ctx), None, func) {
+            self.add(item);
+        }
+    }
+
+    /// Adds a completion item for a keyword
+    fn add_keyword(&mut self, keyword: &'static str) {
+        let item = CompletionItem::builder(CompletionKind::Keyword, keyword)
+            .kind(CompletionItemKind::Keyword)
+            .finish();
+        self.add(item);
+    }
+
     /// Adds a completion item for a field
     fn add_field(&mut self, ctx: &CompletionContext<'_>, field: mun_hir::Field) {
         let item = render_field(RenderContext::new(ctx), field);
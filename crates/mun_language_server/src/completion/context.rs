@@ -2,7 +2,11 @@ use mun_hir::{
     semantics::{Semantics, SemanticsScope},
     AstDatabase,
 };
-use mun_syntax::{ast, utils::find_node_at_offset, AstNode, SyntaxNode, TextRange, TextSize};
+use mun_syntax::{
+    ast::{self, LoopBodyOwner},
+    utils::find_node_at_offset,
+    AstNode, SyntaxKind, SyntaxNode, TextRange, TextSize,
+};
 use ra_ap_text_edit::Indel;
 
 use crate::{db::AnalysisDatabase, FilePosition};
@@ -24,6 +28,31 @@ pub(super) struct CompletionContext<'a> {
     /// True if we're at an ast::PathType
     pub is_path_type: bool,
 
+    /// True if we're at an ast::PathExpr, i.e. where an expression is expected
+    pub is_path_expr: bool,
+
+    /// True if the cursor is inside the body of a loop
+    pub is_in_loop_body: bool,
+
+    /// True if we're directly after the pattern of a `for` loop, i.e. where
+    /// `in` is expected
+    pub is_after_for_pattern: bool,
+
+    /// True if we're directly after an expression on the same line, i.e. where
+    /// the expression can be cast with `as`
+    pub is_after_expr: bool,
+
+    /// True if we're at the position of a new item, e.g. at the top-level of a
+    /// file
+    pub is_new_item: bool,
+
+    /// True if we're at the position of a new item inside an `impl` block
+    pub is_new_assoc_item: bool,
+
+    /// The qualifier of the path that is being completed, i.e. `foo` when
+    /// writing `foo::$0`
+    pub path_qualifier: Option<ast::Path>,
+
     /// The receiver if this is a field or method access, i.e. writing
     /// something.$0
     pub dot_receiver: Option<ast::Expr>,
@@ -63,6 +92,13 @@ impl<'a> CompletionContext<'a> {
             is_trivial_path: false,
             is_param: false,
             is_path_type: false,
+            is_path_expr: false,
+            is_in_loop_body: false,
+            is_after_for_pattern: false,
+            is_after_expr: false,
+            is_new_item: false,
+            is_new_assoc_item: false,
+            path_qualifier: None,
             dot_receiver: None,
         };
 
@@ -90,6 +126,23 @@ impl<'a> CompletionContext<'a> {
             }
 
             self.classify_name_ref(original_file, name_ref);
+            return;
+        }
+
+        // Otherwise, we might be at the start of a new item, e.g. `fn`. The parser
+        // doesn't know what to make of a lone identifier there and wraps it in an
+        // error node.
+        let parent_kind = file_with_fake_ident
+            .token_at_offset(offset)
+            .right_biased()
+            .and_then(|token| token.parent())
+            .filter(|parent| parent.kind() == SyntaxKind::ERROR)
+            .and_then(|error| error.parent())
+            .map(|parent| parent.kind());
+        match parent_kind {
+            Some(SyntaxKind::SOURCE_FILE) => self.is_new_item = true,
+            Some(SyntaxKind::ASSOCIATED_ITEM_LIST) => self.is_new_assoc_item = true,
+            _ => {}
         }
     }
 
@@ -110,12 +163,48 @@ impl<'a> CompletionContext<'a> {
                 .and_then(ast::PathType::cast)
                 .is_some();
 
+            let path_expr = path.syntax().parent().and_then(ast::PathExpr::cast);
+            self.is_path_expr = path_expr.is_some();
+
+            if let Some(path_expr) = path_expr {
+                // The parser takes the path as the iterable of a `for` loop, if the `in`
+                // keyword is missing
+                self.is_after_for_pattern = path_expr
+                    .syntax()
+                    .parent()
+                    .and_then(ast::ForExpr::cast)
+                    .map_or(false, |for_expr| {
+                        for_expr.pat().is_some()
+                            && !for_expr
+                                .syntax()
+                                .children_with_tokens()
+                                .any(|child| child.kind() == SyntaxKind::IN_KW)
+                    });
+
+                self.is_after_expr = follows_expr(path_expr.syntax());
+            }
+
+            self.is_in_loop_body = name_ref
+                .syntax()
+                .ancestors()
+                .take_while(|node| !ast::FunctionDef::can_cast(node.kind()))
+                .filter_map(ast::BlockExpr::cast)
+                .any(|block| is_loop_body(&block));
+
             if let Some(segment) = path.segment() {
                 if segment.has_colon_colon() {
                     return;
                 }
             }
 
+            // The qualifier comes before the point of insertion of the fake ident, so it
+            // should have the same range in the non-modified file
+            if let Some(qualifier) = path.qualifier() {
+                self.path_qualifier =
+                    find_node_with_range(original_file, qualifier.syntax().text_range());
+                return;
+            }
+
             self.is_trivial_path = true;
         }
 
@@ -140,6 +229,45 @@ fn is_node<N: AstNode>(node: &SyntaxNode) -> bool {
     }
 }
 
+/// Returns true if the given `block` is the body of a loop, as opposed to e.g.
+/// the iterable of a `for` loop.
+fn is_loop_body(block: &ast::BlockExpr) -> bool {
+    let Some(parent) = block.syntax().parent() else {
+        return false;
+    };
+
+    let loop_body = if let Some(loop_expr) = ast::LoopExpr::cast(parent.clone()) {
+        loop_expr.loop_body()
+    } else if let Some(while_expr) = ast::WhileExpr::cast(parent.clone()) {
+        while_expr.loop_body()
+    } else if let Some(for_expr) = ast::ForExpr::cast(parent) {
+        for_expr.loop_body()
+    } else {
+        None
+    };
+    loop_body.as_ref() == Some(block)
+}
+
+/// Returns true if the given `node` directly follows an expression on the same
+/// line, e.g. `bar` in `let foo = 5 bar`.
+fn follows_expr(node: &SyntaxNode) -> bool {
+    let mut token = node.first_token().and_then(|token| token.prev_token());
+    while let Some(trivia) = token.clone().filter(|token| token.kind().is_trivia()) {
+        if trivia.text().contains('\n') {
+            return false;
+        }
+        token = trivia.prev_token();
+    }
+
+    token.map_or(false, |token| {
+        let end = token.text_range().end();
+        token
+            .parent_ancestors()
+            .take_while(|node| node.text_range().end() == end)
+            .any(|node| ast::Expr::can_cast(node.kind()))
+    })
+}
+
 /// Returns a node that covers the specified range.
 fn find_node_with_range<N: AstNode>(syntax: &SyntaxNode, range: TextRange) -> Option<N> {
     syntax.covering_element(range).ancestors().find_map(N::cast)
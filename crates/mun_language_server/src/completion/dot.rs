@@ -1,4 +1,5 @@
 use mun_db::Upcast;
+use mun_hir::{HasVisibility, HirDatabase};
 
 use super::{CompletionContext, Completions};
 
@@ -16,10 +17,14 @@ pub(super) fn complete_dot(result: &mut Completions, ctx: &CompletionContext<'_>
         _ => return,
     };
 
-    // Get all the fields of the expression
+    // Get all the fields of the expression that are visible from the current scope
     if let Some(strukt) = receiver_ty.as_struct() {
+        let db: &dyn HirDatabase = ctx.db;
+        let visible_from = ctx.scope.module();
         for field in strukt.fields(ctx.db.upcast()) {
-            result.add_field(ctx, field);
+            if visible_from.map_or(true, |module| field.is_visible_from(db, module)) {
+                result.add_field(ctx, field);
+            }
         }
     };
 }
@@ -22,6 +22,7 @@ pub enum CompletionKind {
     /// Your usual "complete all valid identifiers".
     Reference,
     BuiltinType,
+    Keyword,
 }
 
 /// Type of completion used to provide hints to the user.
@@ -0,0 +1,223 @@
+use super::{CompletionContext, Completions};
+
+/// Adds completions to `result` for keywords that are valid at the position of
+/// the cursor. E.g.:
+/// ```mun
+/// fn foo() {
+///    loop {
+///        br$0
+///    }
+/// }
+/// ```
+pub(super) fn complete_keyword(result: &mut Completions, ctx: &CompletionContext<'_>) {
+    // Only `in` is valid after the pattern of a `for` loop
+    if ctx.is_after_for_pattern {
+        result.add_keyword("in");
+        return;
+    }
+
+    if ctx.is_new_item {
+        for keyword in ["fn", "struct", "type", "impl", "use", "pub", "extern"] {
+            result.add_keyword(keyword);
+        }
+    }
+
+    if ctx.is_new_assoc_item {
+        for keyword in ["fn", "pub"] {
+            result.add_keyword(keyword);
+        }
+    }
+
+    if ctx.is_trivial_path && ctx.is_path_expr {
+        for keyword in [
+            "let", "if", "loop", "while", "for", "return", "true", "false",
+        ] {
+            result.add_keyword(keyword);
+        }
+
+        if ctx.is_in_loop_body {
+            result.add_keyword("break");
+        }
+
+        if ctx.is_after_expr {
+            result.add_keyword("as");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{test_utils::completion_string, CompletionKind};
+
+    #[test]
+    fn test_item_keywords() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        fn foo() {}
+        $0
+        "#,
+            Some(CompletionKind::Keyword)
+        ), @r###"
+        kw fn
+        kw struct
+        kw type
+        kw impl
+        kw use
+        kw pub
+        kw extern
+        "###);
+    }
+
+    #[test]
+    fn test_assoc_item_keywords() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        struct Foo;
+        impl Foo {
+            $0
+        }
+        "#,
+            Some(CompletionKind::Keyword)
+        ), @r###"
+        kw fn
+        kw pub
+        "###);
+    }
+
+    #[test]
+    fn test_expr_keywords() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        fn foo() {
+            let bar = 0;
+            b$0
+        }
+        "#,
+            Some(CompletionKind::Keyword)
+        ), @r###"
+        kw let
+        kw if
+        kw loop
+        kw while
+        kw for
+        kw return
+        kw true
+        kw false
+        "###);
+    }
+
+    #[test]
+    fn test_loop_keywords() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        fn foo() {
+            while true {
+                b$0
+            }
+        }
+        "#,
+            Some(CompletionKind::Keyword)
+        ), @r###"
+        kw let
+        kw if
+        kw loop
+        kw while
+        kw for
+        kw return
+        kw true
+        kw false
+        kw break
+        "###);
+    }
+
+    #[test]
+    fn test_for_loop_keywords() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        fn foo() {
+            for i i$0
+        }
+        "#,
+            Some(CompletionKind::Keyword)
+        ), @r###"
+        kw in
+        "###);
+    }
+
+    #[test]
+    fn test_no_in_keyword_after_in() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        fn foo() {
+            for i in i$0
+        }
+        "#,
+            Some(CompletionKind::Keyword)
+        ), @r###"
+        kw let
+        kw if
+        kw loop
+        kw while
+        kw for
+        kw return
+        kw true
+        kw false
+        "###);
+    }
+
+    #[test]
+    fn test_cast_keyword() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        fn foo() {
+            let bar = 0 a$0
+        }
+        "#,
+            Some(CompletionKind::Keyword)
+        ), @r###"
+        kw let
+        kw if
+        kw loop
+        kw while
+        kw for
+        kw return
+        kw true
+        kw false
+        kw as
+        "###);
+    }
+
+    #[test]
+    fn test_no_cast_keyword_on_new_line() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        fn foo() {
+            let bar = 0
+            a$0
+        }
+        "#,
+            Some(CompletionKind::Keyword)
+        ), @r###"
+        kw let
+        kw if
+        kw loop
+        kw while
+        kw for
+        kw return
+        kw true
+        kw false
+        "###);
+    }
+
+    #[test]
+    fn test_no_keywords_in_type() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        fn foo() {
+            let bar: i$0 = 0;
+        }
+        "#,
+            Some(CompletionKind::Keyword)
+        ), @"");
+    }
+}
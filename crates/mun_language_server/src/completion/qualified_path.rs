@@ -0,0 +1,144 @@
+use mun_hir::{
+    semantics::PathResolution, AssocItem, HasVisibility, HirDatabase, Impl, ModuleDef, Ty,
+};
+
+use super::{CompletionContext, Completions};
+
+/// Adds completions to `result` for qualified paths. Qualified paths are paths
+/// that are preceded by another path and `::`. These complete to the items of
+/// a module or the associated functions of a type. E.g.:
+/// ```mun
+/// fn foo() -> Foo {
+///    Foo::$0
+/// }
+/// ```
+pub(super) fn complete_qualified_path(result: &mut Completions, ctx: &CompletionContext<'_>) {
+    let Some(path) = &ctx.path_qualifier else {
+        return;
+    };
+
+    let Some(resolution) = ctx.sema.resolve_path(path) else {
+        return;
+    };
+
+    let db: &dyn HirDatabase = ctx.db;
+    let visible_from = ctx.scope.module();
+    let ty = match resolution {
+        PathResolution::Def(ModuleDef::Module(module)) => {
+            for (name, def) in module.scope(db, visible_from) {
+                result.add_resolution(ctx, name.to_string(), &def);
+            }
+            return;
+        }
+        PathResolution::Def(ModuleDef::Struct(strukt)) => strukt.ty(db),
+        PathResolution::Def(ModuleDef::TypeAlias(alias)) => alias.target_type(db),
+        PathResolution::SelfType(impl_) => impl_.self_ty(db),
+        PathResolution::Def(ModuleDef::Function(_) | ModuleDef::PrimitiveType(_))
+        | PathResolution::Local(_) => return,
+    };
+
+    complete_associated_items(result, ctx, &ty);
+}
+
+/// Adds completions for all the associated items of `ty` that are visible from
+/// the current scope.
+fn complete_associated_items(result: &mut Completions, ctx: &CompletionContext<'_>, ty: &Ty) {
+    let db: &dyn HirDatabase = ctx.db;
+    let visible_from = ctx.scope.module();
+    for impl_ in Impl::all_for_type(db, ty) {
+        for item in impl_.items(db) {
+            match item {
+                AssocItem::Function(func) => {
+                    if visible_from.map_or(true, |module| func.is_visible_from(db, module)) {
+                        result.add_function(ctx, func);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{test_utils::completion_string, CompletionKind};
+
+    #[test]
+    fn test_module_items() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        //- /foo.mun
+        pub struct Bar;
+        pub fn baz() -> Bar { Bar }
+        fn private() {}
+
+        //- /mod.mun
+        fn main() {
+            foo::$0
+        }
+        "#,
+            Some(CompletionKind::Reference)
+        ), @r###"
+        fn baz -> Bar
+        st Bar
+        "###);
+    }
+
+    #[test]
+    fn test_associated_functions() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        struct Foo { a: i32 }
+        impl Foo {
+            fn new() -> Self { Self { a: 0 } }
+            fn get(self) -> i32 { self.a }
+        }
+        fn main() {
+            Foo::$0
+        }
+        "#,
+            Some(CompletionKind::Reference)
+        ), @r###"
+        fn new -> Foo
+        fn get -> i32
+        "###);
+    }
+
+    #[test]
+    fn test_self_associated_functions() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        struct Foo { a: i32 }
+        impl Foo {
+            fn new() -> Self { Self { a: 0 } }
+            fn default() -> Self { Self::$0 }
+        }
+        "#,
+            Some(CompletionKind::Reference)
+        ), @r###"
+        fn new     -> Foo
+        fn default -> Foo
+        "###);
+    }
+
+    #[test]
+    fn test_private_associated_functions() {
+        insta::assert_snapshot!(completion_string(
+            r#"
+        //- /foo.mun
+        pub struct Foo;
+        impl Foo {
+            pub fn new() -> Self { Foo }
+            fn private() {}
+        }
+
+        //- /mod.mun
+        fn main() {
+            foo::Foo::$0
+        }
+        "#,
+            Some(CompletionKind::Reference)
+        ), @r###"
+        fn new -> Foo
+        "###);
+    }
+}
@@ -1,26 +1,8 @@
 use crate::{
-    change_fixture::{ChangeFixture, RangeOrOffset},
+    change_fixture::position,
     completion::{CompletionItem, CompletionKind},
-    db::AnalysisDatabase,
-    FilePosition,
 };
 
-/// Creates an analysis database from a multi-file fixture and a position marked
-/// with `$0`.
-pub(crate) fn position(fixture: &str) -> (AnalysisDatabase, FilePosition) {
-    let change_fixture = ChangeFixture::parse(fixture);
-    let mut database = AnalysisDatabase::default();
-    database.apply_change(change_fixture.change);
-    let (file_id, range_or_offset) = change_fixture
-        .file_position
-        .expect("expected a marker ($0)");
-    let offset = match range_or_offset {
-        RangeOrOffset::Range(_) => panic!(),
-        RangeOrOffset::Offset(it) => it,
-    };
-    (database, FilePosition { file_id, offset })
-}
-
 /// Creates a list of completions for the specified code. The code must contain
 /// a cursor in the text indicated by `$0`
 pub(crate) fn completion_list(
@@ -1,5 +1,15 @@
-use mun_paths::AbsPathBuf;
+use std::{
+    env,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+};
+
+use mun_paths::{AbsPath, AbsPathBuf};
 use mun_project::ProjectManifest;
+use rustc_hash::FxHasher;
+use serde_derive::Deserialize;
+
+use crate::inlay_hints::InlayHintsConfig;
 
 /// The configuration used by the language server.
 #[derive(Debug, Clone)]
@@ -11,6 +21,29 @@ pub struct Config {
 
     /// A collection of projects discovered within the workspace
     pub discovered_projects: Option<Vec<ProjectManifest>>,
+
+    /// The directory in which the diagnostics of packages are cached between
+    /// sessions. If `None`, nothing is cached. Defaults to a directory for the
+    /// workspace in the cache directory of the user.
+    pub cache_dir: Option<AbsPathBuf>,
+
+    /// Which kinds of inlay hints are shown
+    pub inlay_hints: InlayHintsConfig,
+}
+
+/// The settings that the client can change, either through the initialization
+/// options or with a `workspace/didChangeConfiguration` notification. For
+/// example:
+///
+/// ```json
+/// { "inlayHints": { "typeHints": true, "parameterHints": false } }
+/// ```
+///
+/// Settings that are not specified are reset to their default value.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct ClientSettings {
+    inlay_hints: InlayHintsConfig,
 }
 
 impl Config {
@@ -18,14 +51,86 @@ impl Config {
     pub fn new(root_path: AbsPathBuf) -> Self {
         Self {
             watcher: FilesWatcher::Notify,
+            cache_dir: default_cache_dir(&root_path),
             root_dir: root_path,
             discovered_projects: None,
+            inlay_hints: InlayHintsConfig::default(),
+        }
+    }
+
+    /// Updates the configuration with the settings sent by the client. Invalid
+    /// settings are ignored.
+    pub fn update(&mut self, settings: &serde_json::Value) {
+        if settings.is_null() {
+            return;
+        }
+
+        match serde_json::from_value::<ClientSettings>(settings.clone()) {
+            Ok(settings) => {
+                self.inlay_hints = settings.inlay_hints;
+            }
+            Err(err) => log::warn!("invalid client settings: {err}"),
         }
     }
 }
 
+/// Returns the directory in which the language server caches data of the
+/// workspace at `root_path`. Every workspace has its own directory in the cache
+/// directory of the user, so nothing is written to the workspace itself.
+/// Returns `None` if the user has no cache directory.
+fn default_cache_dir(root_path: &AbsPath) -> Option<AbsPathBuf> {
+    let mut hasher = FxHasher::default();
+    root_path.hash(&mut hasher);
+
+    Some(
+        user_cache_dir()?
+            .join("mun")
+            .join("language_server")
+            .join(format!("{:016x}", hasher.finish())),
+    )
+}
+
+/// Returns the platform-specific cache directory of the user.
+fn user_cache_dir() -> Option<AbsPathBuf> {
+    let home_dir = || env::var_os("HOME").map(PathBuf::from);
+    let dir = if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home_dir().map(|home| home.join("Library").join("Caches"))
+    } else {
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home_dir().map(|home| home.join(".cache")))
+    };
+    dir?.try_into().ok()
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum FilesWatcher {
     Client,
     Notify,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use mun_paths::AbsPathBuf;
+
+    use super::default_cache_dir;
+
+    #[test]
+    fn cache_dir_outside_workspace() {
+        let root: AbsPathBuf = std::env::temp_dir().join("workspace").try_into().unwrap();
+        let other_root = root.join("other");
+
+        let Some(cache_dir) = default_cache_dir(&root) else {
+            // The user has no cache directory
+            return;
+        };
+        assert!(!cache_dir.starts_with(&root));
+        assert_eq!(default_cache_dir(&root), Some(cache_dir.clone()));
+        assert_ne!(default_cache_dir(&other_root), Some(cache_dir));
+    }
+}
@@ -2,13 +2,11 @@
 
 use std::panic;
 
-use mun_db::Upcast;
+use mun_db::{Canceled, CheckCanceled, Upcast};
 use mun_hir::HirDatabase;
 use mun_target::spec::Target;
 use salsa::{Database, Durability, Snapshot};
 
-use crate::cancelation::Canceled;
-
 /// The `AnalysisDatabase` provides the database for all analyses. A database is
 /// given input and produces output based on these inputs through the use of
 /// queries. These queries are memoized which enables us to not have to
@@ -58,7 +56,7 @@ impl salsa::Database for AnalysisDatabase {
         match event.kind {
             salsa::EventKind::DidValidateMemoizedValue { .. }
             | salsa::EventKind::WillExecute { .. } => {
-                self.check_canceled();
+                self.unwind_if_canceled();
             }
             salsa::EventKind::WillBlockOn { .. } => (),
         }
@@ -90,21 +88,14 @@ impl Upcast<dyn mun_hir::HirDatabase> for AnalysisDatabase {
 }
 
 impl AnalysisDatabase {
-    fn check_canceled(&self) {
-        if self.salsa_runtime().is_current_revision_canceled() {
-            Canceled::throw()
-        }
-    }
-
+    /// Runs `f` on this database. If the current revision is canceled while
+    /// `f` is running, `Err(Canceled)` is returned.
     pub fn catch_canceled<F, T>(&self, f: F) -> Result<T, Canceled>
     where
         Self: Sized + panic::RefUnwindSafe,
         F: FnOnce(&Self) -> T + panic::UnwindSafe,
     {
-        panic::catch_unwind(|| f(self)).map_err(|err| match err.downcast::<Canceled>() {
-            Ok(canceled) => *canceled,
-            Err(payload) => panic::resume_unwind(payload),
-        })
+        Canceled::catch(|| f(self))
     }
 }
 
@@ -0,0 +1,164 @@
+//! Classification of names and name references in the syntax tree. This
+//! module figures out which HIR definition a token in the source refers to,
+//! which is the basis of go-to-definition and find-all-references.
+
+use mun_hir::{
+    semantics::{Local, PathResolution, Semantics},
+    Field, HirDatabase, ModuleDef,
+};
+use mun_syntax::{ast, match_ast, AstNode, SyntaxKind, SyntaxNode, SyntaxToken, TextSize};
+
+/// A definition that can be referred to by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Definition {
+    ModuleDef(ModuleDef),
+    Field(Field),
+    Local(Local),
+}
+
+impl Definition {
+    /// Returns the name of the definition as it appears in the source.
+    pub fn name(self, db: &dyn HirDatabase) -> Option<String> {
+        let name = match self {
+            Definition::ModuleDef(def) => match def {
+                ModuleDef::Module(it) => return it.name(db),
+                ModuleDef::Function(it) => it.name(db),
+                ModuleDef::Struct(it) => it.name(db),
+                ModuleDef::TypeAlias(it) => it.name(db),
+                ModuleDef::PrimitiveType(it) => return Some(it.to_string()),
+            },
+            Definition::Field(it) => it.name(db),
+            Definition::Local(it) => it.name(db)?,
+        };
+        Some(name.to_string())
+    }
+
+    /// Converts the result of path resolution into a `Definition`.
+    fn from_path_resolution(db: &dyn HirDatabase, resolution: PathResolution) -> Option<Self> {
+        let def = match resolution {
+            PathResolution::Def(def) => Definition::ModuleDef(def),
+            PathResolution::Local(local) => Definition::Local(local),
+            PathResolution::SelfType(impl_) => {
+                Definition::ModuleDef(impl_.self_ty(db).as_struct()?.into())
+            }
+        };
+        Some(def)
+    }
+}
+
+/// The classification of a token that refers to a definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NameRefClass {
+    /// The token refers to a single definition.
+    Definition(Definition),
+
+    /// The token is a field of a record literal written with the shorthand
+    /// syntax, e.g. `a` in `Foo { a }`. It refers to both a field and a
+    /// local.
+    FieldShorthand { local: Local, field: Field },
+}
+
+impl NameRefClass {
+    /// Returns true if this instance refers to `def`.
+    pub fn refers_to(self, def: Definition) -> bool {
+        match self {
+            NameRefClass::Definition(it) => it == def,
+            NameRefClass::FieldShorthand { local, field } => {
+                def == Definition::Local(local) || def == Definition::Field(field)
+            }
+        }
+    }
+}
+
+/// The classification of a token in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenClass {
+    /// The token is the name of a definition, e.g. `foo` in `fn foo()`.
+    Name(Definition),
+
+    /// The token refers to one or more definitions.
+    NameRef(NameRefClass),
+}
+
+/// Returns the token at `offset` that might refer to a definition. If the
+/// offset lies between two tokens, the identifier is preferred.
+pub(crate) fn token_at_offset(file: &SyntaxNode, offset: TextSize) -> Option<SyntaxToken> {
+    file.token_at_offset(offset)
+        .find(|token| is_reference_token(token.kind()))
+}
+
+/// Returns true if a token of the given `kind` might refer to a definition.
+pub(crate) fn is_reference_token(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::IDENT | SyntaxKind::SELF_KW | SyntaxKind::INT_NUMBER | SyntaxKind::INDEX
+    )
+}
+
+/// Determines what the specified `token` defines or refers to.
+pub(crate) fn classify_token(sema: &Semantics<'_>, token: &SyntaxToken) -> Option<TokenClass> {
+    let parent = token.parent()?;
+    match_ast! {
+        match parent {
+            ast::Name(name) => classify_name(sema, &name).map(TokenClass::Name),
+            ast::NameRef(name_ref) => classify_name_ref(sema, &name_ref).map(TokenClass::NameRef),
+            ast::PathSegment(segment) => {
+                // A path segment without a name, e.g. `self`
+                let resolution = sema.resolve_path(&segment.parent_path())?;
+                let def = Definition::from_path_resolution(sema.db, resolution)?;
+                Some(TokenClass::NameRef(NameRefClass::Definition(def)))
+            },
+            ast::FieldExpr(field_expr) => {
+                // A tuple field access, e.g. `foo.0`
+                let field = sema.resolve_field(&field_expr)?;
+                Some(TokenClass::NameRef(NameRefClass::Definition(Definition::Field(field))))
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Determines the definition that is defined by the specified `name`.
+pub(crate) fn classify_name(sema: &Semantics<'_>, name: &ast::Name) -> Option<Definition> {
+    let parent = name.syntax().parent()?;
+    let def = match_ast! {
+        match parent {
+            ast::FunctionDef(it) => Definition::ModuleDef(sema.to_def(&it)?.into()),
+            ast::StructDef(it) => Definition::ModuleDef(sema.to_def(&it)?.into()),
+            ast::TypeAliasDef(it) => Definition::ModuleDef(sema.to_def(&it)?.into()),
+            ast::RecordFieldDef(it) => Definition::Field(sema.to_def(&it)?),
+            ast::BindPat(it) => Definition::Local(sema.to_def(&it)?),
+            ast::SelfParam(it) => Definition::Local(sema.to_def(&it)?),
+            _ => return None,
+        }
+    };
+    Some(def)
+}
+
+/// Determines the definition(s) that the specified `name_ref` refers to.
+pub(crate) fn classify_name_ref(
+    sema: &Semantics<'_>,
+    name_ref: &ast::NameRef,
+) -> Option<NameRefClass> {
+    let parent = name_ref.syntax().parent()?;
+    match_ast! {
+        match parent {
+            ast::PathSegment(segment) => {
+                let resolution = sema.resolve_path(&segment.parent_path())?;
+                Definition::from_path_resolution(sema.db, resolution).map(NameRefClass::Definition)
+            },
+            ast::FieldExpr(field_expr) => {
+                let field = sema.resolve_field(&field_expr)?;
+                Some(NameRefClass::Definition(Definition::Field(field)))
+            },
+            ast::RecordField(record_field) => {
+                let (field, local) = sema.resolve_record_field(&record_field)?;
+                Some(match local {
+                    Some(local) => NameRefClass::FieldShorthand { local, field },
+                    None => NameRefClass::Definition(Definition::Field(field)),
+                })
+            },
+            _ => None,
+        }
+    }
+}
@@ -1,7 +1,10 @@
+mod fixes;
+
 use std::cell::RefCell;
 
+pub use fixes::Fix;
 use mun_diagnostics::DiagnosticForWith;
-use mun_hir::{AstDatabase, InFile, Module};
+use mun_hir::{diagnostics::Severity, AstDatabase, InFile, Module};
 use mun_hir_input::{FileId, ModuleId, PackageId, SourceDatabase};
 use mun_syntax::{Location, TextRange};
 
@@ -18,8 +21,8 @@ pub struct Diagnostic {
     pub message: String,
     pub range: TextRange,
     pub additional_annotations: Vec<SourceAnnotation>,
-    // pub fix: Option<SourceChange>,
-    // pub severity: Severity,
+    pub fix: Option<Fix>,
+    pub severity: Severity,
 }
 
 /// Converts a location to a a range for use in diagnostics
@@ -40,11 +43,15 @@ pub(crate) fn diagnostics(db: &AnalysisDatabase, file_id: FileId) -> Vec<Diagnos
         message: format!("parse error: {err}"),
         range: location_to_range(err.location()),
         additional_annotations: vec![],
+        fix: None,
+        severity: Severity::Error,
     }));
 
-    // Add all HIR diagnostics
+    // Add all HIR diagnostics and the warnings of lints
     let result = RefCell::new(result);
     let mut sink = mun_hir::diagnostics::DiagnosticSink::new(|d| {
+        let mut fix = fixes::fix(db, d);
+        let severity = d.severity();
         result.borrow_mut().push(d.with_diagnostic(db, |d| {
             Diagnostic {
                 message: format!("{}\n{}", d.title(), d.footer().join("\n"))
@@ -59,6 +66,8 @@ pub(crate) fn diagnostics(db: &AnalysisDatabase, file_id: FileId) -> Vec<Diagnos
                         range: annotation.range,
                     })
                     .collect(),
+                fix: fix.take(),
+                severity,
             }
         }));
     });
@@ -70,7 +79,9 @@ pub(crate) fn diagnostics(db: &AnalysisDatabase, file_id: FileId) -> Vec<Diagnos
             package: package_id,
             local_id,
         };
-        Module::from(module_id).diagnostics(db, &mut sink);
+        let module = Module::from(module_id);
+        module.diagnostics(db, &mut sink);
+        module.lints(db, &mut sink);
     }
     drop(sink);
 
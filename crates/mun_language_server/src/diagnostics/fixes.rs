@@ -0,0 +1,425 @@
+//! Quick fixes for diagnostics. A fix is an edit of the source code that
+//! resolves the problem reported by a diagnostic, e.g. adding the fields that
+//! are missing from a struct literal. Editors show them as code actions.
+
+use mun_hir::{
+    diagnostics::{MismatchedType, MissingFields, UnusedParameter, UnusedVariable},
+    AstDatabase, HirDatabase, HirDisplay, Name, Ty, TyKind,
+};
+use mun_hir_input::FileId;
+use mun_syntax::{
+    ast, AstNode, Direction, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TextRange,
+};
+use ra_ap_text_edit::TextEdit;
+use rustc_hash::FxHashMap;
+
+use crate::{db::AnalysisDatabase, source_change::SourceChange};
+
+/// An edit that resolves the problem reported by a diagnostic.
+#[derive(Debug, Clone)]
+pub struct Fix {
+    /// A short description of the fix that is shown to the user
+    pub label: String,
+
+    /// The edits to apply
+    pub source_change: SourceChange,
+}
+
+impl Fix {
+    /// Constructs a fix that edits a single file.
+    fn new(label: impl Into<String>, file_id: FileId, edit: TextEdit) -> Self {
+        let mut source_file_edits = FxHashMap::default();
+        source_file_edits.insert(file_id, edit);
+        Fix {
+            label: label.into(),
+            source_change: source_file_edits.into(),
+        }
+    }
+}
+
+/// A diagnostic for which a fix can be computed.
+trait DiagnosticWithFix {
+    /// Returns the fix for the diagnostic, or `None` if the problem cannot be
+    /// resolved automatically.
+    fn fix(&self, db: &AnalysisDatabase) -> Option<Fix>;
+}
+
+/// Returns the fix for the specified diagnostic, if there is one.
+pub(crate) fn fix(db: &AnalysisDatabase, diagnostic: &dyn mun_hir::Diagnostic) -> Option<Fix> {
+    if let Some(d) = diagnostic.downcast_ref::<MissingFields>() {
+        d.fix(db)
+    } else if let Some(d) = diagnostic.downcast_ref::<MismatchedType>() {
+        d.fix(db)
+    } else if let Some(d) = diagnostic.downcast_ref::<UnusedVariable>() {
+        d.fix(db)
+    } else if let Some(d) = diagnostic.downcast_ref::<UnusedParameter>() {
+        d.fix(db)
+    } else {
+        None
+    }
+}
+
+/// Adds the missing fields to a struct literal, initialized with a default
+/// value, e.g. `Foo { a: 1 }` becomes `Foo { a: 1, b: 0.0 }`.
+impl DiagnosticWithFix for MissingFields {
+    fn fix(&self, db: &AnalysisDatabase) -> Option<Fix> {
+        let root = db.parse(self.file).syntax_node();
+        let record_lit = ast::RecordLit::cast(self.fields.to_node(&root))?;
+        let field_list = record_lit.record_field_list()?;
+        let l_curly = token_of_kind(field_list.syntax(), SyntaxKind::L_CURLY)?;
+        let r_curly = token_of_kind(field_list.syntax(), SyntaxKind::R_CURLY)?;
+
+        let strukt = self.struct_ty.as_struct()?;
+        let new_fields = self
+            .field_names
+            .iter()
+            .map(|name| {
+                let value = strukt
+                    .field(db, name)
+                    .map_or("()", |field| default_value(db, &field.ty(db)));
+                format!("{name}: {value}")
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let edit = match field_list.fields().last() {
+            // Replace everything between the braces, e.g. `Foo {}` becomes `Foo { a: 0 }`
+            None => TextEdit::replace(
+                TextRange::new(l_curly.text_range().end(), r_curly.text_range().start()),
+                format!(" {new_fields} "),
+            ),
+            Some(last_field) => {
+                let trailing_comma = last_field
+                    .syntax()
+                    .siblings_with_tokens(Direction::Next)
+                    .skip(1)
+                    .find(|element| !element.kind().is_trivia())
+                    .filter(|element| element.kind() == SyntaxKind::COMMA);
+                match trailing_comma {
+                    Some(comma) => {
+                        TextEdit::insert(comma.text_range().end(), format!(" {new_fields},"))
+                    }
+                    None => TextEdit::insert(
+                        last_field.syntax().text_range().end(),
+                        format!(", {new_fields}"),
+                    ),
+                }
+            }
+        };
+
+        Some(Fix::new("Add missing fields", self.file, edit))
+    }
+}
+
+/// Changes the return type of a function to the type of the value that it
+/// returns, e.g. `fn foo() -> i32 { true }` becomes `fn foo() -> bool { true
+/// }`.
+impl DiagnosticWithFix for MismatchedType {
+    fn fix(&self, db: &AnalysisDatabase) -> Option<Fix> {
+        // Only suggest types that are fully known, e.g. not `{float}`
+        if self.found.is_unknown()
+            || self.found.is_never()
+            || matches!(self.found.interned(), TyKind::InferenceVar(_))
+        {
+            return None;
+        }
+
+        let root = db.parse(self.file).syntax_node();
+        let function = returning_function(&self.expr.to_node(&root))?;
+        let found = self.found.display(db).to_string();
+
+        let edit = match function.ret_type().and_then(|ret_type| ret_type.type_ref()) {
+            Some(type_ref) => TextEdit::replace(type_ref.syntax().text_range(), found.clone()),
+            None => TextEdit::insert(
+                function.param_list()?.syntax().text_range().end(),
+                format!(" -> {found}"),
+            ),
+        };
+
+        Some(Fix::new(
+            format!("Change return type to `{found}`"),
+            self.file,
+            edit,
+        ))
+    }
+}
+
+/// Prefixes the name of an unused variable with an underscore, which
+/// suppresses the warning, e.g. `let a = 1;` becomes `let _a = 1;`.
+impl DiagnosticWithFix for UnusedVariable {
+    fn fix(&self, _db: &AnalysisDatabase) -> Option<Fix> {
+        Some(prefix_with_underscore(
+            self.file,
+            self.pat.range(),
+            &self.name,
+        ))
+    }
+}
+
+/// Prefixes the name of an unused parameter with an underscore, which
+/// suppresses the warning, e.g. `fn foo(a: i32)` becomes `fn foo(_a: i32)`.
+impl DiagnosticWithFix for UnusedParameter {
+    fn fix(&self, _db: &AnalysisDatabase) -> Option<Fix> {
+        Some(prefix_with_underscore(
+            self.file,
+            self.pat.range(),
+            &self.name,
+        ))
+    }
+}
+
+/// Returns a fix that inserts an underscore in front of the binding at `range`.
+fn prefix_with_underscore(file_id: FileId, range: TextRange, name: &Name) -> Fix {
+    Fix::new(
+        format!("Prefix `{name}` with an underscore"),
+        file_id,
+        TextEdit::insert(range.start(), String::from("_")),
+    )
+}
+
+/// Returns the function whose return value is `expr`: either the tail
+/// expression of its body, or the value of a `return` expression.
+fn returning_function(expr: &SyntaxNode) -> Option<ast::FunctionDef> {
+    let parent = expr.parent()?;
+    if let Some(block) = ast::BlockExpr::cast(parent.clone()) {
+        let function = ast::FunctionDef::cast(block.syntax().parent()?)?;
+        return block
+            .expr()
+            .filter(|tail| tail.syntax() == expr)
+            .and(Some(function));
+    }
+    if ast::ReturnExpr::can_cast(parent.kind()) {
+        return parent.ancestors().find_map(ast::FunctionDef::cast);
+    }
+    None
+}
+
+/// Returns the first child token of `node` with the specified kind.
+fn token_of_kind(node: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxToken> {
+    node.children_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .find(|token| token.kind() == kind)
+}
+
+/// Returns the literal that is used to initialize a new field of the
+/// specified type. Non-primitive types are initialized with `()`, which
+/// results in a type error that points out that the value still needs to be
+/// filled in.
+fn default_value(db: &dyn HirDatabase, ty: &Ty) -> &'static str {
+    match ty.interned() {
+        TyKind::Bool => "false",
+        TyKind::Int(_) => "0",
+        TyKind::Float(_) => "0.0",
+        TyKind::TypeAlias(alias) => default_value(db, &alias.target_type(db)),
+        _ => "()",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mun_hir_input::SourceDatabase;
+
+    use crate::{change_fixture::ChangeFixture, db::AnalysisDatabase};
+
+    /// Applies the fixes of all diagnostics in the first file of the fixture
+    /// one at a time. Returns the label of every fix, followed by the text of
+    /// the file after applying it.
+    fn fixes_string(fixture: &str) -> String {
+        let fixture = ChangeFixture::parse(fixture);
+        let file_id = fixture.files[0];
+        let mut db = AnalysisDatabase::default();
+        db.apply_change(fixture.change);
+
+        super::super::diagnostics(&db, file_id)
+            .into_iter()
+            .filter_map(|diagnostic| diagnostic.fix)
+            .map(|fix| {
+                let mut text = db.file_text(file_id).to_string();
+                for edit in fix.source_change.source_file_edits.into_values() {
+                    edit.apply(&mut text);
+                }
+                format!("// {}\n{}", fix.label, text.trim())
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    #[test]
+    fn add_missing_fields() {
+        insta::assert_snapshot!(fixes_string(
+            r#"
+        struct Foo { a: i32, b: f64, c: bool, d: Bar }
+        struct Bar;
+        fn main() {
+            let a = Foo {};
+            let b = Foo { a: 1 };
+            let c = Foo { a: 1, b: 2.0, };
+        }
+        "#,
+        ), @r###"
+        // Add missing fields
+        struct Foo { a: i32, b: f64, c: bool, d: Bar }
+        struct Bar;
+        fn main() {
+            let a = Foo { a: 0, b: 0.0, c: false, d: () };
+            let b = Foo { a: 1 };
+            let c = Foo { a: 1, b: 2.0, };
+        }
+
+        // Add missing fields
+        struct Foo { a: i32, b: f64, c: bool, d: Bar }
+        struct Bar;
+        fn main() {
+            let a = Foo {};
+            let b = Foo { a: 1, b: 0.0, c: false, d: () };
+            let c = Foo { a: 1, b: 2.0, };
+        }
+
+        // Add missing fields
+        struct Foo { a: i32, b: f64, c: bool, d: Bar }
+        struct Bar;
+        fn main() {
+            let a = Foo {};
+            let b = Foo { a: 1 };
+            let c = Foo { a: 1, b: 2.0, c: false, d: (), };
+        }
+
+        // Prefix `a` with an underscore
+        struct Foo { a: i32, b: f64, c: bool, d: Bar }
+        struct Bar;
+        fn main() {
+            let _a = Foo {};
+            let b = Foo { a: 1 };
+            let c = Foo { a: 1, b: 2.0, };
+        }
+
+        // Prefix `b` with an underscore
+        struct Foo { a: i32, b: f64, c: bool, d: Bar }
+        struct Bar;
+        fn main() {
+            let a = Foo {};
+            let _b = Foo { a: 1 };
+            let c = Foo { a: 1, b: 2.0, };
+        }
+
+        // Prefix `c` with an underscore
+        struct Foo { a: i32, b: f64, c: bool, d: Bar }
+        struct Bar;
+        fn main() {
+            let a = Foo {};
+            let b = Foo { a: 1 };
+            let _c = Foo { a: 1, b: 2.0, };
+        }
+        "###);
+    }
+
+    #[test]
+    fn prefix_with_underscore() {
+        insta::assert_snapshot!(fixes_string(
+            r#"
+        pub fn foo(a: i32, b: i32) -> i32 {
+            let c = a;
+            a
+        }
+        "#,
+        ), @r###"
+        // Prefix `b` with an underscore
+        pub fn foo(a: i32, _b: i32) -> i32 {
+            let c = a;
+            a
+        }
+
+        // Prefix `c` with an underscore
+        pub fn foo(a: i32, b: i32) -> i32 {
+            let _c = a;
+            a
+        }
+        "###);
+    }
+
+    #[test]
+    fn change_return_type() {
+        insta::assert_snapshot!(fixes_string(
+            r#"
+        struct Foo;
+        fn foo() -> i32 { true }
+        fn bar() { Foo }
+        fn baz(a: bool) -> i32 {
+            if a {
+                return 1.0;
+            }
+            5
+        }
+        fn qux(a: bool) -> i32 {
+            if a {
+                return false;
+            }
+            5
+        }
+        fn main() -> i32 {
+            foo(true) + unknown
+        }
+        "#,
+        ), @r###"
+        // Change return type to `bool`
+        struct Foo;
+        fn foo() -> bool { true }
+        fn bar() { Foo }
+        fn baz(a: bool) -> i32 {
+            if a {
+                return 1.0;
+            }
+            5
+        }
+        fn qux(a: bool) -> i32 {
+            if a {
+                return false;
+            }
+            5
+        }
+        fn main() -> i32 {
+            foo(true) + unknown
+        }
+
+        // Change return type to `Foo`
+        struct Foo;
+        fn foo() -> i32 { true }
+        fn bar() -> Foo { Foo }
+        fn baz(a: bool) -> i32 {
+            if a {
+                return 1.0;
+            }
+            5
+        }
+        fn qux(a: bool) -> i32 {
+            if a {
+                return false;
+            }
+            5
+        }
+        fn main() -> i32 {
+            foo(true) + unknown
+        }
+
+        // Change return type to `bool`
+        struct Foo;
+        fn foo() -> i32 { true }
+        fn bar() { Foo }
+        fn baz(a: bool) -> i32 {
+            if a {
+                return 1.0;
+            }
+            5
+        }
+        fn qux(a: bool) -> bool {
+            if a {
+                return false;
+            }
+            5
+        }
+        fn main() -> i32 {
+            foo(true) + unknown
+        }
+        "###);
+    }
+}
@@ -0,0 +1,222 @@
+//! Persists the diagnostics of packages to disk between sessions of the
+//! language server.
+//!
+//! Only the diagnostics of all the files of a package are cached, together
+//! with a SHA-256 fingerprint of the contents of the package and its
+//! dependencies. The memoized queries of the salsa database, e.g. item trees,
+//! def maps and inference results, are not stored, so other requests still
+//! analyze the package. When the language server starts and a package didn't
+//! change since the last session, its diagnostics are published directly from
+//! the cache.
+//!
+//! A cache is only used by the build of the language server that wrote it,
+//! identified by the commit it was built from, see `build.rs`.
+
+use std::{fs, path::Path};
+
+use lsp_types::Url;
+use mun_paths::AbsPathBuf;
+use rustc_hash::FxHashMap;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::analysis::Fingerprint;
+
+/// The name of the file in the cache directory that stores the cache.
+const CACHE_FILE_NAME: &str = "diagnostics-cache.json";
+
+/// Identifies the build of the language server. The diagnostics of a different
+/// build cannot be trusted.
+const BUILD_ID: &str = env!("MUN_LANGUAGE_SERVER_BUILD_ID");
+
+/// The diagnostics of all the files of a package.
+pub(crate) type PackageDiagnostics = Vec<(Url, Vec<lsp_types::Diagnostic>)>;
+
+/// The cached diagnostics of all packages, keyed by the path of their
+/// manifest.
+#[derive(Debug)]
+pub(crate) struct DiagnosticsCache {
+    /// The directory where the cache is stored or `None` if the cache is only
+    /// kept in memory.
+    dir: Option<AbsPathBuf>,
+
+    /// The cached diagnostics
+    contents: CacheContents,
+
+    /// True if the contents changed since the cache was last loaded or saved
+    dirty: bool,
+}
+
+/// The contents of the cache as it is stored on disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheContents {
+    /// The build of the language server that created the cache
+    build_id: String,
+
+    /// The cached diagnostics per package
+    packages: FxHashMap<String, PackageEntry>,
+}
+
+/// The cached diagnostics of a single package.
+#[derive(Debug, Serialize, Deserialize)]
+struct PackageEntry {
+    /// The fingerprint of the contents of the package and its dependencies at
+    /// the time of analysis.
+    fingerprint: Fingerprint,
+
+    /// The diagnostics of all the files in the package
+    diagnostics: PackageDiagnostics,
+}
+
+impl DiagnosticsCache {
+    /// Loads the cache from the specified directory. If no cache exists or the
+    /// cache cannot be read, an empty cache is returned. The cache is written
+    /// to the same directory when calling [`DiagnosticsCache::save`].
+    pub fn load(dir: Option<AbsPathBuf>) -> Self {
+        let contents = dir
+            .as_ref()
+            .and_then(|dir| {
+                let bytes = fs::read(dir.join(CACHE_FILE_NAME)).ok()?;
+                serde_json::from_slice::<CacheContents>(&bytes)
+                    .map_err(|err| log::warn!("could not read diagnostics cache: {err}"))
+                    .ok()
+            })
+            .filter(|contents| contents.build_id == BUILD_ID)
+            .unwrap_or_else(CacheContents::new);
+
+        DiagnosticsCache {
+            dir,
+            contents,
+            dirty: false,
+        }
+    }
+
+    /// Returns the cached diagnostics of the package with the specified
+    /// manifest, if the contents of the package match `fingerprint`.
+    pub fn get(
+        &self,
+        manifest_path: &Path,
+        fingerprint: Fingerprint,
+    ) -> Option<&PackageDiagnostics> {
+        self.contents
+            .packages
+            .get(&*manifest_path.to_string_lossy())
+            .filter(|entry| entry.fingerprint == fingerprint)
+            .map(|entry| &entry.diagnostics)
+    }
+
+    /// Stores the diagnostics of the package with the specified manifest and
+    /// the fingerprint of its contents.
+    pub fn insert(
+        &mut self,
+        manifest_path: &Path,
+        fingerprint: Fingerprint,
+        diagnostics: PackageDiagnostics,
+    ) {
+        self.contents.packages.insert(
+            manifest_path.to_string_lossy().into_owned(),
+            PackageEntry {
+                fingerprint,
+                diagnostics,
+            },
+        );
+        self.dirty = true;
+    }
+
+    /// Writes the cache to disk. Does nothing if the cache has no directory or
+    /// if nothing changed since the cache was last loaded or saved.
+    pub fn save(&mut self) -> anyhow::Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        if !self.dirty {
+            return Ok(());
+        }
+
+        fs::create_dir_all(dir)?;
+
+        // Write to a temporary file first to never leave a partially written cache
+        // behind.
+        let path = dir.join(CACHE_FILE_NAME);
+        let tmp_path = dir.join(format!("{CACHE_FILE_NAME}.tmp"));
+        fs::write(&tmp_path, serde_json::to_vec(&self.contents)?)?;
+        fs::rename(tmp_path, path)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+impl CacheContents {
+    fn new() -> Self {
+        CacheContents {
+            build_id: BUILD_ID.to_owned(),
+            packages: FxHashMap::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryInto, path::Path};
+
+    use lsp_types::{Diagnostic, Position, Range, Url};
+    use mun_paths::AbsPathBuf;
+
+    use super::DiagnosticsCache;
+
+    fn diagnostics() -> super::PackageDiagnostics {
+        vec![(
+            Url::parse("file:///foo/src/mod.mun").unwrap(),
+            vec![Diagnostic::new_simple(
+                Range::new(Position::new(0, 0), Position::new(0, 3)),
+                String::from("unresolved value `foo`"),
+            )],
+        )]
+    }
+
+    #[test]
+    fn roundtrip() {
+        let dir = tempdir::TempDir::new("diagnostics_cache").unwrap();
+        let dir: AbsPathBuf = dir.path().to_path_buf().try_into().unwrap();
+        let manifest_path = Path::new("/foo/mun.toml");
+
+        let mut cache = DiagnosticsCache::load(Some(dir.clone()));
+        assert!(cache.get(manifest_path, [1; 32]).is_none());
+        cache.insert(manifest_path, [1; 32], diagnostics());
+        cache.save().unwrap();
+
+        let cache = DiagnosticsCache::load(Some(dir));
+        assert_eq!(cache.get(manifest_path, [1; 32]), Some(&diagnostics()));
+        assert!(cache.get(manifest_path, [2; 32]).is_none());
+        assert!(cache.get(Path::new("/bar/mun.toml"), [1; 32]).is_none());
+    }
+
+    #[test]
+    fn build_id_mismatch() {
+        let dir = tempdir::TempDir::new("diagnostics_cache").unwrap();
+        let dir: AbsPathBuf = dir.path().to_path_buf().try_into().unwrap();
+        let manifest_path = Path::new("/foo/mun.toml");
+
+        let mut cache = DiagnosticsCache::load(Some(dir.clone()));
+        cache.contents.build_id = String::from("0000000");
+        cache.insert(manifest_path, [1; 32], diagnostics());
+        cache.save().unwrap();
+
+        let cache = DiagnosticsCache::load(Some(dir));
+        assert!(cache.get(manifest_path, [1; 32]).is_none());
+    }
+
+    #[test]
+    fn save_only_when_changed() {
+        let dir = tempdir::TempDir::new("diagnostics_cache").unwrap();
+        let dir: AbsPathBuf = dir.path().to_path_buf().try_into().unwrap();
+        let cache_path = dir.join(super::CACHE_FILE_NAME);
+
+        let mut cache = DiagnosticsCache::load(Some(dir));
+        cache.save().unwrap();
+        assert!(!cache_path.exists());
+
+        cache.insert(Path::new("/foo/mun.toml"), [1; 32], diagnostics());
+        cache.save().unwrap();
+        assert!(cache_path.exists());
+    }
+}
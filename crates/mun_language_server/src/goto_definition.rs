@@ -0,0 +1,229 @@
+use mun_hir::semantics::Semantics;
+use mun_syntax::AstNode;
+
+use crate::{
+    db::AnalysisDatabase,
+    definition::{classify_token, token_at_offset, NameRefClass, TokenClass},
+    navigation_target::{NavigationTarget, ToNav},
+    FilePosition,
+};
+
+/// Returns the definition(s) of the item at the given position. Returns `None`
+/// if there is nothing at the position that refers to a definition.
+///
+/// For the name of a definition itself, the definition is returned. For a
+/// field of a record literal that uses the shorthand syntax (e.g. `Foo { a }`)
+/// both the field and the local are returned.
+pub(crate) fn goto_definition(
+    db: &AnalysisDatabase,
+    position: FilePosition,
+) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let token = token_at_offset(file.syntax(), position.offset)?;
+
+    let targets = match classify_token(&sema, &token)? {
+        TokenClass::Name(def) | TokenClass::NameRef(NameRefClass::Definition(def)) => {
+            def.to_nav(db).into_iter().collect()
+        }
+        TokenClass::NameRef(NameRefClass::FieldShorthand { local, field }) => {
+            [field.to_nav(db), local.to_nav(db)]
+                .into_iter()
+                .flatten()
+                .collect()
+        }
+    };
+
+    Some(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use mun_hir_input::SourceDatabase;
+
+    use crate::change_fixture::position;
+
+    /// Returns a string representation of the definitions of the item at the
+    /// cursor (`$0`). Every definition is written as the path of the file, the
+    /// range to navigate to and the text in that range.
+    fn goto_definition_string(fixture: &str) -> String {
+        let (db, position) = position(fixture);
+        let targets = super::goto_definition(&db, position).expect("nothing at the cursor");
+        targets
+            .into_iter()
+            .map(|target| {
+                let source_root = db.source_root(db.file_source_root(target.file_id));
+                let text = db.file_text(target.file_id);
+                let range = target.focus_or_full_range();
+                format!(
+                    "{} {:?}: {}",
+                    source_root.relative_path(target.file_id),
+                    range,
+                    &text[range]
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn goto_function() {
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        fn foo() -> i32 { 3 }
+        fn main() -> i32 { fo$0o() }
+        "#
+        ), @r###"
+        mod.mun 3..6: foo
+        "###);
+    }
+
+    #[test]
+    fn goto_local() {
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        fn main(a: i32) -> i32 {
+            let b = a;
+            let b = b$0 + a;
+            b
+        }
+        "#
+        ), @r###"
+        mod.mun 33..34: b
+        "###);
+    }
+
+    #[test]
+    fn goto_struct_and_fields() {
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        struct Foo { a: i32 }
+        fn main() -> i32 {
+            let foo = Fo$0o { a: 3 };
+            foo.a
+        }
+        "#
+        ), @r###"
+        mod.mun 7..10: Foo
+        "###);
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        struct Foo { a: i32 }
+        fn main() -> i32 {
+            let foo = Foo { a: 3 };
+            foo.$0a
+        }
+        "#
+        ), @r###"
+        mod.mun 13..14: a
+        "###);
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        struct Foo(i32, f32);
+        fn main() -> f32 {
+            let foo = Foo(3, 4.0);
+            foo.$01
+        }
+        "#
+        ), @r###"
+        mod.mun 16..19: f32
+        "###);
+    }
+
+    #[test]
+    fn goto_field_shorthand() {
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        struct Foo { a: i32 }
+        fn main() -> Foo {
+            let a = 3;
+            Foo { $0a }
+        }
+        "#
+        ), @r###"
+        mod.mun 13..14: a
+        mod.mun 49..50: a
+        "###);
+    }
+
+    #[test]
+    fn goto_self() {
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        struct Foo { a: i32 }
+        impl Foo {
+            fn new() -> Self { Self { a: 0 } }
+            fn get(self) -> i32 { se$0lf.a }
+        }
+        "#
+        ), @r###"
+        mod.mun 83..87: self
+        "###);
+    }
+
+    #[test]
+    fn goto_associated_function() {
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        struct Foo { a: i32 }
+        impl Foo {
+            fn new() -> Self { Self { a: 0 } }
+        }
+        fn main() -> Foo { Foo::n$0ew() }
+        "#
+        ), @r###"
+        mod.mun 40..43: new
+        "###);
+    }
+
+    #[test]
+    fn goto_other_file() {
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        //- /foo.mun
+        pub struct Bar;
+        pub fn baz() -> Bar { Bar }
+
+        //- /mod.mun
+        use foo::Bar;
+        fn main() -> Bar { foo::ba$0z() }
+        "#
+        ), @r###"
+        foo.mun 23..26: baz
+        "###);
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        //- /foo.mun
+        pub struct Bar;
+
+        //- /mod.mun
+        use foo::Bar;
+        fn main() -> B$0ar { foo::Bar }
+        "#
+        ), @r###"
+        foo.mun 11..14: Bar
+        "###);
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        //- /foo.mun
+        pub struct Bar;
+
+        //- /mod.mun
+        fn main() -> f$0oo::Bar { foo::Bar }
+        "#
+        ), @r###"
+        foo.mun 0..17: pub struct Bar;
+        "###);
+    }
+
+    #[test]
+    fn goto_definition_itself() {
+        insta::assert_snapshot!(goto_definition_string(
+            r#"
+        fn fo$0o() {}
+        "#
+        ), @r###"
+        mod.mun 3..6: foo
+        "###);
+    }
+}
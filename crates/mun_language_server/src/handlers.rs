@@ -1,7 +1,7 @@
 use lsp_types::{CompletionContext, CompletionItem, DocumentSymbol};
 use mun_syntax::{AstNode, TextSize};
 
-use crate::{from_lsp, state::LanguageServerSnapshot, to_lsp, FilePosition};
+use crate::{from_lsp, lsp_utils::LspError, state::LanguageServerSnapshot, to_lsp, FilePosition};
 
 /// Computes the document symbols for a specific document. Converts the LSP
 /// types to internal formats and calls
@@ -86,6 +86,208 @@ pub(crate) fn handle_completion(
     Ok(Some(items.into()))
 }
 
+/// Computes the location(s) of the definition of the item at the requested
+/// position.
+pub(crate) fn handle_goto_definition(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::GotoDefinitionParams,
+) -> anyhow::Result<Option<lsp_types::GotoDefinitionResponse>> {
+    let position = from_lsp::file_position(&snapshot, params.text_document_position_params)?;
+
+    let targets = match snapshot.analysis.goto_definition(position)? {
+        None => return Ok(None),
+        Some(targets) => targets,
+    };
+
+    let locations = targets
+        .into_iter()
+        .map(|target| to_lsp::location_from_nav(&snapshot, target))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(Some(locations.into()))
+}
+
+/// Computes the locations of all references to the item at the requested
+/// position.
+pub(crate) fn handle_references(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::ReferenceParams,
+) -> anyhow::Result<Option<Vec<lsp_types::Location>>> {
+    let position = from_lsp::file_position(&snapshot, params.text_document_position)?;
+
+    let references = match snapshot
+        .analysis
+        .find_all_refs(position, params.context.include_declaration)?
+    {
+        None => return Ok(None),
+        Some(references) => references,
+    };
+
+    let locations = references
+        .into_iter()
+        .map(|reference| to_lsp::location(&snapshot, reference))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(Some(locations))
+}
+
+/// Checks whether the item at the requested position can be renamed and
+/// returns the range of its name.
+pub(crate) fn handle_prepare_rename(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> anyhow::Result<Option<lsp_types::PrepareRenameResponse>> {
+    let position = from_lsp::file_position(&snapshot, params)?;
+
+    let range = snapshot
+        .analysis
+        .prepare_rename(position)?
+        .map_err(to_rename_error)?;
+
+    let line_index = snapshot.analysis.file_line_index(range.file_id)?;
+    Ok(Some(lsp_types::PrepareRenameResponse::Range(
+        to_lsp::range(range.range, &line_index),
+    )))
+}
+
+/// Renames the item at the requested position and all its references.
+pub(crate) fn handle_rename(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::RenameParams,
+) -> anyhow::Result<Option<lsp_types::WorkspaceEdit>> {
+    let position = from_lsp::file_position(&snapshot, params.text_document_position)?;
+
+    let source_change = snapshot
+        .analysis
+        .rename(position, &params.new_name)?
+        .map_err(to_rename_error)?;
+
+    Ok(Some(to_lsp::workspace_edit(&snapshot, source_change)?))
+}
+
+/// Computes the semantic tokens of an entire document.
+pub(crate) fn handle_semantic_tokens_full(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::SemanticTokensParams,
+) -> anyhow::Result<Option<lsp_types::SemanticTokensResult>> {
+    let file_id = from_lsp::file_id(&snapshot, &params.text_document.uri)?;
+    let line_index = snapshot.analysis.file_line_index(file_id)?;
+
+    let highlights = snapshot.analysis.highlight(file_id, None)?;
+    Ok(Some(
+        to_lsp::semantic_tokens(&line_index, highlights).into(),
+    ))
+}
+
+/// Computes the semantic tokens of a range in a document.
+pub(crate) fn handle_semantic_tokens_range(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::SemanticTokensRangeParams,
+) -> anyhow::Result<Option<lsp_types::SemanticTokensRangeResult>> {
+    let file_id = from_lsp::file_id(&snapshot, &params.text_document.uri)?;
+    let line_index = snapshot.analysis.file_line_index(file_id)?;
+    let range = from_lsp::text_range(&line_index, params.range);
+
+    let highlights = snapshot.analysis.highlight(file_id, Some(range))?;
+    Ok(Some(
+        to_lsp::semantic_tokens(&line_index, highlights).into(),
+    ))
+}
+
+/// Computes the inlay hints of a range in a document.
+pub(crate) fn handle_inlay_hints(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::InlayHintParams,
+) -> anyhow::Result<Option<Vec<lsp_types::InlayHint>>> {
+    let file_id = from_lsp::file_id(&snapshot, &params.text_document.uri)?;
+    let line_index = snapshot.analysis.file_line_index(file_id)?;
+    let range = from_lsp::text_range(&line_index, params.range);
+
+    let hints =
+        snapshot
+            .analysis
+            .inlay_hints(file_id, Some(range), &snapshot.config.inlay_hints)?;
+    Ok(Some(
+        hints
+            .into_iter()
+            .map(|hint| to_lsp::inlay_hint(&line_index, hint))
+            .collect(),
+    ))
+}
+
+/// Formats a document. The formatting options of the client are ignored, Mun
+/// code is always formatted the same way. Returns no edits if the document
+/// contains syntax errors.
+pub(crate) fn handle_formatting(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::DocumentFormattingParams,
+) -> anyhow::Result<Option<Vec<lsp_types::TextEdit>>> {
+    let file_id = from_lsp::file_id(&snapshot, &params.text_document.uri)?;
+    let Some(formatted) = snapshot.analysis.format(file_id)? else {
+        return Ok(None);
+    };
+
+    let source_file = snapshot.analysis.parse(file_id)?;
+    if source_file.syntax().text() == formatted.as_str() {
+        return Ok(Some(Vec::new()));
+    }
+
+    // Replace the entire document, the edit is applied as a whole anyway
+    let line_index = snapshot.analysis.file_line_index(file_id)?;
+    Ok(Some(vec![lsp_types::TextEdit {
+        range: to_lsp::range(source_file.syntax().text_range(), &line_index),
+        new_text: formatted,
+    }]))
+}
+
+/// Computes the quick fixes for the diagnostics in the requested range of a
+/// document.
+pub(crate) fn handle_code_action(
+    snapshot: LanguageServerSnapshot,
+    params: lsp_types::CodeActionParams,
+) -> anyhow::Result<Option<lsp_types::CodeActionResponse>> {
+    let file_id = from_lsp::file_id(&snapshot, &params.text_document.uri)?;
+    let line_index = snapshot.analysis.file_line_index(file_id)?;
+    let range = from_lsp::text_range(&line_index, params.range);
+
+    let mut actions = Vec::new();
+    for mut diagnostic in snapshot.analysis.diagnostics(file_id)? {
+        if diagnostic.range.intersect(range).is_none() {
+            continue;
+        }
+        let Some(fix) = diagnostic.fix.take() else {
+            continue;
+        };
+
+        actions.push(lsp_types::CodeActionOrCommand::CodeAction(
+            lsp_types::CodeAction {
+                title: fix.label,
+                kind: Some(lsp_types::CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![to_lsp::diagnostic(
+                    &snapshot,
+                    diagnostic,
+                    &line_index,
+                )?]),
+                edit: Some(to_lsp::workspace_edit(&snapshot, fix.source_change)?),
+                command: None,
+                is_preferred: Some(true),
+                disabled: None,
+                data: None,
+            },
+        ));
+    }
+
+    Ok(Some(actions))
+}
+
+/// Converts a `RenameError` to an error that is shown to the user.
+fn to_rename_error(err: crate::rename::RenameError) -> LspError {
+    LspError::new(
+        lsp_server::ErrorCode::InvalidRequest as i32,
+        err.to_string(),
+    )
+}
+
 /// Constructs a hierarchy of `DocumentSymbols` for a list of symbols that
 /// specify which index is the parent of a symbol. The parent index must always
 /// be smaller than the current index.
@@ -0,0 +1,301 @@
+//! Inlay hints are pieces of text that the editor shows inline with the source
+//! code without them being part of the file. They show information that
+//! follows from analyzing the code, like the inferred type of a `let` binding
+//! or the name of the parameter that an argument is passed to.
+
+use mun_hir::{
+    semantics::{PathResolution, Semantics},
+    HirDisplay, ModuleDef,
+};
+use mun_hir_input::FileId;
+use mun_syntax::{
+    ast::{self, ArgListOwner, NameOwner, TypeAscriptionOwner},
+    match_ast, AstNode, SyntaxNode, TextRange,
+};
+use serde_derive::Deserialize;
+
+use crate::db::AnalysisDatabase;
+
+/// Determines which kinds of inlay hints are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InlayHintsConfig {
+    /// Show the inferred type of `let` bindings without a type ascription
+    pub type_hints: bool,
+
+    /// Show the names of the parameters that arguments are passed to
+    pub parameter_hints: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        InlayHintsConfig {
+            type_hints: true,
+            parameter_hints: true,
+        }
+    }
+}
+
+/// The kind of information that an inlay hint shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlayKind {
+    /// The type of a binding, shown after its name
+    TypeHint,
+
+    /// The name of a parameter, shown before the argument
+    ParameterHint,
+}
+
+/// A hint that is shown in the editor at the start or end of `range`,
+/// depending on its kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlayHint {
+    pub range: TextRange,
+    pub kind: InlayKind,
+    pub label: String,
+}
+
+/// Computes the inlay hints of the specified file. If `range` is specified,
+/// only the hints of nodes that intersect with it are computed.
+pub(crate) fn inlay_hints(
+    db: &AnalysisDatabase,
+    file_id: FileId,
+    range: Option<TextRange>,
+    config: &InlayHintsConfig,
+) -> Vec<InlayHint> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+
+    let mut hints = Vec::new();
+    for node in file.syntax().descendants() {
+        if range.is_some_and(|range| range.intersect(node.text_range()).is_none()) {
+            continue;
+        }
+
+        match_ast! {
+            match node {
+                ast::LetStmt(it) => {
+                    if config.type_hints {
+                        binding_type_hint(&sema, &it, &mut hints);
+                    }
+                },
+                ast::CallExpr(it) => {
+                    if config.parameter_hints {
+                        parameter_name_hints(&sema, &it, &mut hints);
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+
+    hints
+}
+
+/// Adds a hint with the inferred type of a `let` binding, unless the binding
+/// specifies its type explicitly.
+fn binding_type_hint(sema: &Semantics<'_>, let_stmt: &ast::LetStmt, hints: &mut Vec<InlayHint>) {
+    if let_stmt.ascribed_type().is_some() {
+        return;
+    }
+    let Some(ast::PatKind::BindPat(bind_pat)) = let_stmt.pat().map(|pat| pat.kind()) else {
+        return;
+    };
+    let Some(name) = bind_pat.name() else {
+        return;
+    };
+    let Some(local) = sema.to_def(&bind_pat) else {
+        return;
+    };
+
+    // Showing an unknown type adds no information
+    let ty = local.ty(sema.db);
+    if ty.is_unknown() {
+        return;
+    }
+
+    hints.push(InlayHint {
+        range: name.syntax().text_range(),
+        kind: InlayKind::TypeHint,
+        label: ty.display(sema.db).to_string(),
+    });
+}
+
+/// Adds a hint with the name of the parameter before every argument of a call
+/// to a function. Arguments that already read like the name of the parameter
+/// are skipped.
+fn parameter_name_hints(sema: &Semantics<'_>, call: &ast::CallExpr, hints: &mut Vec<InlayHint>) {
+    let Some(ast::ExprKind::PathExpr(callee)) = call.expr().map(|expr| expr.kind()) else {
+        return;
+    };
+    let Some(PathResolution::Def(ModuleDef::Function(function))) =
+        callee.path().and_then(|path| sema.resolve_path(&path))
+    else {
+        return;
+    };
+    let Some(arg_list) = call.arg_list() else {
+        return;
+    };
+
+    for (param, arg) in function.params(sema.db).iter().zip(arg_list.args()) {
+        let Some(name) = param.name(sema.db) else {
+            continue;
+        };
+        let name = name.to_string();
+        if is_argument_similar_to_param_name(arg.syntax(), &name) {
+            continue;
+        }
+
+        hints.push(InlayHint {
+            range: arg.syntax().text_range(),
+            kind: InlayKind::ParameterHint,
+            label: name,
+        });
+    }
+}
+
+/// Returns true if the argument ends with the name of the parameter, e.g.
+/// `foo` or `bar.foo` for a parameter named `foo`.
+fn is_argument_similar_to_param_name(arg: &SyntaxNode, param_name: &str) -> bool {
+    let text = arg.text().to_string();
+    text.strip_suffix(param_name)
+        .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with('.') || prefix.ends_with("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use mun_hir_input::SourceDatabase;
+    use mun_syntax::TextRange;
+
+    use super::{InlayHintsConfig, InlayKind};
+    use crate::{change_fixture::ChangeFixture, db::AnalysisDatabase};
+
+    /// Returns a string representation of the inlay hints of the first file in
+    /// the fixture. Every hint is written on a separate line as the range and
+    /// text of the node it belongs to, its kind and its label.
+    fn inlay_hints_string(
+        fixture: &str,
+        range: Option<TextRange>,
+        config: InlayHintsConfig,
+    ) -> String {
+        let fixture = ChangeFixture::parse(fixture);
+        let file_id = fixture.files[0];
+        let mut db = AnalysisDatabase::default();
+        db.apply_change(fixture.change);
+
+        let text = db.file_text(file_id);
+        super::inlay_hints(&db, file_id, range, &config)
+            .into_iter()
+            .map(|hint| {
+                let kind = match hint.kind {
+                    InlayKind::TypeHint => "type",
+                    InlayKind::ParameterHint => "parameter",
+                };
+                format!(
+                    "{:?} {}: {kind} {}",
+                    hint.range, &text[hint.range], hint.label
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn type_hints() {
+        insta::assert_snapshot!(inlay_hints_string(
+            r#"
+        struct Foo { a: i32 }
+        fn main() {
+            let a = 3;
+            let b: i64 = 4;
+            let foo = Foo { a };
+            let c = foo.a * 2;
+            let d = unknown;
+            let e;
+            e = 4u8;
+        }
+        "#,
+            None,
+            InlayHintsConfig::default(),
+        ), @r###"
+        42..43 a: type i32
+        77..80 foo: type Foo
+        102..103 c: type i32
+        "###);
+    }
+
+    #[test]
+    fn parameter_hints() {
+        insta::assert_snapshot!(inlay_hints_string(
+            r#"
+        //- /mod.mun
+        struct Foo { lhs: i32 }
+        struct Bar(i32);
+        fn add(lhs: i32, rhs: i32) -> i32 { lhs + rhs }
+        fn main(rhs: i32, foo: Foo) -> i32 {
+            let bar = Bar(3);
+            add(1, rhs) + add(foo.lhs, add(2, 3)) + baz::sub(bar.0, 1)
+        }
+
+        //- /baz.mun
+        pub fn sub(lhs: i32, rhs: i32) -> i32 { lhs - rhs }
+        "#,
+            None,
+            InlayHintsConfig::default(),
+        ), @r###"
+        134..137 bar: type Bar
+        156..157 1: parameter lhs
+        179..188 add(2, 3): parameter rhs
+        183..184 2: parameter lhs
+        186..187 3: parameter rhs
+        201..206 bar.0: parameter lhs
+        208..209 1: parameter rhs
+        "###);
+    }
+
+    #[test]
+    fn disabled_hints() {
+        let fixture = r#"
+        fn add(lhs: i32, rhs: i32) -> i32 { lhs + rhs }
+        fn main() {
+            let a = add(1, 2);
+        }
+        "#;
+
+        insta::assert_snapshot!(inlay_hints_string(
+            fixture,
+            None,
+            InlayHintsConfig {
+                type_hints: false,
+                parameter_hints: true,
+            },
+        ), @r###"
+        76..77 1: parameter lhs
+        79..80 2: parameter rhs
+        "###);
+        insta::assert_snapshot!(inlay_hints_string(
+            fixture,
+            None,
+            InlayHintsConfig {
+                type_hints: true,
+                parameter_hints: false,
+            },
+        ), @r###"
+        68..69 a: type i32
+        "###);
+    }
+
+    #[test]
+    fn hints_in_range() {
+        insta::assert_snapshot!(inlay_hints_string(
+            r#"
+        fn foo() { let a = 1; }
+        fn bar() { let b = 2; }
+        "#,
+            Some(TextRange::new(24.into(), 40.into())),
+            InlayHintsConfig::default(),
+        ), @r###"
+        39..40 b: type i32
+        "###);
+    }
+}
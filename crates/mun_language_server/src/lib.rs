@@ -1,6 +1,7 @@
 use std::convert::TryFrom;
 
 pub use config::{Config, FilesWatcher};
+pub use inlay_hints::InlayHintsConfig;
 pub use main_loop::main_loop;
 use mun_hir_input::FileId;
 use mun_paths::AbsPathBuf;
@@ -19,14 +20,24 @@ mod change_fixture;
 mod completion;
 mod config;
 mod db;
+mod definition;
 mod diagnostics;
+mod diagnostics_cache;
 mod file_structure;
 mod from_lsp;
+mod goto_definition;
 mod handlers;
+mod inlay_hints;
 mod lsp_utils;
 mod main_loop;
+mod navigation_target;
+mod references;
+mod rename;
+mod semantic_tokens;
+mod source_change;
 mod state;
 mod symbol_kind;
+mod syntax_highlighting;
 mod to_lsp;
 
 /// Represents a position in a file
@@ -108,6 +119,9 @@ pub fn run_server() -> anyhow::Result<()> {
         };
 
         let mut config = Config::new(root_dir);
+        if let Some(options) = &initialize_params.initialization_options {
+            config.update(options);
+        }
 
         // Determine type of watcher to use
         let supports_file_watcher_dynamic_registration = initialize_params
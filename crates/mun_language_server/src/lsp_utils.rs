@@ -48,6 +48,22 @@ pub(crate) fn apply_document_changes(
     }
 }
 
+/// An error that is sent to the client as the response to a request, with a
+/// specific error code. Other errors are reported as internal errors.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub(crate) struct LspError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl LspError {
+    /// Constructs a new `LspError`
+    pub fn new(code: i32, message: String) -> Self {
+        LspError { code, message }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use lsp_types::{Position, Range, TextDocumentContentChangeEvent};
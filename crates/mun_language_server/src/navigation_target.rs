@@ -0,0 +1,124 @@
+use either::Either;
+use mun_hir::{HasSource, HirDatabase, InFile, ModuleDef};
+use mun_hir_input::FileId;
+use mun_syntax::{
+    ast::{self, NameOwner},
+    AstNode, SyntaxNode, TextRange,
+};
+
+use crate::definition::Definition;
+
+/// `NavigationTarget` represents an element in the editor's UI which you can
+/// click on to navigate to a particular piece of code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavigationTarget {
+    /// The file that contains the element
+    pub file_id: FileId,
+
+    /// The range of the entire element, e.g. the whole function definition
+    pub full_range: TextRange,
+
+    /// The most interesting range within `full_range`, e.g. the name of a
+    /// function. `None` if there is no such range, e.g. for modules.
+    pub focus_range: Option<TextRange>,
+}
+
+impl NavigationTarget {
+    /// Returns the range to navigate to.
+    pub fn focus_or_full_range(&self) -> TextRange {
+        self.focus_range.unwrap_or(self.full_range)
+    }
+
+    /// Constructs a `NavigationTarget` from a node in a file. If the node has a
+    /// name, it is used as the focus range.
+    fn from_named<N: NameOwner>(node: InFile<&N>) -> NavigationTarget {
+        NavigationTarget {
+            file_id: node.file_id,
+            full_range: node.value.syntax().text_range(),
+            focus_range: node.value.name().map(|name| name.syntax().text_range()),
+        }
+    }
+
+    /// Constructs a `NavigationTarget` from a node in a file without a name.
+    fn from_syntax(file_id: FileId, node: &SyntaxNode) -> NavigationTarget {
+        NavigationTarget {
+            file_id,
+            full_range: node.text_range(),
+            focus_range: None,
+        }
+    }
+}
+
+/// A trait to convert an item to a `NavigationTarget`.
+pub(crate) trait ToNav {
+    /// Returns the `NavigationTarget` of this item or `None` if the item is
+    /// not defined in source, e.g. a builtin type.
+    fn to_nav(&self, db: &dyn HirDatabase) -> Option<NavigationTarget>;
+}
+
+impl ToNav for Definition {
+    fn to_nav(&self, db: &dyn HirDatabase) -> Option<NavigationTarget> {
+        match *self {
+            Definition::ModuleDef(def) => def.to_nav(db),
+            Definition::Field(field) => field.to_nav(db),
+            Definition::Local(local) => local.to_nav(db),
+        }
+    }
+}
+
+impl ToNav for ModuleDef {
+    fn to_nav(&self, db: &dyn HirDatabase) -> Option<NavigationTarget> {
+        let nav = match self {
+            ModuleDef::Module(module) => {
+                let file_id = module.file_id(db)?;
+                let source_file = db.parse(file_id).tree();
+                NavigationTarget::from_syntax(file_id, source_file.syntax())
+            }
+            ModuleDef::Function(it) => {
+                NavigationTarget::from_named(it.source(db.upcast()).as_ref())
+            }
+            ModuleDef::Struct(it) => NavigationTarget::from_named(it.source(db.upcast()).as_ref()),
+            ModuleDef::TypeAlias(it) => {
+                NavigationTarget::from_named(it.source(db.upcast()).as_ref())
+            }
+            ModuleDef::PrimitiveType(_) => return None,
+        };
+        Some(nav)
+    }
+}
+
+impl ToNav for mun_hir::Field {
+    fn to_nav(&self, db: &dyn HirDatabase) -> Option<NavigationTarget> {
+        let src = self.parent_struct().source(db.upcast());
+        match src.value.kind() {
+            ast::StructKind::Record(_) => Some(NavigationTarget::from_named(
+                self.source(db.upcast()).as_ref(),
+            )),
+            ast::StructKind::Tuple(fields) => {
+                let field = fields.fields().nth(self.index(db) as usize)?;
+                Some(NavigationTarget::from_syntax(src.file_id, field.syntax()))
+            }
+            ast::StructKind::Unit => None,
+        }
+    }
+}
+
+impl ToNav for mun_hir::semantics::Local {
+    fn to_nav(&self, db: &dyn HirDatabase) -> Option<NavigationTarget> {
+        let src = self.source(db);
+        let nav = match &src.value {
+            Either::Left(pat) => match pat.kind() {
+                ast::PatKind::BindPat(bind_pat) => {
+                    NavigationTarget::from_named(src.with_value(&bind_pat))
+                }
+                ast::PatKind::PlaceholderPat(_) => {
+                    NavigationTarget::from_syntax(src.file_id, pat.syntax())
+                }
+            },
+            Either::Right(self_param) => {
+                NavigationTarget::from_syntax(src.file_id, self_param.syntax())
+            }
+        };
+        Some(nav)
+    }
+}
@@ -0,0 +1,237 @@
+use mun_hir::{semantics::Semantics, HirDatabase};
+use mun_hir_input::FileId;
+use mun_syntax::{AstNode, SyntaxElement, SyntaxToken};
+
+use crate::{
+    db::AnalysisDatabase,
+    definition::{
+        classify_token, is_reference_token, token_at_offset, Definition, NameRefClass, TokenClass,
+    },
+    navigation_target::ToNav,
+    FilePosition, FileRange,
+};
+
+/// Finds all references to the item at the given position. Returns `None` if
+/// there is nothing at the position that refers to a definition.
+///
+/// If `include_declaration` is true, the name of the definition itself is
+/// included in the result.
+pub(crate) fn find_all_refs(
+    db: &AnalysisDatabase,
+    position: FilePosition,
+    include_declaration: bool,
+) -> Option<Vec<FileRange>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let token = token_at_offset(file.syntax(), position.offset)?;
+
+    let def = match classify_token(&sema, &token)? {
+        TokenClass::Name(def) | TokenClass::NameRef(NameRefClass::Definition(def)) => def,
+        TokenClass::NameRef(NameRefClass::FieldShorthand { field, .. }) => Definition::Field(field),
+    };
+
+    let mut references = Vec::new();
+    if include_declaration {
+        if let Some(nav) = def.to_nav(db) {
+            references.push(FileRange {
+                file_id: nav.file_id,
+                range: nav.focus_or_full_range(),
+            });
+        }
+    }
+    references.extend(
+        find_references(&sema, def)
+            .into_iter()
+            .map(|reference| reference.file_range()),
+    );
+
+    Some(references)
+}
+
+/// A token in the source that refers to a definition.
+#[derive(Debug, Clone)]
+pub(crate) struct Reference {
+    /// The file that contains the reference
+    pub file_id: FileId,
+
+    /// The token that refers to the definition
+    pub token: SyntaxToken,
+
+    /// How the token refers to the definition
+    pub class: NameRefClass,
+}
+
+impl Reference {
+    /// Returns the location of the reference.
+    pub fn file_range(&self) -> FileRange {
+        FileRange {
+            file_id: self.file_id,
+            range: self.token.text_range(),
+        }
+    }
+}
+
+/// Finds all tokens that refer to the specified definition.
+///
+/// Instead of resolving every name in the search scope, the files are first
+/// searched for tokens whose text matches the name of the definition. Only
+/// those tokens are resolved.
+pub(crate) fn find_references(sema: &Semantics<'_>, def: Definition) -> Vec<Reference> {
+    let Some(name) = def.name(sema.db) else {
+        return Vec::new();
+    };
+
+    let mut references = Vec::new();
+    for file_id in search_scope(sema.db, def) {
+        let file = sema.parse(file_id);
+        let tokens = file
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .filter(|token| {
+                // The text of a tuple field index includes the dot, e.g. `.0`
+                is_reference_token(token.kind()) && token.text().trim_start_matches('.') == name
+            });
+
+        for token in tokens {
+            if let Some(TokenClass::NameRef(class)) = classify_token(sema, &token) {
+                if class.refers_to(def) {
+                    references.push(Reference {
+                        file_id,
+                        token,
+                        class,
+                    });
+                }
+            }
+        }
+    }
+
+    references
+}
+
+/// Returns the files in which the specified definition can be referenced.
+fn search_scope(db: &dyn HirDatabase, def: Definition) -> Vec<FileId> {
+    if let Definition::Local(local) = def {
+        return vec![local.source(db).file_id];
+    }
+
+    let packages = db.packages();
+    packages
+        .iter()
+        .flat_map(|package_id| {
+            let source_root = db.source_root(packages[package_id].source_root);
+            source_root.files().collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use mun_hir_input::SourceDatabase;
+
+    use crate::change_fixture::position;
+
+    /// Returns a string representation of all references to the item at the
+    /// cursor (`$0`), including its declaration. Every reference is written as
+    /// the path of the file, the range of the reference and the text of the
+    /// line that contains it.
+    fn references_string(fixture: &str) -> String {
+        let (db, position) = position(fixture);
+        let references = super::find_all_refs(&db, position, true).expect("nothing at the cursor");
+        references
+            .into_iter()
+            .map(|reference| {
+                let source_root = db.source_root(db.file_source_root(reference.file_id));
+                let text = db.file_text(reference.file_id);
+                let start = usize::from(reference.range.start());
+                let line_start = text[..start].rfind('\n').map_or(0, |idx| idx + 1);
+                let line_end = text[start..]
+                    .find('\n')
+                    .map_or(text.len(), |idx| start + idx);
+                format!(
+                    "{} {:?}: {}",
+                    source_root.relative_path(reference.file_id),
+                    reference.range,
+                    text[line_start..line_end].trim()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn local_references() {
+        insta::assert_snapshot!(references_string(
+            r#"
+        fn main(a$0: i32) -> i32 {
+            let b = a + 1;
+            let a = b + a;
+            a
+        }
+        "#
+        ), @r###"
+        mod.mun 8..9: fn main(a: i32) -> i32 {
+        mod.mun 37..38: let b = a + 1;
+        mod.mun 60..61: let a = b + a;
+        "###);
+    }
+
+    #[test]
+    fn function_references() {
+        insta::assert_snapshot!(references_string(
+            r#"
+        fn foo() -> i32 { 3 }
+        fn bar() -> i32 { foo() + fo$0o() }
+        fn main() -> i32 { let foo = 1; foo + bar() }
+        "#
+        ), @r###"
+        mod.mun 3..6: fn foo() -> i32 { 3 }
+        mod.mun 40..43: fn bar() -> i32 { foo() + foo() }
+        mod.mun 48..51: fn bar() -> i32 { foo() + foo() }
+        "###);
+    }
+
+    #[test]
+    fn field_references() {
+        insta::assert_snapshot!(references_string(
+            r#"
+        struct Foo { a$0: i32 }
+        struct Bar(Foo);
+        fn main() -> i32 {
+            let a = 3;
+            let foo = Foo { a };
+            let bar = Bar(Foo { a: foo.a });
+            bar.0.a
+        }
+        "#
+        ), @r###"
+        mod.mun 13..14: struct Foo { a: i32 }
+        mod.mun 93..94: let foo = Foo { a };
+        mod.mun 122..123: let bar = Bar(Foo { a: foo.a });
+        mod.mun 129..130: let bar = Bar(Foo { a: foo.a });
+        mod.mun 145..146: bar.0.a
+        "###);
+    }
+
+    #[test]
+    fn cross_file_references() {
+        insta::assert_snapshot!(references_string(
+            r#"
+        //- /foo.mun
+        pub struct Bar$0 { a: i32 }
+        pub fn new_bar() -> Bar { Bar { a: 0 } }
+
+        //- /mod.mun
+        use foo::Bar;
+        fn main() -> foo::Bar { let bar: Bar = foo::new_bar(); bar }
+        "#
+        ), @r###"
+        foo.mun 11..14: pub struct Bar { a: i32 }
+        foo.mun 46..49: pub fn new_bar() -> Bar { Bar { a: 0 } }
+        foo.mun 52..55: pub fn new_bar() -> Bar { Bar { a: 0 } }
+        mod.mun 9..12: use foo::Bar;
+        mod.mun 32..35: fn main() -> foo::Bar { let bar: Bar = foo::new_bar(); bar }
+        mod.mun 47..50: fn main() -> foo::Bar { let bar: Bar = foo::new_bar(); bar }
+        "###);
+    }
+}
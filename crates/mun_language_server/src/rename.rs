@@ -0,0 +1,463 @@
+use mun_hir::{
+    semantics::{ScopeDef, Semantics},
+    AssocItem, HirDatabase, Impl, ModuleDef, Name,
+};
+use mun_syntax::{ast, AstNode, SyntaxKind, SyntaxToken};
+use ra_ap_text_edit::{TextEdit, TextEditBuilder};
+use rustc_hash::FxHashMap;
+
+use crate::{
+    db::AnalysisDatabase,
+    definition::{classify_token, token_at_offset, Definition, NameRefClass, TokenClass},
+    navigation_target::ToNav,
+    references::{find_references, Reference},
+    source_change::SourceChange,
+    FilePosition, FileRange,
+};
+
+/// An error that occurred while renaming. The message is meant to be shown to
+/// the user.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct RenameError(String);
+
+pub type RenameResult<T> = Result<T, RenameError>;
+
+macro_rules! bail {
+    ($($arg:tt)*) => {
+        return Err(RenameError(format!($($arg)*)))
+    };
+}
+
+/// Checks whether the item at the given position can be renamed. Returns the
+/// range of the name that will be renamed.
+pub(crate) fn prepare_rename(
+    db: &AnalysisDatabase,
+    position: FilePosition,
+) -> RenameResult<FileRange> {
+    let sema = Semantics::new(db);
+    let (token, def) = find_definition(&sema, position)?;
+    check_renamable(db, def)?;
+    Ok(FileRange {
+        file_id: position.file_id,
+        range: token.text_range(),
+    })
+}
+
+/// Renames the item at the given position and all its references to
+/// `new_name`. Fails if the item cannot be renamed or if renaming would
+/// conflict with another item that is in scope.
+pub(crate) fn rename(
+    db: &AnalysisDatabase,
+    position: FilePosition,
+    new_name: &str,
+) -> RenameResult<SourceChange> {
+    let sema = Semantics::new(db);
+    let (_, def) = find_definition(&sema, position)?;
+    check_renamable(db, def)?;
+
+    if !is_valid_identifier(new_name) {
+        bail!("`{new_name}` is not a valid identifier");
+    }
+
+    let references = find_references(&sema, def);
+    check_conflicts(&sema, def, &references, new_name)?;
+
+    let mut edits: FxHashMap<_, TextEditBuilder> = FxHashMap::default();
+
+    // Rename the definition itself
+    let Some(nav) = def.to_nav(db) else {
+        bail!("cannot find the definition");
+    };
+    let Some(range) = nav.focus_range else {
+        bail!("cannot find the name of the definition");
+    };
+    edits
+        .entry(nav.file_id)
+        .or_insert_with(TextEdit::builder)
+        .replace(range, new_name.to_owned());
+
+    // Rename all references
+    for reference in references {
+        let old_name = reference.token.text();
+        let replacement = match (reference.class, def) {
+            // `Foo { a }` becomes `Foo { new: a }`
+            (NameRefClass::FieldShorthand { .. }, Definition::Field(_)) => {
+                format!("{new_name}: {old_name}")
+            }
+            // `Foo { a }` becomes `Foo { a: new }`
+            (NameRefClass::FieldShorthand { .. }, _) => format!("{old_name}: {new_name}"),
+            (NameRefClass::Definition(_), _) => new_name.to_owned(),
+        };
+        edits
+            .entry(reference.file_id)
+            .or_insert_with(TextEdit::builder)
+            .replace(reference.token.text_range(), replacement);
+    }
+
+    Ok(edits
+        .into_iter()
+        .map(|(file_id, edit)| (file_id, edit.finish()))
+        .collect::<FxHashMap<_, _>>()
+        .into())
+}
+
+/// Finds the token at the given position and the definition it defines or
+/// refers to.
+fn find_definition(
+    sema: &Semantics<'_>,
+    position: FilePosition,
+) -> RenameResult<(SyntaxToken, Definition)> {
+    let file = sema.parse(position.file_id);
+    let token = token_at_offset(file.syntax(), position.offset)
+        .ok_or_else(|| RenameError(String::from("no references found at position")))?;
+
+    let def = match classify_token(sema, &token) {
+        Some(TokenClass::Name(def) | TokenClass::NameRef(NameRefClass::Definition(def))) => def,
+        Some(TokenClass::NameRef(NameRefClass::FieldShorthand { field, .. })) => {
+            Definition::Field(field)
+        }
+        None => bail!("no references found at position"),
+    };
+
+    Ok((token, def))
+}
+
+/// Returns an error if the specified definition cannot be renamed.
+fn check_renamable(db: &dyn HirDatabase, def: Definition) -> RenameResult<()> {
+    match def {
+        Definition::ModuleDef(ModuleDef::Module(_)) => bail!("renaming modules is not supported"),
+        Definition::ModuleDef(ModuleDef::PrimitiveType(_)) => {
+            bail!("cannot rename a builtin type")
+        }
+        Definition::Field(field) if field.name(db).as_str().is_none() => {
+            bail!("cannot rename a tuple field")
+        }
+        Definition::Local(local) if local.source(db).value.is_right() => {
+            bail!("cannot rename `self`")
+        }
+        Definition::ModuleDef(_) | Definition::Field(_) | Definition::Local(_) => Ok(()),
+    }
+}
+
+/// Returns true if `name` is a single identifier, e.g. not a keyword.
+fn is_valid_identifier(name: &str) -> bool {
+    match mun_syntax::tokenize(name).as_slice() {
+        [token] => token.kind == SyntaxKind::IDENT,
+        _ => false,
+    }
+}
+
+/// Returns an error if renaming the specified definition to `new_name` would
+/// conflict with an existing item or change the meaning of one of the
+/// `references`.
+fn check_conflicts(
+    sema: &Semantics<'_>,
+    def: Definition,
+    references: &[Reference],
+    new_name: &str,
+) -> RenameResult<()> {
+    let has_new_name = |name: &Name| name.as_str() == Some(new_name);
+    let db = sema.db;
+
+    // Check that the name is not already used by a sibling of the definition
+    match def {
+        Definition::Field(field) => {
+            let strukt = field.parent_struct();
+            if strukt
+                .fields(db)
+                .iter()
+                .any(|it| has_new_name(&it.name(db)))
+            {
+                bail!(
+                    "struct `{}` already has a field named `{new_name}`",
+                    strukt.name(db)
+                );
+            }
+        }
+        Definition::ModuleDef(ModuleDef::Function(func)) if func.parent_impl(db).is_some() => {
+            let self_ty = func.parent_impl(db).unwrap().self_ty(db);
+            let exists = Impl::all_for_type(db, &self_ty)
+                .into_iter()
+                .flat_map(|impl_| impl_.items(db))
+                .any(|item| match item {
+                    AssocItem::Function(it) => has_new_name(&it.name(db)),
+                });
+            if exists {
+                bail!("an associated function named `{new_name}` already exists");
+            }
+        }
+        Definition::ModuleDef(def) => {
+            let Some(module) = def.module(db) else {
+                return Ok(());
+            };
+            if module
+                .scope(db, None)
+                .iter()
+                .any(|(name, _)| has_new_name(name))
+            {
+                bail!("a definition named `{new_name}` already exists in this module");
+            }
+        }
+        Definition::Local(_) => {}
+    }
+
+    // Check that none of the unqualified references would resolve to something else
+    // after renaming
+    for reference in references {
+        let refers_to_field = matches!(def, Definition::Field(_));
+        if refers_to_field || is_qualified(&reference.token) {
+            continue;
+        }
+
+        let Some(parent) = reference.token.parent() else {
+            continue;
+        };
+        let scope = sema.scope_at_offset(&parent, reference.token.text_range().start());
+        let mut conflicts = false;
+        scope.visit_all_names(&mut |name, def| {
+            if has_new_name(&name) && !matches!(def, ScopeDef::Unknown) {
+                conflicts = true;
+            }
+        });
+        if conflicts {
+            bail!(
+                "`{new_name}` is already in scope where `{}` is used",
+                reference.token.text()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns true if the token is the last segment of a path with a qualifier,
+/// e.g. `bar` in `foo::bar`.
+fn is_qualified(token: &SyntaxToken) -> bool {
+    token
+        .parent()
+        .and_then(|parent| parent.parent())
+        .and_then(ast::PathSegment::cast)
+        .is_some_and(|segment| segment.parent_path().qualifier().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use mun_hir_input::SourceDatabase;
+
+    use crate::change_fixture::position;
+
+    /// Renames the item at the cursor (`$0`) to `new_name` and returns the text
+    /// of all changed files, or the error message if renaming failed.
+    fn rename_string(fixture: &str, new_name: &str) -> String {
+        let (db, position) = position(fixture);
+        let change = match super::rename(&db, position, new_name) {
+            Ok(change) => change,
+            Err(err) => return format!("error: {err}"),
+        };
+
+        let mut files = change
+            .source_file_edits
+            .into_iter()
+            .map(|(file_id, edit)| {
+                let source_root = db.source_root(db.file_source_root(file_id));
+                let mut text = db.file_text(file_id).to_string();
+                edit.apply(&mut text);
+                format!(
+                    "//- /{}\n{}",
+                    source_root.relative_path(file_id),
+                    text.trim()
+                )
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files.join("\n\n")
+    }
+
+    #[test]
+    fn rename_local() {
+        insta::assert_snapshot!(rename_string(
+            r#"
+        fn main(a$0: i32) -> i32 {
+            let b = a + 1;
+            b + a
+        }
+        "#,
+            "c"
+        ), @r###"
+        //- /mod.mun
+        fn main(c: i32) -> i32 {
+            let b = c + 1;
+            b + c
+        }
+        "###);
+    }
+
+    #[test]
+    fn rename_function_across_files() {
+        insta::assert_snapshot!(rename_string(
+            r#"
+        //- /foo.mun
+        pub fn bar$0() -> i32 { 3 }
+
+        //- /mod.mun
+        use foo::bar;
+        fn main() -> i32 { bar() + foo::bar() }
+        "#,
+            "baz"
+        ), @r###"
+        //- /foo.mun
+        pub fn baz() -> i32 { 3 }
+
+        //- /mod.mun
+        use foo::baz;
+        fn main() -> i32 { baz() + foo::baz() }
+        "###);
+    }
+
+    #[test]
+    fn rename_struct() {
+        insta::assert_snapshot!(rename_string(
+            r#"
+        struct Foo { a: i32 }
+        impl Foo {
+            fn new() -> Self { Foo { a: 0 } }
+        }
+        fn main() -> Foo { Fo$0o::new() }
+        "#,
+            "Bar"
+        ), @r###"
+        //- /mod.mun
+        struct Bar { a: i32 }
+        impl Bar {
+            fn new() -> Self { Bar { a: 0 } }
+        }
+        fn main() -> Bar { Bar::new() }
+        "###);
+    }
+
+    #[test]
+    fn rename_field() {
+        insta::assert_snapshot!(rename_string(
+            r#"
+        struct Foo { a$0: i32 }
+        fn main() -> i32 {
+            let a = 3;
+            let foo = Foo { a };
+            Foo { a: foo.a }.a
+        }
+        "#,
+            "b"
+        ), @r###"
+        //- /mod.mun
+        struct Foo { b: i32 }
+        fn main() -> i32 {
+            let a = 3;
+            let foo = Foo { b: a };
+            Foo { b: foo.b }.b
+        }
+        "###);
+    }
+
+    #[test]
+    fn rename_local_in_field_shorthand() {
+        insta::assert_snapshot!(rename_string(
+            r#"
+        struct Foo { a: i32 }
+        fn main() -> Foo {
+            let a$0 = 3;
+            Foo { a }
+        }
+        "#,
+            "b"
+        ), @r###"
+        //- /mod.mun
+        struct Foo { a: i32 }
+        fn main() -> Foo {
+            let b = 3;
+            Foo { a: b }
+        }
+        "###);
+    }
+
+    #[test]
+    fn rename_invalid() {
+        insta::assert_snapshot!(rename_string(
+            r#"
+        fn main(a$0: i32) -> i32 { a }
+        "#,
+            "fn"
+        ), @r###"
+        error: `fn` is not a valid identifier
+        "###);
+        insta::assert_snapshot!(rename_string(
+            r#"
+        fn main(a$0: i32) -> i32 { a }
+        "#,
+            "a b"
+        ), @r###"
+        error: `a b` is not a valid identifier
+        "###);
+        insta::assert_snapshot!(rename_string(
+            r#"
+        struct Foo(i32);
+        fn main(a: Foo) -> i32 { a.$00 }
+        "#,
+            "b"
+        ), @r###"
+        error: cannot rename a tuple field
+        "###);
+        insta::assert_snapshot!(rename_string(
+            r#"
+        fn main() -> i3$02 { 0 }
+        "#,
+            "b"
+        ), @r###"
+        error: cannot rename a builtin type
+        "###);
+    }
+
+    #[test]
+    fn rename_conflicts() {
+        insta::assert_snapshot!(rename_string(
+            r#"
+        fn foo() -> i32 { 3 }
+        fn bar$0() -> i32 { 4 }
+        "#,
+            "foo"
+        ), @r###"
+        error: a definition named `foo` already exists in this module
+        "###);
+        insta::assert_snapshot!(rename_string(
+            r#"
+        fn main(a$0: i32) -> i32 {
+            let b = 3;
+            a + b
+        }
+        "#,
+            "b"
+        ), @r###"
+        error: `b` is already in scope where `a` is used
+        "###);
+        insta::assert_snapshot!(rename_string(
+            r#"
+        struct Foo { a$0: i32, b: i32 }
+        "#,
+            "b"
+        ), @r###"
+        error: struct `Foo` already has a field named `b`
+        "###);
+        insta::assert_snapshot!(rename_string(
+            r#"
+        struct Foo;
+        impl Foo {
+            fn new$0() -> Self { Foo }
+            fn create() -> Self { Foo }
+        }
+        "#,
+            "create"
+        ), @r###"
+        error: an associated function named `create` already exists
+        "###);
+    }
+}
@@ -0,0 +1,139 @@
+//! Defines the semantic token types and modifiers that the language server
+//! reports to the client and the encoding of semantic tokens.
+
+use lsp_types::{
+    SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens, SemanticTokensLegend,
+};
+
+/// Marks a local that is assigned to after its declaration. This is not one of
+/// the modifiers predefined by the LSP.
+pub(crate) const MUTABLE: SemanticTokenModifier = SemanticTokenModifier::new("mutable");
+
+/// The token types that the server reports, the index of a type in this list
+/// is used to refer to it in the encoded tokens.
+const SUPPORTED_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::VARIABLE,
+];
+
+/// The token modifiers that the server reports, the index of a modifier in
+/// this list is the bit that represents it in the encoded tokens.
+const SUPPORTED_MODIFIERS: &[SemanticTokenModifier] = &[
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::DEFAULT_LIBRARY,
+    MUTABLE,
+];
+
+/// Returns the legend of the token types and modifiers that the server
+/// reports.
+pub(crate) fn semantic_tokens_legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: SUPPORTED_TYPES.to_vec(),
+        token_modifiers: SUPPORTED_MODIFIERS.to_vec(),
+    }
+}
+
+/// Returns the index of the specified token type in the legend.
+pub(crate) fn type_index(ty: &SemanticTokenType) -> u32 {
+    SUPPORTED_TYPES
+        .iter()
+        .position(|it| it == ty)
+        .expect("token type is not part of the legend") as u32
+}
+
+/// A set of token modifiers encoded as a bitset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ModifierSet(pub u32);
+
+impl std::ops::BitOrAssign<SemanticTokenModifier> for ModifierSet {
+    fn bitor_assign(&mut self, rhs: SemanticTokenModifier) {
+        let index = SUPPORTED_MODIFIERS
+            .iter()
+            .position(|it| it == &rhs)
+            .expect("token modifier is not part of the legend");
+        self.0 |= 1 << index;
+    }
+}
+
+/// Builds the relative encoding of semantic tokens. Tokens must be pushed in
+/// the order in which they appear in the document and they cannot span
+/// multiple lines.
+#[derive(Default)]
+pub(crate) struct SemanticTokensBuilder {
+    prev_line: u32,
+    prev_char: u32,
+    data: Vec<SemanticToken>,
+}
+
+impl SemanticTokensBuilder {
+    /// Adds a token at the specified `range`.
+    pub fn push(&mut self, range: lsp_types::Range, token_index: u32, modifier_bitset: u32) {
+        let mut push_line = range.start.line;
+        let mut push_char = range.start.character;
+
+        // The start of a token is relative to the start of the previous token if they
+        // are on the same line.
+        if !self.data.is_empty() {
+            push_line -= self.prev_line;
+            if push_line == 0 {
+                push_char -= self.prev_char;
+            }
+        }
+
+        self.data.push(SemanticToken {
+            delta_line: push_line,
+            delta_start: push_char,
+            length: range.end.character - range.start.character,
+            token_type: token_index,
+            token_modifiers_bitset: modifier_bitset,
+        });
+
+        self.prev_line = range.start.line;
+        self.prev_char = range.start.character;
+    }
+
+    /// Returns the encoded tokens.
+    pub fn build(self) -> SemanticTokens {
+        SemanticTokens {
+            result_id: None,
+            data: self.data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Position, Range, SemanticToken};
+
+    use super::SemanticTokensBuilder;
+
+    #[test]
+    fn relative_encoding() {
+        let mut builder = SemanticTokensBuilder::default();
+        builder.push(Range::new(Position::new(1, 4), Position::new(1, 7)), 0, 0);
+        builder.push(Range::new(Position::new(1, 10), Position::new(1, 11)), 1, 1);
+        builder.push(Range::new(Position::new(3, 2), Position::new(3, 5)), 2, 4);
+
+        let token =
+            |delta_line, delta_start, length, token_type, token_modifiers_bitset| SemanticToken {
+                delta_line,
+                delta_start,
+                length,
+                token_type,
+                token_modifiers_bitset,
+            };
+        assert_eq!(
+            builder.build().data,
+            vec![
+                token(1, 4, 3, 0, 0),
+                token(0, 6, 1, 1, 1),
+                token(2, 2, 3, 2, 4),
+            ]
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use mun_hir_input::FileId;
+use ra_ap_text_edit::TextEdit;
+use rustc_hash::FxHashMap;
+
+/// A set of edits to one or more source files, e.g. the result of a rename.
+#[derive(Debug, Clone, Default)]
+pub struct SourceChange {
+    /// The edits per file
+    pub source_file_edits: FxHashMap<FileId, TextEdit>,
+}
+
+impl From<FxHashMap<FileId, TextEdit>> for SourceChange {
+    fn from(source_file_edits: FxHashMap<FileId, TextEdit>) -> Self {
+        SourceChange { source_file_edits }
+    }
+}
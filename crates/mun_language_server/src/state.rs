@@ -9,13 +9,14 @@ use lsp_types::{
 use mun_hir_input::{FileId, PackageId, PackageSet};
 use mun_paths::AbsPathBuf;
 use mun_vfs::VirtualFileSystem;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use rustc_hash::FxHashSet;
 
 use crate::{
     analysis::{Analysis, AnalysisSnapshot},
     change::AnalysisChange,
     config::Config,
+    diagnostics_cache::{DiagnosticsCache, PackageDiagnostics},
     state::utils::Progress,
     to_json, to_lsp,
 };
@@ -51,7 +52,7 @@ pub(crate) struct LanguageServerState {
     pub(crate) request_queue: lsp_server::ReqQueue<(String, Instant), RequestHandler>,
 
     /// The configuration passed by the client
-    pub config: Config,
+    pub config: Arc<Config>,
 
     /// Thread pool for async execution
     pub thread_pool: threadpool::ThreadPool,
@@ -77,6 +78,9 @@ pub(crate) struct LanguageServerState {
     /// Holds the state of the analysis process
     pub analysis: Analysis,
 
+    /// Diagnostics that are persisted between sessions
+    pub diagnostics_cache: Arc<Mutex<DiagnosticsCache>>,
+
     /// All the packages known to the server
     pub packages: Arc<Vec<mun_project::Package>>,
 
@@ -86,12 +90,18 @@ pub(crate) struct LanguageServerState {
 
 /// A snapshot of the state of the language server
 pub(crate) struct LanguageServerSnapshot {
+    /// The configuration passed by the client
+    pub config: Arc<Config>,
+
     /// The virtual filesystem that holds all the file contents
     pub vfs: Arc<RwLock<VirtualFileSystem>>,
 
     /// Holds the state of the analysis process
     pub analysis: AnalysisSnapshot,
 
+    /// Diagnostics that are persisted between sessions
+    pub diagnostics_cache: Arc<Mutex<DiagnosticsCache>>,
+
     /// All the packages known to the server
     pub packages: Arc<Vec<mun_project::Package>>,
 }
@@ -118,10 +128,12 @@ impl LanguageServerState {
         change.set_roots(Vec::default());
         analysis.apply_change(change);
 
+        let diagnostics_cache = DiagnosticsCache::load(config.cache_dir.clone());
+
         LanguageServerState {
             sender,
             request_queue: ReqQueue::default(),
-            config,
+            config: Arc::new(config),
             vfs: Arc::default(),
             vfs_monitor,
             vfs_monitor_receiver,
@@ -130,6 +142,7 @@ impl LanguageServerState {
             task_sender,
             task_receiver,
             analysis,
+            diagnostics_cache: Arc::new(Mutex::new(diagnostics_cache)),
             packages: Arc::new(Vec::new()),
             shutdown_requested: false,
         }
@@ -154,15 +167,24 @@ impl LanguageServerState {
         while let Some(event) = self.next_event(&receiver) {
             if let Event::Lsp(lsp_server::Message::Notification(notification)) = &event {
                 if notification.method == lsp_types::notification::Exit::METHOD {
+                    self.save_diagnostics_cache();
                     return Ok(());
                 }
             }
             self.handle_event(event)?;
         }
 
+        self.save_diagnostics_cache();
         Ok(())
     }
 
+    /// Writes the diagnostics that were cached during this session to disk.
+    pub(crate) fn save_diagnostics_cache(&self) {
+        if let Err(err) = self.diagnostics_cache.lock().save() {
+            log::warn!("could not write diagnostics cache: {err:#}");
+        }
+    }
+
     /// Handles an event from one of the many sources that the language server
     /// subscribes to.
     fn handle_event(&mut self, event: Event) -> anyhow::Result<()> {
@@ -247,58 +269,31 @@ impl LanguageServerState {
 /// Sends all diagnostics of all files
 fn handle_diagnostics(state: LanguageServerSnapshot, sender: Sender<Task>) -> anyhow::Result<()> {
     // Iterate over all files
-    for (idx, _package) in state.packages.iter().enumerate() {
+    for (idx, package) in state.packages.iter().enumerate() {
         let package_id = PackageId(idx as u32);
 
-        // Get all the files
-        let files = state.analysis.package_source_files(package_id)?;
+        // If the package didn't change since its diagnostics were cached, there is no
+        // need to analyze it.
+        let fingerprint = state.analysis.package_fingerprint(package_id)?;
+        let cached = state
+            .diagnostics_cache
+            .lock()
+            .get(package.manifest_path(), fingerprint)
+            .cloned();
+        let package_diagnostics = if let Some(diagnostics) = cached {
+            diagnostics
+        } else {
+            let diagnostics = package_diagnostics(&state, package_id)?;
+            state.diagnostics_cache.lock().insert(
+                package.manifest_path(),
+                fingerprint,
+                diagnostics.clone(),
+            );
+            diagnostics
+        };
 
         // Publish all diagnostics
-        for file in files {
-            let line_index = state.analysis.file_line_index(file)?;
-            let uri = to_lsp::url(&state, file)?;
-            let diagnostics = state.analysis.diagnostics(file)?;
-
-            let diagnostics = {
-                let mut lsp_diagnostics = Vec::with_capacity(diagnostics.len());
-                for d in diagnostics {
-                    lsp_diagnostics.push(lsp_types::Diagnostic {
-                        range: to_lsp::range(d.range, &line_index),
-                        severity: Some(lsp_types::DiagnosticSeverity::ERROR),
-                        code: None,
-                        code_description: None,
-                        source: Some("mun".to_string()),
-                        message: d.message,
-                        related_information: {
-                            let mut annotations =
-                                Vec::with_capacity(d.additional_annotations.len());
-                            for annotation in d.additional_annotations {
-                                annotations.push(lsp_types::DiagnosticRelatedInformation {
-                                    location: lsp_types::Location {
-                                        uri: to_lsp::url(&state, annotation.range.file_id)?,
-                                        range: to_lsp::range(
-                                            annotation.range.value,
-                                            &*state
-                                                .analysis
-                                                .file_line_index(annotation.range.file_id)?,
-                                        ),
-                                    },
-                                    message: annotation.message,
-                                });
-                            }
-                            if annotations.is_empty() {
-                                None
-                            } else {
-                                Some(annotations)
-                            }
-                        },
-                        tags: None,
-                        data: None,
-                    });
-                }
-                lsp_diagnostics
-            };
-
+        for (uri, diagnostics) in package_diagnostics {
             sender
                 .send(Task::Notify(lsp_server::Notification {
                     method: PublishDiagnostics::METHOD.to_owned(),
@@ -315,12 +310,38 @@ fn handle_diagnostics(state: LanguageServerSnapshot, sender: Sender<Task>) -> an
     Ok(())
 }
 
+/// Computes the diagnostics of all the files in the specified package.
+fn package_diagnostics(
+    state: &LanguageServerSnapshot,
+    package_id: PackageId,
+) -> anyhow::Result<PackageDiagnostics> {
+    // Get all the files
+    let files = state.analysis.package_source_files(package_id)?;
+
+    let mut package_diagnostics = Vec::with_capacity(files.len());
+    for file in files {
+        let line_index = state.analysis.file_line_index(file)?;
+        let uri = to_lsp::url(state, file)?;
+        let diagnostics = state.analysis.diagnostics(file)?;
+
+        let diagnostics = diagnostics
+            .into_iter()
+            .map(|d| to_lsp::diagnostic(state, d, &line_index))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        package_diagnostics.push((uri, diagnostics));
+    }
+    Ok(package_diagnostics)
+}
+
 impl LanguageServerState {
     /// Creates a snapshot of the state
     pub fn snapshot(&self) -> LanguageServerSnapshot {
         LanguageServerSnapshot {
+            config: self.config.clone(),
             vfs: self.vfs.clone(),
             analysis: self.analysis.snapshot(),
+            diagnostics_cache: self.diagnostics_cache.clone(),
             packages: self.packages.clone(),
         }
     }
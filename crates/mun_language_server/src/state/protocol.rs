@@ -1,12 +1,15 @@
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 use dispatcher::{NotificationDispatcher, RequestDispatcher};
 use lsp_types::notification::{
-    DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument, DidOpenTextDocument,
+    DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument,
+    DidOpenTextDocument,
 };
 
 use super::LanguageServerState;
-use crate::{from_lsp, handlers, lsp_utils::apply_document_changes, state::RequestHandler};
+use crate::{
+    config::Config, from_lsp, handlers, lsp_utils::apply_document_changes, state::RequestHandler,
+};
 
 pub mod dispatcher;
 
@@ -20,7 +23,7 @@ impl LanguageServerState {
         self.open_docs.insert(path.clone());
         self.vfs
             .write()
-            .set_file_contents(&path, Some(params.text_document.text.into_bytes()));
+            .set_overlay(&path, Some(params.text_document.text.into_bytes()));
         Ok(())
     }
 
@@ -43,7 +46,7 @@ impl LanguageServerState {
             .and_then(|contents| String::from_utf8(contents.to_vec()).ok())
             .expect("if the file_id exists it must be valid utf8");
         apply_document_changes(&mut text, content_changes);
-        vfs.set_file_contents(&path, Some(text.into_bytes()));
+        vfs.set_overlay(&path, Some(text.into_bytes()));
         Ok(())
     }
 
@@ -54,6 +57,11 @@ impl LanguageServerState {
     ) -> anyhow::Result<()> {
         let path = from_lsp::abs_path(&params.text_document.uri)?;
         self.open_docs.remove(&path);
+
+        // Drop the contents of the editor buffer, the contents on disk are used again.
+        // They might have changed while the document was open, so reload them
+        // as well.
+        self.vfs.write().set_overlay(&path, None);
         self.vfs_monitor.reload(&path);
         Ok(())
     }
@@ -70,6 +78,17 @@ impl LanguageServerState {
         Ok(())
     }
 
+    /// Called when a `DidChangeConfiguration` notification was received.
+    fn on_did_change_configuration(
+        &mut self,
+        params: lsp_types::DidChangeConfigurationParams,
+    ) -> anyhow::Result<()> {
+        let mut config = Config::clone(&self.config);
+        config.update(&params.settings);
+        self.config = Arc::new(config);
+        Ok(())
+    }
+
     /// Handles a language server protocol request
     pub(super) fn on_request(
         &mut self,
@@ -92,10 +111,24 @@ impl LanguageServerState {
         RequestDispatcher::new(self, request)
             .on_sync::<lsp_types::request::Shutdown>(|state, _request| {
                 state.shutdown_requested = true;
+                state.save_diagnostics_cache();
                 Ok(())
             })?
             .on::<lsp_types::request::DocumentSymbolRequest>(handlers::handle_document_symbol)?
             .on::<lsp_types::request::Completion>(handlers::handle_completion)?
+            .on::<lsp_types::request::GotoDefinition>(handlers::handle_goto_definition)?
+            .on::<lsp_types::request::References>(handlers::handle_references)?
+            .on::<lsp_types::request::PrepareRenameRequest>(handlers::handle_prepare_rename)?
+            .on::<lsp_types::request::Rename>(handlers::handle_rename)?
+            .on::<lsp_types::request::SemanticTokensFullRequest>(
+                handlers::handle_semantic_tokens_full,
+            )?
+            .on::<lsp_types::request::SemanticTokensRangeRequest>(
+                handlers::handle_semantic_tokens_range,
+            )?
+            .on::<lsp_types::request::InlayHintRequest>(handlers::handle_inlay_hints)?
+            .on::<lsp_types::request::Formatting>(handlers::handle_formatting)?
+            .on::<lsp_types::request::CodeActionRequest>(handlers::handle_code_action)?
             .finish();
 
         Ok(())
@@ -111,6 +144,7 @@ impl LanguageServerState {
             .on::<DidChangeTextDocument>(LanguageServerState::on_did_change_text_document)?
             .on::<DidCloseTextDocument>(LanguageServerState::on_did_close_text_document)?
             .on::<DidChangeWatchedFiles>(LanguageServerState::on_did_change_watched_files)?
+            .on::<DidChangeConfiguration>(LanguageServerState::on_did_change_configuration)?
             .finish();
         Ok(())
     }
@@ -5,6 +5,7 @@ use super::LanguageServerState;
 use crate::{
     cancelation::is_canceled,
     from_json,
+    lsp_utils::LspError,
     state::{LanguageServerSnapshot, Task},
 };
 
@@ -191,6 +192,8 @@ where
                     lsp_server::ErrorCode::ContentModified as i32,
                     "content modified".to_string(),
                 )
+            } else if let Some(err) = e.downcast_ref::<LspError>() {
+                lsp_server::Response::new_err(id, err.code, err.message.clone())
             } else {
                 lsp_server::Response::new_err(
                     id,
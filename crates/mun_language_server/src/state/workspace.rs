@@ -1,14 +1,49 @@
 use std::{
     convert::{TryFrom, TryInto},
+    path::Path,
     sync::Arc,
 };
 
 use mun_hir_input::{FileId, PackageSet, SourceRoot, SourceRootId};
 use mun_paths::{AbsPathBuf, RelativePath};
+use mun_project::EngineApi;
+use mun_std::{CORE_PACKAGE_NAME, CORE_PACKAGE_SOURCES, STD_PACKAGE_NAME, STD_PACKAGE_SOURCES};
+use mun_syntax::SyntaxKind;
 
 use super::LanguageServerState;
 use crate::{change::AnalysisChange, config::FilesWatcher};
 
+/// A package whose sources are embedded in the toolchain.
+struct BuiltinPackage {
+    /// The name by which other packages refer to the package
+    name: &'static str,
+    /// The relative paths and contents of all source files of the package
+    sources: &'static [(&'static str, &'static str)],
+    /// Whether the public items of the package are in scope in all other
+    /// packages
+    is_prelude: bool,
+    /// Whether all other packages, except the prelude, depend on the package
+    is_implicit_dependency: bool,
+}
+
+/// The packages that the compiler adds to the package set of every package.
+/// Their source roots follow those of the packages of the workspace, in this
+/// order.
+const BUILTIN_PACKAGES: &[BuiltinPackage] = &[
+    BuiltinPackage {
+        name: CORE_PACKAGE_NAME,
+        sources: CORE_PACKAGE_SOURCES,
+        is_prelude: true,
+        is_implicit_dependency: false,
+    },
+    BuiltinPackage {
+        name: STD_PACKAGE_NAME,
+        sources: STD_PACKAGE_SOURCES,
+        is_prelude: false,
+        is_implicit_dependency: true,
+    },
+];
+
 impl LanguageServerState {
     /// Called to update all workspaces from the files
     pub(crate) fn fetch_workspaces(&mut self) {
@@ -32,6 +67,8 @@ impl LanguageServerState {
                 },
             )
             .collect::<Vec<_>>();
+        let num_discovered = packages.len();
+        let packages = self.load_dependencies(packages);
 
         // If these packages are the same as the ones we already had, there is little to
         // do.
@@ -39,6 +76,15 @@ impl LanguageServerState {
             return;
         }
 
+        // Warn about packages that require a different version of the toolchain
+        let toolchain = semver::Version::parse(env!("CARGO_PKG_VERSION"))
+            .expect("the version of the language server is not a valid semantic version");
+        for package in &packages {
+            if let Err(err) = package.check_mun_version(&toolchain) {
+                self.show_message(lsp_types::MessageType::ERROR, format!("{err:#}"));
+            }
+        }
+
         // If we use the client to watch for file changes, communicate a request to the
         // client
         if self.config.watcher == FilesWatcher::Client {
@@ -96,25 +142,155 @@ impl LanguageServerState {
 
         // Create the set of packages
         let mut package_set = PackageSet::default();
-        for (idx, _package) in packages.iter().enumerate() {
-            package_set.add_package(SourceRootId(idx as u32));
+        let package_ids = packages
+            .iter()
+            .enumerate()
+            .map(|(idx, package)| {
+                let source_root = SourceRootId(idx as u32);
+                if idx < num_discovered {
+                    package_set.add_package(source_root)
+                } else {
+                    package_set.add_library(package.name().replace('-', "_"), source_root)
+                }
+            })
+            .collect::<Vec<_>>();
+        for (package, &package_id) in packages.iter().zip(&package_ids) {
+            for (name, manifest_path) in package.dependency_manifest_paths() {
+                if let Some(idx) = find_package(&packages, &manifest_path) {
+                    package_set.add_dependency(package_id, name, package_ids[idx]);
+                }
+            }
+        }
+
+        // Add the builtin packages the same way the compiler does
+        let builtin_ids = BUILTIN_PACKAGES
+            .iter()
+            .enumerate()
+            .map(|(idx, builtin)| {
+                let source_root = SourceRootId((packages.len() + idx) as u32);
+                let package_id = package_set.add_library(builtin.name, source_root);
+                if builtin.is_prelude {
+                    package_set.set_prelude(package_id);
+                }
+                package_id
+            })
+            .collect::<Vec<_>>();
+        for (builtin, &builtin_id) in BUILTIN_PACKAGES.iter().zip(&builtin_ids) {
+            if !builtin.is_implicit_dependency {
+                continue;
+            }
+            let dependents = package_set
+                .iter()
+                .filter(|&package| package != builtin_id && Some(package) != package_set.prelude())
+                .collect::<Vec<_>>();
+            for package in dependents {
+                package_set.add_dependency(package, builtin.name, builtin_id);
+            }
         }
         change.set_packages(package_set);
 
         // Store the current set of packages and update the source roots
         self.packages = Arc::new(packages);
+        self.load_builtin_sources();
+        self.generate_engine_api_sources();
         change.set_roots(self.recompute_source_roots());
 
         // Apply all changes to the database
         self.analysis.apply_change(change);
     }
 
+    /// Appends the packages that the specified `packages` depend on, directly
+    /// or indirectly, to `packages`. Every package is only added once.
+    fn load_dependencies(
+        &mut self,
+        mut packages: Vec<mun_project::Package>,
+    ) -> Vec<mun_project::Package> {
+        let mut idx = 0;
+        while idx < packages.len() {
+            let manifest_paths = packages[idx]
+                .dependency_manifest_paths()
+                .map(|(_, manifest_path)| manifest_path)
+                .collect::<Vec<_>>();
+            for manifest_path in manifest_paths {
+                if find_package(&packages, &manifest_path).is_some() {
+                    continue;
+                }
+                match mun_project::Package::from_file(&manifest_path) {
+                    Ok(package) => packages.push(package),
+                    Err(err) => self.show_message(
+                        lsp_types::MessageType::ERROR,
+                        format!("mun failed to load dependency: {err:#}"),
+                    ),
+                }
+            }
+            idx += 1;
+        }
+        packages
+    }
+
+    /// Stores the sources of the [builtin packages](BUILTIN_PACKAGES) in the
+    /// virtual filesystem. They are also written to disk, so that navigating to
+    /// an item of a builtin package opens an actual file.
+    fn load_builtin_sources(&mut self) {
+        let vfs = &mut *self.vfs.write();
+        for builtin in BUILTIN_PACKAGES {
+            let source_dir = builtin_source_dir(builtin.name);
+            for (rel_path, contents) in builtin.sources {
+                let path = source_dir.join(rel_path);
+
+                // The analysis only uses the contents in the virtual filesystem, so failing
+                // to write the file only affects navigation
+                if let Some(parent) = path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                let _ = std::fs::write(&path, contents);
+
+                vfs.set_file_contents(&path, Some(contents.as_bytes().to_vec()));
+            }
+        }
+    }
+
+    /// Generates the `extern` declarations of the engine API descriptions of
+    /// all packages, the same way the compiler does, and stores them in the
+    /// virtual filesystem as a source file in the source directory of their
+    /// package.
+    fn generate_engine_api_sources(&mut self) {
+        let mut errors = Vec::new();
+        {
+            let vfs = &mut *self.vfs.write();
+            for package in self.packages.iter() {
+                let Some(engine_api_path) = package.engine_api_path() else {
+                    continue;
+                };
+                match generate_engine_api_source(&engine_api_path) {
+                    Ok((file_name, contents)) => {
+                        let source_dir = AbsPathBuf::try_from(package.source_directory())
+                            .expect("must be able to convert source dir to absolute path");
+                        vfs.set_file_contents(
+                            &source_dir.join(file_name),
+                            Some(contents.into_bytes()),
+                        );
+                    }
+                    Err(err) => errors.push(err),
+                }
+            }
+        }
+
+        for err in errors {
+            self.show_message(
+                lsp_types::MessageType::ERROR,
+                format!("mun failed to generate engine API declarations: {err:#}"),
+            );
+        }
+    }
+
     /// Recomputes all the source roots based on the `packages`
     pub(crate) fn recompute_source_roots(&self) -> Vec<SourceRoot> {
         // Iterate over all sources and see to which package they belong
-        let mut source_roots = vec![SourceRoot::default(); self.packages.len()];
+        let mut source_roots =
+            vec![SourceRoot::default(); self.packages.len() + BUILTIN_PACKAGES.len()];
 
-        // Source directories
+        // Source directories, followed by those of the builtin packages
         let source_dirs = self
             .packages
             .iter()
@@ -122,6 +298,11 @@ impl LanguageServerState {
                 AbsPathBuf::try_from(p.source_directory())
                     .expect("must be able to convert source dir to absolute path")
             })
+            .chain(
+                BUILTIN_PACKAGES
+                    .iter()
+                    .map(|builtin| builtin_source_dir(builtin.name)),
+            )
             .collect::<Vec<_>>();
 
         // Iterate over all files and find to which source directory they belong,
@@ -146,3 +327,40 @@ impl LanguageServerState {
         source_roots
     }
 }
+
+/// Returns the directory in which the sources of the builtin package `name` are
+/// stored. The directory is specific to the version of the language server,
+/// because the sources of the builtin packages change between versions.
+fn builtin_source_dir(name: &str) -> AbsPathBuf {
+    let path = std::env::temp_dir()
+        .join("mun_language_server")
+        .join(env!("CARGO_PKG_VERSION"))
+        .join(name);
+    AbsPathBuf::try_from(path).expect("the temporary directory is not an absolute path")
+}
+
+/// Generates the source file that declares the engine API described by the
+/// file at `path`. Returns the name of the source file and its contents.
+fn generate_engine_api_source(path: &Path) -> anyhow::Result<(String, String)> {
+    let is_keyword = |name: &str| SyntaxKind::from_keyword(name).is_some();
+    let module_name = EngineApi::module_name(path, is_keyword)?;
+    let engine_api = EngineApi::from_file(path)?;
+    let origin = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let contents = engine_api.to_extern_declarations(&origin, is_keyword)?;
+    Ok((format!("{module_name}.mun"), contents))
+}
+
+/// Returns the index of the package in `packages` whose manifest is located at
+/// `manifest_path`.
+fn find_package(packages: &[mun_project::Package], manifest_path: &Path) -> Option<usize> {
+    let manifest_path = manifest_path.canonicalize().ok()?;
+    packages.iter().position(|package| {
+        package
+            .manifest_path()
+            .canonicalize()
+            .is_ok_and(|path| path == manifest_path)
+    })
+}
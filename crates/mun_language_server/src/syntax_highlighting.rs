@@ -0,0 +1,268 @@
+//! Semantic highlighting of a source file. Instead of highlighting tokens
+//! based on their syntax alone, every name in the file is resolved to the
+//! definition it refers to. This allows editors to tell apart e.g. a local that
+//! shadows a function from the function itself.
+
+use mun_hir::{semantics::Semantics, HirDatabase, ModuleDef};
+use mun_hir_input::FileId;
+use mun_syntax::{AstNode, SyntaxElement, SyntaxToken, TextRange};
+
+use crate::{
+    db::AnalysisDatabase,
+    definition::{classify_token, is_reference_token, Definition, NameRefClass, TokenClass},
+};
+
+/// A range in a file and how it should be highlighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighlightedRange {
+    pub range: TextRange,
+    pub highlight: Highlight,
+}
+
+/// Describes how a range should be highlighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Highlight {
+    pub tag: HighlightTag,
+
+    /// True if the range is the name of a definition, e.g. `foo` in `fn foo()`
+    pub declaration: bool,
+
+    /// True if the range refers to a local that is assigned to after its
+    /// declaration
+    pub mutable: bool,
+}
+
+/// The kind of item that a highlighted range refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightTag {
+    BuiltinType,
+    Field,
+    Function,
+    Local,
+    Module,
+    Parameter,
+    SelfParam,
+    Struct,
+    TypeAlias,
+}
+
+/// Computes the semantic highlighting of the specified file. If `range` is
+/// specified, only the tokens that intersect with it are highlighted.
+pub(crate) fn highlight(
+    db: &AnalysisDatabase,
+    file_id: FileId,
+    range: Option<TextRange>,
+) -> Vec<HighlightedRange> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+
+    file.syntax()
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter(|token| is_reference_token(token.kind()))
+        .filter(|token| range.map_or(true, |range| range.intersect(token.text_range()).is_some()))
+        .filter_map(|token| {
+            let highlight = highlight_token(&sema, &token)?;
+            Some(HighlightedRange {
+                range: token.text_range(),
+                highlight,
+            })
+        })
+        .collect()
+}
+
+/// Determines the highlighting of a single token by resolving it.
+fn highlight_token(sema: &Semantics<'_>, token: &SyntaxToken) -> Option<Highlight> {
+    let (def, declaration) = match classify_token(sema, token)? {
+        TokenClass::Name(def) => (def, true),
+        TokenClass::NameRef(NameRefClass::Definition(def)) => (def, false),
+        // The shorthand `Foo { a }` reads the local, so highlight it as such
+        TokenClass::NameRef(NameRefClass::FieldShorthand { local, .. }) => {
+            (Definition::Local(local), false)
+        }
+    };
+
+    Some(Highlight {
+        tag: highlight_tag(sema.db, def),
+        declaration,
+        mutable: matches!(def, Definition::Local(local) if local.is_mutated(sema.db)),
+    })
+}
+
+/// Returns the `HighlightTag` for the specified definition.
+fn highlight_tag(db: &dyn HirDatabase, def: Definition) -> HighlightTag {
+    match def {
+        Definition::ModuleDef(def) => match def {
+            ModuleDef::Module(_) => HighlightTag::Module,
+            ModuleDef::Function(_) => HighlightTag::Function,
+            ModuleDef::PrimitiveType(_) => HighlightTag::BuiltinType,
+            ModuleDef::Struct(_) => HighlightTag::Struct,
+            ModuleDef::TypeAlias(_) => HighlightTag::TypeAlias,
+        },
+        Definition::Field(_) => HighlightTag::Field,
+        Definition::Local(local) => {
+            if local.is_self(db) {
+                HighlightTag::SelfParam
+            } else if local.is_param(db) {
+                HighlightTag::Parameter
+            } else {
+                HighlightTag::Local
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mun_hir_input::SourceDatabase;
+    use mun_syntax::TextRange;
+
+    use crate::{change_fixture::ChangeFixture, db::AnalysisDatabase};
+
+    /// Returns a string representation of the highlighting of the first file
+    /// in the fixture. Every highlighted range is written on a separate line
+    /// as its range, its text, its tag and its modifiers.
+    fn highlight_string(fixture: &str, range: Option<TextRange>) -> String {
+        let fixture = ChangeFixture::parse(fixture);
+        let file_id = fixture.files[0];
+        let mut db = AnalysisDatabase::default();
+        db.apply_change(fixture.change);
+
+        let text = db.file_text(file_id);
+        super::highlight(&db, file_id, range)
+            .into_iter()
+            .map(|range| {
+                let mut line = format!(
+                    "{:?} {}: {:?}",
+                    range.range, &text[range.range], range.highlight.tag
+                );
+                if range.highlight.declaration {
+                    line.push_str(" declaration");
+                }
+                if range.highlight.mutable {
+                    line.push_str(" mutable");
+                }
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn highlight_items() {
+        insta::assert_snapshot!(highlight_string(
+            r#"
+        //- /mod.mun
+        struct Foo { a: i32 }
+        type Bar = Foo;
+        fn new(a: i32) -> Bar { Foo { a } }
+        fn get(foo: Foo) -> i32 { foo::bar(); foo.a }
+
+        //- /foo.mun
+        pub fn bar() {}
+        "#,
+            None
+        ), @r###"
+        7..10 Foo: Struct declaration
+        13..14 a: Field declaration
+        16..19 i32: BuiltinType
+        27..30 Bar: TypeAlias declaration
+        33..36 Foo: Struct
+        41..44 new: Function declaration
+        45..46 a: Parameter declaration
+        48..51 i32: BuiltinType
+        56..59 Bar: TypeAlias
+        62..65 Foo: Struct
+        68..69 a: Parameter
+        77..80 get: Function declaration
+        81..84 foo: Parameter declaration
+        86..89 Foo: Struct
+        94..97 i32: BuiltinType
+        100..103 foo: Module
+        105..108 bar: Function
+        112..115 foo: Parameter
+        116..117 a: Field
+        "###);
+    }
+
+    #[test]
+    fn highlight_locals() {
+        insta::assert_snapshot!(highlight_string(
+            r#"
+        struct Foo { b: i32 }
+        impl Foo {
+            fn get(self, a: i32) -> i32 {
+                let b = a;
+                let c;
+                c = b;
+                a += c;
+                let a = 3;
+                a + self.b
+            }
+        }
+        "#,
+            None
+        ), @r###"
+        7..10 Foo: Struct declaration
+        13..14 b: Field declaration
+        16..19 i32: BuiltinType
+        27..30 Foo: Struct
+        40..43 get: Function declaration
+        44..48 self: SelfParam declaration
+        50..51 a: Parameter declaration mutable
+        53..56 i32: BuiltinType
+        61..64 i32: BuiltinType
+        79..80 b: Local declaration
+        83..84 a: Parameter mutable
+        98..99 c: Local declaration mutable
+        109..110 c: Local mutable
+        113..114 b: Local
+        124..125 a: Parameter mutable
+        129..130 c: Local mutable
+        144..145 a: Local declaration
+        159..160 a: Local
+        163..167 self: SelfParam
+        168..169 b: Field
+        "###);
+    }
+
+    #[test]
+    fn highlight_shadowed_function() {
+        insta::assert_snapshot!(highlight_string(
+            r#"
+        fn foo() -> i32 { 3 }
+        fn main() -> i32 {
+            let a = foo();
+            let foo = a;
+            foo
+        }
+        "#,
+            None
+        ), @r###"
+        3..6 foo: Function declaration
+        12..15 i32: BuiltinType
+        25..29 main: Function declaration
+        35..38 i32: BuiltinType
+        49..50 a: Local declaration
+        53..56 foo: Function
+        68..71 foo: Local declaration
+        74..75 a: Local
+        81..84 foo: Local
+        "###);
+    }
+
+    #[test]
+    fn highlight_range() {
+        insta::assert_snapshot!(highlight_string(
+            r#"
+        fn foo(a: i32) -> i32 { a }
+        fn bar(b: i32) -> i32 { b }
+        "#,
+            Some(TextRange::new(31.into(), 40.into()))
+        ), @r###"
+        31..34 bar: Function declaration
+        35..36 b: Parameter declaration
+        38..41 i32: BuiltinType
+        "###);
+    }
+}
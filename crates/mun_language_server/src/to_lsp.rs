@@ -4,13 +4,21 @@ use std::{
 };
 
 use lsp_types::Url;
+use mun_hir::diagnostics::Severity;
 use mun_hir_input::{FileId, LineIndex};
 use mun_syntax::{TextRange, TextSize};
 
 use crate::{
     completion::{CompletionItem, CompletionItemKind},
+    diagnostics::Diagnostic,
+    inlay_hints::{InlayHint, InlayKind},
+    navigation_target::NavigationTarget,
+    semantic_tokens::{self, ModifierSet, SemanticTokensBuilder},
+    source_change::SourceChange,
     state::LanguageServerSnapshot,
     symbol_kind::SymbolKind,
+    syntax_highlighting::{Highlight, HighlightTag, HighlightedRange},
+    FileRange,
 };
 
 /// Returns a `Url` object from a given path, will lowercase drive letters if
@@ -90,6 +98,32 @@ pub(crate) fn url(snapshot: &LanguageServerSnapshot, file_id: FileId) -> anyhow:
     Ok(url)
 }
 
+/// Converts the specified `FileRange` to an LSP `Location`.
+pub(crate) fn location(
+    snapshot: &LanguageServerSnapshot,
+    file_range: FileRange,
+) -> anyhow::Result<lsp_types::Location> {
+    let url = url(snapshot, file_range.file_id)?;
+    let line_index = snapshot.analysis.file_line_index(file_range.file_id)?;
+    let range = range(file_range.range, &line_index);
+    Ok(lsp_types::Location::new(url, range))
+}
+
+/// Converts the specified `NavigationTarget` to an LSP `Location` that refers
+/// to the focus range of the target.
+pub(crate) fn location_from_nav(
+    snapshot: &LanguageServerSnapshot,
+    target: NavigationTarget,
+) -> anyhow::Result<lsp_types::Location> {
+    location(
+        snapshot,
+        FileRange {
+            file_id: target.file_id,
+            range: target.focus_or_full_range(),
+        },
+    )
+}
+
 /// Converts from our `CompletionItem` to an LSP `CompletionItem`
 pub(crate) fn completion_item(completion_item: CompletionItem) -> lsp_types::CompletionItem {
     lsp_types::CompletionItem {
@@ -122,3 +156,150 @@ pub(crate) fn completion_item_kind(
         CompletionItemKind::Attribute => lsp_types::CompletionItemKind::ENUM_MEMBER,
     }
 }
+
+/// Converts a `TextEdit` to LSP `TextEdit`s.
+pub(crate) fn text_edits(
+    text_edit: ra_ap_text_edit::TextEdit,
+    line_index: &LineIndex,
+) -> Vec<lsp_types::TextEdit> {
+    text_edit
+        .into_iter()
+        .map(|indel| lsp_types::TextEdit::new(range(indel.delete, line_index), indel.insert))
+        .collect()
+}
+
+/// Converts a `SourceChange` to an LSP `WorkspaceEdit`.
+pub(crate) fn workspace_edit(
+    snapshot: &LanguageServerSnapshot,
+    source_change: SourceChange,
+) -> anyhow::Result<lsp_types::WorkspaceEdit> {
+    let mut changes = std::collections::HashMap::new();
+    for (file_id, edit) in source_change.source_file_edits {
+        let line_index = snapshot.analysis.file_line_index(file_id)?;
+        changes.insert(url(snapshot, file_id)?, text_edits(edit, &line_index));
+    }
+    Ok(lsp_types::WorkspaceEdit::new(changes))
+}
+
+/// Converts a `Diagnostic` to an LSP diagnostic. Its fix is not part of the
+/// LSP diagnostic, but is provided separately as a code action.
+pub(crate) fn diagnostic(
+    snapshot: &LanguageServerSnapshot,
+    diagnostic: Diagnostic,
+    line_index: &LineIndex,
+) -> anyhow::Result<lsp_types::Diagnostic> {
+    let related_information = diagnostic
+        .additional_annotations
+        .into_iter()
+        .map(|annotation| {
+            Ok(lsp_types::DiagnosticRelatedInformation {
+                location: lsp_types::Location {
+                    uri: url(snapshot, annotation.range.file_id)?,
+                    range: range(
+                        annotation.range.value,
+                        &*snapshot
+                            .analysis
+                            .file_line_index(annotation.range.file_id)?,
+                    ),
+                },
+                message: annotation.message,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(lsp_types::Diagnostic {
+        range: range(diagnostic.range, line_index),
+        severity: Some(match diagnostic.severity {
+            Severity::Error => lsp_types::DiagnosticSeverity::ERROR,
+            Severity::Warning => lsp_types::DiagnosticSeverity::WARNING,
+        }),
+        code: None,
+        code_description: None,
+        source: Some("mun".to_string()),
+        message: diagnostic.message,
+        related_information: if related_information.is_empty() {
+            None
+        } else {
+            Some(related_information)
+        },
+        tags: None,
+        data: None,
+    })
+}
+
+/// Converts an `InlayHint` to an LSP inlay hint. Type hints are shown after
+/// the name of a binding, parameter hints before the argument.
+pub(crate) fn inlay_hint(line_index: &LineIndex, inlay_hint: InlayHint) -> lsp_types::InlayHint {
+    let (offset, label, kind) = match inlay_hint.kind {
+        InlayKind::TypeHint => (
+            inlay_hint.range.end(),
+            format!(": {}", inlay_hint.label),
+            lsp_types::InlayHintKind::TYPE,
+        ),
+        InlayKind::ParameterHint => (
+            inlay_hint.range.start(),
+            format!("{}:", inlay_hint.label),
+            lsp_types::InlayHintKind::PARAMETER,
+        ),
+    };
+
+    lsp_types::InlayHint {
+        position: position(offset, line_index),
+        label: lsp_types::InlayHintLabel::String(label),
+        kind: Some(kind),
+        text_edits: None,
+        tooltip: None,
+        padding_left: None,
+        padding_right: (kind == lsp_types::InlayHintKind::PARAMETER).then_some(true),
+        data: None,
+    }
+}
+
+/// Converts highlighted ranges to LSP semantic tokens.
+pub(crate) fn semantic_tokens(
+    line_index: &LineIndex,
+    highlights: Vec<HighlightedRange>,
+) -> lsp_types::SemanticTokens {
+    let mut builder = SemanticTokensBuilder::default();
+    for highlighted_range in highlights {
+        let (token_type, modifiers) =
+            semantic_token_type_and_modifiers(highlighted_range.highlight);
+        builder.push(
+            range(highlighted_range.range, line_index),
+            semantic_tokens::type_index(&token_type),
+            modifiers.0,
+        );
+    }
+    builder.build()
+}
+
+/// Converts a `Highlight` to an LSP semantic token type and its modifiers.
+fn semantic_token_type_and_modifiers(
+    highlight: Highlight,
+) -> (lsp_types::SemanticTokenType, ModifierSet) {
+    let mut modifiers = ModifierSet::default();
+    let token_type = match highlight.tag {
+        HighlightTag::BuiltinType => {
+            modifiers |= lsp_types::SemanticTokenModifier::DEFAULT_LIBRARY;
+            lsp_types::SemanticTokenType::TYPE
+        }
+        HighlightTag::Field => lsp_types::SemanticTokenType::PROPERTY,
+        HighlightTag::Function => lsp_types::SemanticTokenType::FUNCTION,
+        HighlightTag::Local => lsp_types::SemanticTokenType::VARIABLE,
+        HighlightTag::Module => lsp_types::SemanticTokenType::NAMESPACE,
+        HighlightTag::Parameter | HighlightTag::SelfParam => {
+            lsp_types::SemanticTokenType::PARAMETER
+        }
+        HighlightTag::Struct => lsp_types::SemanticTokenType::STRUCT,
+        HighlightTag::TypeAlias => lsp_types::SemanticTokenType::TYPE,
+    };
+
+    if highlight.declaration {
+        modifiers |= lsp_types::SemanticTokenModifier::DECLARATION;
+    }
+    if highlight.mutable {
+        modifiers |= semantic_tokens::MUTABLE;
+    }
+
+    (token_type, modifiers)
+}
@@ -50,3 +50,334 @@ fn test_document_symbols() {
 
     insta::assert_debug_snapshot!(symbols);
 }
+
+#[test]
+fn test_unsaved_document_contents() {
+    let server = Project::with_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name = "foo"
+    version = "0.0.0"
+
+    //- /src/mod.mun
+    fn main() -> i32 {}
+    "#,
+    )
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    let symbol_names = || {
+        let symbols = server.send_request::<lsp_types::request::DocumentSymbolRequest>(
+            lsp_types::DocumentSymbolParams {
+                text_document: server.doc_id("src/mod.mun"),
+                work_done_progress_params: WorkDoneProgressParams::default(),
+                partial_result_params: PartialResultParams::default(),
+            },
+        );
+        match symbols {
+            Some(lsp_types::DocumentSymbolResponse::Nested(symbols)) => symbols
+                .into_iter()
+                .map(|symbol| symbol.name)
+                .collect::<Vec<_>>(),
+            _ => panic!("expected nested document symbols"),
+        }
+    };
+
+    // The contents of an open document take precedence over the contents on disk
+    server.notification::<lsp_types::notification::DidOpenTextDocument>(
+        lsp_types::DidOpenTextDocumentParams {
+            text_document: lsp_types::TextDocumentItem {
+                uri: server.doc_id("src/mod.mun").uri,
+                language_id: String::from("mun"),
+                version: 0,
+                text: String::from("fn main() -> i32 {}\nfn unsaved() {}"),
+            },
+        },
+    );
+    assert_eq!(symbol_names(), vec!["main", "unsaved"]);
+
+    // After closing the document, the contents on disk are used again
+    server.notification::<lsp_types::notification::DidCloseTextDocument>(
+        lsp_types::DidCloseTextDocumentParams {
+            text_document: server.doc_id("src/mod.mun"),
+        },
+    );
+    assert_eq!(symbol_names(), vec!["main"]);
+}
+
+#[test]
+fn test_semantic_tokens() {
+    let server = Project::with_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name = "foo"
+    version = "0.0.0"
+
+    //- /src/mod.mun
+    fn add(a: i32, b: i32) -> i32 {
+        let c = a;
+        c = c + b;
+        c
+    }
+    "#,
+    )
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    let tokens = server.send_request::<lsp_types::request::SemanticTokensFullRequest>(
+        lsp_types::SemanticTokensParams {
+            text_document: server.doc_id("src/mod.mun"),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        },
+    );
+
+    insta::assert_debug_snapshot!(tokens);
+}
+
+#[test]
+fn test_inlay_hints() {
+    let server = Project::with_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name = "foo"
+    version = "0.0.0"
+
+    //- /src/mod.mun
+    fn add(a: i32, b: i32) -> i32 {
+        let c = a;
+        add(c, 3)
+    }
+    "#,
+    )
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    let params = lsp_types::InlayHintParams {
+        text_document: server.doc_id("src/mod.mun"),
+        range: lsp_types::Range::new(
+            lsp_types::Position::new(0, 0),
+            lsp_types::Position::new(3, 1),
+        ),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+    };
+    let hints = server.send_request::<lsp_types::request::InlayHintRequest>(params.clone());
+    insta::assert_debug_snapshot!(hints);
+
+    // Disable the type hints
+    server.notification::<lsp_types::notification::DidChangeConfiguration>(
+        lsp_types::DidChangeConfigurationParams {
+            settings: serde_json::json!({ "inlayHints": { "typeHints": false } }),
+        },
+    );
+    let hints = server.send_request::<lsp_types::request::InlayHintRequest>(params);
+    insta::assert_debug_snapshot!(hints);
+}
+
+#[test]
+fn test_formatting() {
+    let server = Project::with_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name = "foo"
+    version = "0.0.0"
+
+    //- /src/mod.mun
+    fn add(a:i32,b:i32)->i32{a+b}
+
+    //- /src/bar.mun
+    fn main() {
+        let a = 1;
+    }
+    "#,
+    )
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    let params = |path| lsp_types::DocumentFormattingParams {
+        text_document: server.doc_id(path),
+        options: lsp_types::FormattingOptions {
+            tab_size: 4,
+            insert_spaces: true,
+            ..Default::default()
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+    };
+
+    let edits = server.send_request::<lsp_types::request::Formatting>(params("src/mod.mun"));
+    insta::assert_debug_snapshot!(edits);
+
+    // An already formatted document requires no edits
+    let edits = server.send_request::<lsp_types::request::Formatting>(params("src/bar.mun"));
+    assert_eq!(edits, Some(Vec::new()));
+}
+
+#[test]
+fn test_code_actions() {
+    let server = Project::with_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name = "foo"
+    version = "0.0.0"
+
+    //- /src/mod.mun
+    struct Foo { a: i32, b: bool }
+    fn main() -> Foo {
+        Foo { a: 1 }
+    }
+    "#,
+    )
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    let text_document = server.doc_id("src/mod.mun");
+    let uri = text_document.uri.clone();
+    let actions = server
+        .send_request::<lsp_types::request::CodeActionRequest>(lsp_types::CodeActionParams {
+            text_document,
+            range: lsp_types::Range::new(
+                lsp_types::Position::new(2, 4),
+                lsp_types::Position::new(2, 16),
+            ),
+            context: lsp_types::CodeActionContext::default(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .expect("expected code actions");
+
+    // The URI of the document differs between runs, so only snapshot the edits
+    let actions: Vec<_> = actions
+        .into_iter()
+        .map(|action| match action {
+            lsp_types::CodeActionOrCommand::CodeAction(action) => {
+                let edits = action
+                    .edit
+                    .and_then(|edit| edit.changes)
+                    .and_then(|mut changes| changes.remove(&uri));
+                (action.title, action.kind, edits)
+            }
+            lsp_types::CodeActionOrCommand::Command(_) => panic!("expected a code action"),
+        })
+        .collect();
+    insta::assert_debug_snapshot!(actions);
+}
+
+#[test]
+fn test_dependency_diagnostics() {
+    let server = Project::with_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name = "app"
+    version = "0.0.0"
+
+    [dependencies]
+    lib = { path = "lib" }
+
+    //- /src/mod.mun
+    pub fn main() -> i32 { lib::value() }
+
+    //- /lib/mun.toml
+    [package]
+    name = "lib"
+    version = "0.0.0"
+
+    //- /lib/src/mod.mun
+    pub fn value() -> i32 { 5 }
+    "#,
+    )
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    server.wait_for_diagnostics("src/mod.mun", <[_]>::is_empty);
+
+    // Removing a function from a dependency changes the diagnostics of the packages
+    // that use it, even though their own files did not change
+    server.notification::<lsp_types::notification::DidOpenTextDocument>(
+        lsp_types::DidOpenTextDocumentParams {
+            text_document: lsp_types::TextDocumentItem {
+                uri: server.doc_id("lib/src/mod.mun").uri,
+                language_id: String::from("mun"),
+                version: 0,
+                text: String::from("pub fn other() -> i32 { 5 }"),
+            },
+        },
+    );
+    server.wait_for_diagnostics("src/mod.mun", |diagnostics| !diagnostics.is_empty());
+}
+
+#[test]
+fn test_engine_api_declarations() {
+    let server = Project::with_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name = "app"
+    version = "0.0.0"
+    engine-api = "engine_api.toml"
+
+    //- /engine_api.toml
+    [[functions]]
+    name = "now"
+    return_type = "f64"
+
+    //- /src/mod.mun
+    pub fn main() -> f64 { engine_api::now() }
+    "#,
+    )
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    // The declarations of the engine API are generated, so `engine_api::now`
+    // resolves
+    server.wait_for_diagnostics("src/mod.mun", <[_]>::is_empty);
+}
+
+#[test]
+fn test_core_package() {
+    let server = Project::with_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name = "app"
+    version = "0.0.0"
+
+    //- /src/mod.mun
+    pub fn main() -> f32 {
+        assert(true);
+        core::math::abs_i32(-1);
+        abs(-1.0)
+    }
+    "#,
+    )
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    // The items of the `core` package are in scope, like they are when compiling
+    server.wait_for_diagnostics("src/mod.mun", <[_]>::is_empty);
+}
+
+#[test]
+fn test_std_package() {
+    let server = Project::with_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name = "app"
+    version = "0.0.0"
+
+    //- /src/mod.mun
+    pub fn hypot(a: f64, b: f64) -> f64 { std::math::sqrt(a * a + b * b) }
+    "#,
+    )
+    .server()
+    .wait_until_workspace_is_loaded();
+
+    // `std` paths resolve, like they do when compiling
+    server.wait_for_diagnostics("src/mod.mun", <[_]>::is_empty);
+}
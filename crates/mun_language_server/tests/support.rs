@@ -8,8 +8,9 @@ use std::{
 use crossbeam_channel::{after, select};
 use lsp_server::{Connection, Message, Notification, Request};
 use lsp_types::{
-    notification::Exit, request::Shutdown, ProgressParams, ProgressParamsValue, Url,
-    WorkDoneProgress,
+    notification::{Exit, Notification as _, PublishDiagnostics},
+    request::Shutdown,
+    ProgressParams, ProgressParamsValue, PublishDiagnosticsParams, Url, WorkDoneProgress,
 };
 use mun_hir_input::Fixture;
 use mun_language_server::{main_loop, Config, FilesWatcher};
@@ -62,6 +63,7 @@ impl<'a> Project<'a> {
         let config = Config {
             discovered_projects: Some(discovered_projects),
             watcher: FilesWatcher::Client,
+            cache_dir: Some(tmp_dir_path.join("cache")),
             ..Config::new(tmp_dir_path)
         };
 
@@ -122,6 +124,43 @@ impl Server {
         self
     }
 
+    /// Waits until diagnostics for the document at `rel_path` are published
+    /// that satisfy `cond`, and returns them. Diagnostics that were already
+    /// received are also taken into account.
+    pub fn wait_for_diagnostics(
+        &self,
+        rel_path: &str,
+        cond: impl Fn(&[lsp_types::Diagnostic]) -> bool,
+    ) -> Vec<lsp_types::Diagnostic> {
+        let uri = self.doc_id(rel_path).uri;
+        let matching_diagnostics = |msg: &Message| match msg {
+            Message::Notification(n) if n.method == PublishDiagnostics::METHOD => {
+                let params = n
+                    .clone()
+                    .extract::<PublishDiagnosticsParams>(PublishDiagnostics::METHOD)
+                    .unwrap();
+                (params.uri == uri && cond(&params.diagnostics)).then_some(params.diagnostics)
+            }
+            _ => None,
+        };
+
+        let received = self
+            .messages
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(matching_diagnostics);
+        if let Some(diagnostics) = received {
+            return diagnostics;
+        }
+        loop {
+            let msg = self.recv().expect("no response");
+            if let Some(diagnostics) = matching_diagnostics(&msg) {
+                return diagnostics;
+            }
+        }
+    }
+
     /// A function to wait for a specific message to arrive
     fn wait_for_message_cond(&self, n: usize, cond: &dyn Fn(&Message) -> bool) {
         let mut total = 0;
@@ -170,7 +209,7 @@ impl Server {
     }
 
     /// Sends an LSP notification to the main loop.
-    fn notification<N: lsp_types::notification::Notification>(&self, params: N::Params)
+    pub fn notification<N: lsp_types::notification::Notification>(&self, params: N::Params)
     where
         N::Params: Serialize,
     {
@@ -0,0 +1,411 @@
+//! Inspection of munlibs without loading them as shared objects.
+//!
+//! A munlib exports its ABI version and its [`abi::AssemblyInfo`] as constants.
+//! A [`MunLibraryImage`] maps the segments of a munlib into memory the way the
+//! dynamic loader would, and applies the relocations that make the pointers in
+//! these constants refer to the mapped segments. Imports are not resolved and
+//! no code of the munlib is executed, so a munlib can be inspected before it
+//! is trusted.
+
+use std::{collections::HashMap, ffi::CStr, io, mem, path::Path, ptr, slice};
+
+use mun_abi as abi;
+use object::{
+    elf, macho, pe,
+    read::{
+        elf::{ElfFile64, FileHeader, Rela, SectionHeader, Sym},
+        macho::MachOFile64,
+        pe::PeFile64,
+    },
+    Endianness, Object, ObjectSegment,
+};
+
+/// The size of a pointer in a munlib
+const POINTER_SIZE: u64 = mem::size_of::<u64>() as u64;
+
+/// The maximum size of the mapped segments of a munlib. Munlibs are orders of
+/// magnitude smaller, this prevents a malformed munlib from exhausting memory.
+const MAX_IMAGE_SIZE: u64 = 256 * 1024 * 1024;
+
+/// An error that occurs upon construction of a [`MunLibraryImage`].
+#[derive(Debug, thiserror::Error)]
+pub enum ImageError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Failed to parse the munlib: {0}")]
+    InvalidObjectFile(#[from] object::Error),
+    #[error("The object file format of the munlib is not supported")]
+    UnsupportedFormat,
+    #[error("The relocations of the munlib are invalid")]
+    InvalidRelocations,
+    #[error("Address {0:#x} lies outside of the munlib")]
+    InvalidAddress(u64),
+    #[error("The segments of the munlib span {0} bytes, which exceeds the maximum size")]
+    TooLarge(u64),
+    #[error("The assembly info of the munlib is invalid: {0}")]
+    InvalidAssemblyInfo(#[from] abi::InvalidAssemblyInfo),
+    #[error("Missing symbol: {0}")]
+    MissingSymbol(&'static str),
+}
+
+/// The segments of a munlib, mapped into memory without loading the munlib as
+/// a shared object.
+pub struct MunLibraryImage {
+    /// The mapped segments. The memory is allocated as `u64`s, so that the
+    /// constants in the munlib are properly aligned.
+    memory: Vec<u64>,
+    /// The address of the first segment, as specified by the munlib
+    base_address: u64,
+    /// The ABI version of the munlib
+    abi_version: u32,
+    /// The version of the compiler that generated the munlib, if it is
+    /// recorded in the munlib
+    compiler_version: Option<String>,
+    /// The offset of the assembly info in `memory`
+    assembly_info_offset: usize,
+}
+
+impl MunLibraryImage {
+    /// Maps the munlib at `library_path` into memory.
+    pub fn new(library_path: &Path) -> Result<Self, ImageError> {
+        let bytes = std::fs::read(library_path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Maps the munlib with the specified contents into memory.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ImageError> {
+        // The layout of the ABI types depends on the size of a pointer and on
+        // the endianness of the target
+        let file = object::File::parse(bytes)?;
+        if !file.is_64()
+            || file.is_little_endian() != cfg!(target_endian = "little")
+            || !cfg!(target_pointer_width = "64")
+        {
+            return Err(ImageError::UnsupportedFormat);
+        }
+
+        let mut image = Self::map_segments(&file)?;
+        match &file {
+            object::File::Elf64(file) => image.relocate_elf(file)?,
+            object::File::MachO64(file) => image.relocate_macho(file)?,
+            object::File::Pe64(file) => image.relocate_pe(file)?,
+            _ => return Err(ImageError::UnsupportedFormat),
+        }
+
+        // Symbols of Mach-O files are prefixed with an underscore
+        let exports: HashMap<&[u8], u64> = file
+            .exports()?
+            .into_iter()
+            .map(|export| {
+                let name = export.name();
+                let name = match file.format() {
+                    object::BinaryFormat::MachO => name.strip_prefix(b"_").unwrap_or(name),
+                    _ => name,
+                };
+                (name, export.address())
+            })
+            .collect();
+        let find_export = |name: &'static str| {
+            exports
+                .get(name.as_bytes())
+                .copied()
+                .ok_or(ImageError::MissingSymbol(name))
+        };
+
+        let abi_version_address = find_export(abi::ABI_VERSION_SYMBOL_NAME)?;
+        let abi_version_offset = image.offset(abi_version_address, mem::size_of::<u32>())?;
+        image.abi_version = u32::from_ne_bytes(
+            image.bytes()[abi_version_offset..abi_version_offset + mem::size_of::<u32>()]
+                .try_into()
+                .unwrap(),
+        );
+
+        image.compiler_version = find_export(abi::COMPILER_VERSION_SYMBOL_NAME)
+            .ok()
+            .and_then(|address| {
+                let offset = image.offset(address, 0).ok()?;
+                let version = CStr::from_bytes_until_nul(&image.bytes()[offset..]).ok()?;
+                Some(version.to_string_lossy().into_owned())
+            });
+
+        let assembly_info_address = find_export(abi::ASSEMBLY_INFO_SYMBOL_NAME)?;
+        image.assembly_info_offset = image.offset(
+            assembly_info_address,
+            mem::size_of::<abi::AssemblyInfo<'_>>(),
+        )?;
+
+        Ok(image)
+    }
+
+    /// Returns the ABI version of this mun library.
+    pub fn get_abi_version(&self) -> u32 {
+        self.abi_version
+    }
+
+    /// Returns the version of the compiler that generated this mun library, or
+    /// `None` if the library was generated by a compiler that did not record
+    /// its version.
+    pub fn get_compiler_version(&self) -> Option<String> {
+        self.compiler_version.clone()
+    }
+
+    /// Returns the assembly info exported by the mun library. Its pointers
+    /// refer to the mapped segments of the library.
+    ///
+    /// Every pointer and length in the assembly info is validated against the
+    /// mapped segments before the assembly info is returned, so a malformed
+    /// munlib results in an error instead of undefined behavior.
+    pub fn get_info(&self) -> Result<abi::AssemblyInfo<'_>, ImageError> {
+        let info = self.bytes()[self.assembly_info_offset..]
+            .as_ptr()
+            .cast::<abi::AssemblyInfo<'_>>();
+        abi::validate_assembly_info(info, self.bytes())?;
+
+        // Safety: the assembly info was validated to lie within the mapped segments
+        Ok(unsafe { ptr::read(info) })
+    }
+
+    /// Copies the loadable segments of the `file` into memory, at their offset
+    /// from the first segment.
+    fn map_segments(file: &object::File<'_>) -> Result<Self, ImageError> {
+        let segments: Vec<_> = file
+            .segments()
+            .filter(|segment| segment.size() > 0)
+            .collect();
+
+        let base_address = segments
+            .iter()
+            .map(ObjectSegment::address)
+            .min()
+            .unwrap_or_default();
+        let end_address = segments
+            .iter()
+            .map(|segment| segment.address().checked_add(segment.size()))
+            .max()
+            .flatten()
+            .unwrap_or(base_address);
+        let size = end_address - base_address;
+        if size > MAX_IMAGE_SIZE {
+            return Err(ImageError::TooLarge(size));
+        }
+        let size = usize::try_from(size)
+            .ok()
+            .ok_or(ImageError::InvalidAddress(end_address))?;
+
+        let mut image = MunLibraryImage {
+            memory: vec![0; size.div_ceil(mem::size_of::<u64>())],
+            base_address,
+            abi_version: 0,
+            compiler_version: None,
+            assembly_info_offset: 0,
+        };
+
+        for segment in segments {
+            // The data in the file can be padded beyond the size of the segment
+            let data = segment.data()?;
+            let len = data.len().min(segment.size() as usize);
+            let offset = image.offset(segment.address(), len)?;
+            image.bytes_mut()[offset..offset + len].copy_from_slice(&data[..len]);
+        }
+
+        Ok(image)
+    }
+
+    /// Applies the dynamic relocations of an ELF file that point into the
+    /// image.
+    fn relocate_elf(&mut self, file: &ElfFile64<'_, Endianness>) -> Result<(), ImageError> {
+        let endian = file.endian();
+        let data = file.data();
+        let is_mips64el = file.elf_header().is_mips64el(endian);
+        let sections = file.elf_section_table();
+        let symbols = sections.symbols(endian, data, elf::SHT_DYNSYM)?;
+
+        for section in sections.iter() {
+            // Only the relocations of allocated sections are applied by the dynamic
+            // loader
+            if section.sh_flags(endian) & u64::from(elf::SHF_ALLOC) == 0 {
+                continue;
+            }
+            let Some((relocations, _)) = section.rela(endian, data)? else {
+                continue;
+            };
+
+            for relocation in relocations {
+                let addend = relocation.r_addend(endian) as u64;
+                let target = match relocation.r_type(endian, is_mips64el) {
+                    elf::R_X86_64_RELATIVE | elf::R_AARCH64_RELATIVE => addend,
+                    elf::R_X86_64_64 | elf::R_AARCH64_ABS64 => {
+                        let Some(index) = relocation.symbol(endian, is_mips64el) else {
+                            continue;
+                        };
+                        let symbol = symbols.symbol(index)?;
+                        if symbol.is_undefined(endian) {
+                            continue;
+                        }
+                        symbol.st_value(endian).wrapping_add(addend)
+                    }
+                    // Other relocations, e.g. of imported functions, are only used by code
+                    _ => continue,
+                };
+                self.relocate(relocation.r_offset(endian), target)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the rebase opcodes of a Mach-O file.
+    fn relocate_macho(&mut self, file: &MachOFile64<'_, Endianness>) -> Result<(), ImageError> {
+        let endian = file.endian();
+        let mut segment_addresses = Vec::new();
+        let mut opcodes: &[u8] = &[];
+
+        let mut commands = file.macho_load_commands()?;
+        while let Some(command) = commands.next()? {
+            if command.cmd() == macho::LC_DYLD_CHAINED_FIXUPS {
+                return Err(ImageError::UnsupportedFormat);
+            } else if let Some((segment, _)) = command.segment_64()? {
+                segment_addresses.push(segment.vmaddr.get(endian));
+            } else if let Some(dyld_info) = command.dyld_info()? {
+                let offset = dyld_info.rebase_off.get(endian) as usize;
+                let size = dyld_info.rebase_size.get(endian) as usize;
+                opcodes = file
+                    .data()
+                    .get(offset..offset.saturating_add(size))
+                    .ok_or(ImageError::InvalidRelocations)?;
+            }
+        }
+
+        let mut opcodes = opcodes.iter();
+        let mut address = 0u64;
+        while let Some(&opcode) = opcodes.next() {
+            let immediate = opcode & macho::REBASE_IMMEDIATE_MASK;
+            let (count, skip) = match opcode & macho::REBASE_OPCODE_MASK {
+                macho::REBASE_OPCODE_DONE => break,
+                macho::REBASE_OPCODE_SET_TYPE_IMM => continue,
+                macho::REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                    let segment_address = segment_addresses
+                        .get(usize::from(immediate))
+                        .ok_or(ImageError::InvalidRelocations)?;
+                    address = segment_address.wrapping_add(read_uleb128(&mut opcodes)?);
+                    continue;
+                }
+                macho::REBASE_OPCODE_ADD_ADDR_ULEB => {
+                    address = address.wrapping_add(read_uleb128(&mut opcodes)?);
+                    continue;
+                }
+                macho::REBASE_OPCODE_ADD_ADDR_IMM_SCALED => {
+                    address = address.wrapping_add(u64::from(immediate) * POINTER_SIZE);
+                    continue;
+                }
+                macho::REBASE_OPCODE_DO_REBASE_IMM_TIMES => (u64::from(immediate), 0),
+                macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES => (read_uleb128(&mut opcodes)?, 0),
+                macho::REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB => (1, read_uleb128(&mut opcodes)?),
+                macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB => {
+                    (read_uleb128(&mut opcodes)?, read_uleb128(&mut opcodes)?)
+                }
+                _ => return Err(ImageError::InvalidRelocations),
+            };
+
+            for _ in 0..count {
+                self.relocate(address, self.read_pointer(address)?)?;
+                address = address.wrapping_add(POINTER_SIZE).wrapping_add(skip);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the base relocations of a PE file.
+    fn relocate_pe(&mut self, file: &PeFile64<'_>) -> Result<(), ImageError> {
+        let image_base = file.relative_address_base();
+        let Some(mut blocks) = file
+            .data_directories()
+            .relocation_blocks(file.data(), &file.section_table())?
+        else {
+            return Ok(());
+        };
+
+        while let Some(block) = blocks.next()? {
+            for relocation in block {
+                if relocation.typ == pe::IMAGE_REL_BASED_DIR64 {
+                    let address = image_base.wrapping_add(relocation.virtual_address.into());
+                    self.relocate(address, self.read_pointer(address)?)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stores a pointer to the `target` address at `address`.
+    fn relocate(&mut self, address: u64, target: u64) -> Result<(), ImageError> {
+        let pointer =
+            (self.memory.as_ptr() as u64).wrapping_add(target.wrapping_sub(self.base_address));
+        let offset = self.offset(address, mem::size_of::<u64>())?;
+        self.bytes_mut()[offset..offset + mem::size_of::<u64>()]
+            .copy_from_slice(&pointer.to_ne_bytes());
+        Ok(())
+    }
+
+    /// Reads the pointer that is stored at `address`, as it is stored in the
+    /// file.
+    fn read_pointer(&self, address: u64) -> Result<u64, ImageError> {
+        let offset = self.offset(address, mem::size_of::<u64>())?;
+        Ok(u64::from_ne_bytes(
+            self.bytes()[offset..offset + mem::size_of::<u64>()]
+                .try_into()
+                .unwrap(),
+        ))
+    }
+
+    /// Returns the offset in memory of the `len` bytes at `address`.
+    fn offset(&self, address: u64, len: usize) -> Result<usize, ImageError> {
+        address
+            .checked_sub(self.base_address)
+            .and_then(|offset| usize::try_from(offset).ok())
+            .filter(|offset| {
+                offset
+                    .checked_add(len)
+                    .is_some_and(|end| end <= self.bytes().len())
+            })
+            .ok_or(ImageError::InvalidAddress(address))
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // Safety: any `u64` can be viewed as bytes
+        unsafe {
+            slice::from_raw_parts(
+                self.memory.as_ptr().cast::<u8>(),
+                self.memory.len() * mem::size_of::<u64>(),
+            )
+        }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        // Safety: any bytes form a valid `u64`
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.memory.as_mut_ptr().cast::<u8>(),
+                self.memory.len() * mem::size_of::<u64>(),
+            )
+        }
+    }
+}
+
+/// Reads an unsigned LEB128 encoded integer.
+fn read_uleb128(bytes: &mut slice::Iter<'_, u8>) -> Result<u64, ImageError> {
+    let mut result = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.next().ok_or(ImageError::InvalidRelocations)?;
+        if shift >= 64 {
+            return Err(ImageError::InvalidRelocations);
+        }
+        result |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+    }
+}
@@ -0,0 +1,305 @@
+//! Sealing and verification of the integrity of munlibs.
+//!
+//! Every munlib embeds an [`abi::IntegrityInfo`]. Sealing a munlib stores the
+//! SHA-256 hash of its contents, and optionally an Ed25519 signature of that
+//! hash, in the embedded integrity information. A munlib can then be verified
+//! before it is loaded, so that tampered libraries are never executed.
+
+use std::fmt::{self, Write};
+
+use ed25519_dalek::{Signature, Signer, Verifier};
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+use mun_abi as abi;
+use sha2::{Digest, Sha256};
+
+/// An error that occurs when sealing or verifying the integrity of a munlib.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum IntegrityError {
+    /// The munlib does not embed integrity information
+    #[error("the munlib does not contain integrity information")]
+    MissingIntegrityInfo,
+    /// The munlib embeds integrity information more than once
+    #[error("the munlib contains integrity information more than once")]
+    AmbiguousIntegrityInfo,
+    /// The munlib is not hashed
+    #[error("the munlib is not hashed")]
+    NotHashed,
+    /// The content hash of the munlib does not match its contents
+    #[error("the content hash of the munlib does not match its contents")]
+    HashMismatch,
+    /// The munlib is not signed
+    #[error("the munlib is not signed")]
+    NotSigned,
+    /// The munlib is not signed by any of the trusted keys
+    #[error("the munlib is not signed by a trusted key")]
+    UntrustedSignature,
+    /// A key could not be parsed
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+}
+
+/// Describes which integrity checks a munlib must pass before it is loaded.
+#[derive(Clone, PartialEq, Eq)]
+pub enum IntegrityPolicy {
+    /// The content hash of the munlib must match its contents. This detects
+    /// corrupted munlibs, but not deliberate tampering, since anyone can
+    /// recompute the hash of a modified munlib.
+    Hash,
+    /// The munlib must be signed by one of the trusted keys, and its content
+    /// hash must match its contents.
+    Signature(Vec<VerifyingKey>),
+}
+
+impl fmt::Debug for IntegrityPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityPolicy::Hash => f.write_str("Hash"),
+            IntegrityPolicy::Signature(keys) => f
+                .debug_tuple("Signature")
+                .field(&keys.iter().map(encode_key).collect::<Vec<_>>())
+                .finish(),
+        }
+    }
+}
+
+/// Seals the munlib with the specified `contents` by storing its content hash
+/// in the embedded integrity information. If a `signing_key` is specified,
+/// the content hash is also signed.
+pub fn seal(contents: &mut [u8], signing_key: Option<&SigningKey>) -> Result<(), IntegrityError> {
+    let offset = find_integrity_info(contents)?;
+
+    let mut info = abi::IntegrityInfo::UNSEALED;
+    info.flags = abi::IntegrityInfo::HASHED;
+    if signing_key.is_some() {
+        info.flags |= abi::IntegrityInfo::SIGNED;
+    }
+    contents[offset..offset + abi::IntegrityInfo::SIZE].copy_from_slice(&info.to_bytes());
+
+    info.hash = content_hash(contents, offset);
+    if let Some(signing_key) = signing_key {
+        info.signature = signing_key.sign(&info.hash).to_bytes();
+    }
+    contents[offset..offset + abi::IntegrityInfo::SIZE].copy_from_slice(&info.to_bytes());
+
+    Ok(())
+}
+
+/// Verifies that the munlib with the specified `contents` satisfies the
+/// `policy`.
+pub fn verify(contents: &[u8], policy: &IntegrityPolicy) -> Result<(), IntegrityError> {
+    let offset = find_integrity_info(contents)?;
+    let info = read_integrity_info(contents, offset);
+    let hash = info.hash().ok_or(IntegrityError::NotHashed)?;
+
+    let mut unsealed = contents.to_vec();
+    unsealed[offset + abi::IntegrityInfo::HASH_OFFSET..offset + abi::IntegrityInfo::SIZE].fill(0);
+    if content_hash(&unsealed, offset) != *hash {
+        return Err(IntegrityError::HashMismatch);
+    }
+
+    if let IntegrityPolicy::Signature(trusted_keys) = policy {
+        let signature = Signature::from_bytes(info.signature().ok_or(IntegrityError::NotSigned)?);
+        if !trusted_keys
+            .iter()
+            .any(|key| key.verify(hash, &signature).is_ok())
+        {
+            return Err(IntegrityError::UntrustedSignature);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the integrity information that is embedded in the munlib with the
+/// specified `contents`.
+pub fn integrity_info(contents: &[u8]) -> Result<abi::IntegrityInfo, IntegrityError> {
+    find_integrity_info(contents).map(|offset| read_integrity_info(contents, offset))
+}
+
+/// Parses a signing key from the hexadecimal representation of its 32-byte
+/// secret key.
+pub fn parse_signing_key(hex: &str) -> Result<SigningKey, IntegrityError> {
+    decode_key(hex).map(|bytes| SigningKey::from_bytes(&bytes))
+}
+
+/// Parses a verifying key from the hexadecimal representation of its 32-byte
+/// public key.
+pub fn parse_verifying_key(hex: &str) -> Result<VerifyingKey, IntegrityError> {
+    let bytes = decode_key(hex)?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| IntegrityError::InvalidKey(e.to_string()))
+}
+
+/// Returns the hexadecimal representation of a verifying key.
+pub fn encode_key(key: &VerifyingKey) -> String {
+    key.as_bytes().iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    })
+}
+
+/// Decodes the hexadecimal representation of a 32-byte key.
+fn decode_key(hex: &str) -> Result<[u8; 32], IntegrityError> {
+    let hex = hex.trim();
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(IntegrityError::InvalidKey(
+            "expected 64 hexadecimal digits".to_owned(),
+        ));
+    }
+
+    let mut bytes = [0; 32];
+    for (byte, digits) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let digits = std::str::from_utf8(digits).expect("the key is ASCII");
+        *byte = u8::from_str_radix(digits, 16)
+            .map_err(|e| IntegrityError::InvalidKey(format!("`{digits}`: {e}")))?;
+    }
+    Ok(bytes)
+}
+
+/// Returns the offset of the integrity information that is embedded in the
+/// munlib with the specified `contents`.
+fn find_integrity_info(contents: &[u8]) -> Result<usize, IntegrityError> {
+    let mut offsets = contents
+        .windows(abi::INTEGRITY_MAGIC.len())
+        .enumerate()
+        .filter(|(_, window)| *window == abi::INTEGRITY_MAGIC)
+        .map(|(offset, _)| offset)
+        .filter(|offset| offset + abi::IntegrityInfo::SIZE <= contents.len());
+
+    let offset = offsets.next().ok_or(IntegrityError::MissingIntegrityInfo)?;
+    if offsets.next().is_some() {
+        return Err(IntegrityError::AmbiguousIntegrityInfo);
+    }
+    Ok(offset)
+}
+
+/// Reads the integrity information at `offset` in the `contents` of a munlib.
+fn read_integrity_info(contents: &[u8], offset: usize) -> abi::IntegrityInfo {
+    let bytes = contents[offset..offset + abi::IntegrityInfo::SIZE]
+        .try_into()
+        .expect("the integrity information is out of bounds");
+    abi::IntegrityInfo::from_bytes(bytes)
+}
+
+/// Computes the content hash of a munlib whose integrity information at
+/// `offset` has no hash or signature.
+fn content_hash(contents: &[u8], offset: usize) -> [u8; 32] {
+    debug_assert!(contents
+        [offset + abi::IntegrityInfo::HASH_OFFSET..offset + abi::IntegrityInfo::SIZE]
+        .iter()
+        .all(|b| *b == 0));
+    Sha256::digest(contents).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_munlib() -> Vec<u8> {
+        let mut contents = b"header".to_vec();
+        contents.extend_from_slice(&abi::IntegrityInfo::UNSEALED.to_bytes());
+        contents.extend_from_slice(b"code");
+        contents
+    }
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    #[test]
+    fn hash() {
+        let mut contents = fake_munlib();
+        assert_eq!(
+            verify(&contents, &IntegrityPolicy::Hash),
+            Err(IntegrityError::NotHashed)
+        );
+
+        seal(&mut contents, None).unwrap();
+        assert_eq!(verify(&contents, &IntegrityPolicy::Hash), Ok(()));
+        assert_eq!(
+            verify(
+                &contents,
+                &IntegrityPolicy::Signature(vec![signing_key(1).verifying_key()])
+            ),
+            Err(IntegrityError::NotSigned)
+        );
+
+        *contents.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            verify(&contents, &IntegrityPolicy::Hash),
+            Err(IntegrityError::HashMismatch)
+        );
+    }
+
+    #[test]
+    fn signature() {
+        let mut contents = fake_munlib();
+        seal(&mut contents, Some(&signing_key(1))).unwrap();
+
+        let info = integrity_info(&contents).unwrap();
+        assert!(info.hash().is_some());
+        assert!(info.signature().is_some());
+
+        assert_eq!(verify(&contents, &IntegrityPolicy::Hash), Ok(()));
+        assert_eq!(
+            verify(
+                &contents,
+                &IntegrityPolicy::Signature(vec![
+                    signing_key(2).verifying_key(),
+                    signing_key(1).verifying_key()
+                ])
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            verify(
+                &contents,
+                &IntegrityPolicy::Signature(vec![signing_key(2).verifying_key()])
+            ),
+            Err(IntegrityError::UntrustedSignature)
+        );
+
+        // Re-sealing a tampered munlib with another key is detected
+        contents[0] ^= 1;
+        seal(&mut contents, Some(&signing_key(2))).unwrap();
+        assert_eq!(
+            verify(
+                &contents,
+                &IntegrityPolicy::Signature(vec![signing_key(1).verifying_key()])
+            ),
+            Err(IntegrityError::UntrustedSignature)
+        );
+    }
+
+    #[test]
+    fn missing_integrity_info() {
+        assert_eq!(
+            verify(b"header code", &IntegrityPolicy::Hash),
+            Err(IntegrityError::MissingIntegrityInfo)
+        );
+
+        let mut contents = fake_munlib();
+        contents.extend(fake_munlib());
+        assert_eq!(
+            seal(&mut contents, None),
+            Err(IntegrityError::AmbiguousIntegrityInfo)
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let key = signing_key(7).verifying_key();
+        assert_eq!(parse_verifying_key(&encode_key(&key)), Ok(key));
+        assert_eq!(
+            parse_signing_key(&"07".repeat(32)).map(|key| key.verifying_key()),
+            Ok(key)
+        );
+        assert!(matches!(
+            parse_signing_key("07"),
+            Err(IntegrityError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            parse_signing_key(&"zz".repeat(32)),
+            Err(IntegrityError::InvalidKey(_))
+        ));
+    }
+}
@@ -1,8 +1,14 @@
-use std::{ffi::c_void, path::Path};
+use std::{
+    ffi::{c_char, c_void, CStr},
+    path::Path,
+};
 
+pub use image::{ImageError, MunLibraryImage};
 use mun_abi as abi;
 pub use temp_library::TempLibrary;
 
+mod image;
+pub mod integrity;
 mod temp_library;
 
 /// An error that occurs upon construction of a [`MunLibrary`].
@@ -41,7 +47,36 @@ impl MunLibrary {
         // Although loading a library is technically unsafe, we assume here that this is
         // not the case for munlibs.
         let library = TempLibrary::new(library_path)?;
+        Self::from_temp_library(library)
+    }
+
+    /// Loads a munlib library from its contents in memory, e.g. an assembly
+    /// that was just compiled in the same process or that was received over
+    /// the network. On Linux, the library is loaded from an anonymous
+    /// in-memory file, elsewhere from a temporary file; see
+    /// [`TempLibrary::from_bytes`].
+    ///
+    /// # Safety
+    ///
+    /// A munlib is simply a shared object. When a library is loaded,
+    /// initialisation routines contained within it are executed. For the
+    /// purposes of safety, the execution of these routines is conceptually
+    /// the same calling an unknown foreign function and may impose
+    /// arbitrary requirements on the caller for the call to be sound.
+    ///
+    /// Additionally, the callers of this function must also ensure that
+    /// execution of the termination routines contained within the library
+    /// is safe as well. These routines may be executed when the library is
+    /// unloaded.
+    ///
+    /// See [`libloading::Library::new`] for more information.
+    pub unsafe fn from_bytes(bytes: &[u8]) -> Result<Self, InitError> {
+        let library = TempLibrary::from_bytes(bytes)?;
+        Self::from_temp_library(library)
+    }
 
+    /// Verifies that the loaded library is a munlib.
+    unsafe fn from_temp_library(library: TempLibrary) -> Result<Self, InitError> {
         // Verify that the `*.munlib` contains all required functions. Note that this is
         // an unsafe operation because the loaded symbols don't actually contain
         // type information. Casting is therefore unsafe.
@@ -85,6 +120,45 @@ impl MunLibrary {
         get_abi_version_fn()
     }
 
+    /// Returns the version of the compiler that generated this mun library, or
+    /// `None` if the library was generated by a compiler that did not record
+    /// its version.
+    ///
+    /// # Safety
+    ///
+    /// This operations executes a function in the munlib. There is no guarantee
+    /// that the execution of the function wont result in undefined
+    /// behavior.
+    pub unsafe fn get_compiler_version(&self) -> Option<String> {
+        let get_compiler_version_fn: libloading::Symbol<'_, extern "C" fn() -> *const c_char> =
+            self.0
+                .library()
+                .get(abi::GET_COMPILER_VERSION_FN_NAME.as_bytes())
+                .ok()?;
+
+        let version = get_compiler_version_fn();
+        (!version.is_null()).then(|| CStr::from_ptr(version).to_string_lossy().into_owned())
+    }
+
+    /// Returns the integrity information that is embedded in this mun library,
+    /// or `None` if the library was generated by a compiler that did not
+    /// embed it.
+    ///
+    /// # Safety
+    ///
+    /// This operations executes a function in the munlib. There is no guarantee
+    /// that the execution of the function wont result in undefined
+    /// behavior.
+    pub unsafe fn get_integrity(&self) -> Option<abi::IntegrityInfo> {
+        let get_integrity_fn: libloading::Symbol<'_, extern "C" fn() -> *const abi::IntegrityInfo> =
+            self.0
+                .library()
+                .get(abi::GET_INTEGRITY_FN_NAME.as_bytes())
+                .ok()?;
+
+        get_integrity_fn().as_ref().copied()
+    }
+
     /// Returns the assembly info exported by the shared object.
     ///
     /// # Safety
@@ -1,4 +1,4 @@
-use std::{fs, io, path::Path};
+use std::{fs, io, io::Write, path::Path};
 
 use libloading::Library;
 
@@ -9,6 +9,8 @@ pub enum InitError {
     CreateTempFile(io::Error),
     #[error("Failed to copy shared library: {0}.")]
     CopyLibrary(io::Error),
+    #[error("Failed to write shared library: {0}.")]
+    WriteLibrary(io::Error),
     #[error("Failed to load temp shared library: {0}")]
     LoadTempLibrary(#[from] libloading::Error),
 }
@@ -21,8 +23,13 @@ pub enum InitError {
 /// loading the library keeps the file open (Windows) or keeping the file is not
 /// required in the first place (*nix).
 pub struct TempLibrary {
-    _tmp_path: tempfile::TempPath,
+    // Fields are dropped in order of declaration, so the library is unloaded
+    // before the file that it was loaded from is closed or removed.
     library: Library,
+    _tmp_path: Option<tempfile::TempPath>,
+    /// The anonymous in-memory file that the library was loaded from, if any
+    #[cfg(target_os = "linux")]
+    _memfd: Option<fs::File>,
 }
 
 impl TempLibrary {
@@ -54,10 +61,55 @@ impl TempLibrary {
             .into_temp_path();
         fs::copy(path, &tmp_path).map_err(InitError::CopyLibrary)?;
         let library = Library::new(&tmp_path)?;
-        Ok(TempLibrary {
-            _tmp_path: tmp_path,
+        Ok(TempLibrary::from_temp_path(tmp_path, library))
+    }
+
+    /// Loads a dynamic library from its contents in memory.
+    ///
+    /// On Linux the contents are written to an anonymous in-memory file, so
+    /// the filesystem is never touched. On other platforms, or if the kernel
+    /// does not support anonymous files, the contents are written to a unique
+    /// temporary file from which the library is loaded.
+    ///
+    /// # Safety
+    ///
+    /// When a library is loaded, initialisation routines contained within it
+    /// are executed. For the purposes of safety, the execution of these
+    /// routines is conceptually the same calling an unknown foreign
+    /// function and may impose arbitrary requirements on the caller for the
+    /// call to be sound.
+    ///
+    /// Additionally, the callers of this function must also ensure that
+    /// execution of the termination routines contained within the library
+    /// is safe as well. These routines may be executed when the library is
+    /// unloaded.
+    ///
+    /// See [`libloading::Library::new`] for more information.
+    pub unsafe fn from_bytes(bytes: &[u8]) -> Result<Self, InitError> {
+        #[cfg(target_os = "linux")]
+        if let Some((memfd, path)) = memfd::create(bytes) {
+            let library = Library::new(path)?;
+            return Ok(TempLibrary {
+                library,
+                _tmp_path: None,
+                _memfd: Some(memfd),
+            });
+        }
+
+        let mut file = tempfile::NamedTempFile::new().map_err(InitError::CreateTempFile)?;
+        file.write_all(bytes).map_err(InitError::WriteLibrary)?;
+        let tmp_path = file.into_temp_path();
+        let library = Library::new(&tmp_path)?;
+        Ok(TempLibrary::from_temp_path(tmp_path, library))
+    }
+
+    fn from_temp_path(tmp_path: tempfile::TempPath, library: Library) -> Self {
+        TempLibrary {
             library,
-        })
+            _tmp_path: Some(tmp_path),
+            #[cfg(target_os = "linux")]
+            _memfd: None,
+        }
     }
 
     /// Returns the loaded library
@@ -65,3 +117,43 @@ impl TempLibrary {
         &self.library
     }
 }
+
+#[cfg(target_os = "linux")]
+mod memfd {
+    use std::{
+        ffi::CString,
+        fs::File,
+        io::Write,
+        os::fd::{AsRawFd, FromRawFd},
+        path::PathBuf,
+    };
+
+    /// Writes the `bytes` to an anonymous in-memory file. Returns the file
+    /// and the path through which it can be opened, or `None` if anonymous
+    /// files are not supported.
+    pub(super) fn create(bytes: &[u8]) -> Option<(File, PathBuf)> {
+        let name = CString::new("munlib").expect("the name contains no null bytes");
+        // SAFETY: The name is a null-terminated string
+        let fd = unsafe { libc::memfd_create(name.as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return None;
+        }
+
+        // SAFETY: The file descriptor was just created, so it is owned by nobody else
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        file.write_all(bytes).ok()?;
+
+        // The file can only be opened by path if `/proc` is mounted
+        let path = PathBuf::from(format!("/proc/self/fd/{}", file.as_raw_fd()));
+        path.exists().then_some((file, path))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        #[test]
+        fn create() {
+            let (_file, path) = super::create(b"contents").expect("memfd is not supported");
+            assert_eq!(std::fs::read(path).unwrap(), b"contents");
+        }
+    }
+}
@@ -59,8 +59,9 @@ fn cast_from_to<A, B>(src: NonNull<u8>, dest: NonNull<u8>)
 where
     A: Copy + Into<B>,
 {
-    let value = unsafe { *src.cast::<A>().as_ref() };
-    unsafe { *dest.cast::<B>().as_mut() = value.into() };
+    // The fields of packed structs are not aligned
+    let value = unsafe { src.cast::<A>().as_ptr().read_unaligned() };
+    unsafe { dest.cast::<B>().as_ptr().write_unaligned(value.into()) };
 }
 
 pub fn try_cast_from_to(old_id: Type, new_id: Type, src: NonNull<u8>, dest: NonNull<u8>) -> bool {
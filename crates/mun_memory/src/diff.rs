@@ -1,7 +1,14 @@
+mod memory;
 pub mod myers;
 
+use std::collections::HashMap;
+
+pub use self::memory::{
+    memory_diff, memory_diff_with_renames, FieldMemoryDiff, FieldOrigin, MemoryDiff,
+    StructMemoryDiff,
+};
 use self::myers::Change;
-use crate::r#type::{Field, Type};
+use crate::r#type::{Field, StructType, Type};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum FieldEditKind {
@@ -81,10 +88,61 @@ impl PartialOrd for StructDiff {
     }
 }
 
+/// Hints that fields of structs were renamed. The value of a renamed field is
+/// preserved when mapping memory, even if the rename cannot be detected
+/// automatically, e.g. because the type of the field also changed.
+#[derive(Clone, Debug, Default)]
+pub struct FieldRenames {
+    /// Maps the name of a struct to the old and new names of its renamed fields
+    renames: HashMap<String, HashMap<String, String>>,
+}
+
+impl FieldRenames {
+    /// Records that the field `old_name` of the struct called `struct_name`
+    /// was renamed to `new_name`.
+    pub fn insert(
+        &mut self,
+        struct_name: impl Into<String>,
+        old_name: impl Into<String>,
+        new_name: impl Into<String>,
+    ) {
+        self.renames
+            .entry(struct_name.into())
+            .or_default()
+            .insert(old_name.into(), new_name.into());
+    }
+
+    /// Returns the new name of the field `old_name` of the struct called
+    /// `struct_name`, if it was renamed.
+    pub fn new_name(&self, struct_name: &str, old_name: &str) -> Option<&str> {
+        self.renames
+            .get(struct_name)
+            .and_then(|renames| renames.get(old_name))
+            .map(String::as_str)
+    }
+
+    /// Returns true if no fields were renamed.
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty()
+    }
+}
+
 /// Given an `old` and a `new` ordered set of types, computes the difference
 /// based on ordering and equality of struct types. Thus, a diff can consist of
 /// inserted, deleted, moved, and edited (i.e. fields of) struct types.
 pub fn compute_struct_diff(old: &[Type], new: &[Type]) -> Vec<StructDiff> {
+    compute_struct_diff_with_renames(old, new, &FieldRenames::default())
+}
+
+/// Given an `old` and a `new` ordered set of types, computes the difference
+/// like [`compute_struct_diff`]. Fields of the `old` structs are renamed
+/// according to the `renames` before they are compared to the fields of the
+/// `new` structs.
+pub fn compute_struct_diff_with_renames(
+    old: &[Type],
+    new: &[Type],
+    renames: &FieldRenames,
+) -> Vec<StructDiff> {
     let diff = myers::compute_diff(old, new);
     let (deletions, insertions) = myers::split_diff(&diff);
 
@@ -99,7 +157,7 @@ pub fn compute_struct_diff(old: &[Type], new: &[Type]) -> Vec<StructDiff> {
         .collect();
 
     let mut mapping: Vec<StructDiff> = Vec::with_capacity(diff.len());
-    append_struct_mapping(deleted_structs, inserted_structs, &mut mapping);
+    append_struct_mapping(deleted_structs, inserted_structs, renames, &mut mapping);
 
     mapping.shrink_to_fit();
     // Sort to guarantee order of execution when deleting and/or inserting
@@ -123,12 +181,36 @@ impl<'a> From<Field<'a>> for UniqueFieldInfo<'a> {
     }
 }
 
+/// Returns the fields of the old struct `s`, called `struct_name`, with the
+/// `renames` applied. A field is not renamed if the struct already contains a
+/// field with its new name.
+fn renamed_fields<'a>(
+    struct_name: &str,
+    s: StructType<'a>,
+    renames: &'a FieldRenames,
+) -> Vec<UniqueFieldInfo<'a>> {
+    let fields = s.fields();
+    fields
+        .iter()
+        .map(|field| {
+            let mut field_info = UniqueFieldInfo::from(field);
+            if let Some(new_name) = renames.new_name(struct_name, field_info.name) {
+                if fields.find_by_name(new_name).is_none() {
+                    field_info.name = new_name;
+                }
+            }
+            field_info
+        })
+        .collect()
+}
+
 /// Given a set of indices for `deletions` from the `old` slice of types and a
 /// set of indices for `insertions` into the `new` slice of types, appends the
 /// corresponding `Diff` mapping for all
 fn append_struct_mapping(
     deletions: Vec<Change<Type>>,
     insertions: Vec<Change<Type>>,
+    renames: &FieldRenames,
     mapping: &mut Vec<StructDiff>,
 ) {
     struct LengthDescription<'f> {
@@ -147,9 +229,9 @@ fn append_struct_mapping(
         .iter()
         .enumerate()
         .map(|(deletion_index, Change { index, element })| {
-            let fields = element.as_struct().map_or_else(Vec::new, |s| {
-                s.fields().iter().map(UniqueFieldInfo::from).collect()
-            });
+            let fields = element
+                .as_struct()
+                .map_or_else(Vec::new, |s| renamed_fields(element.name(), s, renames));
 
             (deletion_index, *index, element.clone(), fields)
         })
@@ -0,0 +1,277 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+};
+
+use itertools::Itertools;
+
+use super::FieldRenames;
+use crate::{
+    mapping::{Action, Mapping, StructMapping},
+    r#type::Type,
+};
+
+/// Describes how the memory of allocated objects is mapped when an `old` set
+/// of types is replaced by a `new` set of types, e.g. when hot reloading an
+/// assembly. See [`memory_diff`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MemoryDiff {
+    /// The structs whose fields changed, sorted by their new name
+    pub edited: Vec<StructMemoryDiff>,
+    /// The structs that were deleted, sorted by name. Allocated objects of
+    /// these types can no longer be used.
+    pub deleted: Vec<Type>,
+    /// The structs that were inserted, sorted by name
+    pub inserted: Vec<Type>,
+}
+
+/// Describes how the memory of an allocated struct is mapped to the layout of
+/// its new type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StructMemoryDiff {
+    /// The old type of the struct
+    pub old_ty: Type,
+    /// The new type of the struct
+    pub new_ty: Type,
+    /// How the fields of the new type are initialized, in order of declaration
+    pub fields: Vec<FieldMemoryDiff>,
+    /// The names of the fields of the old type whose values are dropped
+    pub dropped_fields: Vec<String>,
+}
+
+/// Describes how a field of a new struct type is initialized.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldMemoryDiff {
+    /// The name of the field
+    pub name: String,
+    /// The type of the field
+    pub ty: Type,
+    /// Where the value of the field originates from
+    pub origin: FieldOrigin,
+}
+
+/// The origin of the value of a field after mapping memory.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FieldOrigin {
+    /// The value is copied from the old field called `old_name`
+    Preserved { old_name: String },
+    /// The value is converted from the old field called `old_name` of type
+    /// `old_ty`
+    Converted { old_name: String, old_ty: Type },
+    /// The field is new, or the value of the old field cannot be converted, so
+    /// the field is zero-initialized
+    Initialized,
+    /// The field is new and is initialized to its default value
+    Default,
+}
+
+impl MemoryDiff {
+    /// Returns true if no memory is mapped, i.e. no structs were edited,
+    /// deleted, or inserted.
+    pub fn is_empty(&self) -> bool {
+        self.edited.is_empty() && self.deleted.is_empty() && self.inserted.is_empty()
+    }
+
+    /// Returns true if mapping memory does not drop the value of any
+    /// allocated struct or field.
+    pub fn is_lossless(&self) -> bool {
+        self.deleted.is_empty()
+            && self
+                .edited
+                .iter()
+                .all(|diff| diff.dropped_fields.is_empty())
+    }
+}
+
+/// Given an `old` and a `new` set of types, describes which fields of
+/// allocated structs are preserved, converted, or dropped when mapping memory,
+/// without mapping any memory.
+pub fn memory_diff(old: &[Type], new: &[Type]) -> MemoryDiff {
+    memory_diff_with_renames(old, new, &FieldRenames::default())
+}
+
+/// Given an `old` and a `new` set of types, describes how memory is mapped
+/// like [`memory_diff`], taking into account that fields were renamed
+/// according to the `renames`.
+pub fn memory_diff_with_renames(old: &[Type], new: &[Type], renames: &FieldRenames) -> MemoryDiff {
+    let mapping = Mapping::with_renames(old, new, renames);
+
+    let edited = mapping
+        .struct_mappings
+        .iter()
+        .map(|(old_ty, struct_mapping)| struct_memory_diff(old_ty, struct_mapping))
+        .sorted_by(|a, b| a.new_ty.name().cmp(b.new_ty.name()))
+        .collect();
+
+    let deleted = mapping
+        .deletions
+        .iter()
+        .cloned()
+        .sorted_by(|a, b| a.name().cmp(b.name()))
+        .collect();
+
+    let inserted = new
+        .iter()
+        .filter(|ty| ty.is_struct())
+        .filter(|ty| {
+            !mapping
+                .struct_mappings
+                .values()
+                .any(|struct_mapping| struct_mapping.new_ty == **ty)
+                && !mapping.identical.iter().any(|(_, new_ty)| new_ty == *ty)
+        })
+        .cloned()
+        .sorted_by(|a, b| a.name().cmp(b.name()))
+        .collect();
+
+    MemoryDiff {
+        edited,
+        deleted,
+        inserted,
+    }
+}
+
+/// Describes the memory mapping of a single struct, based on the actions of
+/// its `struct_mapping`.
+fn struct_memory_diff(old_ty: &Type, struct_mapping: &StructMapping) -> StructMemoryDiff {
+    let old_fields = old_ty
+        .as_struct()
+        .into_iter()
+        .flat_map(|s| s.fields().iter())
+        .collect_vec();
+    let new_fields = struct_mapping
+        .new_ty
+        .as_struct()
+        .into_iter()
+        .flat_map(|s| s.fields().iter())
+        .collect_vec();
+
+    let mut used_fields = HashSet::new();
+    let fields = new_fields
+        .iter()
+        .zip(struct_mapping.field_mapping.iter())
+        .map(|(new_field, field_mapping)| {
+            let old_field = old_offset(&field_mapping.action).and_then(|old_offset| {
+                // Zero-sized fields can share an offset, so prefer a field with the same name
+                old_fields
+                    .iter()
+                    .filter(|old_field| old_field.offset() == old_offset)
+                    .max_by_key(|old_field| old_field.name() == new_field.name())
+            });
+
+            let origin = match old_field {
+                Some(old_field) => {
+                    used_fields.insert(old_field.name());
+
+                    let old_name = old_field.name().to_owned();
+                    if matches!(field_mapping.action, Action::Copy { .. }) {
+                        FieldOrigin::Preserved { old_name }
+                    } else {
+                        FieldOrigin::Converted {
+                            old_name,
+                            old_ty: old_field.ty(),
+                        }
+                    }
+                }
+                None if matches!(field_mapping.action, Action::InitializeDefault { .. }) => {
+                    FieldOrigin::Default
+                }
+                None => FieldOrigin::Initialized,
+            };
+
+            FieldMemoryDiff {
+                name: new_field.name().to_owned(),
+                ty: new_field.ty(),
+                origin,
+            }
+        })
+        .collect();
+
+    let dropped_fields = old_fields
+        .iter()
+        .filter(|old_field| !used_fields.contains(old_field.name()))
+        .map(|old_field| old_field.name().to_owned())
+        .collect();
+
+    StructMemoryDiff {
+        old_ty: old_ty.clone(),
+        new_ty: struct_mapping.new_ty.clone(),
+        fields,
+        dropped_fields,
+    }
+}
+
+/// Returns the offset of the old field that the `action` reads from, if any.
+fn old_offset(action: &Action) -> Option<usize> {
+    match action {
+        Action::ArrayFromValue { old_offset, .. }
+        | Action::ArrayMap { old_offset, .. }
+        | Action::Cast { old_offset, .. }
+        | Action::Copy { old_offset, .. }
+        | Action::ElementFromArray { old_offset, .. }
+        | Action::StructMapFromGc { old_offset, .. }
+        | Action::StructMapFromValue { old_offset, .. }
+        | Action::StructMapInPlace { old_offset, .. } => Some(*old_offset),
+        Action::ArrayAlloc
+        | Action::InitializeDefault { .. }
+        | Action::StructAlloc
+        | Action::ZeroInitialize => None,
+    }
+}
+
+impl Display for MemoryDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for diff in &self.edited {
+            write!(f, "~ struct {}", diff.new_ty.name())?;
+            if diff.old_ty.name() != diff.new_ty.name() {
+                write!(f, " (renamed from `{}`)", diff.old_ty.name())?;
+            }
+            writeln!(f)?;
+
+            for field in &diff.fields {
+                match &field.origin {
+                    FieldOrigin::Preserved { old_name } => {
+                        write!(f, "    = {}: {}", field.name, field.ty.name())?;
+                        if *old_name != field.name {
+                            write!(f, " (renamed from `{old_name}`)")?;
+                        }
+                        writeln!(f)?;
+                    }
+                    FieldOrigin::Converted { old_name, old_ty } => writeln!(
+                        f,
+                        "    ~ {}: {} (converted from `{old_name}: {}`)",
+                        field.name,
+                        field.ty.name(),
+                        old_ty.name()
+                    )?,
+                    FieldOrigin::Initialized => writeln!(
+                        f,
+                        "    + {}: {} (zero-initialized)",
+                        field.name,
+                        field.ty.name()
+                    )?,
+                    FieldOrigin::Default => writeln!(
+                        f,
+                        "    + {}: {} (initialized to its default)",
+                        field.name,
+                        field.ty.name()
+                    )?,
+                }
+            }
+
+            for name in &diff.dropped_fields {
+                writeln!(f, "    - {name} (dropped)")?;
+            }
+        }
+
+        for ty in &self.deleted {
+            writeln!(f, "- struct {} (allocated objects are dropped)", ty.name())?;
+        }
+
+        for ty in &self.inserted {
+            writeln!(f, "+ struct {}", ty.name())?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
 mod array;
+mod heap_dump;
 mod mark_sweep;
 mod ptr;
 mod root_ptr;
 
 use std::{marker::PhantomData, ptr::NonNull};
 
+pub use heap_dump::{HeapDump, HeapObject, HeapReference};
 pub use mark_sweep::MarkSweep;
 pub use ptr::{GcPtr, HasIndirectionPtr, RawGcPtr};
 pub use root_ptr::GcRootPtr;
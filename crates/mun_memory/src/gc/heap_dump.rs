@@ -0,0 +1,278 @@
+use std::{collections::VecDeque, fmt::Write};
+
+use crate::gc::GcPtr;
+
+/// A snapshot of all objects that are allocated by a garbage collector, and
+/// the references between them.
+///
+/// A heap dump helps to find objects that are retained unexpectedly, e.g.
+/// because the host forgot to drop a rooted reference. Objects that are not
+/// reachable from a root are garbage that has not been collected yet.
+#[derive(Clone, Debug, Default)]
+pub struct HeapDump {
+    objects: Vec<HeapObject>,
+}
+
+/// An object in a [`HeapDump`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeapObject {
+    /// The index of the object in the heap dump
+    pub id: usize,
+    /// The handle of the object
+    pub handle: GcPtr,
+    /// The name of the type of the object
+    pub type_name: String,
+    /// The size of the memory of the object in bytes
+    pub size: usize,
+    /// The number of times the object is rooted, e.g. by the host
+    pub roots: u32,
+    /// Whether the object is reachable from a rooted object, or rooted itself
+    pub is_reachable: bool,
+    /// The objects that this object references
+    pub references: Vec<HeapReference>,
+}
+
+/// A reference from one [`HeapObject`] to another.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeapReference {
+    /// The path of the field or array element that holds the reference, e.g.
+    /// `foo.bar` or `[3]`
+    pub field: String,
+    /// The id of the referenced object
+    pub target: usize,
+}
+
+impl HeapDump {
+    /// Constructs a heap dump from the specified objects, whose ids must equal
+    /// their index. Determines which objects are reachable from a root.
+    pub(crate) fn new(mut objects: Vec<HeapObject>) -> Self {
+        let mut queue: VecDeque<usize> = objects
+            .iter()
+            .filter(|object| object.roots > 0)
+            .map(|object| object.id)
+            .collect();
+        for &id in queue.iter() {
+            objects[id].is_reachable = true;
+        }
+
+        while let Some(id) = queue.pop_front() {
+            for reference_idx in 0..objects[id].references.len() {
+                let target = objects[id].references[reference_idx].target;
+                if !objects[target].is_reachable {
+                    objects[target].is_reachable = true;
+                    queue.push_back(target);
+                }
+            }
+        }
+
+        Self { objects }
+    }
+
+    /// Returns all objects, ordered by their id.
+    pub fn objects(&self) -> &[HeapObject] {
+        &self.objects
+    }
+
+    /// Returns the object with the specified `handle`, if it is allocated.
+    pub fn object(&self, handle: GcPtr) -> Option<&HeapObject> {
+        self.objects.iter().find(|object| object.handle == handle)
+    }
+
+    /// Returns the objects that are rooted.
+    pub fn roots(&self) -> impl Iterator<Item = &HeapObject> {
+        self.objects.iter().filter(|object| object.roots > 0)
+    }
+
+    /// Returns the objects that are not reachable from a root, and are
+    /// therefore freed by the next collection.
+    pub fn unreachable(&self) -> impl Iterator<Item = &HeapObject> {
+        self.objects.iter().filter(|object| !object.is_reachable)
+    }
+
+    /// Returns the objects that reference the object with the specified `id`,
+    /// together with the references. This shows why an object is retained.
+    pub fn retainers(&self, id: usize) -> impl Iterator<Item = (&HeapObject, &HeapReference)> {
+        self.objects.iter().flat_map(move |object| {
+            object
+                .references
+                .iter()
+                .filter(move |reference| reference.target == id)
+                .map(move |reference| (object, reference))
+        })
+    }
+
+    /// Returns the total size of the memory of all objects in bytes.
+    pub fn total_size(&self) -> usize {
+        self.objects.iter().map(|object| object.size).sum()
+    }
+
+    /// Serializes the heap dump to JSON. Objects are identified by their id.
+    ///
+    /// ```json
+    /// {"objects":[
+    /// {"id":0,"type":"Foo","size":16,"roots":1,"reachable":true,"references":[{"field":"bar","target":1}]},
+    /// {"id":1,"type":"Bar","size":8,"roots":0,"reachable":true,"references":[]}
+    /// ]}
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"objects\":[");
+        for object in self.objects.iter() {
+            if object.id > 0 {
+                json.push(',');
+            }
+            write!(json, "\n{{\"id\":{},\"type\":", object.id).unwrap();
+            write_json_string(&mut json, &object.type_name);
+            write!(
+                json,
+                ",\"size\":{},\"roots\":{},\"reachable\":{},\"references\":[",
+                object.size, object.roots, object.is_reachable
+            )
+            .unwrap();
+            for (idx, reference) in object.references.iter().enumerate() {
+                if idx > 0 {
+                    json.push(',');
+                }
+                json.push_str("{\"field\":");
+                write_json_string(&mut json, &reference.field);
+                write!(json, ",\"target\":{}}}", reference.target).unwrap();
+            }
+            json.push_str("]}");
+        }
+        json.push_str("\n]}\n");
+        json
+    }
+
+    /// Serializes the heap dump to the DOT language of Graphviz. Rooted
+    /// objects are drawn with a bold border and unreachable objects with a
+    /// dashed border.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph heap {\n    node [shape=box];\n");
+        for object in self.objects.iter() {
+            let mut label = format!("{}\\n{} bytes", escape_dot(&object.type_name), object.size);
+            if object.roots > 0 {
+                write!(label, "\\nrooted {}x", object.roots).unwrap();
+            }
+            let style = if object.roots > 0 {
+                ", style=bold"
+            } else if !object.is_reachable {
+                ", style=dashed"
+            } else {
+                ""
+            };
+            writeln!(dot, "    n{} [label=\"{label}\"{style}];", object.id).unwrap();
+        }
+        for object in self.objects.iter() {
+            for reference in object.references.iter() {
+                writeln!(
+                    dot,
+                    "    n{} -> n{} [label=\"{}\"];",
+                    object.id,
+                    reference.target,
+                    escape_dot(&reference.field)
+                )
+                .unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Writes `value` as a JSON string literal.
+fn write_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// Escapes `value` for use in a quoted DOT string.
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HeapDump, HeapObject, HeapReference};
+    use crate::gc::{GcPtr, RawGcPtr};
+
+    fn object(id: usize, type_name: &str, roots: u32, references: &[(&str, usize)]) -> HeapObject {
+        HeapObject {
+            id,
+            handle: GcPtr::from((id + 1) as RawGcPtr),
+            type_name: type_name.to_owned(),
+            size: 8,
+            roots,
+            is_reachable: false,
+            references: references
+                .iter()
+                .map(|(field, target)| HeapReference {
+                    field: (*field).to_owned(),
+                    target: *target,
+                })
+                .collect(),
+        }
+    }
+
+    fn heap_dump() -> HeapDump {
+        HeapDump::new(vec![
+            object(0, "Foo", 1, &[("bar", 1)]),
+            object(1, "Bar", 0, &[("baz.parent", 0)]),
+            object(2, "[Bar]", 0, &[("[0]", 1)]),
+        ])
+    }
+
+    #[test]
+    fn reachability() {
+        let dump = heap_dump();
+        assert_eq!(
+            dump.roots().map(|object| object.id).collect::<Vec<_>>(),
+            [0]
+        );
+        assert_eq!(
+            dump.unreachable()
+                .map(|object| object.id)
+                .collect::<Vec<_>>(),
+            [2]
+        );
+        assert_eq!(
+            dump.retainers(1)
+                .map(|(object, reference)| (object.id, reference.field.as_str()))
+                .collect::<Vec<_>>(),
+            [(0, "bar"), (2, "[0]")]
+        );
+        assert_eq!(dump.total_size(), 24);
+    }
+
+    #[test]
+    fn json() {
+        insta::assert_snapshot!(heap_dump().to_json(), @r###"
+        {"objects":[
+        {"id":0,"type":"Foo","size":8,"roots":1,"reachable":true,"references":[{"field":"bar","target":1}]},
+        {"id":1,"type":"Bar","size":8,"roots":0,"reachable":true,"references":[{"field":"baz.parent","target":0}]},
+        {"id":2,"type":"[Bar]","size":8,"roots":0,"reachable":false,"references":[{"field":"[0]","target":1}]}
+        ]}
+        "###);
+    }
+
+    #[test]
+    fn dot() {
+        insta::assert_snapshot!(heap_dump().to_dot(), @r###"
+        digraph heap {
+            node [shape=box];
+            n0 [label="Foo\n8 bytes\nrooted 1x", style=bold];
+            n1 [label="Bar\n8 bytes"];
+            n2 [label="[Bar]\n8 bytes", style=dashed];
+            n0 -> n1 [label="bar"];
+            n1 -> n0 [label="baz.parent"];
+            n2 -> n1 [label="[0]"];
+        }
+        "###);
+    }
+}
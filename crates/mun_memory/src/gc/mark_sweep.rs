@@ -1,19 +1,20 @@
 use std::{
     alloc::{Layout, LayoutError},
     borrow::Cow,
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    mem::MaybeUninit,
     pin::Pin,
     ptr::NonNull,
 };
 
-use mapping::{Mapping, StructMapping};
-use parking_lot::RwLock;
+use mapping::{MappedObjects, Mapping, StructMapping};
+use parking_lot::{Mutex, RwLock};
 
 use crate::{
     cast,
     gc::{
-        array::ArrayHeader, Array as GcArray, Event, GcPtr, GcRuntime, Observer, RawGcPtr, Stats,
-        TypeTrace,
+        array::ArrayHeader, Array as GcArray, Event, GcPtr, GcRuntime, HeapDump, HeapObject,
+        HeapReference, Observer, RawGcPtr, Stats, TypeTrace,
     },
     mapping::{self, resolve_struct_to_struct_edit, Action, FieldMapping, MemoryMapper},
     r#type::Type,
@@ -32,7 +33,7 @@ impl Trace {
         };
         let obj_ref = unsafe { obj.as_ref() };
         match obj_ref.ty.kind() {
-            TypeKind::Primitive(_) | TypeKind::Pointer(_) => {}
+            TypeKind::Primitive(_) | TypeKind::Pointer(_) | TypeKind::Opaque(_) => {}
             TypeKind::Struct(_) => {
                 trace.stack.push_back(CompositeTrace::Struct(StructTrace {
                     struct_ptr: unsafe { obj_ref.data.ptr },
@@ -96,22 +97,24 @@ impl TraceEvent {
     /// specified location.
     pub fn new(ptr: NonNull<u8>, ty: Cow<'_, Type>) -> Option<TraceEvent> {
         match ty.kind() {
-            TypeKind::Primitive(_) | TypeKind::Pointer(_) => None,
-            TypeKind::Struct(s) => {
-                return if s.is_gc_struct() {
-                    let deref_ptr = unsafe { ptr.cast::<NonNull<ObjectInfo>>().as_ref() };
-                    Some(TraceEvent::Reference(*deref_ptr))
-                } else {
-                    Some(TraceEvent::InlineStruct(StructTrace {
-                        struct_ptr: ptr.cast(),
-                        struct_type: ty.into_owned(),
-                        field_index: 0,
-                    }))
-                };
-            }
-            TypeKind::Array(_) => Some(TraceEvent::Reference(ptr.cast())),
+            TypeKind::Primitive(_) | TypeKind::Pointer(_) | TypeKind::Opaque(_) => None,
+            TypeKind::Struct(s) if s.is_gc_struct() => Self::reference(ptr),
+            TypeKind::Struct(_) => Some(TraceEvent::InlineStruct(StructTrace {
+                struct_ptr: ptr.cast(),
+                struct_type: ty.into_owned(),
+                field_index: 0,
+            })),
+            TypeKind::Array(_) => Self::reference(ptr),
         }
     }
+
+    /// Constructs a `TraceEvent` for the reference stored at the specified
+    /// location. The memory of an object is zeroed when it is allocated, so
+    /// the reference is null until Mun code has initialized it.
+    fn reference(ptr: NonNull<u8>) -> Option<TraceEvent> {
+        let deref_ptr = unsafe { *ptr.cast::<*mut ObjectInfo>().as_ref() };
+        NonNull::new(deref_ptr).map(TraceEvent::Reference)
+    }
 }
 
 /// A struct that enables iterating over all GC references in a struct. Structs
@@ -177,14 +180,77 @@ impl TypeTrace for Type {
     }
 }
 
+/// The value that the memory of collected objects is overwritten with when
+/// debug checks are enabled.
+const POISON_BYTE: u8 = 0xDD;
+
+/// The maximum number of collected objects whose memory is kept for reuse.
+const SCRATCH_CAPACITY: usize = 1024;
+
+/// The memory of a collected object, kept for reuse by a later allocation
+/// with the same layout.
+struct ScratchObject {
+    info: Box<MaybeUninit<ObjectInfo>>,
+    data: NonNull<u8>,
+}
+
+/// Keeps the memory of collected objects around, so objects that are
+/// allocated and collected over and over again, e.g. the values that are
+/// marshalled for every invocation of a function, don't go through the
+/// global allocator each time.
+#[derive(Default)]
+struct ScratchPool {
+    objects: HashMap<Layout, Vec<ScratchObject>>,
+    len: usize,
+}
+
+/// The memory of a `ScratchPool` is owned by the pool.
+unsafe impl Send for ScratchPool {}
+
+impl ScratchPool {
+    /// Takes the memory for an object with the specified layout from the pool,
+    /// if available.
+    fn take(&mut self, layout: Layout) -> Option<ScratchObject> {
+        let object = self.objects.get_mut(&layout)?.pop()?;
+        self.len -= 1;
+        Some(object)
+    }
+
+    /// Returns the memory of a collected object to the pool. Returns the
+    /// object if the pool is full.
+    fn put(&mut self, layout: Layout, object: ScratchObject) -> Result<(), ScratchObject> {
+        if self.len >= SCRATCH_CAPACITY {
+            return Err(object);
+        }
+        self.objects.entry(layout).or_default().push(object);
+        self.len += 1;
+        Ok(())
+    }
+}
+
+impl Drop for ScratchPool {
+    fn drop(&mut self) {
+        for (layout, objects) in self.objects.drain() {
+            for object in objects {
+                unsafe { std::alloc::dealloc(object.data.as_ptr(), layout) };
+            }
+        }
+    }
+}
+
 /// Implements a simple mark-sweep type garbage collector.
 pub struct MarkSweep<O>
 where
     O: Observer<Event = Event>,
 {
     objects: RwLock<HashMap<GcPtr, Pin<Box<ObjectInfo>>>>,
+    scratch: Mutex<ScratchPool>,
     observer: O,
     stats: RwLock<Stats>,
+    debug_checks: bool,
+    /// Memory that is scanned conservatively for references on every
+    /// collection, by its address, see [`MarkSweep::alloc_roots`]
+    root_regions: Mutex<HashMap<usize, Layout>>,
 }
 
 impl<O> Default for MarkSweep<O>
@@ -194,8 +260,11 @@ where
     fn default() -> Self {
         MarkSweep {
             objects: RwLock::new(HashMap::new()),
+            scratch: Mutex::new(ScratchPool::default()),
             observer: O::default(),
             stats: RwLock::new(Stats::default()),
+            debug_checks: false,
+            root_regions: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -208,11 +277,26 @@ where
     pub fn with_observer(observer: O) -> Self {
         Self {
             objects: RwLock::new(HashMap::new()),
+            scratch: Mutex::new(ScratchPool::default()),
             observer,
             stats: RwLock::new(Stats::default()),
+            debug_checks: false,
+            root_regions: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Enables or disables debug checks. When enabled, the memory of collected
+    /// objects is poisoned before it is freed and the heap is validated
+    /// before and after every collection and after every memory mapping,
+    /// panicking if it is corrupted.
+    ///
+    /// This surfaces bugs like dangling references early, at the cost of
+    /// performance.
+    pub fn with_debug_checks(mut self, enabled: bool) -> Self {
+        self.debug_checks = enabled;
+        self
+    }
+
     /// Logs an allocation
     fn log_alloc(&self, handle: GcPtr, size: usize) {
         {
@@ -227,8 +311,43 @@ where
     pub fn observer(&self) -> &O {
         &self.observer
     }
+
+    /// Allocates `size` bytes of zeroed memory that is scanned conservatively
+    /// for references on every collection, until it is freed with
+    /// [`MarkSweep::free_roots`]. This keeps objects alive that are only
+    /// referenced from memory that the collector cannot trace otherwise, e.g.
+    /// the frames of suspended coroutines.
+    pub fn alloc_roots(&self, size: usize) -> NonNull<u8> {
+        let layout = Layout::from_size_align(size.max(1), ROOT_REGION_ALIGN)
+            .expect("invalid size of root region");
+        let ptr = NonNull::new(unsafe { std::alloc::alloc_zeroed(layout) })
+            .expect("failed to allocate memory for root region");
+        self.root_regions
+            .lock()
+            .insert(ptr.as_ptr() as usize, layout);
+        ptr
+    }
+
+    /// Frees memory that was allocated with [`MarkSweep::alloc_roots`].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`MarkSweep::alloc_roots`] of this
+    /// collector, and must not have been freed yet.
+    pub unsafe fn free_roots(&self, ptr: NonNull<u8>) {
+        let layout = self
+            .root_regions
+            .lock()
+            .remove(&(ptr.as_ptr() as usize))
+            .expect("root region was not allocated by this collector");
+        std::alloc::dealloc(ptr.as_ptr(), layout);
+    }
 }
 
+/// The alignment of the memory that is allocated by [`MarkSweep::alloc_roots`],
+/// which suffices for any value
+const ROOT_REGION_ALIGN: usize = 16;
+
 fn alloc_obj(ty: Type) -> Pin<Box<ObjectInfo>> {
     let ptr = NonNull::new(unsafe { std::alloc::alloc_zeroed(ty.value_layout()) })
         .expect("failed to allocate memory for new object");
@@ -240,6 +359,42 @@ fn alloc_obj(ty: Type) -> Pin<Box<ObjectInfo>> {
     })
 }
 
+/// Allocates an object in the memory of a collected object, that has the same
+/// layout as `ty`.
+fn reuse_obj(ty: Type, object: ScratchObject) -> Pin<Box<ObjectInfo>> {
+    let ScratchObject { mut info, data } = object;
+    unsafe {
+        std::ptr::write_bytes(data.as_ptr(), 0, ty.value_layout().size());
+        info.as_mut_ptr().write(ObjectInfo {
+            data: ObjectInfoData { ptr: data },
+            ty,
+            roots: 0,
+            color: Color::White,
+        });
+        Pin::new(Box::from_raw(Box::into_raw(info).cast::<ObjectInfo>()))
+    }
+}
+
+/// Frees the memory of a collected object, or returns it to `scratch` for
+/// reuse. `layout` is the layout of the object's data.
+fn free_obj(obj: Pin<Box<ObjectInfo>>, layout: Layout, scratch: &mut ScratchPool) {
+    let info = Box::into_raw(Pin::into_inner(obj));
+    unsafe {
+        if (*info).ty.is_array() {
+            std::alloc::dealloc((*info).data.ptr.as_ptr(), layout);
+            drop(Box::from_raw(info));
+            return;
+        }
+
+        let data = (*info).data.ptr;
+        std::ptr::drop_in_place(std::ptr::addr_of_mut!((*info).ty));
+        let info = Box::from_raw(info.cast::<MaybeUninit<ObjectInfo>>());
+        if let Err(object) = scratch.put(layout, ScratchObject { info, data }) {
+            std::alloc::dealloc(object.data.as_ptr(), layout);
+        }
+    }
+}
+
 /// An error that might occur when requesting memory layout of a type
 #[derive(Debug)]
 pub enum MemoryLayoutError {
@@ -471,7 +626,11 @@ where
     fn alloc(&self, ty: &Type) -> GcPtr {
         assert!(ty.is_concrete());
 
-        let object = alloc_obj(ty.clone());
+        let scratch = self.scratch.lock().take(ty.value_layout());
+        let object = match scratch {
+            Some(scratch) => reuse_obj(ty.clone(), scratch),
+            None => alloc_obj(ty.clone()),
+        };
         let size = object.layout().size();
 
         // We want to return a pointer to the `ObjectInfo`, to be used as handle.
@@ -557,15 +716,46 @@ where
     /// Collects all memory that is no longer referenced by rooted objects.
     /// Returns `true` if memory was reclaimed, `false` otherwise.
     pub fn collect(&self) -> bool {
+        self.collect_with_conservative_roots(&[])
+    }
+
+    /// Collects all memory that is no longer referenced by rooted objects, nor
+    /// by any of the `words`, e.g. the contents of a stack that is scanned
+    /// conservatively. A word references an object if it is equal to its
+    /// handle or points into its memory. Returns `true` if memory was
+    /// reclaimed, `false` otherwise.
+    pub fn collect_with_conservative_roots(&self, words: &[usize]) -> bool {
         self.observer.event(Event::Start);
 
         let mut objects = self.objects.write();
 
-        // Get all roots
+        if self.debug_checks {
+            verify_heap(&objects);
+        }
+
+        // Get all roots, including the references from root regions
+        let root_regions = self.root_regions.lock();
+        let conservative_roots = if root_regions.is_empty() {
+            conservative_roots(&objects, words)
+        } else {
+            let mut words = words.to_vec();
+            for (&address, layout) in root_regions.iter() {
+                // Safety: root regions are readable until they are freed
+                let region = unsafe {
+                    std::slice::from_raw_parts(
+                        address as *const usize,
+                        layout.size() / std::mem::size_of::<usize>(),
+                    )
+                };
+                words.extend_from_slice(region);
+            }
+            conservative_roots(&objects, &words)
+        };
+        drop(root_regions);
         let mut roots = objects
             .iter()
-            .filter_map(|(_, obj)| {
-                if obj.roots > 0 {
+            .filter_map(|(handle, obj)| {
+                if obj.roots > 0 || conservative_roots.contains(handle) {
                     Some(obj.as_ref().get_ref() as *const _ as *mut ObjectInfo)
                 } else {
                     None
@@ -596,37 +786,163 @@ where
         }
 
         // Sweep all non-reachable objects
+        let mut scratch = self.scratch.lock();
         let size_before = objects.len();
-        objects.retain(|h, obj| {
-            if obj.color == Color::Black {
-                unsafe {
-                    obj.as_mut().get_unchecked_mut().color = Color::White;
+        let unreachable: Vec<GcPtr> = objects
+            .iter_mut()
+            .filter_map(|(h, obj)| {
+                if obj.color == Color::Black {
+                    unsafe {
+                        obj.as_mut().get_unchecked_mut().color = Color::White;
+                    }
+                    None
+                } else {
+                    Some(*h)
                 }
-                true
-            } else {
-                let value_memory_layout = obj.layout();
-                unsafe { std::alloc::dealloc(obj.data.ptr.as_mut(), value_memory_layout) };
-                self.observer.event(Event::Deallocation(*h));
-                {
-                    let mut stats = self.stats.write();
-                    stats.allocated_memory -= value_memory_layout.size();
+            })
+            .collect();
+        for h in unreachable {
+            let obj = objects.remove(&h).expect("unreachable object must exist");
+            let value_memory_layout = obj.layout();
+            if self.debug_checks {
+                unsafe {
+                    std::ptr::write_bytes(
+                        obj.data.ptr.as_ptr(),
+                        POISON_BYTE,
+                        value_memory_layout.size(),
+                    );
                 }
-                false
             }
-        });
+            free_obj(obj, value_memory_layout, &mut scratch);
+            self.observer.event(Event::Deallocation(h));
+            {
+                let mut stats = self.stats.write();
+                stats.allocated_memory -= value_memory_layout.size();
+            }
+        }
+        drop(scratch);
         let size_after = objects.len();
 
+        if self.debug_checks {
+            verify_heap(&objects);
+        }
+
         self.observer.event(Event::End);
 
         size_before != size_after
     }
+
+    /// Returns a snapshot of all allocated objects and the references between
+    /// them.
+    pub fn dump_heap(&self) -> HeapDump {
+        let objects = self.objects.read();
+
+        let mut handles: Vec<GcPtr> = objects.keys().copied().collect();
+        handles.sort();
+        let ids: HashMap<GcPtr, usize> = handles
+            .iter()
+            .enumerate()
+            .map(|(id, handle)| (*handle, id))
+            .collect();
+
+        let heap_objects = handles
+            .iter()
+            .enumerate()
+            .map(|(id, handle)| {
+                let object = &objects[handle];
+
+                let mut references = Vec::new();
+                object_references(object, &mut references);
+
+                HeapObject {
+                    id,
+                    handle: *handle,
+                    type_name: object.ty.name().to_owned(),
+                    size: object.layout().size(),
+                    roots: object.roots,
+                    is_reachable: false,
+                    references: references
+                        .into_iter()
+                        .filter_map(|(field, reference)| {
+                            ids.get(&reference)
+                                .map(|&target| HeapReference { field, target })
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        HeapDump::new(heap_objects)
+    }
+}
+
+/// Collects the references of an object, labeled with the path of the field
+/// or array element that holds them.
+fn object_references(object: &ObjectInfo, references: &mut Vec<(String, GcPtr)>) {
+    match object.ty.kind() {
+        TypeKind::Primitive(_) | TypeKind::Pointer(_) | TypeKind::Opaque(_) => {}
+        TypeKind::Struct(_) => {
+            value_references(unsafe { object.data.ptr }, &object.ty, "", references);
+        }
+        TypeKind::Array(_) => {
+            let array = ArrayHandle {
+                obj: NonNull::from(object),
+            };
+            let element_ty = array.element_type();
+            for (idx, element) in array.elements().enumerate() {
+                field_references(element, &element_ty, format!("[{idx}]"), references);
+            }
+        }
+    }
+}
+
+/// Collects the references in the fields of the value of the struct type `ty`
+/// at `ptr`. The paths of the fields are prefixed with `path`.
+fn value_references(
+    ptr: NonNull<u8>,
+    ty: &Type,
+    path: &str,
+    references: &mut Vec<(String, GcPtr)>,
+) {
+    let Some(struct_ty) = ty.as_struct() else {
+        return;
+    };
+    for field in struct_ty.fields().iter() {
+        let field_ptr = unsafe { NonNull::new_unchecked(ptr.as_ptr().add(field.offset())) };
+        let field_path = if path.is_empty() {
+            field.name().to_owned()
+        } else {
+            format!("{path}.{}", field.name())
+        };
+        field_references(field_ptr, &field.ty(), field_path, references);
+    }
+}
+
+/// Collects the references in a field of type `ty` at `ptr`, whose path is
+/// `path`.
+fn field_references(
+    ptr: NonNull<u8>,
+    ty: &Type,
+    path: String,
+    references: &mut Vec<(String, GcPtr)>,
+) {
+    match ty.kind() {
+        TypeKind::Primitive(_) | TypeKind::Pointer(_) | TypeKind::Opaque(_) => {}
+        TypeKind::Struct(s) if !s.is_gc_struct() => value_references(ptr, ty, &path, references),
+        TypeKind::Struct(_) | TypeKind::Array(_) => {
+            let reference = unsafe { ptr.cast::<*const ObjectInfo>().as_ptr().read_unaligned() };
+            if !reference.is_null() {
+                references.push((path, reference.into()));
+            }
+        }
+    }
 }
 
 impl<O> MemoryMapper for MarkSweep<O>
 where
     O: Observer<Event = Event>,
 {
-    fn map_memory(&self, mapping: Mapping) -> Vec<GcPtr> {
+    fn map_memory(&self, mapping: Mapping) -> MappedObjects {
         unsafe fn get_field_ptr(struct_ptr: NonNull<u8>, offset: usize) -> NonNull<u8> {
             let mut ptr = struct_ptr.as_ptr() as usize;
             ptr += offset;
@@ -805,6 +1121,9 @@ where
                         // zero initialize
                     }
                 }
+                mapping::Action::InitializeDefault { value } => unsafe {
+                    std::ptr::copy_nonoverlapping(value.as_ptr(), dest.as_ptr(), value.len());
+                },
                 mapping::Action::StructAlloc => {
                     let object = alloc_obj(new_ty.clone());
 
@@ -947,12 +1266,13 @@ where
         }
 
         let mut new_allocations = Vec::new();
+        let mut migrated = Vec::new();
 
         // Map struct types
         objects
-            .values_mut()
-            .filter(|object_info| object_info.ty.is_struct())
-            .for_each(|object_info| {
+            .iter_mut()
+            .filter(|(_, object_info)| object_info.ty.is_struct())
+            .for_each(|(ptr, object_info)| {
                 if let Some(conversion) = mapping.struct_mappings.get(&object_info.ty) {
                     let old_layout = object_info.ty.value_layout();
                     let src = unsafe { object_info.data.ptr };
@@ -970,7 +1290,12 @@ where
                         dest,
                     );
 
-                    unsafe { std::alloc::dealloc(src.as_ptr(), old_layout) };
+                    unsafe {
+                        if self.debug_checks {
+                            std::ptr::write_bytes(src.as_ptr(), POISON_BYTE, old_layout.size());
+                        }
+                        std::alloc::dealloc(src.as_ptr(), old_layout);
+                    };
 
                     object_info.set(ObjectInfo {
                         data: ObjectInfoData { ptr: dest },
@@ -978,14 +1303,16 @@ where
                         color: object_info.color,
                         ty: conversion.new_ty.clone(),
                     });
+
+                    migrated.push(*ptr);
                 }
             });
 
         // Map rooted array types
         objects
-            .values_mut()
-            .filter(|object_info| object_info.ty.is_array())
-            .for_each(|object_info| {
+            .iter_mut()
+            .filter(|(_, object_info)| object_info.ty.is_array())
+            .for_each(|(ptr, object_info)| {
                 let mut ty = object_info.ty.clone();
                 let mut stack = Vec::new();
 
@@ -1026,6 +1353,8 @@ where
                         // Update the type of arrays of arrays
                         object_info.as_mut().ty = conversion.new_ty.clone();
                     }
+
+                    migrated.push(*ptr);
                 }
             });
 
@@ -1042,7 +1371,78 @@ where
             self.log_alloc(handle, size);
         }
 
-        deleted
+        if self.debug_checks {
+            verify_heap(&objects);
+        }
+
+        MappedObjects { migrated, deleted }
+    }
+}
+
+/// Returns the objects that are referenced by any of the `words`, because a
+/// word is equal to the handle of an object or points into its memory.
+fn conservative_roots(
+    objects: &HashMap<GcPtr, Pin<Box<ObjectInfo>>>,
+    words: &[usize],
+) -> HashSet<GcPtr> {
+    let mut roots = HashSet::new();
+    if words.is_empty() {
+        return roots;
+    }
+
+    // The memory of all objects, by the address at which it starts
+    let memory: BTreeMap<usize, (usize, GcPtr)> = objects
+        .iter()
+        .map(|(&handle, object)| {
+            let start = unsafe { object.data.ptr }.as_ptr() as usize;
+            (start, (start + object.layout().size(), handle))
+        })
+        .collect();
+
+    for &word in words {
+        let handle = GcPtr::from(word as RawGcPtr);
+        if objects.contains_key(&handle) {
+            roots.insert(handle);
+        } else if let Some((_, &(end, handle))) = memory.range(..=word).next_back() {
+            // A pointer one past the end of the memory still refers to the object
+            if word <= end {
+                roots.insert(handle);
+            }
+        }
+    }
+
+    roots
+}
+
+/// Validates that the heap is consistent, panicking if it is not. Every
+/// object must be unmarked and may only reference allocated objects.
+fn verify_heap(objects: &HashMap<GcPtr, Pin<Box<ObjectInfo>>>) {
+    for (&handle, object) in objects {
+        assert_eq!(
+            object.color,
+            Color::White,
+            "heap corruption: object {handle:?} of type `{}` is still marked",
+            object.ty.name()
+        );
+
+        if object.ty.is_array() {
+            let header = unsafe { object.data.array.as_ref() };
+            assert!(
+                header.length <= header.capacity,
+                "heap corruption: array {handle:?} of type `{}` has a length of {} but a capacity of {}",
+                object.ty.name(),
+                header.length,
+                header.capacity
+            );
+        }
+
+        for reference in object.ty.trace(handle) {
+            assert!(
+                objects.contains_key(&reference),
+                "heap corruption: object {handle:?} of type `{}` references {reference:?}, which is not an allocated object",
+                object.ty.name()
+            );
+        }
     }
 }
 
@@ -1115,9 +1515,10 @@ impl ObjectInfo {
     /// Returns the layout of the data pointed to by data
     pub fn layout(&self) -> Layout {
         match self.ty.kind() {
-            TypeKind::Struct(_) | TypeKind::Primitive(_) | TypeKind::Pointer(_) => {
-                self.ty.value_layout()
-            }
+            TypeKind::Struct(_)
+            | TypeKind::Primitive(_)
+            | TypeKind::Pointer(_)
+            | TypeKind::Opaque(_) => self.ty.value_layout(),
             TypeKind::Array(array) => {
                 let elem_count = unsafe { self.data.array.as_ref().capacity };
                 let elem_layout = repeat_layout(array.element_type().value_layout(), elem_count)
@@ -1,6 +1,6 @@
 pub use r#type::{
-    ArrayType, Field, FieldData, HasStaticType, PointerType, StructType, StructTypeBuilder, Type,
-    TypeCollectionStats, TypeKind,
+    ArrayType, Field, FieldData, HasStaticType, OpaqueType, PointerType, ReprCStruct, StructType,
+    StructTypeBuilder, Type, TypeCollectionStats, TypeKind,
 };
 
 pub mod ffi {
@@ -4,7 +4,7 @@ use itertools::Itertools;
 use mun_abi::Guid;
 
 use crate::{
-    diff::{compute_struct_diff, FieldDiff, StructDiff},
+    diff::{compute_struct_diff_with_renames, FieldDiff, FieldRenames, StructDiff},
     gc::GcPtr,
     r#type::Type,
     ArrayType, Field, TypeKind,
@@ -70,6 +70,8 @@ pub enum Action {
         element_action: Box<Action>,
         old_offset: usize,
     },
+    /// Initialize the memory with the default value of the field.
+    InitializeDefault { value: Box<[u8]> },
     /// Allocate a new struct and ensure zero-initalization.
     StructAlloc,
     /// Allocate a new struct and map from a heap-allocated struct.
@@ -83,9 +85,15 @@ pub enum Action {
 }
 
 impl Mapping {
-    #[allow(clippy::mutable_key_type)]
     pub fn new(old: &[Type], new: &[Type]) -> Self {
-        let diff = compute_struct_diff(old, new);
+        Self::with_renames(old, new, &FieldRenames::default())
+    }
+
+    /// Constructs the mapping from `old` to `new` types, preserving the values
+    /// of the fields that were renamed according to the `renames`.
+    #[allow(clippy::mutable_key_type)]
+    pub fn with_renames(old: &[Type], new: &[Type], renames: &FieldRenames) -> Self {
+        let diff = compute_struct_diff_with_renames(old, new, renames);
 
         let mut conversions = HashMap::new();
         let mut deletions = HashSet::new();
@@ -188,6 +196,11 @@ pub unsafe fn field_mapping(old_ty: &Type, new_ty: &Type, diff: &[FieldDiff]) ->
         .into_iter()
         .flat_map(|s| s.fields().iter())
         .collect_vec();
+    let new_fields = new_ty
+        .as_struct()
+        .into_iter()
+        .flat_map(|s| s.fields().iter())
+        .collect_vec();
 
     let deletions: HashSet<usize> = diff
         .iter()
@@ -235,7 +248,11 @@ pub unsafe fn field_mapping(old_ty: &Type, new_ty: &Type, diff: &[FieldDiff]) ->
             }),
             FieldDiff::Insert { index, new_type } => Some((
                 *index,
-                if new_type.is_struct() && !new_type.is_value_type() {
+                if let Some(value) = new_fields.get(*index).and_then(Field::default_value) {
+                    Action::InitializeDefault {
+                        value: Box::from(value),
+                    }
+                } else if new_type.is_struct() && !new_type.is_value_type() {
                     Action::StructAlloc
                 } else if new_type.is_array() {
                     Action::ArrayAlloc
@@ -291,11 +308,6 @@ pub unsafe fn field_mapping(old_ty: &Type, new_ty: &Type, diff: &[FieldDiff]) ->
         }
     }
 
-    let new_fields = new_ty
-        .as_struct()
-        .into_iter()
-        .flat_map(|s| s.fields().iter())
-        .collect_vec();
     StructMapping {
         field_mapping: mapping
             .into_iter()
@@ -323,6 +335,7 @@ pub fn resolve_edit(old_ty: &Type, new_ty: &Type, old_offset: usize) -> Action {
         TypeKind::Struct(_) => resolve_struct_edit(old_ty, new_ty, old_offset),
         TypeKind::Pointer(_) => resolve_pointer_edit(old_ty, new_ty),
         TypeKind::Array(old_array) => resolve_array_edit(old_array, new_ty, old_offset),
+        TypeKind::Opaque(_) => resolve_opaque_edit(old_ty, new_ty, old_offset),
     }
 }
 
@@ -347,6 +360,7 @@ fn resolve_primitive_edit(
         TypeKind::Array(new_array) => {
             resolve_primitive_to_array_edit(old_ty, new_array, old_offset)
         }
+        TypeKind::Opaque(_) => Action::ZeroInitialize,
     }
 }
 
@@ -382,7 +396,7 @@ fn resolve_primitive_to_array_edit(
 
 fn resolve_struct_edit(old_ty: &Type, new_ty: &Type, old_offset: usize) -> Action {
     match &new_ty.kind() {
-        TypeKind::Primitive(_) => Action::ZeroInitialize,
+        TypeKind::Primitive(_) | TypeKind::Opaque(_) => Action::ZeroInitialize,
         TypeKind::Struct(_) => resolve_struct_to_struct_edit(old_ty, new_ty, old_offset),
         TypeKind::Pointer(_) => unreachable!(),
         TypeKind::Array(new_array) => resolve_struct_to_array_edit(old_ty, new_array, old_offset),
@@ -456,6 +470,19 @@ fn resolve_struct_to_array_edit(
     }
 }
 
+/// Handles to opaque types are owned by the host, so they can only be copied
+/// to a field of the same opaque type.
+fn resolve_opaque_edit(old_ty: &Type, new_ty: &Type, old_offset: usize) -> Action {
+    if *old_ty == *new_ty {
+        Action::Copy {
+            old_offset,
+            size: old_ty.value_layout().size(),
+        }
+    } else {
+        Action::ZeroInitialize
+    }
+}
+
 fn resolve_pointer_edit(_old_ty: &Type, _new_ty: &Type) -> Action {
     // Not supported in the language - yet
     unreachable!()
@@ -467,6 +494,7 @@ fn resolve_array_edit(old_array: &ArrayType<'_>, new_ty: &Type, old_offset: usiz
         TypeKind::Struct(_) => resolve_array_to_struct_edit(old_array, new_ty, old_offset),
         TypeKind::Pointer(_) => unreachable!(),
         TypeKind::Array(new_array) => resolve_array_to_array_edit(old_array, new_array, old_offset),
+        TypeKind::Opaque(_) => Action::ZeroInitialize,
     }
 }
 
@@ -512,12 +540,19 @@ fn resolve_array_to_array_edit(
     }
 }
 
+/// The objects that were affected by mapping memory.
+#[derive(Debug, Default)]
+pub struct MappedObjects {
+    /// The objects whose memory was converted to the layout of a new type
+    pub migrated: Vec<GcPtr>,
+    /// The objects of types that were deleted. The corresponding types have
+    /// to remain in-memory until the objects have been deallocated.
+    pub deleted: Vec<GcPtr>,
+}
+
 /// A trait used to map allocated memory using type differences.
 pub trait MemoryMapper {
-    /// Maps its allocated memory using the provided `mapping`.
-    ///
-    /// A `Vec<GcPtr>` is returned containing all objects of types that were
-    /// deleted. The corresponding types have to remain in-memory until the
-    /// objects have been deallocated.
-    fn map_memory(&self, mapping: Mapping) -> Vec<GcPtr>;
+    /// Maps its allocated memory using the provided `mapping`, returning the
+    /// objects that were migrated or whose types were deleted.
+    fn map_memory(&self, mapping: Mapping) -> MappedObjects;
 }
@@ -241,6 +241,7 @@ pub enum TypeKind {
     Pointer(r#pointer::PointerInfo),
     Struct(r#struct::StructInfo),
     Array(r#array::ArrayInfo),
+    Opaque(Guid),
 }
 
 /// Returns information about what kind of type this is.
@@ -273,6 +274,7 @@ pub unsafe extern "C" fn mun_type_kind(ty: Type, kind: *mut TypeKind) -> ErrorHa
             (a as *const ArrayData).cast(),
             Arc::as_ptr(ManuallyDrop::deref(&store)).cast(),
         )),
+        TypeDataKind::Opaque(guid) => TypeKind::Opaque(*guid),
         TypeDataKind::Uninitialized => unreachable!(),
     };
 
@@ -80,6 +80,46 @@ pub unsafe extern "C" fn mun_struct_type_memory_kind(
     ErrorHandle::default()
 }
 
+/// Returns how the fields of the struct are laid out in memory.
+///
+/// # Safety
+///
+/// This function results in undefined behavior if the passed in `StructType`
+/// has been deallocated by a previous call to [`mun_type_release`].
+#[no_mangle]
+pub unsafe extern "C" fn mun_struct_type_repr(
+    ty: StructInfo,
+    repr: *mut abi::StructRepr,
+) -> ErrorHandle {
+    let ty = mun_error_try!(ty
+        .inner()
+        .map_err(|e| format!("invalid argument 'ty': {e}")));
+    let repr = try_deref_mut!(repr);
+    *repr = ty.repr;
+    ErrorHandle::default()
+}
+
+/// Returns whether values of the struct are passed to and returned from public
+/// Mun functions by value, following the C calling convention, instead of as
+/// handles to garbage collected values.
+///
+/// # Safety
+///
+/// This function results in undefined behavior if the passed in `StructType`
+/// has been deallocated by a previous call to [`mun_type_release`].
+#[no_mangle]
+pub unsafe extern "C" fn mun_struct_type_is_passed_by_value(
+    ty: StructInfo,
+    passed_by_value: *mut bool,
+) -> ErrorHandle {
+    let ty = mun_error_try!(ty
+        .inner()
+        .map_err(|e| format!("invalid argument 'ty': {e}")));
+    let passed_by_value = try_deref_mut!(passed_by_value);
+    *passed_by_value = ty.passed_by_value;
+    ErrorHandle::default()
+}
+
 /// An array of [`Field`]s.
 ///
 /// This is backed by a dynamically allocated array. Ownership is transferred
@@ -297,8 +337,8 @@ mod test {
     use super::{
         super::{mun_type_kind, mun_type_release, Type, TypeKind},
         mun_field_name, mun_field_offset, mun_field_type, mun_fields_destroy,
-        mun_struct_type_fields, mun_struct_type_guid, mun_struct_type_memory_kind, Field, Fields,
-        StructInfo,
+        mun_struct_type_fields, mun_struct_type_guid, mun_struct_type_is_passed_by_value,
+        mun_struct_type_memory_kind, mun_struct_type_repr, Field, Fields, StructInfo,
     };
     use crate::{r#type::ffi::r#struct::mun_fields_find_by_name, HasStaticType, StructTypeBuilder};
 
@@ -387,6 +427,74 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_mun_struct_type_repr() {
+        let rust_ty = StructTypeBuilder::new("Foo")
+            .set_repr(abi::StructRepr::Packed)
+            .add_field("foo", u8::type_info().clone())
+            .add_field("bar", i32::type_info().clone())
+            .finish();
+
+        // The fields of a packed struct are not padded
+        let bar = rust_ty
+            .as_struct()
+            .unwrap()
+            .fields()
+            .find_by_name("bar")
+            .unwrap()
+            .offset();
+        assert_eq!(bar, 1);
+
+        let (ty, struct_ty) = unsafe { struct_type(rust_ty.into()) };
+
+        assert_getter1!(mun_struct_type_repr(struct_ty, repr));
+        assert_eq!(repr, abi::StructRepr::Packed);
+
+        assert!(unsafe { mun_type_release(ty) }.is_ok());
+    }
+
+    #[test]
+    fn test_mun_struct_type_repr_invalid_null() {
+        let mut repr = MaybeUninit::uninit();
+        assert_error_snapshot!(unsafe {
+            mun_struct_type_repr(
+                StructInfo(ptr::null(), ptr::null()),
+                repr.as_mut_ptr(),
+            )},
+            @r###""invalid argument \'ty\': null pointer""###
+        );
+
+        let ty = StructTypeBuilder::new("Foo")
+            .add_field("foo", i32::type_info().clone())
+            .finish()
+            .into();
+        let (_ty, struct_ty) = unsafe { struct_type(ty) };
+        assert_error_snapshot!(
+            unsafe { mun_struct_type_repr(struct_ty, ptr::null_mut()) },
+            @r###""invalid argument \'repr\': null pointer""###
+        );
+    }
+
+    #[test]
+    fn test_mun_struct_type_is_passed_by_value() {
+        // Only the compiler passes structs by value
+        let rust_ty = StructTypeBuilder::new("Foo")
+            .set_memory_kind(abi::StructMemoryKind::Value)
+            .set_repr(abi::StructRepr::C)
+            .add_field("foo", i32::type_info().clone())
+            .finish();
+
+        let (ty, struct_ty) = unsafe { struct_type(rust_ty.into()) };
+
+        assert_getter1!(mun_struct_type_is_passed_by_value(
+            struct_ty,
+            passed_by_value
+        ));
+        assert!(!passed_by_value);
+
+        assert!(unsafe { mun_type_release(ty) }.is_ok());
+    }
+
     #[test]
     fn test_mun_struct_type_fields() {
         let i32_type = i32::type_info();
@@ -17,7 +17,7 @@ pub mod ffi;
 use std::{
     alloc::Layout,
     borrow::Cow,
-    collections::VecDeque,
+    collections::{HashMap, VecDeque},
     ffi::c_void,
     fmt::{self, Debug, Display, Formatter},
     hash::{Hash, Hasher},
@@ -116,7 +116,9 @@ impl TypeDataStore {
                         queue.push_back(a.element_ty);
                     }
                 }
-                TypeDataKind::Primitive(_) | TypeDataKind::Uninitialized => {}
+                TypeDataKind::Primitive(_)
+                | TypeDataKind::Opaque(_)
+                | TypeDataKind::Uninitialized => {}
             }
 
             // Iterate over the indirections. This is an interesting case safety wise,
@@ -159,13 +161,18 @@ impl TypeDataStore {
     }
 
     /// Tries to convert multiple [`abi::TypeDefinition`] to internal type
-    /// representations. If the conversion succeeds an updated [`TypeTable`]
-    /// is returned
+    /// representations, together with the default values of their fields. If
+    /// the conversion succeeds an updated [`TypeTable`] is returned
     pub fn try_from_abi<'abi>(
         self: &Arc<Self>,
         definitions: impl Iterator<Item = &'abi abi::TypeDefinition<'abi>>,
+        field_defaults: impl Iterator<Item = &'abi abi::FieldDefault>,
         mut type_table: TypeTable,
     ) -> Result<(TypeTable, Vec<Type>), TryFromAbiError<'abi>> {
+        let field_defaults: HashMap<(abi::Guid, u16), &[u8]> = field_defaults
+            .map(|default| ((default.struct_guid, default.field_index), default.value()))
+            .collect();
+
         // Acquire a lock in the type entries
         let mut entries = self.types.lock();
 
@@ -194,7 +201,7 @@ impl TypeDataStore {
             let inner_ty = unsafe { ty.inner.as_mut() };
             let type_data = match &type_def.data {
                 abi::TypeDefinitionData::Struct(s) => {
-                    StructData::try_from_abi(s, &type_table)?.into()
+                    StructData::try_from_abi(s, &field_defaults, &type_table)?.into()
                 }
             };
             inner_ty.data = type_data;
@@ -315,7 +322,7 @@ impl Debug for Type {
 impl Display for Type {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self.kind() {
-            TypeKind::Primitive(_) => std::fmt::Display::fmt(self.name(), f),
+            TypeKind::Primitive(_) | TypeKind::Opaque(_) => std::fmt::Display::fmt(self.name(), f),
             TypeKind::Struct(s) => std::fmt::Display::fmt(&s, f),
             TypeKind::Pointer(p) => std::fmt::Display::fmt(&p, f),
             TypeKind::Array(a) => std::fmt::Display::fmt(&a, f),
@@ -519,6 +526,8 @@ enum TypeDataKind {
     Pointer(PointerData),
     /// An array
     Array(ArrayData),
+    /// Opaque types whose values are created and owned by the host
+    Opaque(abi::Guid),
     /// Indicates that the type has been allocated but it has not yet been
     /// initialized, this indicates that it still needs to be properly
     /// initialized.
@@ -535,6 +544,9 @@ pub enum TypeKind<'t> {
     Pointer(PointerType<'t>),
     /// An array of values
     Array(ArrayType<'t>),
+    /// Opaque types whose values are created and owned by the host (i.e.
+    /// `extern struct`s)
+    Opaque(&'t abi::Guid),
 }
 
 /// A linked version of [`mun_abi::StructInfo`] that has resolved all
@@ -547,6 +559,11 @@ struct StructData {
     pub fields: Vec<FieldData>,
     /// Struct memory kind
     pub memory_kind: abi::StructMemoryKind,
+    /// How the fields of the struct are laid out in memory
+    pub repr: abi::StructRepr,
+    /// Whether values of the struct are passed to and returned from public Mun
+    /// functions by value
+    pub passed_by_value: bool,
 }
 
 /// Reference information of a struct
@@ -571,6 +588,18 @@ impl<'t> StructType<'t> {
         self.inner.memory_kind
     }
 
+    /// Returns how the fields of this struct are laid out in memory
+    pub fn repr(&self) -> abi::StructRepr {
+        self.inner.repr
+    }
+
+    /// Returns true if values of this struct are passed to and returned from
+    /// public Mun functions by value, following the C calling convention.
+    /// Otherwise they are passed as handles to garbage collected values.
+    pub fn is_passed_by_value(&self) -> bool {
+        self.inner.passed_by_value
+    }
+
     /// Returns true if this struct is a value struct. Value structs are passed
     /// by value and are not allocated by the garbage collector.
     pub fn is_value_struct(&self) -> bool {
@@ -795,6 +824,7 @@ impl Type {
         guid: abi::Guid,
         fields: impl IntoIterator<Item = (String, Type, u16)>,
         memory_kind: abi::StructMemoryKind,
+        repr: abi::StructRepr,
     ) -> Type {
         let fields = fields
             .into_iter()
@@ -802,8 +832,21 @@ impl Type {
                 name,
                 type_info: ty.inner,
                 offset,
+                default: None,
             })
             .collect::<Vec<_>>();
+        Self::new_struct_with_fields(name, layout, guid, fields, memory_kind, repr)
+    }
+
+    /// Constructs a new struct type from already linked fields
+    fn new_struct_with_fields(
+        name: impl Into<String>,
+        layout: Layout,
+        guid: abi::Guid,
+        fields: Vec<FieldData>,
+        memory_kind: abi::StructMemoryKind,
+        repr: abi::StructRepr,
+    ) -> Type {
         GLOBAL_TYPE_STORE.allocate(
             name,
             layout,
@@ -811,11 +854,31 @@ impl Type {
                 guid,
                 fields,
                 memory_kind,
+                repr,
+                // Only the compiler decides how structs are passed to functions
+                passed_by_value: false,
             }
             .into(),
         )
     }
 
+    /// Constructs a new opaque type. Values of opaque types are pointers to
+    /// data that is owned by the host. Mun code declares them as
+    /// `extern struct`, e.g.:
+    ///
+    /// ```mun
+    /// pub extern struct Texture;
+    /// ```
+    pub fn new_opaque(name: impl Into<String>) -> Type {
+        let name = name.into();
+        let guid = abi::Guid::from_opaque_type_name(&name);
+        GLOBAL_TYPE_STORE.allocate(
+            name,
+            Layout::new::<*const c_void>(),
+            TypeDataKind::Opaque(guid),
+        )
+    }
+
     /// Returns a reference to the [`TypeInner`]
     fn inner(&self) -> &TypeData {
         // Safety: taking the reference is always ok because the garbage collector
@@ -849,7 +912,7 @@ impl Type {
     /// value types directly contain their data.
     pub fn is_reference_type(&self) -> bool {
         match self.kind() {
-            TypeKind::Primitive(_) | TypeKind::Pointer(_) => false,
+            TypeKind::Primitive(_) | TypeKind::Pointer(_) | TypeKind::Opaque(_) => false,
             TypeKind::Array(_) => true,
             TypeKind::Struct(s) => s.is_gc_struct(),
         }
@@ -860,7 +923,7 @@ impl Type {
     /// types directly contain their data.
     pub fn is_value_type(&self) -> bool {
         match self.kind() {
-            TypeKind::Primitive(_) | TypeKind::Pointer(_) => true,
+            TypeKind::Primitive(_) | TypeKind::Pointer(_) | TypeKind::Opaque(_) => true,
             TypeKind::Array(_) => false,
             TypeKind::Struct(s) => s.is_value_struct(),
         }
@@ -898,6 +961,11 @@ impl Type {
         matches!(self.kind(), TypeKind::Array(_))
     }
 
+    /// Returns whether this is an opaque type that is owned by the host.
+    pub fn is_opaque(&self) -> bool {
+        matches!(self.kind(), TypeKind::Opaque(_))
+    }
+
     /// Returns the kind of the type
     pub fn kind(&self) -> TypeKind<'_> {
         match &self.inner().data {
@@ -914,6 +982,7 @@ impl Type {
                 inner: a,
                 store: &self.store,
             }),
+            TypeDataKind::Opaque(guid) => TypeKind::Opaque(guid),
             TypeDataKind::Uninitialized => {
                 unreachable!("should never be able to query the kind of an uninitialized type")
             }
@@ -924,7 +993,7 @@ impl Type {
     /// type that doesn't refer to another type like a pointer.
     pub fn is_concrete(&self) -> bool {
         match self.kind() {
-            TypeKind::Primitive(_) | TypeKind::Struct(_) => true,
+            TypeKind::Primitive(_) | TypeKind::Struct(_) | TypeKind::Opaque(_) => true,
             TypeKind::Pointer(_) | TypeKind::Array(_) => false,
         }
     }
@@ -933,7 +1002,7 @@ impl Type {
     /// represents a concrete type.
     pub fn as_concrete(&self) -> Option<&abi::Guid> {
         match self.kind() {
-            TypeKind::Primitive(g) => Some(g),
+            TypeKind::Primitive(g) | TypeKind::Opaque(g) => Some(g),
             TypeKind::Struct(s) => Some(s.guid()),
             TypeKind::Pointer(_) | TypeKind::Array(_) => None,
         }
@@ -967,13 +1036,19 @@ impl Type {
     }
 
     /// Tries to convert multiple [`abi::TypeDefinition`] to internal type
-    /// representations. If the conversion succeeds an updated [`TypeTable`]
-    /// is returned.
+    /// representations. The `field_defaults` provide the default values of
+    /// the fields of the struct types. If the conversion succeeds an updated
+    /// [`TypeTable`] is returned.
     pub fn try_from_abi<'abi>(
         type_info: impl IntoIterator<Item = &'abi abi::TypeDefinition<'abi>>,
+        field_defaults: impl IntoIterator<Item = &'abi abi::FieldDefault>,
         type_table: TypeTable,
     ) -> Result<(TypeTable, Vec<Type>), TryFromAbiError<'abi>> {
-        GLOBAL_TYPE_STORE.try_from_abi(type_info.into_iter(), type_table)
+        GLOBAL_TYPE_STORE.try_from_abi(
+            type_info.into_iter(),
+            field_defaults.into_iter(),
+            type_table,
+        )
     }
 
     /// Returns the type that represents a pointer to this type
@@ -1025,17 +1100,21 @@ impl Type {
 }
 
 impl StructData {
-    /// Tries to convert from an `abi::StructInfo`.
+    /// Tries to convert from an `abi::StructInfo`. The default values of the
+    /// fields are looked up in `field_defaults` by the struct's guid and the
+    /// index of the field.
     fn try_from_abi<'abi>(
         struct_info: &'abi abi::StructDefinition<'abi>,
+        field_defaults: &HashMap<(abi::Guid, u16), &[u8]>,
         type_table: &TypeTable,
     ) -> Result<StructData, TryFromAbiError<'abi>> {
         let fields: Result<Vec<FieldData>, TryFromAbiError<'abi>> = izip!(
+            0u16..,
             struct_info.field_names(),
             struct_info.field_types(),
             struct_info.field_offsets()
         )
-        .map(|(name, type_id, offset)| {
+        .map(|(index, name, type_id, offset)| {
             type_table
                 .find_type_info_by_id(type_id)
                 .ok_or_else(|| TryFromAbiError::UnknownTypeId(type_id.clone()))
@@ -1043,6 +1122,9 @@ impl StructData {
                     name: name.to_owned(),
                     type_info: type_info.inner,
                     offset: *offset,
+                    default: field_defaults
+                        .get(&(struct_info.guid, index))
+                        .map(|value| Box::from(*value)),
                 })
         })
         .collect();
@@ -1051,6 +1133,8 @@ impl StructData {
             guid: struct_info.guid,
             fields,
             memory_kind: struct_info.memory_kind,
+            repr: struct_info.repr,
+            passed_by_value: struct_info.passed_by_value,
         })
     }
 }
@@ -1064,6 +1148,9 @@ pub struct FieldData {
     pub type_info: NonNull<TypeData>,
     /// The field's offset
     pub offset: u16,
+    /// The value that the field is initialized with when it is added to an
+    /// existing struct, in the memory representation of the field's type
+    pub default: Option<Box<[u8]>>,
     // TODO: Field accessibility levels
     // const MunPrivacy_t *field_privacies,
 }
@@ -1093,8 +1180,18 @@ impl<'t> Field<'t> {
     pub fn offset(&self) -> usize {
         self.inner.offset as _
     }
+
+    /// Returns the default value of the field in the memory representation of
+    /// its type, if any.
+    pub fn default_value(&self) -> Option<&'t [u8]> {
+        self.inner.default.as_deref()
+    }
 }
 
+/// The name, type, offset, and default value of a field that is added through a
+/// [`StructTypeBuilder`].
+type BuilderField = (String, Type, usize, Option<Box<[u8]>>);
+
 /// A helper struct to create a struct type.
 pub struct StructTypeBuilder {
     /// The name of the struct type
@@ -1103,8 +1200,11 @@ pub struct StructTypeBuilder {
     /// The type of memory management for this struct
     memory_kind: abi::StructMemoryKind,
 
+    /// How the fields of the struct are laid out in memory
+    repr: abi::StructRepr,
+
     /// The fields of the struct
-    fields: Vec<(String, Type, usize)>,
+    fields: Vec<BuilderField>,
 
     /// Layout of the struct.
     layout: Layout,
@@ -1118,6 +1218,7 @@ impl StructTypeBuilder {
         Self {
             name: name.into(),
             memory_kind: abi::StructMemoryKind::Gc,
+            repr: abi::StructRepr::Default,
             fields: Vec::new(),
             layout: Layout::from_size_align(0, 1).expect("invalid default layout"),
             guid: None,
@@ -1130,19 +1231,55 @@ impl StructTypeBuilder {
         self
     }
 
+    /// Sets how the fields of the struct are laid out in memory. This must be
+    /// called before any fields are added.
+    pub fn set_repr(mut self, repr: abi::StructRepr) -> Self {
+        assert!(
+            self.fields.is_empty(),
+            "the representation must be set before fields are added"
+        );
+        self.repr = repr;
+        self
+    }
+
     /// Adds a field to the struct
-    pub fn add_field(mut self, name: impl Into<String>, ty: Type) -> Self {
+    pub fn add_field(self, name: impl Into<String>, ty: Type) -> Self {
+        self.add_field_inner(name.into(), ty, None)
+    }
+
+    /// Adds a field to the struct that has a default value. The value must be
+    /// in the memory representation of the field's type.
+    pub fn add_field_with_default(
+        self,
+        name: impl Into<String>,
+        ty: Type,
+        default: impl Into<Box<[u8]>>,
+    ) -> Self {
+        let default = default.into();
+        assert!(
+            ty.is_primitive() && default.len() == ty.value_layout().size(),
+            "the default value of a field must match the layout of its primitive type"
+        );
+        self.add_field_inner(name.into(), ty, Some(default))
+    }
+
+    fn add_field_inner(mut self, name: String, ty: Type, default: Option<Box<[u8]>>) -> Self {
         let field_layout = if ty.is_value_type() {
             ty.value_layout()
         } else {
-            Layout::new::<std::ffi::c_void>()
+            Layout::new::<*const std::ffi::c_void>()
+        };
+        let field_layout = if self.repr == abi::StructRepr::Packed {
+            Layout::from_size_align(field_layout.size(), 1).expect("invalid packed field layout")
+        } else {
+            field_layout
         };
 
         let (new_layout, offset) = self
             .layout
             .extend(field_layout)
             .expect("cannot extend struct layout");
-        self.fields.push((name.into(), ty, offset));
+        self.fields.push((name, ty, offset, default));
         self.layout = new_layout;
         self
     }
@@ -1165,21 +1302,30 @@ impl StructTypeBuilder {
         } else {
             let guid_string = build_struct_guid_string(
                 &self.name,
+                self.repr,
                 self.fields
                     .iter()
-                    .map(|(name, ty, offset)| (name, Cow::Borrowed(ty), *offset)),
+                    .map(|(name, ty, offset, _)| (name, Cow::Borrowed(ty), *offset)),
             );
             abi::Guid::from_str(&guid_string)
         };
 
-        Type::new_struct(
+        // Like in C, the size of a struct is a multiple of its alignment
+        Type::new_struct_with_fields(
             self.name,
-            self.layout,
+            self.layout.pad_to_align(),
             guid,
             self.fields
                 .into_iter()
-                .map(|(name, ty, offset)| (name, ty, offset.try_into().expect("offset too large"))),
+                .map(|(name, ty, offset, default)| FieldData {
+                    name,
+                    type_info: ty.inner,
+                    offset: offset.try_into().expect("offset too large"),
+                    default,
+                })
+                .collect(),
             self.memory_kind,
+            self.repr,
         )
     }
 }
@@ -1188,6 +1334,7 @@ impl StructTypeBuilder {
 /// fields.
 fn build_struct_guid_string<'t, N: AsRef<str> + 't>(
     name: &str,
+    repr: abi::StructRepr,
     fields: impl Iterator<Item = (N, Cow<'t, Type>, usize)>,
 ) -> String {
     let fields: Vec<String> = fields
@@ -1198,7 +1345,10 @@ fn build_struct_guid_string<'t, N: AsRef<str> + 't>(
         .collect();
 
     format!(
-        "struct {name}{{{fields}}}",
+        "{repr}struct {name}{{{fields}}}",
+        repr = repr
+            .attribute()
+            .map_or(String::new(), |attr| format!("{attr} ")),
         name = name,
         fields = fields.join(",")
     )
@@ -1213,12 +1363,14 @@ fn build_type_guid_string(ty: &Type) -> String {
             } else {
                 build_struct_guid_string(
                     ty.name(),
+                    s.repr(),
                     s.fields()
                         .iter()
                         .map(|f| (f.name(), Cow::Owned(f.ty()), f.offset())),
                 )
             }
         }
+        TypeKind::Opaque(_) => format!("extern struct {}", ty.name()),
         TypeKind::Array(_) | TypeKind::Primitive(_) | TypeKind::Pointer(_) => ty.name().to_owned(),
     }
 }
@@ -1228,6 +1380,107 @@ pub trait HasStaticType {
     fn type_info() -> &'static Type;
 }
 
+/// A Rust type that is exposed to Mun as an opaque type. Mun code declares the
+/// type as an `extern struct` with the same name and can only pass around
+/// references to values of the type; its values are created and owned by the
+/// host.
+///
+/// ```rust
+/// # use mun_memory::OpaqueType;
+/// struct Texture {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// impl OpaqueType for Texture {
+///     const NAME: &'static str = "Texture";
+/// }
+///
+/// assert!(Texture::opaque_type_info().is_opaque());
+/// ```
+pub trait OpaqueType: 'static {
+    /// The name of the `extern struct` in Mun
+    const NAME: &'static str;
+
+    /// Returns the type information of the opaque type
+    fn opaque_type_info() -> &'static Type
+    where
+        Self: Sized,
+    {
+        static VALUE: Lazy<StaticTypeMap<Type>> = Lazy::new(StaticTypeMap::default);
+        Lazy::force(&VALUE).call_once::<Self, _>(|| Type::new_opaque(Self::NAME))
+    }
+}
+
+/// A Rust type that mirrors a small `#[repr(C)]` value struct in Mun. Values
+/// of such structs are passed to and returned from public Mun functions by
+/// value, so the host can marshal them without allocating memory.
+///
+/// ```rust
+/// # use mun_memory::{HasStaticType, ReprCStruct, Type};
+/// // #[repr(C)]
+/// // pub struct(value) Vector2 { x: f32, y: f32 }
+/// #[repr(C)]
+/// #[derive(Clone, Copy)]
+/// struct Vector2 {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// unsafe impl ReprCStruct for Vector2 {
+///     const NAME: &'static str = "Vector2";
+///
+///     fn fields() -> Vec<(&'static str, Type)> {
+///         vec![
+///             ("x", f32::type_info().clone()),
+///             ("y", f32::type_info().clone()),
+///         ]
+///     }
+/// }
+///
+/// assert!(Vector2::repr_c_type_info().is_struct());
+/// ```
+///
+/// # Safety
+///
+/// The Rust type must be `#[repr(C)]` and must declare the same fields as the
+/// Mun struct, in the same order and with the same types.
+pub unsafe trait ReprCStruct: Copy + 'static {
+    /// The name of the struct in Mun
+    const NAME: &'static str;
+
+    /// Returns the names and types of the fields of the struct, in the order
+    /// in which they are declared
+    fn fields() -> Vec<(&'static str, Type)>;
+
+    /// Returns the type information of the struct
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layout of the Rust type differs from the layout of the
+    /// fields.
+    fn repr_c_type_info() -> &'static Type
+    where
+        Self: Sized,
+    {
+        static VALUE: Lazy<StaticTypeMap<Type>> = Lazy::new(StaticTypeMap::default);
+        Lazy::force(&VALUE).call_once::<Self, _>(|| {
+            let ty = StructTypeBuilder::new(Self::NAME)
+                .set_memory_kind(abi::StructMemoryKind::Value)
+                .set_repr(abi::StructRepr::C)
+                .add_fields(Self::fields())
+                .finish();
+            assert_eq!(
+                ty.value_layout(),
+                Layout::new::<Self>(),
+                "the layout of `{}` does not match the layout of its fields",
+                std::any::type_name::<Self>()
+            );
+            ty
+        })
+    }
+}
+
 macro_rules! impl_primitive_type {
     ($($ty:ty),+) => {
         $(
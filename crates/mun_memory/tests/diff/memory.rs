@@ -0,0 +1,189 @@
+use mun_memory::{
+    diff::{memory_diff, memory_diff_with_renames, FieldOrigin, FieldRenames},
+    type_table::TypeTable,
+    StructTypeBuilder,
+};
+
+use crate::fake_struct;
+
+#[test]
+fn unchanged() {
+    let type_table = TypeTable::default();
+
+    let struct1 = fake_struct!(type_table, "struct1",
+        "a" => i64, "b" => f64
+    );
+
+    let types = [struct1];
+    let diff = memory_diff(&types, &types);
+    assert!(diff.is_empty());
+    assert!(diff.is_lossless());
+}
+
+#[test]
+fn edit_fields() {
+    let type_table = TypeTable::default();
+
+    let struct1 = fake_struct!(type_table, "struct1",
+        "a" => i64, "b" => f64, "c" => bool
+    );
+    let struct2 = fake_struct!(type_table, "struct1",
+        "a" => i64, "b" => i64, "d" => f32
+    );
+
+    let diff = memory_diff(
+        std::slice::from_ref(&struct1),
+        std::slice::from_ref(&struct2),
+    );
+    assert!(!diff.is_lossless());
+
+    let [struct_diff] = diff.edited.as_slice() else {
+        panic!("expected a single edited struct");
+    };
+    assert_eq!(struct_diff.old_ty, struct1);
+    assert_eq!(struct_diff.new_ty, struct2);
+    assert_eq!(
+        struct_diff
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), &field.origin))
+            .collect::<Vec<_>>(),
+        [
+            (
+                "a",
+                &FieldOrigin::Preserved {
+                    old_name: String::from("a")
+                }
+            ),
+            (
+                "b",
+                &FieldOrigin::Converted {
+                    old_name: String::from("b"),
+                    old_ty: type_table.find_type_info_by_name("core::f64").unwrap(),
+                }
+            ),
+            ("d", &FieldOrigin::Initialized),
+        ]
+    );
+    assert_eq!(struct_diff.dropped_fields, ["c"]);
+
+    assert_eq!(
+        diff.to_string(),
+        "\
+~ struct struct1
+    = a: core::i64
+    ~ b: core::i64 (converted from `b: core::f64`)
+    + d: core::f32 (zero-initialized)
+    - c (dropped)
+"
+    );
+}
+
+#[test]
+fn delete_and_insert_structs() {
+    let type_table = TypeTable::default();
+
+    let struct1 = fake_struct!(type_table, "struct1",
+        "a" => i64, "b" => f64
+    );
+    let struct2 = fake_struct!(type_table, "struct2",
+        "c" => bool
+    );
+    let struct3 = fake_struct!(type_table, "struct3",
+        "d" => f32, "e" => u8
+    );
+
+    let diff = memory_diff(
+        &[struct1.clone(), struct2.clone()],
+        &[struct1, struct3.clone()],
+    );
+    assert!(diff.edited.is_empty());
+    assert_eq!(diff.deleted, [struct2]);
+    assert_eq!(diff.inserted, [struct3]);
+    assert!(!diff.is_lossless());
+
+    assert_eq!(
+        diff.to_string(),
+        "\
+- struct struct2 (allocated objects are dropped)
++ struct struct3
+"
+    );
+}
+
+#[test]
+fn rename_field() {
+    let type_table = TypeTable::default();
+
+    let struct1 = fake_struct!(type_table, "struct1",
+        "a" => i32, "b" => f64
+    );
+    let struct2 = fake_struct!(type_table, "struct1",
+        "c" => i64, "b" => f64
+    );
+
+    let old = std::slice::from_ref(&struct1);
+    let new = std::slice::from_ref(&struct2);
+
+    // The field changed both its name and type, so its value is dropped
+    let diff = memory_diff(old, new);
+    assert_eq!(diff.edited[0].fields[0].origin, FieldOrigin::Initialized);
+    assert_eq!(diff.edited[0].dropped_fields, ["a"]);
+
+    let mut renames = FieldRenames::default();
+    renames.insert("struct1", "a", "c");
+
+    let diff = memory_diff_with_renames(old, new, &renames);
+    assert!(diff.is_lossless());
+    assert_eq!(
+        diff.to_string(),
+        "\
+~ struct struct1
+    ~ c: core::i64 (converted from `a: core::i32`)
+    = b: core::f64
+"
+    );
+}
+
+#[test]
+fn insert_field_with_default() {
+    let type_table = TypeTable::default();
+
+    let struct1 = fake_struct!(type_table, "struct1",
+        "a" => i64
+    );
+    let struct2 = StructTypeBuilder::new("struct1")
+        .add_field("a", type_table.find_type_info_by_name("core::i64").unwrap())
+        .add_field_with_default(
+            "b",
+            type_table.find_type_info_by_name("core::f32").unwrap(),
+            1.5f32.to_ne_bytes(),
+        )
+        .finish();
+
+    assert_eq!(
+        struct2
+            .as_struct()
+            .unwrap()
+            .fields()
+            .get(1)
+            .unwrap()
+            .default_value(),
+        Some(&1.5f32.to_ne_bytes()[..])
+    );
+
+    let diff = memory_diff(
+        std::slice::from_ref(&struct1),
+        std::slice::from_ref(&struct2),
+    );
+    assert!(diff.is_lossless());
+    assert_eq!(diff.edited[0].fields[1].origin, FieldOrigin::Default);
+    assert_eq!(
+        diff.to_string(),
+        "\
+~ struct struct1
+    = a: core::i64
+    + b: core::f32 (initialized to its default)
+"
+    );
+}
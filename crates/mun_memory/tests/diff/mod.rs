@@ -1,3 +1,4 @@
+mod memory;
 mod myers;
 mod structs;
 mod util;
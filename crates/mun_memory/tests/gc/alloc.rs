@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use mun_memory::{
-    gc::{Event, GcRootPtr, GcRuntime, MarkSweep},
+    gc::{Event, GcRootPtr, GcRuntime, HasIndirectionPtr, MarkSweep, RawGcPtr},
     HasStaticType,
 };
 
@@ -34,6 +34,25 @@ fn collect_simple() {
     assert_eq!(events.next(), None);
 }
 
+#[test]
+fn collect_reuses_memory() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default();
+    let mut handle = runtime.alloc(i64::type_info());
+    unsafe { *handle.deref_mut::<i64>() = 42 };
+
+    runtime.collect();
+
+    // The memory of the collected object is reused, and zeroed
+    let reused = runtime.alloc(i64::type_info());
+    assert_eq!(reused, handle);
+    assert_eq!(unsafe { *reused.deref::<i64>() }, 0);
+
+    // Objects with a different layout don't reuse the memory
+    let other = runtime.alloc(bool::type_info());
+    assert_ne!(other, handle);
+    assert_eq!(&runtime.ptr_type(other), bool::type_info());
+}
+
 #[test]
 fn collect_rooted() {
     let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
@@ -68,3 +87,46 @@ fn collect_rooted() {
     assert_eq!(events.next(), Some(Event::End));
     assert_eq!(events.next(), None);
 }
+
+#[test]
+fn collect_with_conservative_roots() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default();
+    let by_handle = runtime.alloc(i64::type_info());
+    let by_pointer = runtime.alloc(i64::type_info());
+    let garbage = runtime.alloc(i64::type_info());
+
+    // Words that are equal to a handle, or that point into the memory of an object,
+    // keep it alive
+    let words = [RawGcPtr::from(by_handle) as usize, unsafe {
+        by_pointer.deref::<i64>() as usize
+    }];
+    assert!(runtime.collect_with_conservative_roots(&words));
+
+    let events = runtime.observer().take_all();
+    assert!(events.contains(&Event::Deallocation(garbage)));
+    assert!(!events.contains(&Event::Deallocation(by_handle)));
+    assert!(!events.contains(&Event::Deallocation(by_pointer)));
+}
+
+#[test]
+fn collect_with_root_regions() {
+    let runtime = MarkSweep::<EventAggregator<Event>>::default();
+    let referenced = runtime.alloc(i64::type_info());
+    let garbage = runtime.alloc(i64::type_info());
+
+    // References that are stored in a root region keep objects alive
+    let region = runtime.alloc_roots(2 * std::mem::size_of::<usize>());
+    unsafe { *region.cast::<usize>().as_ptr() = RawGcPtr::from(referenced) as usize };
+    assert!(runtime.collect());
+
+    let events = runtime.observer().take_all();
+    assert!(events.contains(&Event::Deallocation(garbage)));
+    assert!(!events.contains(&Event::Deallocation(referenced)));
+
+    // Once the region is freed, the object is collected
+    unsafe { runtime.free_roots(region) };
+    assert!(runtime.collect());
+
+    let events = runtime.observer().take_all();
+    assert!(events.contains(&Event::Deallocation(referenced)));
+}
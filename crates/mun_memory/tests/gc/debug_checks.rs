@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use mun_memory::{
+    gc::{Array, Event, GcPtr, GcRootPtr, GcRuntime, HasIndirectionPtr, MarkSweep, RawGcPtr},
+    HasStaticType, StructTypeBuilder,
+};
+
+use super::util::EventAggregator;
+
+struct FooObject {
+    bar: GcPtr,
+}
+
+#[test]
+fn collect_with_debug_checks() {
+    let bar_type_info = i64::type_info().array_type();
+    let foo_type_info = StructTypeBuilder::new("core::Foo")
+        .add_field("bar", bar_type_info.clone())
+        .finish();
+
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default().with_debug_checks(true));
+    let mut foo_ptr = GcRootPtr::new(&runtime, runtime.alloc(&foo_type_info));
+    let bar = runtime.alloc_array(&bar_type_info, 2).as_raw();
+    let garbage = runtime.alloc(i64::type_info());
+
+    unsafe {
+        (*foo_ptr.deref_mut::<FooObject>()).bar = bar;
+    }
+
+    // The heap is valid, so only the unreferenced object is collected
+    assert!(runtime.collect());
+
+    let events = runtime.observer().take_all();
+    assert!(events.contains(&Event::Deallocation(garbage)));
+    assert!(!events.contains(&Event::Deallocation(bar)));
+}
+
+#[test]
+fn collect_uninitialized_reference() {
+    let foo_type_info = StructTypeBuilder::new("core::Foo")
+        .add_field("bar", i64::type_info().array_type())
+        .finish();
+
+    // Mun code has yet to initialize the reference, which is therefore null
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default().with_debug_checks(true));
+    let _foo_ptr = GcRootPtr::new(&runtime, runtime.alloc(&foo_type_info));
+
+    assert!(!runtime.collect());
+}
+
+#[test]
+#[should_panic(expected = "heap corruption")]
+fn debug_checks_detect_dangling_reference() {
+    let foo_type_info = StructTypeBuilder::new("core::Foo")
+        .add_field("bar", i64::type_info().array_type())
+        .finish();
+
+    let runtime = MarkSweep::<EventAggregator<Event>>::default().with_debug_checks(true);
+    let mut foo_handle = runtime.alloc(&foo_type_info);
+
+    // Store a reference to memory that is not managed by the collector
+    let not_an_object = 0u64;
+    unsafe {
+        (*foo_handle.deref_mut::<FooObject>()).bar =
+            (&not_an_object as *const u64 as RawGcPtr).into();
+    }
+
+    runtime.collect();
+}
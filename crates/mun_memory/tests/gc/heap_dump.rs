@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use mun_abi as abi;
+use mun_memory::{
+    gc::{Array, Event, GcPtr, GcRootPtr, GcRuntime, HasIndirectionPtr, HeapReference, MarkSweep},
+    HasStaticType, StructTypeBuilder,
+};
+
+use super::util::EventAggregator;
+
+struct FooObject {
+    bar: GcPtr,
+    baz_bars: GcPtr,
+}
+
+#[test]
+fn dump_heap() {
+    let bar_type_info = i64::type_info().array_type();
+    let baz_type_info = StructTypeBuilder::new("core::Baz")
+        .set_memory_kind(abi::StructMemoryKind::Value)
+        .add_field("bars", bar_type_info.clone())
+        .finish();
+    let foo_type_info = StructTypeBuilder::new("core::Foo")
+        .add_field("bar", bar_type_info.clone())
+        .add_field("baz", baz_type_info)
+        .finish();
+
+    let runtime = Arc::new(MarkSweep::<EventAggregator<Event>>::default());
+    let mut foo_ptr = GcRootPtr::new(&runtime, runtime.alloc(&foo_type_info));
+    let bar = runtime.alloc_array(&bar_type_info, 2).as_raw();
+    let baz_bars = runtime.alloc_array(&bar_type_info, 0).as_raw();
+    let garbage = runtime.alloc(i64::type_info());
+
+    unsafe {
+        let foo_object = &mut *foo_ptr.deref_mut::<FooObject>();
+        foo_object.bar = bar;
+        foo_object.baz_bars = baz_bars;
+    }
+
+    let dump = runtime.dump_heap();
+    assert_eq!(dump.objects().len(), 4);
+
+    let foo_dump = dump.object(foo_ptr.handle()).unwrap();
+    assert_eq!(foo_dump.type_name, "core::Foo");
+    assert_eq!(foo_dump.roots, 1);
+    assert_eq!(
+        foo_dump.references,
+        [
+            HeapReference {
+                field: String::from("bar"),
+                target: dump.object(bar).unwrap().id,
+            },
+            HeapReference {
+                field: String::from("baz.bars"),
+                target: dump.object(baz_bars).unwrap().id,
+            },
+        ]
+    );
+
+    let garbage = dump.object(garbage).unwrap();
+    assert_eq!(
+        dump.unreachable()
+            .map(|object| object.id)
+            .collect::<Vec<_>>(),
+        [garbage.id]
+    );
+    assert_eq!(
+        dump.retainers(dump.object(bar).unwrap().id)
+            .map(|(object, _)| object.id)
+            .collect::<Vec<_>>(),
+        [foo_dump.id]
+    );
+}
@@ -1,4 +1,6 @@
 mod alloc;
+mod debug_checks;
+mod heap_dump;
 mod structs;
 #[macro_use]
 mod util;
@@ -0,0 +1,187 @@
+//! The protocol with which clients, like the runtime, communicate with a
+//! compiler daemon (`mun daemon`).
+//!
+//! The daemon listens on a TCP socket on the loopback interface and writes the
+//! address of the socket, and a randomly generated token, to the
+//! [`DAEMON_FILENAME`] file in the output directory of the package. Messages
+//! are newline-terminated lines of UTF-8 text. Clients send [`Request`]s to the
+//! daemon, the first of which must be [`Request::Authenticate`] with the token.
+//! Whenever the daemon builds the package, because a client requested it or
+//! because a source file changed, it sends [`Notification`]s to all
+//! authenticated clients.
+
+use std::{
+    fmt,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::anyhow;
+
+/// The name of the file in the output directory that contains the
+/// [`DaemonInfo`] of the compiler daemon
+pub const DAEMON_FILENAME: &str = ".mundaemon";
+
+/// Describes how to connect to a running compiler daemon
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DaemonInfo {
+    /// The address on which the daemon listens
+    pub address: SocketAddr,
+    /// The token with which clients authenticate, see
+    /// [`Request::Authenticate`]
+    pub token: String,
+}
+
+/// Returns how to connect to the compiler daemon that built the munlib at
+/// `library_path`, by searching the ancestors of the munlib for a
+/// [`DAEMON_FILENAME`] file. Returns `None` if no daemon is running.
+pub fn find_daemon(library_path: &Path) -> Option<DaemonInfo> {
+    library_path
+        .ancestors()
+        .skip(1)
+        .map(|dir| dir.join(DAEMON_FILENAME))
+        .find(|path| path.is_file())
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|info| info.parse().ok())
+}
+
+/// A request from a client to the compiler daemon
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Request {
+    /// Authenticate with the token of the daemon. The daemon disconnects
+    /// clients whose first request is not a valid authentication.
+    Authenticate(String),
+    /// Build the assemblies whose sources changed
+    Build,
+}
+
+/// A notification from the compiler daemon to its clients
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Notification {
+    /// The munlib at the specified path was written
+    Assembly(PathBuf),
+    /// A build finished. All munlibs that were written during the build have
+    /// been announced.
+    Finished,
+    /// A build failed, because the package contains errors
+    Failed,
+}
+
+impl fmt::Display for DaemonInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.address)?;
+        writeln!(f, "{}", self.token)
+    }
+}
+
+impl FromStr for DaemonInfo {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let (Some(address), Some(token)) = (lines.next(), lines.next()) else {
+            return Err(anyhow!("invalid daemon info: `{s}`"));
+        };
+
+        Ok(DaemonInfo {
+            address: address.parse()?,
+            token: token.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Request::Authenticate(token) => write!(f, "authenticate {token}"),
+            Request::Build => f.write_str("build"),
+        }
+    }
+}
+
+impl FromStr for Request {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim_end() {
+            "build" => Ok(Request::Build),
+            s => s
+                .strip_prefix("authenticate ")
+                .map(|token| Request::Authenticate(token.to_owned()))
+                .ok_or_else(|| anyhow!("invalid request: `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for Notification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Notification::Assembly(path) => write!(f, "assembly {}", path.display()),
+            Notification::Finished => f.write_str("finished"),
+            Notification::Failed => f.write_str("failed"),
+        }
+    }
+}
+
+impl FromStr for Notification {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim_end() {
+            "finished" => Ok(Notification::Finished),
+            "failed" => Ok(Notification::Failed),
+            s => s
+                .strip_prefix("assembly ")
+                .map(|path| Notification::Assembly(PathBuf::from(path)))
+                .ok_or_else(|| anyhow!("invalid notification: `{s}`")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{DaemonInfo, Notification, Request};
+
+    #[test]
+    fn daemon_info_roundtrip() {
+        let info = DaemonInfo {
+            address: "127.0.0.1:4242".parse().unwrap(),
+            token: String::from("0123456789abcdef"),
+        };
+        assert_eq!(info.to_string().parse::<DaemonInfo>().unwrap(), info);
+
+        // Clients cannot authenticate without a token
+        assert!("127.0.0.1:4242".parse::<DaemonInfo>().is_err());
+    }
+
+    #[test]
+    fn request_roundtrip() {
+        assert_eq!(Request::Build.to_string(), "build");
+        assert_eq!("build\n".parse::<Request>().unwrap(), Request::Build);
+        assert_eq!(
+            "authenticate 0123456789abcdef\n"
+                .parse::<Request>()
+                .unwrap(),
+            Request::Authenticate(String::from("0123456789abcdef"))
+        );
+        assert!("rebuild".parse::<Request>().is_err());
+    }
+
+    #[test]
+    fn notification_roundtrip() {
+        let notifications = [
+            Notification::Assembly(PathBuf::from("target/mod with spaces.munlib")),
+            Notification::Finished,
+            Notification::Failed,
+        ];
+        for notification in notifications {
+            let line = format!("{notification}\n");
+            assert_eq!(line.parse::<Notification>().unwrap(), notification);
+        }
+
+        assert!("assembled foo.munlib".parse::<Notification>().is_err());
+    }
+}
@@ -0,0 +1,385 @@
+use std::{fmt::Write, path::Path, str::FromStr};
+
+use anyhow::bail;
+use rustc_hash::FxHashSet;
+use serde_derive::{Deserialize, Serialize};
+
+/// The names of the types that are built into Mun and can be used in an engine
+/// API description without being declared.
+pub const PRIMITIVE_TYPES: &[&str] = &[
+    "bool", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    "f32", "f64",
+];
+
+/// A description of the functions and opaque types that a host (e.g. a game
+/// engine) exposes to Mun code. Usually this information is read from a JSON
+/// or TOML file that is referenced by the `engine-api` key of a mun.toml file.
+///
+/// ```toml
+/// [[types]]
+/// name = "Entity"
+///
+/// [[functions]]
+/// name = "spawn_enemy"
+/// params = [{ name = "health", type = "f32" }]
+/// return_type = "Entity"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EngineApi {
+    /// The opaque types that are owned by the host
+    #[serde(default)]
+    pub types: Vec<EngineType>,
+
+    /// The functions that are provided by the host
+    #[serde(default)]
+    pub functions: Vec<EngineFunction>,
+}
+
+/// An opaque type that is owned by the host.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EngineType {
+    pub name: String,
+}
+
+/// A function that is provided by the host.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EngineFunction {
+    pub name: String,
+    #[serde(default)]
+    pub params: Vec<EngineParam>,
+    #[serde(default)]
+    pub return_type: Option<String>,
+}
+
+/// A parameter of an [`EngineFunction`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EngineParam {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+impl EngineApi {
+    /// Try to read an engine API description from a file. The format of the
+    /// file is determined by its extension, which must either be `json` or
+    /// `toml`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<EngineApi, anyhow::Error> {
+        let path = path.as_ref();
+        let file_contents = std::fs::read_to_string(path).map_err(|e| {
+            anyhow::anyhow!("could not read engine API file '{}': {}", path.display(), e)
+        })?;
+
+        let engine_api = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&file_contents),
+            Some("toml") => Self::from_str(&file_contents),
+            _ => bail!(
+                "engine API file '{}' must either be a JSON or a TOML file",
+                path.display()
+            ),
+        };
+        engine_api.map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+    }
+
+    /// Parses and validates an engine API description from a JSON string.
+    pub fn from_json(s: &str) -> Result<EngineApi, anyhow::Error> {
+        let engine_api = serde_json::from_str::<EngineApi>(s)
+            .map_err(|e| anyhow::anyhow!("could not parse engine API: {e}"))?;
+        engine_api.validate()?;
+        Ok(engine_api)
+    }
+
+    /// Verifies that the names in the description are unique and that all
+    /// types that are referenced are either primitive types or declared
+    /// opaque types.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        let mut type_names = FxHashSet::default();
+        for ty in self.types.iter() {
+            validate_identifier(&ty.name)?;
+            if PRIMITIVE_TYPES.contains(&ty.name.as_str()) {
+                bail!("opaque type `{}` shadows a primitive type", ty.name);
+            }
+            if !type_names.insert(ty.name.as_str()) {
+                bail!("opaque type `{}` is declared more than once", ty.name);
+            }
+        }
+
+        let is_known_type = |ty: &str| PRIMITIVE_TYPES.contains(&ty) || type_names.contains(ty);
+
+        let mut function_names = FxHashSet::default();
+        for function in self.functions.iter() {
+            validate_identifier(&function.name)?;
+            if !function_names.insert(function.name.as_str()) {
+                bail!("function `{}` is declared more than once", function.name);
+            }
+
+            let mut param_names = FxHashSet::default();
+            for param in function.params.iter() {
+                validate_identifier(&param.name)?;
+                if !param_names.insert(param.name.as_str()) {
+                    bail!(
+                        "parameter `{}` of function `{}` is declared more than once",
+                        param.name,
+                        function.name
+                    );
+                }
+                if !is_known_type(&param.ty) {
+                    bail!(
+                        "unknown type `{}` of parameter `{}` of function `{}`",
+                        param.ty,
+                        param.name,
+                        function.name
+                    );
+                }
+            }
+
+            if let Some(return_type) = &function.return_type {
+                if !is_known_type(return_type) {
+                    bail!(
+                        "unknown return type `{}` of function `{}`",
+                        return_type,
+                        function.name
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the name of the module that declares the engine API described
+    /// by the file at `path`, which is the stem of its file name. Both the
+    /// compiler and the language server generate the declarations in a source
+    /// file with this name, e.g. the declarations of `engine_api.toml` end up
+    /// in `package::engine_api`.
+    ///
+    /// `is_keyword` returns whether a name is a reserved keyword of Mun.
+    pub fn module_name(
+        path: &Path,
+        is_keyword: impl Fn(&str) -> bool,
+    ) -> Result<&str, anyhow::Error> {
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| anyhow::anyhow!("invalid engine API file name '{}'", path.display()))
+            .and_then(|name| {
+                validate_identifier(name)?;
+                validate_not_keyword(name, &is_keyword)?;
+                Ok(name)
+            })
+            .map_err(|e| anyhow::anyhow!("invalid engine API file name: {e}"))
+    }
+
+    /// Generates the Mun source code that declares all types and functions of
+    /// the engine API. Opaque types are declared as `extern struct`s, which the
+    /// host has to register with the runtime. `origin` is the name of the
+    /// description file, which is mentioned in a comment.
+    ///
+    /// `is_keyword` returns whether a name is a reserved keyword of Mun.
+    pub fn to_extern_declarations(
+        &self,
+        origin: &str,
+        is_keyword: impl Fn(&str) -> bool,
+    ) -> Result<String, anyhow::Error> {
+        let mut contents =
+            format!("// Generated from `{origin}`. Do not edit this file by hand.\n");
+
+        if !self.types.is_empty() {
+            contents.push('\n');
+        }
+        for ty in self.types.iter() {
+            validate_not_keyword(&ty.name, &is_keyword)?;
+            writeln!(contents, "pub extern struct {};", ty.name)?;
+        }
+
+        for function in self.functions.iter() {
+            validate_not_keyword(&function.name, &is_keyword)?;
+            write!(contents, "\npub extern fn {}(", function.name)?;
+            for (idx, param) in function.params.iter().enumerate() {
+                validate_not_keyword(&param.name, &is_keyword)?;
+                if idx > 0 {
+                    contents.push_str(", ");
+                }
+                write!(contents, "{}: {}", param.name, param.ty)?;
+            }
+            contents.push(')');
+            if let Some(return_type) = &function.return_type {
+                write!(contents, " -> {return_type}")?;
+            }
+            contents.push_str(";\n");
+        }
+
+        Ok(contents)
+    }
+}
+
+impl FromStr for EngineApi {
+    type Err = anyhow::Error;
+
+    /// Parses and validates an engine API description from a TOML string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let engine_api = ::toml::from_str::<EngineApi>(s)
+            .map_err(|e| anyhow::anyhow!("could not parse engine API: {e}"))?;
+        engine_api.validate()?;
+        Ok(engine_api)
+    }
+}
+
+/// Ensures that `name` is a valid identifier.
+fn validate_identifier(name: &str) -> Result<(), anyhow::Error> {
+    let mut chars = name.chars();
+    let is_valid = match chars.next() {
+        Some(c) if c == '_' || c.is_ascii_alphabetic() => {
+            chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+        }
+        _ => false,
+    };
+    if !is_valid {
+        bail!("`{name}` is not a valid identifier");
+    }
+    Ok(())
+}
+
+/// Ensures that `name` is not a reserved keyword.
+fn validate_not_keyword(
+    name: &str,
+    is_keyword: impl Fn(&str) -> bool,
+) -> Result<(), anyhow::Error> {
+    if is_keyword(name) {
+        bail!("`{name}` is a reserved keyword");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, str::FromStr};
+
+    use super::EngineApi;
+
+    #[test]
+    fn parse_toml() {
+        let engine_api = EngineApi::from_str(
+            r#"
+        [[types]]
+        name = "Entity"
+
+        [[functions]]
+        name = "spawn_enemy"
+        params = [{ name = "health", type = "f32" }]
+        return_type = "Entity"
+
+        [[functions]]
+        name = "despawn"
+        params = [{ name = "entity", type = "Entity" }]
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(engine_api.types[0].name, "Entity");
+        assert_eq!(engine_api.functions.len(), 2);
+        assert_eq!(engine_api.functions[0].params[0].ty, "f32");
+        assert_eq!(
+            engine_api.functions[0].return_type.as_deref(),
+            Some("Entity")
+        );
+        assert_eq!(engine_api.functions[1].return_type, None);
+    }
+
+    #[test]
+    fn parse_json() {
+        let engine_api = EngineApi::from_json(
+            r#"{
+            "functions": [
+                { "name": "now", "return_type": "f64" }
+            ]
+        }"#,
+        )
+        .unwrap();
+
+        assert!(engine_api.types.is_empty());
+        assert_eq!(engine_api.functions[0].name, "now");
+        assert!(engine_api.functions[0].params.is_empty());
+    }
+
+    #[test]
+    fn validate() {
+        let err = |s: &str| EngineApi::from_json(s).unwrap_err().to_string();
+
+        assert_eq!(
+            err(r#"{ "functions": [{ "name": "foo", "return_type": "Entity" }] }"#),
+            "unknown return type `Entity` of function `foo`"
+        );
+        assert_eq!(
+            err(r#"{ "functions": [{ "name": "foo" }, { "name": "foo" }] }"#),
+            "function `foo` is declared more than once"
+        );
+        assert_eq!(
+            err(r#"{ "types": [{ "name": "f32" }] }"#),
+            "opaque type `f32` shadows a primitive type"
+        );
+        assert_eq!(
+            err(
+                r#"{ "functions": [{ "name": "foo", "params": [{ "name": "a b", "type": "f32" }] }] }"#
+            ),
+            "`a b` is not a valid identifier"
+        );
+        assert!(err(r#"{ "function": [] }"#).starts_with("could not parse engine API"));
+    }
+
+    #[test]
+    fn extern_declarations() {
+        let is_keyword = |name: &str| name == "loop";
+
+        let engine_api = EngineApi::from_json(
+            r#"{
+            "types": [{ "name": "Entity" }],
+            "functions": [
+                { "name": "spawn_enemy", "params": [{ "name": "health", "type": "f32" }], "return_type": "Entity" },
+                { "name": "move_to", "params": [{ "name": "entity", "type": "Entity" }, { "name": "x", "type": "f32" }] }
+            ]
+        }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            engine_api
+                .to_extern_declarations("engine_api.json", is_keyword)
+                .unwrap(),
+            "// Generated from `engine_api.json`. Do not edit this file by hand.\n\
+            \n\
+            pub extern struct Entity;\n\
+            \n\
+            pub extern fn spawn_enemy(health: f32) -> Entity;\n\
+            \n\
+            pub extern fn move_to(entity: Entity, x: f32);\n"
+        );
+
+        let engine_api = EngineApi::from_json(r#"{ "functions": [{ "name": "loop" }] }"#).unwrap();
+        assert_eq!(
+            engine_api
+                .to_extern_declarations("engine_api.json", is_keyword)
+                .unwrap_err()
+                .to_string(),
+            "`loop` is a reserved keyword"
+        );
+    }
+
+    #[test]
+    fn module_name() {
+        let is_keyword = |name: &str| name == "loop";
+
+        assert_eq!(
+            EngineApi::module_name(Path::new("/foo/engine_api.toml"), is_keyword).unwrap(),
+            "engine_api"
+        );
+        assert_eq!(
+            EngineApi::module_name(Path::new("/foo/loop.toml"), is_keyword)
+                .unwrap_err()
+                .to_string(),
+            "invalid engine API file name: `loop` is a reserved keyword"
+        );
+    }
+}
@@ -1,7 +1,10 @@
-pub use manifest::{Manifest, ManifestMetadata, PackageId};
+pub use engine_api::{EngineApi, EngineFunction, EngineParam, EngineType, PRIMITIVE_TYPES};
+pub use manifest::{Dependency, Manifest, ManifestMetadata, PackageId};
 pub use package::Package;
 pub use project_manifest::ProjectManifest;
 
+pub mod daemon;
+mod engine_api;
 mod manifest;
 mod package;
 mod project_manifest;
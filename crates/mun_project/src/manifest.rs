@@ -1,4 +1,8 @@
-use std::{fmt, path::Path, str::FromStr};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 mod toml;
 
@@ -8,6 +12,12 @@ mod toml;
 pub struct Manifest {
     package_id: PackageId,
     metadata: ManifestMetadata,
+    engine_api: Option<PathBuf>,
+    mun_version: Option<semver::VersionReq>,
+    overflow_checks: Option<bool>,
+    opt_level: Option<u8>,
+    lto: Option<bool>,
+    dependencies: Vec<Dependency>,
 }
 
 /// General metadata for a package.
@@ -16,6 +26,13 @@ pub struct ManifestMetadata {
     pub authors: Vec<String>,
 }
 
+/// A package that another package depends on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dependency {
+    name: String,
+    path: PathBuf,
+}
+
 /// Unique identifier of a package and version
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PackageId {
@@ -52,6 +69,54 @@ impl Manifest {
     pub fn metadata(&self) -> &ManifestMetadata {
         &self.metadata
     }
+
+    /// Returns the path of the engine API description of the package, relative
+    /// to the root of the package.
+    pub fn engine_api(&self) -> Option<&Path> {
+        self.engine_api.as_deref()
+    }
+
+    /// Returns the versions of the Mun toolchain that can build the package,
+    /// if the package restricts them.
+    pub fn mun_version(&self) -> Option<&semver::VersionReq> {
+        self.mun_version.as_ref()
+    }
+
+    /// Returns whether integer arithmetic of the package checks for overflow,
+    /// if the package specifies it.
+    pub fn overflow_checks(&self) -> Option<bool> {
+        self.overflow_checks
+    }
+
+    /// Returns the optimization level with which the package is built, from 0
+    /// to 3, if the package specifies it.
+    pub fn opt_level(&self) -> Option<u8> {
+        self.opt_level
+    }
+
+    /// Returns whether each assembly of the package is optimized as a whole
+    /// after its modules are linked, if the package specifies it.
+    pub fn lto(&self) -> Option<bool> {
+        self.lto
+    }
+
+    /// Returns the packages this package depends on, sorted by name
+    pub fn dependencies(&self) -> &[Dependency] {
+        &self.dependencies
+    }
+}
+
+impl Dependency {
+    /// Returns the name by which the package refers to the dependency
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path of the root of the dependency, relative to the root of
+    /// the package that depends on it.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
 }
 
 impl PackageId {
@@ -108,5 +173,133 @@ mod tests {
         );
         assert_eq!(manifest.metadata().authors, vec!["Mun Team"]);
         assert_eq!(format!("{}", manifest.package_id()), "test v0.2.0");
+        assert_eq!(manifest.engine_api(), None);
+        assert_eq!(manifest.mun_version(), None);
+        assert_eq!(manifest.overflow_checks(), None);
+        assert_eq!(manifest.opt_level(), None);
+        assert_eq!(manifest.lto(), None);
+        assert!(manifest.dependencies().is_empty());
+    }
+
+    #[test]
+    fn parse_engine_api() {
+        let manifest = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+        engine-api = "engine_api.json"
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.engine_api(),
+            Some(std::path::Path::new("engine_api.json"))
+        );
+    }
+
+    #[test]
+    fn parse_mun_version() {
+        let manifest = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+        mun-version="0.6"
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.mun_version(),
+            Some(&semver::VersionReq::parse("0.6").unwrap())
+        );
+    }
+
+    #[test]
+    fn parse_overflow_checks() {
+        let manifest = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+        overflow-checks=true
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.overflow_checks(), Some(true));
+    }
+
+    #[test]
+    fn parse_optimization() {
+        let manifest = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+        opt-level=3
+        lto=true
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.opt_level(), Some(3));
+        assert_eq!(manifest.lto(), Some(true));
+
+        assert!(Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+        opt-level=4
+        "#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parse_dependencies() {
+        let manifest = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+
+        [dependencies]
+        physics = { path = "../physics" }
+        ai_utils = { path = "libs/ai" }
+        "#,
+        )
+        .unwrap();
+
+        let dependencies = manifest
+            .dependencies()
+            .iter()
+            .map(|dependency| (dependency.name(), dependency.path()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dependencies,
+            vec![
+                ("ai_utils", std::path::Path::new("libs/ai")),
+                ("physics", std::path::Path::new("../physics")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_invalid_dependencies() {
+        for dependencies in [
+            r#"my-lib = { path = "../my-lib" }"#,
+            r#"core = { path = "../core" }"#,
+            r#"std = { path = "../std" }"#,
+            r#"physics = { version = "0.1.0" }"#,
+        ] {
+            let manifest = Manifest::from_str(&format!(
+                "[package]\nname=\"test\"\nversion=\"0.2.0\"\n\n[dependencies]\n{dependencies}\n"
+            ));
+            assert!(manifest.is_err(), "{dependencies}");
+        }
     }
 }
@@ -1,20 +1,37 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
 use serde_derive::{Deserialize, Serialize};
 
-use super::{Manifest, ManifestMetadata, PackageId};
+use super::{Dependency, Manifest, ManifestMetadata, PackageId};
 
 /// A manifest as specified in a mun.toml file.
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct TomlManifest {
     package: TomlProject,
+    #[serde(default)]
+    dependencies: BTreeMap<String, TomlDependency>,
 }
 
 /// Represents the `package` section of a mun.toml file.
 #[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
 pub struct TomlProject {
     name: String,
     version: semver::Version,
     authors: Option<Vec<String>>,
+    engine_api: Option<PathBuf>,
+    mun_version: Option<semver::VersionReq>,
+    overflow_checks: Option<bool>,
+    opt_level: Option<u8>,
+    lto: Option<bool>,
+}
+
+/// Represents a single entry in the `dependencies` section of a mun.toml file.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct TomlDependency {
+    path: PathBuf,
 }
 
 impl TomlManifest {
@@ -25,6 +42,32 @@ impl TomlManifest {
             anyhow::bail!("package name cannot be an empty string");
         }
 
+        if let Some(opt_level) = self.package.opt_level {
+            if opt_level > 3 {
+                anyhow::bail!("invalid opt-level {opt_level}: only levels 0-3 are supported");
+            }
+        }
+
+        let dependencies = self
+            .dependencies
+            .into_iter()
+            .map(|(name, dependency)| {
+                if !is_valid_dependency_name(&name) {
+                    anyhow::bail!(
+                        "invalid dependency name '{name}': dependency names must be valid \
+                         identifiers"
+                    );
+                }
+                if RESERVED_DEPENDENCY_NAMES.contains(&name.as_str()) {
+                    anyhow::bail!("invalid dependency name '{name}': the name is reserved");
+                }
+                Ok(Dependency {
+                    name,
+                    path: dependency.path,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
         Ok(Manifest {
             package_id: PackageId {
                 name: name.to_owned(),
@@ -33,6 +76,24 @@ impl TomlManifest {
             metadata: ManifestMetadata {
                 authors: self.package.authors.unwrap_or_default(),
             },
+            engine_api: self.package.engine_api,
+            mun_version: self.package.mun_version,
+            overflow_checks: self.package.overflow_checks,
+            opt_level: self.package.opt_level,
+            lto: self.package.lto,
+            dependencies,
         })
     }
 }
+
+/// The names of the packages that are compiled together with every package.
+const RESERVED_DEPENDENCY_NAMES: &[&str] = &["core", "std"];
+
+/// Returns true if `name` can be used to refer to a dependency from Mun code.
+fn is_valid_dependency_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
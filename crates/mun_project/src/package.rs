@@ -5,7 +5,7 @@ use std::{
 
 use semver::Version;
 
-use crate::{Manifest, PackageId};
+use crate::{Manifest, PackageId, MANIFEST_FILENAME};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Package {
@@ -65,6 +65,47 @@ impl Package {
     pub fn source_directory(&self) -> PathBuf {
         self.root().join("src")
     }
+
+    /// Returns the path to the engine API description of the package, if the
+    /// package has one
+    pub fn engine_api_path(&self) -> Option<PathBuf> {
+        self.manifest()
+            .engine_api()
+            .map(|engine_api| self.root().join(engine_api))
+    }
+
+    /// Verifies that version `toolchain` of the Mun toolchain can build the
+    /// package, as required by the `mun-version` field of its manifest.
+    /// Pre-releases of the toolchain satisfy the requirements of the
+    /// corresponding release, e.g. `0.6.0-dev` satisfies `0.6`.
+    pub fn check_mun_version(&self, toolchain: &Version) -> anyhow::Result<()> {
+        let Some(required) = self.manifest().mun_version() else {
+            return Ok(());
+        };
+
+        let release = Version::new(toolchain.major, toolchain.minor, toolchain.patch);
+        if required.matches(&release) {
+            return Ok(());
+        }
+
+        anyhow::bail!(
+            "package '{}' requires Mun {required}, but the toolchain is Mun {toolchain}. Install a \
+             matching toolchain, or change `mun-version` in '{}'",
+            self.name(),
+            self.manifest_path().display()
+        )
+    }
+
+    /// Returns the names of the dependencies of the package together with the
+    /// paths of their manifests
+    pub fn dependency_manifest_paths(&self) -> impl Iterator<Item = (&str, PathBuf)> + '_ {
+        self.manifest().dependencies().iter().map(|dependency| {
+            (
+                dependency.name(),
+                self.root().join(dependency.path()).join(MANIFEST_FILENAME),
+            )
+        })
+    }
 }
 
 impl fmt::Display for Package {
@@ -72,3 +113,42 @@ impl fmt::Display for Package {
         write!(f, "{}", self.package_id())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, str::FromStr};
+
+    use semver::Version;
+
+    use crate::{Manifest, Package};
+
+    #[test]
+    fn check_mun_version() {
+        let manifest = Manifest::from_str(
+            r#"
+        [package]
+        name="test"
+        version="0.2.0"
+        mun-version="0.6"
+        "#,
+        )
+        .unwrap();
+        let package = Package::new(manifest, Path::new("/test/mun.toml"));
+
+        assert!(package
+            .check_mun_version(&Version::parse("0.6.2").unwrap())
+            .is_ok());
+        assert!(package
+            .check_mun_version(&Version::parse("0.6.0-dev").unwrap())
+            .is_ok());
+
+        let error = package
+            .check_mun_version(&Version::parse("0.5.0").unwrap())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "package 'test' requires Mun ^0.6, but the toolchain is Mun 0.5.0. Install a matching \
+             toolchain, or change `mun-version` in '/test/mun.toml'"
+        );
+    }
+}
@@ -5,7 +5,7 @@ use std::{
 
 use mun_memory::{
     gc::{GcPtr, GcRuntime, HasIndirectionPtr},
-    Type,
+    ReprCStruct, Type,
 };
 
 use crate::{
@@ -302,3 +302,84 @@ impl RootedStruct {
         StructRef::new(RawStruct(self.handle.handle()), runtime)
     }
 }
+
+/// A value of a small `#[repr(C)]` value struct, mirrored by the Rust type `T`.
+///
+/// Mun functions pass such structs by value, following the C calling
+/// convention of the target, so marshalling a `StructValue` never allocates
+/// memory. Whether a struct is passed by value is decided by the compiler, see
+/// [`StructType::is_passed_by_value`](crate::StructType::is_passed_by_value).
+/// Other structs are marshalled as a [`StructRef`].
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StructValue<T: ReprCStruct>(pub T);
+
+impl<T: ReprCStruct> StructValue<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: ReprCStruct> From<T> for StructValue<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: ReprCStruct> ArgumentReflection for StructValue<T> {
+    fn type_info(&self, _runtime: &Runtime) -> Type {
+        T::repr_c_type_info().clone()
+    }
+
+    fn static_type_info() -> Option<Type> {
+        Some(T::repr_c_type_info().clone())
+    }
+
+    const PASSED_BY_VALUE: bool = true;
+}
+
+impl<T: ReprCStruct> ReturnTypeReflection for StructValue<T> {
+    fn accepts_type(ty: &Type) -> bool {
+        T::repr_c_type_info() == ty
+    }
+
+    fn type_hint() -> &'static str {
+        T::NAME
+    }
+
+    const PASSED_BY_VALUE: bool = true;
+}
+
+impl<'t, T: ReprCStruct> Marshal<'t> for StructValue<T> {
+    type MunType = T;
+
+    fn marshal_from<'r>(value: Self::MunType, _runtime: &'r Runtime) -> Self
+    where
+        Self: 't,
+        'r: 't,
+    {
+        Self(value)
+    }
+
+    fn marshal_into(self) -> Self::MunType {
+        self.0
+    }
+
+    fn marshal_from_ptr<'r>(
+        ptr: NonNull<Self::MunType>,
+        _runtime: &'r Runtime,
+        _type_info: &Type,
+    ) -> Self
+    where
+        Self: 't,
+        'r: 't,
+    {
+        // The struct might be a field of a packed struct
+        Self(unsafe { ptr.as_ptr().read_unaligned() })
+    }
+
+    fn marshal_to_ptr(value: Self, ptr: NonNull<Self::MunType>, _type_info: &Type) {
+        unsafe { ptr.as_ptr().write_unaligned(value.0) };
+    }
+}
@@ -9,28 +9,80 @@ use std::{
 use itertools::Itertools;
 use log::error;
 use mun_abi as abi;
-use mun_libloader::{MunLibrary, TempLibrary};
+use mun_libloader::{
+    integrity::{self, IntegrityError, IntegrityPolicy},
+    MunLibrary, TempLibrary,
+};
 use mun_memory::{
+    diff::FieldRenames,
     mapping::{Mapping, MemoryMapper},
     type_table::TypeTable,
     Type,
 };
 
-use crate::{garbage_collector::GarbageCollector, DispatchTable};
+use crate::{
+    garbage_collector::GarbageCollector, profiler, DispatchTable, FunctionDefinition, ReloadReport,
+};
 
 /// An error that occurs upon loading of a Mun library.
 #[derive(Debug, thiserror::Error)]
 pub enum LoadError {
+    /// An assembly with the same path was already loaded
     #[error("An assembly with the same name already exists")]
     AlreadyExists,
+    /// No assembly with the specified path was loaded
+    #[error("No assembly was loaded from `{}`", .0.display())]
+    NotLoaded(PathBuf),
+    /// Failed to load the shared library
     #[error(transparent)]
     FailedToLoadSharedLibrary(#[from] mun_libloader::InitError),
-    #[error("ABI version mismatch. munlib is `{actual}` but runtime is `{expected}`")]
-    MismatchedAbiVersions { expected: u32, actual: u32 },
+    /// Failed to read the metadata of the munlib without loading it
+    #[error(transparent)]
+    FailedToInspectLibrary(#[from] mun_libloader::ImageError),
+    /// The munlib was compiled for a different ABI version
+    #[error(
+        "ABI version mismatch. munlib is `{actual}` but runtime supports `{}` through `{expected}`{}",
+        abi::MIN_ABI_VERSION,
+        toolchain_mismatch_hint(.compiler_version.as_deref())
+    )]
+    MismatchedAbiVersions {
+        /// The newest ABI version supported by the runtime
+        expected: u32,
+        /// The ABI version of the munlib
+        actual: u32,
+        /// The version of the compiler that generated the munlib, if it is
+        /// recorded in the munlib
+        compiler_version: Option<String>,
+    },
+    /// The munlib failed the integrity checks that the runtime requires
+    #[error("Integrity check of `{}` failed: {error}", .library_path.display())]
+    FailedIntegrityCheck {
+        /// The path of the munlib
+        library_path: PathBuf,
+        /// The reason why the check failed
+        #[source]
+        error: IntegrityError,
+    },
+    /// The munlib makes use of ABI features that the runtime does not support
+    #[error("munlib uses unsupported ABI capabilities `{0:?}`")]
+    UnsupportedCapabilities(abi::Capabilities),
+    /// An I/O error occurred
     #[error(transparent)]
     Other(#[from] io::Error),
 }
 
+/// Describes which version of the Mun toolchain generated a munlib compared to
+/// the version of the runtime, if the munlib records it.
+pub(crate) fn toolchain_mismatch_hint(compiler_version: Option<&str>) -> String {
+    compiler_version.map_or_else(String::new, |compiler_version| {
+        format!(
+            ". The munlib was compiled by Mun {compiler_version}, but the runtime is Mun {}; \
+             recompile it with a matching toolchain",
+            env!("CARGO_PKG_VERSION")
+        )
+    })
+}
+
 /// An error that occurs upon linking of a Mun assembly.
 #[derive(Debug, thiserror::Error)]
 pub enum LinkError {
@@ -85,12 +137,39 @@ pub enum LinkFunctionsError {
     },
 }
 
+/// Describes an assembly that is linked by a [`Runtime`](crate::Runtime).
+#[derive(Clone)]
+pub struct AssemblyMetadata {
+    /// The path of the shared library that the assembly was loaded from
+    pub library_path: PathBuf,
+    /// The path of the assembly's module
+    pub module_path: String,
+    /// The ABI version that the assembly was compiled for
+    pub abi_version: u32,
+    /// The optional ABI features that the assembly makes use of
+    pub capabilities: abi::Capabilities,
+    /// The version of the compiler that generated the assembly, if it is
+    /// recorded in the assembly
+    pub compiler_version: Option<String>,
+    /// The functions exported by the assembly, sorted by name
+    pub functions: Vec<Arc<FunctionDefinition>>,
+    /// The types exported by the assembly, sorted by name
+    pub types: Vec<Type>,
+    /// The names of the assemblies that the assembly depends on
+    pub dependencies: Vec<String>,
+    /// The names of the functions called by the assembly that are not provided
+    /// by any linked assembly or the host, sorted by name
+    pub unresolved_functions: Vec<String>,
+}
+
 /// An assembly is a hot reloadable compilation unit, consisting of one or more
 /// Mun modules.
 pub struct Assembly {
     library_path: PathBuf,
     library: TempLibrary,
     info: abi::AssemblyInfo<'static>,
+    abi_version: u32,
+    compiler_version: Option<String>,
     allocator: Arc<GarbageCollector>,
 }
 
@@ -98,6 +177,9 @@ impl Assembly {
     /// Loads an assembly and its information for the shared library at
     /// `library_path`. The resulting `Assembly` is ensured to be linkable.
     ///
+    /// If an `integrity` policy is specified, the shared library is verified
+    /// before it is loaded.
+    ///
     /// # Safety
     ///
     /// A munlib is simply a shared object. When a library is loaded,
@@ -112,22 +194,87 @@ impl Assembly {
     /// unloaded.
     ///
     /// See [`libloading::Library::new`] for more information.
-    pub unsafe fn load(library_path: &Path, gc: Arc<GarbageCollector>) -> Result<Self, LoadError> {
-        let mut library = MunLibrary::new(library_path)?;
+    pub unsafe fn load(
+        library_path: &Path,
+        gc: Arc<GarbageCollector>,
+        integrity: Option<&IntegrityPolicy>,
+    ) -> Result<Self, LoadError> {
+        if integrity.is_some() {
+            // Load the verified contents, so the file cannot be replaced in between
+            let bytes = std::fs::read(library_path)?;
+            return Self::load_from_memory(library_path, &bytes, gc, integrity);
+        }
+
+        let library = MunLibrary::new(library_path)?;
+        Self::from_library(library_path, library, gc)
+    }
+
+    /// Loads an assembly from the contents of its shared library in memory,
+    /// e.g. after streaming it over the network or unpacking it from an
+    /// archive. On Linux the shared library is loaded from an anonymous
+    /// in-memory file, elsewhere it is written to a temporary file first.
+    ///
+    /// The assembly is identified by `library_path`, the location that the
+    /// shared library would have on disk, which does not need to exist. The
+    /// dependencies of the assembly are resolved relative to it.
+    ///
+    /// If an `integrity` policy is specified, the contents are verified before
+    /// they are loaded.
+    ///
+    /// # Safety
+    ///
+    /// See [`Assembly::load`].
+    pub unsafe fn load_from_memory(
+        library_path: &Path,
+        bytes: &[u8],
+        gc: Arc<GarbageCollector>,
+        integrity: Option<&IntegrityPolicy>,
+    ) -> Result<Self, LoadError> {
+        if let Some(policy) = integrity {
+            integrity::verify(bytes, policy).map_err(|error| LoadError::FailedIntegrityCheck {
+                library_path: library_path.to_path_buf(),
+                error,
+            })?;
+        }
+
+        let library = MunLibrary::from_bytes(bytes)?;
+        Self::from_library(library_path, library, gc)
+    }
+
+    /// Constructs an assembly from a loaded library, after verifying that its
+    /// ABI version and capabilities are supported by the runtime.
+    unsafe fn from_library(
+        library_path: &Path,
+        mut library: MunLibrary,
+        gc: Arc<GarbageCollector>,
+    ) -> Result<Self, LoadError> {
+        // The names of the functions of the library may reuse the addresses of names
+        // of libraries that were unloaded
+        profiler::forget_function_names();
 
         let version = library.get_abi_version();
-        if abi::ABI_VERSION != version {
+        let compiler_version = library.get_compiler_version();
+        if !abi::is_compatible_abi_version(version) {
             return Err(LoadError::MismatchedAbiVersions {
                 expected: abi::ABI_VERSION,
                 actual: version,
+                compiler_version,
             });
         }
 
+        let info = library.get_info();
+        let unsupported_capabilities = info.unsupported_capabilities();
+        if !unsupported_capabilities.is_empty() {
+            return Err(LoadError::UnsupportedCapabilities(unsupported_capabilities));
+        }
+
         let allocator_ptr = Arc::into_raw(gc.clone()) as *mut std::ffi::c_void;
         library.set_allocator_handle(allocator_ptr);
 
         let assembly = Assembly {
-            info: library.get_info(),
+            info,
+            abi_version: version,
+            compiler_version,
             library_path: library_path.to_path_buf(),
             library: library.into_inner(),
             allocator: gc,
@@ -203,7 +350,7 @@ impl Assembly {
                     })?;
 
                 // Ensure that the function is in the runtime dispatch table
-                if let Some(existing_fn_def) = dispatch_table.get_fn(fn_prototype.name()) {
+                if let Some(existing_fn_def) = dispatch_table.get_linked_fn(fn_prototype.name()) {
                     if fn_proto_arg_type_infos != existing_fn_def.prototype.signature.arg_types
                         || fn_proto_ret_type_info != existing_fn_def.prototype.signature.return_type
                     {
@@ -271,6 +418,9 @@ impl Assembly {
             assemblies
                 .iter()
                 .flat_map(|asm| asm.info().symbols.types().iter()),
+            assemblies
+                .iter()
+                .flat_map(|asm| asm.info().field_defaults().iter()),
             type_table.clone(),
         )
         .map_err(|e| LinkError::LoadType(e.to_string()))?;
@@ -289,7 +439,7 @@ impl Assembly {
 
         // Insert all assemblies' functions into the dispatch table
         for assembly in assemblies.iter() {
-            dispatch_table.insert_module(&assembly.info().symbols, &type_table);
+            dispatch_table.insert_module(assembly.info(), &type_table);
         }
 
         let functions_to_link = assemblies
@@ -310,11 +460,17 @@ impl Assembly {
     /// Tries to link the `unlinked_assemblies`, resulting in a new
     /// [`DispatchTable`] on success. This leaves the original
     /// `dispatch_table` intact, in case of linking errors.
+    ///
+    /// The values of fields that were renamed according to the `field_renames`
+    /// are preserved. The types that were remapped and the objects that were
+    /// migrated are recorded in the `report`.
     pub(super) fn relink_all(
         unlinked_assemblies: &mut HashMap<PathBuf, Assembly>,
         linked_assemblies: &mut HashMap<PathBuf, Assembly>,
         dispatch_table: &DispatchTable,
         type_table: &TypeTable,
+        field_renames: &FieldRenames,
+        report: &mut ReloadReport,
     ) -> Result<(DispatchTable, TypeTable), LinkError> {
         let mut dependencies: HashMap<String, Vec<String>> = unlinked_assemblies
             .values()
@@ -369,9 +525,12 @@ impl Assembly {
             });
 
             // Collect all types that need to be loaded
-            let (updated_type_table, new_types) =
-                Type::try_from_abi(new_assembly.info.symbols.types(), type_table)
-                    .map_err(|e| LinkError::LoadType(e.to_string()))?;
+            let (updated_type_table, new_types) = Type::try_from_abi(
+                new_assembly.info.symbols.types(),
+                new_assembly.info.field_defaults(),
+                type_table,
+            )
+            .map_err(|e| LinkError::LoadType(e.to_string()))?;
             type_table = updated_type_table;
 
             // Load all types, retrying types that depend on other unloaded types within the
@@ -389,19 +548,31 @@ impl Assembly {
 
             // Memory map allocated object
             if let Some((old_assembly, old_types)) = old_types {
-                let mapping = Mapping::new(&old_types, &new_types);
-                let _deleted_objects = old_assembly.allocator.map_memory(mapping);
+                let mapping = Mapping::with_renames(&old_types, &new_types, field_renames);
+                report.remapped_types.extend(
+                    mapping
+                        .struct_mappings
+                        .keys()
+                        .map(|ty| ty.name().to_owned()),
+                );
+                report
+                    .deleted_types
+                    .extend(mapping.deletions.iter().map(|ty| ty.name().to_owned()));
+
+                let mapped_objects = old_assembly.allocator.map_memory(mapping);
+                report.migrated_objects += mapped_objects.migrated.len();
+                report.deleted_objects += mapped_objects.deleted.len();
                 // DISCUSSION: Do we need to maintain an assembly for the type
                 // LUT of allocated objects with deleted types?
             }
 
             // Remove the old assembly's functions from the dispatch table
             if let Some(old_assembly) = old_assembly {
-                dispatch_table.remove_module(&old_assembly.info.symbols);
+                dispatch_table.remove_module(&old_assembly.info);
             }
 
             // Insert the new assembly's functions into the dispatch table
-            dispatch_table.insert_module(&new_assembly.info.symbols, &type_table);
+            dispatch_table.insert_module(&new_assembly.info, &type_table);
 
             let functions_to_link = new_assembly
                 .info_mut()
@@ -431,10 +602,174 @@ impl Assembly {
             dependencies.retain(|_, dependencies| !dependencies.is_empty());
         }
 
-        let mut newly_linked = HashMap::new();
-        std::mem::swap(unlinked_assemblies, &mut newly_linked);
+        Assembly::replace_linked(unlinked_assemblies, linked_assemblies);
+
+        report.remapped_types.sort();
+        report.deleted_types.sort();
 
-        for (old_path, new_assembly) in newly_linked {
+        // Collect types
+        Type::collect_unreferenced_type_data();
+
+        Ok((dispatch_table, type_table))
+    }
+
+    /// Returns true if the `unlinked_assemblies` can replace the assemblies
+    /// they are associated with by [patching](Assembly::patch_all) function
+    /// pointers. This is the case if every unlinked assembly replaces an
+    /// already linked assembly that defines exactly the same types, field
+    /// defaults and function prototypes, i.e. only the bodies of its functions
+    /// changed. Any other difference requires
+    /// [relinking](Assembly::relink_all).
+    pub(super) fn is_patchable(
+        unlinked_assemblies: &HashMap<PathBuf, Assembly>,
+        linked_assemblies: &HashMap<PathBuf, Assembly>,
+    ) -> bool {
+        unlinked_assemblies.iter().all(|(old_path, new_assembly)| {
+            let Some(old_assembly) = linked_assemblies.get(old_path) else {
+                return false;
+            };
+
+            let old_symbols = &old_assembly.info.symbols;
+            let new_symbols = &new_assembly.info.symbols;
+            if old_symbols.path() != new_symbols.path()
+                || old_symbols.types().len() != new_symbols.types().len()
+            {
+                return false;
+            }
+
+            // `TypeDefinition` equality only compares the guid of structs, which does not
+            // cover every property that affects how values are stored and marshalled.
+            let same_types = new_symbols.types().iter().all(|new_type| {
+                old_symbols
+                    .types()
+                    .iter()
+                    .any(|old_type| is_same_type_definition(old_type, new_type))
+            }) && is_same_field_defaults(
+                old_assembly.info.field_defaults(),
+                new_assembly.info.field_defaults(),
+            );
+
+            let same_functions =
+                |old_fns: &[abi::FunctionDefinition<'_>],
+                 new_fns: &[abi::FunctionDefinition<'_>]| {
+                    old_fns.len() == new_fns.len()
+                        && new_fns.iter().all(|new_fn| {
+                            old_fns.iter().any(|old_fn| {
+                                old_fn.prototype.name() == new_fn.prototype.name()
+                                    && old_fn.prototype.signature == new_fn.prototype.signature
+                            })
+                        })
+                };
+
+            same_types
+                && same_functions(old_symbols.functions(), new_symbols.functions())
+                && same_functions(
+                    old_assembly.info.private_functions(),
+                    new_assembly.info.private_functions(),
+                )
+        })
+    }
+
+    /// Replaces linked assemblies with the `unlinked_assemblies` by patching
+    /// the function pointers in the dispatch tables, resulting in a new
+    /// [`DispatchTable`] on success. This leaves the original `dispatch_table`
+    /// intact, in case of linking errors.
+    ///
+    /// The unlinked assemblies are complete assemblies, not deltas, and the
+    /// pointers to all of their functions are patched, whether their bodies
+    /// changed or not. In contrast to [`Assembly::relink_all`], the types of
+    /// the linked assemblies are reused and no memory is mapped, which makes
+    /// this much faster. The caller must ensure that the assemblies are
+    /// [patchable](Assembly::is_patchable).
+    pub(super) fn patch_all(
+        unlinked_assemblies: &mut HashMap<PathBuf, Assembly>,
+        linked_assemblies: &mut HashMap<PathBuf, Assembly>,
+        dispatch_table: &DispatchTable,
+        type_table: &TypeTable,
+    ) -> Result<DispatchTable, LinkError> {
+        // Clone the dispatch table, such that we can roll back if linking fails
+        let mut dispatch_table = dispatch_table.clone();
+
+        // Maps the function pointers of the old assemblies to those of the new ones
+        let mut patched_fn_ptrs: HashMap<*const c_void, *const c_void> = HashMap::new();
+
+        for (old_path, new_assembly) in unlinked_assemblies.iter_mut() {
+            let old_assembly = linked_assemblies
+                .get(old_path)
+                .expect("Patched assemblies must replace a linked assembly.");
+
+            // The types are unchanged, so link the new assembly to the existing types
+            let types_to_link = new_assembly
+                .info_mut()
+                .type_lut
+                .iter_mut()
+                // Only take signatures into account that do *not* yet have a type handle assigned
+                // by the compiler.
+                .filter(|(_, ptr, _)| ptr.is_null());
+
+            Assembly::link_all_types(type_table, types_to_link).map_err(LinkError::MissingTypes)?;
+
+            let old_fns = old_assembly
+                .info
+                .symbols
+                .functions()
+                .iter()
+                .chain(old_assembly.info.private_functions());
+            for old_fn in old_fns {
+                if let Some(new_fn) = new_assembly
+                    .info
+                    .symbols
+                    .functions()
+                    .iter()
+                    .chain(new_assembly.info.private_functions())
+                    .find(|new_fn| new_fn.prototype.name() == old_fn.prototype.name())
+                {
+                    patched_fn_ptrs.insert(old_fn.fn_ptr, new_fn.fn_ptr);
+                }
+            }
+
+            dispatch_table.remove_module(&old_assembly.info);
+            dispatch_table.insert_module(&new_assembly.info, type_table);
+        }
+
+        for new_assembly in unlinked_assemblies.values_mut() {
+            let functions_to_link = new_assembly
+                .info_mut()
+                .dispatch_table
+                .iter_mut()
+                // Only take signatures into account that do *not* yet have a function pointer
+                // assigned by the compiler.
+                .filter(|(ptr, _)| ptr.is_null());
+
+            Assembly::link_all_functions(&dispatch_table, type_table, functions_to_link)?;
+        }
+
+        // Redirect the calls of the remaining assemblies to the new functions. This
+        // cannot fail, so it is safe to modify the linked assemblies.
+        for (path, assembly) in linked_assemblies.iter_mut() {
+            if unlinked_assemblies.contains_key(path) {
+                continue;
+            }
+
+            for fn_ptr in assembly.info_mut().dispatch_table.ptrs_mut() {
+                if let Some(new_fn_ptr) = patched_fn_ptrs.get(fn_ptr) {
+                    *fn_ptr = *new_fn_ptr;
+                }
+            }
+        }
+
+        Assembly::replace_linked(unlinked_assemblies, linked_assemblies);
+
+        Ok(dispatch_table)
+    }
+
+    /// Moves the `unlinked_assemblies` into the `linked_assemblies`, replacing
+    /// the assemblies that they are associated with.
+    fn replace_linked(
+        unlinked_assemblies: &mut HashMap<PathBuf, Assembly>,
+        linked_assemblies: &mut HashMap<PathBuf, Assembly>,
+    ) {
+        for (old_path, new_assembly) in unlinked_assemblies.drain() {
             assert!(
                 linked_assemblies.remove(&old_path).is_some(),
                 "Assembly must exist."
@@ -443,11 +778,6 @@ impl Assembly {
             let new_path = new_assembly.library_path.clone();
             linked_assemblies.insert(new_path, new_assembly);
         }
-
-        // Collect types
-        Type::collect_unreferenced_type_data();
-
-        Ok((dispatch_table, type_table))
     }
 
     /// Returns the assembly's information.
@@ -461,14 +791,104 @@ impl Assembly {
         unsafe { std::mem::transmute(&mut self.info) }
     }
 
+    /// Returns the version of the compiler that generated the assembly, or
+    /// `None` if the assembly does not record it.
+    pub fn compiler_version(&self) -> Option<&str> {
+        self.compiler_version.as_deref()
+    }
+
     /// Returns the path corresponding to the assembly's library.
     pub fn library_path(&self) -> &Path {
         self.library_path.as_path()
     }
 
+    /// Describes the assembly, resolving its functions and types using the
+    /// specified tables.
+    pub(crate) fn metadata(
+        &self,
+        dispatch_table: &DispatchTable,
+        type_table: &TypeTable,
+    ) -> AssemblyMetadata {
+        let symbols = &self.info.symbols;
+
+        let functions = symbols
+            .functions()
+            .iter()
+            .filter_map(|fn_def| dispatch_table.get_fn(fn_def.prototype.name()))
+            .sorted_by(|a, b| a.prototype.name.cmp(&b.prototype.name))
+            .collect();
+
+        let types = symbols
+            .types()
+            .iter()
+            .filter_map(|type_def| type_table.find_type_info_by_name(type_def.name()))
+            .sorted_by(|a, b| a.name().cmp(b.name()))
+            .collect();
+
+        let unresolved_functions = self
+            .info
+            .dispatch_table
+            .iter()
+            .filter(|(ptr, prototype)| {
+                ptr.is_null() || dispatch_table.get_linked_fn(prototype.name()).is_none()
+            })
+            .map(|(_, prototype)| prototype.name().to_owned())
+            .sorted()
+            .collect();
+
+        AssemblyMetadata {
+            library_path: self.library_path.clone(),
+            module_path: symbols.path().to_owned(),
+            abi_version: self.abi_version,
+            capabilities: self.info.capabilities(),
+            compiler_version: self.compiler_version.clone(),
+            functions,
+            types,
+            dependencies: self.info.dependencies().map(ToOwned::to_owned).collect(),
+            unresolved_functions,
+        }
+    }
+
     /// Converts the `Assembly` into a `TempLibrary`, consuming the input in the
     /// process.
     pub fn into_library(self) -> TempLibrary {
         self.library
     }
 }
+
+/// Returns true if `old` and `new` describe the same type, including every
+/// property of a struct that determines how its values are stored and
+/// marshalled. [`abi::TypeDefinition`]'s `PartialEq` implementation only
+/// compares the guid of structs, which does not depend on e.g. the memory kind
+/// of the struct.
+fn is_same_type_definition(old: &abi::TypeDefinition<'_>, new: &abi::TypeDefinition<'_>) -> bool {
+    if old != new || old.name() != new.name() {
+        return false;
+    }
+
+    match (old.as_struct(), new.as_struct()) {
+        (Some(old), Some(new)) => {
+            old.memory_kind == new.memory_kind
+                && old.repr == new.repr
+                && old.passed_by_value == new.passed_by_value
+                && old.field_names().eq(new.field_names())
+                && old.field_types() == new.field_types()
+                && old.field_offsets() == new.field_offsets()
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Returns true if `old` and `new` contain the same default values for the
+/// same struct fields, regardless of their order.
+fn is_same_field_defaults(old: &[abi::FieldDefault], new: &[abi::FieldDefault]) -> bool {
+    old.len() == new.len()
+        && new.iter().all(|new_default| {
+            old.iter().any(|old_default| {
+                old_default.struct_guid == new_default.struct_guid
+                    && old_default.field_index == new_default.field_index
+                    && old_default.value() == new_default.value()
+            })
+        })
+}
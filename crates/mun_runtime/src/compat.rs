@@ -0,0 +1,330 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+use mun_abi as abi;
+use mun_libloader::{
+    integrity::{self, IntegrityError},
+    MunLibraryImage,
+};
+use mun_memory::{mapping::Mapping, Type};
+
+use crate::{
+    assembly::{toolchain_mismatch_hint, LoadError},
+    Runtime,
+};
+
+/// A reason why an assembly cannot be linked into a [`Runtime`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CompatIssue {
+    /// The assembly was compiled for an incompatible ABI version
+    #[error(
+        "ABI version mismatch. `{}` is `{actual}` but runtime supports `{}` through `{expected}`{}",
+        library_path.display(),
+        abi::MIN_ABI_VERSION,
+        toolchain_mismatch_hint(compiler_version.as_deref())
+    )]
+    MismatchedAbiVersion {
+        /// The path of the library
+        library_path: PathBuf,
+        /// The newest ABI version supported by the runtime
+        expected: u32,
+        /// The ABI version of the library
+        actual: u32,
+        /// The version of the compiler that generated the library, if it is
+        /// recorded in the library
+        compiler_version: Option<String>,
+    },
+    /// The assembly failed the integrity checks that the runtime requires
+    #[error("Integrity check of `{}` failed: {error}", library_path.display())]
+    FailedIntegrityCheck {
+        /// The path of the library
+        library_path: PathBuf,
+        /// The reason why the check failed
+        error: IntegrityError,
+    },
+    /// The assembly makes use of ABI features that the runtime does not
+    /// support
+    #[error(
+        "`{}` uses unsupported ABI capabilities `{capabilities:?}`",
+        library_path.display()
+    )]
+    UnsupportedCapabilities {
+        /// The path of the library
+        library_path: PathBuf,
+        /// The capabilities that are not supported by the runtime
+        capabilities: abi::Capabilities,
+    },
+    /// A library the assembly depends on neither exists nor is loaded
+    #[error("Missing dependency `{}`", .0.display())]
+    MissingDependency(PathBuf),
+    /// The types of the assembly could not be loaded
+    #[error("Failed to load types: {0}")]
+    InvalidTypes(String),
+    /// A type that is used by the assembly is not provided by the runtime
+    #[error("Missing type `{0}`")]
+    MissingType(String),
+    /// A function that is used by the assembly is not provided by the runtime
+    #[error("Missing function `{0}`")]
+    MissingFunction(String),
+    /// A function that is used by the assembly has a different signature in
+    /// the runtime
+    #[error("The function signature in the dispatch table does not match.\nExpected:\n\tfn {expected}\n\nFound:\n\tfn {found}")]
+    MismatchedSignature {
+        /// Expected function signature
+        expected: String,
+        /// Function signature found in dispatch table
+        found: String,
+    },
+}
+
+/// The result of checking whether an assembly can be linked into a
+/// [`Runtime`]; see [`Runtime::can_load`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompatReport {
+    /// All issues that prevent the assembly from being linked
+    pub issues: Vec<CompatIssue>,
+    /// The names of types of already linked assemblies whose layout differs in
+    /// the new assembly. Existing instances of these types are migrated.
+    pub migrated_types: Vec<String>,
+    /// The names of types of already linked assemblies that no longer exist in
+    /// the new assembly. Existing instances of these types are lost.
+    pub deleted_types: Vec<String>,
+}
+
+impl CompatReport {
+    /// Returns true if the assembly can be linked into the runtime.
+    pub fn is_compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Runtime {
+    /// Checks whether the library at `library_path` and its dependencies can
+    /// be linked into this runtime, without actually linking them.
+    ///
+    /// The check verifies the integrity of the libraries if the runtime
+    /// requires it, the ABI version of the libraries, whether all their
+    /// dependencies are available, whether all functions and types they use
+    /// are provided, and how the types of already linked assemblies would be
+    /// migrated if the library replaces one of them.
+    ///
+    /// The libraries are not loaded as shared objects, so none of their code is
+    /// executed. Instead, their ABI version and assembly information are read
+    /// from the constants that they export; see [`MunLibraryImage`]. The
+    /// assembly information is validated against the mapped segments of a
+    /// library before it is used, so a malformed library results in an error.
+    pub fn can_load(&self, library_path: impl AsRef<Path>) -> Result<CompatReport, LoadError> {
+        let library_path = library_path.as_ref().canonicalize()?;
+
+        let linked = self.linked.read_recursive();
+        let mut report = CompatReport::default();
+
+        // Map the library and all its dependencies that are not yet linked
+        let mut libraries: HashMap<PathBuf, MunLibraryImage> = HashMap::new();
+        let mut to_load = VecDeque::new();
+        to_load.push_back(library_path);
+        while let Some(library_path) = to_load.pop_front() {
+            if libraries.contains_key(&library_path)
+                || linked.assemblies.contains_key(&library_path)
+            {
+                continue;
+            }
+
+            if !library_path.is_file() {
+                report
+                    .issues
+                    .push(CompatIssue::MissingDependency(library_path));
+                continue;
+            }
+
+            let image = match &self.integrity {
+                Some(policy) => {
+                    let bytes = std::fs::read(&library_path)?;
+                    if let Err(error) = integrity::verify(&bytes, policy) {
+                        report.issues.push(CompatIssue::FailedIntegrityCheck {
+                            library_path,
+                            error,
+                        });
+                        continue;
+                    }
+                    MunLibraryImage::from_bytes(&bytes)?
+                }
+                None => MunLibraryImage::new(&library_path)?,
+            };
+            let version = image.get_abi_version();
+            if !abi::is_compatible_abi_version(version) {
+                report.issues.push(CompatIssue::MismatchedAbiVersion {
+                    library_path,
+                    expected: abi::ABI_VERSION,
+                    actual: version,
+                    compiler_version: image.get_compiler_version(),
+                });
+                continue;
+            }
+
+            let info = image.get_info()?;
+            let capabilities = info.unsupported_capabilities();
+            if !capabilities.is_empty() {
+                report.issues.push(CompatIssue::UnsupportedCapabilities {
+                    library_path,
+                    capabilities,
+                });
+                continue;
+            }
+
+            let parent = library_path.parent().expect("Invalid library path");
+            let extension = library_path.extension();
+            for dependency in info.dependencies() {
+                let mut dependency_path = parent.join(dependency);
+                if let Some(extension) = extension {
+                    dependency_path = dependency_path.with_extension(extension);
+                }
+                to_load.push_back(dependency_path);
+            }
+
+            libraries.insert(library_path, image);
+        }
+
+        if !report.is_compatible() {
+            return Ok(report);
+        }
+
+        let infos = libraries
+            .values()
+            .map(MunLibraryImage::get_info)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Remove the types and functions of the assemblies that would be replaced
+        let mut type_table = linked.type_table.clone();
+        let mut dispatch_table = linked.dispatch_table.clone();
+        let mut old_types: Vec<Type> = Vec::new();
+        for info in &infos {
+            let replaced_assembly = linked
+                .assemblies
+                .values()
+                .find(|assembly| assembly.info().symbols.path() == info.symbols.path());
+            if let Some(replaced_assembly) = replaced_assembly {
+                let replaced_info = replaced_assembly.info();
+                old_types.extend(
+                    replaced_info
+                        .symbols
+                        .types()
+                        .iter()
+                        .filter_map(|type_info| type_table.remove_type_by_type_info(type_info)),
+                );
+                dispatch_table.remove_module(replaced_info);
+            }
+        }
+
+        // Load the types of the new assemblies into a copy of the type table
+        let (type_table, new_types) = match Type::try_from_abi(
+            infos.iter().flat_map(|info| info.symbols.types().iter()),
+            infos.iter().flat_map(|info| info.field_defaults().iter()),
+            type_table,
+        ) {
+            Ok(result) => result,
+            Err(e) => {
+                report.issues.push(CompatIssue::InvalidTypes(e.to_string()));
+                return Ok(report);
+            }
+        };
+
+        report.issues.extend(
+            infos
+                .iter()
+                .flat_map(|info| info.type_lut.iter())
+                .filter(|(type_id, ptr, _)| {
+                    ptr.is_null() && type_table.find_type_info_by_id(type_id).is_none()
+                })
+                .map(|(_, _, debug_name)| CompatIssue::MissingType(debug_name.to_owned())),
+        );
+        if !report.is_compatible() {
+            return Ok(report);
+        }
+
+        // Verify that all functions the new assemblies depend on are available
+        for info in &infos {
+            dispatch_table.insert_module(info, &type_table);
+        }
+        for (_, fn_prototype) in infos
+            .iter()
+            .flat_map(|info| info.dispatch_table.iter())
+            .filter(|(ptr, _)| ptr.is_null())
+        {
+            let fn_name = fn_prototype.name();
+            let Some(existing_fn_def) = dispatch_table.get_linked_fn(fn_name) else {
+                report
+                    .issues
+                    .push(CompatIssue::MissingFunction(fn_name.to_owned()));
+                continue;
+            };
+
+            let resolve = |type_id: &abi::TypeId<'_>| {
+                type_table
+                    .find_type_info_by_id(type_id)
+                    .ok_or_else(|| CompatIssue::MissingType(type_id.to_string()))
+            };
+            let signature = fn_prototype
+                .signature
+                .arg_types()
+                .iter()
+                .map(resolve)
+                .collect::<Result<Vec<_>, _>>()
+                .and_then(|arg_types| {
+                    Ok((arg_types, resolve(&fn_prototype.signature.return_type)?))
+                });
+            let (arg_types, return_type) = match signature {
+                Ok(signature) => signature,
+                Err(issue) => {
+                    report.issues.push(issue);
+                    continue;
+                }
+            };
+
+            let existing_signature = &existing_fn_def.prototype.signature;
+            if arg_types != existing_signature.arg_types
+                || return_type != existing_signature.return_type
+            {
+                report.issues.push(CompatIssue::MismatchedSignature {
+                    expected: format!(
+                        "{fn_name}({}) -> {}",
+                        arg_types.iter().map(Type::name).join(", "),
+                        return_type.name()
+                    ),
+                    found: format!(
+                        "{fn_name}({}) -> {}",
+                        existing_signature
+                            .arg_types
+                            .iter()
+                            .map(Type::name)
+                            .join(", "),
+                        existing_signature.return_type.name()
+                    ),
+                });
+            }
+        }
+
+        // Determine how the existing types would be migrated
+        let mapping = Mapping::with_renames(&old_types, &new_types, &self.field_renames.read());
+        report.migrated_types = mapping
+            .struct_mappings
+            .keys()
+            .map(|ty| ty.name().to_owned())
+            .sorted()
+            .collect();
+        report.deleted_types = mapping
+            .deletions
+            .iter()
+            .map(|ty| ty.name().to_owned())
+            .sorted()
+            .collect();
+
+        drop((dispatch_table, type_table, old_types, new_types, mapping));
+        Type::collect_unreferenced_type_data();
+
+        Ok(report)
+    }
+}
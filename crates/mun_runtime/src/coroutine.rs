@@ -0,0 +1,313 @@
+//! Implements coroutines, the suspended invocations of resumable functions.
+//!
+//! A resumable function allocates its frame through [`coroutine_alloc`]
+//! before it suspends for the first time. The garbage collector scans the
+//! frame conservatively, so the objects that are referenced by local
+//! variables of the function stay alive while it is suspended.
+//!
+//! The code that resumes and destroys a coroutine is part of the assembly that
+//! defines the resumable function. When the assemblies are hot reloaded, that
+//! code may be unloaded, so a coroutine that was started before the reload can
+//! neither be resumed nor destroyed. Instead, the runtime keeps track of the
+//! frames that were allocated on behalf of every coroutine, and frees them
+//! directly.
+
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    ffi,
+    mem::{ManuallyDrop, MaybeUninit},
+    ptr::NonNull,
+    rc::Rc,
+};
+
+use mun_abi as abi;
+
+use crate::{gc_stress, get_allocator, invocation::InvocationLock, profiler, InvokeArgs, Runtime};
+
+thread_local! {
+    /// The frames of the coroutines that are executing on the current thread,
+    /// innermost last
+    static EXECUTING_FRAMES: RefCell<Vec<Frames>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The addresses of the frames that were allocated on behalf of a coroutine
+/// and have not been freed yet
+type Frames = Rc<RefCell<HashSet<usize>>>;
+
+/// Records that the frames that are allocated and freed on the current thread
+/// belong to a coroutine, until the scope is dropped.
+struct FramesScope;
+
+impl FramesScope {
+    fn enter(frames: &Frames) -> Self {
+        EXECUTING_FRAMES.with(|executing| executing.borrow_mut().push(frames.clone()));
+        Self
+    }
+}
+
+impl Drop for FramesScope {
+    fn drop(&mut self) {
+        EXECUTING_FRAMES.with(|executing| executing.borrow_mut().pop());
+    }
+}
+
+/// Allocates `size` bytes for the frame of a coroutine on behalf of Mun code.
+pub(crate) extern "C" fn coroutine_alloc(size: usize, alloc_handle: *mut ffi::c_void) -> *mut u8 {
+    // Safety: `coroutine_alloc` is only called from within Mun assemblies' core
+    // logic, so we are guaranteed that the `Runtime` and its `GarbageCollector`
+    // still exist if this function is called, and will continue to do so for the
+    // duration of this function.
+    let allocator = ManuallyDrop::new(unsafe { get_allocator(alloc_handle) });
+
+    gc_stress::collect_before_mun_alloc(alloc_handle);
+
+    let frame = allocator.alloc_roots(size);
+    EXECUTING_FRAMES.with(|executing| {
+        if let Some(frames) = executing.borrow().last() {
+            frames.borrow_mut().insert(frame.as_ptr() as usize);
+        }
+    });
+
+    frame.as_ptr()
+}
+
+/// Frees the frame of a coroutine on behalf of Mun code.
+pub(crate) extern "C" fn coroutine_free(frame: *mut u8, alloc_handle: *mut ffi::c_void) {
+    let Some(frame) = NonNull::new(frame) else {
+        return;
+    };
+
+    // Safety: see `coroutine_alloc`
+    let allocator = ManuallyDrop::new(unsafe { get_allocator(alloc_handle) });
+
+    EXECUTING_FRAMES.with(|executing| {
+        if let Some(frames) = executing.borrow().last() {
+            frames.borrow_mut().remove(&(frame.as_ptr() as usize));
+        }
+    });
+
+    // Safety: Mun code only frees frames that it allocated with `coroutine_alloc`,
+    // exactly once.
+    unsafe { allocator.free_roots(frame) };
+}
+
+/// An error that occurs upon starting a [`Coroutine`].
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct StartCoroutineError(String);
+
+/// An error that occurs upon resuming a [`Coroutine`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum CoroutineError {
+    /// The coroutine has already completed.
+    #[error("the coroutine has already completed")]
+    Complete,
+    /// The assemblies were reloaded since the coroutine was started. The code
+    /// that the coroutine was suspended in may no longer exist.
+    #[error("the coroutine was invalidated by a reload of the assemblies")]
+    Invalidated,
+    /// The coroutine panicked the last time it was resumed.
+    #[error("the coroutine panicked")]
+    Poisoned,
+}
+
+/// The state of a [`Coroutine`] after it was resumed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoroutineState {
+    /// The coroutine is suspended at a `yield` expression, and has to be
+    /// resumed again to continue.
+    Suspended,
+    /// The coroutine returned.
+    Complete,
+}
+
+/// The state of a [`Coroutine`], including whether its frame still exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Status {
+    /// The coroutine is suspended and its frame exists
+    Suspended,
+    /// The coroutine is being resumed. If it is still resuming when it is
+    /// dropped, it panicked.
+    Resuming,
+    /// The coroutine returned and its frame was destroyed
+    Complete,
+    /// The coroutine panicked or was invalidated, and its frames were freed
+    Abandoned(CoroutineError),
+}
+
+/// A suspended invocation of a resumable Mun function, which is started with
+/// [`Runtime::start_coroutine`].
+///
+/// A coroutine doesn't start executing until it is resumed for the first
+/// time. Every call to [`Coroutine::resume`] executes the function until it
+/// reaches a `yield` expression or returns. Typically, a host resumes its
+/// coroutines once per frame of its game loop, with the time that has elapsed
+/// since the previous frame.
+///
+/// Coroutines are invalidated when the assemblies are reloaded, see
+/// [`CoroutineError::Invalidated`]. Dropping a coroutine that has not
+/// completed frees its frame and, thereby, releases the objects that it
+/// references.
+pub struct Coroutine<'runtime> {
+    runtime: &'runtime Runtime,
+    function_name: String,
+    /// The [epoch](Runtime::epoch) in which the coroutine was started
+    epoch: u64,
+    context: Box<abi::CoroutineContext>,
+    status: Status,
+    /// The frames that were allocated on behalf of the coroutine, including
+    /// those of the resumable functions that it awaits
+    frames: Frames,
+}
+
+impl Runtime {
+    /// Starts a coroutine that invokes the resumable Mun function called
+    /// `function_name` with the specified `arguments`. The coroutine is
+    /// suspended until it is resumed for the first time.
+    pub fn start_coroutine<ArgTypes: InvokeArgs>(
+        &self,
+        function_name: &str,
+        arguments: ArgTypes,
+    ) -> Result<Coroutine<'_>, StartCoroutineError> {
+        // Hold a shared lock for the duration of the invocation, this prevents the
+        // assemblies from being swapped while the function is executing.
+        let linked = InvocationLock::acquire(&self.linked);
+        let _executing = self.enter_mun_code(&linked);
+
+        let function = self
+            .lookup_function(function_name)
+            .map_err(StartCoroutineError)?;
+        if !function.is_resumable {
+            return Err(StartCoroutineError(format!(
+                "'{function_name}' is not a resumable function, use `Runtime::invoke` to invoke \
+                 it instead."
+            )));
+        }
+        arguments
+            .can_invoke(self, &function.prototype.signature)
+            .map_err(StartCoroutineError)?;
+
+        let frames = Frames::default();
+        let mut context = Box::new(MaybeUninit::<abi::CoroutineContext>::uninit());
+        {
+            let _frames = FramesScope::enter(&frames);
+            // Safety: the signature of the function was validated
+            unsafe { arguments.invoke_resumable(function.fn_ptr, context.as_mut_ptr()) };
+        }
+
+        Ok(Coroutine {
+            runtime: self,
+            function_name: function_name.to_owned(),
+            epoch: self.epoch(),
+            // Safety: a resumable function initializes its context before it suspends
+            // for the first time.
+            context: unsafe { Box::from_raw(Box::into_raw(context).cast()) },
+            status: Status::Suspended,
+            frames,
+        })
+    }
+}
+
+impl<'runtime> Coroutine<'runtime> {
+    /// Returns the name of the resumable function.
+    pub fn function_name(&self) -> &str {
+        &self.function_name
+    }
+
+    /// Returns whether the coroutine has completed.
+    pub fn is_complete(&self) -> bool {
+        self.status == Status::Complete
+    }
+
+    /// Resumes the coroutine until it reaches the next `yield` expression or
+    /// returns. `resume_value` becomes the value of the `yield` expression at
+    /// which the coroutine was suspended, it is ignored when the coroutine is
+    /// resumed for the first time.
+    ///
+    /// Once the coroutine has completed, its frame is destroyed. If it panics,
+    /// the panic unwinds into the caller and the coroutine cannot be resumed
+    /// anymore.
+    pub fn resume(&mut self, resume_value: f32) -> Result<CoroutineState, CoroutineError> {
+        // Hold a shared lock for the duration of the invocation, this prevents the
+        // assemblies from being swapped while the coroutine is executing.
+        let linked = InvocationLock::acquire(&self.runtime.linked);
+        let _executing = self.runtime.enter_mun_code(&linked);
+
+        if self.status == Status::Resuming {
+            self.abandon(CoroutineError::Poisoned);
+        } else if self.status == Status::Suspended && self.runtime.epoch() != self.epoch {
+            self.abandon(CoroutineError::Invalidated);
+        }
+        match self.status {
+            Status::Complete => return Err(CoroutineError::Complete),
+            Status::Abandoned(error) => return Err(error),
+            Status::Suspended | Status::Resuming => (),
+        }
+
+        // Discards the profiler frames of Mun functions that unwind because of a panic
+        let _profiler_frames = profiler::StackGuard::new();
+        let _frames = FramesScope::enter(&self.frames);
+
+        self.status = Status::Resuming;
+        self.context.resume_value = resume_value;
+        // Safety: the coroutine is suspended, and the code that resumes it still
+        // exists because the assemblies were not reloaded. Mun code can panic, which
+        // is only defined behavior across a `C-unwind` call.
+        let is_done = unsafe {
+            let resume: extern "C-unwind" fn(*mut ffi::c_void) -> u8 =
+                std::mem::transmute(self.context.resume);
+            resume(self.context.handle) != 0
+        };
+        if !is_done {
+            self.status = Status::Suspended;
+            return Ok(CoroutineState::Suspended);
+        }
+
+        // The coroutine is suspended at its final suspension point, so destroying it
+        // only frees its frame
+        (self.context.destroy)(self.context.handle);
+        self.status = Status::Complete;
+        Ok(CoroutineState::Complete)
+    }
+
+    /// Frees the frames of the coroutine without executing any of its code,
+    /// after which it can no longer be resumed.
+    fn abandon(&mut self, error: CoroutineError) {
+        for frame in self.frames.borrow_mut().drain() {
+            // Safety: the frames were allocated by `coroutine_alloc` and Mun code
+            // can no longer free them.
+            unsafe {
+                self.runtime
+                    .gc
+                    .free_roots(NonNull::new_unchecked(frame as *mut u8));
+            }
+        }
+        self.status = Status::Abandoned(error);
+    }
+}
+
+impl Drop for Coroutine<'_> {
+    fn drop(&mut self) {
+        match self.status {
+            Status::Suspended => (),
+            Status::Resuming => {
+                self.abandon(CoroutineError::Poisoned);
+                return;
+            }
+            Status::Complete | Status::Abandoned(_) => return,
+        }
+
+        let linked = InvocationLock::acquire(&self.runtime.linked);
+        if self.runtime.epoch() != self.epoch {
+            self.abandon(CoroutineError::Invalidated);
+            return;
+        }
+
+        let _executing = self.runtime.enter_mun_code(&linked);
+        let _frames = FramesScope::enter(&self.frames);
+        // The code that destroys the coroutine still exists, because the assemblies
+        // were not reloaded
+        (self.context.destroy)(self.context.handle);
+    }
+}
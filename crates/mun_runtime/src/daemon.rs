@@ -0,0 +1,134 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{Shutdown, SocketAddr, TcpStream},
+    sync::mpsc::{channel, Receiver, TryRecvError},
+};
+
+use log::error;
+use mun_project::daemon::{Notification, Request};
+
+/// A connection with a compiler daemon (`mun daemon`), see
+/// [`Runtime::connect_to_daemon`](crate::Runtime::connect_to_daemon).
+///
+/// Notifications are received on a background thread, so the runtime can
+/// poll them without blocking.
+pub(crate) struct DaemonConnection {
+    stream: TcpStream,
+    notifications: Receiver<Notification>,
+}
+
+impl DaemonConnection {
+    /// Connects to the compiler daemon at `address` and authenticates with
+    /// its `token`.
+    pub fn connect(address: SocketAddr, token: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        let reader = BufReader::new(stream.try_clone()?);
+
+        let (tx, rx) = channel();
+        std::thread::Builder::new()
+            .name("mun-daemon-connection".to_owned())
+            .spawn(move || {
+                for line in reader.lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+
+                    match line.parse() {
+                        Ok(notification) => {
+                            if tx.send(notification).is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => error!("Invalid message from the compiler daemon: {e}"),
+                    }
+                }
+            })?;
+
+        let connection = Self {
+            stream,
+            notifications: rx,
+        };
+        connection.send(&Request::Authenticate(token.to_owned()))?;
+        Ok(connection)
+    }
+
+    /// Sends the `request` to the daemon.
+    pub fn send(&self, request: &Request) -> io::Result<()> {
+        (&self.stream).write_all(format!("{request}\n").as_bytes())
+    }
+
+    /// Returns the notifications that were received since the last call.
+    /// Returns `None` if the daemon disconnected and all of its notifications
+    /// have been received.
+    pub fn try_recv(&self) -> Option<Vec<Notification>> {
+        let mut notifications: Vec<Notification> = self.notifications.try_iter().collect();
+        match self.notifications.try_recv() {
+            Ok(notification) => notifications.push(notification),
+            Err(TryRecvError::Disconnected) if notifications.is_empty() => return None,
+            Err(TryRecvError::Empty | TryRecvError::Disconnected) => (),
+        }
+        Some(notifications)
+    }
+}
+
+impl Drop for DaemonConnection {
+    fn drop(&mut self) {
+        // Stops the thread that receives notifications
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::{Ipv4Addr, TcpListener},
+        path::PathBuf,
+        time::{Duration, Instant},
+    };
+
+    use mun_project::daemon::{Notification, Request};
+
+    use super::DaemonConnection;
+
+    #[test]
+    fn request_and_notify() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let connection =
+            DaemonConnection::connect(listener.local_addr().unwrap(), "0123456789abcdef").unwrap();
+        let (mut stream, _) = listener.accept().unwrap();
+
+        connection.send(&Request::Build).unwrap();
+        let mut reader = BufReader::new(&stream);
+        let mut request = String::new();
+        reader.read_line(&mut request).unwrap();
+        assert_eq!(request, "authenticate 0123456789abcdef\n");
+        request.clear();
+        reader.read_line(&mut request).unwrap();
+        assert_eq!(request, "build\n");
+
+        stream
+            .write_all(b"assembly target/mod.munlib\nfinished\n")
+            .unwrap();
+
+        let expected = [
+            Notification::Assembly(PathBuf::from("target/mod.munlib")),
+            Notification::Finished,
+        ];
+        let mut notifications = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while notifications.len() < expected.len() && Instant::now() < deadline {
+            notifications.extend(connection.try_recv().unwrap());
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(notifications, expected);
+
+        // The connection reports that the daemon disconnected
+        drop(stream);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while connection.try_recv().is_some() {
+            assert!(Instant::now() < deadline, "the disconnect was not detected");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
@@ -11,20 +11,33 @@ use crate::function_info::FunctionDefinition;
 #[derive(Clone, Default)]
 pub struct DispatchTable {
     functions: FxHashMap<String, Arc<FunctionDefinition>>,
+    /// Functions that assemblies link against, but that may not be accessed by
+    /// the host
+    private_functions: FxHashMap<String, Arc<FunctionDefinition>>,
 }
 
 impl DispatchTable {
     /// Retrieves the [`FunctionDefinition`] corresponding to `fn_path`, if it
-    /// exists.
+    /// exists and may be accessed by the host.
     pub fn get_fn(&self, fn_path: &str) -> Option<Arc<FunctionDefinition>> {
         self.functions.get(fn_path).cloned()
     }
 
-    /// Retrieves the name of all available functions.
+    /// Retrieves the name of all functions that may be accessed by the host.
     pub fn get_fn_names(&self) -> impl Iterator<Item = &str> {
         self.functions.keys().map(String::as_str)
     }
 
+    /// Retrieves the [`FunctionDefinition`] corresponding to `fn_path` that
+    /// assemblies link against, if it exists. In contrast to
+    /// [`DispatchTable::get_fn`], this includes private functions.
+    pub fn get_linked_fn(&self, fn_path: &str) -> Option<Arc<FunctionDefinition>> {
+        self.functions
+            .get(fn_path)
+            .or_else(|| self.private_functions.get(fn_path))
+            .cloned()
+    }
+
     /// Inserts the `fn_info` for `fn_path` into the dispatch table.
     ///
     /// If the dispatch table already contained this `fn_path`, the value is
@@ -44,24 +57,49 @@ impl DispatchTable {
 
     /// Removes the function definitions from the given assembly from this
     /// dispatch table.
-    pub fn remove_module(&mut self, assembly: &abi::ModuleInfo<'_>) {
-        for function in assembly.functions() {
-            if let Some(value) = self.functions.get(function.prototype.name()) {
-                if value.fn_ptr == function.fn_ptr {
-                    self.functions.remove(function.prototype.name());
+    pub fn remove_module(&mut self, assembly: &abi::AssemblyInfo<'_>) {
+        fn remove_functions(
+            functions: &mut FxHashMap<String, Arc<FunctionDefinition>>,
+            fn_defs: &[abi::FunctionDefinition<'_>],
+        ) {
+            for function in fn_defs {
+                if let Some(value) = functions.get(function.prototype.name()) {
+                    if value.fn_ptr == function.fn_ptr {
+                        functions.remove(function.prototype.name());
+                    }
                 }
             }
         }
+
+        remove_functions(&mut self.functions, assembly.symbols.functions());
+        remove_functions(&mut self.private_functions, assembly.private_functions());
     }
 
     /// Add the function definitions from the given assembly from this dispatch
     /// table.
-    pub fn insert_module(&mut self, assembly: &abi::ModuleInfo<'_>, type_table: &TypeTable) {
-        for fn_def in assembly.functions() {
-            let fn_def = FunctionDefinition::try_from_abi(fn_def, type_table)
-                .expect("All types from a loaded assembly must exist in the type table.");
+    pub fn insert_module(&mut self, assembly: &abi::AssemblyInfo<'_>, type_table: &TypeTable) {
+        fn insert_functions(
+            functions: &mut FxHashMap<String, Arc<FunctionDefinition>>,
+            fn_defs: &[abi::FunctionDefinition<'_>],
+            type_table: &TypeTable,
+        ) {
+            for fn_def in fn_defs {
+                let fn_def = FunctionDefinition::try_from_abi(fn_def, type_table)
+                    .expect("All types from a loaded assembly must exist in the type table.");
 
-            self.insert_fn(fn_def.prototype.name.clone(), Arc::new(fn_def));
+                functions.insert(fn_def.prototype.name.clone(), Arc::new(fn_def));
+            }
         }
+
+        insert_functions(
+            &mut self.functions,
+            assembly.symbols.functions(),
+            type_table,
+        );
+        insert_functions(
+            &mut self.private_functions,
+            assembly.private_functions(),
+            type_table,
+        );
     }
 }
@@ -0,0 +1,156 @@
+use std::{
+    ffi::c_void,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use parking_lot::RwLock;
+
+use crate::{
+    invocation::InvocationLock, FunctionDefinition, InvokeArgs, InvokeErr, Marshal,
+    ReturnTypeReflection, Runtime,
+};
+
+/// An error that occurs upon retrieving a [`FunctionHandle`].
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct FunctionHandleError(String);
+
+/// A handle to a Mun function that was looked up and validated against the
+/// signature `fn(ArgTypes) -> ReturnType` once. Invoking the handle skips the
+/// lookup by name and, if the types of all arguments are known statically,
+/// the validation of the arguments.
+///
+/// When the assemblies are hot reloaded, the handle transparently looks up
+/// and validates the function again the next time it is invoked. Until then,
+/// invoking the handle boils down to an indirect call through the function
+/// pointer that it is bound to.
+pub struct FunctionHandle<'runtime, ArgTypes, ReturnType> {
+    runtime: &'runtime Runtime,
+    function_name: String,
+    /// The epoch of the runtime in which the handle was last bound. Stored
+    /// after all other fields of the binding, so that observing the current
+    /// epoch guarantees that the binding is up-to-date.
+    epoch: AtomicU64,
+    /// The function pointer of the bound function
+    fn_ptr: AtomicPtr<c_void>,
+    /// Whether the types of all arguments were validated when binding
+    validated_args: AtomicBool,
+    /// The bound function, required to validate arguments whose types are
+    /// only known at runtime
+    function: RwLock<Arc<FunctionDefinition>>,
+    _phantom: PhantomData<fn(ArgTypes) -> ReturnType>,
+}
+
+impl Runtime {
+    /// Returns a handle to the Mun function called `function_name`, after
+    /// validating that it can be invoked with `ArgTypes` and returns
+    /// `ReturnType`. This is cheaper than [`Runtime::invoke`] for functions
+    /// that are invoked repeatedly, e.g. every frame.
+    pub fn get_function<ArgTypes, ReturnType>(
+        &self,
+        function_name: &str,
+    ) -> Result<FunctionHandle<'_, ArgTypes, ReturnType>, FunctionHandleError>
+    where
+        ArgTypes: InvokeArgs,
+        ReturnType: ReturnTypeReflection,
+    {
+        let _linked = self.linked.read_recursive();
+        let epoch = self.epoch();
+        let (function, validated_args) =
+            FunctionHandle::<ArgTypes, ReturnType>::bind(self, function_name)
+                .map_err(FunctionHandleError)?;
+
+        Ok(FunctionHandle {
+            runtime: self,
+            function_name: function_name.to_owned(),
+            epoch: AtomicU64::new(epoch),
+            fn_ptr: AtomicPtr::new(function.fn_ptr.cast_mut()),
+            validated_args: AtomicBool::new(validated_args),
+            function: RwLock::new(function),
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<'runtime, ArgTypes, ReturnType> FunctionHandle<'runtime, ArgTypes, ReturnType>
+where
+    ArgTypes: InvokeArgs,
+    ReturnType: ReturnTypeReflection,
+{
+    /// Returns the name of the function.
+    pub fn function_name(&self) -> &str {
+        &self.function_name
+    }
+
+    /// Invokes the function with the specified `arguments`.
+    pub fn invoke(&self, arguments: ArgTypes) -> Result<ReturnType, InvokeErr<'_, ArgTypes>>
+    where
+        ReturnType: Marshal<'runtime> + 'runtime,
+    {
+        // Hold a shared lock for the duration of the invocation, this prevents the
+        // assemblies from being swapped while the function is executing.
+        let linked = InvocationLock::acquire(&self.runtime.linked);
+        let _executing = self.runtime.enter_mun_code(&linked);
+
+        if let Err(msg) = self.rebind_if_reloaded() {
+            return Err(InvokeErr {
+                msg,
+                function_name: &self.function_name,
+                arguments,
+            });
+        }
+
+        if !self.validated_args.load(Ordering::Relaxed) {
+            let function = self.function.read();
+            if let Err(msg) = arguments.can_invoke(self.runtime, &function.prototype.signature) {
+                return Err(InvokeErr {
+                    msg,
+                    function_name: &self.function_name,
+                    arguments,
+                });
+            }
+        }
+
+        let fn_ptr = self.fn_ptr.load(Ordering::Relaxed);
+        let result: ReturnType::MunType = unsafe { arguments.invoke(fn_ptr) };
+        Ok(Marshal::marshal_from(result, self.runtime))
+    }
+
+    /// Binds the handle to the function again if the assemblies were reloaded
+    /// since it was last bound. Must be called while holding a lock on the
+    /// linked assemblies, which guarantees that the epoch doesn't change.
+    ///
+    /// Multiple threads can rebind the handle simultaneously, but as they all
+    /// bind it to the same function, the binding is always consistent.
+    fn rebind_if_reloaded(&self) -> Result<(), String> {
+        let epoch = self.runtime.epoch();
+        if self.epoch.load(Ordering::Acquire) == epoch {
+            return Ok(());
+        }
+
+        let (function, validated_args) = Self::bind(self.runtime, &self.function_name)?;
+        self.fn_ptr
+            .store(function.fn_ptr.cast_mut(), Ordering::Relaxed);
+        self.validated_args.store(validated_args, Ordering::Relaxed);
+        *self.function.write() = function;
+        self.epoch.store(epoch, Ordering::Release);
+        Ok(())
+    }
+
+    /// Looks up the function called `function_name` and validates its
+    /// signature, returning the function and whether the types of all
+    /// arguments were validated. Must be called while holding a lock on the
+    /// linked assemblies.
+    fn bind(
+        runtime: &Runtime,
+        function_name: &str,
+    ) -> Result<(Arc<FunctionDefinition>, bool), String> {
+        let function = runtime.find_function::<ReturnType>(function_name)?;
+        let validated_args = ArgTypes::validate_signature(&function.prototype.signature)?;
+        Ok((function, validated_args))
+    }
+}
@@ -11,6 +11,9 @@ pub struct FunctionDefinition {
     pub prototype: FunctionPrototype,
     /// Function pointer
     pub fn_ptr: *const c_void,
+    /// Whether the function is resumable, in which case it can only be started
+    /// as a [`Coroutine`](crate::Coroutine)
+    pub is_resumable: bool,
 }
 
 impl FunctionDefinition {
@@ -39,6 +42,7 @@ impl FunctionDefinition {
         Ok(Self {
             prototype,
             fn_ptr: fn_def.fn_ptr,
+            is_resumable: fn_def.kind == abi::FunctionKind::Resumable,
         })
     }
 }
@@ -70,7 +74,7 @@ impl FunctionPrototype {
 
 /// A linked version of [`mun_abi::FunctionSignature`] that has resolved all
 /// occurrences of `TypeId` with `TypeInfo`.
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct FunctionSignature {
     /// Argument types
     pub arg_types: Vec<Type>,
@@ -128,7 +132,8 @@ macro_rules! into_function_info_impl {
                                 arg_types: vec![$(<$T as mun_memory::HasStaticType>::type_info().clone(),)*],
                                 return_type: <R as mun_memory::HasStaticType>::type_info().clone(),
                             }
-                        }
+                        },
+                        is_resumable: false,
                     }
                 }
             }
@@ -197,6 +202,7 @@ impl FunctionDefinitionBuilder {
                 },
             },
             fn_ptr: self.fn_ptr,
+            is_resumable: false,
         })
     }
 }
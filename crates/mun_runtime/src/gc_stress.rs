@@ -0,0 +1,231 @@
+//! Implements the stress mode of the garbage collector, see
+//! [`RuntimeBuilder::gc_stress_mode`](crate::RuntimeBuilder::gc_stress_mode).
+//!
+//! Objects that are only referenced from the stack of a Mun function are not
+//! rooted. To collect garbage while Mun code is executing, the stack of the
+//! executing thread and the registers in which Mun code keeps values across
+//! calls are scanned conservatively: every word that refers to an object keeps
+//! it alive. The stacks of other threads cannot be scanned, so garbage is only
+//! collected if no other thread is executing Mun code at the same time.
+
+use std::{cell::RefCell, ffi, sync::Arc};
+
+use parking_lot::Mutex;
+
+use crate::garbage_collector::GarbageCollector;
+
+thread_local! {
+    /// The Mun code that is executing on the current thread, outermost first
+    static EXECUTIONS: RefCell<Vec<Execution>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Mun code that is executing on the current thread
+struct Execution {
+    /// The collector of the runtime whose code is executing
+    collector: Arc<StressCollector>,
+    /// An address above the stack frames of the executing Mun code
+    anchor: usize,
+}
+
+/// Collects garbage before every allocation, whenever the roots of all
+/// objects are known.
+pub(crate) struct StressCollector {
+    gc: Arc<GarbageCollector>,
+    /// The number of threads that are executing Mun code. Entering Mun code
+    /// waits for a collection that is in progress.
+    executing_threads: Mutex<usize>,
+}
+
+impl StressCollector {
+    /// Constructs a collector for `gc`.
+    pub fn new(gc: Arc<GarbageCollector>) -> Arc<Self> {
+        Arc::new(Self {
+            gc,
+            executing_threads: Mutex::new(0),
+        })
+    }
+
+    /// Records that the current thread executes Mun code until the returned
+    /// scope is dropped. `anchor` must be a local variable of the function
+    /// that calls into Mun code, so it is located above the stack frames of
+    /// the Mun functions.
+    pub fn enter<T>(self: &Arc<Self>, anchor: &T) -> ExecutionScope {
+        let nested = self.execution().is_some();
+        if !nested {
+            *self.executing_threads.lock() += 1;
+        }
+
+        EXECUTIONS.with(|executions| {
+            executions.borrow_mut().push(Execution {
+                collector: self.clone(),
+                anchor: anchor as *const T as usize,
+            });
+        });
+
+        ExecutionScope { nested }
+    }
+
+    /// Collects garbage before an allocation by the host.
+    pub fn collect(&self) {
+        let executing_threads = self.executing_threads.lock();
+        match self.execution() {
+            // A host function that is called from Mun code
+            Some(anchor) if *executing_threads == 1 => self.collect_from_stack(anchor),
+            // The host itself, outside of any invocation
+            None if *executing_threads == 0 => {
+                self.gc.collect();
+            }
+            // Other threads are executing Mun code
+            _ => (),
+        }
+    }
+
+    /// Collects all garbage that is not referenced from the stack below
+    /// `anchor`, nor from the registers of the current thread.
+    fn collect_from_stack(&self, anchor: usize) {
+        with_stack_words(anchor, |words| {
+            self.gc.collect_with_conservative_roots(words);
+        });
+    }
+
+    /// Returns the anchor of the outermost Mun code of this runtime that is
+    /// executing on the current thread, if any.
+    fn execution(&self) -> Option<usize> {
+        EXECUTIONS.with(|executions| {
+            executions
+                .borrow()
+                .iter()
+                .find(|execution| std::ptr::eq(&*execution.collector, self))
+                .map(|execution| execution.anchor)
+        })
+    }
+}
+
+/// A scope in which the current thread executes Mun code, see
+/// [`StressCollector::enter`].
+pub(crate) struct ExecutionScope {
+    /// Whether the thread already executed Mun code of the same runtime
+    nested: bool,
+}
+
+impl Drop for ExecutionScope {
+    fn drop(&mut self) {
+        let execution = EXECUTIONS
+            .with(|executions| executions.borrow_mut().pop())
+            .expect("execution scopes must be dropped in reverse order");
+
+        if !self.nested {
+            *execution.collector.executing_threads.lock() -= 1;
+        }
+    }
+}
+
+/// Collects garbage before an allocation by Mun code with the garbage
+/// collector at `alloc_handle`, if its runtime runs in stress mode.
+///
+/// Mun code that is not executing on behalf of the runtime, e.g. because the
+/// host called a function pointer directly, never triggers a collection.
+pub(crate) fn collect_before_mun_alloc(alloc_handle: *const ffi::c_void) {
+    let collector = EXECUTIONS.with(|executions| {
+        executions
+            .borrow()
+            .iter()
+            .find(|execution| Arc::as_ptr(&execution.collector.gc).cast() == alloc_handle)
+            .map(|execution| execution.collector.clone())
+    });
+
+    if let Some(collector) = collector {
+        collector.collect();
+    }
+}
+
+/// Calls `f` with the words on the stack of the current thread, from the
+/// stack pointer up to `anchor`, after spilling the callee-saved registers to
+/// the stack. On architectures whose registers cannot be spilled, `f` is not
+/// called.
+#[inline(never)]
+fn with_stack_words(anchor: usize, f: impl FnOnce(&[usize])) {
+    let mut registers = [0usize; REGISTER_COUNT];
+    let Some(stack_pointer) = spill_registers(&mut registers) else {
+        return;
+    };
+
+    // The spilled registers are located in this stack frame, above the stack
+    // pointer
+    let word_size = std::mem::size_of::<usize>();
+    let words: Vec<usize> = (stack_pointer..anchor)
+        .step_by(word_size)
+        // Safety: the stack is readable from the stack pointer up to the frame of
+        // the function that contains the anchor.
+        .map(|address| unsafe { std::ptr::read_volatile(address as *const usize) })
+        .collect();
+    std::hint::black_box(&registers);
+
+    f(&words);
+}
+
+#[cfg(target_arch = "x86_64")]
+const REGISTER_COUNT: usize = 8;
+
+/// Stores the callee-saved registers in `registers` and returns the stack
+/// pointer.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn spill_registers(registers: &mut [usize; REGISTER_COUNT]) -> Option<usize> {
+    let stack_pointer: usize;
+    // Safety: only writes to `registers`. `rsi` and `rdi` are only callee-saved
+    // on Windows.
+    unsafe {
+        std::arch::asm!(
+            "mov [{registers}], rbx",
+            "mov [{registers} + 8], rbp",
+            "mov [{registers} + 16], r12",
+            "mov [{registers} + 24], r13",
+            "mov [{registers} + 32], r14",
+            "mov [{registers} + 40], r15",
+            "mov [{registers} + 48], rsi",
+            "mov [{registers} + 56], rdi",
+            "mov {stack_pointer}, rsp",
+            registers = in(reg) registers.as_mut_ptr(),
+            stack_pointer = out(reg) stack_pointer,
+            options(nostack, preserves_flags),
+        );
+    }
+    Some(stack_pointer)
+}
+
+#[cfg(target_arch = "aarch64")]
+const REGISTER_COUNT: usize = 11;
+
+/// Stores the callee-saved registers in `registers` and returns the stack
+/// pointer.
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+fn spill_registers(registers: &mut [usize; REGISTER_COUNT]) -> Option<usize> {
+    let stack_pointer: usize;
+    // Safety: only writes to `registers`
+    unsafe {
+        std::arch::asm!(
+            "stp x19, x20, [{registers}]",
+            "stp x21, x22, [{registers}, #16]",
+            "stp x23, x24, [{registers}, #32]",
+            "stp x25, x26, [{registers}, #48]",
+            "stp x27, x28, [{registers}, #64]",
+            "str x29, [{registers}, #80]",
+            "mov {stack_pointer}, sp",
+            registers = in(reg) registers.as_mut_ptr(),
+            stack_pointer = out(reg) stack_pointer,
+            options(nostack, preserves_flags),
+        );
+    }
+    Some(stack_pointer)
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const REGISTER_COUNT: usize = 0;
+
+/// Spilling the registers is not supported on this architecture.
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn spill_registers(_registers: &mut [usize; REGISTER_COUNT]) -> Option<usize> {
+    None
+}
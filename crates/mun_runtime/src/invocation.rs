@@ -0,0 +1,91 @@
+//! Synchronizes invocations of Mun functions with hot reloading and garbage
+//! collection.
+//!
+//! Every invocation holds a shared lock on the linked assemblies of its
+//! runtime, whereas reloading assemblies and collecting garbage require the
+//! lock exclusively. Objects that are only referenced from the stack of a Mun
+//! function are not rooted, so neither is allowed to happen while Mun code is
+//! executing.
+//!
+//! A thread that holds the lock can only reacquire it if it doesn't wait for a
+//! pending exclusive lock, which would never be granted. The runtime therefore
+//! keeps track of the locks that are held by the current thread.
+
+use std::cell::RefCell;
+
+use parking_lot::{RwLock, RwLockReadGuard};
+
+thread_local! {
+    /// The addresses of the locks on linked assemblies that are held by the
+    /// current thread, innermost last
+    static HELD_LOCKS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the address that identifies `lock`.
+fn lock_address<T>(lock: &RwLock<T>) -> usize {
+    lock as *const RwLock<T> as usize
+}
+
+/// Records that the current thread holds `lock` for as long as the scope is
+/// alive. The lock is held either by the thread itself, or on its behalf,
+/// e.g. by the thread that distributes a batch of invocations over a thread
+/// pool.
+pub(crate) struct LockScope {
+    address: usize,
+}
+
+impl LockScope {
+    /// Records that the current thread holds `lock`.
+    pub fn enter<T>(lock: &RwLock<T>) -> Self {
+        let address = lock_address(lock);
+        HELD_LOCKS.with(|locks| locks.borrow_mut().push(address));
+        Self { address }
+    }
+
+    /// Returns whether the current thread holds `lock`.
+    pub fn is_held<T>(lock: &RwLock<T>) -> bool {
+        let address = lock_address(lock);
+        HELD_LOCKS.with(|locks| locks.borrow().contains(&address))
+    }
+}
+
+impl Drop for LockScope {
+    fn drop(&mut self) {
+        HELD_LOCKS.with(|locks| {
+            let mut locks = locks.borrow_mut();
+            if let Some(idx) = locks.iter().rposition(|&address| address == self.address) {
+                locks.remove(idx);
+            }
+        });
+    }
+}
+
+/// A shared lock on the linked assemblies that is held for the duration of an
+/// invocation.
+pub(crate) struct InvocationLock<'a, T> {
+    _guard: RwLockReadGuard<'a, T>,
+    _scope: LockScope,
+}
+
+impl<'a, T> InvocationLock<'a, T> {
+    /// Acquires a shared lock on `lock`.
+    ///
+    /// If a reload or collection is waiting for the lock, this waits until it
+    /// has finished, so a steady stream of invocations cannot postpone it
+    /// indefinitely. The exception is an invocation from within another
+    /// invocation on the same thread, e.g. by a host function that is called
+    /// from Mun code. It has to skip ahead, because the pending exclusive
+    /// lock is never granted while the outer invocation holds the lock.
+    pub fn acquire(lock: &'a RwLock<T>) -> Self {
+        let guard = if LockScope::is_held(lock) {
+            lock.read_recursive()
+        } else {
+            lock.read()
+        };
+
+        Self {
+            _guard: guard,
+            _scope: LockScope::enter(lock),
+        }
+    }
+}
@@ -9,50 +9,86 @@ mod assembly;
 mod garbage_collector;
 mod adt;
 mod array;
+mod compat;
+mod coroutine;
+mod daemon;
 mod dispatch_table;
+mod function_handle;
 mod function_info;
+mod gc_stress;
+mod invocation;
+mod logging;
 mod marshal;
+mod opaque;
+mod profiler;
 mod reflection;
+mod reload;
+mod result;
 mod utils;
 
 use std::{
     cmp,
-    collections::{BTreeMap, HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     ffi,
     ffi::c_void,
     fmt::{Debug, Display, Formatter},
+    io,
     mem::ManuallyDrop,
+    net::SocketAddr,
     path::{Path, PathBuf},
     ptr::NonNull,
     sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
         mpsc::{channel, Receiver},
         Arc,
     },
 };
 
-use assembly::LoadError;
+use daemon::DaemonConnection;
 use dispatch_table::DispatchTable;
 use garbage_collector::GarbageCollector;
+use gc_stress::{ExecutionScope, StressCollector};
+use invocation::{InvocationLock, LockScope};
 use log::{debug, error, info};
 use mun_abi as abi;
+pub use mun_abi::{LogKind, OverflowOp};
+pub use mun_libloader::integrity;
+use mun_libloader::integrity::IntegrityPolicy;
 use mun_memory::{
+    diff::FieldRenames,
     gc::{self, Array, GcRuntime},
     type_table::TypeTable,
 };
 // Re-export some useful types so crates dont have to depend on mun_memory as well.
-pub use mun_memory::{Field, FieldData, HasStaticType, PointerType, StructType, Type};
-use mun_project::LOCKFILE_NAME;
+pub use mun_memory::{
+    Field, FieldData, HasStaticType, OpaqueType, PointerType, ReprCStruct, StructType, Type,
+};
+use mun_project::{
+    daemon::{find_daemon, Notification, Request},
+    LOCKFILE_NAME,
+};
+use mun_std::intrinsics::Intrinsic;
 use notify::{event::ModifyKind, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard};
+use rayon::prelude::*;
+use reload::ReloadHook;
 
 pub use crate::{
-    adt::{RootedStruct, StructRef},
+    adt::{RootedStruct, StructRef, StructValue},
     array::{ArrayRef, RawArray, RootedArray},
-    assembly::{Assembly, LinkError, LinkFunctionsError},
+    assembly::{Assembly, AssemblyMetadata, LinkError, LinkFunctionsError, LoadError},
+    compat::{CompatIssue, CompatReport},
+    coroutine::{Coroutine, CoroutineError, CoroutineState, StartCoroutineError},
+    function_handle::{FunctionHandle, FunctionHandleError},
     function_info::{
         FunctionDefinition, FunctionPrototype, FunctionSignature, IntoFunctionDefinition,
     },
+    logging::{LogHandler, LOG_FN_NAME},
     marshal::Marshal,
+    opaque::OpaqueRef,
+    profiler::{FunctionProfile, Profile, PROFILE_ENTER_FN_NAME, PROFILE_EXIT_FN_NAME},
     reflection::{ArgumentReflection, ReturnTypeReflection},
+    reload::{ReloadEvent, ReloadReport},
 };
 
 /// Options for the construction of a [`Runtime`].
@@ -63,6 +99,19 @@ pub struct RuntimeOptions {
     pub type_table: TypeTable,
     /// Custom user injected functions
     pub user_functions: Vec<FunctionDefinition>,
+    /// Whether to run the garbage collector in stress mode, see
+    /// [`RuntimeBuilder::gc_stress_mode`]
+    pub gc_stress_mode: bool,
+    /// The integrity checks that munlibs must pass before they are loaded, see
+    /// [`RuntimeBuilder::require_integrity`]
+    pub integrity: Option<IntegrityPolicy>,
+    /// The contents of munlibs that are loaded from memory instead of from
+    /// disk, by the path that identifies them, see
+    /// [`RuntimeBuilder::insert_assembly_bytes`]
+    pub in_memory_assemblies: HashMap<PathBuf, Vec<u8>>,
+    /// The handler that receives the messages that Mun code writes, see
+    /// [`RuntimeBuilder::set_log_handler`]
+    pub log_handler: Option<LogHandler>,
 }
 
 /// Retrieve the allocator using the provided handle.
@@ -99,6 +148,8 @@ extern "C" fn new(
     // of this function.
     let allocator = ManuallyDrop::new(unsafe { get_allocator(alloc_handle) });
 
+    gc_stress::collect_before_mun_alloc(alloc_handle);
+
     // Safety: the Mun Compiler guarantees that `new` is never called with
     // `ptr::null()`.
     let handle = allocator.as_ref().alloc(&type_info);
@@ -106,6 +157,34 @@ extern "C" fn new(
     handle.into()
 }
 
+/// The name of the function that the `core` package calls to panic.
+pub const CORE_PANIC_FN_NAME: &str = "core::panic";
+
+/// Panics on behalf of Mun code. The panic unwinds through the Mun functions on
+/// the stack, up to the host function that invoked Mun code.
+extern "C-unwind" fn core_panic() {
+    panic!("explicit panic in Mun code");
+}
+
+/// The name of the function that Mun code calls when an overflow-checked
+/// integer operation overflows.
+pub const OVERFLOW_FN_NAME: &str = "overflow";
+
+/// The payload of the panic that is raised when an overflow-checked integer
+/// operation in Mun code overflows. Hosts can recover it from the result of
+/// [`std::panic::catch_unwind`] with `downcast_ref`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct OverflowError(pub OverflowOp);
+
+/// Panics with an [`OverflowError`] on behalf of Mun code. Like
+/// [`core_panic`], the panic unwinds through the Mun functions on the stack,
+/// which are always invoked through the `C-unwind` ABI.
+extern "C-unwind" fn overflow(op: u8) {
+    let op = OverflowOp::from_u8(op).expect("invalid overflow operation");
+    std::panic::panic_any(OverflowError(op));
+}
+
 extern "C" fn new_array(
     type_handle: *const ffi::c_void,
     length: usize,
@@ -121,6 +200,8 @@ extern "C" fn new_array(
     // of this function.
     let allocator = ManuallyDrop::new(unsafe { get_allocator(alloc_handle) });
 
+    gc_stress::collect_before_mun_alloc(alloc_handle);
+
     let handle = allocator.as_ref().alloc_array(&type_info, length);
 
     handle.as_raw().into()
@@ -140,10 +221,34 @@ impl RuntimeBuilder {
                 library_path: library_path.into(),
                 type_table: TypeTable::default(),
                 user_functions: Vec::default(),
+                gc_stress_mode: false,
+                integrity: None,
+                in_memory_assemblies: HashMap::new(),
+                log_handler: None,
             },
         }
     }
 
+    /// Provides the contents of the munlib at `library_path`, which is then
+    /// loaded from memory instead of from disk. The path does not need to
+    /// exist; it only identifies the munlib. This allows loading the entry
+    /// point library and its dependencies after streaming them over the
+    /// network or unpacking them from an archive. See
+    /// [`Assembly::load_from_memory`].
+    ///
+    /// To hot reload a munlib that was loaded from memory, use
+    /// [`Runtime::stage_assembly`].
+    pub fn insert_assembly_bytes<P: Into<PathBuf>>(
+        mut self,
+        library_path: P,
+        bytes: Vec<u8>,
+    ) -> Self {
+        self.options
+            .in_memory_assemblies
+            .insert(library_path.into(), bytes);
+        self
+    }
+
     /// Adds a custom user function to the dispatch table.
     pub fn insert_fn<S: Into<String>, F: IntoFunctionDefinition>(
         mut self,
@@ -154,6 +259,76 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Registers the Rust type `T` as an opaque type. Mun code can refer to
+    /// the type by declaring an `extern struct` with the same name (i.e.
+    /// [`OpaqueType::NAME`]) and pass around references to values of the
+    /// type as [`OpaqueRef<T>`].
+    pub fn register_type<T: OpaqueType>(mut self) -> Self {
+        self.options
+            .type_table
+            .insert_type(T::opaque_type_info().clone());
+        self
+    }
+
+    /// Enables or disables the stress mode of the garbage collector. This is a
+    /// debug mode that helps to catch bugs in the handling of garbage
+    /// collected objects by the embedder and in the mapping of memory during
+    /// hot reloads, e.g. in integration tests. In stress mode:
+    ///
+    /// - every allocation by the host, e.g. [`Runtime::construct_array`], first
+    ///   collects all objects that are not rooted,
+    /// - the memory of collected objects is poisoned, and
+    /// - the heap is validated after every collection and every hot reload,
+    ///   panicking if it is corrupted.
+    ///
+    /// Objects that are only referenced from the stack of a Mun function are
+    /// not rooted. Allocations by Mun code, and by host functions that are
+    /// called from Mun code, therefore scan the stack and the registers of the
+    /// current thread for references, and only collect if no other thread is
+    /// executing Mun code at the same time. Scanning is only supported on
+    /// `x86_64` and `AArch64`. Objects that are only referenced from Mun code
+    /// that was not invoked through the runtime, e.g. through a function
+    /// pointer, are never collected.
+    ///
+    /// Stress mode slows down the runtime considerably and is not meant to be
+    /// used in production.
+    pub fn gc_stress_mode(mut self, enabled: bool) -> Self {
+        self.options.gc_stress_mode = enabled;
+        self
+    }
+
+    /// Requires all munlibs, including the ones that are hot reloaded, to pass
+    /// the integrity checks of the `policy` before they are loaded. A munlib
+    /// that fails the checks is never loaded, so none of its code is executed.
+    ///
+    /// Munlibs are hashed and signed with `mun build --hash` and
+    /// `mun build --sign <KEY>`, respectively.
+    pub fn require_integrity(mut self, policy: IntegrityPolicy) -> Self {
+        self.options.integrity = Some(policy);
+        self
+    }
+
+    /// Installs a handler that receives the messages that Mun code writes with
+    /// the built-in `print(...)` and `log(...)` functions, e.g. to show them
+    /// in the console of a game. Without a handler, printed messages are
+    /// written to the standard output and logged messages to the [log]
+    /// facade.
+    ///
+    /// The handler is installed when the runtime is constructed, and is shared
+    /// by all runtimes in the process: the handler that was installed last
+    /// receives the messages of all runtimes. A panic in the handler unwinds
+    /// through the Mun functions on the stack, up to the host function that
+    /// invoked Mun code.
+    ///
+    /// [log]: https://docs.rs/log
+    pub fn set_log_handler(
+        mut self,
+        handler: impl Fn(LogKind, &str) + Send + Sync + 'static,
+    ) -> Self {
+        self.options.log_handler = Some(Arc::new(handler));
+        self
+    }
+
     /// Constructs a [`Runtime`] with the builder's options.
     ///
     /// # Safety
@@ -188,6 +363,26 @@ pub enum InitError {
 
 /// A runtime for the Mun language.
 ///
+/// # Multithreading
+///
+/// The runtime is both `Send` and `Sync`, which means that a single runtime can
+/// be shared between threads (e.g. using an `Arc<Runtime>`) and functions can
+/// be invoked from multiple worker threads simultaneously.
+///
+/// Every invocation holds a shared lock on the linked assemblies for the
+/// duration of the call. Hot reloading through [`Runtime::update`] and
+/// collecting garbage through [`Runtime::gc_collect`] require the lock
+/// exclusively, so they wait for the invocations that are in flight to
+/// finish. In the meantime, new invocations wait for them, which prevents
+/// worker threads that keep invoking functions from postponing a reload
+/// indefinitely. Invocations from within an invocation on the same thread,
+/// e.g. by a host function that is called from Mun code, never wait, as that
+/// would deadlock. Host functions must therefore not wait for a thread that
+/// reloads assemblies or collects garbage.
+///
+/// Each successful reload starts a new [epoch](Runtime::epoch). Hosts can
+/// subscribe to reloads with [`Runtime::on_reload`].
+///
 /// # Logging
 ///
 /// The runtime uses [log] as a logging facade, but does not install a logger.
@@ -197,16 +392,46 @@ pub enum InitError {
 /// [log]: https://docs.rs/log
 /// [log-impl]: https://docs.rs/log/0.4.13/log/#available-logging-implementations
 pub struct Runtime {
+    linked: RwLock<LinkedState>,
+    reload: Mutex<ReloadState>,
+    reload_hooks: Mutex<Vec<ReloadHook>>,
+    field_renames: RwLock<FieldRenames>,
+    epoch: AtomicU64,
+    gc: Arc<GarbageCollector>,
+    /// Whether a collection was deferred because it was requested from within
+    /// an invocation
+    gc_collect_pending: AtomicBool,
+    /// Collects garbage before every allocation, if the garbage collector runs
+    /// in stress mode
+    gc_stress: Option<Arc<StressCollector>>,
+    integrity: Option<IntegrityPolicy>,
+}
+
+/// The state of all linked assemblies. Invocations hold a shared lock on this
+/// state, hot reloading an exclusive lock.
+struct LinkedState {
     assemblies: HashMap<PathBuf, Assembly>,
+    dispatch_table: DispatchTable,
+    type_table: TypeTable,
+}
+
+/// The state required to detect changes to the linked assemblies.
+struct ReloadState {
     /// Assemblies that have changed and thus need to be relinked. Maps the old
     /// to the (potentially) new path.
     assemblies_to_relink: BTreeMap<PathBuf, PathBuf>,
-    dispatch_table: DispatchTable,
-    type_table: TypeTable,
     watcher: RecommendedWatcher,
     watcher_rx: Receiver<notify::Result<Event>>,
     renamed_files: HashMap<usize, PathBuf>,
-    gc: Arc<GarbageCollector>,
+    /// The contents of assemblies that were handed to the runtime in memory,
+    /// see [`Runtime::stage_assembly`].
+    staged_assemblies: HashMap<PathBuf, Vec<u8>>,
+    /// Whether a relink was deferred, e.g. because it was requested from
+    /// within an invocation, or because assemblies were staged.
+    pending_relink: bool,
+    /// The connection with the compiler daemon, if any. While connected, the
+    /// daemon's notifications supersede file system events.
+    daemon: Option<DaemonConnection>,
 }
 
 impl Runtime {
@@ -216,6 +441,18 @@ impl Runtime {
         RuntimeBuilder::new(library_path)
     }
 
+    /// Constructs a [`RuntimeBuilder`] for a runtime that loads the munlib
+    /// with the specified contents from memory. The munlib is identified by
+    /// `library_path`, which does not need to exist. See
+    /// [`RuntimeBuilder::insert_assembly_bytes`].
+    pub fn builder_from_memory<P: Into<PathBuf>>(
+        library_path: P,
+        bytes: Vec<u8>,
+    ) -> RuntimeBuilder {
+        let library_path = library_path.into();
+        RuntimeBuilder::new(library_path.clone()).insert_assembly_bytes(library_path, bytes)
+    }
+
     /// Constructs a new `Runtime` that loads the library at `library_path` and
     /// its dependencies. The `Runtime` contains a file watcher that is
     /// triggered with an interval of `dur`.
@@ -256,29 +493,119 @@ impl Runtime {
             "new_array",
         ));
 
+        options.user_functions.push(IntoFunctionDefinition::into(
+            coroutine::coroutine_alloc as extern "C" fn(usize, *mut ffi::c_void) -> *mut u8,
+            "coroutine_alloc",
+        ));
+
+        options.user_functions.push(IntoFunctionDefinition::into(
+            coroutine::coroutine_free as extern "C" fn(*mut u8, *mut ffi::c_void),
+            "coroutine_free",
+        ));
+
+        options.user_functions.push(IntoFunctionDefinition::into(
+            profiler::profile_enter as extern "C" fn(*const u8),
+            PROFILE_ENTER_FN_NAME,
+        ));
+
+        options.user_functions.push(IntoFunctionDefinition::into(
+            profiler::profile_exit as extern "C" fn(*const u8),
+            PROFILE_EXIT_FN_NAME,
+        ));
+
+        // Add the intrinsics of the `std` package before the user functions, so an
+        // embedder can replace them
+        for (name, intrinsic) in mun_std::intrinsics::FUNCTIONS {
+            let fn_def = match *intrinsic {
+                Intrinsic::F64ToF64(f) => IntoFunctionDefinition::into(f, *name),
+                Intrinsic::F64F64ToF64(f) => IntoFunctionDefinition::into(f, *name),
+                Intrinsic::UnitToF64(f) => IntoFunctionDefinition::into(f, *name),
+                Intrinsic::U64ToUnit(f) => IntoFunctionDefinition::into(f, *name),
+            };
+            dispatch_table.insert_fn(*name, Arc::new(fn_def));
+        }
+
         options.user_functions.into_iter().for_each(|fn_def| {
             dispatch_table.insert_fn(fn_def.prototype.name.clone(), Arc::new(fn_def));
         });
 
+        // Add the functions of the `core` package that are provided by the runtime.
+        // `IntoFunctionDefinition` is only implemented for functions that cannot
+        // unwind, so the definition is built by hand.
+        dispatch_table.insert_fn(
+            CORE_PANIC_FN_NAME,
+            FunctionDefinition::builder(CORE_PANIC_FN_NAME)
+                .set_ptr(core_panic as extern "C-unwind" fn() as *const ffi::c_void)
+                .finish(),
+        );
+        dispatch_table.insert_fn(
+            OVERFLOW_FN_NAME,
+            FunctionDefinition::builder(OVERFLOW_FN_NAME)
+                .add_argument(<u8 as HasStaticType>::type_info().clone())
+                .set_ptr(overflow as extern "C-unwind" fn(u8) as *const ffi::c_void)
+                .finish(),
+        );
+        dispatch_table.insert_fn(
+            LOG_FN_NAME,
+            FunctionDefinition::builder(LOG_FN_NAME)
+                .add_argument(<u8 as HasStaticType>::type_info().clone())
+                .add_argument(<*const u8 as HasStaticType>::type_info().clone())
+                .add_argument(<usize as HasStaticType>::type_info().clone())
+                .add_argument(<*const u8 as HasStaticType>::type_info().clone())
+                .add_argument(<*const u64 as HasStaticType>::type_info().clone())
+                .add_argument(<usize as HasStaticType>::type_info().clone())
+                .set_ptr(
+                    logging::log
+                        as extern "C-unwind" fn(u8, *const u8, usize, *const u8, *const u64, usize)
+                        as *const ffi::c_void,
+                )
+                .finish(),
+        );
+        if let Some(handler) = options.log_handler {
+            logging::set_log_handler(handler);
+        }
+
         let watcher: RecommendedWatcher = notify::recommended_watcher(move |res| {
             tx.send(res).expect("Failed to send filesystem event.");
         })?;
+        let gc = Arc::new(
+            self::garbage_collector::GarbageCollector::default()
+                .with_debug_checks(options.gc_stress_mode),
+        );
+        let gc_stress = options
+            .gc_stress_mode
+            .then(|| StressCollector::new(gc.clone()));
         let mut runtime = Runtime {
-            assemblies: HashMap::new(),
-            assemblies_to_relink: BTreeMap::new(),
-            dispatch_table,
-            type_table,
-            watcher,
-            watcher_rx: rx,
-            renamed_files: HashMap::new(),
-            gc: Arc::new(self::garbage_collector::GarbageCollector::default()),
+            linked: RwLock::new(LinkedState {
+                assemblies: HashMap::new(),
+                dispatch_table,
+                type_table,
+            }),
+            reload: Mutex::new(ReloadState {
+                assemblies_to_relink: BTreeMap::new(),
+                watcher,
+                watcher_rx: rx,
+                renamed_files: HashMap::new(),
+                staged_assemblies: HashMap::new(),
+                pending_relink: false,
+                daemon: None,
+            }),
+            reload_hooks: Mutex::new(Vec::new()),
+            field_renames: RwLock::new(FieldRenames::default()),
+            epoch: AtomicU64::new(0),
+            gc,
+            gc_collect_pending: AtomicBool::new(false),
+            gc_stress,
+            integrity: options.integrity,
         };
 
-        runtime.add_assembly(&options.library_path)?;
+        runtime.add_assembly(&options.library_path, options.in_memory_assemblies)?;
         Ok(runtime)
     }
 
-    /// Adds an assembly corresponding to the library at `library_path`.
+    /// Adds an assembly corresponding to the library at `library_path`. The
+    /// library and its dependencies are loaded from memory if their contents
+    /// are in `in_memory`, and from disk otherwise.
     ///
     /// # Safety
     ///
@@ -294,16 +621,27 @@ impl Runtime {
     /// unloaded.
     ///
     /// See [`Assembly::load`] for more information.
-    unsafe fn add_assembly(&mut self, library_path: &Path) -> Result<(), LinkError> {
-        let library_path = library_path
-            .canonicalize()
-            .map_err(|e| LinkError::LoadAssembly(LoadError::Other(e)))?;
+    unsafe fn add_assembly(
+        &mut self,
+        library_path: &Path,
+        mut in_memory: HashMap<PathBuf, Vec<u8>>,
+    ) -> Result<(), LinkError> {
+        let library_path = if in_memory.contains_key(library_path) {
+            library_path.to_path_buf()
+        } else {
+            library_path
+                .canonicalize()
+                .map_err(|e| LinkError::LoadAssembly(LoadError::Other(e)))?
+        };
 
-        if self.assemblies.contains_key(&library_path) {
+        let linked = self.linked.get_mut();
+        let reload = self.reload.get_mut();
+        if linked.assemblies.contains_key(&library_path) {
             return Err(LoadError::AlreadyExists.into());
         }
 
         let mut loaded = HashMap::new();
+        let mut loaded_from_disk = HashSet::new();
         let mut to_load = VecDeque::new();
         to_load.push_back(library_path);
 
@@ -315,7 +653,17 @@ impl Runtime {
                 continue;
             }
 
-            let assembly = Assembly::load(&library_path, self.gc.clone())?;
+            let assembly = if let Some(bytes) = in_memory.remove(&library_path) {
+                Assembly::load_from_memory(
+                    &library_path,
+                    &bytes,
+                    self.gc.clone(),
+                    self.integrity.as_ref(),
+                )?
+            } else {
+                loaded_from_disk.insert(library_path.clone());
+                Assembly::load(&library_path, self.gc.clone(), self.integrity.as_ref())?
+            };
 
             let parent = library_path.parent().expect("Invalid library path");
             let extension = library_path.extension();
@@ -336,26 +684,61 @@ impl Runtime {
             }
         }
 
-        (self.dispatch_table, self.type_table) =
-            Assembly::link_all(loaded.values_mut(), &self.dispatch_table, &self.type_table)?;
+        (linked.dispatch_table, linked.type_table) = Assembly::link_all(
+            loaded.values_mut(),
+            &linked.dispatch_table,
+            &linked.type_table,
+        )?;
 
         for (library_path, assembly) in loaded {
-            self.watcher
-                .watch(library_path.parent().unwrap(), RecursiveMode::NonRecursive)
-                .expect("Path must exist as we just loaded the library");
+            // Assemblies that were loaded from memory are hot reloaded by staging them
+            if loaded_from_disk.contains(&library_path) {
+                reload
+                    .watcher
+                    .watch(library_path.parent().unwrap(), RecursiveMode::NonRecursive)
+                    .expect("Path must exist as we just loaded the library");
+            }
 
-            self.assemblies.insert(library_path, assembly);
+            linked.assemblies.insert(library_path, assembly);
         }
 
         Ok(())
     }
 
     /// Retrieves the function definition corresponding to `function_name`, if
-    /// available.
+    /// available. Functions that are not `pub` cannot be retrieved.
     pub fn get_function_definition(&self, function_name: &str) -> Option<Arc<FunctionDefinition>> {
-        // TODO: Verify that when someone tries to invoke a non-public function, it
-        // should fail.
-        self.dispatch_table.get_fn(function_name)
+        self.linked
+            .read_recursive()
+            .dispatch_table
+            .get_fn(function_name)
+    }
+
+    /// Returns the names of all functions annotated with `#[test]` in the
+    /// loaded assemblies, sorted by name.
+    pub fn test_functions(&self) -> Vec<String> {
+        self.function_names_of_kind(abi::FunctionKind::Test)
+    }
+
+    /// Returns the names of all functions annotated with `#[bench]` in the
+    /// loaded assemblies, sorted by name.
+    pub fn bench_functions(&self) -> Vec<String> {
+        self.function_names_of_kind(abi::FunctionKind::Bench)
+    }
+
+    /// Returns the names of all functions of the specified `kind` in the
+    /// loaded assemblies, sorted by name.
+    fn function_names_of_kind(&self, kind: abi::FunctionKind) -> Vec<String> {
+        let linked = self.linked.read_recursive();
+        let mut names: Vec<String> = linked
+            .assemblies
+            .values()
+            .flat_map(|assembly| assembly.info().symbols.functions())
+            .filter(|fn_def| fn_def.kind == kind)
+            .map(|fn_def| fn_def.prototype.name().to_owned())
+            .collect();
+        names.sort();
+        names
     }
 
     /// For a given `fn_name`, find the most similar name in `fn_names`
@@ -378,24 +761,216 @@ impl Runtime {
                     None
                 }
             })
-            .min_by(|(_, dist1), (_, dist2)| dist1.cmp(dist2));
+            // Names are compared when their distances are equal, so the suggestion does not
+            // depend on the iteration order of `fn_names`
+            .min_by(|(name1, dist1), (name2, dist2)| dist1.cmp(dist2).then(name1.cmp(name2)));
         found_match.map(|(closest_name, _)| closest_name)
     }
 
+    /// Describes all linked assemblies, sorted by the path of their shared
+    /// library.
+    pub fn assemblies(&self) -> Vec<AssemblyMetadata> {
+        let linked = self.linked.read_recursive();
+        let mut assemblies: Vec<AssemblyMetadata> = linked
+            .assemblies
+            .values()
+            .map(|assembly| assembly.metadata(&linked.dispatch_table, &linked.type_table))
+            .collect();
+        assemblies.sort_by(|a, b| a.library_path.cmp(&b.library_path));
+        assemblies
+    }
+
     /// Retrieves the type definition corresponding to `type_name`, if
     /// available.
     pub fn get_type_info_by_name(&self, type_name: &str) -> Option<Type> {
-        self.type_table.find_type_info_by_name(type_name)
+        self.linked
+            .read_recursive()
+            .type_table
+            .find_type_info_by_name(type_name)
     }
 
     /// Retrieve the type information corresponding to the `type_id`, if
     /// available.
     pub fn get_type_info_by_id(&self, type_id: &abi::TypeId<'_>) -> Option<Type> {
-        self.type_table.find_type_info_by_id(type_id)
+        self.linked
+            .read_recursive()
+            .type_table
+            .find_type_info_by_id(type_id)
+    }
+
+    /// Returns the current epoch of the runtime. The epoch starts at zero and
+    /// is incremented every time the assemblies are successfully reloaded.
+    ///
+    /// This can be used by hosts to invalidate data that they derived from
+    /// the previously linked assemblies.
+    pub fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::Acquire)
+    }
+
+    /// Registers a `hook` that is called with a [`ReloadEvent`] before and
+    /// after [`Runtime::update`] reloads assemblies. The report of a successful
+    /// reload describes which functions changed signature, which types were
+    /// remapped, and how many objects were migrated, so the host can
+    /// invalidate its caches and fetch new function handles. Hooks are called
+    /// in the order in which they were registered, on the thread that calls
+    /// [`Runtime::update`].
+    ///
+    /// [`ReloadEvent::Before`] is only emitted once the reload is certain to
+    /// happen, and is always followed by exactly one [`ReloadEvent::After`] or
+    /// [`ReloadEvent::Failed`] for the same reload. A reload that is deferred,
+    /// because [`Runtime::update`] is called from within an invocation, emits
+    /// no events until it is performed.
+    ///
+    /// Hooks must not call [`Runtime::update`], [`Runtime::stage_assembly`],
+    /// or [`Runtime::on_reload`], as that would deadlock. They may invoke
+    /// functions and query the runtime.
+    pub fn on_reload(&self, hook: impl FnMut(&ReloadEvent<'_>) + Send + 'static) {
+        self.reload_hooks.lock().push(Box::new(hook));
+    }
+
+    /// Calls all registered reload hooks with the specified `event`.
+    fn emit_reload_event(&self, event: &ReloadEvent<'_>) {
+        for hook in self.reload_hooks.lock().iter_mut() {
+            hook(event);
+        }
+    }
+
+    /// Records that the field `old_name` of the struct called `struct_name` was
+    /// renamed to `new_name`, so the values of the field are preserved when
+    /// the assemblies are reloaded. This is only required if the rename cannot
+    /// be detected automatically, e.g. because the type of the field changed
+    /// as well.
+    ///
+    /// The rename applies to all subsequent reloads.
+    pub fn rename_field(
+        &self,
+        struct_name: impl Into<String>,
+        old_name: impl Into<String>,
+        new_name: impl Into<String>,
+    ) {
+        self.field_renames
+            .write()
+            .insert(struct_name, old_name, new_name);
+    }
+
+    /// Stages the contents of a freshly compiled version of the assembly that
+    /// was loaded from `library_path`. The staged assembly is linked on the
+    /// next call to [`Runtime::update`], like an assembly that changed on
+    /// disk, but without waiting for the compiler to write it to the output
+    /// directory and the file watcher to pick it up. This allows a compiler
+    /// that runs in the same process, e.g. in an in-game script editor, to
+    /// hand its results directly to the runtime.
+    ///
+    /// Returns an error if no assembly was loaded from `library_path`.
+    pub fn stage_assembly(
+        &self,
+        library_path: impl AsRef<Path>,
+        bytes: Vec<u8>,
+    ) -> Result<(), LoadError> {
+        let library_path = library_path.as_ref();
+        let library_path = library_path
+            .canonicalize()
+            .unwrap_or_else(|_| library_path.to_path_buf());
+
+        if !self
+            .linked
+            .read_recursive()
+            .assemblies
+            .contains_key(&library_path)
+        {
+            return Err(LoadError::NotLoaded(library_path));
+        }
+
+        let mut reload = self.reload.lock();
+        reload
+            .assemblies_to_relink
+            .insert(library_path.clone(), library_path.clone());
+        reload.staged_assemblies.insert(library_path, bytes);
+        reload.pending_relink = true;
+        Ok(())
+    }
+
+    /// Connects to the compiler daemon (`mun daemon`) that builds the loaded
+    /// assemblies. The daemon writes its address, and the token with which
+    /// clients authenticate, to the output directory of the package, which is
+    /// found by searching the directories that contain the loaded assemblies.
+    /// Returns the address of the daemon.
+    ///
+    /// While connected, the runtime relinks the assemblies that the daemon
+    /// announces as soon as a build finishes, instead of waiting for file
+    /// system events. Announced munlibs that are not linked are ignored. If
+    /// the daemon disconnects, the runtime falls back to watching the file
+    /// system.
+    pub fn connect_to_daemon(&self) -> io::Result<SocketAddr> {
+        let info = {
+            let linked = self.linked.read_recursive();
+            let mut paths: Vec<&PathBuf> = linked.assemblies.keys().collect();
+            paths.sort();
+            paths.into_iter().find_map(|path| find_daemon(path))
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "no compiler daemon is running for the loaded assemblies",
+            )
+        })?;
+
+        self.connect_to_daemon_at(info.address, &info.token)?;
+        Ok(info.address)
+    }
+
+    /// Connects to the compiler daemon (`mun daemon`) at the specified
+    /// address and authenticates with its `token`, see
+    /// [`Runtime::connect_to_daemon`].
+    pub fn connect_to_daemon_at(&self, address: SocketAddr, token: &str) -> io::Result<()> {
+        let connection = DaemonConnection::connect(address, token)?;
+        info!("Connected to the compiler daemon at {address}.");
+        self.reload.lock().daemon = Some(connection);
+        Ok(())
+    }
+
+    /// Requests the connected compiler daemon to build the assemblies whose
+    /// sources changed. The request does not block; the rebuilt assemblies are
+    /// linked by a later call to [`Runtime::update`].
+    ///
+    /// Returns an error if the runtime is not connected to a daemon, see
+    /// [`Runtime::connect_to_daemon`].
+    pub fn request_build(&self) -> io::Result<()> {
+        self.reload
+            .lock()
+            .daemon
+            .as_ref()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotConnected,
+                    "not connected to a compiler daemon",
+                )
+            })?
+            .send(&Request::Build)
     }
 
     /// Updates the state of the runtime. This includes checking for file
-    /// changes, and reloading compiled assemblies.
+    /// changes, and reloading compiled assemblies, including assemblies that
+    /// were staged with [`Runtime::stage_assembly`].
+    ///
+    /// Reloading always loads the entire rebuilt assembly. However, if only the
+    /// bodies of its functions changed, the pointers to all of its functions
+    /// are swapped in the dispatch tables, while the existing types and
+    /// memory are kept. This skips mapping memory, which makes reloading
+    /// considerably faster.
+    ///
+    /// If functions are being invoked on other threads while the assemblies
+    /// need to be reloaded, this waits for them to finish, see
+    /// [multithreading](Runtime#multithreading). If `update` is called from
+    /// within an invocation on the current thread, e.g. by a host function,
+    /// the reload is deferred until the next call to `update` instead.
+    ///
+    /// A collection that was deferred by [`Runtime::gc_collect`] is also
+    /// performed.
+    ///
+    /// The hooks that were registered with [`Runtime::on_reload`] are notified
+    /// before and after the assemblies are reloaded.
+    ///
     /// # Safety
     ///
     /// A munlib is simply a shared object. When a library is loaded,
@@ -410,16 +985,21 @@ impl Runtime {
     /// unloaded.
     ///
     /// See [`Assembly::load`] for more information.
-    pub unsafe fn update(&mut self) -> bool {
+    pub unsafe fn update(&self) -> bool {
         fn is_lockfile(path: &Path) -> bool {
             path.file_name().expect("Invalid file path.") == LOCKFILE_NAME
         }
 
         unsafe fn relink_assemblies(
-            runtime: &mut Runtime,
+            to_load: &mut BTreeMap<PathBuf, PathBuf>,
+            staged: &mut HashMap<PathBuf, Vec<u8>>,
+            linked: &mut LinkedState,
+            gc: &Arc<GarbageCollector>,
+            field_renames: &FieldRenames,
+            integrity: Option<&IntegrityPolicy>,
+            report: &mut ReloadReport,
         ) -> Result<(DispatchTable, TypeTable), LinkError> {
             let mut loaded = HashMap::new();
-            let to_load = &mut runtime.assemblies_to_relink;
 
             info!("Relinking assemblies:");
             for (old_path, new_path) in to_load.iter() {
@@ -438,7 +1018,12 @@ impl Runtime {
                     continue;
                 }
 
-                let assembly = Assembly::load(&new_path, runtime.gc.clone())?;
+                let assembly = match staged.remove(&new_path) {
+                    Some(bytes) => {
+                        Assembly::load_from_memory(&new_path, &bytes, gc.clone(), integrity)?
+                    }
+                    None => Assembly::load(&new_path, gc.clone(), integrity)?,
+                };
 
                 let parent = new_path.parent().expect("Invalid library path");
                 let extension = new_path.extension();
@@ -454,23 +1039,82 @@ impl Runtime {
                     }
 
                     if !loaded.contains_key(&library_path)
-                        && !runtime.assemblies.contains_key(&library_path)
+                        && !linked.assemblies.contains_key(&library_path)
                     {
                         to_load.insert(old_path.clone(), library_path);
                     }
                 }
             }
 
+            report.assemblies = loaded
+                .values()
+                .map(|assembly| assembly.library_path().to_path_buf())
+                .collect();
+            report.assemblies.sort();
+
+            // If only the bodies of functions changed, the existing types and memory remain
+            // valid
+            if Assembly::is_patchable(&loaded, &linked.assemblies) {
+                info!("Only function bodies changed, patching dispatch tables.");
+                report.patched = true;
+
+                let dispatch_table = Assembly::patch_all(
+                    &mut loaded,
+                    &mut linked.assemblies,
+                    &linked.dispatch_table,
+                    &linked.type_table,
+                )?;
+                return Ok((dispatch_table, linked.type_table.clone()));
+            }
+
             Assembly::relink_all(
                 &mut loaded,
-                &mut runtime.assemblies,
-                &runtime.dispatch_table,
-                &runtime.type_table,
+                &mut linked.assemblies,
+                &linked.dispatch_table,
+                &linked.type_table,
+                field_renames,
+                report,
             )
         }
 
-        let mut requires_relink = false;
-        while let Ok(Ok(event)) = self.watcher_rx.try_recv() {
+        if self.gc_collect_pending.load(Ordering::Acquire) {
+            self.gc_collect();
+        }
+
+        let mut reload = self.reload.lock();
+        let reload = &mut *reload;
+
+        let mut requires_relink = reload.pending_relink;
+        if let Some(daemon) = &reload.daemon {
+            if let Some(notifications) = daemon.try_recv() {
+                for notification in notifications {
+                    match notification {
+                        Notification::Assembly(path) => {
+                            let path = path.canonicalize().unwrap_or(path);
+                            if self.linked.read_recursive().assemblies.contains_key(&path) {
+                                reload.assemblies_to_relink.insert(path.clone(), path);
+                            } else {
+                                debug!(
+                                    "Ignoring the munlib at {}, which is not linked.",
+                                    path.display()
+                                );
+                            }
+                        }
+                        Notification::Finished => requires_relink = true,
+                        Notification::Failed => debug!("The compiler daemon failed to build."),
+                    }
+                }
+            } else {
+                info!("Disconnected from the compiler daemon.");
+                reload.daemon = None;
+            }
+        }
+
+        while let Ok(Ok(event)) = reload.watcher_rx.try_recv() {
+            if reload.daemon.is_some() {
+                continue;
+            }
+
             for path in event.paths {
                 if is_lockfile(&path) {
                     match event.kind {
@@ -490,16 +1134,16 @@ impl Runtime {
                     match event.kind {
                         EventKind::Modify(ModifyKind::Name(_)) => {
                             let tracker = event.attrs.tracker().expect("Invalid RENAME event.");
-                            if let Some(old_path) = self.renamed_files.remove(&tracker) {
-                                self.assemblies_to_relink.insert(old_path, path);
+                            if let Some(old_path) = reload.renamed_files.remove(&tracker) {
+                                reload.assemblies_to_relink.insert(old_path, path);
                                 // on_file_changed(self, &old_path, &path);
                             } else {
-                                self.renamed_files.insert(tracker, path);
+                                reload.renamed_files.insert(tracker, path);
                             }
                         }
                         EventKind::Modify(_) => {
                             // TODO: don't overwrite existing
-                            self.assemblies_to_relink.insert(path.clone(), path);
+                            reload.assemblies_to_relink.insert(path.clone(), path);
                         }
                         _ => (),
                     }
@@ -508,20 +1152,83 @@ impl Runtime {
         }
 
         if requires_relink {
-            if self.assemblies_to_relink.is_empty() {
+            if reload.assemblies_to_relink.is_empty() {
                 debug!("The compiler didn't write a munlib.");
             } else {
-                match relink_assemblies(self) {
+                // Waiting for the invocations to finish would deadlock if they include one on
+                // this thread
+                if LockScope::is_held(&self.linked) {
+                    debug!("Deferring reload until the current invocation has finished.");
+                    reload.pending_relink = true;
+                    return false;
+                }
+
+                // An upgradable lock excludes other reloads, but still allows functions to
+                // be invoked, e.g. by the hooks of `ReloadEvent::Before`
+                let linked = self.linked.upgradable_read();
+                reload.pending_relink = false;
+
+                // The compiler writes the munlibs of all packages, including those of
+                // libraries like `std` that none of the linked assemblies depend on.
+                reload
+                    .assemblies_to_relink
+                    .retain(|old_path, _| linked.assemblies.contains_key(old_path));
+                if reload.assemblies_to_relink.is_empty() {
+                    debug!("The compiler didn't write a linked munlib.");
+                    return false;
+                }
+
+                let assemblies: Vec<PathBuf> =
+                    reload.assemblies_to_relink.keys().cloned().collect();
+                {
+                    // Functions that are invoked by the hooks must not wait for this reload
+                    let _scope = LockScope::enter(&self.linked);
+                    self.emit_reload_event(&ReloadEvent::Before {
+                        assemblies: &assemblies,
+                    });
+                }
+
+                // Assemblies cannot be swapped while functions are being invoked. Upgrading
+                // waits for the invocations that are in flight, while new invocations wait
+                // for the reload.
+                let mut linked = RwLockUpgradableReadGuard::upgrade(linked);
+
+                let mut report = ReloadReport::default();
+                let result = relink_assemblies(
+                    &mut reload.assemblies_to_relink,
+                    &mut reload.staged_assemblies,
+                    &mut linked,
+                    &self.gc,
+                    &self.field_renames.read(),
+                    self.integrity.as_ref(),
+                    &mut report,
+                );
+
+                // Staged assemblies that were not linked are outdated by now
+                reload.staged_assemblies.clear();
+
+                match result {
                     Ok((dispatch_table, type_table)) => {
                         info!("Succesfully reloaded assemblies.");
 
-                        self.dispatch_table = dispatch_table;
-                        self.type_table = type_table;
-                        self.assemblies_to_relink.clear();
+                        report.diff_functions(&linked.dispatch_table, &dispatch_table);
+                        linked.dispatch_table = dispatch_table;
+                        linked.type_table = type_table;
+                        reload.assemblies_to_relink.clear();
+                        report.epoch = self.epoch.fetch_add(1, Ordering::AcqRel) + 1;
+
+                        // Release the assemblies, so hooks can fetch new function handles
+                        drop(linked);
+                        self.emit_reload_event(&ReloadEvent::After(&report));
 
                         return true;
                     }
-                    Err(e) => error!("Failed to relink assemblies: {e}"),
+                    Err(e) => {
+                        error!("Failed to relink assemblies: {e}");
+
+                        drop(linked);
+                        self.emit_reload_event(&ReloadEvent::Failed(&e));
+                    }
                 }
             }
         }
@@ -540,7 +1247,24 @@ impl Runtime {
     /// Collects all memory that is no longer referenced by rooted objects.
     /// Returns `true` if memory was reclaimed, `false` otherwise. This
     /// behavior will likely change in the future.
+    ///
+    /// Objects that are only referenced from the stack of a Mun function are
+    /// not rooted, so this waits for functions that are being invoked on
+    /// other threads to finish, see [multithreading](Runtime#multithreading).
+    /// If it is called from within an invocation on the current thread, e.g.
+    /// by a host function, the collection is deferred until the next call to
+    /// [`Runtime::update`] instead.
     pub fn gc_collect(&self) -> bool {
+        // Waiting for the invocations to finish would deadlock if they include one on
+        // this thread
+        if LockScope::is_held(&self.linked) {
+            debug!("Deferring garbage collection until the current invocation has finished.");
+            self.gc_collect_pending.store(true, Ordering::Release);
+            return false;
+        }
+
+        let _linked = self.linked.write();
+        self.gc_collect_pending.store(false, Ordering::Release);
         self.gc.collect()
     }
 
@@ -549,6 +1273,49 @@ impl Runtime {
         self.gc.stats()
     }
 
+    /// Returns a snapshot of all objects that are allocated by the garbage
+    /// collector and the references between them. Use it to find out why
+    /// memory is retained, e.g. by writing it to a file with
+    /// [`gc::HeapDump::to_dot`].
+    pub fn dump_heap(&self) -> gc::HeapDump {
+        self.gc.dump_heap()
+    }
+
+    /// Returns how often Mun functions were called and how long they took,
+    /// since the profile was last reset. Only functions of assemblies that
+    /// were compiled with profiling enabled (`mun build --profiling`) are
+    /// measured.
+    ///
+    /// The profiler is shared by all runtimes in the process, so the profile
+    /// also contains the functions of other runtimes.
+    pub fn profile(&self) -> Profile {
+        profiler::snapshot()
+    }
+
+    /// Discards all measurements of the profiler, see [`Runtime::profile`].
+    pub fn reset_profile(&self) {
+        profiler::reset();
+    }
+
+    /// Collects all garbage if the garbage collector runs in stress mode, see
+    /// [`RuntimeBuilder::gc_stress_mode`]. Must be called before every
+    /// allocation by the host.
+    fn gc_stress_collect(&self) {
+        if let Some(gc_stress) = &self.gc_stress {
+            gc_stress.collect();
+        }
+    }
+
+    /// Records that the current thread executes Mun code until the returned
+    /// scope is dropped, if the garbage collector runs in stress mode.
+    /// `anchor` must be a local variable of the function that calls into Mun
+    /// code.
+    fn enter_mun_code<T>(&self, anchor: &T) -> Option<ExecutionScope> {
+        self.gc_stress
+            .as_ref()
+            .map(|gc_stress| gc_stress.enter(anchor))
+    }
+
     /// Constructs an array with a predefined element type.
     pub fn construct_typed_array<
         't,
@@ -568,6 +1335,7 @@ impl Runtime {
             .size_hint()
             .1
             .expect("iterator doesn't return upper bound");
+        self.gc_stress_collect();
         let mut array_handle = self.gc.alloc_array(&array_type, array_capacity);
 
         let mut element_ptr = array_handle.data().as_ptr();
@@ -617,6 +1385,7 @@ impl Runtime {
             .size_hint()
             .1
             .expect("iterator doesn't return upper bound");
+        self.gc_stress_collect();
         let mut array_handle = self.gc.alloc_array(&array_type, array_capacity);
 
         let mut element_ptr = array_handle.data().as_ptr();
@@ -675,7 +1444,7 @@ impl<'name, T: InvokeArgs> InvokeErr<'name, T> {
     /// successful invocation.
     // FIXME: `unwrap_or_else` does not compile for `StructRef`, due to
     // https://doc.rust-lang.org/nomicon/lifetime-mismatch.html#improperly-reduced-borrows
-    pub fn retry<'r, 'o, Output>(self, runtime: &'r mut Runtime) -> Result<Output, Self>
+    pub fn retry<'r, 'o, Output>(self, runtime: &'r Runtime) -> Result<Output, Self>
     where
         Output: 'o + ReturnTypeReflection + Marshal<'o>,
         'r: 'o,
@@ -689,7 +1458,7 @@ impl<'name, T: InvokeArgs> InvokeErr<'name, T> {
     /// output.
     // FIXME: `unwrap_or_else` does not compile for `StructRef`, due to
     // https://doc.rust-lang.org/nomicon/lifetime-mismatch.html#improperly-reduced-borrows
-    pub fn wait<'r, 'o, Output>(mut self, runtime: &'r mut Runtime) -> Output
+    pub fn wait<'r, 'o, Output>(mut self, runtime: &'r Runtime) -> Output
     where
         Output: 'o + ReturnTypeReflection + Marshal<'o>,
         'r: 'o,
@@ -711,12 +1480,12 @@ impl<'name, T: InvokeArgs> InvokeErr<'name, T> {
     /// When calling this function, you have to guarantee that `runtime` can be
     /// dereferenced and is valid for `'o`. The `Output` value can only
     /// contain a shared borrow of `runtime`.
-    unsafe fn retry_impl<'o, Output>(self, runtime: *mut Runtime) -> Result<Output, Self>
+    unsafe fn retry_impl<'o, Output>(self, runtime: *const Runtime) -> Result<Output, Self>
     where
         Output: 'o + ReturnTypeReflection + Marshal<'o>,
     {
         // Safety: Guaranteed by the caller to be valid to dereference.
-        let runtime = &mut *runtime;
+        let runtime = &*runtime;
 
         eprintln!("{}", self.msg);
         while !runtime.update() {
@@ -734,13 +1503,28 @@ pub trait InvokeArgs {
     /// arguments
     fn can_invoke(&self, runtime: &Runtime, signature: &FunctionSignature) -> Result<(), String>;
 
+    /// Determines whether the specified function can be called with arguments
+    /// of these types, without inspecting their values. Returns `true` if
+    /// the types of all arguments are known statically; otherwise the
+    /// arguments also have to be validated with [`InvokeArgs::can_invoke`].
+    fn validate_signature(signature: &FunctionSignature) -> Result<bool, String>;
+
     /// Calls the specified function with these function arguments
     ///
     /// # Safety
     ///
     /// The `fn_ptr` is cast and invoked which might result in undefined
-    /// behavior.
+    /// behavior. A panic in the invoked function unwinds into the caller.
     unsafe fn invoke<ReturnType>(self, fn_ptr: *const c_void) -> ReturnType;
+
+    /// Calls the specified resumable function with these function arguments,
+    /// which stores the handle of the suspended coroutine in `context`
+    ///
+    /// # Safety
+    ///
+    /// The `fn_ptr` is cast and invoked which might result in undefined
+    /// behavior. `context` must be valid for writes.
+    unsafe fn invoke_resumable(self, fn_ptr: *const c_void, context: *mut abi::CoroutineContext);
 }
 
 // Implement `InvokeTraits` for tuples up to and including 20 elements
@@ -767,16 +1551,63 @@ seq_macro::seq!(I in 0..N {
                     arg_types[I].name(),
                 ));
             }
+            reflection::validate_pass_mode(&arg_types[I], T~I::PASSED_BY_VALUE)
+                .map_err(|msg| format!("Invalid argument type at index {}. {msg}.", I))?;
             )*
 
             Ok(())
         }
 
+        #[allow(unused_mut)]
+        fn validate_signature(signature: &FunctionSignature) -> Result<bool, String> {
+            let arg_types = &signature.arg_types;
+
+            // Ensure the number of arguments match
+            #[allow(clippy::len_zero)]
+            if N != arg_types.len() {
+                return Err(format!("Invalid argument count. Expected {} arguments, got {}", arg_types.len(), N))
+            }
+
+            let mut is_static = true;
+            #(
+            match T~I::static_type_info() {
+                Some(ty) if arg_types[I] != ty => {
+                    return Err(format!(
+                        "Invalid argument type at index {}. Expected: {}. Found: {}.",
+                        I,
+                        ty.name(),
+                        arg_types[I].name(),
+                    ));
+                }
+                Some(_) => {}
+                None => is_static = false,
+            }
+            reflection::validate_pass_mode(&arg_types[I], T~I::PASSED_BY_VALUE)
+                .map_err(|msg| format!("Invalid argument type at index {}. {msg}.", I))?;
+            )*
+
+            Ok(is_static)
+        }
+
         unsafe fn invoke<ReturnType>(self, fn_ptr: *const c_void) -> ReturnType {
+            // Discards the profiler frames of Mun functions that unwind because of a panic
+            let _profiler_frames = profiler::StackGuard::new();
+
             #[allow(clippy::type_complexity)]
-            let function: fn(#(T~I::MunType,)*) -> ReturnType = core::mem::transmute(fn_ptr);
+            // Mun code can panic, e.g. through `core::panic` or an overflow check. The panic
+            // unwinds through the Mun frames into the host, which is only defined behavior
+            // across a `C-unwind` call.
+            let function: extern "C-unwind" fn(#(T~I::MunType,)*) -> ReturnType = core::mem::transmute(fn_ptr);
             function(#(self.I.marshal_into(),)*)
         }
+
+        unsafe fn invoke_resumable(self, fn_ptr: *const c_void, context: *mut abi::CoroutineContext) {
+            // The function only allocates the frame of the coroutine before it suspends, but
+            // like all Mun functions, it is called through the `C-unwind` ABI
+            #[allow(clippy::type_complexity)]
+            let function: extern "C-unwind" fn(#(T~I::MunType,)* *mut abi::CoroutineContext) = core::mem::transmute(fn_ptr);
+            function(#(self.I.marshal_into(),)* context);
+        }
     }
 });
 )*});
@@ -798,55 +1629,194 @@ impl Runtime {
     where
         'runtime: 'ret,
     {
-        // Get the function information from the runtime
-        let function_info = match self.get_function_definition(function_name).ok_or_else(|| {
-            format!("failed to obtain function '{function_name}', no such function exists.")
-        }) {
-            Ok(function_info) => function_info,
-            Err(msg) => {
-                let available_names = self.dispatch_table.get_fn_names();
-                let suggested_name =
-                    Self::find_best_match_for_fn_name(function_name, available_names, None);
-
-                let suggested_message = suggested_name.map_or_else(
-                    || msg.clone(),
-                    |name| format!("{msg} There is a function with a similar name: {name}"),
-                );
+        // Hold a shared lock for the duration of the invocation, this prevents the
+        // assemblies from being swapped while the function is executing.
+        let linked = InvocationLock::acquire(&self.linked);
+        let _executing = self.enter_mun_code(&linked);
+
+        let function_info =
+            match self.find_invocable_function::<ReturnType, _>(function_name, &arguments) {
+                Ok(function_info) => function_info,
+                Err(msg) => {
+                    return Err(InvokeErr {
+                        msg,
+                        function_name,
+                        arguments,
+                    })
+                }
+            };
 
-                return Err(InvokeErr {
-                    msg: suggested_message,
-                    function_name,
-                    arguments,
-                });
-            }
-        };
+        let result: ReturnType::MunType = unsafe { arguments.invoke(function_info.fn_ptr) };
+        Ok(Marshal::marshal_from(result, self))
+    }
 
-        // Validate the arguments
-        match arguments.can_invoke(self, &function_info.prototype.signature) {
-            Ok(_) => {}
-            Err(msg) => {
-                return Err(InvokeErr {
-                    msg,
-                    function_name,
-                    arguments,
-                })
-            }
-        };
+    /// Invokes the Mun function called `function_name` once for every set of
+    /// `arguments` and returns the results in the same order.
+    ///
+    /// The function is looked up and its signature is validated once for the
+    /// entire batch, which avoids the overhead of calling
+    /// [`Runtime::invoke`] in a loop, e.g. when updating every entity in a
+    /// scene with the same function. The assemblies cannot be hot reloaded
+    /// while the batch is executing.
+    pub fn invoke_batch<'runtime, 'ret, 'name, 'args, ReturnType, ArgTypes>(
+        &'runtime self,
+        function_name: &'name str,
+        arguments: &'args [ArgTypes],
+    ) -> Result<Vec<ReturnType>, InvokeErr<'name, &'args [ArgTypes]>>
+    where
+        ReturnType: ReturnTypeReflection + Marshal<'ret> + 'ret,
+        ArgTypes: InvokeArgs + Clone,
+        'runtime: 'ret,
+    {
+        // Hold a shared lock for the duration of the batch, this prevents the
+        // assemblies from being swapped in between invocations.
+        let linked = InvocationLock::acquire(&self.linked);
+        let _executing = self.enter_mun_code(&linked);
+
+        let function_info = self
+            .find_batch_invocable_function::<ReturnType, _>(function_name, arguments)
+            .map_err(|msg| InvokeErr {
+                msg,
+                function_name,
+                arguments,
+            })?;
+
+        Ok(arguments
+            .iter()
+            .map(|args| {
+                let result: ReturnType::MunType =
+                    unsafe { args.clone().invoke(function_info.fn_ptr) };
+                Marshal::marshal_from(result, self)
+            })
+            .collect())
+    }
 
-        // Validate the return type
-        if !ReturnType::accepts_type(&function_info.prototype.signature.return_type) {
-            return Err(InvokeErr {
-                msg: format!(
-                    "unexpected return type, got '{}', expected '{}",
-                    &function_info.prototype.signature.return_type.name(),
-                    ReturnType::type_hint()
-                ),
+    /// Invokes the Mun function called `function_name` once for every set of
+    /// `arguments`, distributing the invocations over the threads of a
+    /// [`rayon`] thread pool, and returns the results in the same order.
+    ///
+    /// The global thread pool is used, unless this is called from within
+    /// [`rayon::ThreadPool::install`], in which case the invocations run on
+    /// that pool.
+    ///
+    /// See [`Runtime::invoke_batch`]. The function must not depend on the order
+    /// in which the invocations are executed.
+    pub fn invoke_batch_parallel<'runtime, 'ret, 'name, 'args, ReturnType, ArgTypes>(
+        &'runtime self,
+        function_name: &'name str,
+        arguments: &'args [ArgTypes],
+    ) -> Result<Vec<ReturnType>, InvokeErr<'name, &'args [ArgTypes]>>
+    where
+        ReturnType: ReturnTypeReflection + Marshal<'ret> + Send + 'ret,
+        ArgTypes: InvokeArgs + Clone + Sync,
+        'runtime: 'ret,
+    {
+        // Hold a shared lock for the duration of the batch, this prevents the
+        // assemblies from being swapped in between invocations.
+        let _linked = InvocationLock::acquire(&self.linked);
+
+        let function_info = self
+            .find_batch_invocable_function::<ReturnType, _>(function_name, arguments)
+            .map_err(|msg| InvokeErr {
+                msg,
                 function_name,
                 arguments,
-            });
+            })?;
+
+        let results = arguments
+            .par_iter()
+            .map(|args| {
+                // The lock is held on behalf of the threads of the pool
+                let scope = LockScope::enter(&self.linked);
+                let _executing = self.enter_mun_code(&scope);
+                let result: ReturnType::MunType =
+                    unsafe { args.clone().invoke(function_info.fn_ptr) };
+                Marshal::marshal_from(result, self)
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Retrieves the definition of the function called `function_name` and
+    /// validates that it can be invoked with the specified `arguments` and
+    /// `ReturnType`.
+    fn find_invocable_function<ReturnType: ReturnTypeReflection, ArgTypes: InvokeArgs>(
+        &self,
+        function_name: &str,
+        arguments: &ArgTypes,
+    ) -> Result<Arc<FunctionDefinition>, String> {
+        let function_info = self.find_function::<ReturnType>(function_name)?;
+        arguments.can_invoke(self, &function_info.prototype.signature)?;
+        Ok(function_info)
+    }
+
+    /// Retrieves the definition of the function called `function_name` and
+    /// validates that it can be invoked with every set of `arguments` and
+    /// `ReturnType`.
+    fn find_batch_invocable_function<ReturnType: ReturnTypeReflection, ArgTypes: InvokeArgs>(
+        &self,
+        function_name: &str,
+        arguments: &[ArgTypes],
+    ) -> Result<Arc<FunctionDefinition>, String> {
+        let function_info = self.find_function::<ReturnType>(function_name)?;
+        for args in arguments {
+            args.can_invoke(self, &function_info.prototype.signature)?;
+        }
+        Ok(function_info)
+    }
+
+    /// Retrieves the definition of the function called `function_name` and
+    /// validates that it returns `ReturnType`. Resumable functions cannot be
+    /// invoked, they have to be started as a [`Coroutine`].
+    fn find_function<ReturnType: ReturnTypeReflection>(
+        &self,
+        function_name: &str,
+    ) -> Result<Arc<FunctionDefinition>, String> {
+        let function_info = self.lookup_function(function_name)?;
+        if function_info.is_resumable {
+            return Err(format!(
+                "'{function_name}' is a resumable function, use `Runtime::start_coroutine` to \
+                 start it instead."
+            ));
         }
 
-        let result: ReturnType::MunType = unsafe { arguments.invoke(function_info.fn_ptr) };
-        Ok(Marshal::marshal_from(result, self))
+        // Validate the return type
+        if !ReturnType::accepts_type(&function_info.prototype.signature.return_type) {
+            return Err(format!(
+                "unexpected return type, got '{}', expected '{}",
+                &function_info.prototype.signature.return_type.name(),
+                ReturnType::type_hint()
+            ));
+        }
+        reflection::validate_pass_mode(
+            &function_info.prototype.signature.return_type,
+            ReturnType::PASSED_BY_VALUE,
+        )
+        .map_err(|msg| format!("unexpected return type, {msg}"))?;
+
+        Ok(function_info)
+    }
+
+    /// Retrieves the definition of the function called `function_name`,
+    /// suggesting a function with a similar name if it doesn't exist.
+    fn lookup_function(&self, function_name: &str) -> Result<Arc<FunctionDefinition>, String> {
+        let linked = self.linked.read_recursive();
+
+        // Get the function information from the runtime
+        let function_info = linked.dispatch_table.get_fn(function_name).ok_or_else(|| {
+            let msg =
+                format!("failed to obtain function '{function_name}', no such function exists.");
+            let available_names = linked.dispatch_table.get_fn_names();
+            let suggested_name =
+                Self::find_best_match_for_fn_name(function_name, available_names, None);
+
+            suggested_name.map_or_else(
+                || msg.clone(),
+                |name| format!("{msg} There is a function with a similar name: {name}"),
+            )
+        })?;
+
+        Ok(function_info)
     }
 }
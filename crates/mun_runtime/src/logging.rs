@@ -0,0 +1,189 @@
+//! Writes the messages of the built-in `print(...)` and `log(...)` functions of
+//! Mun code.
+//!
+//! Mun code formats a message by calling [`LOG_FN_NAME`] with the format
+//! string and the arguments. The runtime formats the message and passes it to
+//! the handler that was installed with [`RuntimeBuilder::set_log_handler`], or
+//! writes it to the standard output and the [log] facade otherwise.
+//!
+//! [`RuntimeBuilder::set_log_handler`]: crate::RuntimeBuilder::set_log_handler
+
+use std::{fmt, fmt::Write as _, slice, str, sync::Arc};
+
+use mun_abi::{LogArgKind, LogKind};
+use parking_lot::{const_rwlock, RwLock};
+
+/// The name of the function that Mun code calls to write a formatted message.
+pub const LOG_FN_NAME: &str = "log";
+
+/// A function that receives the formatted messages that Mun code writes with
+/// the built-in `print(...)` and `log(...)` functions.
+pub type LogHandler = Arc<dyn Fn(LogKind, &str) + Send + Sync>;
+
+/// The handler that receives the messages of all runtimes in the process, if
+/// any
+static LOG_HANDLER: RwLock<Option<LogHandler>> = const_rwlock(None);
+
+/// Installs the handler that receives the messages of all runtimes in the
+/// process.
+pub(crate) fn set_log_handler(handler: LogHandler) {
+    *LOG_HANDLER.write() = Some(handler);
+}
+
+/// Writes a message if no handler was installed. Printed messages are written
+/// to the standard output and logged messages to the [log] facade.
+fn default_log_handler(kind: LogKind, message: &str) {
+    match kind {
+        LogKind::Print => println!("{message}"),
+        LogKind::Log => log::info!(target: "mun", "{message}"),
+    }
+}
+
+/// An argument of a formatted message.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LogArg {
+    Bool(bool),
+    Int(i128),
+    UInt(u128),
+    Float32(f32),
+    Float64(f64),
+}
+
+impl LogArg {
+    /// Constructs an argument of the specified kind from the two words in which
+    /// Mun code passes its value, the least significant word first.
+    fn from_words(kind: LogArgKind, low: u64, high: u64) -> Self {
+        let bits = (u128::from(high) << 64) | u128::from(low);
+        match kind {
+            LogArgKind::Bool => LogArg::Bool(bits != 0),
+            LogArgKind::Int => LogArg::Int(bits as i128),
+            LogArgKind::UInt => LogArg::UInt(bits),
+            LogArgKind::Float32 => LogArg::Float32(f32::from_bits(bits as u32)),
+            LogArgKind::Float64 => LogArg::Float64(f64::from_bits(bits as u64)),
+        }
+    }
+}
+
+impl fmt::Display for LogArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogArg::Bool(value) => value.fmt(f),
+            LogArg::Int(value) => value.fmt(f),
+            LogArg::UInt(value) => value.fmt(f),
+            LogArg::Float32(value) => value.fmt(f),
+            LogArg::Float64(value) => value.fmt(f),
+        }
+    }
+}
+
+/// Replaces every `{}` in `format` with the next argument. Braces are escaped
+/// by doubling them, e.g. `{{`. The compiler guarantees that there is an
+/// argument for every placeholder, and that there are no unmatched braces.
+fn format_message(format: &str, args: impl IntoIterator<Item = LogArg>) -> String {
+    let mut args = args.into_iter();
+    let mut message = String::with_capacity(format.len());
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.next_if_eq(&'}').is_some() => {
+                if let Some(arg) = args.next() {
+                    write!(message, "{arg}").expect("writing to a string cannot fail");
+                }
+            }
+            '{' | '}' => {
+                chars.next_if_eq(&c);
+                message.push(c);
+            }
+            c => message.push(c),
+        }
+    }
+    message
+}
+
+/// Formats a message on behalf of Mun code and passes it to the installed
+/// handler. The handler is allowed to panic, in which case the panic unwinds
+/// through the Mun functions on the stack, up to the host function that invoked
+/// Mun code.
+pub(crate) extern "C-unwind" fn log(
+    kind: u8,
+    format: *const u8,
+    format_len: usize,
+    arg_kinds: *const u8,
+    args: *const u64,
+    arg_count: usize,
+) {
+    let kind = LogKind::from_u8(kind).expect("invalid log kind");
+
+    // Safety: the Mun Compiler guarantees that `format` points to a UTF-8 string of
+    // `format_len` bytes, and that `arg_kinds` and `args` hold `arg_count`
+    // kinds and two words per argument, respectively.
+    let (format, arg_kinds, args) = unsafe {
+        (
+            str::from_utf8_unchecked(slice::from_raw_parts(format, format_len)),
+            slice::from_raw_parts(arg_kinds, arg_count),
+            slice::from_raw_parts(args, 2 * arg_count),
+        )
+    };
+    let args = arg_kinds
+        .iter()
+        .zip(args.chunks_exact(2))
+        .map(|(kind, words)| {
+            let kind = LogArgKind::from_u8(*kind).expect("invalid log argument kind");
+            LogArg::from_words(kind, words[0], words[1])
+        });
+    let message = format_message(format, args);
+
+    // The handler is cloned, so it can install another handler
+    let handler = LOG_HANDLER.read().clone();
+    match handler {
+        Some(handler) => handler(kind, &message),
+        None => default_log_handler(kind, &message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mun_abi::LogArgKind;
+
+    use super::{format_message, LogArg};
+
+    #[test]
+    fn from_words() {
+        assert_eq!(
+            LogArg::from_words(LogArgKind::Bool, 1, 0),
+            LogArg::Bool(true)
+        );
+        assert_eq!(
+            LogArg::from_words(LogArgKind::Int, u64::MAX, u64::MAX),
+            LogArg::Int(-1)
+        );
+        assert_eq!(
+            LogArg::from_words(LogArgKind::UInt, 0, 1),
+            LogArg::UInt(1 << 64)
+        );
+        assert_eq!(
+            LogArg::from_words(LogArgKind::Float32, 1.5f32.to_bits().into(), 0),
+            LogArg::Float32(1.5)
+        );
+        assert_eq!(
+            LogArg::from_words(LogArgKind::Float64, 2.5f64.to_bits(), 0),
+            LogArg::Float64(2.5)
+        );
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(format_message("hello", []), "hello");
+        assert_eq!(
+            format_message(
+                "{} + {} = {}",
+                [LogArg::Int(-1), LogArg::Float64(0.5), LogArg::Float32(-0.5)]
+            ),
+            "-1 + 0.5 = -0.5"
+        );
+        assert_eq!(
+            format_message("{{{}}} }}", [LogArg::Bool(false)]),
+            "{false} }"
+        );
+    }
+}
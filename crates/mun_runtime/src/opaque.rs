@@ -0,0 +1,148 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    ptr::NonNull,
+};
+
+use mun_memory::{HasStaticType, OpaqueType, Type};
+
+use crate::{marshal::Marshal, ArgumentReflection, ReturnTypeReflection, Runtime};
+
+/// A reference to a value of an opaque type that is owned by the host.
+///
+/// Mun code declares an opaque type as an `extern struct` without fields, e.g.
+/// `extern struct Texture;`. Values of the type are passed to and from Mun
+/// functions as plain pointers, so they round-trip through Mun code without
+/// being copied. The Rust type has to be registered with the runtime through
+/// [`RuntimeBuilder::register_type`](crate::RuntimeBuilder::register_type).
+///
+/// The runtime never dereferences or frees the pointer; the host is
+/// responsible for keeping the value alive for as long as Mun code might use
+/// it.
+#[repr(transparent)]
+pub struct OpaqueRef<T: OpaqueType>(NonNull<T>);
+
+impl<T: OpaqueType> OpaqueRef<T> {
+    /// Constructs a reference to the host value at `ptr`.
+    pub fn new(ptr: NonNull<T>) -> Self {
+        Self(ptr)
+    }
+
+    /// Returns a raw pointer to the host value.
+    pub fn as_ptr(&self) -> *mut T {
+        self.0.as_ptr()
+    }
+
+    /// Returns a shared reference to the host value.
+    ///
+    /// # Safety
+    ///
+    /// The host value must still be alive and must not be mutably borrowed
+    /// elsewhere for the duration of the lifetime `'a`.
+    pub unsafe fn as_ref<'a>(&self) -> &'a T {
+        &*self.0.as_ptr()
+    }
+
+    /// Returns a mutable reference to the host value.
+    ///
+    /// # Safety
+    ///
+    /// The host value must still be alive and must not be borrowed elsewhere
+    /// for the duration of the lifetime `'a`.
+    pub unsafe fn as_mut<'a>(&mut self) -> &'a mut T {
+        &mut *self.0.as_ptr()
+    }
+}
+
+impl<T: OpaqueType> From<&mut T> for OpaqueRef<T> {
+    fn from(value: &mut T) -> Self {
+        Self(NonNull::from(value))
+    }
+}
+
+impl<T: OpaqueType> Clone for OpaqueRef<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: OpaqueType> Copy for OpaqueRef<T> {}
+
+impl<T: OpaqueType> PartialEq for OpaqueRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: OpaqueType> Eq for OpaqueRef<T> {}
+
+impl<T: OpaqueType> Debug for OpaqueRef<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OpaqueRef")
+            .field(&T::NAME)
+            .field(&self.0)
+            .finish()
+    }
+}
+
+// Values of opaque types are owned by the host, which is responsible for
+// synchronizing access to them.
+unsafe impl<T: OpaqueType> Send for OpaqueRef<T> {}
+unsafe impl<T: OpaqueType> Sync for OpaqueRef<T> {}
+
+impl<T: OpaqueType> HasStaticType for OpaqueRef<T> {
+    fn type_info() -> &'static Type {
+        T::opaque_type_info()
+    }
+}
+
+impl<T: OpaqueType> ArgumentReflection for OpaqueRef<T> {
+    fn type_info(&self, _runtime: &Runtime) -> Type {
+        T::opaque_type_info().clone()
+    }
+
+    fn static_type_info() -> Option<Type> {
+        Some(T::opaque_type_info().clone())
+    }
+}
+
+impl<T: OpaqueType> ReturnTypeReflection for OpaqueRef<T> {
+    fn accepts_type(ty: &Type) -> bool {
+        T::opaque_type_info() == ty
+    }
+
+    fn type_hint() -> &'static str {
+        T::NAME
+    }
+}
+
+impl<'t, T: OpaqueType> Marshal<'t> for OpaqueRef<T> {
+    type MunType = OpaqueRef<T>;
+
+    fn marshal_from<'r>(value: Self::MunType, _runtime: &'r Runtime) -> Self
+    where
+        Self: 't,
+        'r: 't,
+    {
+        value
+    }
+
+    fn marshal_into(self) -> Self::MunType {
+        self
+    }
+
+    fn marshal_from_ptr<'r>(
+        ptr: NonNull<Self::MunType>,
+        _runtime: &'r Runtime,
+        _type_info: &Type,
+    ) -> Self
+    where
+        Self: 't,
+        'r: 't,
+    {
+        unsafe { ptr.as_ptr().read() }
+    }
+
+    fn marshal_to_ptr(value: Self, mut ptr: NonNull<Self::MunType>, _type_info: &Type) {
+        unsafe { *ptr.as_mut() = value };
+    }
+}
@@ -0,0 +1,451 @@
+//! A profiler that measures how often Mun functions are called and how long
+//! they take.
+//!
+//! Assemblies that are compiled with profiling enabled call
+//! [`PROFILE_ENTER_FN_NAME`] when a function is entered and
+//! [`PROFILE_EXIT_FN_NAME`] before it returns. Assemblies that are compiled
+//! without profiling do not call the profiler at all.
+
+use std::{
+    cell::RefCell,
+    ffi::CStr,
+    fmt::Write as _,
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+
+/// The name of the function that Mun code calls when a function is entered, if
+/// it was compiled with profiling enabled.
+pub const PROFILE_ENTER_FN_NAME: &str = "profile_enter";
+
+/// The name of the function that Mun code calls before a function returns, if
+/// it was compiled with profiling enabled.
+pub const PROFILE_EXIT_FN_NAME: &str = "profile_exit";
+
+/// The maximum number of calls that are recorded for the trace of a thread.
+/// Calls beyond this number are still counted, but are left out of the trace.
+const MAX_TRACE_EVENTS: usize = 1 << 20;
+
+static PROFILER: Lazy<Mutex<Profiler>> = Lazy::new(|| Mutex::new(Profiler::new()));
+
+/// The identifier of the next thread that calls into the profiler
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Incremented whenever a library is loaded, which may reuse the addresses of
+/// the names of functions in libraries that were unloaded
+static NAME_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// The functions that are executing on the current thread, innermost last
+    static STACK: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+
+    /// The indices of the functions in the profile of the current thread, keyed
+    /// by the address of their name
+    static FUNCTION_IDS: RefCell<FunctionIds> = RefCell::new(FunctionIds {
+        generation: NAME_GENERATION.load(Ordering::Acquire),
+        ids: FxHashMap::default(),
+    });
+
+    /// The measurements of the current thread
+    static THREAD_PROFILE: Arc<Mutex<ThreadProfile>> = PROFILER.lock().register_thread();
+}
+
+/// A call of a function that has not returned yet
+struct Frame {
+    function: usize,
+    start: Instant,
+    /// The time spent in the functions that this function called
+    callee_time: Duration,
+}
+
+/// A completed call of a function
+#[derive(Clone, Copy, Debug)]
+struct TraceEvent {
+    function: usize,
+    start: Instant,
+    duration: Duration,
+}
+
+/// The names of functions that were interned by their address. Addresses are
+/// only valid for the [`NAME_GENERATION`] in which they were interned.
+struct FunctionIds {
+    generation: u64,
+    ids: FxHashMap<*const u8, usize>,
+}
+
+/// The measurements of all threads. Only locked when a thread first calls into
+/// the profiler, and when a profile is taken or reset.
+struct Profiler {
+    start: Instant,
+    threads: Vec<Arc<Mutex<ThreadProfile>>>,
+}
+
+/// The measurements of a single thread. Only locked by other threads when a
+/// profile is taken or reset.
+struct ThreadProfile {
+    thread: u64,
+    functions: Vec<FunctionProfile>,
+    function_ids: FxHashMap<String, usize>,
+    events: Vec<TraceEvent>,
+    is_trace_truncated: bool,
+}
+
+impl Profiler {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            threads: Vec::new(),
+        }
+    }
+
+    /// Adds the measurements of a new thread.
+    fn register_thread(&mut self) -> Arc<Mutex<ThreadProfile>> {
+        let profile = Arc::new(Mutex::new(ThreadProfile {
+            thread: NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed),
+            functions: Vec::new(),
+            function_ids: FxHashMap::default(),
+            events: Vec::new(),
+            is_trace_truncated: false,
+        }));
+        self.threads.push(profile.clone());
+        profile
+    }
+}
+
+impl ThreadProfile {
+    /// Returns the index of the function called `name`, registering it if
+    /// this is its first call on this thread.
+    fn function_id(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.function_ids.get(name) {
+            return id;
+        }
+
+        let id = self.functions.len();
+        self.functions.push(FunctionProfile {
+            name: name.to_owned(),
+            call_count: 0,
+            total_time: Duration::ZERO,
+            self_time: Duration::ZERO,
+        });
+        self.function_ids.insert(name.to_owned(), id);
+        id
+    }
+}
+
+/// Returns the index of the function called `name` in the profile of the
+/// current thread. The name is only read the first time its address is seen.
+fn function_id(name: *const u8) -> usize {
+    FUNCTION_IDS.with(|function_ids| {
+        let mut function_ids = function_ids.borrow_mut();
+        let generation = NAME_GENERATION.load(Ordering::Acquire);
+        if function_ids.generation != generation {
+            function_ids.generation = generation;
+            function_ids.ids.clear();
+        }
+
+        *function_ids.ids.entry(name).or_insert_with(|| {
+            // SAFETY: Mun code passes a null-terminated string that lives as long as its
+            // assembly
+            let name = unsafe { CStr::from_ptr(name.cast()) };
+            THREAD_PROFILE.with(|profile| profile.lock().function_id(&name.to_string_lossy()))
+        })
+    })
+}
+
+/// Invalidates the addresses of function names that the profiler has seen.
+/// Must be called after a library is loaded, because its names may be at the
+/// addresses of names of libraries that were unloaded.
+pub(crate) fn forget_function_names() {
+    NAME_GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// Reports that the function called `name` was entered.
+pub(crate) extern "C" fn profile_enter(name: *const u8) {
+    let function = function_id(name);
+
+    STACK.with(|stack| {
+        stack.borrow_mut().push(Frame {
+            function,
+            start: Instant::now(),
+            callee_time: Duration::ZERO,
+        });
+    });
+}
+
+/// Reports that the function called `name` returns.
+pub(crate) extern "C" fn profile_exit(name: *const u8) {
+    let end = Instant::now();
+    let function = function_id(name);
+
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        let Some(index) = stack.iter().rposition(|frame| frame.function == function) else {
+            return;
+        };
+        stack.truncate(index + 1);
+        let frame = stack.pop().expect("the stack contains the frame");
+
+        let duration = end.duration_since(frame.start);
+        if let Some(caller) = stack.last_mut() {
+            caller.callee_time += duration;
+        }
+
+        // The time of a recursive call is already part of the time of the
+        // outermost call
+        let is_recursive = stack.iter().any(|frame| frame.function == function);
+
+        THREAD_PROFILE.with(|profile| {
+            let mut profile = profile.lock();
+            let function_profile = &mut profile.functions[function];
+            function_profile.call_count += 1;
+            function_profile.self_time += duration.saturating_sub(frame.callee_time);
+            if !is_recursive {
+                function_profile.total_time += duration;
+            }
+
+            if profile.events.len() < MAX_TRACE_EVENTS {
+                profile.events.push(TraceEvent {
+                    function,
+                    start: frame.start,
+                    duration,
+                });
+            } else {
+                profile.is_trace_truncated = true;
+            }
+        });
+    });
+}
+
+/// Discards the frames of the Mun functions that were entered on the current
+/// thread after the guard was created, when it is dropped. Mun functions that
+/// unwind because of a panic never report that they return, so every
+/// invocation of Mun code holds a guard.
+pub(crate) struct StackGuard {
+    depth: usize,
+}
+
+impl StackGuard {
+    pub fn new() -> Self {
+        Self {
+            depth: STACK.with(|stack| stack.borrow().len()),
+        }
+    }
+}
+
+impl Drop for StackGuard {
+    fn drop(&mut self) {
+        // The thread-local may already be destroyed if the thread is exiting
+        let _ = STACK.try_with(|stack| stack.borrow_mut().truncate(self.depth));
+    }
+}
+
+/// Returns the measurements that were made since the profile was last reset.
+pub(crate) fn snapshot() -> Profile {
+    let profiler = PROFILER.lock();
+    let threads: Vec<_> = profiler
+        .threads
+        .iter()
+        .map(|thread| thread.lock())
+        .collect();
+
+    let mut functions: FxHashMap<&str, FunctionProfile> = FxHashMap::default();
+    let mut trace = Vec::new();
+    let mut is_trace_truncated = false;
+    for thread in &threads {
+        for function in thread.functions.iter().filter(|f| f.call_count > 0) {
+            let profile = functions
+                .entry(&function.name)
+                .or_insert_with(|| FunctionProfile {
+                    name: function.name.clone(),
+                    call_count: 0,
+                    total_time: Duration::ZERO,
+                    self_time: Duration::ZERO,
+                });
+            profile.call_count += function.call_count;
+            profile.total_time += function.total_time;
+            profile.self_time += function.self_time;
+        }
+
+        trace.extend(thread.events.iter().map(|event| TraceCall {
+            name: thread.functions[event.function].name.clone(),
+            thread: thread.thread,
+            start: event.start.saturating_duration_since(profiler.start),
+            duration: event.duration,
+        }));
+        is_trace_truncated |= thread.is_trace_truncated;
+    }
+
+    let mut functions: Vec<FunctionProfile> = functions.into_values().collect();
+    functions.sort_by(|a, b| {
+        b.total_time
+            .cmp(&a.total_time)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    trace.sort_by_key(|call| (call.start, call.thread));
+
+    Profile {
+        functions,
+        trace,
+        is_trace_truncated,
+    }
+}
+
+/// Discards all measurements.
+pub(crate) fn reset() {
+    let mut profiler = PROFILER.lock();
+    profiler.start = Instant::now();
+    for thread in &profiler.threads {
+        let mut thread = thread.lock();
+        for function in thread.functions.iter_mut() {
+            function.call_count = 0;
+            function.total_time = Duration::ZERO;
+            function.self_time = Duration::ZERO;
+        }
+        thread.events.clear();
+        thread.is_trace_truncated = false;
+    }
+}
+
+/// The measurements of a single Mun function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionProfile {
+    /// The full name of the function, e.g. `foo::bar`
+    pub name: String,
+    /// The number of times the function returned
+    pub call_count: u64,
+    /// The cumulative time spent in the function, including the time spent in
+    /// the functions that it called. Recursive calls are only counted once.
+    pub total_time: Duration,
+    /// The time spent in the function itself, excluding the time spent in
+    /// other profiled functions
+    pub self_time: Duration,
+}
+
+/// A single call of a Mun function in a [`Profile`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TraceCall {
+    name: String,
+    thread: u64,
+    start: Duration,
+    duration: Duration,
+}
+
+/// The measurements of all Mun functions that were called since the profile
+/// was last reset, see [`Runtime::profile`](crate::Runtime::profile).
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    functions: Vec<FunctionProfile>,
+    trace: Vec<TraceCall>,
+    is_trace_truncated: bool,
+}
+
+impl Profile {
+    /// Returns the measurements of all functions that were called, sorted by
+    /// their total time in descending order.
+    pub fn functions(&self) -> &[FunctionProfile] {
+        &self.functions
+    }
+
+    /// Returns the measurements of the function with the specified full name,
+    /// if it was called.
+    pub fn function(&self, name: &str) -> Option<&FunctionProfile> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    /// Returns true if calls were left out of the trace, because too many
+    /// calls were made. Their measurements are still included in
+    /// [`Profile::functions`].
+    pub fn is_trace_truncated(&self) -> bool {
+        self.is_trace_truncated
+    }
+
+    /// Writes every call as a complete event in the Chrome trace event format,
+    /// which can be viewed with `chrome://tracing` or
+    /// [Perfetto](https://ui.perfetto.dev).
+    pub fn write_chrome_trace(&self, mut writer: impl io::Write) -> io::Result<()> {
+        writer.write_all(self.to_chrome_trace().as_bytes())
+    }
+
+    /// Returns every call as a complete event in the Chrome trace event
+    /// format, see [`Profile::write_chrome_trace`].
+    pub fn to_chrome_trace(&self) -> String {
+        let pid = std::process::id();
+
+        let mut json = String::from("{\"traceEvents\":[");
+        for (idx, call) in self.trace.iter().enumerate() {
+            if idx > 0 {
+                json.push(',');
+            }
+            json.push_str("\n{\"name\":");
+            write_json_string(&mut json, &call.name);
+            write!(
+                json,
+                ",\"cat\":\"mun\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":{pid},\"tid\":{}}}",
+                call.start.as_secs_f64() * 1e6,
+                call.duration.as_secs_f64() * 1e6,
+                call.thread,
+            )
+            .expect("writing to a string cannot fail");
+        }
+        json.push_str("\n],\"displayTimeUnit\":\"ns\"}\n");
+        json
+    }
+}
+
+/// Writes `value` as a JSON string literal.
+fn write_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => {
+                write!(json, "\\u{:04x}", c as u32).expect("writing to a string cannot fail");
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{FunctionProfile, Profile, TraceCall};
+
+    #[test]
+    fn chrome_trace() {
+        let profile = Profile {
+            functions: vec![FunctionProfile {
+                name: String::from("foo::\"bar\""),
+                call_count: 1,
+                total_time: Duration::from_micros(3),
+                self_time: Duration::from_micros(3),
+            }],
+            trace: vec![TraceCall {
+                name: String::from("foo::\"bar\""),
+                thread: 2,
+                start: Duration::from_nanos(1500),
+                duration: Duration::from_micros(3),
+            }],
+            is_trace_truncated: false,
+        };
+
+        let pid = std::process::id();
+        assert_eq!(
+            profile.to_chrome_trace(),
+            format!(
+                "{{\"traceEvents\":[\n{{\"name\":\"foo::\\\"bar\\\"\",\"cat\":\"mun\",\"ph\":\"X\",\"ts\":1.500,\"dur\":3.000,\"pid\":{pid},\"tid\":2}}\n],\"displayTimeUnit\":\"ns\"}}\n"
+            )
+        );
+    }
+}
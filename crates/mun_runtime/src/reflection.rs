@@ -9,6 +9,10 @@ pub trait ReturnTypeReflection: Sized {
 
     /// Returns a type hint to indicate the name of this type
     fn type_hint() -> &'static str;
+
+    /// Whether Mun functions return values of this type as structs by value,
+    /// see [`StructType::is_passed_by_value`](crate::StructType::is_passed_by_value).
+    const PASSED_BY_VALUE: bool = false;
 }
 
 /// A type to emulate dynamic typing across compilation units for statically
@@ -16,6 +20,37 @@ pub trait ReturnTypeReflection: Sized {
 pub trait ArgumentReflection: Sized {
     /// Retrieves the argument's type information.
     fn type_info(&self, runtime: &Runtime) -> Type;
+
+    /// Retrieves the type information that is shared by all values of this
+    /// type, or `None` if it depends on the value, e.g. for structs.
+    fn static_type_info() -> Option<Type> {
+        None
+    }
+
+    /// Whether values of this type are passed to Mun functions as structs by
+    /// value, see
+    /// [`StructType::is_passed_by_value`](crate::StructType::is_passed_by_value).
+    const PASSED_BY_VALUE: bool = false;
+}
+
+/// Validates that Mun functions pass values of type `ty` in the same way as
+/// values of a Rust type, which are passed as structs by value if
+/// `passed_by_value` is true.
+pub(crate) fn validate_pass_mode(ty: &Type, passed_by_value: bool) -> Result<(), String> {
+    let ty_passed_by_value = ty
+        .as_struct()
+        .is_some_and(|struct_ty| struct_ty.is_passed_by_value());
+    match (ty_passed_by_value, passed_by_value) {
+        (true, false) => Err(format!(
+            "`{}` is passed by value, it can only be marshalled as a `StructValue`",
+            ty.name()
+        )),
+        (false, true) => Err(format!(
+            "`{}` is not passed by value, it cannot be marshalled as a `StructValue`",
+            ty.name()
+        )),
+        _ => Ok(()),
+    }
 }
 
 macro_rules! impl_primitive_type {
@@ -25,6 +60,10 @@ macro_rules! impl_primitive_type {
                 fn type_info(&self, _runtime: &Runtime) -> Type {
                     <Self as HasStaticType>::type_info().clone()
                 }
+
+                fn static_type_info() -> Option<Type> {
+                    Some(<Self as HasStaticType>::type_info().clone())
+                }
             }
 
             impl ReturnTypeReflection for $ty {
@@ -63,15 +102,16 @@ macro_rules! impl_primitive_type {
                 {
                     // TODO: Avoid unsafe `read` fn by using adding `Clone` trait to T.
                     // This also requires changes to the `impl Struct`
-                    unsafe { ptr.as_ptr().read() }
+                    // The fields of packed structs are not aligned
+                    unsafe { ptr.as_ptr().read_unaligned() }
                 }
 
                 fn marshal_to_ptr(
                     value: Self,
-                    mut ptr: std::ptr::NonNull<Self::MunType>,
+                    ptr: std::ptr::NonNull<Self::MunType>,
                     _type_info: &Type,
                 ) {
-                    unsafe { *ptr.as_mut() = value };
+                    unsafe { ptr.as_ptr().write_unaligned(value) };
                 }
             }
         )+
@@ -104,6 +144,10 @@ where
     fn type_info(&self, _runtime: &Runtime) -> Type {
         <Self as HasStaticType>::type_info().clone()
     }
+
+    fn static_type_info() -> Option<Type> {
+        Some(<Self as HasStaticType>::type_info().clone())
+    }
 }
 
 impl<T> ReturnTypeReflection for *const T
@@ -126,6 +170,10 @@ where
     fn type_info(&self, _runtime: &Runtime) -> Type {
         <Self as HasStaticType>::type_info().clone()
     }
+
+    fn static_type_info() -> Option<Type> {
+        Some(<Self as HasStaticType>::type_info().clone())
+    }
 }
 
 impl<T> ReturnTypeReflection for *mut T
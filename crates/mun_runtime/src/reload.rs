@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use crate::{DispatchTable, LinkError};
+
+/// A hook that is called when the [`Runtime`](crate::Runtime) reloads
+/// assemblies, see [`Runtime::on_reload`](crate::Runtime::on_reload).
+pub(crate) type ReloadHook = Box<dyn FnMut(&ReloadEvent<'_>) + Send>;
+
+/// An event that is emitted when the [`Runtime`](crate::Runtime) reloads
+/// assemblies.
+#[derive(Debug)]
+pub enum ReloadEvent<'a> {
+    /// The assemblies that were loaded from the specified paths are about to be
+    /// reloaded.
+    ///
+    /// Hooks can still invoke functions and query the runtime, which uses the
+    /// previously linked assemblies until the reload completes. Once the hooks
+    /// return, the reload waits for the functions that are being invoked on
+    /// other threads to finish.
+    Before {
+        /// The paths of the assemblies that changed, sorted
+        assemblies: &'a [PathBuf],
+    },
+    /// The assemblies were successfully reloaded and swapped in. Hooks can
+    /// invalidate data that was derived from the previously linked assemblies
+    /// and fetch new function handles.
+    After(&'a ReloadReport),
+    /// Reloading the assemblies failed. The previously linked assemblies remain
+    /// in use.
+    Failed(&'a LinkError),
+}
+
+/// Describes the changes made by a successful reload of assemblies.
+#[derive(Clone, Debug, Default)]
+pub struct ReloadReport {
+    /// The [epoch](crate::Runtime::epoch) that the runtime entered with the
+    /// reload
+    pub epoch: u64,
+    /// The paths of the shared libraries of the reloaded assemblies, sorted
+    pub assemblies: Vec<PathBuf>,
+    /// Whether only the bodies of functions changed, in which case the
+    /// pointers to the functions of the rebuilt assemblies were swapped into
+    /// the dispatch tables and no memory was mapped
+    pub patched: bool,
+    /// The names of the functions whose signature changed, sorted
+    pub changed_functions: Vec<String>,
+    /// The names of the functions that were added, sorted
+    pub added_functions: Vec<String>,
+    /// The names of the functions that were removed, sorted
+    pub removed_functions: Vec<String>,
+    /// The names of the types whose layout changed and whose allocated objects
+    /// were therefore remapped, sorted
+    pub remapped_types: Vec<String>,
+    /// The names of the types that were removed, sorted
+    pub deleted_types: Vec<String>,
+    /// The number of allocated objects that were migrated to the layout of
+    /// their new type
+    pub migrated_objects: usize,
+    /// The number of allocated objects whose type was removed. These objects
+    /// can no longer be accessed from Mun code.
+    pub deleted_objects: usize,
+}
+
+impl ReloadReport {
+    /// Records the functions that were added, removed, or whose signature
+    /// changed between the `old` and the `new` dispatch table.
+    pub(crate) fn diff_functions(&mut self, old: &DispatchTable, new: &DispatchTable) {
+        for name in new.get_fn_names() {
+            let new_fn = new.get_fn(name).expect("Function must exist.");
+            match old.get_fn(name) {
+                Some(old_fn) if old_fn.prototype.signature != new_fn.prototype.signature => {
+                    self.changed_functions.push(name.to_owned());
+                }
+                Some(_) => (),
+                None => self.added_functions.push(name.to_owned()),
+            }
+        }
+
+        self.removed_functions.extend(
+            old.get_fn_names()
+                .filter(|name| new.get_fn(name).is_none())
+                .map(ToOwned::to_owned),
+        );
+
+        self.changed_functions.sort();
+        self.added_functions.sort();
+        self.removed_functions.sort();
+    }
+}
@@ -0,0 +1,92 @@
+use std::ptr::NonNull;
+
+use mun_memory::Type;
+
+use crate::{
+    adt::{RawStruct, StructRef},
+    marshal::Marshal,
+    reflection::ReturnTypeReflection,
+    Runtime,
+};
+
+/// Mun represents a `Result<T, E>` as a value struct with the fields `is_err`,
+/// `ok`, and `err`. Only the field that corresponds to `is_err` holds a valid
+/// value.
+fn from_struct_ref<'t, T, E>(result: StructRef<'t>) -> Result<T, E>
+where
+    T: ReturnTypeReflection + Marshal<'t> + 't,
+    E: ReturnTypeReflection + Marshal<'t> + 't,
+{
+    // The field types have already been verified by `accepts_type`
+    if result
+        .get::<bool>("is_err")
+        .expect("missing `is_err` field")
+    {
+        Err(result.get("err").expect("missing `err` field"))
+    } else {
+        Ok(result.get("ok").expect("missing `ok` field"))
+    }
+}
+
+impl<T: ReturnTypeReflection, E: ReturnTypeReflection> ReturnTypeReflection for Result<T, E> {
+    fn accepts_type(ty: &Type) -> bool {
+        let Some(s) = ty.as_struct() else {
+            return false;
+        };
+
+        let fields = s.fields();
+        let field_accepts = |name: &str, accepts_type: fn(&Type) -> bool| {
+            fields
+                .find_by_name(name)
+                .is_some_and(|field| accepts_type(&field.ty()))
+        };
+
+        ty.name().starts_with("Result<")
+            && fields.len() == 3
+            && field_accepts("is_err", bool::accepts_type)
+            && field_accepts("ok", T::accepts_type)
+            && field_accepts("err", E::accepts_type)
+    }
+
+    fn type_hint() -> &'static str {
+        "Result"
+    }
+}
+
+impl<'t, T, E> Marshal<'t> for Result<T, E>
+where
+    T: ReturnTypeReflection + Marshal<'t> + 't,
+    E: ReturnTypeReflection + Marshal<'t> + 't,
+{
+    type MunType = RawStruct;
+
+    fn marshal_from<'r>(value: Self::MunType, runtime: &'r Runtime) -> Self
+    where
+        Self: 't,
+        'r: 't,
+    {
+        from_struct_ref(StructRef::marshal_from(value, runtime))
+    }
+
+    fn marshal_into(self) -> Self::MunType {
+        // `Result` does not implement `ArgumentReflection`, so it is never
+        // passed to Mun.
+        unreachable!("a `Result` cannot be marshalled into Mun")
+    }
+
+    fn marshal_from_ptr<'r>(
+        ptr: NonNull<Self::MunType>,
+        runtime: &'r Runtime,
+        type_info: &Type,
+    ) -> Self
+    where
+        Self: 't,
+        'r: 't,
+    {
+        from_struct_ref(StructRef::marshal_from_ptr(ptr, runtime, type_info))
+    }
+
+    fn marshal_to_ptr(_value: Self, _ptr: NonNull<Self::MunType>, _type_info: &Type) {
+        unreachable!("a `Result` cannot be marshalled into Mun")
+    }
+}
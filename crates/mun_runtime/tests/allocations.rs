@@ -0,0 +1,149 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use mun_runtime::{HasStaticType, ReprCStruct, StructRef, StructValue, Type};
+use mun_test::CompileAndRunTestDriver;
+
+/// An allocator that counts the number of allocations made by each thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the number of allocations that the current thread made while
+/// running `f`.
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn invoke_primitives_does_not_allocate() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    // The first invocation initializes the static type information
+    let result: i32 = driver.runtime.invoke("add", (1i32, 2i32)).unwrap();
+    assert_eq!(result, 3);
+
+    let allocations = count_allocations(|| {
+        for i in 0..1000i32 {
+            let result: i32 = driver.runtime.invoke("add", (i, 1i32)).unwrap();
+            assert_eq!(result, i + 1);
+        }
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn invoke_struct_by_value_does_not_allocate() {
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Vector2 {
+        x: f32,
+        y: f32,
+    }
+
+    unsafe impl ReprCStruct for Vector2 {
+        const NAME: &'static str = "Vector2";
+
+        fn fields() -> Vec<(&'static str, Type)> {
+            vec![
+                ("x", f32::type_info().clone()),
+                ("y", f32::type_info().clone()),
+            ]
+        }
+    }
+
+    let driver = CompileAndRunTestDriver::new(
+        r#"
+    #[repr(C)]
+    pub struct(value) Vector2 { x: f32, y: f32 }
+
+    pub fn add(a: Vector2, b: Vector2) -> Vector2 {
+        Vector2 { x: a.x + b.x, y: a.y + b.y }
+    }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let a = StructValue(Vector2 { x: 1.0, y: 2.0 });
+    let b = StructValue(Vector2 { x: 3.0, y: 4.0 });
+
+    // The first invocation initializes the static type information
+    let sum: StructValue<Vector2> = driver.runtime.invoke("add", (a, b)).unwrap();
+    assert_eq!(sum.into_inner(), Vector2 { x: 4.0, y: 6.0 });
+
+    let allocations = count_allocations(|| {
+        for _ in 0..1000 {
+            let sum: StructValue<Vector2> = driver.runtime.invoke("add", (a, b)).unwrap();
+            assert_eq!(sum.into_inner(), Vector2 { x: 4.0, y: 6.0 });
+        }
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn invoke_structs_reuses_collected_memory() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct(value) Vec3 { x: f32, y: f32, z: f32 }
+
+    pub fn vec3(x: f32, y: f32, z: f32) -> Vec3 { Vec3 { x, y, z } }
+
+    pub fn scale(v: Vec3, s: f32) -> Vec3 { Vec3 { x: v.x * s, y: v.y * s, z: v.z * s } }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+    let runtime = &driver.runtime;
+
+    let v: StructRef<'_> = runtime.invoke("vec3", (1f32, 2f32, 3f32)).unwrap();
+    let v = v.root();
+
+    // Simulates a frame that invokes the function many times and collects the
+    // garbage at the end
+    let frame = || {
+        count_allocations(|| {
+            for _ in 0..100 {
+                let scaled: StructRef<'_> =
+                    runtime.invoke("scale", (v.as_ref(runtime), 2f32)).unwrap();
+                assert_eq!(scaled.get::<f32>("z").unwrap(), 6.0);
+            }
+        })
+    };
+
+    // The first frame allocates the memory for the returned structs
+    assert!(frame() > 0);
+    assert!(runtime.gc_collect());
+
+    // Subsequent frames reuse the memory of the collected structs
+    for _ in 0..10 {
+        assert_eq!(frame(), 0);
+        assert!(runtime.gc_collect());
+    }
+}
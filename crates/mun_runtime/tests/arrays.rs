@@ -114,3 +114,66 @@ fn construct_array_simple() {
     assert_eq!(array.len(), test_data.len());
     assert_eq!(array.iter().collect_vec(), test_data);
 }
+
+#[test]
+fn for_loops() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn sum_range(n: i32) -> i32 {
+        let sum = 0;
+        for i in 0..n {
+            sum += i;
+        }
+        sum
+    }
+    pub fn sum_inclusive_range(n: u8) -> u32 {
+        let sum = 0;
+        for i in 0..=n {
+            sum += i as u32;
+        }
+        sum
+    }
+    pub fn sum_array(array: [i32]) -> i32 {
+        let sum = 0;
+        for value in array {
+            sum += value;
+        }
+        sum
+    }
+    pub fn sum_array_literal() -> i32 {
+        let sum = 0;
+        for value in [5, 4, 3, 2, 1] {
+            if value == 2 {
+                break;
+            }
+            sum += value;
+        }
+        sum
+    }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let result: i32 = driver.runtime.invoke("sum_range", (10i32,)).unwrap();
+    assert_eq!(result, 45);
+    let result: i32 = driver.runtime.invoke("sum_range", (-1i32,)).unwrap();
+    assert_eq!(result, 0);
+
+    // Iterating up to the maximum value of a type must not overflow
+    let result: u32 = driver
+        .runtime
+        .invoke("sum_inclusive_range", (u8::MAX,))
+        .unwrap();
+    assert_eq!(result, 32640);
+
+    let array = driver.runtime.construct_array([1, 2, 3, 4]);
+    let result: i32 = driver.runtime.invoke("sum_array", (array,)).unwrap();
+    assert_eq!(result, 10);
+    let array = driver.runtime.construct_array(std::iter::empty::<i32>());
+    let result: i32 = driver.runtime.invoke("sum_array", (array,)).unwrap();
+    assert_eq!(result, 0);
+
+    let result: i32 = driver.runtime.invoke("sum_array_literal", ()).unwrap();
+    assert_eq!(result, 12);
+}
@@ -0,0 +1,253 @@
+use std::time::{Duration, Instant};
+
+use mun_runtime::{CoroutineError, CoroutineState, Runtime, StructRef};
+use mun_test::{CompileAndRunTestDriver, CompileTestDriver};
+
+const WAIT: &str = r"
+    pub resumable fn wait(seconds: f32) {
+        let elapsed = 0.0;
+        while elapsed < seconds {
+            elapsed += yield;
+        }
+    }
+    ";
+
+#[test]
+fn resume_until_complete() {
+    let driver =
+        CompileAndRunTestDriver::new(WAIT, |builder| builder).expect("Failed to build test driver");
+
+    let mut coroutine = driver
+        .runtime
+        .start_coroutine("wait", (1.0f32,))
+        .expect("Failed to start coroutine");
+    assert_eq!(coroutine.function_name(), "wait");
+
+    // The first resumption runs up to the first `yield`, its value is ignored
+    assert_eq!(coroutine.resume(10.0), Ok(CoroutineState::Suspended));
+    assert_eq!(coroutine.resume(0.4), Ok(CoroutineState::Suspended));
+    assert_eq!(coroutine.resume(0.4), Ok(CoroutineState::Suspended));
+    assert_eq!(coroutine.resume(0.4), Ok(CoroutineState::Complete));
+    assert!(coroutine.is_complete());
+
+    assert_eq!(coroutine.resume(0.4), Err(CoroutineError::Complete));
+}
+
+#[test]
+fn resume_nested_resumable_function() {
+    let driver = CompileAndRunTestDriver::new(
+        &format!(
+            r"
+    {WAIT}
+
+    pub struct(gc) Door {{ open: bool }}
+
+    pub fn new_door() -> Door {{ Door {{ open: false }} }}
+
+    pub resumable fn open(door: Door) {{
+        wait(1.0);
+        door.open = true;
+    }}
+    "
+        ),
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = &driver.runtime;
+    let door: StructRef<'_> = runtime.invoke("new_door", ()).unwrap();
+    let door = door.root();
+
+    let mut coroutine = runtime
+        .start_coroutine("open", (door.as_ref(runtime),))
+        .expect("Failed to start coroutine");
+
+    let mut frames = 0;
+    while coroutine.resume(0.25) == Ok(CoroutineState::Suspended) {
+        assert!(!door.as_ref(runtime).get::<bool>("open").unwrap());
+        frames += 1;
+    }
+    assert!(coroutine.is_complete());
+    assert_eq!(frames, 4);
+    assert!(door.as_ref(runtime).get::<bool>("open").unwrap());
+}
+
+#[test]
+fn suspended_coroutine_keeps_objects_alive() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct(gc) Door { open: bool }
+
+    pub resumable fn hold() {
+        let door = Door { open: false };
+        yield;
+        door.open = true;
+    }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = &driver.runtime;
+    let mut coroutine = runtime
+        .start_coroutine("hold", ())
+        .expect("Failed to start coroutine");
+    assert_eq!(coroutine.resume(0.0), Ok(CoroutineState::Suspended));
+
+    // The door is only referenced from the frame of the coroutine
+    assert!(!runtime.gc_collect());
+    assert!(runtime.gc_stats().allocated_memory > 0);
+
+    assert_eq!(coroutine.resume(0.0), Ok(CoroutineState::Complete));
+    assert!(runtime.gc_collect());
+    assert_eq!(runtime.gc_stats().allocated_memory, 0);
+}
+
+#[test]
+fn gc_stress_mode_keeps_objects_referenced_from_coroutine_frames() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct(gc) Door { open: bool }
+
+    pub resumable fn swap() {
+        let door = Door { open: false };
+        yield;
+        let other = Door { open: true };
+        door.open = other.open;
+        yield;
+        door.open = !door.open;
+    }
+    ",
+        |builder| builder.gc_stress_mode(true),
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = &driver.runtime;
+    let mut coroutine = runtime
+        .start_coroutine("swap", ())
+        .expect("Failed to start coroutine");
+    while coroutine.resume(0.0) == Ok(CoroutineState::Suspended) {}
+    assert!(coroutine.is_complete());
+
+    assert!(runtime.gc_collect());
+    assert_eq!(runtime.gc_stats().allocated_memory, 0);
+}
+
+#[test]
+fn drop_suspended_coroutine() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct(gc) Door { open: bool }
+
+    pub resumable fn hold() {
+        let door = Door { open: false };
+        yield;
+        door.open = true;
+    }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = &driver.runtime;
+    let mut coroutine = runtime
+        .start_coroutine("hold", ())
+        .expect("Failed to start coroutine");
+    assert_eq!(coroutine.resume(0.0), Ok(CoroutineState::Suspended));
+    drop(coroutine);
+
+    assert!(runtime.gc_collect());
+    assert_eq!(runtime.gc_stats().allocated_memory, 0);
+}
+
+#[test]
+fn invoke_resumable_function() {
+    let driver =
+        CompileAndRunTestDriver::new(WAIT, |builder| builder).expect("Failed to build test driver");
+
+    let result: Result<(), _> = driver.runtime.invoke("wait", (1.0f32,));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "'wait' is a resumable function, use `Runtime::start_coroutine` to start it instead."
+    );
+}
+
+#[test]
+fn start_coroutine_of_normal_function() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let err = driver
+        .runtime
+        .start_coroutine("main", ())
+        .err()
+        .expect("a normal function cannot be started as a coroutine");
+    assert_eq!(
+        err.to_string(),
+        "'main' is not a resumable function, use `Runtime::invoke` to invoke it instead."
+    );
+}
+
+#[test]
+fn start_coroutine_with_invalid_arguments() {
+    let driver =
+        CompileAndRunTestDriver::new(WAIT, |builder| builder).expect("Failed to build test driver");
+
+    assert!(driver.runtime.start_coroutine("wait", (1i32,)).is_err());
+    assert!(driver.runtime.start_coroutine("wait", ()).is_err());
+}
+
+#[test]
+fn reload_invalidates_coroutine() {
+    let mut driver = CompileTestDriver::from_file(WAIT);
+
+    // Safety: We compiled the library ourselves, therefor loading the library is
+    // safe.
+    let runtime = unsafe { Runtime::builder(driver.lib_path()).finish() }
+        .expect("Failed to construct runtime");
+    let mut coroutine = runtime
+        .start_coroutine("wait", (1.0f32,))
+        .expect("Failed to start coroutine");
+    assert_eq!(coroutine.resume(0.0), Ok(CoroutineState::Suspended));
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub resumable fn wait(seconds: f32) {
+        let elapsed = 0.0;
+        while elapsed < seconds * 2.0 {
+            elapsed += yield;
+        }
+    }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefor updating the runtime is
+    // safe.
+    let start_time = Instant::now();
+    while !unsafe { runtime.update() } {
+        assert!(
+            start_time.elapsed() < Duration::from_secs(10),
+            "runtime did not update after recompilation within 10 seconds"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(coroutine.resume(0.5), Err(CoroutineError::Invalidated));
+    assert_eq!(coroutine.resume(0.5), Err(CoroutineError::Invalidated));
+
+    // Coroutines that are started after the reload use the reloaded function
+    let mut coroutine = runtime
+        .start_coroutine("wait", (1.0f32,))
+        .expect("Failed to start coroutine");
+    let mut frames = 0;
+    while coroutine.resume(0.5) == Ok(CoroutineState::Suspended) {
+        frames += 1;
+    }
+    assert_eq!(frames, 4);
+}
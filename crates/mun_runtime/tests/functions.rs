@@ -1,6 +1,7 @@
 #[macro_use]
 mod util;
 
+use mun_runtime::StructRef;
 use mun_test::CompileAndRunTestDriver;
 
 #[test]
@@ -117,7 +118,376 @@ fn multiple_match_exists_function() {
     assert_eq!(
         err.to_string(),
         format!(
-            "failed to obtain function '{EXPECTED_FN_NAME}', no such function exists. There is a function with a similar name: foobar_b"
+            "failed to obtain function '{EXPECTED_FN_NAME}', no such function exists. There is a function with a similar name: foobar_a"
         )
     );
 }
+
+#[test]
+fn test_functions() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 5 }
+
+    #[test]
+    fn test_main() -> bool { main() == 5 }
+
+    #[test]
+    fn a_test() {}
+
+    #[bench]
+    fn bench_main() { main(); }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    assert_eq!(driver.runtime.test_functions(), vec!["a_test", "test_main"]);
+    assert_eq!(driver.runtime.bench_functions(), vec!["bench_main"]);
+    assert_invoke_eq!(bool, true, driver, "test_main");
+}
+
+#[test]
+fn invoke_batch() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let arguments: Vec<(i32, i32)> = (0..100).map(|i| (i, i * 2)).collect();
+    let expected: Vec<i32> = (0..100).map(|i| i * 3).collect();
+
+    let result: Vec<i32> = driver
+        .runtime
+        .invoke_batch("add", &arguments)
+        .expect("Failed to call function");
+    assert_eq!(result, expected);
+
+    let result: Vec<i32> = driver
+        .runtime
+        .invoke_batch_parallel("add", &arguments)
+        .expect("Failed to call function");
+    assert_eq!(result, expected);
+
+    // Invocations run on the thread pool of the caller
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .expect("Failed to build thread pool");
+    let result: Vec<i32> = pool
+        .install(|| driver.runtime.invoke_batch_parallel("add", &arguments))
+        .expect("Failed to call function");
+    assert_eq!(result, expected);
+
+    let result: Result<Vec<i32>, _> = driver.runtime.invoke_batch("add", &[(1i64, 2i64)]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn function_handle() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let add = driver
+        .runtime
+        .get_function::<(i32, i32), i32>("add")
+        .expect("Failed to get function");
+    assert_eq!(add.function_name(), "add");
+    assert_eq!(add.invoke((1, 2)).unwrap(), 3);
+    assert_eq!(add.invoke((3, 4)).unwrap(), 7);
+
+    assert!(driver
+        .runtime
+        .get_function::<(i64, i64), i32>("add")
+        .is_err());
+    assert!(driver
+        .runtime
+        .get_function::<(i32, i32), f32>("add")
+        .is_err());
+    assert!(driver.runtime.get_function::<(), ()>("sub").is_err());
+}
+
+#[test]
+fn core_package() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn clamped(value: f32) -> f32 { clamp(value, 0.0, 1.0) }
+    pub fn distance(a: i32, b: i32) -> i32 { core::math::abs_i32(a - b) }
+    pub fn check_positive(value: i32) { assert(value > 0) }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let clamped: f32 = driver.runtime.invoke("clamped", (2.5f32,)).unwrap();
+    assert_eq!(clamped, 1.0);
+    let distance: i32 = driver.runtime.invoke("distance", (3i32, 8i32)).unwrap();
+    assert_eq!(distance, 5);
+
+    driver
+        .runtime
+        .invoke::<(), _>("check_positive", (1i32,))
+        .unwrap();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        driver.runtime.invoke::<(), _>("check_positive", (-1i32,))
+    }));
+    assert!(result.is_err());
+}
+
+#[test]
+fn core_assert_panic_is_caught() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn fail() { assert(false) }
+    pub fn fail_with(value: i32) -> i32 { assert(false); value }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        driver.runtime.invoke::<(), _>("fail", ())
+    }));
+    assert!(result.is_err());
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        driver
+            .runtime
+            .invoke_batch::<i32, _>("fail_with", &[(1i32,), (2i32,)])
+    }));
+    assert!(result.is_err());
+
+    let fail = driver
+        .runtime
+        .get_function::<(), ()>("fail")
+        .expect("Failed to get function");
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| fail.invoke(())));
+    assert!(result.is_err());
+
+    // The runtime remains usable after a panic unwound through it
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        driver.runtime.invoke::<(), _>("fail", ())
+    }));
+    assert!(result.is_err());
+}
+
+#[test]
+fn overflow_checks() {
+    let driver = CompileAndRunTestDriver::from_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name="foo"
+    version="0.0.0"
+    overflow-checks=true
+
+    //- /src/mod.mun
+    pub fn add(a: u8, b: u8) -> u8 { a + b }
+    pub fn negate(a: i32) -> i32 { -a }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let sum: u8 = driver.runtime.invoke("add", (200u8, 55u8)).unwrap();
+    assert_eq!(sum, 255);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        driver.runtime.invoke::<u8, _>("add", (200u8, 56u8))
+    }));
+    let payload = result.expect_err("overflow did not trap");
+    assert_eq!(
+        payload.downcast_ref::<mun_runtime::OverflowError>(),
+        Some(&mun_runtime::OverflowError(mun_runtime::OverflowOp::Add))
+    );
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        driver.runtime.invoke::<i32, _>("negate", (i32::MIN,))
+    }));
+    let payload = result.expect_err("overflow did not trap");
+    assert_eq!(
+        payload.downcast_ref::<mun_runtime::OverflowError>(),
+        Some(&mun_runtime::OverflowError(mun_runtime::OverflowOp::Negate))
+    );
+
+    let add = driver
+        .runtime
+        .get_function::<(u8, u8), u8>("add")
+        .expect("Failed to get function");
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| add.invoke((255, 1))));
+    let payload = result.expect_err("overflow did not trap");
+    assert_eq!(
+        payload.downcast_ref::<mun_runtime::OverflowError>(),
+        Some(&mun_runtime::OverflowError(mun_runtime::OverflowOp::Add))
+    );
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        driver
+            .runtime
+            .invoke_batch::<u8, _>("add", &[(1u8, 2u8), (255u8, 1u8)])
+    }));
+    let payload = result.expect_err("overflow did not trap");
+    assert_eq!(
+        payload.downcast_ref::<mun_runtime::OverflowError>(),
+        Some(&mun_runtime::OverflowError(mun_runtime::OverflowOp::Add))
+    );
+}
+
+#[test]
+fn std_package() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn hypot(a: f64, b: f64) -> f64 { std::math::sqrt(a * a + b * b) }
+    pub fn halfway(a: f64, b: f64) -> f64 { std::lerp(a, b, 0.5) }
+    pub fn roll() -> f64 { std::random::random_range(1.0, 7.0) }
+    pub fn reseed(seed: u64) { std::random::seed(seed) }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let hypot: f64 = driver.runtime.invoke("hypot", (3.0f64, 4.0f64)).unwrap();
+    assert_eq!(hypot, 5.0);
+    let halfway: f64 = driver.runtime.invoke("halfway", (2.0f64, 4.0f64)).unwrap();
+    assert_eq!(halfway, 3.0);
+
+    driver.runtime.invoke::<(), _>("reseed", (7u64,)).unwrap();
+    let first: f64 = driver.runtime.invoke("roll", ()).unwrap();
+    driver.runtime.invoke::<(), _>("reseed", (7u64,)).unwrap();
+    let second: f64 = driver.runtime.invoke("roll", ()).unwrap();
+    assert_eq!(first, second);
+    assert!((1.0..7.0).contains(&first));
+}
+
+#[test]
+fn numeric_casts() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn truncate(a: i32) -> u8 { a as u8 }
+    pub fn sign_extend(a: i8) -> i64 { a as i64 }
+    pub fn zero_extend(a: u8) -> i64 { a as i64 }
+    pub fn float_to_int(a: f64) -> i16 { a as i16 }
+    pub fn float_to_uint(a: f32) -> u32 { a as u32 }
+    pub fn int_to_float(a: u64) -> f64 { a as f64 }
+    pub fn narrow_float(a: f64) -> f32 { a as f32 }
+    pub fn bool_to_int(a: bool) -> usize { a as usize }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let truncated: u8 = driver.runtime.invoke("truncate", (300i32,)).unwrap();
+    assert_eq!(truncated, 44);
+    let sign_extended: i64 = driver.runtime.invoke("sign_extend", (-2i8,)).unwrap();
+    assert_eq!(sign_extended, -2);
+    let zero_extended: i64 = driver.runtime.invoke("zero_extend", (254u8,)).unwrap();
+    assert_eq!(zero_extended, 254);
+
+    for value in [-3.9f64, 1e10, -1e10, f64::NAN] {
+        let result: i16 = driver.runtime.invoke("float_to_int", (value,)).unwrap();
+        assert_eq!(result, value as i16);
+    }
+    for value in [3.9f32, -1.0, 1e20, f32::INFINITY, f32::NAN] {
+        let result: u32 = driver.runtime.invoke("float_to_uint", (value,)).unwrap();
+        assert_eq!(result, value as u32);
+    }
+
+    let float: f64 = driver.runtime.invoke("int_to_float", (u64::MAX,)).unwrap();
+    assert_eq!(float, u64::MAX as f64);
+    let narrowed: f32 = driver.runtime.invoke("narrow_float", (0.1f64,)).unwrap();
+    assert_eq!(narrowed, 0.1f32);
+    let int: usize = driver.runtime.invoke("bool_to_int", (true,)).unwrap();
+    assert_eq!(int, 1);
+}
+
+#[test]
+fn result_return_type() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    struct(value) Error { code: i32 }
+
+    fn check(a: i32) -> Result<i32, Error> {
+        if a < 0 { Err(Error { code: a }) } else { Ok(a) }
+    }
+
+    pub fn double(a: i32) -> Result<i32, Error> {
+        let b = check(a)?;
+        Ok(b * 2)
+    }
+
+    pub fn positive(a: f32) -> Result<f32, bool> {
+        if a > 0.0 { Ok(a) } else { Err(a == 0.0) }
+    }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let result: Result<i32, StructRef<'_>> = driver.runtime.invoke("double", (21i32,)).unwrap();
+    assert_eq!(result.ok(), Some(42));
+
+    let result: Result<i32, StructRef<'_>> = driver.runtime.invoke("double", (-3i32,)).unwrap();
+    let error = result.expect_err("expected an error");
+    assert_eq!(error.get::<i32>("code").unwrap(), -3);
+
+    let result: Result<f32, bool> = driver.runtime.invoke("positive", (0.0f32,)).unwrap();
+    assert_eq!(result, Err(true));
+
+    let result: Result<Result<f32, i32>, _> = driver.runtime.invoke("positive", (1.0f32,));
+    assert!(result.is_err(), "mismatched error types must be rejected");
+}
+
+#[test]
+fn private_functions_are_not_accessible() {
+    let driver = CompileAndRunTestDriver::from_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name="foo"
+    version="0.0.0"
+
+    //- /src/mod.mun
+    use package::foo::bar;
+    fn secret() -> i32 { 5 }
+    pub(package) fn package_secret() -> i32 { 6 }
+    pub fn main() -> i32 { bar() }
+
+    //- /src/foo.mun
+    use super::{package_secret, secret};
+    pub fn bar() -> i32 { secret() + package_secret() }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    // Private functions are still linked by other modules
+    let result: i32 = driver.runtime.invoke("main", ()).unwrap();
+    assert_eq!(result, 11);
+
+    // But they cannot be accessed by the host
+    assert!(driver.runtime.get_function_definition("secret").is_none());
+    assert!(driver
+        .runtime
+        .get_function_definition("package_secret")
+        .is_none());
+    assert!(driver.runtime.invoke::<i32, _>("secret", ()).is_err());
+
+    // Nor are they part of the functions that the assemblies export to the host
+    let mut exported_functions: Vec<String> = driver
+        .runtime
+        .assemblies()
+        .iter()
+        .flat_map(|assembly| assembly.functions.iter())
+        .map(|function| function.prototype.name.clone())
+        .collect();
+    exported_functions.sort();
+    assert_eq!(exported_functions, ["foo::bar", "main"]);
+}
@@ -1,8 +1,13 @@
 #[macro_use]
 mod util;
 
-use mun_runtime::StructRef;
-use mun_test::CompileAndRunTestDriver;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use mun_runtime::{ReloadEvent, Runtime, StructRef};
+use mun_test::{CompileAndRunTestDriver, CompileTestDriver};
 
 #[test]
 fn reloadable_function_single_file() {
@@ -54,6 +59,201 @@ fn reloadable_function_multi_file() {
     assert_invoke_eq!(i32, 10, driver, "main");
 }
 
+#[test]
+fn reloadable_function_in_memory() {
+    let mut driver = CompileAndRunTestDriver::from_fixture(
+        r#"
+    //- /mun.toml
+    [package]
+    name="foo"
+    version="0.0.0"
+
+    //- /src/mod.mun
+    use package::foo::bar;
+    pub fn main() -> i32 { bar() }
+
+    //- /src/foo.mun
+    pub fn bar() -> i32 { 5 }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+    assert_invoke_eq!(i32, 5, driver, "main");
+
+    driver.update_file_in_memory(
+        "foo.mun",
+        r#"
+    pub fn bar() -> i32 { 10 }
+    "#,
+    );
+    assert_invoke_eq!(i32, 10, driver, "main");
+}
+
+#[test]
+fn reloadable_function_handle() {
+    let mut driver = CompileTestDriver::from_file(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefor loading the library is
+    // safe.
+    let runtime = unsafe { Runtime::builder(driver.lib_path()).finish() }
+        .expect("Failed to construct runtime");
+    let main = runtime
+        .get_function::<(), i32>("main")
+        .expect("Failed to get function");
+    assert_eq!(main.invoke(()).unwrap(), 5);
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub fn main() -> i32 { 10 }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefor updating the runtime is
+    // safe.
+    let start_time = Instant::now();
+    while !unsafe { runtime.update() } {
+        assert!(
+            start_time.elapsed() < Duration::from_secs(10),
+            "runtime did not update after recompilation within 10 seconds"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    // The handle is bound to the reloaded function
+    assert_eq!(main.invoke(()).unwrap(), 10);
+}
+
+#[test]
+fn reloadable_function_handle_changed_signature() {
+    let mut driver = CompileTestDriver::from_file(
+        r"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefor loading the library is
+    // safe.
+    let runtime = unsafe { Runtime::builder(driver.lib_path()).finish() }
+        .expect("Failed to construct runtime");
+    let add = runtime
+        .get_function::<(i32, i32), i32>("add")
+        .expect("Failed to get function");
+    assert_eq!(add.invoke((2, 3)).unwrap(), 5);
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub fn add(a: i64, b: i64) -> i64 { a + b }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefor updating the runtime is
+    // safe.
+    let start_time = Instant::now();
+    while !unsafe { runtime.update() } {
+        assert!(
+            start_time.elapsed() < Duration::from_secs(10),
+            "runtime did not update after recompilation within 10 seconds"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    // The handle cannot be bound to a function with a different signature
+    assert!(add.invoke((2, 3)).is_err());
+}
+
+#[test]
+fn reloadable_function_body_only() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct(gc) Foo { a: i32 }
+    pub fn new_foo() -> Foo { Foo { a: 5 } }
+    pub fn get(foo: Foo) -> i32 { foo.a }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let foo_struct: StructRef<'_> = driver
+        .runtime
+        .invoke("new_foo", ())
+        .expect("Failed to call function");
+    let foo_struct = foo_struct.root();
+    let foo_type = driver
+        .runtime
+        .get_type_info_by_name("Foo")
+        .expect("Failed to get type");
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub struct(gc) Foo { a: i32 }
+    pub fn new_foo() -> Foo { Foo { a: 5 } }
+    pub fn get(foo: Foo) -> i32 { foo.a * 2 }
+    ",
+    );
+
+    // Only a function body changed, so the existing type should be reused
+    assert_eq!(
+        driver
+            .runtime
+            .get_type_info_by_name("Foo")
+            .expect("Failed to get type"),
+        foo_type
+    );
+
+    let foo_struct = foo_struct.as_ref(&driver.runtime);
+    assert_invoke_eq!(i32, 10, driver, "get", foo_struct);
+}
+
+#[test]
+fn reloadable_struct_memory_kind() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct(gc) Foo { a: i32 }
+    pub fn get() -> i32 { 5 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    {
+        let reports = reports.clone();
+        driver.runtime.on_reload(move |event| {
+            if let ReloadEvent::After(report) = event {
+                reports.lock().unwrap().push(report.patched);
+            }
+        });
+    }
+
+    // The guid of a struct does not depend on its memory kind, but the type must
+    // still be relinked rather than patched.
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub struct(value) Foo { a: i32 }
+    pub fn get() -> i32 { 10 }
+    ",
+    );
+
+    assert_eq!(reports.lock().unwrap().as_slice(), &[false]);
+    let foo_type = driver
+        .runtime
+        .get_type_info_by_name("Foo")
+        .expect("Failed to get type");
+    assert!(foo_type
+        .as_struct()
+        .expect("Foo is not a struct")
+        .is_value_struct());
+    assert_invoke_eq!(i32, 10, driver, "get");
+}
+
 #[test]
 fn reloadable_struct_decl_single_file() {
     let mut driver = CompileAndRunTestDriver::new(
@@ -117,6 +317,110 @@ fn reloadable_struct_decl_single_file() {
     );
 }
 
+#[test]
+fn reloadable_renamed_field() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct(gc) Foo { a: i32, b: f64 }
+    pub fn new_foo() -> Foo { Foo { a: 5, b: 1.0 } }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let foo_struct: StructRef<'_> = driver
+        .runtime
+        .invoke("new_foo", ())
+        .expect("Failed to call function");
+    let foo_struct = foo_struct.root();
+
+    // Changing both the name and the type of a field cannot be detected as a rename
+    driver.runtime.rename_field("Foo", "a", "c");
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub struct(gc) Foo { c: i64, b: f64 }
+    pub fn new_foo() -> Foo { Foo { c: 5, b: 1.0 } }
+    ",
+    );
+
+    let foo_struct = foo_struct.as_ref(&driver.runtime);
+    assert_eq!(
+        foo_struct
+            .get::<i64>("c")
+            .expect("Failed to get struct field"),
+        5
+    );
+    assert_eq!(
+        foo_struct
+            .get::<f64>("b")
+            .expect("Failed to get struct field"),
+        1.0
+    );
+}
+
+#[test]
+fn reloadable_field_with_default() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct(gc) Foo { a: i32 }
+    pub fn new_foo() -> Foo { Foo { a: 5 } }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let foo_struct: StructRef<'_> = driver
+        .runtime
+        .invoke("new_foo", ())
+        .expect("Failed to call function");
+    let foo_struct = foo_struct.root();
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub struct(gc) Foo { a: i32, b: f64 = 1.5, c: i8 = -3, d: bool = true }
+    pub fn new_foo() -> Foo { Foo { a: 5 } }
+    ",
+    );
+
+    // Added fields are initialized to their default value
+    let foo_struct = foo_struct.as_ref(&driver.runtime);
+    assert_eq!(
+        foo_struct
+            .get::<i32>("a")
+            .expect("Failed to get struct field"),
+        5
+    );
+    assert_eq!(
+        foo_struct
+            .get::<f64>("b")
+            .expect("Failed to get struct field"),
+        1.5
+    );
+    assert_eq!(
+        foo_struct
+            .get::<i8>("c")
+            .expect("Failed to get struct field"),
+        -3
+    );
+    assert!(foo_struct
+        .get::<bool>("d")
+        .expect("Failed to get struct field"));
+
+    let new_foo_struct: StructRef<'_> = driver
+        .runtime
+        .invoke("new_foo", ())
+        .expect("Failed to call function");
+    assert_eq!(
+        new_foo_struct
+            .get::<i8>("c")
+            .expect("Failed to get struct field"),
+        -3
+    );
+}
+
 #[test]
 fn reloadable_struct_decl_multi_file() {
     let mut driver = CompileAndRunTestDriver::from_fixture(
@@ -139,7 +443,7 @@ fn reloadable_struct_decl_multi_file() {
 
     //- /src/foo.mun
     struct(gc) Bar {
-        m: i64,
+        pub m: i64,
     }
     "#,
         |builder| builder,
@@ -190,3 +494,175 @@ fn reloadable_struct_decl_multi_file() {
         1
     );
 }
+
+#[test]
+fn reload_hooks() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct(gc) Foo { a: i32 }
+    pub fn new_foo() -> Foo { Foo { a: 5 } }
+    pub fn get(foo: Foo) -> i32 { foo.a }
+    pub fn removed() {}
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let foo_struct: StructRef<'_> = driver
+        .runtime
+        .invoke("new_foo", ())
+        .expect("Failed to call function");
+    let _foo_struct = foo_struct.root();
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    {
+        let reports = reports.clone();
+        driver.runtime.on_reload(move |event| {
+            if let ReloadEvent::After(report) = event {
+                reports.lock().unwrap().push((*report).clone());
+            }
+        });
+    }
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub struct(gc) Foo { a: i64 }
+    pub fn new_foo() -> Foo { Foo { a: 5 } }
+    pub fn get(foo: Foo) -> i64 { foo.a }
+    pub fn added() {}
+    ",
+    );
+
+    let reports = reports.lock().unwrap();
+    let [report] = reports.as_slice() else {
+        panic!("Expected a single reload report, found {reports:?}");
+    };
+    assert_eq!(report.epoch, driver.runtime.epoch());
+    assert!(!report.patched);
+    assert_eq!(report.changed_functions, ["get", "new_foo"]);
+    assert_eq!(report.added_functions, ["added"]);
+    assert_eq!(report.removed_functions, ["removed"]);
+    assert_eq!(report.remapped_types, ["Foo"]);
+    assert!(report.deleted_types.is_empty());
+    assert_eq!(report.migrated_objects, 1);
+    assert_eq!(report.deleted_objects, 0);
+}
+
+#[test]
+fn reload_hooks_invoke_before_reload() {
+    let mut driver = CompileTestDriver::from_file(
+        r"
+    pub fn value() -> i32 { 5 }
+    ",
+    );
+    // Safety: We compiled the library ourselves, therefor loading the library is
+    // safe.
+    let runtime = Arc::new(
+        unsafe { Runtime::builder(driver.lib_path()).finish() }
+            .expect("Failed to construct runtime"),
+    );
+
+    // The hook invokes a function of the assemblies that are about to be reloaded,
+    // which must not deadlock
+    let values = Arc::new(Mutex::new(Vec::new()));
+    {
+        let weak_runtime = Arc::downgrade(&runtime);
+        let values = values.clone();
+        runtime.on_reload(move |event| {
+            if let ReloadEvent::Before { .. } = event {
+                let runtime = weak_runtime.upgrade().unwrap();
+                let value: i32 = runtime.invoke("value", ()).unwrap();
+                values.lock().unwrap().push(value);
+            }
+        });
+    }
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub fn value() -> i32 { 10 }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefor updating the runtime is
+    // safe.
+    let start_time = Instant::now();
+    while !unsafe { runtime.update() } {
+        assert!(
+            start_time.elapsed() < Duration::from_secs(10),
+            "runtime did not update after recompilation within 10 seconds"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(values.lock().unwrap().as_slice(), &[5]);
+    let value: i32 = runtime.invoke("value", ()).unwrap();
+    assert_eq!(value, 10);
+}
+
+#[test]
+fn reload_hooks_are_paired_while_invoking_from_other_threads() {
+    let mut driver = CompileTestDriver::from_file(
+        r"
+    pub fn value() -> i32 { 5 }
+    ",
+    );
+    // Safety: We compiled the library ourselves, therefor loading the library is
+    // safe.
+    let runtime = Arc::new(
+        unsafe { Runtime::builder(driver.lib_path()).finish() }
+            .expect("Failed to construct runtime"),
+    );
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    {
+        let events = events.clone();
+        runtime.on_reload(move |event| {
+            let event = match event {
+                ReloadEvent::Before { .. } => "before",
+                ReloadEvent::After(_) => "after",
+                ReloadEvent::Failed(_) => "failed",
+            };
+            events.lock().unwrap().push(event);
+        });
+    }
+
+    // Keep invoking the function until the reloaded version is observed
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let runtime = runtime.clone();
+            std::thread::spawn(move || loop {
+                let value: i32 = runtime.invoke("value", ()).unwrap();
+                if value == 10 {
+                    break;
+                }
+            })
+        })
+        .collect();
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub fn value() -> i32 { 10 }
+    ",
+    );
+
+    // Safety: We compiled the library ourselves, therefor updating the runtime is
+    // safe.
+    let start_time = Instant::now();
+    while !unsafe { runtime.update() } {
+        assert!(
+            start_time.elapsed() < Duration::from_secs(10),
+            "runtime did not update after recompilation within 10 seconds"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // Invocations on other threads delay the reload, but never announce it twice
+    assert_eq!(events.lock().unwrap().as_slice(), &["before", "after"]);
+}
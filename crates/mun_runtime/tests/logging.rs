@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use mun_runtime::LogKind;
+use mun_test::CompileAndRunTestDriver;
+use parking_lot::Mutex;
+
+#[test]
+fn log_handler() {
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let driver = CompileAndRunTestDriver::new(
+        r#"
+    pub fn main(health: i32, speed: f32, is_alive: bool) {
+        print("health = {}, speed = {}", health, speed);
+        log("{{alive}}: {}", is_alive);
+        print("{} {} {}", -1i8, 255u8, 170141183460469231731687303715884105727i128);
+        print("tab\tnewline\n");
+    }
+    "#,
+        {
+            let messages = messages.clone();
+            move |builder| {
+                builder.set_log_handler(move |kind, message| {
+                    messages.lock().push((kind, message.to_owned()));
+                })
+            }
+        },
+    )
+    .expect("Failed to build test driver");
+
+    let _: () = driver
+        .runtime
+        .invoke("main", (100i32, 2.5f32, true))
+        .unwrap();
+
+    assert_eq!(
+        *messages.lock(),
+        [
+            (LogKind::Print, "health = 100, speed = 2.5".to_owned()),
+            (LogKind::Log, "{alive}: true".to_owned()),
+            (
+                LogKind::Print,
+                "-1 255 170141183460469231731687303715884105727".to_owned()
+            ),
+            (LogKind::Print, "tab\tnewline\n".to_owned()),
+        ]
+    );
+}
@@ -1,4 +1,7 @@
-use mun_runtime::{ArgumentReflection, Marshal, ReturnTypeReflection, StructRef};
+use mun_runtime::{
+    ArgumentReflection, HasStaticType, Marshal, OpaqueRef, OpaqueType, ReprCStruct,
+    ReturnTypeReflection, StructRef, StructValue, Type,
+};
 use mun_test::CompileAndRunTestDriver;
 
 #[macro_use]
@@ -625,6 +628,71 @@ fn extern_fn_invalid_sig() {
     assert_invoke_eq!(isize, 16, driver, "main");
 }
 
+struct Texture {
+    width: u32,
+}
+
+impl OpaqueType for Texture {
+    const NAME: &'static str = "Texture";
+}
+
+#[test]
+fn extern_struct() {
+    extern "C" fn texture_width(texture: OpaqueRef<Texture>) -> u32 {
+        unsafe { texture.as_ref() }.width
+    }
+
+    let driver = CompileAndRunTestDriver::new(
+        r#"
+    pub extern struct Texture;
+    extern fn texture_width(texture: Texture) -> u32;
+
+    pub fn double_width(texture: Texture) -> u32 {
+        texture_width(texture) * 2
+    }
+
+    pub fn identity(texture: Texture) -> Texture {
+        let other = texture;
+        other
+    }
+    "#,
+        |builder| {
+            builder.register_type::<Texture>().insert_fn(
+                "texture_width",
+                texture_width as extern "C" fn(OpaqueRef<Texture>) -> u32,
+            )
+        },
+    )
+    .expect("Failed to build test driver");
+
+    let mut texture = Texture { width: 64 };
+    let texture_ref = OpaqueRef::from(&mut texture);
+
+    let width: u32 = driver
+        .runtime
+        .invoke("double_width", (texture_ref,))
+        .unwrap();
+    assert_eq!(width, 128);
+
+    // The handle round-trips without copying the host value
+    let result: OpaqueRef<Texture> = driver.runtime.invoke("identity", (texture_ref,)).unwrap();
+    assert_eq!(result, texture_ref);
+    assert_eq!(result.as_ptr(), &mut texture as *mut Texture);
+}
+
+#[test]
+fn extern_struct_unregistered() {
+    let result = CompileAndRunTestDriver::new(
+        r#"
+    pub extern struct Texture;
+    pub fn identity(texture: Texture) -> Texture { texture }
+    "#,
+        |builder| builder,
+    );
+
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_primitive_types() {
     fn test_field<
@@ -735,3 +803,62 @@ fn signed_and_unsigned_rem() {
     assert_invoke_eq!(i32, -2, driver, "signed");
     assert_invoke_eq!(i32, 2, driver, "unsigned");
 }
+
+#[test]
+fn marshal_struct_by_value() {
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Vector2 {
+        x: f32,
+        y: f32,
+    }
+
+    unsafe impl ReprCStruct for Vector2 {
+        const NAME: &'static str = "Vector2";
+
+        fn fields() -> Vec<(&'static str, Type)> {
+            vec![
+                ("x", <f32 as HasStaticType>::type_info().clone()),
+                ("y", <f32 as HasStaticType>::type_info().clone()),
+            ]
+        }
+    }
+
+    let driver = CompileAndRunTestDriver::new(
+        r#"
+    #[repr(C)]
+    pub struct(value) Vector2 { x: f32, y: f32 }
+
+    pub fn add(a: Vector2, b: Vector2) -> Vector2 {
+        Vector2 { x: a.x + b.x, y: a.y + b.y }
+    }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let ty = driver.runtime.get_type_info_by_name("Vector2").unwrap();
+    assert!(ty.as_struct().unwrap().is_passed_by_value());
+
+    let sum: StructValue<Vector2> = driver
+        .runtime
+        .invoke(
+            "add",
+            (
+                StructValue(Vector2 { x: 1.0, y: 2.0 }),
+                StructValue(Vector2 { x: 3.0, y: 4.0 }),
+            ),
+        )
+        .unwrap();
+    assert_eq!(sum.into_inner(), Vector2 { x: 4.0, y: 6.0 });
+
+    // Structs that are passed by value cannot be marshalled as a `StructRef`
+    let result: Result<StructRef<'_>, _> = driver.runtime.invoke(
+        "add",
+        (
+            StructValue(Vector2 { x: 1.0, y: 2.0 }),
+            StructValue(Vector2 { x: 3.0, y: 4.0 }),
+        ),
+    );
+    assert!(result.is_err());
+}
@@ -1,4 +1,6 @@
-use mun_runtime::{ArrayRef, StructRef};
+use std::{cell::RefCell, sync::Arc};
+
+use mun_runtime::{ArrayRef, Runtime, StructRef};
 use mun_test::CompileAndRunTestDriver;
 
 #[macro_use]
@@ -43,6 +45,194 @@ fn gc_trace() {
     assert_eq!(runtime.gc_stats().allocated_memory, 0);
 }
 
+#[test]
+fn dump_heap() {
+    let driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: Bar,
+        bars: [Bar],
+    }
+
+    pub struct Bar {
+        baz: i64
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo {
+            bar: Bar { baz: 1 },
+            bars: [Bar { baz: 2 }],
+        }
+    }
+    "#,
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = &driver.runtime;
+    let foo_struct: StructRef<'_> = runtime.invoke("new_foo", ()).unwrap();
+    let foo_struct = foo_struct.root();
+
+    let dump = runtime.dump_heap();
+    let roots: Vec<_> = dump.roots().collect();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0].type_name, "Foo");
+
+    let fields: Vec<_> = roots[0]
+        .references
+        .iter()
+        .map(|reference| reference.field.as_str())
+        .collect();
+    assert_eq!(fields, ["bar", "bars"]);
+    assert_eq!(dump.unreachable().count(), 0);
+
+    drop(foo_struct);
+
+    // Without a root, all objects are garbage
+    let dump = runtime.dump_heap();
+    assert_eq!(dump.objects().len(), 4);
+    assert_eq!(dump.unreachable().count(), 4);
+}
+
+#[test]
+fn gc_stress_mode() {
+    let mut driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        a: i32,
+        b: [Bar],
+    }
+
+    pub struct Bar {
+        c: i64,
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo { a: 1, b: [Bar { c: 2 }, Bar { c: 3 }] }
+    }
+
+    pub fn sum(values: [i64]) -> i64 {
+        values[0] + values[1]
+    }
+    "#,
+        |builder| builder.gc_stress_mode(true),
+    )
+    .expect("Failed to build test driver");
+
+    let foo_struct: StructRef<'_> = driver.runtime.invoke("new_foo", ()).unwrap();
+    let foo_struct = foo_struct.root();
+
+    // Constructing an array collects all unrooted objects and validates the heap
+    let values = driver.runtime.construct_array([4i64, 5]);
+    let sum: i64 = driver.runtime.invoke("sum", (values,)).unwrap();
+    assert_eq!(sum, 9);
+
+    driver.update_file(
+        "mod.mun",
+        r#"
+    pub struct Foo {
+        a: i32,
+        b: [Bar],
+    }
+
+    pub struct Bar {
+        c: i64,
+        d: f64,
+    }
+    "#,
+    );
+    assert!(driver.runtime.gc_collect());
+
+    let foo_struct = foo_struct.as_ref(&driver.runtime);
+    assert_eq!(foo_struct.get::<i32>("a").unwrap(), 1);
+    let c: Vec<i64> = foo_struct
+        .get::<ArrayRef<'_, StructRef<'_>>>("b")
+        .unwrap()
+        .iter()
+        .map(|bar| bar.get::<i64>("c").unwrap())
+        .collect();
+    assert_eq!(c, [2, 3]);
+}
+
+#[test]
+fn gc_stress_mode_collects_on_mun_allocations() {
+    let driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        value: i64,
+    }
+
+    pub fn allocate(count: i64) -> i64 {
+        let kept = Foo { value: 7 };
+        let values = [Foo { value: 1 }, Foo { value: 2 }];
+        let i = 0;
+        while i < count {
+            let garbage = Foo { value: i };
+            i += 1;
+        }
+        kept.value + values[0].value + values[1].value
+    }
+    "#,
+        |builder| builder.gc_stress_mode(true),
+    )
+    .expect("Failed to build test driver");
+
+    // Objects that are referenced from the stack of the function must survive
+    // the collections
+    let value: i64 = driver.runtime.invoke("allocate", (100i64,)).unwrap();
+    assert_eq!(value, 10);
+
+    // Every allocation collected the garbage of the previous iterations
+    assert!(driver.runtime.dump_heap().objects().len() < 10);
+}
+
+thread_local! {
+    static RUNTIME: RefCell<Option<Arc<Runtime>>> = RefCell::new(None);
+}
+
+extern "C" fn sum_host_array() -> i64 {
+    RUNTIME.with(|runtime| {
+        let runtime = runtime.borrow();
+        let runtime = runtime.as_ref().unwrap();
+        let array = runtime.construct_array([1i64, 2]);
+        array.iter().sum()
+    })
+}
+
+#[test]
+fn gc_stress_mode_keeps_objects_referenced_from_mun_frames() {
+    let driver = CompileAndRunTestDriver::new(
+        r#"
+    pub struct Foo {
+        value: i64,
+    }
+
+    extern fn sum_host_array() -> i64;
+
+    pub fn allocate_in_host() -> i64 {
+        let foo = Foo { value: 39 };
+        foo.value + sum_host_array()
+    }
+    "#,
+        |builder| {
+            builder
+                .gc_stress_mode(true)
+                .insert_fn("sum_host_array", sum_host_array as extern "C" fn() -> i64)
+        },
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = Arc::new(driver.runtime);
+    RUNTIME.with(|r| *r.borrow_mut() = Some(runtime.clone()));
+
+    // Constructing the array from within the host function must not collect the
+    // object that the Mun function still references
+    let value: i64 = runtime.invoke("allocate_in_host", ()).unwrap();
+    assert_eq!(value, 42);
+
+    RUNTIME.with(|r| r.borrow_mut().take());
+}
+
 #[test]
 fn map_struct_insert_field1() {
     let mut driver = CompileAndRunTestDriver::new(
@@ -2479,7 +2669,7 @@ fn test_type_table() {
 
     //- /src/bar.mun
     pub struct Bar {
-        value: i32
+        pub value: i32
     }
     "#,
         |builder| builder,
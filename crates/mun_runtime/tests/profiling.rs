@@ -0,0 +1,142 @@
+use std::sync::{Mutex, PoisonError};
+
+use mun_compiler::Config;
+use mun_test::CompileAndRunTestDriver;
+
+/// The profiler is shared by all runtimes in the process, so tests that reset
+/// it must not run concurrently
+static PROFILER_LOCK: Mutex<()> = Mutex::new(());
+
+/// Compiles `text` with profiling enabled.
+fn profiled_driver(text: &str) -> CompileAndRunTestDriver {
+    CompileAndRunTestDriver::with_config(
+        text,
+        Config {
+            profiling: true,
+            ..Config::default()
+        },
+        |builder| builder,
+    )
+    .expect("Failed to build test driver")
+}
+
+#[test]
+fn profile_function_calls() {
+    let _lock = PROFILER_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let driver = profiled_driver(
+        r"
+    fn fibonacci(n: i64) -> i64 {
+        if n <= 1 {
+            return n;
+        }
+        fibonacci(n - 1) + fibonacci(n - 2)
+    }
+
+    pub fn main(n: i64) -> i64 {
+        fibonacci(n)
+    }
+    ",
+    );
+
+    driver.runtime.reset_profile();
+    let result: i64 = driver.runtime.invoke("main", (5i64,)).unwrap();
+    assert_eq!(result, 5);
+
+    let profile = driver.runtime.profile();
+    let main = profile.function("main").expect("`main` was not profiled");
+    assert_eq!(main.call_count, 1);
+
+    // fibonacci(5) calls itself 14 times
+    let fibonacci = profile
+        .function("fibonacci")
+        .expect("`fibonacci` was not profiled");
+    assert_eq!(fibonacci.call_count, 15);
+    assert!(fibonacci.total_time <= main.total_time);
+    assert!(fibonacci.self_time <= fibonacci.total_time);
+    assert!(main.self_time <= main.total_time);
+
+    let trace = profile.to_chrome_trace();
+    assert!(trace.starts_with("{\"traceEvents\":["));
+    assert_eq!(trace.matches("\"name\":\"fibonacci\"").count(), 15);
+    assert_eq!(trace.matches("\"name\":\"main\"").count(), 1);
+
+    driver.runtime.reset_profile();
+    assert!(driver.runtime.profile().functions().is_empty());
+}
+
+#[test]
+fn profile_after_panic() {
+    let _lock = PROFILER_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let driver = profiled_driver(
+        r"
+    fn fail() { assert(false) }
+
+    pub fn main(should_fail: bool) -> i32 {
+        if should_fail {
+            fail();
+        }
+        1
+    }
+    ",
+    );
+
+    driver.runtime.reset_profile();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        driver.runtime.invoke::<i32, _>("main", (true,))
+    }));
+    assert!(result.is_err());
+    let result: i32 = driver.runtime.invoke("main", (false,)).unwrap();
+    assert_eq!(result, 1);
+
+    // The calls that unwound are not counted, and do not affect later calls
+    let profile = driver.runtime.profile();
+    assert!(profile.function("fail").is_none());
+    let main = profile.function("main").expect("`main` was not profiled");
+    assert_eq!(main.call_count, 1);
+    assert!(main.self_time <= main.total_time);
+}
+
+#[test]
+fn profile_parallel_calls() {
+    let _lock = PROFILER_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let driver = profiled_driver(
+        r"
+    fn fibonacci(n: i64) -> i64 {
+        if n <= 1 {
+            return n;
+        }
+        fibonacci(n - 1) + fibonacci(n - 2)
+    }
+
+    pub fn main(n: i64) -> i64 {
+        fibonacci(n)
+    }
+    ",
+    );
+
+    driver.runtime.reset_profile();
+    let arguments = vec![(3i64,); 64];
+    let results: Vec<i64> = driver
+        .runtime
+        .invoke_batch_parallel("main", &arguments)
+        .unwrap();
+    assert!(results.iter().all(|&result| result == 2));
+
+    // The measurements of all threads are combined
+    let profile = driver.runtime.profile();
+    let main = profile.function("main").expect("`main` was not profiled");
+    assert_eq!(main.call_count, 64);
+
+    // fibonacci(3) calls itself 4 times
+    let fibonacci = profile
+        .function("fibonacci")
+        .expect("`fibonacci` was not profiled");
+    assert_eq!(fibonacci.call_count, 64 * 5);
+    assert_eq!(
+        profile
+            .to_chrome_trace()
+            .matches("\"name\":\"fibonacci\"")
+            .count(),
+        64 * 5
+    );
+}
@@ -1,5 +1,7 @@
-use mun_runtime::LinkFunctionsError;
-use mun_test::CompileAndRunTestDriver;
+use std::path::Path;
+
+use mun_runtime::{CompatIssue, LinkFunctionsError, Runtime, Type};
+use mun_test::{CompileAndRunTestDriver, CompileTestDriver};
 
 #[macro_use]
 mod util;
@@ -176,6 +178,138 @@ fn error_assembly_not_linkable() {
     );
 }
 
+#[test]
+fn can_load() {
+    extern "C" fn dependency() -> i32 {
+        5
+    }
+
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct Foo { a: i32 }
+    pub struct Bar { a: i32 }
+
+    pub fn foo() -> Foo { Foo { a: 1 } }
+    pub fn bar() -> Bar { Bar { a: 2 } }
+    ",
+        |builder| builder.insert_fn("dependency", dependency as extern "C" fn() -> i32),
+    )
+    .expect("Failed to build test driver");
+
+    let compatible = CompileTestDriver::from_file(
+        r"
+    pub struct Foo { a: i32, b: f32 }
+
+    extern fn dependency() -> i32;
+
+    pub fn foo() -> Foo { Foo { a: dependency(), b: 1.0 } }
+    ",
+    );
+    let report = driver.runtime.can_load(compatible.lib_path()).unwrap();
+    assert!(report.is_compatible(), "{:?}", report.issues);
+    assert_eq!(report.migrated_types, vec!["Foo"]);
+    assert_eq!(report.deleted_types, vec!["Bar"]);
+
+    let incompatible = CompileTestDriver::from_file(
+        r"
+    extern fn dependency() -> f32;
+    extern fn missing();
+
+    pub fn main() -> f32 { missing(); dependency() }
+    ",
+    );
+    let report = driver.runtime.can_load(incompatible.lib_path()).unwrap();
+    assert!(!report.is_compatible());
+    assert!(report
+        .issues
+        .contains(&CompatIssue::MissingFunction(String::from("missing"))));
+    assert!(report.issues.contains(&CompatIssue::MismatchedSignature {
+        expected: String::from("dependency() -> core::f32"),
+        found: String::from("dependency() -> core::i32"),
+    }));
+
+    // Nothing was linked
+    assert!(driver.runtime.get_type_info_by_name("Bar").is_some());
+    assert!(driver.runtime.get_function_definition("main").is_none());
+}
+
+#[test]
+fn library_image_matches_loaded_library() {
+    let driver = CompileTestDriver::from_file(
+        r"
+    pub struct Foo { a: i32, b: f32 }
+
+    extern fn dependency() -> i32;
+
+    pub fn foo() -> Foo { Foo { a: dependency(), b: 1.0 } }
+    fn private() -> i32 { 1 }
+    pub fn uses_private() -> i32 { private() }
+    ",
+    );
+
+    // The image resolves the pointers of the assembly info without loading the
+    // library, so its description should be identical to that of the loaded
+    // library.
+    let image = mun_libloader::MunLibraryImage::new(driver.lib_path()).unwrap();
+    let library = unsafe { mun_libloader::MunLibrary::new(driver.lib_path()) }.unwrap();
+    unsafe {
+        assert_eq!(image.get_abi_version(), library.get_abi_version());
+        assert_eq!(image.get_compiler_version(), library.get_compiler_version());
+        assert_eq!(
+            image.get_info().unwrap().to_string(),
+            library.get_info().to_string()
+        );
+    }
+}
+
+/// Verifies that a munlib whose segments claim an enormous size is rejected by
+/// [`Runtime::can_load`] instead of exhausting memory.
+#[test]
+#[cfg(target_os = "linux")]
+fn can_load_rejects_oversized_image() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let library = CompileTestDriver::from_file(
+        r"
+    pub fn main() -> i32 { 10 }
+    ",
+    );
+    let mut bytes = std::fs::read(library.lib_path()).unwrap();
+
+    // Enlarge the memory size of the first loadable segment in the ELF program
+    // headers
+    let read_u16 = |bytes: &[u8], offset: usize| {
+        u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap()) as usize
+    };
+    let program_headers_offset = u64::from_le_bytes(bytes[0x20..0x28].try_into().unwrap()) as usize;
+    let program_header_size = read_u16(&bytes, 0x36);
+    let num_program_headers = read_u16(&bytes, 0x38);
+    let load_header = (0..num_program_headers)
+        .map(|idx| program_headers_offset + idx * program_header_size)
+        .find(|&offset| bytes[offset..offset + 4] == 1u32.to_le_bytes())
+        .expect("no loadable segment");
+    bytes[load_header + 0x28..load_header + 0x30].copy_from_slice(&(1u64 << 40).to_le_bytes());
+
+    let malformed_dir = tempfile::tempdir().unwrap();
+    let malformed_path = malformed_dir
+        .path()
+        .join(library.lib_path().file_name().unwrap());
+    std::fs::write(&malformed_path, bytes).unwrap();
+
+    assert!(matches!(
+        driver.runtime.can_load(&malformed_path),
+        Err(mun_runtime::LoadError::FailedToInspectLibrary(
+            mun_libloader::ImageError::TooLarge(_)
+        ))
+    ));
+}
+
 #[test]
 fn arg_missing_bug() {
     let driver = CompileAndRunTestDriver::new(
@@ -231,3 +365,65 @@ fn cyclic_struct() {
         .ty();
     assert_eq!(foo_foo_ty, foo_ty);
 }
+
+#[test]
+fn assemblies() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct Foo { a: i32 }
+
+    pub fn foo(a: i32) -> Foo { Foo { a } }
+    pub fn main() -> i32 { foo(3).a }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let assemblies = driver.runtime.assemblies();
+    assert_eq!(assemblies.len(), 1);
+
+    let assembly = &assemblies[0];
+    assert_eq!(assembly.abi_version, mun_abi::ABI_VERSION);
+    assert!(assembly.capabilities.is_empty());
+    assert!(assembly.dependencies.is_empty());
+    assert!(assembly.unresolved_functions.is_empty());
+
+    let function_names: Vec<&str> = assembly
+        .functions
+        .iter()
+        .map(|function| function.prototype.name.as_str())
+        .collect();
+    assert_eq!(function_names, vec!["foo", "main"]);
+
+    let foo_signature = &assembly.functions[0].prototype.signature;
+    assert_eq!(foo_signature.arg_types.len(), 1);
+    assert_eq!(foo_signature.arg_types[0].name(), "core::i32");
+    assert_eq!(foo_signature.return_type.name(), "Foo");
+
+    let type_names: Vec<&str> = assembly.types.iter().map(Type::name).collect();
+    assert_eq!(type_names, vec!["Foo"]);
+}
+
+#[test]
+fn load_from_memory() {
+    let driver = CompileTestDriver::from_file(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+    );
+    let bytes = std::fs::read(driver.lib_path()).unwrap();
+
+    // The path only identifies the assembly and does not exist
+    let library_path = Path::new("in-memory").join("mod.munlib");
+    assert!(!library_path.exists());
+
+    // Safety: We compiled the library ourselves, therefor loading the library is
+    // safe.
+    let runtime = unsafe { Runtime::builder_from_memory(&library_path, bytes).finish() }
+        .expect("Failed to construct runtime");
+    let result: i32 = runtime.invoke("main", ()).unwrap();
+    assert_eq!(result, 5);
+
+    let assemblies = runtime.assemblies();
+    assert_eq!(assemblies[0].library_path, library_path);
+}
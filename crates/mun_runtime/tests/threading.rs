@@ -1,5 +1,190 @@
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
 use mun_runtime::Runtime;
+use mun_test::{CompileAndRunTestDriver, CompileTestDriver};
 
 // Ensures the [`Runtime`] is Send
 trait IsSend: Send {}
 impl IsSend for Runtime {}
+
+// Ensures the [`Runtime`] is Sync
+trait IsSync: Sync {}
+impl IsSync for Runtime {}
+
+#[test]
+fn invoke_from_multiple_threads() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub fn fibonacci(n: i64) -> i64 {
+        if n <= 1 {
+            n
+        } else {
+            fibonacci(n - 1) + fibonacci(n - 2)
+        }
+    }
+    ",
+        |builder| builder,
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = Arc::new(driver.runtime);
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let runtime = runtime.clone();
+            std::thread::spawn(move || {
+                (0..100)
+                    .map(|_| {
+                        let result: i64 = runtime.invoke("fibonacci", (10i64,)).unwrap();
+                        result
+                    })
+                    .sum::<i64>()
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 5500);
+    }
+}
+
+#[test]
+fn reload_while_invoking_from_multiple_threads() {
+    let mut driver = CompileTestDriver::from_file(
+        r"
+    pub fn value() -> i32 { 5 }
+    ",
+    );
+    let runtime = Arc::new(
+        unsafe { Runtime::builder(driver.lib_path()).finish() }.expect("Failed to build runtime"),
+    );
+
+    // Keep invoking the function until the reloaded version is observed
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let runtime = runtime.clone();
+            std::thread::spawn(move || loop {
+                let value: i32 = runtime.invoke("value", ()).unwrap();
+                if value == 10 {
+                    break;
+                }
+                assert_eq!(value, 5);
+            })
+        })
+        .collect();
+
+    driver.update_file(
+        "mod.mun",
+        r"
+    pub fn value() -> i32 { 10 }
+    ",
+    );
+
+    // The stream of invocations must not starve the reload
+    let start_time = Instant::now();
+    while !unsafe { runtime.update() } {
+        assert!(
+            start_time.elapsed() < Duration::from_secs(10),
+            "the reload was starved by the invocations"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn pause() {
+    PAUSED.store(true, Ordering::Release);
+    std::thread::sleep(Duration::from_millis(100));
+}
+
+#[test]
+fn gc_collect_waits_for_invocations() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct Foo { value: i64 }
+
+    extern fn pause();
+
+    pub fn allocate_and_pause() -> i64 {
+        let foo = Foo { value: 42 };
+        pause();
+        foo.value
+    }
+    ",
+        // Stress mode poisons the memory of collected objects
+        |builder| {
+            builder
+                .gc_stress_mode(true)
+                .insert_fn("pause", pause as extern "C" fn())
+        },
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = Arc::new(driver.runtime);
+    let handle = {
+        let runtime = runtime.clone();
+        std::thread::spawn(move || {
+            let value: i64 = runtime.invoke("allocate_and_pause", ()).unwrap();
+            value
+        })
+    };
+
+    while !PAUSED.load(Ordering::Acquire) {
+        std::thread::yield_now();
+    }
+
+    // `foo` is only referenced from the stack of the paused function, so it
+    // may only be collected after the invocation has finished.
+    assert!(runtime.gc_collect());
+    assert_eq!(handle.join().unwrap(), 42);
+}
+
+thread_local! {
+    static RUNTIME: RefCell<Option<Arc<Runtime>>> = RefCell::new(None);
+}
+
+extern "C" fn collect() -> bool {
+    RUNTIME.with(|runtime| runtime.borrow().as_ref().unwrap().gc_collect())
+}
+
+#[test]
+fn gc_collect_within_invocation_is_deferred() {
+    let driver = CompileAndRunTestDriver::new(
+        r"
+    pub struct Foo { value: i64 }
+
+    extern fn collect() -> bool;
+
+    pub fn allocate_and_collect() -> i64 {
+        let foo = Foo { value: 42 };
+        if collect() { 0 } else { foo.value }
+    }
+    ",
+        |builder| builder.insert_fn("collect", collect as extern "C" fn() -> bool),
+    )
+    .expect("Failed to build test driver");
+
+    let runtime = Arc::new(driver.runtime);
+    RUNTIME.with(|r| *r.borrow_mut() = Some(runtime.clone()));
+
+    let value: i64 = runtime.invoke("allocate_and_collect", ()).unwrap();
+    assert_eq!(value, 42);
+    assert!(runtime.gc_stats().allocated_memory > 0);
+
+    // The next update performs the deferred collection
+    assert!(!unsafe { runtime.update() });
+    assert_eq!(runtime.gc_stats().allocated_memory, 0);
+
+    RUNTIME.with(|r| r.borrow_mut().take());
+}
@@ -1,6 +1,8 @@
 //! Exposes the Mun runtime using the C ABI.
 
-use std::{ffi::c_void, mem::ManuallyDrop, ops::Deref, os::raw::c_char, slice};
+use std::{
+    collections::HashMap, ffi::c_void, mem::ManuallyDrop, ops::Deref, os::raw::c_char, slice,
+};
 
 use mun_abi as abi;
 use mun_capi_utils::{
@@ -175,6 +177,7 @@ pub unsafe extern "C" fn mun_runtime_create(
                 },
             },
             fn_ptr: def.fn_ptr,
+            is_resumable: false,
         })
     })
     .collect::<Result<_, _>>());
@@ -183,6 +186,10 @@ pub unsafe extern "C" fn mun_runtime_create(
         library_path: library_path.into(),
         user_functions,
         type_table,
+        gc_stress_mode: false,
+        integrity: None,
+        in_memory_assemblies: HashMap::default(),
+        log_handler: None,
     };
 
     let runtime = match mun_runtime::Runtime::new(runtime_options) {
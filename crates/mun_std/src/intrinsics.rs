@@ -0,0 +1,120 @@
+//! The functions of the `std` package that are implemented by the runtime.
+//! Every intrinsic is declared as an `extern fn` in the Mun sources of the
+//! package and is listed in [`FUNCTIONS`].
+
+use std::{
+    cell::Cell,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// An intrinsic, distinguished by its signature.
+#[derive(Clone, Copy, Debug)]
+pub enum Intrinsic {
+    /// `fn(f64) -> f64`
+    F64ToF64(extern "C" fn(f64) -> f64),
+
+    /// `fn(f64, f64) -> f64`
+    F64F64ToF64(extern "C" fn(f64, f64) -> f64),
+
+    /// `fn() -> f64`
+    UnitToF64(extern "C" fn() -> f64),
+
+    /// `fn(u64)`
+    U64ToUnit(extern "C" fn(u64)),
+}
+
+/// The full names of all intrinsics together with their implementation.
+pub const FUNCTIONS: &[(&str, Intrinsic)] = &[
+    ("std::math::sin", Intrinsic::F64ToF64(sin)),
+    ("std::math::cos", Intrinsic::F64ToF64(cos)),
+    ("std::math::tan", Intrinsic::F64ToF64(tan)),
+    ("std::math::sqrt", Intrinsic::F64ToF64(sqrt)),
+    ("std::math::pow", Intrinsic::F64F64ToF64(pow)),
+    ("std::random::random", Intrinsic::UnitToF64(random)),
+    ("std::random::seed", Intrinsic::U64ToUnit(seed)),
+];
+
+extern "C" fn sin(value: f64) -> f64 {
+    value.sin()
+}
+
+extern "C" fn cos(value: f64) -> f64 {
+    value.cos()
+}
+
+extern "C" fn tan(value: f64) -> f64 {
+    value.tan()
+}
+
+extern "C" fn sqrt(value: f64) -> f64 {
+    value.sqrt()
+}
+
+extern "C" fn pow(base: f64, exponent: f64) -> f64 {
+    base.powf(exponent)
+}
+
+thread_local! {
+    /// The state of the random number generator of the current thread, or zero
+    /// if it has not been seeded yet.
+    static RANDOM_STATE: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Returns a pseudo-random number in the range `[0, 1)`. Every thread has its
+/// own generator, which is seeded from the system time on first use unless
+/// [`seed`] was called.
+extern "C" fn random() -> f64 {
+    RANDOM_STATE.with(|state| {
+        let mut x = state.get();
+        if x == 0 {
+            x = mix(SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_nanos() as u64));
+        }
+
+        // xorshift64*
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+
+        // Use the upper 53 bits, which fit exactly in the mantissa of an `f64`
+        (x.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64
+    })
+}
+
+/// Seeds the random number generator of the current thread, making the
+/// sequence of numbers returned by [`random`] reproducible.
+extern "C" fn seed(seed: u64) {
+    RANDOM_STATE.with(|state| state.set(mix(seed)));
+}
+
+/// Scrambles the bits of `seed` (splitmix64), so that similar seeds result in
+/// different sequences. Never returns zero, which is not a valid state of the
+/// generator.
+fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    if z == 0 {
+        1
+    } else {
+        z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{random, seed};
+
+    #[test]
+    fn random_is_reproducible() {
+        seed(42);
+        let first: Vec<f64> = (0..8).map(|_| random()).collect();
+        seed(42);
+        let second: Vec<f64> = (0..8).map(|_| random()).collect();
+        assert_eq!(first, second);
+        assert!(first.iter().all(|value| (0.0..1.0).contains(value)));
+    }
+}
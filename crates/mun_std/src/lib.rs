@@ -0,0 +1,32 @@
+//! The standard library of Mun.
+//!
+//! The `std` package is compiled together with every package and can be
+//! referred to through paths that start with `std`, e.g. `std::math::sin`.
+//! Part of it is implemented in Mun, the rest consists of [`intrinsics`] that
+//! every runtime provides.
+//!
+//! This crate also contains the sources of the `core` package, whose public
+//! items are in scope in every package. The sources live here rather than in
+//! the compiler, so that tools like the language server can analyze them
+//! without depending on the compiler backend.
+
+pub mod intrinsics;
+
+/// The name by which packages refer to the `core` package.
+pub const CORE_PACKAGE_NAME: &str = "core";
+
+/// The relative paths and contents of all source files of the `core` package.
+pub const CORE_PACKAGE_SOURCES: &[(&str, &str)] = &[
+    ("mod.mun", include_str!("../core/mod.mun")),
+    ("math.mun", include_str!("../core/math.mun")),
+];
+
+/// The name by which packages refer to the `std` package.
+pub const STD_PACKAGE_NAME: &str = "std";
+
+/// The relative paths and contents of all source files of the `std` package.
+pub const STD_PACKAGE_SOURCES: &[(&str, &str)] = &[
+    ("mod.mun", include_str!("../std/mod.mun")),
+    ("math.mun", include_str!("../std/math.mun")),
+    ("random.mun", include_str!("../std/random.mun")),
+];
@@ -8,7 +8,7 @@ mod traits;
 
 use std::marker::PhantomData;
 
-pub use mun_abi::StructMemoryKind;
+pub use mun_abi::{StructMemoryKind, StructRepr};
 
 pub use self::{
     expr_extensions::*,
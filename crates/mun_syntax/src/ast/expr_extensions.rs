@@ -155,6 +155,28 @@ impl BinExpr {
     }
 }
 
+impl ast::RangeExpr {
+    pub fn op_token(&self) -> Option<SyntaxToken> {
+        self.syntax()
+            .children_with_tokens()
+            .filter_map(rowan::NodeOrToken::into_token)
+            .find(|token| matches!(token.kind(), T![..] | T![..=]))
+    }
+
+    /// Returns true if the range includes its end, e.g. `0..=10`.
+    pub fn is_inclusive(&self) -> bool {
+        self.op_token().is_some_and(|token| token.kind() == T![..=])
+    }
+
+    pub fn start(&self) -> Option<ast::Expr> {
+        children(self).next()
+    }
+
+    pub fn end(&self) -> Option<ast::Expr> {
+        children(self).nth(1)
+    }
+}
+
 #[derive(PartialEq, Eq)]
 pub enum FieldKind {
     Name(ast::NameRef),
@@ -5,8 +5,8 @@ use rowan::{GreenNodeData, GreenTokenData, NodeOrToken};
 use text_size::TextRange;
 
 use crate::{
-    ast::{self, child_opt, AstNode, NameOwner},
-    SyntaxKind, SyntaxNode, TokenText, T,
+    ast::{self, child_opt, AstNode, AttrsOwner, NameOwner},
+    SmolStr, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TokenText, T,
 };
 
 impl ast::Name {
@@ -32,6 +32,13 @@ impl ast::NameRef {
 }
 
 impl ast::FunctionDef {
+    /// Returns true if the function is marked as `resumable`.
+    pub fn is_resumable(&self) -> bool {
+        self.syntax()
+            .children_with_tokens()
+            .any(|it| it.kind() == SyntaxKind::RESUMABLE_KW)
+    }
+
     /// Returns the signature range.
     ///
     /// ```rust, ignore
@@ -190,6 +197,15 @@ impl ast::StructDef {
 
         TextRange::new(start, end)
     }
+
+    /// Returns the hints of the `#[repr(..)]` attributes of the struct, e.g.
+    /// `C` and `packed` for `#[repr(C, packed)]`.
+    pub fn repr_hints(&self) -> impl Iterator<Item = SyntaxToken> {
+        self.attrs()
+            .filter(|attr| attr.simple_name().as_deref() == Some("repr"))
+            .filter_map(|attr| attr.token_tree())
+            .flat_map(|token_tree| token_tree.idents().collect::<Vec<_>>())
+    }
 }
 
 pub enum VisibilityKind {
@@ -256,3 +272,27 @@ impl ast::TypeAliasDef {
         TextRange::new(start, end)
     }
 }
+
+impl ast::Attr {
+    /// Returns the name of the attribute if its path consists of a single
+    /// identifier, e.g. `allow` for `#[allow(dead_code)]`.
+    pub fn simple_name(&self) -> Option<SmolStr> {
+        let path = self.path()?;
+        if path.qualifier().is_some() {
+            return None;
+        }
+        let name_ref = path.segment()?.name_ref()?;
+        Some(SmolStr::new(name_ref.text()))
+    }
+}
+
+impl ast::TokenTree {
+    /// Returns the identifiers in the token tree, e.g. `dead_code` and
+    /// `unused_variables` for `(dead_code, unused_variables)`.
+    pub fn idents(&self) -> impl Iterator<Item = SyntaxToken> {
+        self.syntax()
+            .descendants_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .filter(|token| token.kind() == SyntaxKind::IDENT)
+    }
+}
@@ -173,6 +173,38 @@ impl AssociatedItemList {
     }
 }
 
+// Attr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Attr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for Attr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, ATTR)
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Attr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl Attr {
+    pub fn path(&self) -> Option<Path> {
+        super::child_opt(self)
+    }
+
+    pub fn token_tree(&self) -> Option<TokenTree> {
+        super::child_opt(self)
+    }
+}
+
 // BinExpr
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -315,6 +347,38 @@ impl CallExpr {
     }
 }
 
+// CastExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CastExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for CastExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, CAST_EXPR)
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(CastExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl CastExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+
+    pub fn type_ref(&self) -> Option<TypeRef> {
+        super::child_opt(self)
+    }
+}
+
 // Condition
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -362,13 +426,18 @@ impl AstNode for Expr {
                 | PREFIX_EXPR
                 | PATH_EXPR
                 | BIN_EXPR
+                | RANGE_EXPR
+                | CAST_EXPR
+                | TRY_EXPR
                 | PAREN_EXPR
                 | CALL_EXPR
                 | FIELD_EXPR
                 | IF_EXPR
                 | LOOP_EXPR
                 | WHILE_EXPR
+                | FOR_EXPR
                 | RETURN_EXPR
+                | YIELD_EXPR
                 | BREAK_EXPR
                 | BLOCK_EXPR
                 | ARRAY_EXPR
@@ -393,13 +462,18 @@ pub enum ExprKind {
     PrefixExpr(PrefixExpr),
     PathExpr(PathExpr),
     BinExpr(BinExpr),
+    RangeExpr(RangeExpr),
+    CastExpr(CastExpr),
+    TryExpr(TryExpr),
     ParenExpr(ParenExpr),
     CallExpr(CallExpr),
     FieldExpr(FieldExpr),
     IfExpr(IfExpr),
     LoopExpr(LoopExpr),
     WhileExpr(WhileExpr),
+    ForExpr(ForExpr),
     ReturnExpr(ReturnExpr),
+    YieldExpr(YieldExpr),
     BreakExpr(BreakExpr),
     BlockExpr(BlockExpr),
     ArrayExpr(ArrayExpr),
@@ -426,6 +500,21 @@ impl From<BinExpr> for Expr {
         Expr { syntax: n.syntax }
     }
 }
+impl From<RangeExpr> for Expr {
+    fn from(n: RangeExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<CastExpr> for Expr {
+    fn from(n: CastExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
+impl From<TryExpr> for Expr {
+    fn from(n: TryExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
 impl From<ParenExpr> for Expr {
     fn from(n: ParenExpr) -> Expr {
         Expr { syntax: n.syntax }
@@ -456,11 +545,21 @@ impl From<WhileExpr> for Expr {
         Expr { syntax: n.syntax }
     }
 }
+impl From<ForExpr> for Expr {
+    fn from(n: ForExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
 impl From<ReturnExpr> for Expr {
     fn from(n: ReturnExpr) -> Expr {
         Expr { syntax: n.syntax }
     }
 }
+impl From<YieldExpr> for Expr {
+    fn from(n: YieldExpr) -> Expr {
+        Expr { syntax: n.syntax }
+    }
+}
 impl From<BreakExpr> for Expr {
     fn from(n: BreakExpr) -> Expr {
         Expr { syntax: n.syntax }
@@ -494,13 +593,18 @@ impl Expr {
             PREFIX_EXPR => ExprKind::PrefixExpr(PrefixExpr::cast(self.syntax.clone()).unwrap()),
             PATH_EXPR => ExprKind::PathExpr(PathExpr::cast(self.syntax.clone()).unwrap()),
             BIN_EXPR => ExprKind::BinExpr(BinExpr::cast(self.syntax.clone()).unwrap()),
+            RANGE_EXPR => ExprKind::RangeExpr(RangeExpr::cast(self.syntax.clone()).unwrap()),
+            CAST_EXPR => ExprKind::CastExpr(CastExpr::cast(self.syntax.clone()).unwrap()),
+            TRY_EXPR => ExprKind::TryExpr(TryExpr::cast(self.syntax.clone()).unwrap()),
             PAREN_EXPR => ExprKind::ParenExpr(ParenExpr::cast(self.syntax.clone()).unwrap()),
             CALL_EXPR => ExprKind::CallExpr(CallExpr::cast(self.syntax.clone()).unwrap()),
             FIELD_EXPR => ExprKind::FieldExpr(FieldExpr::cast(self.syntax.clone()).unwrap()),
             IF_EXPR => ExprKind::IfExpr(IfExpr::cast(self.syntax.clone()).unwrap()),
             LOOP_EXPR => ExprKind::LoopExpr(LoopExpr::cast(self.syntax.clone()).unwrap()),
             WHILE_EXPR => ExprKind::WhileExpr(WhileExpr::cast(self.syntax.clone()).unwrap()),
+            FOR_EXPR => ExprKind::ForExpr(ForExpr::cast(self.syntax.clone()).unwrap()),
             RETURN_EXPR => ExprKind::ReturnExpr(ReturnExpr::cast(self.syntax.clone()).unwrap()),
+            YIELD_EXPR => ExprKind::YieldExpr(YieldExpr::cast(self.syntax.clone()).unwrap()),
             BREAK_EXPR => ExprKind::BreakExpr(BreakExpr::cast(self.syntax.clone()).unwrap()),
             BLOCK_EXPR => ExprKind::BlockExpr(BlockExpr::cast(self.syntax.clone()).unwrap()),
             ARRAY_EXPR => ExprKind::ArrayExpr(ArrayExpr::cast(self.syntax.clone()).unwrap()),
@@ -573,6 +677,39 @@ impl FieldExpr {
     }
 }
 
+// ForExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for ForExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, FOR_EXPR)
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(ForExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl ast::LoopBodyOwner for ForExpr {}
+impl ForExpr {
+    pub fn pat(&self) -> Option<Pat> {
+        super::child_opt(self)
+    }
+
+    pub fn iterable(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
 // FunctionDef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -599,6 +736,7 @@ impl ast::NameOwner for FunctionDef {}
 impl ast::VisibilityOwner for FunctionDef {}
 impl ast::DocCommentsOwner for FunctionDef {}
 impl ast::ExternOwner for FunctionDef {}
+impl ast::AttrsOwner for FunctionDef {}
 impl FunctionDef {
     pub fn param_list(&self) -> Option<ParamList> {
         super::child_opt(self)
@@ -665,6 +803,7 @@ impl AstNode for Impl {
 }
 impl ast::VisibilityOwner for Impl {}
 impl ast::DocCommentsOwner for Impl {}
+impl ast::AttrsOwner for Impl {}
 impl Impl {
     pub fn associated_item_list(&self) -> Option<AssociatedItemList> {
         super::child_opt(self)
@@ -1210,6 +1349,10 @@ impl PathType {
     pub fn path(&self) -> Option<Path> {
         super::child_opt(self)
     }
+
+    pub fn type_arg_list(&self) -> Option<TypeArgList> {
+        super::child_opt(self)
+    }
 }
 
 // PlaceholderPat
@@ -1264,6 +1407,30 @@ impl PrefixExpr {
     }
 }
 
+// RangeExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for RangeExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, RANGE_EXPR)
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(RangeExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl RangeExpr {}
+
 // RecordField
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1322,7 +1489,11 @@ impl ast::NameOwner for RecordFieldDef {}
 impl ast::VisibilityOwner for RecordFieldDef {}
 impl ast::DocCommentsOwner for RecordFieldDef {}
 impl ast::TypeAscriptionOwner for RecordFieldDef {}
-impl RecordFieldDef {}
+impl RecordFieldDef {
+    pub fn default_value(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
 
 // RecordFieldDefList
 
@@ -1623,12 +1794,66 @@ impl AstNode for StructDef {
 impl ast::NameOwner for StructDef {}
 impl ast::VisibilityOwner for StructDef {}
 impl ast::DocCommentsOwner for StructDef {}
+impl ast::ExternOwner for StructDef {}
+impl ast::AttrsOwner for StructDef {}
 impl StructDef {
     pub fn memory_type_specifier(&self) -> Option<MemoryTypeSpecifier> {
         super::child_opt(self)
     }
 }
 
+// TokenTree
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TokenTree {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for TokenTree {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, TOKEN_TREE)
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(TokenTree { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl TokenTree {}
+
+// TryExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TryExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for TryExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, TRY_EXPR)
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(TryExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl TryExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
+
 // TupleFieldDef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1711,12 +1936,41 @@ impl AstNode for TypeAliasDef {
 impl ast::NameOwner for TypeAliasDef {}
 impl ast::VisibilityOwner for TypeAliasDef {}
 impl ast::DocCommentsOwner for TypeAliasDef {}
+impl ast::AttrsOwner for TypeAliasDef {}
 impl TypeAliasDef {
     pub fn type_ref(&self) -> Option<TypeRef> {
         super::child_opt(self)
     }
 }
 
+// TypeArgList
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeArgList {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for TypeArgList {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, TYPE_ARG_LIST)
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(TypeArgList { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl TypeArgList {
+    pub fn type_args(&self) -> impl Iterator<Item = TypeRef> {
+        super::children(self)
+    }
+}
+
 // TypeRef
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1920,6 +2174,30 @@ impl WhileExpr {
     }
 }
 
+// YieldExpr
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct YieldExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+
+impl AstNode for YieldExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, YIELD_EXPR)
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(YieldExpr { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl YieldExpr {}
+
 // IntNumber
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -0,0 +1,168 @@
+//! Entry points for fuzzing the parser.
+//!
+//! The parser must never panic, no matter the input. Any text, even arbitrary
+//! bytes, results in a syntax tree that contains error nodes where the input
+//! could not be parsed. The functions in this module parse the input and check
+//! the invariants of the resulting tree, they panic if an invariant does not
+//! hold. They are used by the fuzz targets in the `fuzz` directory of this
+//! crate, which can be run with `cargo fuzz run parser` or
+//! `cargo fuzz run reparse`.
+
+use std::str;
+
+use ra_ap_text_edit::Indel;
+
+use crate::{AstNode, Parse, SourceFile, SyntaxNode, SyntaxNodePtr, TextRange, TextSize};
+
+/// Parses `data` as a source file and checks the invariants of the resulting
+/// tree. Invalid UTF-8 sequences are replaced before parsing, like an editor
+/// would do when opening the file.
+pub fn check_parser(data: &[u8]) {
+    let text = String::from_utf8_lossy(data);
+    let parse = SourceFile::parse(&text);
+    check_parse_invariants(&parse, &text);
+}
+
+/// Checks that incrementally reparsing a source file after an edit results in
+/// the same tree as parsing the edited text from scratch. Returns early if
+/// `data` does not describe an edit, see [`CheckReparse::from_data`].
+pub fn check_reparse(data: &[u8]) {
+    if let Some(check) = CheckReparse::from_data(data) {
+        check.run();
+    }
+}
+
+/// A source text and an edit to apply to it.
+#[derive(Debug, Clone)]
+pub struct CheckReparse {
+    text: String,
+    edit: Indel,
+    edited_text: String,
+}
+
+impl CheckReparse {
+    /// Decodes a `CheckReparse` from fuzzer input. The first line contains the
+    /// offset of the edit, the second line the length of the text to delete,
+    /// the third line the text to insert and the rest of the input is the
+    /// source text. Returns `None` if the input does not describe a valid edit.
+    pub fn from_data(data: &[u8]) -> Option<Self> {
+        let data = str::from_utf8(data).ok()?;
+        let mut lines = data.splitn(4, '\n');
+        let offset: u32 = lines.next()?.trim().parse().ok()?;
+        let delete_len: u32 = lines.next()?.trim().parse().ok()?;
+        let insert = lines.next()?.to_owned();
+        let text = lines.next()?.to_owned();
+
+        let delete = TextRange::at(offset.into(), delete_len.into());
+        let full_range = TextRange::up_to(TextSize::of(text.as_str()));
+        if !full_range.contains_range(delete)
+            || !text.is_char_boundary(delete.start().into())
+            || !text.is_char_boundary(delete.end().into())
+        {
+            return None;
+        }
+
+        let edit = Indel { insert, delete };
+        let mut edited_text = text.clone();
+        edit.apply(&mut edited_text);
+
+        Some(CheckReparse {
+            text,
+            edit,
+            edited_text,
+        })
+    }
+
+    /// Reparses the text with the edit applied and compares the result with
+    /// parsing the edited text from scratch.
+    pub fn run(&self) {
+        let parse = SourceFile::parse(&self.text);
+        let new_parse = parse.reparse(&self.edit);
+        check_parse_invariants(&new_parse, &self.edited_text);
+
+        let full_reparse = SourceFile::parse(&self.edited_text);
+        assert_eq!(
+            format!("{:#?}", new_parse.tree().syntax()),
+            format!("{:#?}", full_reparse.tree().syntax()),
+            "incremental reparse differs from full reparse"
+        );
+        assert_eq!(new_parse.errors(), full_reparse.errors());
+    }
+}
+
+/// Checks the invariants that must hold for the result of parsing `text`.
+fn check_parse_invariants(parse: &Parse<SourceFile>, text: &str) {
+    let root = parse.syntax_node();
+
+    // The tree represents the input text exactly
+    assert_eq!(
+        root.text().to_string(),
+        text,
+        "tree text differs from input"
+    );
+
+    // All errors point into the text
+    let full_range = root.text_range();
+    for error in parse.errors() {
+        let location = error.location();
+        assert!(
+            location.offset() <= location.end_offset()
+                && full_range.contains_inclusive(location.offset())
+                && full_range.contains_inclusive(location.end_offset()),
+            "error {error:?} lies outside of the text"
+        );
+    }
+
+    check_node_invariants(&root);
+}
+
+/// Checks that the children of every node cover the node without gaps and that
+/// every non-empty node can be found again from a pointer to it. Empty nodes,
+/// e.g. error nodes for missing tokens, cannot be told apart from their
+/// siblings by their range.
+fn check_node_invariants(root: &SyntaxNode) {
+    for node in root.descendants() {
+        let mut offset = node.text_range().start();
+        for child in node.children_with_tokens() {
+            assert_eq!(
+                child.text_range().start(),
+                offset,
+                "gap between the children of {node:?}"
+            );
+            offset = child.text_range().end();
+        }
+        assert_eq!(
+            offset,
+            node.text_range().end(),
+            "children of {node:?} are too short"
+        );
+
+        if !node.text_range().is_empty() {
+            assert_eq!(SyntaxNodePtr::new(&node).to_node(root), node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_parser, check_reparse};
+
+    #[test]
+    fn parser_invariants() {
+        check_parser(b"fn foo(a: i32) -> i32 { a + 1 }");
+        check_parser(b"struct Foo { a: i32, \xff\xfe }");
+        check_parser(b"impl { fn } pub use ::{");
+        check_parser(b"fn main() { let a = \"abc; /* ");
+        check_parser(b"");
+    }
+
+    #[test]
+    fn reparse_invariants() {
+        check_reparse(b"14\n1\n2\nfn foo() -> i32 { 1 }");
+        check_reparse(b"0\n2\nstruct\nfn foo() {}");
+
+        // Invalid edits are ignored
+        check_reparse(b"100\n0\n\nfn foo() {}");
+        check_reparse(b"not an edit");
+    }
+}
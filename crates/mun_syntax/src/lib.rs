@@ -14,6 +14,7 @@
 mod syntax_kind;
 
 pub mod ast;
+pub mod fuzz;
 mod parsing;
 mod ptr;
 mod syntax_error;
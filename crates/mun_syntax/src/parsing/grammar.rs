@@ -1,4 +1,5 @@
 mod adt;
+mod attributes;
 mod declarations;
 mod expressions;
 mod params;
@@ -11,15 +12,15 @@ use super::{
     parser::{CompletedMarker, Marker, Parser},
     token_set::TokenSet,
     SyntaxKind::{
-        self, ARG_LIST, ARRAY_EXPR, ARRAY_TYPE, BIND_PAT, BIN_EXPR, BLOCK_EXPR, BREAK_EXPR,
-        CALL_EXPR, CONDITION, EOF, ERROR, EXPR_STMT, EXTERN, FIELD_EXPR, FLOAT_NUMBER,
-        FUNCTION_DEF, GC_KW, IDENT, IF_EXPR, INDEX, INDEX_EXPR, INT_NUMBER, LET_STMT, LITERAL,
-        LOOP_EXPR, MEMORY_TYPE_SPECIFIER, NAME, NAME_REF, NEVER_TYPE, PARAM, PARAM_LIST,
+        self, ARG_LIST, ARRAY_EXPR, ARRAY_TYPE, ATTR, BIND_PAT, BIN_EXPR, BLOCK_EXPR, BREAK_EXPR,
+        CALL_EXPR, CAST_EXPR, CONDITION, EOF, ERROR, EXPR_STMT, EXTERN, FIELD_EXPR, FLOAT_NUMBER,
+        FOR_EXPR, FUNCTION_DEF, GC_KW, IDENT, IF_EXPR, INDEX, INDEX_EXPR, INT_NUMBER, LET_STMT,
+        LITERAL, LOOP_EXPR, MEMORY_TYPE_SPECIFIER, NAME, NAME_REF, NEVER_TYPE, PARAM, PARAM_LIST,
         PAREN_EXPR, PATH, PATH_EXPR, PATH_SEGMENT, PATH_TYPE, PLACEHOLDER_PAT, PREFIX_EXPR,
-        RECORD_FIELD, RECORD_FIELD_DEF, RECORD_FIELD_DEF_LIST, RECORD_FIELD_LIST, RECORD_LIT,
-        RENAME, RETURN_EXPR, RET_TYPE, SELF_PARAM, SOURCE_FILE, STRING, STRUCT_DEF,
-        TUPLE_FIELD_DEF, TUPLE_FIELD_DEF_LIST, TYPE_ALIAS_DEF, USE, USE_TREE, USE_TREE_LIST,
-        VALUE_KW, VISIBILITY, WHILE_EXPR,
+        RANGE_EXPR, RECORD_FIELD, RECORD_FIELD_DEF, RECORD_FIELD_DEF_LIST, RECORD_FIELD_LIST,
+        RECORD_LIT, RENAME, RESUMABLE_KW, RETURN_EXPR, RET_TYPE, SELF_PARAM, SOURCE_FILE, STRING,
+        STRUCT_DEF, TOKEN_TREE, TRY_EXPR, TUPLE_FIELD_DEF, TUPLE_FIELD_DEF_LIST, TYPE_ALIAS_DEF,
+        TYPE_ARG_LIST, USE, USE_TREE, USE_TREE_LIST, VALUE_KW, VISIBILITY, WHILE_EXPR, YIELD_EXPR,
     },
 };
 
@@ -64,7 +65,7 @@ fn name_recovery(p: &mut Parser<'_>, recovery: TokenSet) {
 }
 
 fn name(p: &mut Parser<'_>) {
-    name_recovery(p, TokenSet::empty());
+    name_recovery(p, declarations::DECLARATION_RECOVERY_SET);
 }
 
 fn name_ref(p: &mut Parser<'_>) {
@@ -106,6 +107,53 @@ fn opt_visibility(p: &mut Parser<'_>) -> bool {
     }
 }
 
+/// Parses the elements of a list that are separated by `delim` and closed by
+/// `ket`, e.g. the arguments of a call. The opening delimiter must already have
+/// been consumed.
+///
+/// An element is only parsed if the current token is in `first`. Other tokens
+/// are reported and skipped, and a block is skipped as a whole, until the list
+/// can be continued. The list is closed early at the tokens in `recovery`, so
+/// an unclosed list does not consume the code that follows it.
+fn delimited(
+    p: &mut Parser<'_>,
+    ket: SyntaxKind,
+    delim: SyntaxKind,
+    message: &str,
+    first: TokenSet,
+    recovery: TokenSet,
+    mut element: impl FnMut(&mut Parser<'_>),
+) {
+    while !p.at(EOF) && !p.at(ket) {
+        if p.at(delim) {
+            // An element is missing, e.g. `(a, , b)`
+            let m = p.start();
+            p.error(message);
+            p.bump(delim);
+            m.complete(p, ERROR);
+            continue;
+        }
+
+        if !p.at_ts(first) {
+            if p.at(T!['}']) || p.at_ts(recovery) {
+                break;
+            } else if p.at(T!['{']) {
+                error_block(p, message);
+            } else {
+                p.error_and_bump(message);
+            }
+            p.eat(delim);
+            continue;
+        }
+
+        element(p);
+        if !p.at(ket) && !p.eat(delim) && p.at_ts(first) {
+            p.error(format!("expected {delim:?}"));
+        }
+    }
+    p.expect(ket);
+}
+
 fn error_block(p: &mut Parser<'_>, message: &str) {
     assert!(p.at(T!['{']));
     let m = p.start();
@@ -1,14 +1,36 @@
 use super::{
-    declarations, error_block, name, name_recovery, opt_visibility, types, Marker, Parser, EOF,
-    GC_KW, IDENT, MEMORY_TYPE_SPECIFIER, RECORD_FIELD_DEF, RECORD_FIELD_DEF_LIST, STRUCT_DEF,
-    TUPLE_FIELD_DEF, TUPLE_FIELD_DEF_LIST, TYPE_ALIAS_DEF, VALUE_KW,
+    declarations, delimited, expressions, name, name_recovery, opt_visibility, types, Marker,
+    Parser, TokenSet, GC_KW, IDENT, MEMORY_TYPE_SPECIFIER, RECORD_FIELD_DEF, RECORD_FIELD_DEF_LIST,
+    STRUCT_DEF, TUPLE_FIELD_DEF, TUPLE_FIELD_DEF_LIST, TYPE_ALIAS_DEF, VALUE_KW,
 };
 
+/// The tokens that start a field
+const FIELD_FIRST: TokenSet = TokenSet::new(&[IDENT, T![pub]]);
+
+/// The tokens at which an unclosed record field list ends. Unlike other
+/// declarations, fields can start with `pub`.
+const RECORD_FIELD_RECOVERY_SET: TokenSet = TokenSet::new(&[
+    T![fn],
+    T![struct],
+    T![type],
+    T![use],
+    T![impl],
+    T![extern],
+    T![#],
+]);
+
+/// The tokens at which an unclosed tuple field list ends
+const TUPLE_FIELD_RECOVERY_SET: TokenSet =
+    declarations::DECLARATION_RECOVERY_SET.union(TokenSet::new(&[T!['{']]));
+
 pub(super) fn struct_def(p: &mut Parser<'_>, m: Marker) {
     assert!(p.at(T![struct]));
     p.bump(T![struct]);
     opt_memory_type_specifier(p);
-    name_recovery(p, declarations::DECLARATION_RECOVERY_SET);
+    name_recovery(
+        p,
+        declarations::DECLARATION_RECOVERY_SET.union(TokenSet::new(&[T!['(']])),
+    );
     match p.current() {
         T![;] => {
             p.bump(T![;]);
@@ -25,7 +47,10 @@ pub(super) fn struct_def(p: &mut Parser<'_>, m: Marker) {
 pub(super) fn type_alias_def(p: &mut Parser<'_>, m: Marker) {
     assert!(p.at(T![type]));
     p.bump(T![type]);
-    name(p);
+    name_recovery(
+        p,
+        declarations::DECLARATION_RECOVERY_SET.union(TokenSet::new(&[T![=]])),
+    );
     if p.eat(T![=]) {
         types::type_(p);
     }
@@ -37,17 +62,15 @@ pub(super) fn record_field_def_list(p: &mut Parser<'_>) {
     assert!(p.at(T!['{']));
     let m = p.start();
     p.bump(T!['{']);
-    while !p.at(T!['}']) && !p.at(EOF) {
-        if p.at(T!['{']) {
-            error_block(p, "expected a field");
-            continue;
-        }
-        record_field_def(p);
-        if !p.at(T!['}']) {
-            p.expect(T![,]);
-        }
-    }
-    p.expect(T!['}']);
+    delimited(
+        p,
+        T!['}'],
+        T![,],
+        "expected a field declaration",
+        FIELD_FIRST,
+        RECORD_FIELD_RECOVERY_SET,
+        record_field_def,
+    );
     p.eat(T![;]);
     m.complete(p, RECORD_FIELD_DEF_LIST);
 }
@@ -76,25 +99,30 @@ pub(super) fn tuple_field_def_list(p: &mut Parser<'_>) {
     assert!(p.at(T!['(']));
     let m = p.start();
     p.bump(T!['(']);
-    while !p.at(T![')']) && !p.at(EOF) {
-        let m = p.start();
-        if !p.at_ts(types::TYPE_FIRST) {
-            m.abandon(p);
-            p.error_and_bump("expected a type");
-            break;
-        }
-        types::type_(p);
-        m.complete(p, TUPLE_FIELD_DEF);
-
-        if !p.at(T![')']) {
-            p.expect(T![,]);
-        }
-    }
-    p.expect(T![')']);
+    delimited(
+        p,
+        T![')'],
+        T![,],
+        "expected a type",
+        types::TYPE_FIRST.union(TokenSet::new(&[T![pub]])),
+        TUPLE_FIELD_RECOVERY_SET,
+        tuple_field_def,
+    );
     p.eat(T![;]);
     m.complete(p, TUPLE_FIELD_DEF_LIST);
 }
 
+fn tuple_field_def(p: &mut Parser<'_>) {
+    let m = p.start();
+    opt_visibility(p);
+    if p.at_ts(types::TYPE_FIRST) {
+        types::type_(p);
+    } else {
+        p.error("expected a type");
+    }
+    m.complete(p, TUPLE_FIELD_DEF);
+}
+
 fn record_field_def(p: &mut Parser<'_>) {
     let m = p.start();
     opt_visibility(p);
@@ -102,9 +130,11 @@ fn record_field_def(p: &mut Parser<'_>) {
         name(p);
         p.expect(T![:]);
         types::type_(p);
-        m.complete(p, RECORD_FIELD_DEF);
+        if p.eat(T![=]) {
+            expressions::expr(p);
+        }
     } else {
-        m.abandon(p);
-        p.error_and_bump("expected a field declaration");
+        p.error("expected a field name");
     }
+    m.complete(p, RECORD_FIELD_DEF);
 }
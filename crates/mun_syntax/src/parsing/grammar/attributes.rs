@@ -0,0 +1,49 @@
+use super::{paths, Parser, ATTR, EOF, TOKEN_TREE};
+
+/// Parses the attributes in front of a declaration, e.g.
+/// `#[allow(dead_code)]`.
+pub(super) fn outer_attrs(p: &mut Parser<'_>) {
+    while p.at(T![#]) {
+        attr(p);
+    }
+}
+
+fn attr(p: &mut Parser<'_>) {
+    assert!(p.at(T![#]));
+    let m = p.start();
+    p.bump(T![#]);
+    if p.expect(T!['[']) {
+        if paths::is_path_start(p) {
+            paths::type_path(p);
+        } else {
+            p.error("expected an attribute name");
+        }
+        if matches!(p.current(), T!['('] | T!['['] | T!['{']) {
+            token_tree(p);
+        }
+        p.expect(T![']']);
+    }
+    m.complete(p, ATTR);
+}
+
+/// Parses a sequence of arbitrary tokens enclosed by balanced delimiters, e.g.
+/// the arguments of an attribute.
+fn token_tree(p: &mut Parser<'_>) {
+    let closing = match p.current() {
+        T!['('] => T![')'],
+        T!['['] => T![']'],
+        T!['{'] => T!['}'],
+        _ => unreachable!("a token tree starts with a delimiter"),
+    };
+    let m = p.start();
+    p.bump_any();
+    while !p.at(EOF) && !p.at(closing) {
+        match p.current() {
+            T!['('] | T!['['] | T!['{'] => token_tree(p),
+            T![')'] | T![']'] | T!['}'] => break,
+            _ => p.bump_any(),
+        }
+    }
+    p.expect(closing);
+    m.complete(p, TOKEN_TREE);
+}
@@ -1,12 +1,26 @@
 use super::{
-    adt, error_block, expressions, name, name_recovery, opt_visibility, params, paths, traits,
-    types, Marker, Parser, TokenSet, EOF, ERROR, EXTERN, FUNCTION_DEF, RENAME, RET_TYPE, USE,
-    USE_TREE, USE_TREE_LIST,
+    adt, attributes, delimited, error_block, expressions, name, name_recovery, opt_visibility,
+    params, paths, traits, types, Marker, Parser, TokenSet, EOF, ERROR, EXTERN, FUNCTION_DEF,
+    IDENT, RENAME, RESUMABLE_KW, RET_TYPE, USE, USE_TREE, USE_TREE_LIST,
 };
 use crate::{parsing::grammar::paths::is_use_path_start, T};
 
+/// The tokens that start a declaration. When recovering from an error, the
+/// parser never skips these tokens, because they most likely start the next
+/// declaration.
+pub(super) const DECLARATION_FIRST: TokenSet = TokenSet::new(&[
+    T![fn],
+    T![pub],
+    T![struct],
+    T![type],
+    T![use],
+    T![impl],
+    T![extern],
+    T![#],
+]);
+
 pub(super) const DECLARATION_RECOVERY_SET: TokenSet =
-    TokenSet::new(&[T![fn], T![pub], T![struct], T![use], T![;], T![impl]]);
+    DECLARATION_FIRST.union(TokenSet::new(&[T![;]]));
 
 pub(super) fn mod_contents(p: &mut Parser<'_>) {
     while !p.at(EOF) {
@@ -16,6 +30,7 @@ pub(super) fn mod_contents(p: &mut Parser<'_>) {
 
 pub(super) fn declaration(p: &mut Parser<'_>, stop_on_r_curly: bool) {
     let m = p.start();
+    attributes::outer_attrs(p);
     let m = match maybe_declaration(p, m) {
         Ok(()) => return,
         Err(m) => m,
@@ -31,7 +46,16 @@ pub(super) fn declaration(p: &mut Parser<'_>, stop_on_r_curly: bool) {
             e.complete(p, ERROR);
         }
         EOF | T!['}'] => p.error("expected a declaration"),
-        _ => p.error_and_bump("expected a declaration"),
+        _ => {
+            // Skip everything up to the start of the next declaration
+            let e = p.start();
+            p.error("expected a declaration");
+            p.bump_any();
+            while !p.at(EOF) && !p.at(T!['{']) && !p.at(T!['}']) && !p.at_ts(DECLARATION_FIRST) {
+                p.bump_any();
+            }
+            e.complete(p, ERROR);
+        }
     }
 }
 
@@ -47,11 +71,20 @@ pub(super) fn maybe_declaration(p: &mut Parser<'_>, m: Marker) -> Result<(), Mar
         abi(p);
     }
 
+    // `resumable` is a contextual keyword, so it is only a modifier if it is
+    // followed by `fn`
+    if p.at_contextual_kw("resumable") && p.nth_at(1, T![fn]) {
+        p.bump_remap(RESUMABLE_KW);
+    }
+
     match p.current() {
         T![fn] => {
             fn_def(p);
             m.complete(p, FUNCTION_DEF);
         }
+        T![struct] => {
+            adt::struct_def(p, m);
+        }
         _ => return Err(m),
     }
     Ok(())
@@ -87,7 +120,10 @@ pub(super) fn fn_def(p: &mut Parser<'_>) {
     assert!(p.at(T![fn]));
     p.bump(T![fn]);
 
-    name_recovery(p, DECLARATION_RECOVERY_SET.union(TokenSet::new(&[T![')']])));
+    name_recovery(
+        p,
+        DECLARATION_RECOVERY_SET.union(TokenSet::new(&[T!['('], T![')']])),
+    );
 
     if p.at(T!['(']) {
         params::param_list(p);
@@ -172,16 +208,21 @@ fn use_tree_list(p: &mut Parser<'_>) {
     assert!(p.at(T!['{']));
     let m = p.start();
     p.bump(T!['{']);
-    while !p.at(EOF) && !p.at(T!['}']) {
-        use_tree(p, false);
-        if !p.at(T!['}']) {
-            p.expect(T![,]);
-        }
-    }
-    p.expect(T!['}']);
+    delimited(
+        p,
+        T!['}'],
+        T![,],
+        "expected a use tree",
+        USE_TREE_FIRST,
+        DECLARATION_RECOVERY_SET,
+        |p| use_tree(p, false),
+    );
     m.complete(p, USE_TREE_LIST);
 }
 
+/// The tokens that start a nested use tree
+const USE_TREE_FIRST: TokenSet = TokenSet::new(&[IDENT, T![self], T![*]]);
+
 fn opt_rename(p: &mut Parser<'_>) {
     if p.at(T![as]) {
         let m = p.start();
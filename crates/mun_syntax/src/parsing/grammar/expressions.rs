@@ -1,17 +1,26 @@
 use super::{
-    error_block, expressions, name_ref_or_index, paths, patterns, types, BlockLike,
+    declarations, delimited, expressions, name_ref_or_index, paths, patterns, types, BlockLike,
     CompletedMarker, Marker, Parser, SyntaxKind, TokenSet, ARG_LIST, ARRAY_EXPR, BIN_EXPR,
-    BLOCK_EXPR, BREAK_EXPR, CALL_EXPR, CONDITION, EOF, ERROR, EXPR_STMT, FIELD_EXPR, FLOAT_NUMBER,
-    IDENT, IF_EXPR, INDEX, INDEX_EXPR, INT_NUMBER, LET_STMT, LITERAL, LOOP_EXPR, PAREN_EXPR,
-    PATH_EXPR, PATH_TYPE, PREFIX_EXPR, RECORD_FIELD, RECORD_FIELD_LIST, RECORD_LIT, RETURN_EXPR,
-    STRING, WHILE_EXPR,
+    BLOCK_EXPR, BREAK_EXPR, CALL_EXPR, CAST_EXPR, CONDITION, EOF, ERROR, EXPR_STMT, FIELD_EXPR,
+    FLOAT_NUMBER, FOR_EXPR, IDENT, IF_EXPR, INDEX, INDEX_EXPR, INT_NUMBER, LET_STMT, LITERAL,
+    LOOP_EXPR, PAREN_EXPR, PATH_EXPR, PATH_TYPE, PREFIX_EXPR, RANGE_EXPR, RECORD_FIELD,
+    RECORD_FIELD_LIST, RECORD_LIT, RETURN_EXPR, STRING, TRY_EXPR, WHILE_EXPR, YIELD_EXPR,
 };
 use crate::parsing::grammar::paths::PATH_FIRST;
 
 pub(crate) const LITERAL_FIRST: TokenSet =
     TokenSet::new(&[T![true], T![false], INT_NUMBER, FLOAT_NUMBER, STRING]);
 
-const EXPR_RECOVERY_SET: TokenSet = TokenSet::new(&[T![let]]);
+/// The tokens that are never skipped when an expression is missing. A block
+/// ends at the start of a declaration, which most likely means that the block
+/// is not closed.
+const EXPR_RECOVERY_SET: TokenSet =
+    declarations::DECLARATION_FIRST.union(TokenSet::new(&[T![let], T![;]]));
+
+/// The tokens at which an unclosed list of expressions ends, e.g. the arguments
+/// of a call
+const EXPR_LIST_RECOVERY_SET: TokenSet =
+    EXPR_RECOVERY_SET.union(TokenSet::new(&[T![')'], T![']']]));
 
 const ATOM_EXPR_FIRST: TokenSet = LITERAL_FIRST.union(PATH_FIRST).union(TokenSet::new(&[
     IDENT,
@@ -21,8 +30,10 @@ const ATOM_EXPR_FIRST: TokenSet = LITERAL_FIRST.union(PATH_FIRST).union(TokenSet
     T![if],
     T![loop],
     T![return],
+    T![yield],
     T![break],
     T![while],
+    T![for],
 ]));
 
 const LHS_FIRST: TokenSet = ATOM_EXPR_FIRST.union(TokenSet::new(&[T![!], T![-]]));
@@ -46,7 +57,7 @@ struct Restrictions {
 }
 
 pub(crate) fn expr_block_contents(p: &mut Parser<'_>) {
-    while !p.at(EOF) && !p.at(T!['}']) {
+    while !p.at(EOF) && !p.at(T!['}']) && !p.at_ts(declarations::DECLARATION_FIRST) {
         if p.eat(T![;]) {
             continue;
         }
@@ -153,11 +164,21 @@ fn expr_bp(p: &mut Parser<'_>, r: Restrictions, bp: u8) -> (Option<CompletedMark
             break;
         }
 
+        if op == T![as] {
+            lhs = cast_expr(p, lhs);
+            continue;
+        }
+
         let m = lhs.precede(p);
         p.bump(op);
 
         expr_bp(p, r, op_bp + 1);
-        lhs = m.complete(p, BIN_EXPR);
+        let kind = if op == T![..] || op == T![..=] {
+            RANGE_EXPR
+        } else {
+            BIN_EXPR
+        };
+        lhs = m.complete(p, kind);
     }
 
     (Some(lhs), BlockLike::NotBlock)
@@ -165,6 +186,7 @@ fn expr_bp(p: &mut Parser<'_>, r: Restrictions, bp: u8) -> (Option<CompletedMark
 
 fn current_op(p: &Parser<'_>) -> (u8, SyntaxKind) {
     match p.current() {
+        T![as] => (12, T![as]),
         T![+] if p.at(T![+=]) => (1, T![+=]),
         T![+] => (10, T![+]),
         T![-] if p.at(T![-=]) => (1, T![-=]),
@@ -183,6 +205,8 @@ fn current_op(p: &Parser<'_>) -> (u8, SyntaxKind) {
         T![|] => (6, T![|]),
         T![^] if p.at(T![^=]) => (1, T![^=]),
         T![^] => (7, T![^]),
+        T![.] if p.at(T![..=]) => (2, T![..=]),
+        T![.] if p.at(T![..]) => (2, T![..]),
         T![=] if p.at(T![==]) => (5, T![==]),
         T![=] => (1, T![=]),
         T![!] if p.at(T![!=]) => (5, T![!=]),
@@ -198,6 +222,14 @@ fn current_op(p: &Parser<'_>) -> (u8, SyntaxKind) {
     }
 }
 
+fn cast_expr(p: &mut Parser<'_>, lhs: CompletedMarker) -> CompletedMarker {
+    assert!(p.at(T![as]));
+    let m = lhs.precede(p);
+    p.bump(T![as]);
+    types::type_no_type_args(p);
+    m.complete(p, CAST_EXPR)
+}
+
 fn lhs(p: &mut Parser<'_>, r: Restrictions) -> Option<(CompletedMarker, BlockLike)> {
     let m;
     let kind = match p.current() {
@@ -228,8 +260,9 @@ fn postfix_expr(
         lhs = match p.current() {
             T!['('] if allow_calls => call_expr(p, lhs),
             T!['['] if allow_calls => index_expr(p, lhs),
-            T![.] => postfix_dot_expr(p, lhs),
+            T![.] if !p.at(T![..]) => postfix_dot_expr(p, lhs),
             INDEX => field_expr(p, lhs),
+            T![?] => try_expr(p, lhs),
             _ => break,
         };
         allow_calls = true;
@@ -245,6 +278,13 @@ fn call_expr(p: &mut Parser<'_>, lhs: CompletedMarker) -> CompletedMarker {
     m.complete(p, CALL_EXPR)
 }
 
+fn try_expr(p: &mut Parser<'_>, lhs: CompletedMarker) -> CompletedMarker {
+    assert!(p.at(T![?]));
+    let m = lhs.precede(p);
+    p.bump(T![?]);
+    m.complete(p, TRY_EXPR)
+}
+
 fn index_expr(p: &mut Parser<'_>, lhs: CompletedMarker) -> CompletedMarker {
     assert!(p.at(T!['[']));
     let m = lhs.precede(p);
@@ -258,18 +298,15 @@ fn arg_list(p: &mut Parser<'_>) {
     assert!(p.at(T!['(']));
     let m = p.start();
     p.bump(T!['(']);
-    while !p.at(T![')']) && !p.at(EOF) {
-        if !p.at_ts(EXPR_FIRST) {
-            p.error("expected expression");
-            break;
-        }
-
-        expr(p);
-        if !p.at(T![')']) && !p.expect(T![,]) {
-            break;
-        }
-    }
-    p.eat(T![')']);
+    delimited(
+        p,
+        T![')'],
+        T![,],
+        "expected expression",
+        EXPR_FIRST,
+        EXPR_LIST_RECOVERY_SET,
+        expr,
+    );
     m.complete(p, ARG_LIST);
 }
 
@@ -317,7 +354,9 @@ fn atom_expr(p: &mut Parser<'_>, r: Restrictions) -> Option<(CompletedMarker, Bl
         T![if] => if_expr(p),
         T![loop] => loop_expr(p),
         T![return] => ret_expr(p),
+        T![yield] => yield_expr(p),
         T![while] => while_expr(p),
+        T![for] => for_expr(p),
         T![break] => break_expr(p, r),
         _ => {
             p.error_recover("expected expression", EXPR_RECOVERY_SET);
@@ -325,7 +364,7 @@ fn atom_expr(p: &mut Parser<'_>, r: Restrictions) -> Option<(CompletedMarker, Bl
         }
     };
     let blocklike = match marker.kind() {
-        IF_EXPR | WHILE_EXPR | LOOP_EXPR | BLOCK_EXPR => BlockLike::Block,
+        IF_EXPR | WHILE_EXPR | LOOP_EXPR | FOR_EXPR | BLOCK_EXPR => BlockLike::Block,
         _ => BlockLike::NotBlock,
     };
     Some((marker, blocklike))
@@ -404,6 +443,13 @@ fn ret_expr(p: &mut Parser<'_>) -> CompletedMarker {
     m.complete(p, RETURN_EXPR)
 }
 
+fn yield_expr(p: &mut Parser<'_>) -> CompletedMarker {
+    assert!(p.at(T![yield]));
+    let m = p.start();
+    p.bump(T![yield]);
+    m.complete(p, YIELD_EXPR)
+}
+
 fn break_expr(p: &mut Parser<'_>, r: Restrictions) -> CompletedMarker {
     assert!(p.at(T![break]));
     let m = p.start();
@@ -423,44 +469,56 @@ fn while_expr(p: &mut Parser<'_>) -> CompletedMarker {
     m.complete(p, WHILE_EXPR)
 }
 
+fn for_expr(p: &mut Parser<'_>) -> CompletedMarker {
+    assert!(p.at(T![for]));
+    let m = p.start();
+    p.bump(T![for]);
+    patterns::pattern(p);
+    p.expect(T![in]);
+    expr_no_struct(p);
+    block(p);
+    m.complete(p, FOR_EXPR)
+}
+
 fn record_field_list(p: &mut Parser<'_>) {
     assert!(p.at(T!['{']));
     let m = p.start();
     p.bump(T!['{']);
-    while !p.at(EOF) && !p.at(T!['}']) {
-        match p.current() {
-            IDENT | INT_NUMBER => {
-                let m = p.start();
-                name_ref_or_index(p);
-                if p.eat(T![:]) {
-                    expr(p);
-                }
-                m.complete(p, RECORD_FIELD);
-            }
-            T!['{'] => error_block(p, "expected a field"),
-            _ => p.error_and_bump("expected an identifier"),
-        }
-        if !p.at(T!['}']) {
-            p.expect(T![,]);
-        }
-    }
-    p.expect(T!['}']);
+    delimited(
+        p,
+        T!['}'],
+        T![,],
+        "expected a field",
+        TokenSet::new(&[IDENT, INT_NUMBER]),
+        EXPR_RECOVERY_SET,
+        record_field,
+    );
     m.complete(p, RECORD_FIELD_LIST);
 }
 
+fn record_field(p: &mut Parser<'_>) {
+    let m = p.start();
+    name_ref_or_index(p);
+    if p.eat(T![:]) {
+        expr(p);
+    }
+    m.complete(p, RECORD_FIELD);
+}
+
 fn array_expr(p: &mut Parser<'_>) -> CompletedMarker {
     assert!(p.at(T!['[']));
     let m = p.start();
 
     p.bump(T!['[']);
-    while !p.at(EOF) && !p.at(T![']']) {
-        expr(p);
-
-        if !p.at(T![']']) && !p.expect(T![,]) {
-            break;
-        }
-    }
-    p.expect(T![']']);
+    delimited(
+        p,
+        T![']'],
+        T![,],
+        "expected expression",
+        EXPR_FIRST,
+        EXPR_LIST_RECOVERY_SET,
+        expr,
+    );
 
     m.complete(p, ARRAY_EXPR)
 }
@@ -1,4 +1,7 @@
-use super::{patterns, types, Parser, TokenSet, EOF, NAME, PARAM, PARAM_LIST, SELF_PARAM};
+use super::{
+    declarations, delimited, patterns, types, Parser, TokenSet, IDENT, NAME, PARAM, PARAM_LIST,
+    SELF_PARAM,
+};
 
 pub(super) fn param_list(p: &mut Parser<'_>) {
     list(p);
@@ -12,21 +15,24 @@ fn list(p: &mut Parser<'_>) {
 
     opt_self_param(p);
 
-    while !p.at(EOF) && !p.at(T![')']) {
-        if !p.at_ts(VALUE_PARAMETER_FIRST) {
-            p.error("expected value parameter");
-            break;
-        }
-        param(p);
-        if !p.at(T![')']) {
-            p.expect(T![,]);
-        }
-    }
-    p.expect(T![')']);
+    delimited(
+        p,
+        T![')'],
+        T![,],
+        "expected value parameter",
+        VALUE_PARAMETER_FIRST,
+        PARAMETER_RECOVERY_SET,
+        param,
+    );
     m.complete(p, PARAM_LIST);
 }
 
-const VALUE_PARAMETER_FIRST: TokenSet = patterns::PATTERN_FIRST;
+const VALUE_PARAMETER_FIRST: TokenSet = TokenSet::new(&[IDENT, T![_]]);
+
+/// The tokens at which an unclosed parameter list ends, e.g. the `->` of the
+/// return type or the body of the function
+const PARAMETER_RECOVERY_SET: TokenSet =
+    declarations::DECLARATION_RECOVERY_SET.union(TokenSet::new(&[T![-], T!['{']]));
 
 fn param(p: &mut Parser<'_>) {
     let m = p.start();
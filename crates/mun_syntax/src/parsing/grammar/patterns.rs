@@ -1,13 +1,12 @@
-use super::{
-    expressions, name, paths, CompletedMarker, Parser, TokenSet, BIND_PAT, IDENT, PLACEHOLDER_PAT,
-};
+use super::{name, CompletedMarker, Parser, TokenSet, BIND_PAT, IDENT, PLACEHOLDER_PAT};
 
-pub(super) const PATTERN_FIRST: TokenSet = expressions::LITERAL_FIRST
-    .union(paths::PATH_FIRST)
-    .union(TokenSet::new(&[T![-], T![_]]));
+/// The tokens that follow a pattern. If a pattern is missing, e.g. `let = 1;`,
+/// these tokens are not skipped.
+const PATTERN_RECOVERY_SET: TokenSet =
+    TokenSet::new(&[T![let], T![in], T![=], T![:], T![;], T![,], T![')']]);
 
 pub(super) fn pattern(p: &mut Parser<'_>) {
-    pattern_r(p, PATTERN_FIRST);
+    pattern_r(p, PATTERN_RECOVERY_SET);
 }
 
 pub(super) fn pattern_r(p: &mut Parser<'_>, recovery_set: TokenSet) {
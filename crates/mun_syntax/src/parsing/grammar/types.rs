@@ -1,9 +1,26 @@
-use super::{paths, Parser, TokenSet, ARRAY_TYPE, NEVER_TYPE, PATH_TYPE};
+use super::{
+    declarations, delimited, paths, Parser, TokenSet, ARRAY_TYPE, NEVER_TYPE, PATH_TYPE,
+    TYPE_ARG_LIST,
+};
 
 pub(super) const TYPE_FIRST: TokenSet =
     paths::PATH_FIRST.union(TokenSet::new(&[T![never], T!['[']]));
 
-pub(super) const TYPE_RECOVERY_SET: TokenSet = TokenSet::new(&[T!['('], T![,], T![pub]]);
+/// The tokens that follow a type. If a type is missing, e.g. `let a: = 1;`,
+/// these tokens are not skipped.
+pub(super) const TYPE_RECOVERY_SET: TokenSet =
+    declarations::DECLARATION_RECOVERY_SET.union(TokenSet::new(&[
+        T!['('],
+        T![')'],
+        T![']'],
+        T![,],
+        T![=],
+        T![>],
+    ]));
+
+/// The tokens at which an unclosed list of type arguments ends, e.g. the body
+/// of the function in `fn foo() -> Foo<i32 {}`
+const TYPE_ARG_RECOVERY_SET: TokenSet = TYPE_RECOVERY_SET.union(TokenSet::new(&[T!['{']]));
 
 pub(super) fn ascription(p: &mut Parser<'_>) {
     p.expect(T![:]);
@@ -11,22 +28,53 @@ pub(super) fn ascription(p: &mut Parser<'_>) {
 }
 
 pub(super) fn type_(p: &mut Parser<'_>) {
+    type_with_args(p, true);
+}
+
+/// Parses a type without type arguments at the top level. This is used after
+/// `as`, where a `<` is parsed as a comparison, e.g. `a as i32 < b`.
+pub(super) fn type_no_type_args(p: &mut Parser<'_>) {
+    type_with_args(p, false);
+}
+
+fn type_with_args(p: &mut Parser<'_>, allow_type_args: bool) {
     match p.current() {
         T!['['] => array_type(p),
         T![never] => never_type(p),
-        _ if paths::is_path_start(p) => path_type(p),
+        _ if paths::is_path_start(p) => path_type(p, allow_type_args),
         _ => {
             p.error_recover("expected type", TYPE_RECOVERY_SET);
         }
     }
 }
 
-pub(super) fn path_type(p: &mut Parser<'_>) {
+fn path_type(p: &mut Parser<'_>, allow_type_args: bool) {
     let m = p.start();
     paths::type_path(p);
+    if allow_type_args && p.at(T![<]) {
+        type_arg_list(p);
+    }
     m.complete(p, PATH_TYPE);
 }
 
+/// Parses the type arguments of a path type, e.g. `<i32, bool>` in
+/// `Result<i32, bool>`.
+fn type_arg_list(p: &mut Parser<'_>) {
+    assert!(p.at(T![<]));
+    let m = p.start();
+    p.bump(T![<]);
+    delimited(
+        p,
+        T![>],
+        T![,],
+        "expected type",
+        TYPE_FIRST,
+        TYPE_ARG_RECOVERY_SET,
+        type_,
+    );
+    m.complete(p, TYPE_ARG_LIST);
+}
+
 fn never_type(p: &mut Parser<'_>) {
     assert!(p.at(T![never]));
     let m = p.start();
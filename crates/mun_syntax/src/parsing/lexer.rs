@@ -12,7 +12,7 @@ use self::{
     strings::scan_string,
 };
 use crate::{
-    SyntaxKind::{self, ERROR, IDENT, NEQ, STRING, UNDERSCORE, WHITESPACE},
+    SyntaxKind::{self, DOT, ERROR, IDENT, NEQ, STRING, UNDERSCORE, WHITESPACE},
     TextSize,
 };
 
@@ -31,7 +31,18 @@ pub fn tokenize(text: &str) -> Vec<Token> {
     let mut text = text;
     let mut result = Vec::new();
     while !text.is_empty() {
-        let token = next_token(text);
+        // The dot following another dot is never the start of a field index,
+        // e.g. `0..10` is a range and not `0.` followed by `.10`.
+        let token = if result.last().map(|token: &Token| token.kind) == Some(DOT)
+            && text.starts_with('.')
+        {
+            Token {
+                kind: DOT,
+                len: TextSize::from(1),
+            }
+        } else {
+            next_token(text)
+        };
         result.push(token);
         let len: u32 = token.len.into();
         text = &text[len as usize..];
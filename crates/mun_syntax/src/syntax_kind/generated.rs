@@ -47,6 +47,7 @@ pub enum SyntaxKind {
     COLON,
     COMMA,
     EXCLAMATION,
+    QUESTION,
     UNDERSCORE,
     EQEQ,
     NEQ,
@@ -98,6 +99,7 @@ pub enum SyntaxKind {
     SELF_KW,
     EXTERN_KW,
     IMPL_KW,
+    YIELD_KW,
     INT_NUMBER,
     FLOAT_NUMBER,
     STRING,
@@ -108,6 +110,7 @@ pub enum SyntaxKind {
     COMMENT,
     GC_KW,
     VALUE_KW,
+    RESUMABLE_KW,
     SOURCE_FILE,
     FUNCTION_DEF,
     EXTERN,
@@ -126,12 +129,15 @@ pub enum SyntaxKind {
     PATH_TYPE,
     ARRAY_TYPE,
     NEVER_TYPE,
+    TYPE_ARG_LIST,
     LET_STMT,
     EXPR_STMT,
     PATH_EXPR,
     PREFIX_EXPR,
     LITERAL,
     BIN_EXPR,
+    CAST_EXPR,
+    TRY_EXPR,
     PAREN_EXPR,
     CALL_EXPR,
     FIELD_EXPR,
@@ -139,10 +145,13 @@ pub enum SyntaxKind {
     INDEX_EXPR,
     BLOCK_EXPR,
     RETURN_EXPR,
+    YIELD_EXPR,
     WHILE_EXPR,
     LOOP_EXPR,
+    FOR_EXPR,
     BREAK_EXPR,
     ARRAY_EXPR,
+    RANGE_EXPR,
     CONDITION,
     BIND_PAT,
     PLACEHOLDER_PAT,
@@ -161,6 +170,8 @@ pub enum SyntaxKind {
     IMPL,
     ASSOCIATED_ITEM_LIST,
     ASSOCIATED_ITEM,
+    ATTR,
+    TOKEN_TREE,
     // Technical kind so that we can cast from u16 safely
     #[doc(hidden)]
     __LAST,
@@ -238,6 +249,9 @@ macro_rules! T {
     (!) => {
         $crate::SyntaxKind::EXCLAMATION
     };
+    (?) => {
+        $crate::SyntaxKind::QUESTION
+    };
     (_) => {
         $crate::SyntaxKind::UNDERSCORE
     };
@@ -391,6 +405,9 @@ macro_rules! T {
     (impl) => {
         $crate::SyntaxKind::IMPL_KW
     };
+    (yield) => {
+        $crate::SyntaxKind::YIELD_KW
+    };
 }
 
 impl From<u16> for SyntaxKind {
@@ -437,6 +454,7 @@ impl SyntaxKind {
         | SELF_KW
         | EXTERN_KW
         | IMPL_KW
+        | YIELD_KW
         )
     }
 
@@ -466,6 +484,7 @@ impl SyntaxKind {
         | COLON
         | COMMA
         | EXCLAMATION
+        | QUESTION
         | UNDERSCORE
         | EQEQ
         | NEQ
@@ -528,6 +547,7 @@ impl SyntaxKind {
             COLON => &SyntaxInfo { name: "COLON" },
             COMMA => &SyntaxInfo { name: "COMMA" },
             EXCLAMATION => &SyntaxInfo { name: "EXCLAMATION" },
+            QUESTION => &SyntaxInfo { name: "QUESTION" },
             UNDERSCORE => &SyntaxInfo { name: "UNDERSCORE" },
             EQEQ => &SyntaxInfo { name: "EQEQ" },
             NEQ => &SyntaxInfo { name: "NEQ" },
@@ -579,6 +599,7 @@ impl SyntaxKind {
             SELF_KW => &SyntaxInfo { name: "SELF_KW" },
             EXTERN_KW => &SyntaxInfo { name: "EXTERN_KW" },
             IMPL_KW => &SyntaxInfo { name: "IMPL_KW" },
+            YIELD_KW => &SyntaxInfo { name: "YIELD_KW" },
             INT_NUMBER => &SyntaxInfo { name: "INT_NUMBER" },
             FLOAT_NUMBER => &SyntaxInfo { name: "FLOAT_NUMBER" },
             STRING => &SyntaxInfo { name: "STRING" },
@@ -589,6 +610,7 @@ impl SyntaxKind {
             COMMENT => &SyntaxInfo { name: "COMMENT" },
             GC_KW => &SyntaxInfo { name: "GC_KW" },
             VALUE_KW => &SyntaxInfo { name: "VALUE_KW" },
+            RESUMABLE_KW => &SyntaxInfo { name: "RESUMABLE_KW" },
             SOURCE_FILE => &SyntaxInfo { name: "SOURCE_FILE" },
             FUNCTION_DEF => &SyntaxInfo { name: "FUNCTION_DEF" },
             EXTERN => &SyntaxInfo { name: "EXTERN" },
@@ -607,12 +629,15 @@ impl SyntaxKind {
             PATH_TYPE => &SyntaxInfo { name: "PATH_TYPE" },
             ARRAY_TYPE => &SyntaxInfo { name: "ARRAY_TYPE" },
             NEVER_TYPE => &SyntaxInfo { name: "NEVER_TYPE" },
+            TYPE_ARG_LIST => &SyntaxInfo { name: "TYPE_ARG_LIST" },
             LET_STMT => &SyntaxInfo { name: "LET_STMT" },
             EXPR_STMT => &SyntaxInfo { name: "EXPR_STMT" },
             PATH_EXPR => &SyntaxInfo { name: "PATH_EXPR" },
             PREFIX_EXPR => &SyntaxInfo { name: "PREFIX_EXPR" },
             LITERAL => &SyntaxInfo { name: "LITERAL" },
             BIN_EXPR => &SyntaxInfo { name: "BIN_EXPR" },
+            CAST_EXPR => &SyntaxInfo { name: "CAST_EXPR" },
+            TRY_EXPR => &SyntaxInfo { name: "TRY_EXPR" },
             PAREN_EXPR => &SyntaxInfo { name: "PAREN_EXPR" },
             CALL_EXPR => &SyntaxInfo { name: "CALL_EXPR" },
             FIELD_EXPR => &SyntaxInfo { name: "FIELD_EXPR" },
@@ -620,10 +645,13 @@ impl SyntaxKind {
             INDEX_EXPR => &SyntaxInfo { name: "INDEX_EXPR" },
             BLOCK_EXPR => &SyntaxInfo { name: "BLOCK_EXPR" },
             RETURN_EXPR => &SyntaxInfo { name: "RETURN_EXPR" },
+            YIELD_EXPR => &SyntaxInfo { name: "YIELD_EXPR" },
             WHILE_EXPR => &SyntaxInfo { name: "WHILE_EXPR" },
             LOOP_EXPR => &SyntaxInfo { name: "LOOP_EXPR" },
+            FOR_EXPR => &SyntaxInfo { name: "FOR_EXPR" },
             BREAK_EXPR => &SyntaxInfo { name: "BREAK_EXPR" },
             ARRAY_EXPR => &SyntaxInfo { name: "ARRAY_EXPR" },
+            RANGE_EXPR => &SyntaxInfo { name: "RANGE_EXPR" },
             CONDITION => &SyntaxInfo { name: "CONDITION" },
             BIND_PAT => &SyntaxInfo { name: "BIND_PAT" },
             PLACEHOLDER_PAT => &SyntaxInfo { name: "PLACEHOLDER_PAT" },
@@ -642,6 +670,8 @@ impl SyntaxKind {
             IMPL => &SyntaxInfo { name: "IMPL" },
             ASSOCIATED_ITEM_LIST => &SyntaxInfo { name: "ASSOCIATED_ITEM_LIST" },
             ASSOCIATED_ITEM => &SyntaxInfo { name: "ASSOCIATED_ITEM" },
+            ATTR => &SyntaxInfo { name: "ATTR" },
+            TOKEN_TREE => &SyntaxInfo { name: "TOKEN_TREE" },
             TOMBSTONE => &SyntaxInfo { name: "TOMBSTONE" },
             EOF => &SyntaxInfo { name: "EOF" },
             __LAST => &SyntaxInfo { name: "__LAST" },
@@ -677,6 +707,7 @@ impl SyntaxKind {
             "self" => SELF_KW,
             "extern" => EXTERN_KW,
             "impl" => IMPL_KW,
+            "yield" => YIELD_KW,
             _ => return None,
         };
         Some(kw)
@@ -707,6 +738,7 @@ impl SyntaxKind {
             ':' => COLON,
             ',' => COMMA,
             '!' => EXCLAMATION,
+            '?' => QUESTION,
             '_' => UNDERSCORE,
             _ => return None,
         };
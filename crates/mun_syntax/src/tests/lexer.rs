@@ -403,3 +403,31 @@ fn binary_cmp() {
     WHITESPACE 5 "\n    "
     "#);
 }
+
+#[test]
+fn ranges() {
+    insta::assert_snapshot!(dump_text_tokens(
+        r#"
+    0..10
+    1..=2
+    a.0..b.1"#), @r#"
+    WHITESPACE 5 "\n    "
+    INT_NUMBER 1 "0"
+    DOT 1 "."
+    DOT 1 "."
+    INT_NUMBER 2 "10"
+    WHITESPACE 5 "\n    "
+    INT_NUMBER 1 "1"
+    DOT 1 "."
+    DOT 1 "."
+    EQ 1 "="
+    INT_NUMBER 1 "2"
+    WHITESPACE 5 "\n    "
+    IDENT 1 "a"
+    INDEX 2 ".0"
+    DOT 1 "."
+    DOT 1 "."
+    IDENT 1 "b"
+    INDEX 2 ".1"
+    "#);
+}
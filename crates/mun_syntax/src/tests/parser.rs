@@ -993,6 +993,100 @@ fn struct_def() {
     "#);
 }
 
+#[test]
+fn struct_field_defaults() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+    struct Foo {
+        a: f64 = 1.0,
+        b: i32 = -1,
+        c: bool,
+        d: u8 =, // error: expected expression
+    }
+    "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..135
+      WHITESPACE@0..5 "\n    "
+      STRUCT_DEF@5..130
+        STRUCT_KW@5..11 "struct"
+        WHITESPACE@11..12 " "
+        NAME@12..15
+          IDENT@12..15 "Foo"
+        WHITESPACE@15..16 " "
+        RECORD_FIELD_DEF_LIST@16..130
+          L_CURLY@16..17 "{"
+          WHITESPACE@17..26 "\n        "
+          RECORD_FIELD_DEF@26..38
+            NAME@26..27
+              IDENT@26..27 "a"
+            COLON@27..28 ":"
+            WHITESPACE@28..29 " "
+            PATH_TYPE@29..32
+              PATH@29..32
+                PATH_SEGMENT@29..32
+                  NAME_REF@29..32
+                    IDENT@29..32 "f64"
+            WHITESPACE@32..33 " "
+            EQ@33..34 "="
+            WHITESPACE@34..35 " "
+            LITERAL@35..38
+              FLOAT_NUMBER@35..38 "1.0"
+          COMMA@38..39 ","
+          WHITESPACE@39..48 "\n        "
+          RECORD_FIELD_DEF@48..59
+            NAME@48..49
+              IDENT@48..49 "b"
+            COLON@49..50 ":"
+            WHITESPACE@50..51 " "
+            PATH_TYPE@51..54
+              PATH@51..54
+                PATH_SEGMENT@51..54
+                  NAME_REF@51..54
+                    IDENT@51..54 "i32"
+            WHITESPACE@54..55 " "
+            EQ@55..56 "="
+            WHITESPACE@56..57 " "
+            PREFIX_EXPR@57..59
+              MINUS@57..58 "-"
+              LITERAL@58..59
+                INT_NUMBER@58..59 "1"
+          COMMA@59..60 ","
+          WHITESPACE@60..69 "\n        "
+          RECORD_FIELD_DEF@69..76
+            NAME@69..70
+              IDENT@69..70 "c"
+            COLON@70..71 ":"
+            WHITESPACE@71..72 " "
+            PATH_TYPE@72..76
+              PATH@72..76
+                PATH_SEGMENT@72..76
+                  NAME_REF@72..76
+                    IDENT@72..76 "bool"
+          COMMA@76..77 ","
+          WHITESPACE@77..86 "\n        "
+          RECORD_FIELD_DEF@86..94
+            NAME@86..87
+              IDENT@86..87 "d"
+            COLON@87..88 ":"
+            WHITESPACE@88..89 " "
+            PATH_TYPE@89..91
+              PATH@89..91
+                PATH_SEGMENT@89..91
+                  NAME_REF@89..91
+                    IDENT@89..91 "u8"
+            WHITESPACE@91..92 " "
+            EQ@92..93 "="
+            ERROR@93..94
+              COMMA@93..94 ","
+          WHITESPACE@94..95 " "
+          COMMENT@95..124 "// error: expected ex ..."
+          WHITESPACE@124..129 "\n    "
+          R_CURLY@129..130 "}"
+      WHITESPACE@130..135 "\n    "
+    error Offset(93): expected expression
+    "#);
+}
+
 #[test]
 fn unary_expr() {
     insta::assert_snapshot!(SourceFile::parse(
@@ -1056,6 +1150,315 @@ fn unary_expr() {
     "#);
 }
 
+#[test]
+fn cast_expr() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+    fn foo() {
+        let a = -b as u8 * 2
+        let c = d + e as f64 as i32
+    }
+    "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..91
+      FUNCTION_DEF@0..86
+        WHITESPACE@0..5 "\n    "
+        FN_KW@5..7 "fn"
+        WHITESPACE@7..8 " "
+        NAME@8..11
+          IDENT@8..11 "foo"
+        PARAM_LIST@11..13
+          L_PAREN@11..12 "("
+          R_PAREN@12..13 ")"
+        WHITESPACE@13..14 " "
+        BLOCK_EXPR@14..86
+          L_CURLY@14..15 "{"
+          WHITESPACE@15..24 "\n        "
+          LET_STMT@24..44
+            LET_KW@24..27 "let"
+            WHITESPACE@27..28 " "
+            BIND_PAT@28..29
+              NAME@28..29
+                IDENT@28..29 "a"
+            WHITESPACE@29..30 " "
+            EQ@30..31 "="
+            WHITESPACE@31..32 " "
+            BIN_EXPR@32..44
+              CAST_EXPR@32..40
+                PREFIX_EXPR@32..34
+                  MINUS@32..33 "-"
+                  PATH_EXPR@33..34
+                    PATH@33..34
+                      PATH_SEGMENT@33..34
+                        NAME_REF@33..34
+                          IDENT@33..34 "b"
+                WHITESPACE@34..35 " "
+                AS_KW@35..37 "as"
+                WHITESPACE@37..38 " "
+                PATH_TYPE@38..40
+                  PATH@38..40
+                    PATH_SEGMENT@38..40
+                      NAME_REF@38..40
+                        IDENT@38..40 "u8"
+              WHITESPACE@40..41 " "
+              STAR@41..42 "*"
+              WHITESPACE@42..43 " "
+              LITERAL@43..44
+                INT_NUMBER@43..44 "2"
+          WHITESPACE@44..53 "\n        "
+          LET_STMT@53..80
+            LET_KW@53..56 "let"
+            WHITESPACE@56..57 " "
+            BIND_PAT@57..58
+              NAME@57..58
+                IDENT@57..58 "c"
+            WHITESPACE@58..59 " "
+            EQ@59..60 "="
+            WHITESPACE@60..61 " "
+            BIN_EXPR@61..80
+              PATH_EXPR@61..62
+                PATH@61..62
+                  PATH_SEGMENT@61..62
+                    NAME_REF@61..62
+                      IDENT@61..62 "d"
+              WHITESPACE@62..63 " "
+              PLUS@63..64 "+"
+              WHITESPACE@64..65 " "
+              CAST_EXPR@65..80
+                CAST_EXPR@65..73
+                  PATH_EXPR@65..66
+                    PATH@65..66
+                      PATH_SEGMENT@65..66
+                        NAME_REF@65..66
+                          IDENT@65..66 "e"
+                  WHITESPACE@66..67 " "
+                  AS_KW@67..69 "as"
+                  WHITESPACE@69..70 " "
+                  PATH_TYPE@70..73
+                    PATH@70..73
+                      PATH_SEGMENT@70..73
+                        NAME_REF@70..73
+                          IDENT@70..73 "f64"
+                WHITESPACE@73..74 " "
+                AS_KW@74..76 "as"
+                WHITESPACE@76..77 " "
+                PATH_TYPE@77..80
+                  PATH@77..80
+                    PATH_SEGMENT@77..80
+                      NAME_REF@77..80
+                        IDENT@77..80 "i32"
+          WHITESPACE@80..85 "\n    "
+          R_CURLY@85..86 "}"
+      WHITESPACE@86..91 "\n    "
+    "#);
+}
+
+#[test]
+fn try_expr() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+    fn foo() -> Result<i32, bool> {
+        let a = bar()?
+        Ok(a.b? + 1)
+    }
+    "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..91
+      FUNCTION_DEF@0..86
+        WHITESPACE@0..5 "\n    "
+        FN_KW@5..7 "fn"
+        WHITESPACE@7..8 " "
+        NAME@8..11
+          IDENT@8..11 "foo"
+        PARAM_LIST@11..13
+          L_PAREN@11..12 "("
+          R_PAREN@12..13 ")"
+        WHITESPACE@13..14 " "
+        RET_TYPE@14..34
+          THIN_ARROW@14..16 "->"
+          WHITESPACE@16..17 " "
+          PATH_TYPE@17..34
+            PATH@17..23
+              PATH_SEGMENT@17..23
+                NAME_REF@17..23
+                  IDENT@17..23 "Result"
+            TYPE_ARG_LIST@23..34
+              LT@23..24 "<"
+              PATH_TYPE@24..27
+                PATH@24..27
+                  PATH_SEGMENT@24..27
+                    NAME_REF@24..27
+                      IDENT@24..27 "i32"
+              COMMA@27..28 ","
+              WHITESPACE@28..29 " "
+              PATH_TYPE@29..33
+                PATH@29..33
+                  PATH_SEGMENT@29..33
+                    NAME_REF@29..33
+                      IDENT@29..33 "bool"
+              GT@33..34 ">"
+        WHITESPACE@34..35 " "
+        BLOCK_EXPR@35..86
+          L_CURLY@35..36 "{"
+          WHITESPACE@36..45 "\n        "
+          LET_STMT@45..59
+            LET_KW@45..48 "let"
+            WHITESPACE@48..49 " "
+            BIND_PAT@49..50
+              NAME@49..50
+                IDENT@49..50 "a"
+            WHITESPACE@50..51 " "
+            EQ@51..52 "="
+            WHITESPACE@52..53 " "
+            TRY_EXPR@53..59
+              CALL_EXPR@53..58
+                PATH_EXPR@53..56
+                  PATH@53..56
+                    PATH_SEGMENT@53..56
+                      NAME_REF@53..56
+                        IDENT@53..56 "bar"
+                ARG_LIST@56..58
+                  L_PAREN@56..57 "("
+                  R_PAREN@57..58 ")"
+              QUESTION@58..59 "?"
+          WHITESPACE@59..68 "\n        "
+          CALL_EXPR@68..80
+            PATH_EXPR@68..70
+              PATH@68..70
+                PATH_SEGMENT@68..70
+                  NAME_REF@68..70
+                    IDENT@68..70 "Ok"
+            ARG_LIST@70..80
+              L_PAREN@70..71 "("
+              BIN_EXPR@71..79
+                TRY_EXPR@71..75
+                  FIELD_EXPR@71..74
+                    PATH_EXPR@71..72
+                      PATH@71..72
+                        PATH_SEGMENT@71..72
+                          NAME_REF@71..72
+                            IDENT@71..72 "a"
+                    DOT@72..73 "."
+                    NAME_REF@73..74
+                      IDENT@73..74 "b"
+                  QUESTION@74..75 "?"
+                WHITESPACE@75..76 " "
+                PLUS@76..77 "+"
+                WHITESPACE@77..78 " "
+                LITERAL@78..79
+                  INT_NUMBER@78..79 "1"
+              R_PAREN@79..80 ")"
+          WHITESPACE@80..85 "\n    "
+          R_CURLY@85..86 "}"
+      WHITESPACE@86..91 "\n    "
+    "#);
+}
+
+#[test]
+fn type_args() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+    fn foo(a: Result<[u8], Result<i32, bool>>) -> bool {
+        a as i32 < b
+    }
+    "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..89
+      FUNCTION_DEF@0..84
+        WHITESPACE@0..5 "\n    "
+        FN_KW@5..7 "fn"
+        WHITESPACE@7..8 " "
+        NAME@8..11
+          IDENT@8..11 "foo"
+        PARAM_LIST@11..47
+          L_PAREN@11..12 "("
+          PARAM@12..46
+            BIND_PAT@12..13
+              NAME@12..13
+                IDENT@12..13 "a"
+            COLON@13..14 ":"
+            WHITESPACE@14..15 " "
+            PATH_TYPE@15..46
+              PATH@15..21
+                PATH_SEGMENT@15..21
+                  NAME_REF@15..21
+                    IDENT@15..21 "Result"
+              TYPE_ARG_LIST@21..46
+                LT@21..22 "<"
+                ARRAY_TYPE@22..26
+                  L_BRACKET@22..23 "["
+                  PATH_TYPE@23..25
+                    PATH@23..25
+                      PATH_SEGMENT@23..25
+                        NAME_REF@23..25
+                          IDENT@23..25 "u8"
+                  R_BRACKET@25..26 "]"
+                COMMA@26..27 ","
+                WHITESPACE@27..28 " "
+                PATH_TYPE@28..45
+                  PATH@28..34
+                    PATH_SEGMENT@28..34
+                      NAME_REF@28..34
+                        IDENT@28..34 "Result"
+                  TYPE_ARG_LIST@34..45
+                    LT@34..35 "<"
+                    PATH_TYPE@35..38
+                      PATH@35..38
+                        PATH_SEGMENT@35..38
+                          NAME_REF@35..38
+                            IDENT@35..38 "i32"
+                    COMMA@38..39 ","
+                    WHITESPACE@39..40 " "
+                    PATH_TYPE@40..44
+                      PATH@40..44
+                        PATH_SEGMENT@40..44
+                          NAME_REF@40..44
+                            IDENT@40..44 "bool"
+                    GT@44..45 ">"
+                GT@45..46 ">"
+          R_PAREN@46..47 ")"
+        WHITESPACE@47..48 " "
+        RET_TYPE@48..55
+          THIN_ARROW@48..50 "->"
+          WHITESPACE@50..51 " "
+          PATH_TYPE@51..55
+            PATH@51..55
+              PATH_SEGMENT@51..55
+                NAME_REF@51..55
+                  IDENT@51..55 "bool"
+        WHITESPACE@55..56 " "
+        BLOCK_EXPR@56..84
+          L_CURLY@56..57 "{"
+          WHITESPACE@57..66 "\n        "
+          BIN_EXPR@66..78
+            CAST_EXPR@66..74
+              PATH_EXPR@66..67
+                PATH@66..67
+                  PATH_SEGMENT@66..67
+                    NAME_REF@66..67
+                      IDENT@66..67 "a"
+              WHITESPACE@67..68 " "
+              AS_KW@68..70 "as"
+              WHITESPACE@70..71 " "
+              PATH_TYPE@71..74
+                PATH@71..74
+                  PATH_SEGMENT@71..74
+                    NAME_REF@71..74
+                      IDENT@71..74 "i32"
+            WHITESPACE@74..75 " "
+            LT@75..76 "<"
+            WHITESPACE@76..77 " "
+            PATH_EXPR@77..78
+              PATH@77..78
+                PATH_SEGMENT@77..78
+                  NAME_REF@77..78
+                    IDENT@77..78 "b"
+          WHITESPACE@78..83 "\n    "
+          R_CURLY@83..84 "}"
+      WHITESPACE@84..89 "\n    "
+    "#);
+}
+
 #[test]
 fn binary_expr() {
     insta::assert_snapshot!(SourceFile::parse(
@@ -2440,6 +2843,77 @@ fn return_expr() {
     "#);
 }
 
+#[test]
+fn resumable_fn() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+    pub resumable fn wait(seconds: f32) {
+        let elapsed = yield;
+    }
+    fn resumable() {}
+    "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..104
+      FUNCTION_DEF@0..77
+        WHITESPACE@0..5 "\n    "
+        VISIBILITY@5..8
+          PUB_KW@5..8 "pub"
+        WHITESPACE@8..9 " "
+        RESUMABLE_KW@9..18 "resumable"
+        WHITESPACE@18..19 " "
+        FN_KW@19..21 "fn"
+        WHITESPACE@21..22 " "
+        NAME@22..26
+          IDENT@22..26 "wait"
+        PARAM_LIST@26..40
+          L_PAREN@26..27 "("
+          PARAM@27..39
+            BIND_PAT@27..34
+              NAME@27..34
+                IDENT@27..34 "seconds"
+            COLON@34..35 ":"
+            WHITESPACE@35..36 " "
+            PATH_TYPE@36..39
+              PATH@36..39
+                PATH_SEGMENT@36..39
+                  NAME_REF@36..39
+                    IDENT@36..39 "f32"
+          R_PAREN@39..40 ")"
+        WHITESPACE@40..41 " "
+        BLOCK_EXPR@41..77
+          L_CURLY@41..42 "{"
+          WHITESPACE@42..51 "\n        "
+          LET_STMT@51..71
+            LET_KW@51..54 "let"
+            WHITESPACE@54..55 " "
+            BIND_PAT@55..62
+              NAME@55..62
+                IDENT@55..62 "elapsed"
+            WHITESPACE@62..63 " "
+            EQ@63..64 "="
+            WHITESPACE@64..65 " "
+            YIELD_EXPR@65..70
+              YIELD_KW@65..70 "yield"
+            SEMI@70..71 ";"
+          WHITESPACE@71..76 "\n    "
+          R_CURLY@76..77 "}"
+      FUNCTION_DEF@77..99
+        WHITESPACE@77..82 "\n    "
+        FN_KW@82..84 "fn"
+        WHITESPACE@84..85 " "
+        NAME@85..94
+          IDENT@85..94 "resumable"
+        PARAM_LIST@94..96
+          L_PAREN@94..95 "("
+          R_PAREN@95..96 ")"
+        WHITESPACE@96..97 " "
+        BLOCK_EXPR@97..99
+          L_CURLY@97..98 "{"
+          R_CURLY@98..99 "}"
+      WHITESPACE@99..104 "\n    "
+    "#);
+}
+
 #[test]
 fn loop_expr() {
     insta::assert_snapshot!(SourceFile::parse(
@@ -2546,6 +3020,219 @@ fn break_expr() {
     "#);
 }
 
+#[test]
+fn for_expr() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+    fn foo() {
+        for i in 0..10 {}
+        for _ in a..=b + 1 { break; }
+        for value in values {};
+    }
+    "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..122
+      FUNCTION_DEF@0..117
+        WHITESPACE@0..5 "\n    "
+        FN_KW@5..7 "fn"
+        WHITESPACE@7..8 " "
+        NAME@8..11
+          IDENT@8..11 "foo"
+        PARAM_LIST@11..13
+          L_PAREN@11..12 "("
+          R_PAREN@12..13 ")"
+        WHITESPACE@13..14 " "
+        BLOCK_EXPR@14..117
+          L_CURLY@14..15 "{"
+          WHITESPACE@15..24 "\n        "
+          EXPR_STMT@24..41
+            FOR_EXPR@24..41
+              FOR_KW@24..27 "for"
+              WHITESPACE@27..28 " "
+              BIND_PAT@28..29
+                NAME@28..29
+                  IDENT@28..29 "i"
+              WHITESPACE@29..30 " "
+              IN_KW@30..32 "in"
+              WHITESPACE@32..33 " "
+              RANGE_EXPR@33..38
+                LITERAL@33..34
+                  INT_NUMBER@33..34 "0"
+                DOTDOT@34..36 ".."
+                LITERAL@36..38
+                  INT_NUMBER@36..38 "10"
+              WHITESPACE@38..39 " "
+              BLOCK_EXPR@39..41
+                L_CURLY@39..40 "{"
+                R_CURLY@40..41 "}"
+          WHITESPACE@41..50 "\n        "
+          EXPR_STMT@50..79
+            FOR_EXPR@50..79
+              FOR_KW@50..53 "for"
+              WHITESPACE@53..54 " "
+              PLACEHOLDER_PAT@54..55
+                UNDERSCORE@54..55 "_"
+              WHITESPACE@55..56 " "
+              IN_KW@56..58 "in"
+              WHITESPACE@58..59 " "
+              RANGE_EXPR@59..68
+                PATH_EXPR@59..60
+                  PATH@59..60
+                    PATH_SEGMENT@59..60
+                      NAME_REF@59..60
+                        IDENT@59..60 "a"
+                DOTDOTEQ@60..63 "..="
+                BIN_EXPR@63..68
+                  PATH_EXPR@63..64
+                    PATH@63..64
+                      PATH_SEGMENT@63..64
+                        NAME_REF@63..64
+                          IDENT@63..64 "b"
+                  WHITESPACE@64..65 " "
+                  PLUS@65..66 "+"
+                  WHITESPACE@66..67 " "
+                  LITERAL@67..68
+                    INT_NUMBER@67..68 "1"
+              WHITESPACE@68..69 " "
+              BLOCK_EXPR@69..79
+                L_CURLY@69..70 "{"
+                WHITESPACE@70..71 " "
+                EXPR_STMT@71..77
+                  BREAK_EXPR@71..76
+                    BREAK_KW@71..76 "break"
+                  SEMI@76..77 ";"
+                WHITESPACE@77..78 " "
+                R_CURLY@78..79 "}"
+          WHITESPACE@79..88 "\n        "
+          EXPR_STMT@88..111
+            FOR_EXPR@88..110
+              FOR_KW@88..91 "for"
+              WHITESPACE@91..92 " "
+              BIND_PAT@92..97
+                NAME@92..97
+                  IDENT@92..97 "value"
+              WHITESPACE@97..98 " "
+              IN_KW@98..100 "in"
+              WHITESPACE@100..101 " "
+              PATH_EXPR@101..107
+                PATH@101..107
+                  PATH_SEGMENT@101..107
+                    NAME_REF@101..107
+                      IDENT@101..107 "values"
+              WHITESPACE@107..108 " "
+              BLOCK_EXPR@108..110
+                L_CURLY@108..109 "{"
+                R_CURLY@109..110 "}"
+            SEMI@110..111 ";"
+          WHITESPACE@111..116 "\n    "
+          R_CURLY@116..117 "}"
+      WHITESPACE@117..122 "\n    "
+    "#);
+}
+
+#[test]
+fn range_expr() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+    fn foo() {
+        let a = 0..10;
+        let b = x.0..y.1;
+        let c = 1 + 2..=3 * 4;
+    }
+    "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..106
+      FUNCTION_DEF@0..101
+        WHITESPACE@0..5 "\n    "
+        FN_KW@5..7 "fn"
+        WHITESPACE@7..8 " "
+        NAME@8..11
+          IDENT@8..11 "foo"
+        PARAM_LIST@11..13
+          L_PAREN@11..12 "("
+          R_PAREN@12..13 ")"
+        WHITESPACE@13..14 " "
+        BLOCK_EXPR@14..101
+          L_CURLY@14..15 "{"
+          WHITESPACE@15..24 "\n        "
+          LET_STMT@24..38
+            LET_KW@24..27 "let"
+            WHITESPACE@27..28 " "
+            BIND_PAT@28..29
+              NAME@28..29
+                IDENT@28..29 "a"
+            WHITESPACE@29..30 " "
+            EQ@30..31 "="
+            WHITESPACE@31..32 " "
+            RANGE_EXPR@32..37
+              LITERAL@32..33
+                INT_NUMBER@32..33 "0"
+              DOTDOT@33..35 ".."
+              LITERAL@35..37
+                INT_NUMBER@35..37 "10"
+            SEMI@37..38 ";"
+          WHITESPACE@38..47 "\n        "
+          LET_STMT@47..64
+            LET_KW@47..50 "let"
+            WHITESPACE@50..51 " "
+            BIND_PAT@51..52
+              NAME@51..52
+                IDENT@51..52 "b"
+            WHITESPACE@52..53 " "
+            EQ@53..54 "="
+            WHITESPACE@54..55 " "
+            RANGE_EXPR@55..63
+              FIELD_EXPR@55..58
+                PATH_EXPR@55..56
+                  PATH@55..56
+                    PATH_SEGMENT@55..56
+                      NAME_REF@55..56
+                        IDENT@55..56 "x"
+                INDEX@56..58 ".0"
+              DOTDOT@58..60 ".."
+              FIELD_EXPR@60..63
+                PATH_EXPR@60..61
+                  PATH@60..61
+                    PATH_SEGMENT@60..61
+                      NAME_REF@60..61
+                        IDENT@60..61 "y"
+                INDEX@61..63 ".1"
+            SEMI@63..64 ";"
+          WHITESPACE@64..73 "\n        "
+          LET_STMT@73..95
+            LET_KW@73..76 "let"
+            WHITESPACE@76..77 " "
+            BIND_PAT@77..78
+              NAME@77..78
+                IDENT@77..78 "c"
+            WHITESPACE@78..79 " "
+            EQ@79..80 "="
+            WHITESPACE@80..81 " "
+            RANGE_EXPR@81..94
+              BIN_EXPR@81..86
+                LITERAL@81..82
+                  INT_NUMBER@81..82 "1"
+                WHITESPACE@82..83 " "
+                PLUS@83..84 "+"
+                WHITESPACE@84..85 " "
+                LITERAL@85..86
+                  INT_NUMBER@85..86 "2"
+              DOTDOTEQ@86..89 "..="
+              BIN_EXPR@89..94
+                LITERAL@89..90
+                  INT_NUMBER@89..90 "3"
+                WHITESPACE@90..91 " "
+                STAR@91..92 "*"
+                WHITESPACE@92..93 " "
+                LITERAL@93..94
+                  INT_NUMBER@93..94 "4"
+            SEMI@94..95 ";"
+          WHITESPACE@95..100 "\n    "
+          R_CURLY@100..101 "}"
+      WHITESPACE@101..106 "\n    "
+    "#);
+}
+
 #[test]
 fn while_expr() {
     insta::assert_snapshot!(SourceFile::parse(
@@ -3102,6 +3789,115 @@ fn visibility() {
     "#);
 }
 
+#[test]
+fn field_visibility() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+    struct Foo { pub a: i32, pub(package) b: i32, c: i32 }
+    struct Bar(pub i32, pub(super) f64, bool);
+    "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..111
+      WHITESPACE@0..5 "\n    "
+      STRUCT_DEF@5..59
+        STRUCT_KW@5..11 "struct"
+        WHITESPACE@11..12 " "
+        NAME@12..15
+          IDENT@12..15 "Foo"
+        WHITESPACE@15..16 " "
+        RECORD_FIELD_DEF_LIST@16..59
+          L_CURLY@16..17 "{"
+          WHITESPACE@17..18 " "
+          RECORD_FIELD_DEF@18..28
+            VISIBILITY@18..21
+              PUB_KW@18..21 "pub"
+            WHITESPACE@21..22 " "
+            NAME@22..23
+              IDENT@22..23 "a"
+            COLON@23..24 ":"
+            WHITESPACE@24..25 " "
+            PATH_TYPE@25..28
+              PATH@25..28
+                PATH_SEGMENT@25..28
+                  NAME_REF@25..28
+                    IDENT@25..28 "i32"
+          COMMA@28..29 ","
+          WHITESPACE@29..30 " "
+          RECORD_FIELD_DEF@30..49
+            VISIBILITY@30..42
+              PUB_KW@30..33 "pub"
+              L_PAREN@33..34 "("
+              PACKAGE_KW@34..41 "package"
+              R_PAREN@41..42 ")"
+            WHITESPACE@42..43 " "
+            NAME@43..44
+              IDENT@43..44 "b"
+            COLON@44..45 ":"
+            WHITESPACE@45..46 " "
+            PATH_TYPE@46..49
+              PATH@46..49
+                PATH_SEGMENT@46..49
+                  NAME_REF@46..49
+                    IDENT@46..49 "i32"
+          COMMA@49..50 ","
+          WHITESPACE@50..51 " "
+          RECORD_FIELD_DEF@51..57
+            NAME@51..52
+              IDENT@51..52 "c"
+            COLON@52..53 ":"
+            WHITESPACE@53..54 " "
+            PATH_TYPE@54..57
+              PATH@54..57
+                PATH_SEGMENT@54..57
+                  NAME_REF@54..57
+                    IDENT@54..57 "i32"
+          WHITESPACE@57..58 " "
+          R_CURLY@58..59 "}"
+      WHITESPACE@59..64 "\n    "
+      STRUCT_DEF@64..106
+        STRUCT_KW@64..70 "struct"
+        WHITESPACE@70..71 " "
+        NAME@71..74
+          IDENT@71..74 "Bar"
+        TUPLE_FIELD_DEF_LIST@74..106
+          L_PAREN@74..75 "("
+          TUPLE_FIELD_DEF@75..82
+            VISIBILITY@75..78
+              PUB_KW@75..78 "pub"
+            WHITESPACE@78..79 " "
+            PATH_TYPE@79..82
+              PATH@79..82
+                PATH_SEGMENT@79..82
+                  NAME_REF@79..82
+                    IDENT@79..82 "i32"
+          COMMA@82..83 ","
+          WHITESPACE@83..84 " "
+          TUPLE_FIELD_DEF@84..98
+            VISIBILITY@84..94
+              PUB_KW@84..87 "pub"
+              L_PAREN@87..88 "("
+              SUPER_KW@88..93 "super"
+              R_PAREN@93..94 ")"
+            WHITESPACE@94..95 " "
+            PATH_TYPE@95..98
+              PATH@95..98
+                PATH_SEGMENT@95..98
+                  NAME_REF@95..98
+                    IDENT@95..98 "f64"
+          COMMA@98..99 ","
+          WHITESPACE@99..100 " "
+          TUPLE_FIELD_DEF@100..104
+            PATH_TYPE@100..104
+              PATH@100..104
+                PATH_SEGMENT@100..104
+                  NAME_REF@100..104
+                    IDENT@100..104 "bool"
+          R_PAREN@104..105 ")"
+          SEMI@105..106 ";"
+      WHITESPACE@106..111 "\n    "
+    "#);
+}
+
 #[test]
 fn extern_fn() {
     insta::assert_snapshot!(SourceFile::parse(
@@ -3130,6 +3926,31 @@ fn extern_fn() {
     "#);
 }
 
+#[test]
+fn extern_struct() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+    pub extern struct Texture;
+    "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..36
+      WHITESPACE@0..5 "\n    "
+      STRUCT_DEF@5..31
+        VISIBILITY@5..8
+          PUB_KW@5..8 "pub"
+        WHITESPACE@8..9 " "
+        EXTERN@9..15
+          EXTERN_KW@9..15 "extern"
+        WHITESPACE@15..16 " "
+        STRUCT_KW@16..22 "struct"
+        WHITESPACE@22..23 " "
+        NAME@23..30
+          IDENT@23..30 "Texture"
+        SEMI@30..31 ";"
+      WHITESPACE@31..36 "\n    "
+    "#);
+}
+
 #[test]
 fn type_alias_def() {
     insta::assert_snapshot!(SourceFile::parse(
@@ -3479,11 +4300,9 @@ fn use_() {
         WHITESPACE@365..366 " "
         ERROR@366..367
           COLON@366..367 ":"
-      ERROR@367..368
+      ERROR@367..370
         COLON@367..368 ":"
-      ERROR@368..369
         STAR@368..369 "*"
-      ERROR@369..370
         SEMI@369..370 ";"
       WHITESPACE@370..371 " "
       COMMENT@371..379 "// Error"
@@ -3659,7 +4478,479 @@ fn use_() {
     error Offset(366): expected one of `self`, `super`, `package` or an identifier
     error Offset(367): expected SEMI
     error Offset(367): expected a declaration
-    error Offset(368): expected a declaration
-    error Offset(369): expected a declaration
+    "#);
+}
+
+#[test]
+fn use_tree_list_recovery() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+        use some::{ {}, fn foo() {} };
+        use other::Item;
+        "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..73
+      WHITESPACE@0..9 "\n        "
+      USE@9..24
+        USE_KW@9..12 "use"
+        WHITESPACE@12..13 " "
+        USE_TREE@13..24
+          PATH@13..17
+            PATH_SEGMENT@13..17
+              NAME_REF@13..17
+                IDENT@13..17 "some"
+          COLONCOLON@17..19 "::"
+          USE_TREE_LIST@19..24
+            L_CURLY@19..20 "{"
+            WHITESPACE@20..21 " "
+            ERROR@21..23
+              L_CURLY@21..22 "{"
+              R_CURLY@22..23 "}"
+            COMMA@23..24 ","
+      FUNCTION_DEF@24..36
+        WHITESPACE@24..25 " "
+        FN_KW@25..27 "fn"
+        WHITESPACE@27..28 " "
+        NAME@28..31
+          IDENT@28..31 "foo"
+        PARAM_LIST@31..33
+          L_PAREN@31..32 "("
+          R_PAREN@32..33 ")"
+        WHITESPACE@33..34 " "
+        BLOCK_EXPR@34..36
+          L_CURLY@34..35 "{"
+          R_CURLY@35..36 "}"
+      WHITESPACE@36..37 " "
+      ERROR@37..38
+        R_CURLY@37..38 "}"
+      ERROR@38..39
+        SEMI@38..39 ";"
+      WHITESPACE@39..48 "\n        "
+      USE@48..64
+        USE_KW@48..51 "use"
+        WHITESPACE@51..52 " "
+        USE_TREE@52..63
+          PATH@52..63
+            PATH@52..57
+              PATH_SEGMENT@52..57
+                NAME_REF@52..57
+                  IDENT@52..57 "other"
+            COLONCOLON@57..59 "::"
+            PATH_SEGMENT@59..63
+              NAME_REF@59..63
+                IDENT@59..63 "Item"
+        SEMI@63..64 ";"
+      WHITESPACE@64..73 "\n        "
+    error Offset(21): expected a use tree
+    error Offset(24): expected R_CURLY
+    error Offset(24): expected SEMI
+    error Offset(37): unmatched }
+    error Offset(38): expected a declaration
+    "#);
+}
+
+#[test]
+fn attributes() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+        #[allow(dead_code, unused_variables)]
+        fn foo() {}
+        #[test]
+        pub struct Foo;
+        impl Foo {
+            #[allow(unused_parameters)]
+            fn bar(a: i32) {}
+        }
+        #[]
+        fn baz() {}
+        "#).debug_dump(), @r##"
+    SOURCE_FILE@0..246
+      FUNCTION_DEF@0..66
+        WHITESPACE@0..9 "\n        "
+        ATTR@9..46
+          HASH@9..10 "#"
+          L_BRACKET@10..11 "["
+          PATH@11..16
+            PATH_SEGMENT@11..16
+              NAME_REF@11..16
+                IDENT@11..16 "allow"
+          TOKEN_TREE@16..45
+            L_PAREN@16..17 "("
+            IDENT@17..26 "dead_code"
+            COMMA@26..27 ","
+            WHITESPACE@27..28 " "
+            IDENT@28..44 "unused_variables"
+            R_PAREN@44..45 ")"
+          R_BRACKET@45..46 "]"
+        WHITESPACE@46..55 "\n        "
+        FN_KW@55..57 "fn"
+        WHITESPACE@57..58 " "
+        NAME@58..61
+          IDENT@58..61 "foo"
+        PARAM_LIST@61..63
+          L_PAREN@61..62 "("
+          R_PAREN@62..63 ")"
+        WHITESPACE@63..64 " "
+        BLOCK_EXPR@64..66
+          L_CURLY@64..65 "{"
+          R_CURLY@65..66 "}"
+      WHITESPACE@66..75 "\n        "
+      STRUCT_DEF@75..106
+        ATTR@75..82
+          HASH@75..76 "#"
+          L_BRACKET@76..77 "["
+          PATH@77..81
+            PATH_SEGMENT@77..81
+              NAME_REF@77..81
+                IDENT@77..81 "test"
+          R_BRACKET@81..82 "]"
+        WHITESPACE@82..91 "\n        "
+        VISIBILITY@91..94
+          PUB_KW@91..94 "pub"
+        WHITESPACE@94..95 " "
+        STRUCT_KW@95..101 "struct"
+        WHITESPACE@101..102 " "
+        NAME@102..105
+          IDENT@102..105 "Foo"
+        SEMI@105..106 ";"
+      WHITESPACE@106..115 "\n        "
+      IMPL@115..205
+        IMPL_KW@115..119 "impl"
+        WHITESPACE@119..120 " "
+        PATH_TYPE@120..123
+          PATH@120..123
+            PATH_SEGMENT@120..123
+              NAME_REF@120..123
+                IDENT@120..123 "Foo"
+        WHITESPACE@123..124 " "
+        ASSOCIATED_ITEM_LIST@124..205
+          L_CURLY@124..125 "{"
+          FUNCTION_DEF@125..195
+            WHITESPACE@125..138 "\n            "
+            ATTR@138..165
+              HASH@138..139 "#"
+              L_BRACKET@139..140 "["
+              PATH@140..145
+                PATH_SEGMENT@140..145
+                  NAME_REF@140..145
+                    IDENT@140..145 "allow"
+              TOKEN_TREE@145..164
+                L_PAREN@145..146 "("
+                IDENT@146..163 "unused_parameters"
+                R_PAREN@163..164 ")"
+              R_BRACKET@164..165 "]"
+            WHITESPACE@165..178 "\n            "
+            FN_KW@178..180 "fn"
+            WHITESPACE@180..181 " "
+            NAME@181..184
+              IDENT@181..184 "bar"
+            PARAM_LIST@184..192
+              L_PAREN@184..185 "("
+              PARAM@185..191
+                BIND_PAT@185..186
+                  NAME@185..186
+                    IDENT@185..186 "a"
+                COLON@186..187 ":"
+                WHITESPACE@187..188 " "
+                PATH_TYPE@188..191
+                  PATH@188..191
+                    PATH_SEGMENT@188..191
+                      NAME_REF@188..191
+                        IDENT@188..191 "i32"
+              R_PAREN@191..192 ")"
+            WHITESPACE@192..193 " "
+            BLOCK_EXPR@193..195
+              L_CURLY@193..194 "{"
+              R_CURLY@194..195 "}"
+          WHITESPACE@195..204 "\n        "
+          R_CURLY@204..205 "}"
+      FUNCTION_DEF@205..237
+        WHITESPACE@205..214 "\n        "
+        ATTR@214..217
+          HASH@214..215 "#"
+          L_BRACKET@215..216 "["
+          R_BRACKET@216..217 "]"
+        WHITESPACE@217..226 "\n        "
+        FN_KW@226..228 "fn"
+        WHITESPACE@228..229 " "
+        NAME@229..232
+          IDENT@229..232 "baz"
+        PARAM_LIST@232..234
+          L_PAREN@232..233 "("
+          R_PAREN@233..234 ")"
+        WHITESPACE@234..235 " "
+        BLOCK_EXPR@235..237
+          L_CURLY@235..236 "{"
+          R_CURLY@236..237 "}"
+      WHITESPACE@237..246 "\n        "
+    error Offset(216): expected an attribute name
+    "##);
+}
+
+#[test]
+fn list_recovery() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+        fn foo(a: i32, 1, b: i32) -> Foo<i32 bool> {
+            let = bar(1 2, , 3);
+            let a: = [1, ;
+        }
+        struct Bar(i32, 1, f64);
+        "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..165
+      FUNCTION_DEF@0..123
+        WHITESPACE@0..9 "\n        "
+        FN_KW@9..11 "fn"
+        WHITESPACE@11..12 " "
+        NAME@12..15
+          IDENT@12..15 "foo"
+        PARAM_LIST@15..34
+          L_PAREN@15..16 "("
+          PARAM@16..22
+            BIND_PAT@16..17
+              NAME@16..17
+                IDENT@16..17 "a"
+            COLON@17..18 ":"
+            WHITESPACE@18..19 " "
+            PATH_TYPE@19..22
+              PATH@19..22
+                PATH_SEGMENT@19..22
+                  NAME_REF@19..22
+                    IDENT@19..22 "i32"
+          COMMA@22..23 ","
+          WHITESPACE@23..24 " "
+          ERROR@24..25
+            INT_NUMBER@24..25 "1"
+          COMMA@25..26 ","
+          WHITESPACE@26..27 " "
+          PARAM@27..33
+            BIND_PAT@27..28
+              NAME@27..28
+                IDENT@27..28 "b"
+            COLON@28..29 ":"
+            WHITESPACE@29..30 " "
+            PATH_TYPE@30..33
+              PATH@30..33
+                PATH_SEGMENT@30..33
+                  NAME_REF@30..33
+                    IDENT@30..33 "i32"
+          R_PAREN@33..34 ")"
+        WHITESPACE@34..35 " "
+        RET_TYPE@35..51
+          THIN_ARROW@35..37 "->"
+          WHITESPACE@37..38 " "
+          PATH_TYPE@38..51
+            PATH@38..41
+              PATH_SEGMENT@38..41
+                NAME_REF@38..41
+                  IDENT@38..41 "Foo"
+            TYPE_ARG_LIST@41..51
+              LT@41..42 "<"
+              PATH_TYPE@42..45
+                PATH@42..45
+                  PATH_SEGMENT@42..45
+                    NAME_REF@42..45
+                      IDENT@42..45 "i32"
+              WHITESPACE@45..46 " "
+              PATH_TYPE@46..50
+                PATH@46..50
+                  PATH_SEGMENT@46..50
+                    NAME_REF@46..50
+                      IDENT@46..50 "bool"
+              GT@50..51 ">"
+        WHITESPACE@51..52 " "
+        BLOCK_EXPR@52..123
+          L_CURLY@52..53 "{"
+          WHITESPACE@53..66 "\n            "
+          LET_STMT@66..86
+            LET_KW@66..69 "let"
+            WHITESPACE@69..70 " "
+            EQ@70..71 "="
+            WHITESPACE@71..72 " "
+            CALL_EXPR@72..85
+              PATH_EXPR@72..75
+                PATH@72..75
+                  PATH_SEGMENT@72..75
+                    NAME_REF@72..75
+                      IDENT@72..75 "bar"
+              ARG_LIST@75..85
+                L_PAREN@75..76 "("
+                LITERAL@76..77
+                  INT_NUMBER@76..77 "1"
+                WHITESPACE@77..78 " "
+                LITERAL@78..79
+                  INT_NUMBER@78..79 "2"
+                COMMA@79..80 ","
+                WHITESPACE@80..81 " "
+                ERROR@81..82
+                  COMMA@81..82 ","
+                WHITESPACE@82..83 " "
+                LITERAL@83..84
+                  INT_NUMBER@83..84 "3"
+                R_PAREN@84..85 ")"
+            SEMI@85..86 ";"
+          WHITESPACE@86..99 "\n            "
+          LET_STMT@99..113
+            LET_KW@99..102 "let"
+            WHITESPACE@102..103 " "
+            BIND_PAT@103..104
+              NAME@103..104
+                IDENT@103..104 "a"
+            COLON@104..105 ":"
+            WHITESPACE@105..106 " "
+            EQ@106..107 "="
+            WHITESPACE@107..108 " "
+            ARRAY_EXPR@108..111
+              L_BRACKET@108..109 "["
+              LITERAL@109..110
+                INT_NUMBER@109..110 "1"
+              COMMA@110..111 ","
+            WHITESPACE@111..112 " "
+            SEMI@112..113 ";"
+          WHITESPACE@113..122 "\n        "
+          R_CURLY@122..123 "}"
+      WHITESPACE@123..132 "\n        "
+      STRUCT_DEF@132..156
+        STRUCT_KW@132..138 "struct"
+        WHITESPACE@138..139 " "
+        NAME@139..142
+          IDENT@139..142 "Bar"
+        TUPLE_FIELD_DEF_LIST@142..156
+          L_PAREN@142..143 "("
+          TUPLE_FIELD_DEF@143..146
+            PATH_TYPE@143..146
+              PATH@143..146
+                PATH_SEGMENT@143..146
+                  NAME_REF@143..146
+                    IDENT@143..146 "i32"
+          COMMA@146..147 ","
+          WHITESPACE@147..148 " "
+          ERROR@148..149
+            INT_NUMBER@148..149 "1"
+          COMMA@149..150 ","
+          WHITESPACE@150..151 " "
+          TUPLE_FIELD_DEF@151..154
+            PATH_TYPE@151..154
+              PATH@151..154
+                PATH_SEGMENT@151..154
+                  NAME_REF@151..154
+                    IDENT@151..154 "f64"
+          R_PAREN@154..155 ")"
+          SEMI@155..156 ";"
+      WHITESPACE@156..165 "\n        "
+    error Offset(24): expected value parameter
+    error Offset(45): expected COMMA
+    error Offset(69): expected pattern
+    error Offset(77): expected COMMA
+    error Offset(81): expected expression
+    error Offset(105): expected type
+    error Offset(111): expected R_BRACKET
+    error Offset(148): expected a type
+    "#);
+}
+
+#[test]
+fn unclosed_delimiter_recovery() {
+    insta::assert_snapshot!(SourceFile::parse(
+        r#"
+        fn foo(a: i32 {
+            bar(a,
+        struct Foo { a: i32,
+        type = i32;
+        fn baz() {}
+        "#,
+    ).debug_dump(), @r#"
+    SOURCE_FILE@0..121
+      FUNCTION_DEF@0..43
+        WHITESPACE@0..9 "\n        "
+        FN_KW@9..11 "fn"
+        WHITESPACE@11..12 " "
+        NAME@12..15
+          IDENT@12..15 "foo"
+        PARAM_LIST@15..22
+          L_PAREN@15..16 "("
+          PARAM@16..22
+            BIND_PAT@16..17
+              NAME@16..17
+                IDENT@16..17 "a"
+            COLON@17..18 ":"
+            WHITESPACE@18..19 " "
+            PATH_TYPE@19..22
+              PATH@19..22
+                PATH_SEGMENT@19..22
+                  NAME_REF@19..22
+                    IDENT@19..22 "i32"
+        WHITESPACE@22..23 " "
+        BLOCK_EXPR@23..43
+          L_CURLY@23..24 "{"
+          WHITESPACE@24..37 "\n            "
+          EXPR_STMT@37..43
+            CALL_EXPR@37..43
+              PATH_EXPR@37..40
+                PATH@37..40
+                  PATH_SEGMENT@37..40
+                    NAME_REF@37..40
+                      IDENT@37..40 "bar"
+              ARG_LIST@40..43
+                L_PAREN@40..41 "("
+                PATH_EXPR@41..42
+                  PATH@41..42
+                    PATH_SEGMENT@41..42
+                      NAME_REF@41..42
+                        IDENT@41..42 "a"
+                COMMA@42..43 ","
+      WHITESPACE@43..52 "\n        "
+      STRUCT_DEF@52..72
+        STRUCT_KW@52..58 "struct"
+        WHITESPACE@58..59 " "
+        NAME@59..62
+          IDENT@59..62 "Foo"
+        WHITESPACE@62..63 " "
+        RECORD_FIELD_DEF_LIST@63..72
+          L_CURLY@63..64 "{"
+          WHITESPACE@64..65 " "
+          RECORD_FIELD_DEF@65..71
+            NAME@65..66
+              IDENT@65..66 "a"
+            COLON@66..67 ":"
+            WHITESPACE@67..68 " "
+            PATH_TYPE@68..71
+              PATH@68..71
+                PATH_SEGMENT@68..71
+                  NAME_REF@68..71
+                    IDENT@68..71 "i32"
+          COMMA@71..72 ","
+      WHITESPACE@72..81 "\n        "
+      TYPE_ALIAS_DEF@81..92
+        TYPE_KW@81..85 "type"
+        WHITESPACE@85..86 " "
+        EQ@86..87 "="
+        WHITESPACE@87..88 " "
+        PATH_TYPE@88..91
+          PATH@88..91
+            PATH_SEGMENT@88..91
+              NAME_REF@88..91
+                IDENT@88..91 "i32"
+        SEMI@91..92 ";"
+      FUNCTION_DEF@92..112
+        WHITESPACE@92..101 "\n        "
+        FN_KW@101..103 "fn"
+        WHITESPACE@103..104 " "
+        NAME@104..107
+          IDENT@104..107 "baz"
+        PARAM_LIST@107..109
+          L_PAREN@107..108 "("
+          R_PAREN@108..109 ")"
+        WHITESPACE@109..110 " "
+        BLOCK_EXPR@110..112
+          L_CURLY@110..111 "{"
+          R_CURLY@111..112 "}"
+      WHITESPACE@112..121 "\n        "
+    error Offset(22): expected R_PAREN
+    error Offset(43): expected R_PAREN
+    error Offset(43): expected R_CURLY
+    error Offset(72): expected R_CURLY
+    error Offset(85): expected a name
     "#);
 }
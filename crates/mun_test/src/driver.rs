@@ -4,7 +4,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use mun_compiler::{Config, DisplayColor, Driver, PathOrInline, RelativePathBuf};
+use mun_compiler::{Config, DisplayColor, Driver, InMemoryAssembly, PathOrInline, RelativePathBuf};
 use mun_hir_input::Fixture;
 use mun_runtime::{InitError, Runtime, RuntimeBuilder};
 
@@ -65,10 +65,17 @@ impl CompileTestDriver {
 
     /// Constructs a new `CompileTestDriver` from a single Mun source.
     pub fn from_file(text: &str) -> Self {
+        Self::from_file_with_config(text, Config::default())
+    }
+
+    /// Constructs a new `CompileTestDriver` from a single Mun source, which is
+    /// compiled with the specified `config`. The output directory of the
+    /// `config` is replaced by a temporary directory.
+    pub fn from_file_with_config(text: &str, config: Config) -> Self {
         let temp_dir = tempfile::TempDir::new().unwrap();
         let config = Config {
             out_dir: Some(temp_dir.path().to_path_buf()),
-            ..Config::default()
+            ..config
         };
         let input = PathOrInline::Inline {
             rel_path: RelativePathBuf::from("mod.mun"),
@@ -108,6 +115,25 @@ impl CompileTestDriver {
         self.driver.write_all_assemblies(true).unwrap();
     }
 
+    /// Updates the text of the Mun source and returns the assemblies that
+    /// changed, without writing them to disk.
+    pub fn update_file_in_memory(
+        &mut self,
+        path: impl AsRef<mun_paths::RelativePath>,
+        text: &str,
+    ) -> Vec<InMemoryAssembly> {
+        self.driver.set_file_text(path, text).unwrap();
+
+        let compiler_errors = self
+            .driver
+            .emit_diagnostics_to_string(DisplayColor::Disable)
+            .expect("error creating diagnostics");
+        if let Some(compiler_errors) = compiler_errors {
+            panic!("compiler errors:\n{compiler_errors}")
+        }
+        self.driver.build_changed_assemblies().unwrap()
+    }
+
     /// Returns the path to the generated `*.munlib` library.
     pub fn lib_path(&self) -> &Path {
         &self.out_path
@@ -177,6 +203,25 @@ impl CompileAndRunTestDriver {
         Ok(Self { driver, runtime })
     }
 
+    /// Constructs a `CompileAndRunTestDriver` from a single Mun source file
+    /// that is compiled with the specified `config`, and a `config_fn` that
+    /// allows modification of a [`RuntimeBuilder`].
+    pub fn with_config(
+        text: &str,
+        config: Config,
+        config_fn: impl FnOnce(RuntimeBuilder) -> RuntimeBuilder,
+    ) -> Result<Self, InitError> {
+        let driver = CompileTestDriver::from_file_with_config(text, config);
+        let builder = Runtime::builder(driver.lib_path());
+
+        // Safety: We compiled the library ourselves, therefor loading the munlib is
+        // safe.
+        let build = config_fn(builder);
+        let runtime = unsafe { build.finish() }?;
+
+        Ok(Self { driver, runtime })
+    }
+
     /// Updates the text of the Mun source and ensures that the generated
     /// assembly has been reloaded.
     ///
@@ -199,4 +244,21 @@ impl CompileAndRunTestDriver {
             }
         }
     }
+
+    /// Updates the text of the Mun source and hands the recompiled assemblies
+    /// directly to the runtime, without writing them to disk.
+    pub fn update_file_in_memory(&mut self, path: impl AsRef<mun_paths::RelativePath>, text: &str) {
+        for assembly in self.driver.update_file_in_memory(path, text) {
+            self.runtime
+                .stage_assembly(&assembly.path, assembly.bytes)
+                .expect("could not stage assembly");
+        }
+
+        // Safety: We compiled the library ourselves, therefor updating the runtime is
+        // safe.
+        assert!(
+            unsafe { self.runtime.update() },
+            "runtime did not update after staging the recompiled assemblies"
+        );
+    }
 }
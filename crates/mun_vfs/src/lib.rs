@@ -17,6 +17,10 @@ pub struct FileId(pub u32);
 /// The `VirtualFileSystem` is a struct that manages a set of files and their
 /// content. Changes to the instance are logged, they can be be retrieved via
 /// the `take_changes` method.
+///
+/// The contents of a file can be overlaid with in-memory contents, e.g. the
+/// unsaved contents of a buffer in an editor. As long as a file has an overlay,
+/// the overlay is returned instead of the contents on disk.
 #[derive(Default)]
 pub struct VirtualFileSystem {
     /// Used to convert from paths to `FileId` and vice versa.
@@ -25,6 +29,10 @@ pub struct VirtualFileSystem {
     /// Per file the content of the file, or `None` if no content is available
     file_contents: Vec<Option<Vec<u8>>>,
 
+    /// Per file the in-memory content that overlays the content of the file,
+    /// or `None` if the file has no overlay.
+    overlays: Vec<Option<Vec<u8>>>,
+
     /// A record of changes to this instance.
     changes: Vec<ChangedFile>,
 }
@@ -69,7 +77,7 @@ impl VirtualFileSystem {
     pub fn file_id(&self, path: &AbsPath) -> Option<FileId> {
         self.interner
             .get(path)
-            .filter(|&file_id| self.get(file_id).is_some())
+            .filter(move |&file_id| self.get(file_id).is_some())
     }
 
     /// Returns the path of the file with the specified `FileId`.
@@ -79,40 +87,66 @@ impl VirtualFileSystem {
 
     /// Returns the content of the file with the specified `FileId`.
     pub fn file_contents(&self, file_id: FileId) -> Option<&[u8]> {
-        self.get(file_id).as_deref()
+        self.get(file_id)
     }
 
     /// Returns an iterator that iterates all `FileId`s and their path.
     pub fn iter(&self) -> impl Iterator<Item = (FileId, &AbsPath)> + '_ {
-        self.file_contents
-            .iter()
-            .enumerate()
-            .filter(|(_, contents)| contents.is_some())
-            .map(move |(id, _)| {
-                let file_id = FileId(id as u32);
-                let path = self.interner.lookup(file_id);
-                (file_id, path)
-            })
+        (0..self.file_contents.len())
+            .map(|id| FileId(id as u32))
+            .filter(move |&file_id| self.get(file_id).is_some())
+            .map(move |file_id| (file_id, self.interner.lookup(file_id)))
     }
 
     /// Notifies this instance that the contents of the specified file has
     /// changed to something else. Returns true if the new contents is
     /// actually different.
+    ///
+    /// If the file has an overlay, the new contents are stored but they only
+    /// become visible once the overlay is removed. In that case no change is
+    /// recorded and `false` is returned.
     pub fn set_file_contents(&mut self, path: &AbsPath, contents: Option<Vec<u8>>) -> bool {
         let file_id = self.alloc_file_id(path);
-        let kind = match (&self.get(file_id), &contents) {
-            (None, None) => return false,
-            (None, Some(_)) => ChangeKind::Create,
-            (Some(_), None) => ChangeKind::Delete,
-            (Some(old), Some(new)) if old == new => return false,
-            (Some(_), Some(_)) => ChangeKind::Modify,
+        if self.has_overlay(file_id) {
+            self.file_contents[file_id.0 as usize] = contents;
+            return false;
+        }
+
+        let Some(kind) = change_kind(self.get(file_id), contents.as_deref()) else {
+            return false;
         };
 
-        *self.get_mut(file_id) = contents;
+        self.file_contents[file_id.0 as usize] = contents;
         self.changes.push(ChangedFile { file_id, kind });
         true
     }
 
+    /// Overlays the contents of the specified file with in-memory `contents`,
+    /// or removes the overlay if `contents` is `None`. After removing the
+    /// overlay, the contents of the file on disk are visible again. Returns
+    /// true if the visible contents of the file actually changed.
+    pub fn set_overlay(&mut self, path: &AbsPath, contents: Option<Vec<u8>>) -> bool {
+        let file_id = self.alloc_file_id(path);
+        let idx = file_id.0 as usize;
+        let new = contents.as_deref().or(self.file_contents[idx].as_deref());
+        let kind = change_kind(self.get(file_id), new);
+
+        self.overlays[idx] = contents;
+        match kind {
+            Some(kind) => {
+                self.changes.push(ChangedFile { file_id, kind });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if the contents of the file with the specified `FileId`
+    /// are overlaid with in-memory contents.
+    pub fn has_overlay(&self, file_id: FileId) -> bool {
+        self.overlays[file_id.0 as usize].is_some()
+    }
+
     /// Returns the `FileId` for the specified path and ensures that we can use
     /// it with this instance.
     fn alloc_file_id(&mut self, path: &AbsPath) -> FileId {
@@ -120,21 +154,30 @@ impl VirtualFileSystem {
         let idx = file_id.0 as usize;
         let len = self.file_contents.len().max(idx + 1);
         self.file_contents.resize(len, None);
+        self.overlays.resize(len, None);
         file_id
     }
 
-    /// Returns a reference to the current content of a specific file. This
-    /// function is only used internally. Use the `file_contents` function
-    /// to get the contents of a file.
-    fn get(&self, file_id: FileId) -> &Option<Vec<u8>> {
-        &self.file_contents[file_id.0 as usize]
+    /// Returns a reference to the current content of a specific file, taking
+    /// overlays into account. This function is only used internally. Use the
+    /// `file_contents` function to get the contents of a file.
+    fn get(&self, file_id: FileId) -> Option<&[u8]> {
+        let idx = file_id.0 as usize;
+        self.overlays[idx]
+            .as_deref()
+            .or(self.file_contents[idx].as_deref())
     }
+}
 
-    /// Returns a mutable reference to the current content of a specific file.
-    /// This function is only used internally. Use the `set_file_contents`
-    /// function to update the contents of a file.
-    fn get_mut(&mut self, file_id: FileId) -> &mut Option<Vec<u8>> {
-        &mut self.file_contents[file_id.0 as usize]
+/// Determines how the contents of a file changes when going from `old` to
+/// `new`. Returns `None` if the contents are the same.
+fn change_kind(old: Option<&[u8]>, new: Option<&[u8]>) -> Option<ChangeKind> {
+    match (old, new) {
+        (None, None) => None,
+        (None, Some(_)) => Some(ChangeKind::Create),
+        (Some(_), None) => Some(ChangeKind::Delete),
+        (Some(old), Some(new)) if old == new => None,
+        (Some(_), Some(_)) => Some(ChangeKind::Modify),
     }
 }
 
@@ -207,6 +250,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn overlay() {
+        let mut vfs = VirtualFileSystem::default();
+
+        // Construct a fake file name
+        let abs_manifest_dir: AbsPathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .try_into()
+            .unwrap();
+        let test_path = abs_manifest_dir.as_path().join("test");
+
+        // Store the contents on disk and overlay it with other contents
+        assert!(vfs.set_file_contents(&test_path, Some(vec![0])));
+        let file_id = vfs.file_id(&test_path).unwrap();
+        assert!(vfs.set_overlay(&test_path, Some(vec![1])));
+        assert!(vfs.has_overlay(file_id));
+        assert_eq!(vfs.file_contents(file_id), Some([1].as_slice()));
+
+        // Changes on disk are hidden by the overlay
+        assert!(!vfs.set_file_contents(&test_path, Some(vec![2])));
+        assert_eq!(vfs.file_contents(file_id), Some([1].as_slice()));
+
+        // Removing the overlay reveals the contents on disk
+        assert!(vfs.set_overlay(&test_path, None));
+        assert!(!vfs.has_overlay(file_id));
+        assert_eq!(vfs.file_contents(file_id), Some([2].as_slice()));
+
+        // Overlaying a file that doesn't exist on disk creates it, removing the
+        // overlay deletes it again
+        let test_path2 = abs_manifest_dir.as_path().join("test2");
+        assert!(vfs.set_overlay(&test_path2, Some(vec![3])));
+        let file_id2 = vfs.file_id(&test_path2).unwrap();
+        assert!(vfs.set_overlay(&test_path2, None));
+        assert_eq!(vfs.file_id(&test_path2), None);
+
+        assert_eq!(
+            vfs.take_changes(),
+            vec![
+                ChangedFile {
+                    file_id,
+                    kind: ChangeKind::Create
+                },
+                ChangedFile {
+                    file_id,
+                    kind: ChangeKind::Modify
+                },
+                ChangedFile {
+                    file_id,
+                    kind: ChangeKind::Modify
+                },
+                ChangedFile {
+                    file_id: file_id2,
+                    kind: ChangeKind::Create
+                },
+                ChangedFile {
+                    file_id: file_id2,
+                    kind: ChangeKind::Delete
+                },
+            ]
+        );
+    }
+
     #[test]
     fn iter() {
         let mut vfs = VirtualFileSystem::default();